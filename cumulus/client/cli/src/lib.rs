@@ -307,6 +307,42 @@ pub struct RunCmd {
 	/// Will use the specified relay chain chainspec.
 	#[arg(long, conflicts_with_all = ["relay_chain_rpc_urls", "collator"])]
 	pub relay_chain_light_client: bool,
+
+	/// File containing the secret URI of the relay chain account paying for on-demand coretime,
+	/// e.g. a mnemonic.
+	///
+	/// When set, the collator places an on-demand order on the relay chain whenever the parachain
+	/// has pending transactions and no order in flight.
+	#[arg(
+		long,
+		value_name = "PATH",
+		requires_all = ["on_demand_max_amount", "on_demand_spend_limit"]
+	)]
+	pub on_demand_order_account_file: Option<PathBuf>,
+
+	/// The maximum amount paid for a single on-demand order, in the smallest unit of the relay
+	/// chain currency.
+	#[arg(long, value_name = "AMOUNT", requires = "on_demand_order_account_file")]
+	pub on_demand_max_amount: Option<u128>,
+
+	/// The total amount the collator may spend on on-demand orders, in the smallest unit of the
+	/// relay chain currency.
+	///
+	/// Every order is accounted with `--on-demand-max-amount`. The amount spent is kept in the
+	/// database of the node, so the limit holds across restarts. Raise the limit to place further
+	/// orders once it is reached.
+	#[arg(long, value_name = "AMOUNT", requires = "on_demand_order_account_file")]
+	pub on_demand_spend_limit: Option<u128>,
+
+	/// The number of ready transactions in the transaction pool required to place an on-demand
+	/// order.
+	#[arg(long, value_name = "COUNT", default_value_t = 1)]
+	pub on_demand_min_ready_transactions: usize,
+
+	/// The number of relay chain blocks to wait after placing an on-demand order before placing
+	/// the next one.
+	#[arg(long, value_name = "BLOCKS", default_value_t = 2)]
+	pub on_demand_min_relay_blocks_between_orders: u32,
 }
 
 impl RunCmd {
@@ -329,7 +365,18 @@ impl RunCmd {
 				_ => RelayChainMode::Embedded,
 			};
 
-		CollatorOptions { relay_chain_mode }
+		let on_demand_orders =
+			self.on_demand_order_account_file
+				.clone()
+				.map(|account_file| OnDemandOrderOptions {
+					account_file,
+					max_amount: self.on_demand_max_amount.unwrap_or_default(),
+					spend_limit: self.on_demand_spend_limit.unwrap_or_default(),
+					min_ready_transactions: self.on_demand_min_ready_transactions,
+					min_relay_blocks_between_orders: self.on_demand_min_relay_blocks_between_orders,
+				});
+
+		CollatorOptions { relay_chain_mode, on_demand_orders }
 	}
 }
 
@@ -349,6 +396,23 @@ pub enum RelayChainMode {
 pub struct CollatorOptions {
 	/// How this collator retrieves relay chain information
 	pub relay_chain_mode: RelayChainMode,
+	/// Whether and how this collator buys on-demand coretime
+	pub on_demand_orders: Option<OnDemandOrderOptions>,
+}
+
+/// Options for buying on-demand coretime on the relay chain
+#[derive(Clone, Debug)]
+pub struct OnDemandOrderOptions {
+	/// File containing the secret URI of the relay chain account paying for the orders
+	pub account_file: PathBuf,
+	/// The maximum amount paid for a single order
+	pub max_amount: u128,
+	/// The total amount that may be spent on orders
+	pub spend_limit: u128,
+	/// The number of ready transactions required to place an order
+	pub min_ready_transactions: usize,
+	/// The number of relay chain blocks to wait between orders
+	pub min_relay_blocks_between_orders: u32,
 }
 
 /// A non-redundant version of the `RunCmd` that sets the `validator` field when the
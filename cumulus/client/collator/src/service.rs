@@ -18,13 +18,17 @@
 //! operations used in parachain consensus/authoring.
 
 use cumulus_client_network::WaitToAnnounce;
-use cumulus_primitives_core::{CollationInfo, CollectCollationInfo, ParachainBlockData};
+use cumulus_primitives_core::{
+	check_bundle_messages, AbridgedHostConfiguration, CollationInfo, CollectCollationInfo,
+	ParachainBlockBundle, ParachainBlockData,
+};
 
 use sc_client_api::BlockBackend;
 use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_consensus::BlockStatus;
 use sp_core::traits::SpawnNamed;
 use sp_runtime::traits::{Block as BlockT, HashingFor, Header as HeaderT, Zero};
+use sp_trie::StorageProof;
 
 use cumulus_client_consensus_common::ParachainCandidate;
 use polkadot_node_primitives::{
@@ -58,6 +62,29 @@ pub trait ServiceInterface<Block: BlockT> {
 		candidate: ParachainCandidate<Block>,
 	) -> Option<(Collation, ParachainBlockData<Block>)>;
 
+	/// Build a single [`Collation`] out of consecutive [`ParachainCandidate`]s, each one built on
+	/// top of the previous one and the first one on top of `parent_header`. This requires that all
+	/// the underlying blocks have been fully imported into the underlying client.
+	///
+	/// Bundles whose merged messages can not be sent by one candidate under `host_config`, the
+	/// configuration at the relay parent, are refused.
+	///
+	/// This also returns the unencoded parachain block bundle, in case that is desired.
+	///
+	/// The default implementation doesn't support bundles and always returns `None`.
+	fn build_bundle_collation(
+		&self,
+		_parent_header: &Block::Header,
+		_candidates: Vec<(Block::Hash, ParachainCandidate<Block>)>,
+		_host_config: &AbridgedHostConfiguration,
+	) -> Option<(Collation, ParachainBlockBundle<Block>)> {
+		tracing::error!(
+			target: LOG_TARGET,
+			"Building a bundle collation is not supported by this collator service.",
+		);
+		None
+	}
+
 	/// Inform networking systems that the block should be announced after a signal has
 	/// been received to indicate the block has been seconded by a relay-chain validator.
 	///
@@ -287,6 +314,134 @@ where
 		Some((collation, block_data))
 	}
 
+	/// Build a single [`Collation`] out of consecutive [`ParachainCandidate`]s, each one built on
+	/// top of the previous one and the first one on top of `parent_header`. This requires that all
+	/// the underlying blocks have been fully imported into the underlying client, as it fetches
+	/// underlying runtime API data.
+	///
+	/// The storage proofs of the candidates are merged into one compact proof, so trie nodes
+	/// accessed by multiple blocks are only sent once. The messages of all blocks are sent in
+	/// order. The bundle is refused if they break the rules the relay chain applies to the
+	/// messages of a candidate under `host_config`, the configuration at the relay parent, as
+	/// the validation function would reject it.
+	///
+	/// This also returns the unencoded parachain block bundle, in case that is desired.
+	pub fn build_bundle_collation(
+		&self,
+		parent_header: &Block::Header,
+		candidates: Vec<(Block::Hash, ParachainCandidate<Block>)>,
+		host_config: &AbridgedHostConfiguration,
+	) -> Option<(Collation, ParachainBlockBundle<Block>)> {
+		let mut blocks = Vec::with_capacity(candidates.len());
+		let mut proofs = Vec::with_capacity(candidates.len());
+		let mut bundle_info: Option<CollationInfo> = None;
+
+		for (block_hash, candidate) in candidates {
+			let collation_info = self
+				.fetch_collation_info(block_hash, candidate.block.header())
+				.map_err(|e| {
+					tracing::error!(
+						target: LOG_TARGET,
+						error = ?e,
+						"Failed to collect collation info.",
+					)
+				})
+				.ok()
+				.flatten()?;
+
+			bundle_info = Some(match bundle_info {
+				None => collation_info,
+				Some(mut info) => {
+					info.upward_messages.extend(collation_info.upward_messages);
+					info.horizontal_messages.extend(collation_info.horizontal_messages);
+					info.new_validation_code =
+						collation_info.new_validation_code.or(info.new_validation_code);
+					info.processed_downward_messages = info
+						.processed_downward_messages
+						.saturating_add(collation_info.processed_downward_messages);
+					info.hrmp_watermark = collation_info.hrmp_watermark;
+					info.head_data = collation_info.head_data;
+					info
+				},
+			});
+
+			blocks.push(candidate.block);
+			proofs.push(candidate.proof);
+		}
+
+		let Some(collation_info) = bundle_info else {
+			tracing::error!(target: LOG_TARGET, "Cannot build a bundle collation without blocks.");
+			return None
+		};
+
+		if let Err(error) = check_bundle_messages(
+			collation_info.upward_messages.len(),
+			&collation_info.horizontal_messages,
+			host_config,
+		) {
+			tracing::error!(
+				target: LOG_TARGET,
+				?error,
+				"Messages of the bundle can not be sent by one candidate.",
+			);
+			return None
+		}
+
+		// Nodes that are created by a block of the bundle are only reachable from the state of the
+		// following blocks, so compacting against the parent state drops them. The validation
+		// function recreates them while executing the blocks.
+		let compact_proof = match StorageProof::merge(proofs)
+			.into_compact_proof::<HashingFor<Block>>(*parent_header.state_root())
+		{
+			Ok(proof) => proof,
+			Err(e) => {
+				tracing::error!(target: "cumulus-collator", "Failed to compact proof: {:?}", e);
+				return None
+			},
+		};
+
+		let bundle = ParachainBlockBundle::<Block>::new(blocks, compact_proof);
+
+		let pov = polkadot_node_primitives::maybe_compress_pov(PoV {
+			block_data: BlockData(bundle.encode()),
+		});
+
+		let upward_messages = collation_info
+			.upward_messages
+			.try_into()
+			.map_err(|e| {
+				tracing::error!(
+					target: LOG_TARGET,
+					error = ?e,
+					"Number of upward messages should not be greater than `MAX_UPWARD_MESSAGE_NUM`",
+				)
+			})
+			.ok()?;
+		let horizontal_messages = collation_info
+			.horizontal_messages
+			.try_into()
+			.map_err(|e| {
+				tracing::error!(
+					target: LOG_TARGET,
+					error = ?e,
+					"Number of horizontal messages should not be greater than `MAX_HORIZONTAL_MESSAGE_NUM`",
+				)
+			})
+			.ok()?;
+
+		let collation = Collation {
+			upward_messages,
+			new_validation_code: collation_info.new_validation_code,
+			processed_downward_messages: collation_info.processed_downward_messages,
+			horizontal_messages,
+			hrmp_watermark: collation_info.hrmp_watermark,
+			head_data: collation_info.head_data,
+			proof_of_validity: MaybeCompressedPoV::Compressed(pov),
+		};
+
+		Some((collation, bundle))
+	}
+
 	/// Inform the networking systems that the block should be announced after an appropriate
 	/// signal has been received. This returns the sending half of the signal.
 	pub fn announce_with_barrier(
@@ -319,6 +474,15 @@ where
 		CollatorService::build_collation(self, parent_header, block_hash, candidate)
 	}
 
+	fn build_bundle_collation(
+		&self,
+		parent_header: &Block::Header,
+		candidates: Vec<(Block::Hash, ParachainCandidate<Block>)>,
+		host_config: &AbridgedHostConfiguration,
+	) -> Option<(Collation, ParachainBlockBundle<Block>)> {
+		CollatorService::build_bundle_collation(self, parent_header, candidates, host_config)
+	}
+
 	fn announce_with_barrier(
 		&self,
 		block_hash: Block::Hash,
@@ -95,6 +95,8 @@ pub struct BuilderTaskParams<
 	/// likelihood of encountering unfavorable notification arrival timings (i.e. we don't want to
 	/// wait for relay chain notifications because we woke up too early).
 	pub slot_drift: Duration,
+	/// The number of blocks to build on top of each other at one slot and submit as one bundle.
+	pub blocks_per_bundle: u32,
 }
 
 #[derive(Debug)]
@@ -200,8 +202,11 @@ where
 			authoring_duration,
 			para_backend,
 			slot_drift,
+			blocks_per_bundle,
 		} = params;
 
+		let blocks_per_bundle = blocks_per_bundle.max(1);
+
 		let slot_timer = SlotTimer::<_, _, P>::new_with_drift(para_client.clone(), slot_drift);
 
 		let mut collator = {
@@ -395,14 +400,18 @@ where
 				validation_data.max_pov_size / 2
 			} as usize;
 
+			// The blocks of a bundle share the authoring time and the PoV.
+			let block_authoring_duration = authoring_duration / blocks_per_bundle;
+			let block_pov_size = allowed_pov_size / blocks_per_bundle as usize;
+
 			let Ok(Some(candidate)) = collator
 				.build_block_and_import(
 					&parent_header,
 					&slot_claim,
 					None,
 					(parachain_inherent_data, other_inherent_data),
-					authoring_duration,
-					allowed_pov_size,
+					block_authoring_duration,
+					block_pov_size,
 				)
 				.await
 			else {
@@ -415,10 +424,67 @@ where
 			// Announce the newly built block to our peers.
 			collator.collator_service().announce_block(new_block_hash, None);
 
+			let mut candidates = vec![candidate];
+			while candidates.len() < blocks_per_bundle as usize {
+				let bundle_parent = candidates
+					.last()
+					.expect("There is at least one candidate; qed")
+					.block
+					.header()
+					.clone();
+				let bundle_parent_hash = bundle_parent.hash();
+
+				let validation_data = PersistedValidationData {
+					parent_head: bundle_parent.encode().into(),
+					..validation_data.clone()
+				};
+
+				let inherent_data = match collator
+					.create_inherent_data(
+						relay_parent,
+						&validation_data,
+						bundle_parent_hash,
+						slot_claim.timestamp(),
+					)
+					.await
+				{
+					Err(err) => {
+						tracing::error!(target: crate::LOG_TARGET, ?err);
+						break
+					},
+					Ok(x) => x,
+				};
+
+				let Ok(Some(candidate)) = collator
+					.build_block_and_import(
+						&bundle_parent,
+						&slot_claim,
+						None,
+						inherent_data,
+						block_authoring_duration,
+						block_pov_size,
+					)
+					.await
+				else {
+					tracing::warn!(
+						target: crate::LOG_TARGET,
+						built = candidates.len(),
+						blocks_per_bundle,
+						"Unable to build the next block of the bundle, submitting the blocks built so far."
+					);
+					break
+				};
+
+				collator
+					.collator_service()
+					.announce_block(candidate.block.header().hash(), None);
+				candidates.push(candidate);
+			}
+
 			if let Err(err) = collator_sender.unbounded_send(CollatorMessage {
 				relay_parent,
 				parent_header,
-				parachain_candidate: candidate,
+				parachain_candidates: candidates,
 				validation_code_hash,
 				core_index: *core_index,
 			}) {
@@ -17,6 +17,7 @@
 use codec::Encode;
 
 use cumulus_client_collator::service::ServiceInterface as CollatorServiceInterface;
+use cumulus_client_consensus_common::load_abridged_host_configuration;
 use cumulus_relay_chain_interface::RelayChainInterface;
 
 use polkadot_node_primitives::{MaybeCompressedPoV, SubmitCollationParams};
@@ -76,7 +77,13 @@ where
 
 	let collator_service = params.collator_service;
 	while let Some(collator_message) = params.collator_receiver.next().await {
-		handle_collation_message(collator_message, &collator_service, &mut overseer_handle).await;
+		handle_collation_message(
+			collator_message,
+			&collator_service,
+			&params.relay_client,
+			&mut overseer_handle,
+		)
+		.await;
 	}
 }
 
@@ -86,34 +93,84 @@ where
 async fn handle_collation_message<Block: BlockT>(
 	message: CollatorMessage<Block>,
 	collator_service: &impl CollatorServiceInterface<Block>,
+	relay_client: &impl RelayChainInterface,
 	overseer_handle: &mut OverseerHandle,
 ) {
 	let CollatorMessage {
 		parent_header,
-		parachain_candidate,
+		parachain_candidates,
 		validation_code_hash,
 		relay_parent,
 		core_index,
 	} = message;
 
-	let hash = parachain_candidate.block.header().hash();
-	let number = *parachain_candidate.block.header().number();
-	let (collation, block_data) =
-		match collator_service.build_collation(&parent_header, hash, parachain_candidate) {
-			Some(collation) => collation,
-			None => {
-				tracing::warn!(target: LOG_TARGET, %hash, ?number, ?core_index, "Unable to build collation.");
-				return;
+	let Some(last_header) = parachain_candidates.last().map(|c| c.block.header().clone()) else {
+		tracing::warn!(target: LOG_TARGET, ?core_index, "No candidates to build a collation from.");
+		return;
+	};
+	let hash = last_header.hash();
+	let number = *last_header.number();
+
+	let collation = if parachain_candidates.len() == 1 {
+		let parachain_candidate =
+			parachain_candidates.into_iter().next().expect("There is one candidate; qed");
+		collator_service.build_collation(&parent_header, hash, parachain_candidate).map(
+			|(collation, block_data)| {
+				tracing::info!(
+					target: LOG_TARGET,
+					"PoV size {{ header: {:.2}kB, extrinsics: {:.2}kB, storage_proof: {:.2}kB }}",
+					block_data.header().encoded_size() as f64 / 1024f64,
+					block_data.extrinsics().encoded_size() as f64 / 1024f64,
+					block_data.storage_proof().encoded_size() as f64 / 1024f64,
+				);
+				collation
+			},
+		)
+	} else {
+		// The merged messages of the bundle are checked against the limits at the relay parent.
+		let host_config = match load_abridged_host_configuration(relay_parent, relay_client).await {
+			Ok(Some(host_config)) => host_config,
+			Ok(None) => {
+				tracing::error!(
+					target: LOG_TARGET,
+					?relay_parent,
+					"Active config is missing in relay chain storage.",
+				);
+				return
+			},
+			Err(err) => {
+				tracing::error!(
+					target: LOG_TARGET,
+					?err,
+					?relay_parent,
+					"Failed to read active config from relay chain client.",
+				);
+				return
 			},
 		};
 
-	tracing::info!(
-		target: LOG_TARGET,
-		"PoV size {{ header: {:.2}kB, extrinsics: {:.2}kB, storage_proof: {:.2}kB }}",
-		block_data.header().encoded_size() as f64 / 1024f64,
-		block_data.extrinsics().encoded_size() as f64 / 1024f64,
-		block_data.storage_proof().encoded_size() as f64 / 1024f64,
-	);
+		let candidates = parachain_candidates
+			.into_iter()
+			.map(|candidate| (candidate.block.header().hash(), candidate))
+			.collect();
+		collator_service
+			.build_bundle_collation(&parent_header, candidates, &host_config)
+			.map(|(collation, bundle)| {
+				tracing::info!(
+					target: LOG_TARGET,
+					"PoV size {{ blocks: {}, blocks_size: {:.2}kB, storage_proof: {:.2}kB }}",
+					bundle.blocks().len(),
+					bundle.blocks().encoded_size() as f64 / 1024f64,
+					bundle.storage_proof().encoded_size() as f64 / 1024f64,
+				);
+				collation
+			})
+	};
+
+	let Some(collation) = collation else {
+		tracing::warn!(target: LOG_TARGET, %hash, ?number, ?core_index, "Unable to build collation.");
+		return;
+	};
 
 	if let MaybeCompressedPoV::Compressed(ref pov) = collation.proof_of_validity {
 		tracing::info!(
@@ -27,6 +27,10 @@
 //! chain block. The collator implementation then expects that we have that many cores scheduled
 //! during the relay chain block. After the block is built, the block builder task sends it to
 //! the collation task which compresses it and submits it to the collation-generation subsystem.
+//!
+//! Optionally, multiple blocks can be built on top of each other at one slot and submitted as one
+//! [`ParachainBlockBundle`](cumulus_primitives_core::ParachainBlockBundle), which shares the
+//! storage proof between the blocks.
 
 use codec::Codec;
 use consensus_common::ParachainCandidate;
@@ -93,6 +97,12 @@ pub struct Params<BI, CIDP, Client, Backend, RClient, CHP, Proposer, CS> {
 	/// Drift slots by a fixed duration. This can be used to create more preferrable authoring
 	/// timings.
 	pub slot_drift: Duration,
+	/// The number of blocks to build on top of each other at one slot and submit as one bundle.
+	/// The authoring duration and the PoV size are split among them. `1` disables bundling.
+	///
+	/// The runtime needs to allow multiple blocks per slot with the same timestamp and have enough
+	/// unincluded segment capacity for the blocks of a bundle.
+	pub blocks_per_bundle: u32,
 }
 
 /// Run aura-based block building and collation task.
@@ -150,6 +160,7 @@ where
 		authoring_duration: params.authoring_duration,
 		collator_sender: tx,
 		slot_drift: params.slot_drift,
+		blocks_per_bundle: params.blocks_per_bundle,
 	};
 
 	let block_builder_fut =
@@ -166,8 +177,9 @@ struct CollatorMessage<Block: BlockT> {
 	pub relay_parent: RelayHash,
 	/// The header of the parent block.
 	pub parent_header: Block::Header,
-	/// The parachain block candidate.
-	pub parachain_candidate: ParachainCandidate<Block>,
+	/// The parachain block candidates, each one built on top of the previous one. If there is more
+	/// than one, they are submitted as a bundle.
+	pub parachain_candidates: Vec<ParachainCandidate<Block>>,
 	/// The validation code hash at the parent block.
 	pub validation_code_hash: ValidationCodeHash,
 	/// Core index that this block should be submitted on
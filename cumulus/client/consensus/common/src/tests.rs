@@ -276,6 +276,10 @@ impl RelayChainInterface for Relaychain {
 		unimplemented!("Not needed for test");
 	}
 
+	async fn submit_extrinsic(&self, _: Vec<u8>) -> RelayChainResult<PHash> {
+		unimplemented!("Not needed for test");
+	}
+
 	async fn call_runtime_api(
 		&self,
 		_method_name: &'static str,
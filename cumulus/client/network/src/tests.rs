@@ -339,6 +339,10 @@ impl RelayChainInterface for DummyRelayChainInterface {
 		unimplemented!("Not needed for test");
 	}
 
+	async fn submit_extrinsic(&self, _: Vec<u8>) -> RelayChainResult<PHash> {
+		unimplemented!("Not needed for test");
+	}
+
 	async fn call_runtime_api(
 		&self,
 		_method_name: &'static str,
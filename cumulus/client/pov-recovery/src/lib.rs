@@ -63,7 +63,7 @@ use polkadot_primitives::{
 	Id as ParaId, SessionIndex,
 };
 
-use cumulus_primitives_core::ParachainBlockData;
+use cumulus_primitives_core::{ParachainBlockBundle, ParachainBlockData};
 use cumulus_relay_chain_interface::{RelayChainInterface, RelayChainResult};
 
 use codec::Decode;
@@ -388,8 +388,22 @@ where
 				},
 			};
 
-		let block_data = match ParachainBlockData::<Block>::decode(&mut &raw_block_data[..]) {
-			Ok(d) => d,
+		let blocks = if ParachainBlockBundle::<Block>::is_bundle(&raw_block_data) {
+			ParachainBlockBundle::<Block>::decode(&mut &raw_block_data[..])
+				.map(|bundle| bundle.deconstruct().0)
+		} else {
+			ParachainBlockData::<Block>::decode(&mut &raw_block_data[..])
+				.map(|block_data| vec![block_data.into_block()])
+		};
+
+		let mut blocks = match blocks {
+			Ok(blocks) if !blocks.is_empty() => blocks.into_iter(),
+			Ok(_) => {
+				tracing::warn!(target: LOG_TARGET, "Recovered PoV contains an empty block bundle");
+
+				self.reset_candidate(block_hash);
+				return
+			},
 			Err(error) => {
 				tracing::warn!(
 					target: LOG_TARGET,
@@ -402,7 +416,9 @@ where
 			},
 		};
 
-		let block = block_data.into_block();
+		let block = blocks.next().expect("There is at least one block; qed");
+		// The other blocks of a bundle are imported after the block they are built on.
+		let bundle_descendants = blocks.collect::<Vec<_>>();
 
 		let parent = *block.header().parent_hash();
 
@@ -422,6 +438,7 @@ where
 						"Waiting for recovery of parent.",
 					);
 
+					self.wait_for_bundle_descendants(&block, bundle_descendants);
 					self.waiting_for_parent.entry(parent).or_default().push(block);
 					return
 				} else {
@@ -451,9 +468,22 @@ where
 			_ => (),
 		}
 
+		self.wait_for_bundle_descendants(&block, bundle_descendants);
 		self.import_block(block);
 	}
 
+	/// Let the blocks of a bundle that are built on top of `first` wait for their parent, so that
+	/// they are imported in order together with `first`.
+	fn wait_for_bundle_descendants(&mut self, first: &Block, descendants: Vec<Block>) {
+		let mut parent = first.hash();
+
+		for block in descendants {
+			let hash = block.hash();
+			self.waiting_for_parent.entry(parent).or_default().push(block);
+			parent = hash;
+		}
+	}
+
 	/// Import the given `block`.
 	///
 	/// This will also recursively drain `waiting_for_parent` and import them as well.
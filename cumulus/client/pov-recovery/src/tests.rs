@@ -495,6 +495,10 @@ impl RelayChainInterface for Relaychain {
 		unimplemented!("Not needed for test");
 	}
 
+	async fn submit_extrinsic(&self, _: Vec<u8>) -> RelayChainResult<PHash> {
+		unimplemented!("Not needed for test");
+	}
+
 	async fn call_runtime_api(
 		&self,
 		_method_name: &'static str,
@@ -714,6 +718,96 @@ async fn single_pending_candidate_recovery_success(#[case] runtime_version: u32)
 	assert_matches!(import_requests_rx.next().timeout(Duration::from_millis(100)).await, None);
 }
 
+#[tokio::test]
+async fn bundle_recovery_imports_all_blocks() {
+	sp_tracing::init_for_tests();
+
+	let (recovery_subsystem_tx, mut recovery_subsystem_rx) =
+		AvailabilityRecoverySubsystemHandle::new();
+	let recovery_delay_range =
+		RecoveryDelayRange { min: Duration::from_millis(0), max: Duration::from_millis(10) };
+	let (_explicit_recovery_chan_tx, explicit_recovery_chan_rx) = mpsc::channel(10);
+	let mut candidates = make_candidate_chain(1..3);
+	let first_header = Header::decode(&mut &candidates[0].commitments.head_data.0[..]).unwrap();
+	let second_header = Header::decode(&mut &candidates[1].commitments.head_data.0[..]).unwrap();
+	// Both blocks are part of a single candidate, whose head is the second block.
+	let candidate = candidates.remove(1);
+	let candidate_hash = candidate.hash();
+
+	let relay_chain_client = Relaychain::new(vec![(
+		PHeader {
+			parent_hash: PHash::from_low_u64_be(0),
+			number: 1,
+			state_root: PHash::random(),
+			extrinsics_root: PHash::random(),
+			digest: Default::default(),
+		},
+		vec![candidate],
+	)]);
+
+	let mut known_blocks = HashMap::new();
+	known_blocks.insert(GENESIS_HASH, BlockStatus::InChainWithState);
+	let (parachain_client, _import_notifications_tx, _finality_notifications_tx) =
+		ParachainClient::new(vec![dummy_usage_info(0)], Arc::new(Mutex::new(known_blocks)));
+	let (parachain_import_queue, mut import_requests_rx) = ParachainImportQueue::new();
+
+	let pov_recovery = PoVRecovery::<Block, _, _>::new(
+		Box::new(recovery_subsystem_tx),
+		recovery_delay_range,
+		Arc::new(parachain_client),
+		Box::new(parachain_import_queue),
+		relay_chain_client,
+		ParaId::new(1000),
+		explicit_recovery_chan_rx,
+		Arc::new(DummySyncOracle::default()),
+	);
+
+	task::spawn(pov_recovery.run());
+
+	assert_matches!(
+		recovery_subsystem_rx.next().await,
+		Some(AvailabilityRecoveryMessage::RecoverAvailableData(
+			receipt,
+			session_index,
+			None,
+			None,
+			response_tx
+		)) => {
+			assert_eq!(receipt.hash(), candidate_hash);
+			assert_eq!(session_index, TEST_SESSION_INDEX);
+			response_tx.send(
+				Ok(
+					AvailableData {
+						pov: Arc::new(PoV {
+							block_data: ParachainBlockBundle::<Block>::new(
+								vec![
+									Block::new(first_header.clone(), vec![]),
+									Block::new(second_header.clone(), vec![]),
+								],
+								CompactProof {encoded_nodes: vec![]}
+							).encode().into()
+						}),
+						validation_data: dummy_pvd(),
+					}
+				)
+			).unwrap()
+		}
+	);
+
+	// No more recovery messages received.
+	assert_matches!(recovery_subsystem_rx.next().timeout(Duration::from_millis(100)).await, None);
+
+	// Both blocks of the bundle are imported, in order.
+	assert_matches!(import_requests_rx.next().await, Some(incoming_blocks) => {
+		assert_eq!(incoming_blocks.len(), 2);
+		assert_eq!(incoming_blocks[0].header, Some(first_header));
+		assert_eq!(incoming_blocks[1].header, Some(second_header));
+	});
+
+	// No import request received
+	assert_matches!(import_requests_rx.next().timeout(Duration::from_millis(100)).await, None);
+}
+
 #[tokio::test]
 async fn single_pending_candidate_recovery_retry_succeeds() {
 	sp_tracing::init_for_tests();
@@ -41,6 +41,7 @@ use sc_client_api::{
 	blockchain::BlockStatus, Backend, BlockchainEvents, HeaderBackend, ImportNotifications,
 	StorageProof,
 };
+use sc_service::RpcHandlers;
 use sc_telemetry::TelemetryWorkerHandle;
 use sp_api::{CallApiAt, CallApiAtParams, CallContext, ProvideRuntimeApi};
 use sp_consensus::SyncOracle;
@@ -58,6 +59,7 @@ pub struct RelayChainInProcessInterface {
 	backend: Arc<FullBackend>,
 	sync_oracle: Arc<dyn SyncOracle + Send + Sync>,
 	overseer_handle: Handle,
+	rpc_handlers: Option<RpcHandlers>,
 }
 
 impl RelayChainInProcessInterface {
@@ -68,7 +70,13 @@ impl RelayChainInProcessInterface {
 		sync_oracle: Arc<dyn SyncOracle + Send + Sync>,
 		overseer_handle: Handle,
 	) -> Self {
-		Self { full_client, backend, sync_oracle, overseer_handle }
+		Self { full_client, backend, sync_oracle, overseer_handle, rpc_handlers: None }
+	}
+
+	/// Submit extrinsics through the given RPC handlers of the relay chain node.
+	pub fn with_rpc_handlers(mut self, rpc_handlers: RpcHandlers) -> Self {
+		self.rpc_handlers = Some(rpc_handlers);
+		self
 	}
 }
 
@@ -316,6 +324,20 @@ impl RelayChainInterface for RelayChainInProcessInterface {
 	) -> RelayChainResult<BTreeMap<CoreIndex, VecDeque<ParaId>>> {
 		Ok(self.full_client.runtime_api().claim_queue(hash)?)
 	}
+
+	async fn submit_extrinsic(&self, extrinsic: Vec<u8>) -> RelayChainResult<PHash> {
+		let Some(rpc_handlers) = &self.rpc_handlers else {
+			return Err(RelayChainError::GenericError(
+				"Submitting extrinsics requires the RPC handlers of the relay chain node.".into(),
+			))
+		};
+
+		rpc_handlers
+			.handle()
+			.call("author_submitExtrinsic", [sp_core::Bytes(extrinsic)])
+			.await
+			.map_err(|e| RelayChainError::Application(Box::new(e) as Box<_>))
+	}
 }
 
 pub enum BlockCheckStatus {
@@ -380,6 +402,9 @@ fn build_polkadot_full_node(
 			prepare_workers_hard_max_num: None,
 			prepare_workers_soft_max_num: None,
 			enable_approval_voting_parallel: false,
+			approval_traffic_capture: None,
+			overseer_watchdog: None,
+			grandpa_monitor: None,
 		},
 	)?;
 
@@ -407,14 +432,17 @@ pub fn build_inprocess_relay_chain(
 	)
 	.map_err(|e| RelayChainError::Application(Box::new(e) as Box<_>))?;
 
-	let relay_chain_interface = Arc::new(RelayChainInProcessInterface::new(
-		full_node.client,
-		full_node.backend,
-		full_node.sync_service,
-		full_node.overseer_handle.clone().ok_or(RelayChainError::GenericError(
-			"Overseer not running in full node.".to_string(),
-		))?,
-	));
+	let relay_chain_interface = Arc::new(
+		RelayChainInProcessInterface::new(
+			full_node.client,
+			full_node.backend,
+			full_node.sync_service,
+			full_node.overseer_handle.clone().ok_or(RelayChainError::GenericError(
+				"Overseer not running in full node.".to_string(),
+			))?,
+		)
+		.with_rpc_handlers(full_node.rpc_handlers),
+	);
 
 	task_manager.add_child(full_node.task_manager);
 
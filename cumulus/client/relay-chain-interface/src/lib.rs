@@ -244,6 +244,11 @@ pub trait RelayChainInterface: Send + Sync {
 		&self,
 		relay_parent: PHash,
 	) -> RelayChainResult<BTreeMap<CoreIndex, VecDeque<ParaId>>>;
+
+	/// Submit a signed extrinsic to the transaction pool of the relay chain.
+	///
+	/// Returns the hash of the extrinsic.
+	async fn submit_extrinsic(&self, extrinsic: Vec<u8>) -> RelayChainResult<PHash>;
 }
 
 #[async_trait]
@@ -398,6 +403,10 @@ where
 	) -> RelayChainResult<BTreeMap<CoreIndex, VecDeque<ParaId>>> {
 		(**self).claim_queue(relay_parent).await
 	}
+
+	async fn submit_extrinsic(&self, extrinsic: Vec<u8>) -> RelayChainResult<PHash> {
+		(**self).submit_extrinsic(extrinsic).await
+	}
 }
 
 /// Helper function to call an arbitrary runtime API using a `RelayChainInterface` client.
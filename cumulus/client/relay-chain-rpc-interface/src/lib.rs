@@ -282,4 +282,8 @@ impl RelayChainInterface for RelayChainRpcInterface {
 	> {
 		self.rpc_client.parachain_host_claim_queue(relay_parent).await
 	}
+
+	async fn submit_extrinsic(&self, extrinsic: Vec<u8>) -> RelayChainResult<RelayHash> {
+		self.rpc_client.author_submit_extrinsic(extrinsic.into()).await
+	}
 }
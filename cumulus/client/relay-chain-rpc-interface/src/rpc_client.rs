@@ -304,6 +304,15 @@ impl RelayChainRpcClient {
 		self.request("state_getReadProof", params).await
 	}
 
+	/// Submit a fully formatted extrinsic to the transaction pool.
+	pub async fn author_submit_extrinsic(
+		&self,
+		extrinsic: sp_core::Bytes,
+	) -> Result<RelayHash, RelayChainError> {
+		let params = rpc_params![extrinsic];
+		self.request("author_submitExtrinsic", params).await
+	}
+
 	/// Retrieve storage item at `storage_key`
 	pub async fn state_get_storage(
 		&self,
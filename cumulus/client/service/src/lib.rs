@@ -51,6 +51,9 @@ use std::{sync::Arc, time::Duration};
 
 pub use cumulus_primitives_proof_size_hostfunction::storage_proof_size;
 
+#[cfg(feature = "on-demand")]
+pub mod on_demand;
+
 /// Host functions that should be used in parachain nodes.
 ///
 /// Contains the standard substrate host functions, as well as a
@@ -0,0 +1,959 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Pay-as-you-go block production on on-demand coretime.
+//!
+//! A parachain without bulk coretime only gets a core when someone places an order with the
+//! `OnDemandAssignmentProvider` pallet of the relay chain. [`start_on_demand_order_placement`]
+//! spawns a task that does this on behalf of the collator: on every new best relay chain block it
+//! checks whether the parachain has pending transactions and no order already in flight, and
+//! places a new order as long as the configured spend limit allows it.
+//!
+//! The orders are placed by an [`OnDemandOrderPlacer`]. [`SignedOnDemandOrderPlacer`] signs
+//! `OnDemandAssignmentProvider::place_order_keep_alive` with the paying account and submits it
+//! through the [`RelayChainInterface`].
+//!
+//! The amount spent on orders is kept in the auxiliary storage of the parachain node, so the spend
+//! limit also holds across restarts of the node.
+
+use cumulus_client_cli::OnDemandOrderOptions;
+use cumulus_primitives_core::ParaId;
+use cumulus_relay_chain_interface::{call_runtime_api, RelayChainInterface, RelayChainResult};
+use futures::StreamExt;
+use polkadot_primitives::{
+	AccountId, Balance, BlockId, BlockNumber, Hash as PHash, Header as PHeader, Nonce,
+};
+use sc_client_api::AuxStore;
+use sc_service::TaskManager;
+use sc_telemetry::log;
+use sc_transaction_pool_api::TransactionPool;
+use sp_core::{Decode, Encode, OpaqueMetadata};
+use sp_crypto_hashing::{twox_128, twox_64};
+use sp_runtime::traits::Header;
+use std::{
+	ops::ControlFlow,
+	path::Path,
+	str::FromStr,
+	sync::{Arc, Mutex},
+};
+use subxt::{
+	client::RuntimeVersion as SubxtRuntimeVersion, config::DefaultExtrinsicParamsBuilder,
+	dynamic::Value, utils::H256, OfflineClient, PolkadotConfig,
+};
+use subxt_signer::{sr25519::Keypair, SecretUri};
+
+const LOG_TARGET: &str = "cumulus::on-demand";
+
+/// Name of the on-demand assignment pallet in the relay chain runtime.
+const ON_DEMAND_PALLET: &str = "OnDemandAssignmentProvider";
+
+/// Key of the amount spent on orders in the auxiliary storage, followed by the para id.
+const SPENT_KEY: &[u8] = b"cumulus_on_demand_spent";
+
+/// The metadata version used to build the order extrinsics.
+const METADATA_VERSION: u32 = 15;
+
+/// The number of relay chain blocks an order extrinsic stays valid.
+const ORDER_MORTALITY: u64 = 16;
+
+/// Error returned by an [`OnDemandOrderPlacer`].
+pub type OnDemandOrderError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Places on-demand orders on the relay chain.
+///
+/// Implementations are expected to sign `OnDemandAssignmentProvider::place_order_keep_alive` with
+/// the account paying for the coretime and to submit it to the relay chain.
+#[async_trait::async_trait]
+pub trait OnDemandOrderPlacer: Send + Sync {
+	/// Place an order for `para_id`, paying at most `max_amount`, at the state of `relay_parent`.
+	async fn place_order(
+		&self,
+		relay_parent: PHash,
+		max_amount: Balance,
+		para_id: ParaId,
+	) -> Result<(), OnDemandOrderError>;
+}
+
+/// [`OnDemandOrderPlacer`] signing the orders with an sr25519 account of the relay chain.
+///
+/// The extrinsics are built from the metadata of the relay chain runtime, which is fetched again
+/// whenever the runtime is upgraded.
+pub struct SignedOnDemandOrderPlacer<RCInterface> {
+	relay_chain_interface: RCInterface,
+	signer: Keypair,
+	// The client building the extrinsics, together with the spec version it was built for.
+	offline_client: Mutex<Option<(u32, OfflineClient<PolkadotConfig>)>>,
+}
+
+impl<RCInterface> SignedOnDemandOrderPlacer<RCInterface> {
+	/// Create a new order placer paying with the account of `signer`.
+	pub fn new(relay_chain_interface: RCInterface, signer: Keypair) -> Self {
+		Self { relay_chain_interface, signer, offline_client: Mutex::new(None) }
+	}
+
+	/// Create a new order placer paying with the account of the given secret URI.
+	pub fn from_uri(
+		relay_chain_interface: RCInterface,
+		secret_uri: &str,
+	) -> Result<Self, OnDemandOrderError> {
+		let signer = Keypair::from_uri(&SecretUri::from_str(secret_uri)?)?;
+		Ok(Self::new(relay_chain_interface, signer))
+	}
+
+	/// Create a new order placer paying with the account of the secret URI stored in the file at
+	/// `path`.
+	pub fn from_file(
+		relay_chain_interface: RCInterface,
+		path: &Path,
+	) -> Result<Self, OnDemandOrderError> {
+		let secret_uri = std::fs::read_to_string(path).map_err(|err| {
+			format!("Failed to read the on-demand order account from {}: {err}", path.display())
+		})?;
+		Self::from_uri(relay_chain_interface, secret_uri.trim())
+	}
+
+	/// The account paying for the orders.
+	pub fn account_id(&self) -> AccountId {
+		AccountId::from(self.signer.public_key().0)
+	}
+}
+
+impl<RCInterface: RelayChainInterface> SignedOnDemandOrderPlacer<RCInterface> {
+	async fn offline_client(
+		&self,
+		relay_parent: PHash,
+	) -> Result<OfflineClient<PolkadotConfig>, OnDemandOrderError> {
+		let version = self.relay_chain_interface.version(relay_parent).await?;
+		if let Some((spec_version, client)) = &*self.offline_client.lock().expect("Poisoned lock") {
+			if *spec_version == version.spec_version {
+				return Ok(client.clone())
+			}
+		}
+
+		let metadata: Option<OpaqueMetadata> = call_runtime_api(
+			&self.relay_chain_interface,
+			"Metadata_metadata_at_version",
+			relay_parent,
+			METADATA_VERSION,
+		)
+		.await?;
+		let metadata = metadata.ok_or("The relay chain runtime does not provide metadata V15")?;
+		let metadata = subxt::Metadata::decode(&mut &metadata[..])?;
+		let genesis_hash = self
+			.relay_chain_interface
+			.header(BlockId::Number(0))
+			.await?
+			.ok_or("The relay chain genesis header is unknown")?
+			.hash();
+
+		let client = OfflineClient::new(
+			H256::from(genesis_hash.to_fixed_bytes()),
+			SubxtRuntimeVersion {
+				spec_version: version.spec_version,
+				transaction_version: version.transaction_version,
+			},
+			metadata,
+		);
+		*self.offline_client.lock().expect("Poisoned lock") =
+			Some((version.spec_version, client.clone()));
+
+		Ok(client)
+	}
+}
+
+#[async_trait::async_trait]
+impl<RCInterface: RelayChainInterface> OnDemandOrderPlacer
+	for SignedOnDemandOrderPlacer<RCInterface>
+{
+	async fn place_order(
+		&self,
+		relay_parent: PHash,
+		max_amount: Balance,
+		para_id: ParaId,
+	) -> Result<(), OnDemandOrderError> {
+		let client = self.offline_client(relay_parent).await?;
+		let number = *self
+			.relay_chain_interface
+			.header(BlockId::Hash(relay_parent))
+			.await?
+			.ok_or("The relay parent header is unknown")?
+			.number();
+		let nonce: Nonce = call_runtime_api(
+			&self.relay_chain_interface,
+			"AccountNonceApi_account_nonce",
+			relay_parent,
+			self.account_id(),
+		)
+		.await?;
+
+		let call = subxt::dynamic::tx(
+			ON_DEMAND_PALLET,
+			"place_order_keep_alive",
+			vec![
+				Value::u128(max_amount),
+				Value::unnamed_composite(vec![Value::u128(u32::from(para_id).into())]),
+			],
+		);
+		let params = DefaultExtrinsicParamsBuilder::<PolkadotConfig>::new()
+			.nonce(nonce.into())
+			.mortal_unchecked(
+				number.into(),
+				H256::from(relay_parent.to_fixed_bytes()),
+				ORDER_MORTALITY,
+			)
+			.build();
+		let extrinsic =
+			client.tx().create_signed_offline(&call, &self.signer, params)?.into_encoded();
+
+		let hash = self.relay_chain_interface.submit_extrinsic(extrinsic).await?;
+		log::debug!(
+			target: LOG_TARGET,
+			"Submitted on-demand order {hash:?} with nonce {nonce} at relay block #{number}.",
+		);
+
+		Ok(())
+	}
+}
+
+/// Configuration of the on-demand order placement.
+#[derive(Debug, Clone)]
+pub struct OnDemandOrderConfig {
+	/// The maximum amount paid for a single order.
+	pub max_amount: Balance,
+	/// The total amount the node may spend on orders.
+	///
+	/// Every placed order is accounted with `max_amount`, as the price actually charged is not
+	/// known to the node. The amount spent is kept in the auxiliary storage of the node, so it is
+	/// not reset by a restart.
+	pub spend_limit: Balance,
+	/// The number of ready transactions in the parachain pool required to place an order.
+	pub min_ready_transactions: usize,
+	/// The number of relay chain blocks to wait after an order before placing the next one.
+	pub min_relay_blocks_between_orders: BlockNumber,
+}
+
+impl From<&OnDemandOrderOptions> for OnDemandOrderConfig {
+	fn from(options: &OnDemandOrderOptions) -> Self {
+		Self {
+			max_amount: options.max_amount,
+			spend_limit: options.spend_limit,
+			min_ready_transactions: options.min_ready_transactions,
+			min_relay_blocks_between_orders: options.min_relay_blocks_between_orders,
+		}
+	}
+}
+
+/// Parameters given to [`start_on_demand_order_placement`].
+pub struct StartOnDemandOrderPlacementParams<'a, Pool, RCInterface, Placer, AS> {
+	pub para_id: ParaId,
+	pub config: OnDemandOrderConfig,
+	pub transaction_pool: Arc<Pool>,
+	pub relay_chain_interface: RCInterface,
+	pub order_placer: Placer,
+	/// Where the amount spent on orders is kept, usually the parachain client.
+	pub aux_store: Arc<AS>,
+	pub task_manager: &'a TaskManager,
+}
+
+/// Start placing on-demand orders for the parachain whenever it has pending transactions.
+pub fn start_on_demand_order_placement<Pool, RCInterface, Placer, AS>(
+	StartOnDemandOrderPlacementParams {
+		para_id,
+		config,
+		transaction_pool,
+		relay_chain_interface,
+		order_placer,
+		aux_store,
+		task_manager,
+	}: StartOnDemandOrderPlacementParams<Pool, RCInterface, Placer, AS>,
+) where
+	Pool: TransactionPool + 'static,
+	RCInterface: RelayChainInterface + 'static,
+	Placer: OnDemandOrderPlacer + 'static,
+	AS: AuxStore + Send + Sync + 'static,
+{
+	log::info!(
+		target: LOG_TARGET,
+		"Placing on-demand orders for para {para_id}, paying at most {} per order and {} in total.",
+		config.max_amount,
+		config.spend_limit,
+	);
+
+	task_manager.spawn_handle().spawn(
+		"cumulus-on-demand-orders",
+		None,
+		run_on_demand_order_placement(
+			OrderPlacement {
+				para_id,
+				config,
+				relay_chain_interface,
+				order_placer,
+				aux_store,
+				spent: 0,
+				last_order: None,
+			},
+			move || transaction_pool.status().ready,
+		),
+	);
+}
+
+async fn run_on_demand_order_placement<RCInterface, Placer, AS>(
+	mut placement: OrderPlacement<RCInterface, Placer, AS>,
+	ready_transactions: impl Fn() -> usize,
+) where
+	RCInterface: RelayChainInterface,
+	Placer: OnDemandOrderPlacer,
+	AS: AuxStore,
+{
+	let mut best_headers =
+		match placement.relay_chain_interface.new_best_notification_stream().await {
+			Ok(stream) => stream,
+			Err(err) => {
+				log::error!(
+					target: LOG_TARGET,
+					"Failed to subscribe to relay chain best blocks, not placing on-demand orders: {err:?}",
+				);
+				return
+			},
+		};
+
+	if let Err(err) = placement.load_spent() {
+		log::error!(
+			target: LOG_TARGET,
+			"Failed to load the amount spent on on-demand orders, not placing orders: {err:?}",
+		);
+		return
+	}
+
+	while let Some(header) = best_headers.next().await {
+		if placement.on_new_best(&header, ready_transactions()).await.is_break() {
+			return
+		}
+	}
+}
+
+struct OrderPlacement<RCInterface, Placer, AS> {
+	para_id: ParaId,
+	config: OnDemandOrderConfig,
+	relay_chain_interface: RCInterface,
+	order_placer: Placer,
+	aux_store: Arc<AS>,
+	spent: Balance,
+	last_order: Option<BlockNumber>,
+}
+
+impl<RCInterface, Placer, AS> OrderPlacement<RCInterface, Placer, AS>
+where
+	RCInterface: RelayChainInterface,
+	Placer: OnDemandOrderPlacer,
+	AS: AuxStore,
+{
+	fn load_spent(&mut self) -> sp_blockchain::Result<()> {
+		self.spent = match self.aux_store.get_aux(&spent_key(self.para_id))? {
+			Some(encoded) => Balance::decode(&mut &encoded[..]).map_err(|e| {
+				sp_blockchain::Error::Backend(format!("Invalid amount spent on orders: {e}"))
+			})?,
+			None => 0,
+		};
+		Ok(())
+	}
+
+	fn store_spent(&self, spent: Balance) -> sp_blockchain::Result<()> {
+		let key = spent_key(self.para_id);
+		let encoded = spent.encode();
+		self.aux_store.insert_aux(&[(&key[..], &encoded[..])], &[])
+	}
+
+	/// Place an order at the new best relay chain block `header` if needed. Breaks once no further
+	/// orders may be placed.
+	async fn on_new_best(
+		&mut self,
+		header: &PHeader,
+		ready_transactions: usize,
+	) -> ControlFlow<()> {
+		let relay_parent = header.hash();
+		let number = *header.number();
+
+		if ready_transactions < self.config.min_ready_transactions {
+			return ControlFlow::Continue(())
+		}
+
+		if self.last_order.is_some_and(|last| {
+			number.saturating_sub(last) < self.config.min_relay_blocks_between_orders
+		}) {
+			return ControlFlow::Continue(())
+		}
+
+		if self.config.spend_limit.saturating_sub(self.spent) < self.config.max_amount {
+			log::warn!(
+				target: LOG_TARGET,
+				"Spend limit of {} reached after spending {}, no longer placing on-demand orders.",
+				self.config.spend_limit,
+				self.spent,
+			);
+			return ControlFlow::Break(())
+		}
+
+		match order_in_flight(&self.relay_chain_interface, self.para_id, relay_parent).await {
+			Ok(true) => return ControlFlow::Continue(()),
+			Ok(false) => {},
+			Err(err) => {
+				log::debug!(
+					target: LOG_TARGET,
+					"Failed to fetch on-demand queue state at {relay_parent:?}: {err:?}",
+				);
+				return ControlFlow::Continue(())
+			},
+		}
+
+		// The order is accounted before it is placed, so that a crash in between can not make the
+		// node spend more than the limit.
+		let spent = self.spent.saturating_add(self.config.max_amount);
+		if let Err(err) = self.store_spent(spent) {
+			log::error!(
+				target: LOG_TARGET,
+				"Failed to store the amount spent on on-demand orders, no longer placing orders: {err:?}",
+			);
+			return ControlFlow::Break(())
+		}
+
+		self.last_order = Some(number);
+		match self
+			.order_placer
+			.place_order(relay_parent, self.config.max_amount, self.para_id)
+			.await
+		{
+			Ok(()) => {
+				self.spent = spent;
+				log::info!(
+					target: LOG_TARGET,
+					"Placed on-demand order at relay block #{number} (max amount {}, spent {spent} of {}).",
+					self.config.max_amount,
+					self.config.spend_limit,
+				);
+			},
+			Err(err) => {
+				log::warn!(
+					target: LOG_TARGET,
+					"Failed to place on-demand order at relay block #{number}: {err}",
+				);
+				if let Err(err) = self.store_spent(self.spent) {
+					log::warn!(
+						target: LOG_TARGET,
+						"Failed to revert the amount spent on on-demand orders: {err:?}",
+					);
+					self.spent = spent;
+				}
+			},
+		}
+
+		ControlFlow::Continue(())
+	}
+}
+
+/// Returns `true` if the parachain already has a claim on a core or an order in the on-demand
+/// queue of the relay chain at `relay_parent`.
+async fn order_in_flight(
+	relay_chain_interface: &impl RelayChainInterface,
+	para_id: ParaId,
+	relay_parent: PHash,
+) -> RelayChainResult<bool> {
+	let claim_queue = relay_chain_interface.claim_queue(relay_parent).await?;
+	if claim_queue.values().any(|claims| claims.contains(&para_id)) {
+		return Ok(true)
+	}
+
+	// Orders of a para that is already assigned to a core are queued with affinity to that core.
+	// The affinity entry only exists as long as such orders are around.
+	if relay_chain_interface
+		.get_storage_by_key(relay_parent, &para_id_affinity_key(para_id))
+		.await?
+		.is_some()
+	{
+		return Ok(true)
+	}
+
+	let Some(free_entries) = relay_chain_interface
+		.get_storage_by_key(relay_parent, &free_entries_key())
+		.await?
+	else {
+		return Ok(false)
+	};
+
+	// Mirrors `BinaryHeap<EnqueuedOrder>` of `polkadot-runtime-parachains`.
+	let free_entries = Vec::<(ParaId, u32)>::decode(&mut &free_entries[..])?;
+
+	Ok(free_entries.iter().any(|(id, _)| *id == para_id))
+}
+
+fn spent_key(para_id: ParaId) -> Vec<u8> {
+	(SPENT_KEY, para_id).encode()
+}
+
+fn free_entries_key() -> Vec<u8> {
+	[twox_128(ON_DEMAND_PALLET.as_bytes()), twox_128(b"FreeEntries")].concat()
+}
+
+fn para_id_affinity_key(para_id: ParaId) -> Vec<u8> {
+	let encoded = para_id.encode();
+	[
+		&twox_128(ON_DEMAND_PALLET.as_bytes())[..],
+		&twox_128(b"ParaIdAffinity")[..],
+		&twox_64(&encoded)[..],
+		&encoded[..],
+	]
+	.concat()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use cumulus_relay_chain_interface::{
+		CommittedCandidateReceipt, CoreIndex, CoreState, InboundDownwardMessage,
+		InboundHrmpMessage, OccupiedCoreAssumption, OverseerHandle, PersistedValidationData,
+		SessionIndex, StorageValue, ValidationCodeHash, ValidatorId,
+	};
+	use frame_support::{traits::StorageInfoTrait, StorageHasher, Twox64Concat};
+	use futures::{executor::block_on, Stream};
+	use sc_client_api::StorageProof;
+	use sp_version::RuntimeVersion;
+	use std::{
+		collections::{BTreeMap, HashMap, VecDeque},
+		io::Write,
+		pin::Pin,
+	};
+
+	const PARA_ID: ParaId = ParaId::new(2000);
+	const MAX_AMOUNT: Balance = 100;
+
+	#[derive(Default)]
+	struct TestRelayChain {
+		claim_queue: Mutex<BTreeMap<CoreIndex, VecDeque<ParaId>>>,
+		storage: Mutex<HashMap<Vec<u8>, Vec<u8>>>,
+	}
+
+	#[async_trait::async_trait]
+	impl RelayChainInterface for TestRelayChain {
+		async fn get_storage_by_key(
+			&self,
+			_: PHash,
+			key: &[u8],
+		) -> RelayChainResult<Option<StorageValue>> {
+			Ok(self.storage.lock().unwrap().get(key).cloned())
+		}
+
+		async fn validators(&self, _: PHash) -> RelayChainResult<Vec<ValidatorId>> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn best_block_hash(&self) -> RelayChainResult<PHash> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn header(&self, _: BlockId) -> RelayChainResult<Option<PHeader>> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn finalized_block_hash(&self) -> RelayChainResult<PHash> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn call_runtime_api(
+			&self,
+			_: &'static str,
+			_: PHash,
+			_: &[u8],
+		) -> RelayChainResult<Vec<u8>> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn retrieve_dmq_contents(
+			&self,
+			_: ParaId,
+			_: PHash,
+		) -> RelayChainResult<Vec<InboundDownwardMessage>> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn retrieve_all_inbound_hrmp_channel_contents(
+			&self,
+			_: ParaId,
+			_: PHash,
+		) -> RelayChainResult<BTreeMap<ParaId, Vec<InboundHrmpMessage>>> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn persisted_validation_data(
+			&self,
+			_: PHash,
+			_: ParaId,
+			_: OccupiedCoreAssumption,
+		) -> RelayChainResult<Option<PersistedValidationData>> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn candidate_pending_availability(
+			&self,
+			_: PHash,
+			_: ParaId,
+		) -> RelayChainResult<Option<CommittedCandidateReceipt>> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn session_index_for_child(&self, _: PHash) -> RelayChainResult<SessionIndex> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn import_notification_stream(
+			&self,
+		) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn new_best_notification_stream(
+			&self,
+		) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn wait_for_block(&self, _: PHash) -> RelayChainResult<()> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn finality_notification_stream(
+			&self,
+		) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn is_major_syncing(&self) -> RelayChainResult<bool> {
+			unimplemented!("Not needed for test")
+		}
+
+		fn overseer_handle(&self) -> RelayChainResult<OverseerHandle> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn prove_read(&self, _: PHash, _: &Vec<Vec<u8>>) -> RelayChainResult<StorageProof> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn validation_code_hash(
+			&self,
+			_: PHash,
+			_: ParaId,
+			_: OccupiedCoreAssumption,
+		) -> RelayChainResult<Option<ValidationCodeHash>> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn candidates_pending_availability(
+			&self,
+			_: PHash,
+			_: ParaId,
+		) -> RelayChainResult<Vec<CommittedCandidateReceipt>> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn version(&self, _: PHash) -> RelayChainResult<RuntimeVersion> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn availability_cores(
+			&self,
+			_: PHash,
+		) -> RelayChainResult<Vec<CoreState<PHash, BlockNumber>>> {
+			unimplemented!("Not needed for test")
+		}
+
+		async fn claim_queue(
+			&self,
+			_: PHash,
+		) -> RelayChainResult<BTreeMap<CoreIndex, VecDeque<ParaId>>> {
+			Ok(self.claim_queue.lock().unwrap().clone())
+		}
+
+		async fn submit_extrinsic(&self, _: Vec<u8>) -> RelayChainResult<PHash> {
+			unimplemented!("Not needed for test")
+		}
+	}
+
+	#[derive(Default)]
+	struct TestOrderPlacer {
+		orders: Mutex<Vec<(PHash, Balance, ParaId)>>,
+		fail: Mutex<bool>,
+	}
+
+	#[async_trait::async_trait]
+	impl OnDemandOrderPlacer for Arc<TestOrderPlacer> {
+		async fn place_order(
+			&self,
+			relay_parent: PHash,
+			max_amount: Balance,
+			para_id: ParaId,
+		) -> Result<(), OnDemandOrderError> {
+			if *self.fail.lock().unwrap() {
+				return Err("Failed to submit".into())
+			}
+			self.orders.lock().unwrap().push((relay_parent, max_amount, para_id));
+			Ok(())
+		}
+	}
+
+	#[derive(Default)]
+	struct TestAuxStore(Mutex<HashMap<Vec<u8>, Vec<u8>>>);
+
+	impl AuxStore for TestAuxStore {
+		fn insert_aux<
+			'a,
+			'b: 'a,
+			'c: 'a,
+			I: IntoIterator<Item = &'a (&'c [u8], &'c [u8])>,
+			D: IntoIterator<Item = &'a &'b [u8]>,
+		>(
+			&self,
+			insert: I,
+			delete: D,
+		) -> sp_blockchain::Result<()> {
+			let mut store = self.0.lock().unwrap();
+			for (key, value) in insert {
+				store.insert(key.to_vec(), value.to_vec());
+			}
+			for key in delete {
+				store.remove(*key);
+			}
+			Ok(())
+		}
+
+		fn get_aux(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+			Ok(self.0.lock().unwrap().get(key).cloned())
+		}
+	}
+
+	struct TestSetup {
+		relay_chain: Arc<TestRelayChain>,
+		order_placer: Arc<TestOrderPlacer>,
+		aux_store: Arc<TestAuxStore>,
+	}
+
+	impl TestSetup {
+		fn new() -> Self {
+			Self {
+				relay_chain: Default::default(),
+				order_placer: Default::default(),
+				aux_store: Default::default(),
+			}
+		}
+
+		fn placement(
+			&self,
+			spend_limit: Balance,
+		) -> OrderPlacement<Arc<TestRelayChain>, Arc<TestOrderPlacer>, TestAuxStore> {
+			let mut placement = OrderPlacement {
+				para_id: PARA_ID,
+				config: OnDemandOrderConfig {
+					max_amount: MAX_AMOUNT,
+					spend_limit,
+					min_ready_transactions: 1,
+					min_relay_blocks_between_orders: 2,
+				},
+				relay_chain_interface: self.relay_chain.clone(),
+				order_placer: self.order_placer.clone(),
+				aux_store: self.aux_store.clone(),
+				spent: 0,
+				last_order: None,
+			};
+			placement.load_spent().unwrap();
+			placement
+		}
+
+		fn orders(&self) -> Vec<BlockNumber> {
+			self.order_placer
+				.orders
+				.lock()
+				.unwrap()
+				.iter()
+				.map(|(relay_parent, max_amount, para_id)| {
+					assert_eq!((*max_amount, *para_id), (MAX_AMOUNT, PARA_ID));
+					(1..10).find(|number| header(*number).hash() == *relay_parent).unwrap()
+				})
+				.collect()
+		}
+
+		fn stored_spent(&self) -> Option<Balance> {
+			self.aux_store
+				.get_aux(&spent_key(PARA_ID))
+				.unwrap()
+				.map(|encoded| Balance::decode(&mut &encoded[..]).unwrap())
+		}
+	}
+
+	fn header(number: BlockNumber) -> PHeader {
+		PHeader::new(
+			number,
+			Default::default(),
+			Default::default(),
+			Default::default(),
+			Default::default(),
+		)
+	}
+
+	#[test]
+	fn storage_keys_match_pallet_layout() {
+		let storage_info =
+			<polkadot_test_runtime::OnDemandAssignmentProvider as StorageInfoTrait>::storage_info();
+		let prefix = |name: &str| {
+			let info = storage_info
+				.iter()
+				.find(|info| info.storage_name == name.as_bytes())
+				.expect("Storage item exists");
+			assert_eq!(info.pallet_name, ON_DEMAND_PALLET.as_bytes());
+			info.prefix.clone()
+		};
+
+		assert_eq!(free_entries_key(), prefix("FreeEntries"));
+		assert_eq!(
+			para_id_affinity_key(PARA_ID),
+			[prefix("ParaIdAffinity"), Twox64Concat::hash(&PARA_ID.encode())].concat(),
+		);
+	}
+
+	#[test]
+	fn places_orders_for_pending_transactions() {
+		let setup = TestSetup::new();
+		let mut placement = setup.placement(10 * MAX_AMOUNT);
+
+		block_on(async {
+			// No pending transactions.
+			assert!(placement.on_new_best(&header(1), 0).await.is_continue());
+			assert!(setup.orders().is_empty());
+
+			assert!(placement.on_new_best(&header(2), 1).await.is_continue());
+			// Too close to the previous order.
+			assert!(placement.on_new_best(&header(3), 1).await.is_continue());
+			assert!(placement.on_new_best(&header(4), 1).await.is_continue());
+		});
+
+		assert_eq!(setup.orders(), vec![2, 4]);
+		assert_eq!(setup.stored_spent(), Some(2 * MAX_AMOUNT));
+	}
+
+	#[test]
+	fn does_not_place_orders_while_one_is_in_flight() {
+		let setup = TestSetup::new();
+		let mut placement = setup.placement(10 * MAX_AMOUNT);
+
+		block_on(async {
+			setup
+				.relay_chain
+				.claim_queue
+				.lock()
+				.unwrap()
+				.insert(CoreIndex(0), VecDeque::from([PARA_ID]));
+			assert!(placement.on_new_best(&header(1), 1).await.is_continue());
+			setup.relay_chain.claim_queue.lock().unwrap().clear();
+
+			setup
+				.relay_chain
+				.storage
+				.lock()
+				.unwrap()
+				.insert(para_id_affinity_key(PARA_ID), 1u32.encode());
+			assert!(placement.on_new_best(&header(2), 1).await.is_continue());
+			setup.relay_chain.storage.lock().unwrap().clear();
+
+			let free_entries = vec![(ParaId::new(1000), 0u32), (PARA_ID, 1u32)];
+			setup
+				.relay_chain
+				.storage
+				.lock()
+				.unwrap()
+				.insert(free_entries_key(), free_entries.encode());
+			assert!(placement.on_new_best(&header(3), 1).await.is_continue());
+			assert!(setup.orders().is_empty());
+
+			// Only orders of other paras are queued.
+			let free_entries = vec![(ParaId::new(1000), 0u32)];
+			setup
+				.relay_chain
+				.storage
+				.lock()
+				.unwrap()
+				.insert(free_entries_key(), free_entries.encode());
+			assert!(placement.on_new_best(&header(4), 1).await.is_continue());
+		});
+
+		assert_eq!(setup.orders(), vec![4]);
+	}
+
+	#[test]
+	fn spend_limit_holds_across_restarts() {
+		let setup = TestSetup::new();
+		let mut placement = setup.placement(2 * MAX_AMOUNT + MAX_AMOUNT / 2);
+
+		block_on(async {
+			assert!(placement.on_new_best(&header(1), 1).await.is_continue());
+			assert!(placement.on_new_best(&header(3), 1).await.is_continue());
+			assert!(placement.on_new_best(&header(5), 1).await.is_break());
+		});
+		assert_eq!(setup.orders(), vec![1, 3]);
+
+		// A restarted node knows what was spent before.
+		let mut placement = setup.placement(2 * MAX_AMOUNT + MAX_AMOUNT / 2);
+		assert!(block_on(placement.on_new_best(&header(7), 1)).is_break());
+
+		// Raising the limit allows further orders.
+		let mut placement = setup.placement(3 * MAX_AMOUNT);
+		assert!(block_on(placement.on_new_best(&header(7), 1)).is_continue());
+		assert_eq!(setup.orders(), vec![1, 3, 7]);
+		assert_eq!(setup.stored_spent(), Some(3 * MAX_AMOUNT));
+	}
+
+	#[test]
+	fn failed_orders_are_not_accounted() {
+		let setup = TestSetup::new();
+		let mut placement = setup.placement(10 * MAX_AMOUNT);
+
+		*setup.order_placer.fail.lock().unwrap() = true;
+		assert!(block_on(placement.on_new_best(&header(1), 1)).is_continue());
+		assert_eq!(setup.stored_spent(), Some(0));
+
+		*setup.order_placer.fail.lock().unwrap() = false;
+		// The failed order still delays the next one.
+		assert!(block_on(placement.on_new_best(&header(2), 1)).is_continue());
+		assert!(block_on(placement.on_new_best(&header(3), 1)).is_continue());
+		assert_eq!(setup.orders(), vec![3]);
+		assert_eq!(setup.stored_spent(), Some(MAX_AMOUNT));
+	}
+
+	#[test]
+	fn order_account_is_read_from_a_file() {
+		let mut file = tempfile::NamedTempFile::new().unwrap();
+		writeln!(file, "//Alice").unwrap();
+
+		let from_file =
+			SignedOnDemandOrderPlacer::from_file(TestRelayChain::default(), file.path()).unwrap();
+		let from_uri =
+			SignedOnDemandOrderPlacer::from_uri(TestRelayChain::default(), "//Alice").unwrap();
+		assert_eq!(from_file.account_id(), from_uri.account_id());
+
+		assert!(SignedOnDemandOrderPlacer::from_file(
+			TestRelayChain::default(),
+			&file.path().with_extension("missing"),
+		)
+		.is_err());
+	}
+}
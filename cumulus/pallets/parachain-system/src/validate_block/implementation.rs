@@ -18,7 +18,8 @@
 
 use super::{trie_cache, trie_recorder, MemoryOptimizedValidationParams};
 use cumulus_primitives_core::{
-	relay_chain::Hash as RHash, ParachainBlockData, PersistedValidationData,
+	check_bundle_messages, relay_chain::Hash as RHash, AbridgedHostConfiguration,
+	ParachainBlockBundle, ParachainBlockData, PersistedValidationData,
 };
 use cumulus_primitives_parachain_inherent::ParachainInherentData;
 
@@ -34,7 +35,7 @@ use sp_core::storage::{ChildInfo, StateVersion};
 use sp_externalities::{set_and_run_with_externalities, Externalities};
 use sp_io::KillStorageResult;
 use sp_runtime::traits::{Block as BlockT, ExtrinsicLike, HashingFor, Header as HeaderT};
-use sp_trie::{MemoryDB, ProofSizeProvider};
+use sp_trie::{HashDBT, MemoryDB, ProofSizeProvider, EMPTY_PREFIX};
 use trie_recorder::SizeOnlyRecorderProvider;
 
 type TrieBackend<B> = sp_state_machine::TrieBackend<
@@ -57,7 +58,10 @@ environmental::environmental!(recorder: trait ProofSizeProvider);
 ///
 /// This function is doing roughly the following:
 ///
-/// 1. We decode the [`ParachainBlockData`] from the `block_data` in `params`.
+/// 1. We decode the [`ParachainBlockData`] from the `block_data` in `params`. The `block_data` can
+/// also be a [`ParachainBlockBundle`], in which case steps 2, 5 and 6 are done for each block of
+/// the bundle, on top of the state left by the previous one. The trie nodes created by a block are
+/// added to the in-memory database, as they are not part of the storage proof.
 ///
 /// 2. We are doing some security checks like checking that the `parent_head` in `params`
 /// is the parent of the block we are going to check. We also ensure that the `set_validation_data`
@@ -79,6 +83,7 @@ environmental::environmental!(recorder: trait ProofSizeProvider);
 /// the blocks include running all transactions in the block against our in-memory database and
 /// ensuring that the final storage root matches the storage root in the header of the block. In the
 /// end we return back the [`ValidationResult`] with all the required information for the validator.
+/// For a bundle, the results of all blocks are merged into one.
 #[doc(hidden)]
 #[allow(deprecated)]
 pub fn validate_block<
@@ -98,45 +103,36 @@ where
 	B::Extrinsic: ExtrinsicCall,
 	<B::Extrinsic as ExtrinsicCall>::Call: IsSubType<crate::Call<PSC>>,
 {
-	let block_data = codec::decode_from_bytes::<ParachainBlockData<B>>(block_data)
-		.expect("Invalid parachain block data");
-
-	let parent_header =
+	let mut parent_header =
 		codec::decode_from_bytes::<B::Header>(parent_head.clone()).expect("Invalid parent head");
 
-	let (header, extrinsics, storage_proof) = block_data.deconstruct();
+	let (blocks, storage_proof) = if ParachainBlockBundle::<B>::is_bundle(&block_data) {
+		let (blocks, storage_proof) =
+			codec::decode_from_bytes::<ParachainBlockBundle<B>>(block_data)
+				.expect("Invalid parachain block bundle")
+				.deconstruct();
 
-	let block = B::new(header, extrinsics);
-	assert!(parent_header.hash() == *block.header().parent_hash(), "Invalid parent hash");
+		assert!(!blocks.is_empty(), "Parachain block bundle without blocks");
 
-	let inherent_data = extract_parachain_inherent_data(&block);
+		(blocks, storage_proof)
+	} else {
+		let block_data = codec::decode_from_bytes::<ParachainBlockData<B>>(block_data)
+			.expect("Invalid parachain block data");
 
-	validate_validation_data(
-		&inherent_data.validation_data,
-		relay_parent_number,
-		relay_parent_storage_root,
-		parent_head,
-	);
+		let (header, extrinsics, storage_proof) = block_data.deconstruct();
+
+		(alloc::vec![B::new(header, extrinsics)], storage_proof)
+	};
 
 	// Create the db
-	let db = match storage_proof.to_memory_db(Some(parent_header.state_root())) {
+	let mut db = match storage_proof.to_memory_db(Some(parent_header.state_root())) {
 		Ok((db, _)) => db,
 		Err(_) => panic!("Compact proof decoding failure."),
 	};
 
 	core::mem::drop(storage_proof);
 
-	let mut recorder = SizeOnlyRecorderProvider::new();
-	let cache_provider = trie_cache::CacheProvider::new();
-	// We use the storage root of the `parent_head` to ensure that it is the correct root.
-	// This is already being done above while creating the in-memory db, but let's be paranoid!!
-	let backend = sp_state_machine::TrieBackendBuilder::new_with_cache(
-		db,
-		*parent_header.state_root(),
-		cache_provider,
-	)
-	.with_recorder(recorder.clone())
-	.build();
+	let state_version = <PSC as frame_system::Config>::Version::get().state_version();
 
 	let _guard = (
 		// Replace storage calls with our own implementations
@@ -179,59 +175,173 @@ where
 			.replace_implementation(host_storage_proof_size),
 	);
 
-	run_with_externalities_and_recorder::<B, _, _>(&backend, &mut recorder, || {
-		let relay_chain_proof = crate::RelayChainStateProof::new(
-			PSC::SelfParaId::get(),
-			inherent_data.validation_data.relay_parent_storage_root,
-			inherent_data.relay_chain_state.clone(),
+	let mut parent_head = parent_head;
+	let mut validation_result = None;
+	let mut remaining_blocks = blocks.len();
+
+	for block in blocks {
+		remaining_blocks -= 1;
+
+		assert!(parent_header.hash() == *block.header().parent_hash(), "Invalid parent hash");
+
+		let inherent_data = extract_parachain_inherent_data(&block);
+
+		validate_validation_data(
+			&inherent_data.validation_data,
+			relay_parent_number,
+			relay_parent_storage_root,
+			parent_head,
+		);
+
+		let mut recorder = SizeOnlyRecorderProvider::new();
+		let cache_provider = trie_cache::CacheProvider::new();
+		// We use the storage root of the `parent_head` to ensure that it is the correct root.
+		// For the first block this is already being done above while creating the in-memory db,
+		// but let's be paranoid!!
+		let backend = sp_state_machine::TrieBackendBuilder::new_with_cache(
+			db,
+			*parent_header.state_root(),
+			cache_provider,
 		)
-		.expect("Invalid relay chain state proof");
+		.with_recorder(recorder.clone())
+		.build();
+
+		run_with_externalities_and_recorder::<B, _, _>(
+			&backend,
+			&mut recorder,
+			&mut Default::default(),
+			|| {
+				let relay_chain_proof = crate::RelayChainStateProof::new(
+					PSC::SelfParaId::get(),
+					inherent_data.validation_data.relay_parent_storage_root,
+					inherent_data.relay_chain_state.clone(),
+				)
+				.expect("Invalid relay chain state proof");
+
+				#[allow(deprecated)]
+				let res = CI::check_inherents(&block, &relay_chain_proof);
+
+				if !res.ok() {
+					if log::log_enabled!(log::Level::Error) {
+						res.into_errors().for_each(|e| {
+							log::error!("Checking inherent with identifier `{:?}` failed", e.0)
+						});
+					}
+
+					panic!("Checking inherents failed");
+				}
+			},
+		);
 
-		#[allow(deprecated)]
-		let res = CI::check_inherents(&block, &relay_chain_proof);
+		let header = block.header().clone();
+
+		let mut overlay = sp_state_machine::OverlayedChanges::default();
+		let (block_result, host_config) = run_with_externalities_and_recorder::<B, _, _>(
+			&backend,
+			&mut recorder,
+			&mut overlay,
+			|| {
+				let head_data = HeadData(block.header().encode());
+
+				E::execute_block(block);
+
+				let new_validation_code = crate::NewValidationCode::<PSC>::get();
+				let upward_messages = crate::UpwardMessages::<PSC>::get().try_into().expect(
+					"Number of upward messages should not be greater than `MAX_UPWARD_MESSAGE_NUM`",
+				);
+				let processed_downward_messages = crate::ProcessedDownwardMessages::<PSC>::get();
+				let horizontal_messages = crate::HrmpOutboundMessages::<PSC>::get().try_into().expect(
+					"Number of horizontal messages should not be greater than `MAX_HORIZONTAL_MESSAGE_NUM`",
+				);
+				let hrmp_watermark = crate::HrmpWatermark::<PSC>::get();
+				let host_config = crate::HostConfiguration::<PSC>::get()
+					.expect("Host configuration is set by `set_validation_data`");
+
+				let head_data =
+					if let Some(custom_head_data) = crate::CustomValidationHeadData::<PSC>::get() {
+						HeadData(custom_head_data)
+					} else {
+						head_data
+					};
+
+				let result = ValidationResult {
+					head_data,
+					new_validation_code: new_validation_code.map(Into::into),
+					upward_messages,
+					processed_downward_messages,
+					horizontal_messages,
+					hrmp_watermark,
+				};
+
+				(result, host_config)
+			},
+		);
 
-		if !res.ok() {
-			if log::log_enabled!(log::Level::Error) {
-				res.into_errors().for_each(|e| {
-					log::error!("Checking inherent with identifier `{:?}` failed", e.0)
-				});
+		let transaction = (remaining_blocks > 0).then(|| {
+			overlay
+				.drain_storage_changes(&backend, state_version)
+				.expect("Storage changes of the executed block can be collected")
+				.transaction
+		});
+
+		// The next block of a bundle is built on top of this one, reusing the same db. The trie
+		// nodes created by this block were left out of the storage proof, so we add them.
+		db = backend.into_storage();
+		if let Some(mut transaction) = transaction {
+			for (_, (value, rc)) in transaction.drain() {
+				if rc > 0 {
+					db.insert(EMPTY_PREFIX, &value);
+				}
 			}
-
-			panic!("Checking inherents failed");
 		}
-	});
 
-	run_with_externalities_and_recorder::<B, _, _>(&backend, &mut recorder, || {
-		let head_data = HeadData(block.header().encode());
+		parent_header = header;
+		parent_head = block_result.head_data.0.clone().into();
 
-		E::execute_block(block);
+		validation_result = Some(match validation_result {
+			None => block_result,
+			Some(previous) => merge_validation_results(previous, block_result, &host_config),
+		});
+	}
 
-		let new_validation_code = crate::NewValidationCode::<PSC>::get();
-		let upward_messages = crate::UpwardMessages::<PSC>::get().try_into().expect(
+	validation_result.expect("There is at least one block to validate; qed")
+}
+
+/// Merge the [`ValidationResult`] of a block of a [`ParachainBlockBundle`] into the result of the
+/// blocks preceding it.
+///
+/// The merged messages must follow the rules the relay chain applies to the messages of one
+/// candidate, see [`check_bundle_messages`].
+fn merge_validation_results(
+	previous: ValidationResult,
+	next: ValidationResult,
+	host_config: &AbridgedHostConfiguration,
+) -> ValidationResult {
+	let mut upward_messages = previous.upward_messages.into_inner();
+	upward_messages.extend(next.upward_messages);
+
+	let mut horizontal_messages = previous.horizontal_messages.into_inner();
+	horizontal_messages.extend(next.horizontal_messages);
+
+	if let Err(e) = check_bundle_messages(upward_messages.len(), &horizontal_messages, host_config)
+	{
+		panic!("Messages of the bundle can not be sent by one candidate: {:?}", e);
+	}
+
+	ValidationResult {
+		head_data: next.head_data,
+		new_validation_code: next.new_validation_code.or(previous.new_validation_code),
+		upward_messages: upward_messages.try_into().expect(
 			"Number of upward messages should not be greater than `MAX_UPWARD_MESSAGE_NUM`",
-		);
-		let processed_downward_messages = crate::ProcessedDownwardMessages::<PSC>::get();
-		let horizontal_messages = crate::HrmpOutboundMessages::<PSC>::get().try_into().expect(
+		),
+		processed_downward_messages: previous
+			.processed_downward_messages
+			.saturating_add(next.processed_downward_messages),
+		horizontal_messages: horizontal_messages.try_into().expect(
 			"Number of horizontal messages should not be greater than `MAX_HORIZONTAL_MESSAGE_NUM`",
-		);
-		let hrmp_watermark = crate::HrmpWatermark::<PSC>::get();
-
-		let head_data =
-			if let Some(custom_head_data) = crate::CustomValidationHeadData::<PSC>::get() {
-				HeadData(custom_head_data)
-			} else {
-				head_data
-			};
-
-		ValidationResult {
-			head_data,
-			new_validation_code: new_validation_code.map(Into::into),
-			upward_messages,
-			processed_downward_messages,
-			horizontal_messages,
-			hrmp_watermark,
-		}
-	})
+		),
+		hrmp_watermark: next.hrmp_watermark,
+	}
 }
 
 /// Extract the [`ParachainInherentData`].
@@ -277,10 +387,10 @@ fn validate_validation_data(
 fn run_with_externalities_and_recorder<B: BlockT, R, F: FnOnce() -> R>(
 	backend: &TrieBackend<B>,
 	recorder: &mut SizeOnlyRecorderProvider<HashingFor<B>>,
+	overlay: &mut sp_state_machine::OverlayedChanges<HashingFor<B>>,
 	execute: F,
 ) -> R {
-	let mut overlay = sp_state_machine::OverlayedChanges::default();
-	let mut ext = Ext::<B>::new(&mut overlay, backend);
+	let mut ext = Ext::<B>::new(overlay, backend);
 	recorder.reset();
 
 	recorder::using(recorder, || set_and_run_with_externalities(&mut ext, || execute()))
@@ -15,20 +15,24 @@
 // along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
 
 use codec::{Decode, DecodeAll, Encode};
-use cumulus_primitives_core::{ParachainBlockData, PersistedValidationData};
+use cumulus_primitives_core::{
+	ParaId, ParachainBlockBundle, ParachainBlockData, PersistedValidationData,
+};
 use cumulus_test_client::{
-	generate_extrinsic,
+	generate_extrinsic, import_sealed_block,
 	runtime::{
 		self as test_runtime, Block, Hash, Header, TestPalletCall, UncheckedExtrinsic, WASM_BINARY,
 	},
-	seal_block, transfer, BlockData, BlockOrigin, BuildParachainBlockData, Client,
-	ClientBlockImportExt, DefaultTestClientBuilderExt, HeadData, InitBlockBuilder,
+	seal_block, seal_header, transfer, BlockBuilderAndSupportData, BlockData, BlockOrigin,
+	BuildParachainBlockData, Client, ClientBlockImportExt, DefaultTestClientBuilderExt, HeadData,
+	InitBlockBuilder,
 	Sr25519Keyring::{Alice, Bob, Charlie},
 	TestClientBuilder, TestClientBuilderExt, ValidationParams,
 };
 use cumulus_test_relay_sproof_builder::RelayStateSproofBuilder;
 use sp_consensus_slots::Slot;
-use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use sp_runtime::traits::{Block as BlockT, HashingFor, Header as HeaderT};
+use sp_trie::StorageProof;
 
 use std::{env, process::Command};
 
@@ -36,7 +40,7 @@ use crate::validate_block::MemoryOptimizedValidationParams;
 
 fn call_validate_block_encoded_header(
 	parent_head: Header,
-	block_data: ParachainBlockData<Block>,
+	block_data: impl Encode,
 	relay_parent_storage_root: Hash,
 ) -> cumulus_test_client::ExecutorResult<Vec<u8>> {
 	cumulus_test_client::validate_block(
@@ -53,7 +57,7 @@ fn call_validate_block_encoded_header(
 
 fn call_validate_block(
 	parent_head: Header,
-	block_data: ParachainBlockData<Block>,
+	block_data: impl Encode,
 	relay_parent_storage_root: Hash,
 ) -> cumulus_test_client::ExecutorResult<Header> {
 	call_validate_block_encoded_header(parent_head, block_data, relay_parent_storage_root)
@@ -106,6 +110,102 @@ fn build_block_with_witness(
 	TestBlockData { block, validation_data: persisted_validation_data, slot }
 }
 
+/// A sealed block of a bundle and the storage proof recorded while building it.
+struct TestBundleBlock {
+	block: Block,
+	proof: StorageProof,
+	validation_data: PersistedValidationData,
+}
+
+/// Build a block of a [`ParachainBlockBundle`] on top of `parent_head`, then seal and import it.
+///
+/// All the blocks of a bundle are built against the same relay parent, so they share the
+/// `included_head`, the relay chain slot derived from `timestamp` and the `sproof_builder`.
+fn build_and_import_bundle_block(
+	client: &Client,
+	extra_extrinsics: Vec<UncheckedExtrinsic>,
+	parent_head: &Header,
+	included_head: &Header,
+	timestamp: u64,
+	mut sproof_builder: RelayStateSproofBuilder,
+) -> TestBundleBlock {
+	sproof_builder.para_id = test_runtime::PARACHAIN_ID.into();
+	sproof_builder.included_para_head = Some(HeadData(included_head.encode()));
+
+	let validation_data = PersistedValidationData {
+		relay_parent_number: 1,
+		parent_head: parent_head.encode().into(),
+		..Default::default()
+	};
+
+	let BlockBuilderAndSupportData { mut block_builder, persisted_validation_data, slot } = client
+		.init_block_builder_with_timestamp(
+			parent_head.hash(),
+			Some(validation_data),
+			sproof_builder,
+			timestamp,
+		);
+
+	extra_extrinsics.into_iter().for_each(|e| block_builder.push(e).unwrap());
+
+	let built_block = block_builder.build().expect("Builds the block");
+	let proof = built_block.proof.expect("We enabled proof recording before.");
+
+	let (header, extrinsics) = built_block.block.deconstruct();
+	let block = Block::new(seal_header(header, slot, client), extrinsics);
+
+	futures::executor::block_on(import_sealed_block(client, block.clone()))
+		.expect("Imports the block");
+
+	TestBundleBlock { block, proof, validation_data: persisted_validation_data }
+}
+
+/// Build a bundle of two blocks on top of `parent_head`, the second one with transfers.
+fn build_bundle(client: &Client, parent_head: &Header) -> (Vec<TestBundleBlock>, Hash) {
+	// Both blocks are built in the same slot.
+	let timestamp = test_runtime::SLOT_DURATION * 1_000_000;
+
+	let first = build_and_import_bundle_block(
+		client,
+		Vec::new(),
+		parent_head,
+		parent_head,
+		timestamp,
+		Default::default(),
+	);
+	let second = build_and_import_bundle_block(
+		client,
+		vec![transfer(client, Alice, Bob, 69), transfer(client, Bob, Charlie, 100)],
+		first.block.header(),
+		parent_head,
+		timestamp + test_runtime::SLOT_DURATION / 2,
+		Default::default(),
+	);
+
+	assert_eq!(
+		first.validation_data.relay_parent_storage_root,
+		second.validation_data.relay_parent_storage_root,
+	);
+	let relay_parent_storage_root = second.validation_data.relay_parent_storage_root;
+
+	(vec![first, second], relay_parent_storage_root)
+}
+
+/// Create the [`ParachainBlockBundle`] out of `blocks`, like the collator does.
+fn create_bundle(
+	parent_head: &Header,
+	blocks: Vec<TestBundleBlock>,
+) -> ParachainBlockBundle<Block> {
+	let (blocks, proofs): (Vec<_>, Vec<_>) =
+		blocks.into_iter().map(|block| (block.block, block.proof)).unzip();
+
+	let compact_proof = StorageProof::merge(proofs)
+		.into_compact_proof::<HashingFor<Block>>(*parent_head.state_root())
+		.expect("Creates the compact proof");
+
+	ParachainBlockBundle::new(blocks, compact_proof)
+}
+
 #[test]
 fn validate_block_works() {
 	sp_tracing::try_init_simple();
@@ -339,3 +439,159 @@ fn validate_block_works_with_child_tries() {
 			.expect("Calls `validate_block`");
 	assert_eq!(header, res_header);
 }
+
+#[test]
+fn validate_block_works_with_bundle() {
+	sp_tracing::try_init_simple();
+
+	let (client, parent_head) = create_test_client();
+	let (blocks, relay_parent_storage_root) = build_bundle(&client, &parent_head);
+	let header = blocks.last().unwrap().block.header().clone();
+
+	let separate_proofs_size: usize = blocks
+		.iter()
+		.map(|block| {
+			block
+				.proof
+				.clone()
+				.into_compact_proof::<HashingFor<Block>>(
+					*client
+						.header(*block.block.header().parent_hash())
+						.unwrap()
+						.unwrap()
+						.state_root(),
+				)
+				.unwrap()
+				.encoded_size()
+		})
+		.sum();
+
+	let bundle = create_bundle(&parent_head, blocks);
+	assert!(bundle.storage_proof().encoded_size() < separate_proofs_size);
+
+	let res_header = call_validate_block(parent_head, bundle, relay_parent_storage_root)
+		.expect("Calls `validate_block`");
+	assert_eq!(header, res_header);
+}
+
+#[test]
+fn validate_block_bundle_invalid_parent_hash() {
+	sp_tracing::try_init_simple();
+
+	if env::var("RUN_TEST").is_ok() {
+		let (client, parent_head) = create_test_client();
+		let (blocks, relay_parent_storage_root) = build_bundle(&client, &parent_head);
+
+		let (mut blocks, proof) = create_bundle(&parent_head, blocks).deconstruct();
+		blocks.reverse();
+
+		call_validate_block(
+			parent_head,
+			ParachainBlockBundle::new(blocks, proof),
+			relay_parent_storage_root,
+		)
+		.unwrap_err();
+	} else {
+		let output = Command::new(env::current_exe().unwrap())
+			.args(["validate_block_bundle_invalid_parent_hash", "--", "--nocapture"])
+			.env("RUN_TEST", "1")
+			.output()
+			.expect("Runs the test");
+		assert!(output.status.success());
+
+		assert!(dbg!(String::from_utf8(output.stderr).unwrap()).contains("Invalid parent hash"));
+	}
+}
+
+#[test]
+fn validate_block_bundle_rejects_hrmp_messages_to_the_same_recipient() {
+	sp_tracing::try_init_simple();
+
+	if env::var("RUN_TEST").is_ok() {
+		let (client, parent_head) = create_test_client();
+		let timestamp = test_runtime::SLOT_DURATION * 1_000_000;
+
+		let recipient = ParaId::from(300);
+		let mut sproof_builder = RelayStateSproofBuilder {
+			para_id: test_runtime::PARACHAIN_ID.into(),
+			..Default::default()
+		};
+		let channel = sproof_builder.upsert_outbound_channel(recipient);
+		channel.max_capacity = 10;
+		channel.max_total_size = 1024;
+		channel.max_message_size = 128;
+
+		// Each block on its own sends one message to `recipient`, which is fine.
+		let first = build_and_import_bundle_block(
+			&client,
+			vec![generate_extrinsic(
+				&client,
+				Alice,
+				TestPalletCall::send_hrmp_message { recipient, data: vec![1] },
+			)],
+			&parent_head,
+			&parent_head,
+			timestamp,
+			sproof_builder.clone(),
+		);
+		let second = build_and_import_bundle_block(
+			&client,
+			vec![generate_extrinsic(
+				&client,
+				Bob,
+				TestPalletCall::send_hrmp_message { recipient, data: vec![2] },
+			)],
+			first.block.header(),
+			&parent_head,
+			timestamp + test_runtime::SLOT_DURATION / 2,
+			sproof_builder,
+		);
+		let relay_parent_storage_root = second.validation_data.relay_parent_storage_root;
+
+		call_validate_block(
+			parent_head.clone(),
+			create_bundle(&parent_head, vec![first, second]),
+			relay_parent_storage_root,
+		)
+		.unwrap_err();
+	} else {
+		let output = Command::new(env::current_exe().unwrap())
+			.args([
+				"validate_block_bundle_rejects_hrmp_messages_to_the_same_recipient",
+				"--",
+				"--nocapture",
+			])
+			.env("RUN_TEST", "1")
+			.output()
+			.expect("Runs the test");
+		assert!(output.status.success());
+
+		assert!(dbg!(String::from_utf8(output.stderr).unwrap())
+			.contains("Messages of the bundle can not be sent by one candidate"));
+	}
+}
+
+/// Test that a [`ParachainBlockBundle`] survives an encoding round trip and that it is told apart
+/// from [`ParachainBlockData`].
+#[test]
+fn parachain_block_bundle_encode_and_decode() {
+	sp_tracing::try_init_simple();
+
+	let (client, parent_head) = create_test_client();
+
+	let TestBlockData { block, .. } =
+		build_block_with_witness(&client, Vec::new(), parent_head.clone(), Default::default());
+	let encoded = block.encode();
+	assert!(!ParachainBlockBundle::<Block>::is_bundle(&encoded));
+	assert!(ParachainBlockBundle::<Block>::decode(&mut &encoded[..]).is_err());
+
+	let (blocks, _) = build_bundle(&client, &parent_head);
+	let bundle = create_bundle(&parent_head, blocks);
+
+	let encoded = bundle.encode();
+	assert!(ParachainBlockBundle::<Block>::is_bundle(&encoded));
+
+	let decoded = ParachainBlockBundle::<Block>::decode_all(&mut &encoded[..]).unwrap();
+	assert_eq!(decoded.blocks(), bundle.blocks());
+	assert_eq!(decoded.storage_proof(), bundle.storage_proof());
+}
@@ -20,6 +20,7 @@ use crate::{
 	chain_spec::DiskChainSpecLoader,
 	common::{
 		chain_spec::{Extensions, LoadSpec},
+		fork::ForkSpecCmd,
 		NodeExtraArgs,
 	},
 };
@@ -99,6 +100,11 @@ pub enum Subcommand {
 	/// Export the genesis wasm of the parachain.
 	ExportGenesisWasm(cumulus_client_cli::ExportGenesisWasmCommand),
 
+	/// Create a chain spec that forks the state snapshot of a live chain into a local dev chain.
+	///
+	/// The resulting chain spec is meant to be run with `--dev-block-time`.
+	ForkSpec(ForkSpecCmd),
+
 	/// Sub-commands concerned with benchmarking.
 	/// The pallet benchmarking moved to the `pallet` sub-command.
 	#[command(subcommand)]
@@ -143,6 +149,14 @@ pub struct Cli<Config: CliConfig> {
 	#[arg(long)]
 	pub experimental_use_slot_based: bool,
 
+	/// EXPERIMENTAL: The number of blocks the slot-based collator builds at one slot and submits
+	/// as one bundle, sharing the storage proof.
+	///
+	/// Requires `--experimental-use-slot-based`. The runtime needs to allow multiple blocks per
+	/// slot. Use with care, this flag is unstable and subject to change.
+	#[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+	pub experimental_blocks_per_bundle: u32,
+
 	/// Disable automatic hardware benchmarks.
 	///
 	/// By default these benchmarks are automatically ran at startup and measure
@@ -173,6 +187,7 @@ impl<Config: CliConfig> Cli<Config> {
 		NodeExtraArgs {
 			use_slot_based_consensus: self.experimental_use_slot_based,
 			export_pov: self.export_pov_to_path.clone(),
+			blocks_per_bundle: self.experimental_blocks_per_bundle,
 		}
 	}
 }
@@ -0,0 +1,263 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fork the state of a live parachain into a local development chain.
+//!
+//! The [`ForkSpecCmd`] turns a `remote-externalities` state snapshot (as created by
+//! `try-runtime create-snapshot`) into a raw chain spec whose genesis state is the snapshotted
+//! state. The collator keys of the forked chain are replaced by dev keys and, optionally, a new
+//! sudo key is injected. The resulting chain spec can then be run with manual seal:
+//!
+//! ```bash
+//! polkadot-omni-node fork-spec --chain <live_chain_spec.json> --snapshot <state.snap> \
+//!     --sudo 5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY > fork.json
+//! polkadot-omni-node --chain fork.json --dev-block-time 6000
+//! ```
+//!
+//! The dev overrides assume the pallet names used by the system parachains (`Aura`, `AuraExt`,
+//! `ParachainSystem`, `CollatorSelection`, `Session` and `Sudo`), and that the session keys of the
+//! runtime consist of a single Aura key. Items of pallets that are not part of the forked runtime
+//! are simply never read.
+
+use crate::common::{
+	runtime::{AuraConsensusId, BlockNumber, Consensus, Runtime},
+	types::Block,
+};
+use codec::Encode;
+use remote_externalities::{
+	overrides::{clear_prefix, twox_64_concat_key, value_key},
+	Builder, Mode, OfflineConfig, SnapshotConfig,
+};
+use sc_chain_spec::ChainSpec;
+use sc_cli::{CliConfiguration, SharedParams};
+use sc_service::BasePath;
+use sp_core::{
+	crypto::{key_types::AURA, AccountId32},
+	ed25519, sr25519,
+	storage::StorageMap,
+	Pair,
+};
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT};
+use std::{
+	fs,
+	io::{self, Write},
+	path::PathBuf,
+};
+
+/// Create a raw chain spec that forks the state of a live parachain into a local dev chain.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ForkSpecCmd {
+	/// Path to the state snapshot of the live chain.
+	///
+	/// The snapshot can be created with `try-runtime create-snapshot`.
+	#[arg(long)]
+	pub snapshot: PathBuf,
+
+	/// Secret URI of the dev key that replaces the collator keys of the forked chain.
+	#[arg(long, default_value = "//Alice")]
+	pub dev_seed: String,
+
+	/// Account to set as the sudo key of the forked chain.
+	///
+	/// Only has an effect if the forked runtime contains the `Sudo` pallet.
+	#[arg(long)]
+	pub sudo: Option<AccountId32>,
+
+	/// Output file name or stdout if unspecified.
+	#[arg(long, short)]
+	pub output: Option<PathBuf>,
+
+	#[allow(missing_docs)]
+	#[command(flatten)]
+	pub shared_params: SharedParams,
+}
+
+impl ForkSpecCmd {
+	/// Run the fork-spec command.
+	///
+	/// `chain_spec` is the chain spec of the live chain, it provides the name, id and extensions of
+	/// the resulting chain spec, while its genesis state is replaced by the snapshotted state.
+	pub async fn run(
+		&self,
+		chain_spec: Box<dyn ChainSpec>,
+		runtime: Runtime,
+	) -> sc_cli::Result<()> {
+		match runtime {
+			Runtime::Omni(BlockNumber::U32, Consensus::Aura(aura_id)) =>
+				self.fork::<Block<u32>>(chain_spec, aura_id).await,
+			Runtime::Omni(BlockNumber::U64, Consensus::Aura(aura_id)) =>
+				self.fork::<Block<u64>>(chain_spec, aura_id).await,
+		}
+	}
+
+	async fn fork<B: BlockT>(
+		&self,
+		mut chain_spec: Box<dyn ChainSpec>,
+		aura_id: AuraConsensusId,
+	) -> sc_cli::Result<()>
+	where
+		B::Hash: serde::de::DeserializeOwned,
+		B::Header: serde::de::DeserializeOwned,
+	{
+		let mut ext = Builder::<B>::new()
+			.mode(Mode::Offline(OfflineConfig {
+				state_snapshot: SnapshotConfig::new(&self.snapshot),
+			}))
+			.build()
+			.await?;
+		log::info!("Forking the state of block #{}", ext.header.number());
+
+		let mut storage = ext.to_storage()?;
+		let overrides = DevOverrides::new(&self.dev_seed, aura_id, self.sudo.clone())?;
+		overrides.apply(&mut storage.top);
+		chain_spec.set_storage(storage);
+
+		let json = chain_spec.as_json(true)?;
+		if let Some(output) = &self.output {
+			fs::write(output, json)?;
+		} else {
+			io::stdout().write_all(json.as_bytes())?;
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ForkSpecCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn base_path(&self) -> sc_cli::Result<Option<BasePath>> {
+		// As we are just creating a chain spec a tmp database is enough.
+		Ok(Some(BasePath::new_temp_dir()?))
+	}
+}
+
+/// The storage overrides applied to the forked state to make it runnable by a local dev node.
+struct DevOverrides {
+	/// The public Aura key of the dev collator.
+	aura_key: [u8; 32],
+	/// The account of the dev collator.
+	collator: AccountId32,
+	/// The account to set as the sudo key, if any.
+	sudo: Option<AccountId32>,
+}
+
+impl DevOverrides {
+	fn new(
+		dev_seed: &str,
+		aura_id: AuraConsensusId,
+		sudo: Option<AccountId32>,
+	) -> sc_cli::Result<Self> {
+		let collator = sr25519::Pair::from_string(dev_seed, None)
+			.map_err(|e| format!("Invalid dev seed: {:?}", e))?
+			.public();
+		let aura_key = match aura_id {
+			AuraConsensusId::Sr25519 => collator.0,
+			AuraConsensusId::Ed25519 =>
+				ed25519::Pair::from_string(dev_seed, None)
+					.map_err(|e| format!("Invalid dev seed: {:?}", e))?
+					.public()
+					.0,
+		};
+
+		Ok(Self { aura_key, collator: collator.into(), sudo })
+	}
+
+	fn apply(&self, top: &mut StorageMap) {
+		// The dev collator becomes the only authority.
+		let authorities = vec![self.aura_key].encode();
+		top.insert(value_key(b"Aura", b"Authorities"), authorities.clone());
+		top.insert(value_key(b"AuraExt", b"Authorities"), authorities);
+		top.insert(
+			value_key(b"CollatorSelection", b"Invulnerables"),
+			vec![&self.collator].encode(),
+		);
+		top.remove(&value_key(b"CollatorSelection", b"CandidateList"));
+
+		// Replace the session keys of the live collators, otherwise they would be brought back by
+		// the next session rotation.
+		clear_prefix(top, &value_key(b"Session", b"NextKeys"));
+		clear_prefix(top, &value_key(b"Session", b"KeyOwner"));
+		top.remove(&value_key(b"Session", b"DisabledValidators"));
+		top.insert(value_key(b"Session", b"Validators"), vec![&self.collator].encode());
+		top.insert(
+			value_key(b"Session", b"QueuedKeys"),
+			vec![(&self.collator, self.aura_key)].encode(),
+		);
+		top.insert(
+			twox_64_concat_key(b"Session", b"NextKeys", &self.collator.encode()),
+			self.aura_key.encode(),
+		);
+		top.insert(
+			twox_64_concat_key(b"Session", b"KeyOwner", &(AURA, self.aura_key.to_vec()).encode()),
+			self.collator.encode(),
+		);
+
+		// The dev node starts producing blocks at genesis with a mocked relay chain, so the slot
+		// and relay chain progress recorded by the live chain have to be reset.
+		top.remove(&value_key(b"Aura", b"CurrentSlot"));
+		top.remove(&value_key(b"AuraExt", b"SlotInfo"));
+		top.remove(&value_key(b"ParachainSystem", b"LastRelayChainBlockNumber"));
+		top.remove(&value_key(b"ParachainSystem", b"UnincludedSegment"));
+		top.remove(&value_key(b"ParachainSystem", b"AggregatedUnincludedSegment"));
+
+		if let Some(sudo) = &self.sudo {
+			top.insert(value_key(b"Sudo", b"Key"), sudo.encode());
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dev_overrides_replace_collators_and_reset_slots() {
+		let mut top = StorageMap::new();
+		let live = AccountId32::new([7; 32]);
+		top.insert(value_key(b"Aura", b"CurrentSlot"), 42u64.encode());
+		top.insert(value_key(b"CollatorSelection", b"CandidateList"), vec![1, 2, 3]);
+		top.insert(twox_64_concat_key(b"Session", b"NextKeys", &live.encode()), vec![1, 2, 3]);
+		top.insert(value_key(b"ParachainSystem", b"LastRelayChainBlockNumber"), 42u32.encode());
+
+		let sudo = AccountId32::new([1; 32]);
+		DevOverrides::new("//Alice", AuraConsensusId::Sr25519, Some(sudo.clone()))
+			.unwrap()
+			.apply(&mut top);
+
+		let alice = sr25519::Pair::from_string("//Alice", None).unwrap().public();
+		assert_eq!(top.get(&value_key(b"Aura", b"Authorities")), Some(&vec![alice.0].encode()));
+		assert_eq!(
+			top.get(&value_key(b"CollatorSelection", b"Invulnerables")),
+			Some(&vec![AccountId32::from(alice)].encode())
+		);
+		assert!(!top.contains_key(&value_key(b"CollatorSelection", b"CandidateList")));
+		assert!(!top.contains_key(&twox_64_concat_key(b"Session", b"NextKeys", &live.encode())));
+		assert_eq!(
+			top.get(&twox_64_concat_key(
+				b"Session",
+				b"NextKeys",
+				&AccountId32::from(alice).encode()
+			)),
+			Some(&alice.0.encode())
+		);
+		assert_eq!(top.get(&value_key(b"Sudo", b"Key")), Some(&sudo.encode()));
+		assert!(!top.contains_key(&value_key(b"Aura", b"CurrentSlot")));
+		assert!(!top.contains_key(&value_key(b"ParachainSystem", b"LastRelayChainBlockNumber")));
+	}
+}
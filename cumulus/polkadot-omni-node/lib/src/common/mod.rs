@@ -21,6 +21,7 @@
 pub(crate) mod aura;
 pub mod chain_spec;
 pub mod command;
+pub mod fork;
 pub mod rpc;
 pub mod runtime;
 pub mod spec;
@@ -105,4 +106,7 @@ pub struct NodeExtraArgs {
 
 	/// If set, each `PoV` build by the node will be exported to this folder.
 	pub export_pov: Option<PathBuf>,
+
+	/// The number of blocks the slot-based collator bundles into one `PoV`.
+	pub blocks_per_bundle: u32,
 }
@@ -25,8 +25,13 @@ use crate::common::{
 };
 use cumulus_client_cli::CollatorOptions;
 use cumulus_client_service::{
-	build_network, build_relay_chain_interface, prepare_node_config, start_relay_chain_tasks,
-	BuildNetworkParams, CollatorSybilResistance, DARecoveryProfile, StartRelayChainTasksParams,
+	build_network, build_relay_chain_interface,
+	on_demand::{
+		start_on_demand_order_placement, SignedOnDemandOrderPlacer,
+		StartOnDemandOrderPlacementParams,
+	},
+	prepare_node_config, start_relay_chain_tasks, BuildNetworkParams, CollatorSybilResistance,
+	DARecoveryProfile, StartRelayChainTasksParams,
 };
 use cumulus_primitives_core::{BlockT, ParaId};
 use cumulus_relay_chain_interface::{OverseerHandle, RelayChainInterface};
@@ -328,6 +333,22 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 				})?;
 
 				if validator {
+					if let Some(options) = &collator_options.on_demand_orders {
+						start_on_demand_order_placement(StartOnDemandOrderPlacementParams {
+							para_id,
+							config: options.into(),
+							transaction_pool: transaction_pool.clone(),
+							relay_chain_interface: relay_chain_interface.clone(),
+							order_placer: SignedOnDemandOrderPlacer::from_file(
+								relay_chain_interface.clone(),
+								&options.account_file,
+							)
+							.map_err(sc_service::Error::Application)?,
+							aux_store: client.clone(),
+							task_manager: &task_manager,
+						});
+					}
+
 					Self::StartConsensus::start_consensus(
 						client.clone(),
 						block_import,
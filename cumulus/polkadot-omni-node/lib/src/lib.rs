@@ -25,5 +25,5 @@ mod nodes;
 
 pub use cli::CliConfig;
 pub use command::{run, RunConfig};
-pub use common::{chain_spec, runtime};
+pub use common::{chain_spec, fork, runtime};
 pub use nodes::NODE_VERSION;
@@ -251,6 +251,133 @@ impl<B: BlockT> ParachainBlockData<B> {
 	}
 }
 
+/// Prefix of an encoded [`ParachainBlockBundle`].
+///
+/// The encoding of a single [`ParachainBlockData`] starts with the encoded header of the block,
+/// which begins with the hash of its parent. This prefix allows the validation function to
+/// tell both formats apart.
+pub const PARACHAIN_BLOCK_BUNDLE_PREFIX: &[u8] = b"CMLSBNDL";
+
+/// Consecutive parachain blocks that are sent together as one PoV.
+///
+/// Instead of carrying one storage proof per block, the bundle carries a single proof with all the
+/// trie nodes accessed by any of the blocks. Nodes that are accessed by multiple blocks are only
+/// included once.
+///
+/// The proof is compacted against the state of the parent of the first block. Trie nodes that are
+/// created by a block of the bundle are not part of it, they are recreated while executing the
+/// block.
+#[derive(Clone)]
+pub struct ParachainBlockBundle<B: BlockT> {
+	/// The blocks, in the order they are built on top of each other.
+	blocks: alloc::vec::Vec<B>,
+	/// The data that is required to emulate the storage accesses executed by all blocks.
+	storage_proof: sp_trie::CompactProof,
+}
+
+impl<B: BlockT> ParachainBlockBundle<B> {
+	/// Creates a new instance of `Self`.
+	pub fn new(blocks: alloc::vec::Vec<B>, storage_proof: sp_trie::CompactProof) -> Self {
+		Self { blocks, storage_proof }
+	}
+
+	/// Returns `true` if `encoded` starts with [`PARACHAIN_BLOCK_BUNDLE_PREFIX`], i.e. it is an
+	/// encoded bundle and not an encoded [`ParachainBlockData`].
+	pub fn is_bundle(encoded: &[u8]) -> bool {
+		encoded.starts_with(PARACHAIN_BLOCK_BUNDLE_PREFIX)
+	}
+
+	/// Returns the blocks.
+	pub fn blocks(&self) -> &[B] {
+		&self.blocks
+	}
+
+	/// Returns the [`CompactProof`](sp_trie::CompactProof) shared by all blocks.
+	pub fn storage_proof(&self) -> &sp_trie::CompactProof {
+		&self.storage_proof
+	}
+
+	/// Deconstruct into the inner parts.
+	pub fn deconstruct(self) -> (alloc::vec::Vec<B>, sp_trie::CompactProof) {
+		(self.blocks, self.storage_proof)
+	}
+}
+
+impl<B: BlockT> Encode for ParachainBlockBundle<B> {
+	fn size_hint(&self) -> usize {
+		PARACHAIN_BLOCK_BUNDLE_PREFIX.len() +
+			self.blocks.size_hint() +
+			self.storage_proof.size_hint()
+	}
+
+	fn encode_to<T: codec::Output + ?Sized>(&self, dest: &mut T) {
+		dest.write(PARACHAIN_BLOCK_BUNDLE_PREFIX);
+		self.blocks.encode_to(dest);
+		self.storage_proof.encode_to(dest);
+	}
+}
+
+impl<B: BlockT> Decode for ParachainBlockBundle<B> {
+	fn decode<I: codec::Input>(input: &mut I) -> Result<Self, codec::Error> {
+		let mut prefix = [0u8; PARACHAIN_BLOCK_BUNDLE_PREFIX.len()];
+		input.read(&mut prefix)?;
+
+		if prefix != PARACHAIN_BLOCK_BUNDLE_PREFIX {
+			return Err("Invalid parachain block bundle prefix".into())
+		}
+
+		Ok(Self { blocks: Decode::decode(input)?, storage_proof: Decode::decode(input)? })
+	}
+}
+
+/// Why the merged messages of the blocks of a [`ParachainBlockBundle`] can not be sent by one
+/// candidate.
+#[derive(Clone, Copy, PartialEq, Eq, RuntimeDebug)]
+pub enum BundleMessagesError {
+	/// The blocks send more upward messages than a candidate is allowed to.
+	TooManyUpwardMessages { count: u32, limit: u32 },
+	/// The blocks send more horizontal messages than a candidate is allowed to.
+	TooManyHorizontalMessages { count: u32, limit: u32 },
+	/// A horizontal message to `recipient` follows one to the same or a greater recipient, e.g.
+	/// because two blocks of the bundle send a message to it.
+	HorizontalMessagesNotAscending { recipient: ParaId },
+}
+
+/// Check that the merged messages of the blocks of a [`ParachainBlockBundle`] can be sent by one
+/// candidate.
+///
+/// Every block of a bundle only respects the relay chain rules on its own. The capacities of the
+/// channels and of the upward queue are shared through the unincluded segment, but the limits per
+/// candidate are not: the number of messages is limited and every recipient can only receive one
+/// horizontal message, in strictly ascending order of the recipients.
+pub fn check_bundle_messages(
+	upward_message_count: usize,
+	horizontal_messages: &[OutboundHrmpMessage],
+	host_config: &AbridgedHostConfiguration,
+) -> Result<(), BundleMessagesError> {
+	let count = upward_message_count as u32;
+	let limit = host_config.max_upward_message_num_per_candidate;
+	if count > limit {
+		return Err(BundleMessagesError::TooManyUpwardMessages { count, limit })
+	}
+
+	let count = horizontal_messages.len() as u32;
+	let limit = host_config.hrmp_max_message_num_per_candidate;
+	if count > limit {
+		return Err(BundleMessagesError::TooManyHorizontalMessages { count, limit })
+	}
+
+	match horizontal_messages
+		.windows(2)
+		.find(|pair| pair[0].recipient >= pair[1].recipient)
+	{
+		Some(pair) => Err(BundleMessagesError::HorizontalMessagesNotAscending {
+			recipient: pair[1].recipient,
+		}),
+		None => Ok(()),
+	}
+}
+
 /// A consensus engine ID indicating that this is a Cumulus Parachain.
 pub const CUMULUS_CONSENSUS_ID: ConsensusEngineId = *b"CMLS";
 
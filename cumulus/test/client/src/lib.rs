@@ -39,7 +39,11 @@ use sp_consensus_aura::{AuraApi, Slot};
 use sp_core::Pair;
 use sp_io::TestExternalities;
 use sp_keystore::testing::MemoryKeystore;
-use sp_runtime::{generic::Era, traits::Header, BuildStorage, MultiAddress, SaturatedConversion};
+use sp_runtime::{
+	generic::Era,
+	traits::{Block as BlockT, Header},
+	BuildStorage, MultiAddress, SaturatedConversion,
+};
 use std::sync::Arc;
 pub use substrate_test_client::*;
 
@@ -232,19 +236,17 @@ fn get_keystore() -> sp_keystore::KeystorePtr {
 	Arc::new(keystore)
 }
 
-/// Given parachain block data and a slot, seal the block with an aura seal. Assumes that the
-/// authorities of the test runtime are present in the keyring.
-pub fn seal_block(
-	block: ParachainBlockData,
+/// Given a header and a slot, seal the header with an aura seal. Assumes that the authorities of
+/// the test runtime are present in the keyring.
+pub fn seal_header(
+	mut header: runtime::Header,
 	parachain_slot: Slot,
 	client: &Client,
-) -> ParachainBlockData {
-	let parent_hash = block.header().parent_hash;
-	let authorities = client.runtime_api().authorities(parent_hash).unwrap();
+) -> runtime::Header {
+	let authorities = client.runtime_api().authorities(header.parent_hash).unwrap();
 	let expected_author = slot_author::<<AuraId as AppCrypto>::Pair>(parachain_slot, &authorities)
 		.expect("Should be able to find author");
 
-	let (mut header, extrinsics, proof) = block.deconstruct();
 	let keystore = get_keystore();
 	let seal_digest = seal::<_, sp_consensus_aura::sr25519::AuthorityPair>(
 		&header.hash(),
@@ -253,5 +255,30 @@ pub fn seal_block(
 	)
 	.expect("Should be able to create seal");
 	header.digest_mut().push(seal_digest);
-	ParachainBlockData::new(header, extrinsics, proof)
+	header
+}
+
+/// Given parachain block data and a slot, seal the block with an aura seal. Assumes that the
+/// authorities of the test runtime are present in the keyring.
+pub fn seal_block(
+	block: ParachainBlockData,
+	parachain_slot: Slot,
+	client: &Client,
+) -> ParachainBlockData {
+	let (header, extrinsics, proof) = block.deconstruct();
+	ParachainBlockData::new(seal_header(header, parachain_slot, client), extrinsics, proof)
+}
+
+/// Import a block that was sealed with [`seal_header`]. The seal is passed as post digest, like the
+/// Aura import queue does, so the imported block keeps the hash of the sealed header.
+pub async fn import_sealed_block(client: &Client, block: Block) -> Result<(), sp_consensus::Error> {
+	let (mut header, extrinsics) = block.deconstruct();
+	let seal = header.digest_mut().pop().expect("The block is sealed");
+
+	let mut import = sc_consensus::BlockImportParams::new(BlockOrigin::Own, header);
+	import.body = Some(extrinsics);
+	import.post_digests.push(seal);
+	import.fork_choice = Some(sc_consensus::ForkChoiceStrategy::LongestChain);
+
+	sc_consensus::BlockImport::import_block(client, import).await.map(|_| ())
 }
@@ -24,6 +24,8 @@ pub const TEST_RUNTIME_UPGRADE_KEY: &[u8] = b"+test_runtime_upgrade_key+";
 #[frame_support::pallet(dev_mode)]
 pub mod pallet {
 	use crate::test_pallet::TEST_RUNTIME_UPGRADE_KEY;
+	use alloc::vec::Vec;
+	use cumulus_primitives_core::{ParaId, XcmpMessageSource};
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
 
@@ -33,6 +35,11 @@ pub mod pallet {
 	#[pallet::config]
 	pub trait Config: frame_system::Config + cumulus_pallet_parachain_system::Config {}
 
+	/// Horizontal messages waiting to be sent, by recipient.
+	#[pallet::storage]
+	pub type OutboundHrmpMessages<T: Config> =
+		StorageMap<_, Twox64Concat, ParaId, Vec<Vec<u8>>, ValueQuery>;
+
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {}
 
@@ -73,6 +80,42 @@ pub mod pallet {
 
 			Ok(())
 		}
+
+		/// A dispatchable that queues a horizontal message to `recipient`, it is sent in the
+		/// `on_finalize` of `parachain-system`.
+		#[pallet::weight(0)]
+		pub fn send_hrmp_message(
+			_: OriginFor<T>,
+			recipient: ParaId,
+			data: Vec<u8>,
+		) -> DispatchResult {
+			OutboundHrmpMessages::<T>::append(recipient, data);
+			Ok(())
+		}
+	}
+
+	impl<T: Config> XcmpMessageSource for Pallet<T> {
+		/// Take the first queued message of up to `maximum_channels` recipients, in ascending order
+		/// of the recipients.
+		fn take_outbound_messages(maximum_channels: usize) -> Vec<(ParaId, Vec<u8>)> {
+			let mut recipients = OutboundHrmpMessages::<T>::iter_keys().collect::<Vec<_>>();
+			recipients.sort();
+			recipients.truncate(maximum_channels);
+
+			recipients
+				.into_iter()
+				.filter_map(|recipient| {
+					OutboundHrmpMessages::<T>::mutate_exists(recipient, |messages| {
+						let queue = messages.as_mut()?;
+						let message = queue.remove(0);
+						if queue.is_empty() {
+							*messages = None;
+						}
+						Some((recipient, message))
+					})
+				})
+				.collect()
+		}
 	}
 
 	#[derive(frame_support::DefaultNoBound)]
@@ -497,6 +497,7 @@ where
 					authoring_duration: Duration::from_millis(2000),
 					reinitialize: false,
 					slot_drift: Duration::from_secs(1),
+					blocks_per_bundle: 1,
 				};
 
 				let (collation_future, block_builder_future) =
@@ -755,7 +756,8 @@ impl TestNodeBuilder {
 			false,
 		);
 
-		let collator_options = CollatorOptions { relay_chain_mode: self.relay_chain_mode };
+		let collator_options =
+			CollatorOptions { relay_chain_mode: self.relay_chain_mode, on_demand_orders: None };
 
 		relay_chain_config.network.node_name =
 			format!("{} (relay chain)", relay_chain_config.network.node_name);
@@ -899,7 +901,7 @@ pub fn node_config(
 		},
 		prometheus_config: None,
 		telemetry_endpoints: None,
-		offchain_worker: OffchainWorkerConfig { enabled: true, indexing_enabled: false },
+		offchain_worker: OffchainWorkerConfig::new(true, false),
 		force_authoring: false,
 		disable_grandpa: false,
 		dev_key_seed: Some(key_seed),
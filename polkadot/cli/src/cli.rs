@@ -56,6 +56,47 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Inspect the disputes stored by the dispute coordinator. The node must not be running.
+	InspectDisputes(InspectDisputesCmd),
+}
+
+/// The `inspect-disputes` command.
+#[derive(Debug, Parser)]
+pub struct InspectDisputesCmd {
+	/// Only show the disputes of the given session.
+	#[arg(long)]
+	pub session: Option<u32>,
+
+	/// Print the disputes as JSON, in the format of the `parachain_inspectDisputes` RPC.
+	#[arg(long)]
+	pub json: bool,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: sc_cli::SharedParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub database_params: sc_cli::DatabaseParams,
+
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub keystore_params: sc_cli::KeystoreParams,
+}
+
+impl sc_cli::CliConfiguration for InspectDisputesCmd {
+	fn shared_params(&self) -> &sc_cli::SharedParams {
+		&self.shared_params
+	}
+
+	fn database_params(&self) -> Option<&sc_cli::DatabaseParams> {
+		Some(&self.database_params)
+	}
+
+	fn keystore_params(&self) -> Option<&sc_cli::KeystoreParams> {
+		Some(&self.keystore_params)
+	}
 }
 
 #[allow(missing_docs)]
@@ -115,6 +156,34 @@ pub struct RunCmd {
 	#[arg(long)]
 	pub overseer_channel_capacity_override: Option<usize>,
 
+	/// Log the channel-wait graph of the overseer when a subsystem has not drained its queue for
+	/// the given number of seconds.
+	///
+	/// Also exposes the queue depth of every subsystem and the waits between subsystems as
+	/// Prometheus metrics.
+	#[arg(long, value_name = "SECONDS")]
+	pub overseer_watchdog_threshold: Option<u64>,
+
+	/// Log the state of every non-empty overseer channel each second.
+	#[arg(long, requires = "overseer_watchdog_threshold")]
+	pub overseer_channel_tracing: bool,
+
+	/// Monitor the GRANDPA gossip for equivocations, participation and finality stalls.
+	///
+	/// The state of the monitor is exposed through the `grandpa_monitorStatus` RPC and as
+	/// Prometheus metrics.
+	#[arg(long)]
+	pub grandpa_monitor: bool,
+
+	/// Number of blocks the finalized block may lag behind the best block before
+	/// `--grandpa-monitor` considers finality to be lagging.
+	#[arg(long, default_value_t = 32, requires = "grandpa_monitor")]
+	pub grandpa_monitor_finality_lag: u32,
+
+	/// Report the equivocations detected by `--grandpa-monitor` to the runtime.
+	#[arg(long, requires = "grandpa_monitor")]
+	pub grandpa_monitor_report_equivocations: bool,
+
 	/// Path to the directory where auxiliary worker binaries reside.
 	///
 	/// If not specified, the main binary's directory is searched first, then
@@ -152,6 +221,14 @@ pub struct RunCmd {
 	/// should not be used unless explicitly advised to. It will be removed in the future.
 	#[arg(long, default_value = "true", action=ArgAction::Set)]
 	pub enable_approval_voting_parallel: bool,
+
+	/// Capture the approval-distribution traffic of the node to the given file.
+	///
+	/// The capture can be replayed with the `ApprovalReplay` objective of `subsystem-bench`.
+	/// Requires parallel approval voting. Messages are dropped while the capture can not keep up
+	/// with the traffic and the capture stops once the file reaches 4 GiB.
+	#[arg(long, value_name = "PATH")]
+	pub approval_traffic_capture: Option<PathBuf>,
 }
 
 #[allow(missing_docs)]
@@ -235,6 +235,20 @@ where
 				prepare_workers_hard_max_num: cli.run.prepare_workers_hard_max_num,
 				prepare_workers_soft_max_num: cli.run.prepare_workers_soft_max_num,
 				enable_approval_voting_parallel: cli.run.enable_approval_voting_parallel,
+				approval_traffic_capture: cli.run.approval_traffic_capture,
+				overseer_watchdog: cli.run.overseer_watchdog_threshold.map(|threshold| {
+					polkadot_service::OverseerWatchdogConfig {
+						stall_threshold: std::time::Duration::from_secs(threshold),
+						tracing: cli.run.overseer_channel_tracing,
+					}
+				}),
+				grandpa_monitor: cli.run.grandpa_monitor.then(|| {
+					polkadot_service::GrandpaMonitorConfig {
+						finality_lag_threshold: cli.run.grandpa_monitor_finality_lag,
+						report_equivocations: cli.run.grandpa_monitor_report_equivocations,
+						..Default::default()
+					}
+				}),
 			},
 		)
 		.map(|full| full.task_manager)?;
@@ -450,6 +464,7 @@ pub fn run() -> Result<()> {
 					cmd.run(&config, SUBSTRATE_REFERENCE_HARDWARE.clone())
 						.map_err(Error::SubstrateCli)
 				}),
+				BenchmarkCmd::Compare(cmd) => cmd.run().map_err(Error::SubstrateCli),
 				// NOTE: this allows the Polkadot client to leniently implement
 				// new benchmark commands.
 				#[allow(unreachable_patterns)]
@@ -461,6 +476,24 @@ pub fn run() -> Result<()> {
 			let runner = cli.create_runner(cmd)?;
 			Ok(runner.sync_run(|config| cmd.run::<polkadot_service::Block>(&config))?)
 		},
+		Some(Subcommand::InspectDisputes(cmd)) => {
+			let runner = cli.create_runner(cmd)?;
+			runner.sync_run(|config| {
+				let mut inspection = polkadot_service::inspect_disputes(config)?;
+				if let Some(session) = cmd.session {
+					inspection.sessions.retain(|disputes| disputes.session == session);
+				}
+
+				if cmd.json {
+					let json = serde_json::to_string_pretty(&inspection)
+						.map_err(|e| Error::Other(e.to_string()))?;
+					println!("{json}");
+				} else {
+					print_disputes(&inspection);
+				}
+				Ok(())
+			})
+		},
 	}?;
 
 	#[cfg(feature = "pyroscope")]
@@ -470,3 +503,67 @@ pub fn run() -> Result<()> {
 	}
 	Ok(())
 }
+
+/// Print the disputes of `inspect-disputes` as a table per session.
+fn print_disputes(inspection: &polkadot_service::disputes::DisputesInspection) {
+	use polkadot_service::disputes::{ParticipationStatus, Status};
+
+	println!(
+		"earliest session: {}, spam slots per validator: {}",
+		inspection.earliest_session.map_or("none".into(), |session| session.to_string()),
+		inspection.max_spam_votes,
+	);
+	for session in &inspection.sessions {
+		println!();
+		match session.local_validator {
+			Some(index) => println!("session {} (local validator {index})", session.session),
+			None => println!("session {} (no local validator)", session.session),
+		}
+		println!(
+			"  {:<66}  {:>6}  {:<28}  {:>5}  {:>7}  {:<7}  {}",
+			"candidate", "para", "status", "valid", "invalid", "local", "participation",
+		);
+		for dispute in &session.disputes {
+			let status = match dispute.status {
+				Status::Active => "active".to_string(),
+				Status::Confirmed => "confirmed".to_string(),
+				Status::ConcludedFor(at) => format!("concluded valid at {at}"),
+				Status::ConcludedAgainst(at) => format!("concluded invalid at {at}"),
+			};
+			let participation = match dispute.participation {
+				ParticipationStatus::Voted => "voted",
+				ParticipationStatus::Inactive => "inactive",
+				ParticipationStatus::Unconfirmed => "deferred (unconfirmed)",
+				ParticipationStatus::Pending => "pending",
+				ParticipationStatus::Unknown => "unknown",
+			};
+			println!(
+				"  {:<66}  {:>6}  {:<28}  {:>5}  {:>7}  {:<7}  {}",
+				format!("{:?}", dispute.candidate_hash),
+				dispute.para_id.map_or("?".into(), |para_id| para_id.to_string()),
+				status,
+				dispute.valid_votes,
+				dispute.invalid_votes,
+				match dispute.local_vote {
+					Some(true) => "valid",
+					Some(false) => "invalid",
+					None => "-",
+				},
+				participation,
+			);
+		}
+		if !session.spam_slots.is_empty() {
+			let slots: Vec<_> = session
+				.spam_slots
+				.iter()
+				.map(|slot| {
+					format!(
+						"#{}: {}/{}",
+						slot.validator, slot.unconfirmed_disputes, inspection.max_spam_votes
+					)
+				})
+				.collect();
+			println!("  spam slots: {}", slots.join(", "));
+		}
+	}
+}
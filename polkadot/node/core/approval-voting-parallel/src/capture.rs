@@ -0,0 +1,558 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Capture of the approval-distribution traffic received by a node.
+//!
+//! The capture contains everything approval-distribution needs to process the traffic again: the
+//! blocks imported by approval-voting, the session infos of their sessions, the gossip topology,
+//! the connected peers and the messages they sent. `subsystem-bench` replays captures against a
+//! standalone approval-distribution subsystem.
+//!
+//! A capture starts with an encoded [`CaptureHeader`], followed by encoded [`CapturedEvent`]s
+//! until the end of the file. Only messages of the v3 validation protocol are captured.
+//!
+//! The capture is best effort: messages are dropped while the writer can not keep up with the
+//! traffic and the capture stops once the file reaches [`MAX_CAPTURE_SIZE`].
+
+use std::{
+	collections::HashSet,
+	fs::File,
+	io::{self, BufRead, BufReader, BufWriter, Write},
+	path::Path,
+	time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+use codec::{Decode, Encode};
+use futures::{channel::mpsc, future::BoxFuture, FutureExt, StreamExt};
+use polkadot_node_network_protocol::{
+	self as net_protocol,
+	grid_topology::{SessionGridTopology, TopologyPeerInfo},
+	peer_set::ValidationVersion,
+	v3 as protocol_v3, ObservedRole, OurView, PeerId, View,
+};
+use polkadot_node_primitives::approval::v1::{BlockApprovalMeta, RelayVRFStory};
+use polkadot_node_subsystem::{
+	messages::{ApprovalDistributionMessage, NetworkBridgeEvent, NewGossipTopology},
+	SubsystemSender,
+};
+use polkadot_node_subsystem_util::request_session_info;
+use polkadot_primitives::{
+	AuthorityDiscoveryId, BlockNumber, CandidateHash, CoreIndex, GroupIndex, Hash, SessionIndex,
+	SessionInfo, ValidatorIndex,
+};
+use sp_consensus_slots::Slot;
+
+use crate::LOG_TARGET;
+
+/// The version of the capture format.
+pub const CAPTURE_VERSION: u32 = 1;
+
+/// The maximum size of a capture file, in bytes.
+pub const MAX_CAPTURE_SIZE: u64 = 4 * 1024 * 1024 * 1024;
+
+/// The number of captured messages waiting to be written, more are dropped.
+const CAPTURE_CHANNEL_SIZE: usize = 16 * 1024;
+
+/// The header of a capture.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct CaptureHeader {
+	/// The version of the capture format, see [`CAPTURE_VERSION`].
+	pub version: u32,
+	/// When the capture started, in milliseconds since the unix epoch.
+	pub started_at_millis: u64,
+	/// The slot duration of the captured chain, in milliseconds.
+	pub slot_duration_millis: u64,
+}
+
+/// A captured event and when it happened.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct CapturedEvent {
+	/// The milliseconds elapsed since the start of the capture.
+	pub elapsed_millis: u64,
+	/// The captured message.
+	pub message: CapturedMessage,
+}
+
+/// A block imported by approval-voting, see [`BlockApprovalMeta`].
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct CapturedBlock {
+	/// The hash of the block.
+	pub hash: Hash,
+	/// The number of the block.
+	pub number: BlockNumber,
+	/// The hash of the parent block.
+	pub parent_hash: Hash,
+	/// The candidates included by the block.
+	pub candidates: Vec<(CandidateHash, CoreIndex, GroupIndex)>,
+	/// The consensus slot of the block.
+	pub slot: Slot,
+	/// The session of the block.
+	pub session: SessionIndex,
+	/// The relay VRF story of the block.
+	pub vrf_story: [u8; 32],
+}
+
+/// A captured gossip topology, see [`NewGossipTopology`].
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct CapturedTopology {
+	/// The session of the topology.
+	pub session: SessionIndex,
+	/// The local validator index, if any.
+	pub local_index: Option<ValidatorIndex>,
+	/// The indices of the validators in the shuffling.
+	pub shuffled_indices: Vec<u32>,
+	/// The canonical shuffling of the validators, with their encoded peer ids.
+	pub canonical_shuffling: Vec<(Vec<Vec<u8>>, ValidatorIndex, AuthorityDiscoveryId)>,
+}
+
+/// A message received by approval-voting-parallel which is relevant for approval-distribution.
+///
+/// Peers are identified by their encoded [`PeerId`].
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub enum CapturedMessage {
+	/// The session info of a session, captured before the first block of the session.
+	SessionInfo(SessionIndex, SessionInfo),
+	/// Blocks imported by approval-voting.
+	NewBlocks(Vec<CapturedBlock>),
+	/// A block was finalized.
+	BlockFinalized(Hash, BlockNumber),
+	/// A peer connected.
+	PeerConnected(Vec<u8>, Vec<AuthorityDiscoveryId>),
+	/// A peer disconnected.
+	PeerDisconnected(Vec<u8>),
+	/// The gossip topology of a new session.
+	NewGossipTopology(CapturedTopology),
+	/// The view of a peer changed.
+	PeerViewChange(Vec<u8>, View),
+	/// Our view changed.
+	OurViewChange(View),
+	/// The authority ids of a peer changed.
+	UpdatedAuthorityIds(Vec<u8>, Vec<AuthorityDiscoveryId>),
+	/// A peer sent a message.
+	PeerMessage(Vec<u8>, protocol_v3::ApprovalDistributionMessage),
+}
+
+impl CapturedMessage {
+	/// Capture the blocks of a [`ApprovalDistributionMessage::NewBlocks`] message.
+	pub fn from_new_blocks(blocks: &[BlockApprovalMeta]) -> Self {
+		Self::NewBlocks(
+			blocks
+				.iter()
+				.map(|meta| CapturedBlock {
+					hash: meta.hash,
+					number: meta.number,
+					parent_hash: meta.parent_hash,
+					candidates: meta.candidates.clone(),
+					slot: meta.slot,
+					session: meta.session,
+					vrf_story: meta.vrf_story.0,
+				})
+				.collect(),
+		)
+	}
+
+	/// Capture a network bridge event.
+	///
+	/// Returns `None` for messages of validation protocol versions other than v3.
+	pub fn from_network_event(
+		event: &NetworkBridgeEvent<net_protocol::ApprovalDistributionMessage>,
+	) -> Option<Self> {
+		let message = match event {
+			NetworkBridgeEvent::PeerConnected(peer_id, _, _, authority_ids) => Self::PeerConnected(
+				peer_id.to_bytes(),
+				authority_ids.iter().flatten().cloned().collect(),
+			),
+			NetworkBridgeEvent::PeerDisconnected(peer_id) =>
+				Self::PeerDisconnected(peer_id.to_bytes()),
+			NetworkBridgeEvent::NewGossipTopology(topology) =>
+				Self::NewGossipTopology(CapturedTopology {
+					session: topology.session,
+					local_index: topology.local_index,
+					shuffled_indices: topology
+						.topology
+						.shuffled_indices()
+						.iter()
+						.map(|index| *index as u32)
+						.collect(),
+					canonical_shuffling: topology
+						.topology
+						.canonical_shuffling()
+						.iter()
+						.map(|peer| {
+							(
+								peer.peer_ids.iter().map(|peer_id| peer_id.to_bytes()).collect(),
+								peer.validator_index,
+								peer.discovery_id.clone(),
+							)
+						})
+						.collect(),
+				}),
+			NetworkBridgeEvent::PeerViewChange(peer_id, view) =>
+				Self::PeerViewChange(peer_id.to_bytes(), view.clone()),
+			NetworkBridgeEvent::OurViewChange(view) => Self::OurViewChange((**view).clone()),
+			NetworkBridgeEvent::UpdatedAuthorityIds(peer_id, authority_ids) =>
+				Self::UpdatedAuthorityIds(
+					peer_id.to_bytes(),
+					authority_ids.iter().cloned().collect(),
+				),
+			NetworkBridgeEvent::PeerMessage(peer_id, net_protocol::Versioned::V3(message)) =>
+				Self::PeerMessage(peer_id.to_bytes(), message.clone()),
+			NetworkBridgeEvent::PeerMessage(_, _) => return None,
+		};
+
+		Some(message)
+	}
+
+	/// The message approval-distribution receives for this captured message.
+	///
+	/// Returns `None` for session infos, finalized blocks and invalid peer ids, which
+	/// approval-distribution does not receive as messages.
+	pub fn into_approval_distribution_message(self) -> Option<ApprovalDistributionMessage> {
+		let peer = |bytes: Vec<u8>| PeerId::from_bytes(&bytes).ok();
+
+		let event = match self {
+			Self::SessionInfo(..) | Self::BlockFinalized(..) => return None,
+			Self::NewBlocks(blocks) =>
+				return Some(ApprovalDistributionMessage::NewBlocks(
+					blocks
+						.into_iter()
+						.map(|block| BlockApprovalMeta {
+							hash: block.hash,
+							number: block.number,
+							parent_hash: block.parent_hash,
+							candidates: block.candidates,
+							slot: block.slot,
+							session: block.session,
+							vrf_story: RelayVRFStory(block.vrf_story),
+						})
+						.collect(),
+				)),
+			Self::PeerConnected(peer_id, authority_ids) => NetworkBridgeEvent::PeerConnected(
+				peer(peer_id)?,
+				ObservedRole::Authority,
+				ValidationVersion::V3.into(),
+				Some(authority_ids.into_iter().collect()),
+			),
+			Self::PeerDisconnected(peer_id) => NetworkBridgeEvent::PeerDisconnected(peer(peer_id)?),
+			Self::NewGossipTopology(topology) => {
+				let canonical_shuffling = topology
+					.canonical_shuffling
+					.into_iter()
+					.map(|(peer_ids, validator_index, discovery_id)| TopologyPeerInfo {
+						peer_ids: peer_ids.into_iter().filter_map(peer).collect(),
+						validator_index,
+						discovery_id,
+					})
+					.collect();
+				NetworkBridgeEvent::NewGossipTopology(NewGossipTopology {
+					session: topology.session,
+					topology: SessionGridTopology::new(
+						topology.shuffled_indices.into_iter().map(|index| index as usize).collect(),
+						canonical_shuffling,
+					),
+					local_index: topology.local_index,
+				})
+			},
+			Self::PeerViewChange(peer_id, view) =>
+				NetworkBridgeEvent::PeerViewChange(peer(peer_id)?, view),
+			Self::OurViewChange(view) => NetworkBridgeEvent::OurViewChange(OurView::new(
+				view.iter().copied(),
+				view.finalized_number,
+			)),
+			Self::UpdatedAuthorityIds(peer_id, authority_ids) =>
+				NetworkBridgeEvent::UpdatedAuthorityIds(
+					peer(peer_id)?,
+					authority_ids.into_iter().collect::<HashSet<_>>(),
+				),
+			Self::PeerMessage(peer_id, message) => NetworkBridgeEvent::PeerMessage(
+				peer(peer_id)?,
+				net_protocol::Versioned::V3(message),
+			),
+		};
+
+		Some(ApprovalDistributionMessage::NetworkBridgeUpdate(event))
+	}
+}
+
+/// Read a capture written by a node.
+///
+/// Returns the header of the capture and an iterator decoding the events from the file as they
+/// are consumed.
+pub fn read_capture(path: &Path) -> io::Result<(CaptureHeader, CaptureReader)> {
+	let mut input = BufReader::new(File::open(path)?);
+
+	let header = CaptureHeader::decode(&mut codec::IoReader(&mut input)).map_err(invalid_data)?;
+	if header.version != CAPTURE_VERSION {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			format!("Unsupported capture version {}", header.version),
+		))
+	}
+
+	Ok((header, CaptureReader { input }))
+}
+
+fn invalid_data(err: codec::Error) -> io::Error {
+	io::Error::new(io::ErrorKind::InvalidData, err.to_string())
+}
+
+/// The events of a capture, decoded one at a time, see [`read_capture`].
+pub struct CaptureReader {
+	input: BufReader<File>,
+}
+
+impl Iterator for CaptureReader {
+	type Item = io::Result<CapturedEvent>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		match self.input.fill_buf() {
+			Ok(buf) if buf.is_empty() => None,
+			Ok(_) => Some(
+				CapturedEvent::decode(&mut codec::IoReader(&mut self.input)).map_err(invalid_data),
+			),
+			Err(err) => Some(Err(err)),
+		}
+	}
+}
+
+/// Records captured messages, which are written to the capture file in the background.
+pub(crate) struct TrafficCapture {
+	started: Instant,
+	tx: mpsc::Sender<CapturedEvent>,
+	dropped: u64,
+}
+
+impl TrafficCapture {
+	/// Start a capture into the file at `path`.
+	///
+	/// Returns the capture and the future writing the file, which uses `sender` to fetch the
+	/// session infos of captured blocks. The writer does blocking file IO, it must be spawned as
+	/// a blocking task.
+	pub(crate) fn start<Sender>(
+		path: &Path,
+		slot_duration_millis: u64,
+		mut sender: Sender,
+	) -> io::Result<(Self, BoxFuture<'static, ()>)>
+	where
+		Sender: SubsystemSender<polkadot_node_subsystem::messages::RuntimeApiMessage>,
+	{
+		let mut file = BufWriter::new(File::create(path)?);
+		let started_at_millis =
+			SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+		let header =
+			CaptureHeader { version: CAPTURE_VERSION, started_at_millis, slot_duration_millis };
+		let header = header.encode();
+		file.write_all(&header)?;
+		file.flush()?;
+
+		let (tx, mut rx) = mpsc::channel::<CapturedEvent>(CAPTURE_CHANNEL_SIZE);
+		let path = path.to_path_buf();
+		let writer = async move {
+			let mut known_sessions = HashSet::new();
+			let mut size = header.len() as u64;
+
+			while let Some(event) = rx.next().await {
+				let mut events = Vec::new();
+				if let CapturedMessage::NewBlocks(ref blocks) = event.message {
+					for block in blocks {
+						if !known_sessions.insert(block.session) {
+							continue
+						}
+						match request_session_info(block.hash, block.session, &mut sender)
+							.await
+							.await
+						{
+							Ok(Ok(Some(session_info))) => events.push(CapturedEvent {
+								elapsed_millis: event.elapsed_millis,
+								message: CapturedMessage::SessionInfo(block.session, session_info),
+							}),
+							err => {
+								gum::warn!(
+									target: LOG_TARGET,
+									session = block.session,
+									?err,
+									"Failed to fetch session info for the approval traffic capture",
+								);
+								known_sessions.remove(&block.session);
+							},
+						}
+					}
+				}
+				// Flush on every new block, so the capture is usable while the node is running.
+				let flush = matches!(event.message, CapturedMessage::NewBlocks(_));
+				events.push(event);
+
+				let encoded: Vec<u8> = events.iter().flat_map(|event| event.encode()).collect();
+				size += encoded.len() as u64;
+				if size > MAX_CAPTURE_SIZE {
+					gum::warn!(
+						target: LOG_TARGET,
+						?path,
+						max_size = MAX_CAPTURE_SIZE,
+						"The approval traffic capture reached its maximum size, stopping",
+					);
+					break
+				}
+
+				let written =
+					file.write_all(&encoded)
+						.and_then(|_| if flush { file.flush() } else { Ok(()) });
+				if let Err(err) = written {
+					gum::warn!(
+						target: LOG_TARGET,
+						?err,
+						?path,
+						"Failed to write the approval traffic capture, stopping",
+					);
+					return
+				}
+			}
+
+			let _ = file.flush();
+		}
+		.boxed();
+
+		Ok((Self { started: Instant::now(), tx, dropped: 0 }, writer))
+	}
+
+	/// Record a message.
+	///
+	/// The message is dropped if the writer is behind, the main loop never waits for it.
+	pub(crate) fn record(&mut self, message: CapturedMessage) {
+		let elapsed_millis = self.started.elapsed().as_millis() as u64;
+		match self.tx.try_send(CapturedEvent { elapsed_millis, message }) {
+			Err(err) if err.is_full() => {
+				self.dropped += 1;
+				if self.dropped.is_power_of_two() {
+					gum::warn!(
+						target: LOG_TARGET,
+						dropped = self.dropped,
+						"The approval traffic capture is behind, dropping messages",
+					);
+				}
+			},
+			// The writer stopped, there is nothing left to capture.
+			Ok(()) | Err(_) => {},
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_application_crypto::ByteArray;
+
+	fn authority(seed: u8) -> AuthorityDiscoveryId {
+		AuthorityDiscoveryId::from_slice(&[seed; 32]).unwrap()
+	}
+
+	#[test]
+	fn network_events_survive_capture() {
+		let peer_id = PeerId::random();
+		let events = vec![
+			NetworkBridgeEvent::PeerConnected(
+				peer_id,
+				ObservedRole::Authority,
+				ValidationVersion::V3.into(),
+				Some([authority(1)].into_iter().collect()),
+			),
+			NetworkBridgeEvent::NewGossipTopology(NewGossipTopology {
+				session: 2,
+				topology: SessionGridTopology::new(
+					vec![1, 0],
+					vec![
+						TopologyPeerInfo {
+							peer_ids: vec![peer_id],
+							validator_index: ValidatorIndex(1),
+							discovery_id: authority(1),
+						},
+						TopologyPeerInfo {
+							peer_ids: vec![],
+							validator_index: ValidatorIndex(0),
+							discovery_id: authority(2),
+						},
+					],
+				),
+				local_index: Some(ValidatorIndex(0)),
+			}),
+			NetworkBridgeEvent::PeerViewChange(peer_id, View::new([Hash::repeat_byte(1)], 3)),
+			NetworkBridgeEvent::OurViewChange(OurView::new([Hash::repeat_byte(2)], 4)),
+			NetworkBridgeEvent::PeerMessage(
+				peer_id,
+				net_protocol::Versioned::V3(protocol_v3::ApprovalDistributionMessage::Approvals(
+					vec![],
+				)),
+			),
+			NetworkBridgeEvent::PeerDisconnected(peer_id),
+		];
+
+		for event in events {
+			let captured = CapturedMessage::from_network_event(&event).unwrap();
+			let decoded = CapturedMessage::decode(&mut &captured.encode()[..]).unwrap();
+			let Some(ApprovalDistributionMessage::NetworkBridgeUpdate(replayed)) =
+				decoded.into_approval_distribution_message()
+			else {
+				panic!("Network events are replayed as network bridge updates")
+			};
+			assert_eq!(replayed, event);
+		}
+	}
+
+	#[test]
+	fn older_protocol_versions_are_not_captured() {
+		let event = NetworkBridgeEvent::PeerMessage(
+			PeerId::random(),
+			net_protocol::Versioned::V2(net_protocol::v2::ApprovalDistributionMessage::Approvals(
+				vec![],
+			)),
+		);
+		assert_eq!(CapturedMessage::from_network_event(&event), None);
+	}
+
+	#[test]
+	fn captures_are_read_event_by_event() {
+		let header = CaptureHeader {
+			version: CAPTURE_VERSION,
+			started_at_millis: 1,
+			slot_duration_millis: 6000,
+		};
+		let events = vec![
+			CapturedEvent {
+				elapsed_millis: 0,
+				message: CapturedMessage::BlockFinalized(Hash::repeat_byte(1), 1),
+			},
+			CapturedEvent {
+				elapsed_millis: 10,
+				message: CapturedMessage::PeerDisconnected(PeerId::random().to_bytes()),
+			},
+		];
+
+		let mut file = tempfile::NamedTempFile::new().unwrap();
+		file.write_all(&header.encode()).unwrap();
+		events.iter().for_each(|event| file.write_all(&event.encode()).unwrap());
+		file.flush().unwrap();
+
+		let (read_header, reader) = read_capture(file.path()).unwrap();
+		assert_eq!(read_header, header);
+		assert_eq!(reader.collect::<io::Result<Vec<_>>>().unwrap(), events);
+
+		// A truncated event is an error.
+		file.write_all(&events[0].encode()[..10]).unwrap();
+		file.flush().unwrap();
+		let (_, reader) = read_capture(file.path()).unwrap();
+		assert!(reader.collect::<io::Result<Vec<_>>>().is_err());
+	}
+}
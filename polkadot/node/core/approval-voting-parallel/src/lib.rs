@@ -20,9 +20,13 @@
 //! approval-voting and approval-distribution subsystem, so they can
 //! do their work in parallel, rather than serially, when they are run
 //! as independent subsystems.
+use capture::{CapturedMessage, TrafficCapture};
 use itertools::Itertools;
 use metrics::{Meters, MetricsWatcher};
-use polkadot_node_core_approval_voting::{Config, RealAssignmentCriteria};
+use polkadot_node_core_approval_voting::{
+	timeline::{ApprovalTimelines, TimedAssignmentCriteria},
+	Config, RealAssignmentCriteria,
+};
 use polkadot_node_metrics::metered::{
 	self, channel, unbounded, MeteredReceiver, MeteredSender, UnboundedMeteredReceiver,
 	UnboundedMeteredSender,
@@ -56,10 +60,12 @@ use polkadot_node_core_approval_voting::{
 use std::{
 	collections::{HashMap, HashSet},
 	fmt::Debug,
+	path::PathBuf,
 	sync::Arc,
 	time::Duration,
 };
 use stream::{select_with_strategy, PollNext, SelectWithStrategy};
+pub mod capture;
 pub mod metrics;
 
 #[cfg(test)]
@@ -95,6 +101,8 @@ pub struct ApprovalVotingParallelSubsystem {
 	spawner: Arc<dyn overseer::gen::Spawner + 'static>,
 	clock: Arc<dyn Clock + Send + Sync>,
 	overseer_message_channel_capacity_override: Option<usize>,
+	timelines: ApprovalTimelines,
+	traffic_capture: Option<PathBuf>,
 }
 
 impl ApprovalVotingParallelSubsystem {
@@ -141,9 +149,24 @@ impl ApprovalVotingParallelSubsystem {
 			spawner: Arc::new(spawner),
 			clock,
 			overseer_message_channel_capacity_override,
+			timelines: ApprovalTimelines::default(),
+			traffic_capture: None,
 		}
 	}
 
+	/// Record the approval timelines of recent blocks into the given `timelines`.
+	pub fn with_timelines(mut self, timelines: ApprovalTimelines) -> Self {
+		self.timelines = timelines;
+		self
+	}
+
+	/// Capture the received approval-distribution traffic into the file at `path`, see
+	/// [`capture`].
+	pub fn with_traffic_capture(mut self, path: Option<PathBuf>) -> Self {
+		self.traffic_capture = path;
+		self
+	}
+
 	/// The size of the channel used for the workers.
 	fn workers_channel_size(&self) -> usize {
 		self.overseer_message_channel_capacity_override
@@ -196,7 +219,10 @@ where
 				subsystem.metrics.approval_distribution_metrics(),
 				subsystem.slot_duration_millis,
 				subsystem.clock.clone(),
-				Arc::new(RealAssignmentCriteria {}),
+				Arc::new(TimedAssignmentCriteria::new(
+					RealAssignmentCriteria {},
+					subsystem.timelines.clone(),
+				)),
 			);
 		let task_name = format!("approval-voting-parallel-{}", i);
 		let (to_approval_distribution_worker, mut approval_distribution_work_provider) =
@@ -275,6 +301,7 @@ where
 		"approval-voting-parallel-db",
 		"approval-voting-parallel",
 		subsystem.clock.clone(),
+		subsystem.timelines.clone(),
 	)
 	.await?;
 
@@ -288,6 +315,7 @@ async fn run<Context>(
 	subsystem: ApprovalVotingParallelSubsystem,
 ) -> SubsystemResult<()> {
 	let mut metrics_watcher = MetricsWatcher::new(subsystem.metrics.clone());
+	let capture = start_traffic_capture(&mut ctx, &subsystem);
 	gum::info!(
 		target: LOG_TARGET,
 		"Starting workers"
@@ -301,8 +329,41 @@ async fn run<Context>(
 		"Starting main subsystem loop"
 	);
 
-	run_main_loop(ctx, to_approval_voting_worker, to_approval_distribution_workers, metrics_watcher)
-		.await
+	run_main_loop(
+		ctx,
+		to_approval_voting_worker,
+		to_approval_distribution_workers,
+		metrics_watcher,
+		capture,
+	)
+	.await
+}
+
+// Starts capturing the approval-distribution traffic, if configured.
+//
+// Failing to start the capture is not fatal, the subsystem runs without it.
+#[overseer::contextbounds(ApprovalVotingParallel, prefix = self::overseer)]
+fn start_traffic_capture<Context>(
+	ctx: &mut Context,
+	subsystem: &ApprovalVotingParallelSubsystem,
+) -> Option<TrafficCapture> {
+	let path = subsystem.traffic_capture.as_ref()?;
+	let (capture, writer) =
+		match TrafficCapture::start(path, subsystem.slot_duration_millis, ctx.sender().clone()) {
+			Ok(capture) => capture,
+			Err(err) => {
+				gum::warn!(target: LOG_TARGET, ?err, ?path, "Failed to start the traffic capture");
+				return None
+			},
+		};
+
+	if let Err(err) = ctx.spawn_blocking("approval-traffic-capture", writer) {
+		gum::warn!(target: LOG_TARGET, ?err, "Failed to spawn the traffic capture writer");
+		return None
+	}
+
+	gum::info!(target: LOG_TARGET, ?path, "Capturing approval-distribution traffic");
+	Some(capture)
 }
 
 // Main loop of the subsystem, it shouldn't include any logic just dispatching of messages to
@@ -315,6 +376,7 @@ async fn run_main_loop<Context>(
 	mut to_approval_voting_worker: ToWorker<ApprovalVotingMessage>,
 	mut to_approval_distribution_workers: Vec<ToWorker<ApprovalDistributionMessage>>,
 	metrics_watcher: MetricsWatcher,
+	mut capture: Option<TrafficCapture>,
 ) -> SubsystemResult<()> {
 	loop {
 		futures::select! {
@@ -332,6 +394,9 @@ async fn run_main_loop<Context>(
 						if matches!(msg, OverseerSignal::ActiveLeaves(_)) {
 							metrics_watcher.collect_metrics();
 						}
+						if let (Some(capture), OverseerSignal::BlockFinalized(hash, number)) = (&mut capture, &msg) {
+							capture.record(CapturedMessage::BlockFinalized(*hash, *number));
+						}
 
 						for worker in to_approval_distribution_workers.iter_mut() {
 							worker
@@ -357,6 +422,9 @@ async fn run_main_loop<Context>(
 						// Now the message the approval distribution subsystem would've handled and need to
 						// be forwarded to the workers.
 						ApprovalVotingParallelMessage::NewBlocks(msg) => {
+							if let Some(capture) = &mut capture {
+								capture.record(CapturedMessage::from_new_blocks(&msg));
+							}
 							for worker in to_approval_distribution_workers.iter_mut() {
 								worker
 									.send_message(
@@ -383,6 +451,11 @@ async fn run_main_loop<Context>(
 
 						},
 						ApprovalVotingParallelMessage::NetworkBridgeUpdate(msg) => {
+							if let Some(capture) = &mut capture {
+								if let Some(captured) = CapturedMessage::from_network_event(&msg) {
+									capture.record(captured);
+								}
+							}
 							if let polkadot_node_subsystem::messages::NetworkBridgeEvent::PeerMessage(
 								peer_id,
 								msg,
@@ -216,6 +216,7 @@ fn test_harness<T, Clos, State>(
 			to_approval_voting_worker,
 			to_approval_distribution_workers,
 			metrics_watcher,
+			None,
 		)
 		.await;
 
@@ -563,7 +563,7 @@ pub(crate) async fn handle_new_head<
 			}
 		}
 
-		approval_meta.push(BlockApprovalMeta {
+		let block_meta = BlockApprovalMeta {
 			hash: block_hash,
 			number: block_header.number,
 			parent_hash: block_header.parent_hash,
@@ -574,7 +574,9 @@ pub(crate) async fn handle_new_head<
 			slot,
 			session: session_index,
 			vrf_story: relay_vrf_story,
-		});
+		};
+		state.timelines.note_block_imported(&block_meta, state.clock.tick_now());
+		approval_meta.push(block_meta);
 
 		imported_candidates.push(BlockImportedCandidates {
 			block_hash,
@@ -663,6 +665,7 @@ pub(crate) mod tests {
 				MAX_BLOCKS_WITH_ASSIGNMENT_TIMESTAMPS,
 			)),
 			no_show_stats: Default::default(),
+			timelines: Default::default(),
 		}
 	}
 
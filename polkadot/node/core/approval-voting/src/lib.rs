@@ -103,6 +103,7 @@ pub mod criteria;
 mod import;
 mod ops;
 mod persisted_entries;
+pub mod timeline;
 
 use crate::{
 	approval_checking::{Check, TranchesToApproveResult},
@@ -110,6 +111,7 @@ use crate::{
 	backend::{Backend, OverlayedBackend},
 	criteria::InvalidAssignmentReason,
 	persisted_entries::OurApproval,
+	timeline::ApprovalTimelines,
 };
 
 #[cfg(test)]
@@ -183,6 +185,8 @@ pub struct ApprovalVotingSubsystem {
 	max_approval_retries: u32,
 	/// The backoff before we retry the approval.
 	retry_backoff: Duration,
+	/// The approval timelines of recent blocks.
+	timelines: ApprovalTimelines,
 }
 
 #[derive(Clone)]
@@ -539,9 +543,16 @@ impl ApprovalVotingSubsystem {
 			spawner,
 			max_approval_retries,
 			retry_backoff,
+			timelines: ApprovalTimelines::default(),
 		}
 	}
 
+	/// Record the approval timelines of recent blocks into the given `timelines`.
+	pub fn with_timelines(mut self, timelines: ApprovalTimelines) -> Self {
+		self.timelines = timelines;
+		self
+	}
+
 	/// Revert to the block corresponding to the specified `hash`.
 	/// The operation is not allowed for blocks older than the last finalized one.
 	pub fn revert_to(&self, hash: Hash) -> Result<(), SubsystemError> {
@@ -898,6 +909,7 @@ struct State {
 	per_block_assignments_gathering_times:
 		LruMap<BlockNumber, HashMap<(Hash, CandidateHash), AssignmentGatheringRecord>>,
 	no_show_stats: NoShowStats,
+	timelines: ApprovalTimelines,
 }
 
 // Regularly dump the no-show stats at this block number frequency.
@@ -1257,6 +1269,7 @@ where
 			MAX_BLOCKS_WITH_ASSIGNMENT_TIMESTAMPS,
 		)),
 		no_show_stats: NoShowStats::default(),
+		timelines: subsystem.timelines.clone(),
 	};
 
 	let mut last_finalized_height: Option<BlockNumber> = {
@@ -1480,6 +1493,7 @@ pub async fn start_approval_worker<
 	task_name: &'static str,
 	group_name: &'static str,
 	clock: Arc<dyn Clock + Send + Sync>,
+	timelines: ApprovalTimelines,
 ) -> SubsystemResult<()> {
 	let approval_voting = ApprovalVotingSubsystem::with_config_and_clock(
 		config,
@@ -1491,7 +1505,8 @@ pub async fn start_approval_worker<
 		spawner,
 		MAX_APPROVAL_RETRIES,
 		APPROVAL_CHECKING_TIMEOUT / 2,
-	);
+	)
+	.with_timelines(timelines);
 	let backend = DbBackend::new(db.clone(), approval_voting.db_config);
 	let spawner = approval_voting.spawner.clone();
 	spawner.spawn_blocking(
@@ -1621,6 +1636,13 @@ async fn handle_actions<
 				metrics.on_assignment_produced(assignment_tranche);
 				let block_hash = indirect_cert.block_hash;
 				let validator_index = indirect_cert.validator;
+				state.timelines.note_assignment(
+					block_hash,
+					candidate_hash,
+					validator_index,
+					assignment_tranche,
+					state.clock.tick_now(),
+				);
 
 				if distribute_assignment {
 					approval_voting_sender.send_unbounded_message(
@@ -2817,6 +2839,13 @@ where
 				tick_now,
 				is_duplicate_for_candidate,
 			);
+			state.timelines.note_assignment(
+				assignment.block_hash,
+				*assigned_candidate_hash,
+				assignment.validator,
+				tranche,
+				tick_now,
+			);
 
 			// We've imported a new assignment, so we need to schedule a wake-up for when that might
 			// no-show.
@@ -3068,6 +3097,12 @@ where
 	let para_id = candidate_entry.candidate_receipt().descriptor().para_id();
 	let tick_now = state.clock.tick_now();
 
+	if let (Some(validator_index), Some(false)) = (validator_index, already_approved_by) {
+		state
+			.timelines
+			.note_approval(block_hash, candidate_hash, validator_index, tick_now);
+	}
+
 	let (is_approved, status) = if let Some((approval_entry, status)) = state
 		.approval_status(sender, session_info_provider, &block_entry, &candidate_entry)
 		.await
@@ -3091,6 +3126,9 @@ where
 		let is_approved = check.is_approved(tick_now.saturating_sub(APPROVAL_DELAY));
 		if status.last_no_shows != 0 {
 			metrics.on_observed_no_shows(status.last_no_shows);
+			state
+				.timelines
+				.note_no_shows(block_hash, candidate_hash, &status.no_show_validators);
 			gum::trace!(
 				target: LOG_TARGET,
 				?candidate_hash,
@@ -3124,9 +3162,16 @@ where
 			}
 
 			metrics.on_candidate_approved(status.tranche_now as _);
+			state.timelines.note_candidate_approved(
+				block_hash,
+				candidate_hash,
+				status.tranche_now,
+				tick_now,
+			);
 
 			if is_block_approved && !was_block_approved {
 				metrics.on_block_approved(status.tranche_now as _);
+				state.timelines.note_block_approved(block_hash, tick_now);
 				actions.push(Action::NoteApprovedInChainSelection(block_hash));
 			}
 
@@ -0,0 +1,543 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Approval timelines of recent blocks.
+//!
+//! For every imported block the subsystem records when assignments and approvals for its
+//! candidates were imported, which validators were observed as no-shows and when candidates and
+//! the block got approved. Together with the time spent checking assignment certificates, this
+//! explains after the fact why the approval of a block was slow.
+//!
+//! All times are [`Tick`]s, i.e. multiples of 500ms since the unix epoch.
+
+use std::{
+	sync::{Arc, Weak},
+	time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use polkadot_node_primitives::approval::{
+	criteria::{AssignmentCriteria, Config, InvalidAssignment, OurAssignment},
+	time::Tick,
+	v1::{BlockApprovalMeta, DelayTranche, RelayVRFStory},
+	v2::{AssignmentCertV2, CoreBitfield},
+};
+use polkadot_primitives::{
+	BlockNumber, CandidateHash, CoreIndex, GroupIndex, Hash, SessionIndex, ValidatorIndex,
+};
+use sc_keystore::LocalKeystore;
+use schnellru::{ByLength, LruMap};
+use serde::{Deserialize, Serialize};
+
+/// The number of recent blocks timelines are kept for.
+pub const MAX_BLOCKS_WITH_TIMELINES: u32 = 64;
+
+/// The approval timeline of a single block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockApprovalTimeline {
+	/// The hash of the block.
+	pub block_hash: Hash,
+	/// The number of the block.
+	pub block_number: BlockNumber,
+	/// The session of the block.
+	pub session: SessionIndex,
+	/// When the block was imported by approval-voting.
+	pub imported_at: Tick,
+	/// When all candidates of the block were approved.
+	pub approved_at: Option<Tick>,
+	/// The assignment certificates checked for the candidates of the block.
+	pub assignment_checks: AssignmentChecks,
+	/// The candidates included by the block.
+	pub candidates: Vec<CandidateApprovalTimeline>,
+}
+
+/// Statistics about the assignment certificates checked for a block.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignmentChecks {
+	/// The number of checked certificates.
+	pub count: u32,
+	/// The time spent checking all certificates, in microseconds.
+	pub total_micros: u64,
+	/// The time spent checking the slowest certificate, in microseconds.
+	pub max_micros: u64,
+}
+
+impl AssignmentChecks {
+	fn note(&mut self, elapsed: Duration) {
+		let micros = elapsed.as_micros().try_into().unwrap_or(u64::MAX);
+		self.count = self.count.saturating_add(1);
+		self.total_micros = self.total_micros.saturating_add(micros);
+		self.max_micros = self.max_micros.max(micros);
+	}
+
+	fn merge(&mut self, other: &AssignmentChecks) {
+		self.count = self.count.saturating_add(other.count);
+		self.total_micros = self.total_micros.saturating_add(other.total_micros);
+		self.max_micros = self.max_micros.max(other.max_micros);
+	}
+}
+
+/// The approval timeline of a candidate under a block.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CandidateApprovalTimeline {
+	/// The hash of the candidate.
+	pub candidate_hash: Hash,
+	/// The core the candidate was included on.
+	pub core_index: u32,
+	/// The group that backed the candidate.
+	pub backing_group: u32,
+	/// The assignments imported for the candidate, by ascending tranche.
+	pub tranches: Vec<TrancheTimeline>,
+	/// Validators that were observed as no-shows.
+	pub no_shows: Vec<u32>,
+	/// When the candidate was approved.
+	pub approved_at: Option<Tick>,
+	/// The tranche at which the candidate was approved.
+	pub approved_in_tranche: Option<DelayTranche>,
+}
+
+/// The assignments of a single tranche.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrancheTimeline {
+	/// The delay tranche.
+	pub tranche: DelayTranche,
+	/// The assignments, in the order they were imported.
+	pub assignments: Vec<AssignmentTimeline>,
+}
+
+/// An assignment of a validator and its approval.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssignmentTimeline {
+	/// The index of the assigned validator.
+	pub validator: u32,
+	/// When the assignment was imported.
+	pub assigned_at: Tick,
+	/// When the approval of the validator was imported.
+	pub approved_at: Option<Tick>,
+}
+
+impl CandidateApprovalTimeline {
+	fn assignment_mut(&mut self, validator: ValidatorIndex) -> Option<&mut AssignmentTimeline> {
+		self.tranches
+			.iter_mut()
+			.flat_map(|tranche| tranche.assignments.iter_mut())
+			.find(|assignment| assignment.validator == validator.0)
+	}
+}
+
+// The assignment checks recorded by a single `TimedAssignmentCriteria`, by relay VRF story.
+type CheckedAssignments = Mutex<LruMap<[u8; 32], AssignmentChecks>>;
+
+struct Inner {
+	blocks: LruMap<Hash, BlockApprovalTimeline>,
+	// The relay VRF story of each block, used to attribute assignment checks to blocks.
+	vrf_stories: LruMap<Hash, [u8; 32]>,
+	// Every `TimedAssignmentCriteria` records the checks into its own map, so checking
+	// certificates on the approval-distribution workers does not contend on the timelines lock.
+	// The checks are only summed up when the timelines are read.
+	checked_assignments: Vec<Weak<CheckedAssignments>>,
+}
+
+impl Inner {
+	fn with_assignment_checks(&self, mut timeline: BlockApprovalTimeline) -> BlockApprovalTimeline {
+		let Some(vrf_story) = self.vrf_stories.peek(&timeline.block_hash) else { return timeline };
+		for checked in self.checked_assignments.iter().filter_map(Weak::upgrade) {
+			if let Some(checks) = checked.lock().peek(vrf_story) {
+				timeline.assignment_checks.merge(checks);
+			}
+		}
+		timeline
+	}
+}
+
+/// Approval timelines of the most recent blocks, shared between approval-voting, the
+/// approval-distribution workers and whoever reads them.
+#[derive(Clone)]
+pub struct ApprovalTimelines(Arc<Mutex<Inner>>);
+
+impl Default for ApprovalTimelines {
+	fn default() -> Self {
+		Self(Arc::new(Mutex::new(Inner {
+			blocks: LruMap::new(ByLength::new(MAX_BLOCKS_WITH_TIMELINES)),
+			vrf_stories: LruMap::new(ByLength::new(MAX_BLOCKS_WITH_TIMELINES)),
+			checked_assignments: Vec::new(),
+		})))
+	}
+}
+
+impl ApprovalTimelines {
+	/// Create empty timelines.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// The timeline of the given block, if it is still kept.
+	pub fn block(&self, block_hash: &Hash) -> Option<BlockApprovalTimeline> {
+		let inner = self.0.lock();
+		let timeline = inner.blocks.peek(block_hash).cloned()?;
+		Some(inner.with_assignment_checks(timeline))
+	}
+
+	/// The timelines of all kept blocks, by descending block number.
+	pub fn recent(&self) -> Vec<BlockApprovalTimeline> {
+		let inner = self.0.lock();
+		let mut timelines = inner
+			.blocks
+			.iter()
+			.map(|(_, timeline)| inner.with_assignment_checks(timeline.clone()))
+			.collect::<Vec<_>>();
+		timelines.sort_by(|a, b| b.block_number.cmp(&a.block_number));
+		timelines
+	}
+
+	pub(crate) fn note_block_imported(&self, meta: &BlockApprovalMeta, tick: Tick) {
+		let candidates = meta
+			.candidates
+			.iter()
+			.map(|(candidate_hash, core_index, backing_group)| CandidateApprovalTimeline {
+				candidate_hash: candidate_hash.0,
+				core_index: core_index.0,
+				backing_group: backing_group.0,
+				tranches: Vec::new(),
+				no_shows: Vec::new(),
+				approved_at: None,
+				approved_in_tranche: None,
+			})
+			.collect();
+
+		let mut inner = self.0.lock();
+		inner.vrf_stories.insert(meta.hash, meta.vrf_story.0);
+		inner.blocks.insert(
+			meta.hash,
+			BlockApprovalTimeline {
+				block_hash: meta.hash,
+				block_number: meta.number,
+				session: meta.session,
+				imported_at: tick,
+				approved_at: None,
+				assignment_checks: AssignmentChecks::default(),
+				candidates,
+			},
+		);
+	}
+
+	pub(crate) fn note_assignment(
+		&self,
+		block_hash: Hash,
+		candidate_hash: CandidateHash,
+		validator: ValidatorIndex,
+		tranche: DelayTranche,
+		tick: Tick,
+	) {
+		self.with_candidate(block_hash, candidate_hash, |candidate| {
+			if candidate.assignment_mut(validator).is_some() {
+				return
+			}
+
+			let assignment =
+				AssignmentTimeline { validator: validator.0, assigned_at: tick, approved_at: None };
+			match candidate.tranches.binary_search_by_key(&tranche, |t| t.tranche) {
+				Ok(index) => candidate.tranches[index].assignments.push(assignment),
+				Err(index) => candidate
+					.tranches
+					.insert(index, TrancheTimeline { tranche, assignments: vec![assignment] }),
+			}
+		})
+	}
+
+	pub(crate) fn note_approval(
+		&self,
+		block_hash: Hash,
+		candidate_hash: CandidateHash,
+		validator: ValidatorIndex,
+		tick: Tick,
+	) {
+		self.with_candidate(block_hash, candidate_hash, |candidate| {
+			if let Some(assignment) = candidate.assignment_mut(validator) {
+				assignment.approved_at.get_or_insert(tick);
+			}
+		})
+	}
+
+	pub(crate) fn note_no_shows(
+		&self,
+		block_hash: Hash,
+		candidate_hash: CandidateHash,
+		no_shows: &[ValidatorIndex],
+	) {
+		self.with_candidate(block_hash, candidate_hash, |candidate| {
+			for validator in no_shows {
+				if !candidate.no_shows.contains(&validator.0) {
+					candidate.no_shows.push(validator.0);
+				}
+			}
+		})
+	}
+
+	pub(crate) fn note_candidate_approved(
+		&self,
+		block_hash: Hash,
+		candidate_hash: CandidateHash,
+		tranche: DelayTranche,
+		tick: Tick,
+	) {
+		self.with_candidate(block_hash, candidate_hash, |candidate| {
+			if candidate.approved_at.is_none() {
+				candidate.approved_at = Some(tick);
+				candidate.approved_in_tranche = Some(tranche);
+			}
+		})
+	}
+
+	pub(crate) fn note_block_approved(&self, block_hash: Hash, tick: Tick) {
+		if let Some(block) = self.0.lock().blocks.peek_mut(&block_hash) {
+			block.approved_at.get_or_insert(tick);
+		}
+	}
+
+	fn register_checked_assignments(&self) -> Arc<CheckedAssignments> {
+		let checked = Arc::new(Mutex::new(LruMap::new(ByLength::new(MAX_BLOCKS_WITH_TIMELINES))));
+		let mut inner = self.0.lock();
+		inner.checked_assignments.retain(|checked| checked.strong_count() > 0);
+		inner.checked_assignments.push(Arc::downgrade(&checked));
+		checked
+	}
+
+	fn with_candidate(
+		&self,
+		block_hash: Hash,
+		candidate_hash: CandidateHash,
+		f: impl FnOnce(&mut CandidateApprovalTimeline),
+	) {
+		let mut inner = self.0.lock();
+		if let Some(candidate) = inner.blocks.peek_mut(&block_hash).and_then(|block| {
+			block.candidates.iter_mut().find(|c| c.candidate_hash == candidate_hash.0)
+		}) {
+			f(candidate)
+		}
+	}
+}
+
+/// [`AssignmentCriteria`] which records the time spent checking assignment certificates in the
+/// [`ApprovalTimelines`] of the block the certificate is for.
+///
+/// The checks are kept by the criteria itself and only read by the timelines, so every worker
+/// checking certificates should use its own instance.
+pub struct TimedAssignmentCriteria<C> {
+	inner: C,
+	checked: Arc<CheckedAssignments>,
+}
+
+impl<C> TimedAssignmentCriteria<C> {
+	/// Wrap the given criteria.
+	pub fn new(inner: C, timelines: ApprovalTimelines) -> Self {
+		Self { inner, checked: timelines.register_checked_assignments() }
+	}
+
+	fn note_check(&self, relay_vrf_story: &RelayVRFStory, elapsed: Duration) {
+		if let Some(checks) =
+			self.checked.lock().get_or_insert(relay_vrf_story.0, AssignmentChecks::default)
+		{
+			checks.note(elapsed);
+		}
+	}
+}
+
+impl<C: AssignmentCriteria> AssignmentCriteria for TimedAssignmentCriteria<C> {
+	fn compute_assignments(
+		&self,
+		keystore: &LocalKeystore,
+		relay_vrf_story: RelayVRFStory,
+		config: &Config,
+		leaving_cores: Vec<(CandidateHash, CoreIndex, GroupIndex)>,
+		enable_v2_assignments: bool,
+	) -> std::collections::HashMap<CoreIndex, OurAssignment> {
+		self.inner.compute_assignments(
+			keystore,
+			relay_vrf_story,
+			config,
+			leaving_cores,
+			enable_v2_assignments,
+		)
+	}
+
+	fn check_assignment_cert(
+		&self,
+		claimed_core_bitfield: CoreBitfield,
+		validator_index: ValidatorIndex,
+		config: &Config,
+		relay_vrf_story: RelayVRFStory,
+		assignment: &AssignmentCertV2,
+		backing_groups: Vec<GroupIndex>,
+	) -> Result<DelayTranche, InvalidAssignment> {
+		let started = Instant::now();
+		let result = self.inner.check_assignment_cert(
+			claimed_core_bitfield,
+			validator_index,
+			config,
+			relay_vrf_story.clone(),
+			assignment,
+			backing_groups,
+		);
+		self.note_check(&relay_vrf_story, started.elapsed());
+		result
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::RealAssignmentCriteria;
+	use sp_consensus_slots::Slot;
+
+	fn block_meta(
+		hash: Hash,
+		number: BlockNumber,
+		candidates: &[CandidateHash],
+	) -> BlockApprovalMeta {
+		BlockApprovalMeta {
+			hash,
+			number,
+			parent_hash: Hash::repeat_byte(number as u8),
+			candidates: candidates
+				.iter()
+				.enumerate()
+				.map(|(i, c)| (*c, CoreIndex(i as u32), GroupIndex(i as u32)))
+				.collect(),
+			slot: Slot::from(1),
+			session: 1,
+			vrf_story: RelayVRFStory(hash.0),
+		}
+	}
+
+	#[test]
+	fn records_assignments_approvals_and_no_shows() {
+		let timelines = ApprovalTimelines::new();
+		let block_hash = Hash::repeat_byte(1);
+		let candidate = CandidateHash(Hash::repeat_byte(2));
+		timelines.note_block_imported(&block_meta(block_hash, 1, &[candidate]), 10);
+
+		timelines.note_assignment(block_hash, candidate, ValidatorIndex(3), 2, 12);
+		timelines.note_assignment(block_hash, candidate, ValidatorIndex(1), 0, 11);
+		timelines.note_assignment(block_hash, candidate, ValidatorIndex(2), 0, 11);
+		// Duplicates are ignored.
+		timelines.note_assignment(block_hash, candidate, ValidatorIndex(1), 0, 13);
+		timelines.note_approval(block_hash, candidate, ValidatorIndex(1), 14);
+		timelines.note_approval(block_hash, candidate, ValidatorIndex(3), 15);
+		timelines.note_no_shows(block_hash, candidate, &[ValidatorIndex(2)]);
+		timelines.note_no_shows(block_hash, candidate, &[ValidatorIndex(2)]);
+		timelines.note_candidate_approved(block_hash, candidate, 2, 16);
+		timelines.note_block_approved(block_hash, 16);
+		// Unknown blocks and candidates are ignored.
+		timelines.note_assignment(Hash::repeat_byte(9), candidate, ValidatorIndex(1), 0, 11);
+		timelines.note_approval(
+			block_hash,
+			CandidateHash(Hash::repeat_byte(9)),
+			ValidatorIndex(1),
+			11,
+		);
+
+		let timeline = timelines.block(&block_hash).unwrap();
+		assert_eq!(timeline.imported_at, 10);
+		assert_eq!(timeline.approved_at, Some(16));
+		assert_eq!(
+			timeline.candidates,
+			vec![CandidateApprovalTimeline {
+				candidate_hash: candidate.0,
+				core_index: 0,
+				backing_group: 0,
+				tranches: vec![
+					TrancheTimeline {
+						tranche: 0,
+						assignments: vec![
+							AssignmentTimeline {
+								validator: 1,
+								assigned_at: 11,
+								approved_at: Some(14)
+							},
+							AssignmentTimeline { validator: 2, assigned_at: 11, approved_at: None },
+						],
+					},
+					TrancheTimeline {
+						tranche: 2,
+						assignments: vec![AssignmentTimeline {
+							validator: 3,
+							assigned_at: 12,
+							approved_at: Some(15),
+						}],
+					},
+				],
+				no_shows: vec![2],
+				approved_at: Some(16),
+				approved_in_tranche: Some(2),
+			}]
+		);
+	}
+
+	#[test]
+	fn keeps_only_recent_blocks() {
+		let timelines = ApprovalTimelines::new();
+		for number in 0..MAX_BLOCKS_WITH_TIMELINES + 2 {
+			let hash = Hash::from_low_u64_be(number as u64 + 1);
+			timelines.note_block_imported(&block_meta(hash, number, &[]), number as Tick);
+		}
+
+		let recent = timelines.recent();
+		assert_eq!(recent.len(), MAX_BLOCKS_WITH_TIMELINES as usize);
+		assert_eq!(recent[0].block_number, MAX_BLOCKS_WITH_TIMELINES + 1);
+		assert_eq!(recent.last().unwrap().block_number, 2);
+		assert!(timelines.block(&Hash::from_low_u64_be(1)).is_none());
+	}
+
+	#[test]
+	fn attributes_assignment_checks_of_all_workers_to_blocks() {
+		let timelines = ApprovalTimelines::new();
+		let block_hash = Hash::repeat_byte(1);
+		timelines.note_block_imported(&block_meta(block_hash, 1, &[]), 10);
+
+		let story = RelayVRFStory(block_hash.0);
+		let worker_1 = TimedAssignmentCriteria::new(RealAssignmentCriteria, timelines.clone());
+		let worker_2 = TimedAssignmentCriteria::new(RealAssignmentCriteria, timelines.clone());
+		worker_1.note_check(&story, Duration::from_micros(30));
+		worker_2.note_check(&story, Duration::from_micros(50));
+		worker_2.note_check(&RelayVRFStory([9; 32]), Duration::from_micros(70));
+
+		assert_eq!(
+			timelines.block(&block_hash).unwrap().assignment_checks,
+			AssignmentChecks { count: 2, total_micros: 80, max_micros: 50 },
+		);
+		assert_eq!(
+			timelines.recent()[0].assignment_checks,
+			AssignmentChecks { count: 2, total_micros: 80, max_micros: 50 },
+		);
+
+		// The checks of a stopped worker are gone with it.
+		drop(worker_2);
+		let worker_3 = TimedAssignmentCriteria::new(RealAssignmentCriteria, timelines.clone());
+		worker_3.note_check(&story, Duration::from_micros(10));
+		assert_eq!(timelines.0.lock().checked_assignments.len(), 2);
+		assert_eq!(
+			timelines.block(&block_hash).unwrap().assignment_checks,
+			AssignmentChecks { count: 2, total_micros: 40, max_micros: 30 },
+		);
+		drop(worker_1);
+	}
+}
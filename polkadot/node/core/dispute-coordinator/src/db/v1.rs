@@ -41,8 +41,8 @@ use crate::{
 	LOG_TARGET,
 };
 
-const RECENT_DISPUTES_KEY: &[u8; 15] = b"recent-disputes";
-const EARLIEST_SESSION_KEY: &[u8; 16] = b"earliest-session";
+pub(crate) const RECENT_DISPUTES_KEY: &[u8; 15] = b"recent-disputes";
+pub(crate) const EARLIEST_SESSION_KEY: &[u8; 16] = b"earliest-session";
 const CANDIDATE_VOTES_SUBKEY: &[u8; 15] = b"candidate-votes";
 /// Until what session have votes been cleaned up already?
 const CLEANED_VOTES_WATERMARK_KEY: &[u8; 23] = b"cleaned-votes-watermark";
@@ -188,7 +188,10 @@ impl Backend for DbBackend {
 	}
 }
 
-fn candidate_votes_key(session: SessionIndex, candidate_hash: &CandidateHash) -> [u8; 15 + 4 + 32] {
+pub(crate) fn candidate_votes_key(
+	session: SessionIndex,
+	candidate_hash: &CandidateHash,
+) -> [u8; 15 + 4 + 32] {
 	let mut buf = [0u8; 15 + 4 + 32];
 	buf[..15].copy_from_slice(CANDIDATE_VOTES_SUBKEY);
 
@@ -274,7 +277,7 @@ impl From<Error> for crate::error::Error {
 /// Result alias for DB errors.
 pub type Result<T> = std::result::Result<T, Error>;
 
-fn load_decode<D: Decode>(
+pub(crate) fn load_decode<D: Decode>(
 	db: &dyn Database,
 	col_dispute_data: u32,
 	key: &[u8],
@@ -0,0 +1,371 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Read-only inspection of the disputes stored by the dispute coordinator.
+//!
+//! Spam slots and participations only live in the memory of a running coordinator, so they are
+//! derived from the stored votes the same way the coordinator recovers them on startup. Whether a
+//! candidate was seen included or backed is not stored, hence the spam slot occupancy is an upper
+//! bound of what the coordinator enforces.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use polkadot_node_primitives::{dispute_is_inactive, DisputeStatus, Timestamp};
+use polkadot_node_subsystem_util::database::Database;
+use polkadot_primitives::{Hash, SessionIndex, ValidatorIndex};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+	db::v1::{
+		candidate_votes_key, load_decode, CandidateVotes, RecentDisputes, EARLIEST_SESSION_KEY,
+		RECENT_DISPUTES_KEY,
+	},
+	spam_slots::MAX_SPAM_VOTES,
+	Config,
+};
+
+/// Errors while inspecting the dispute coordinator database.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// The database could not be read.
+	#[error("Reading from database failed: {0}")]
+	Io(#[from] std::io::Error),
+	/// An entry of the database could not be decoded.
+	#[error("Decoding database entry failed: {0}")]
+	Codec(#[from] codec::Error),
+}
+
+impl From<crate::db::v1::Error> for Error {
+	fn from(err: crate::db::v1::Error) -> Self {
+		match err {
+			crate::db::v1::Error::Io(e) => Self::Io(e),
+			crate::db::v1::Error::Codec(e) => Self::Codec(e),
+		}
+	}
+}
+
+/// All disputes known to the dispute coordinator, grouped by session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputesInspection {
+	/// The earliest session the coordinator keeps disputes for.
+	pub earliest_session: Option<SessionIndex>,
+	/// How many unconfirmed disputes a validator may raise per session.
+	pub max_spam_votes: u32,
+	/// Sessions with at least one dispute, in ascending order.
+	pub sessions: Vec<SessionDisputes>,
+}
+
+/// The disputes of a single session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionDisputes {
+	/// The session index.
+	pub session: SessionIndex,
+	/// The index of the local validator in the session, if known.
+	pub local_validator: Option<u32>,
+	/// The disputes raised in the session.
+	pub disputes: Vec<DisputeInfo>,
+	/// Validators occupying spam slots, most occupied first.
+	pub spam_slots: Vec<SpamSlot>,
+}
+
+/// A single dispute.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DisputeInfo {
+	/// The hash of the disputed candidate.
+	pub candidate_hash: Hash,
+	/// The para of the candidate, if the votes are still stored.
+	pub para_id: Option<u32>,
+	/// The relay parent of the candidate, if the votes are still stored.
+	pub relay_parent: Option<Hash>,
+	/// The status of the dispute.
+	pub status: Status,
+	/// Whether the dispute is still considered active.
+	pub active: bool,
+	/// The number of votes for the validity of the candidate.
+	pub valid_votes: u32,
+	/// The number of votes against the validity of the candidate.
+	pub invalid_votes: u32,
+	/// The vote of the local validator, `true` if it voted for the validity of the candidate.
+	pub local_vote: Option<bool>,
+	/// Whether the local validator participated in the dispute.
+	pub participation: ParticipationStatus,
+}
+
+/// The status of a dispute, see [`DisputeStatus`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Status {
+	/// The dispute is ongoing and not yet confirmed.
+	Active,
+	/// The dispute is ongoing and confirmed.
+	Confirmed,
+	/// The dispute concluded for the candidate at the given unix timestamp.
+	ConcludedFor(Timestamp),
+	/// The dispute concluded against the candidate at the given unix timestamp.
+	ConcludedAgainst(Timestamp),
+}
+
+impl From<DisputeStatus> for Status {
+	fn from(status: DisputeStatus) -> Self {
+		match status {
+			DisputeStatus::Active => Self::Active,
+			DisputeStatus::Confirmed => Self::Confirmed,
+			DisputeStatus::ConcludedFor(at) => Self::ConcludedFor(at),
+			DisputeStatus::ConcludedAgainst(at) => Self::ConcludedAgainst(at),
+		}
+	}
+}
+
+/// The participation of the local validator in a dispute.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ParticipationStatus {
+	/// The local validator voted.
+	Voted,
+	/// The dispute is no longer active, the coordinator won't participate anymore.
+	Inactive,
+	/// The dispute is not confirmed, participation is deferred as it might be spam.
+	Unconfirmed,
+	/// The local validator has yet to participate.
+	Pending,
+	/// The local validator is not known for the session.
+	Unknown,
+}
+
+/// The spam slots occupied by a validator in a session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SpamSlot {
+	/// The validator index.
+	pub validator: u32,
+	/// The number of unconfirmed disputes the validator voted in.
+	pub unconfirmed_disputes: u32,
+}
+
+/// Inspect all disputes in the database of the dispute coordinator.
+///
+/// `now` is the unix timestamp to determine whether concluded disputes are still active and
+/// `local_validator` returns the index of the local validator in a session, if any.
+pub fn inspect_disputes(
+	db: &dyn Database,
+	config: &Config,
+	now: Timestamp,
+	mut local_validator: impl FnMut(SessionIndex) -> Option<ValidatorIndex>,
+) -> Result<DisputesInspection, Error> {
+	let column = config.col_dispute_data;
+	let earliest_session = load_decode(db, column, EARLIEST_SESSION_KEY)?;
+	let recent_disputes: RecentDisputes =
+		load_decode(db, column, RECENT_DISPUTES_KEY)?.unwrap_or_default();
+
+	let mut by_session = BTreeMap::<SessionIndex, Vec<_>>::new();
+	for ((session, candidate_hash), status) in recent_disputes {
+		by_session.entry(session).or_default().push((candidate_hash, status));
+	}
+
+	let mut sessions = Vec::with_capacity(by_session.len());
+	for (session, disputes) in by_session {
+		let local = local_validator(session);
+		let mut spam_slots = BTreeMap::<ValidatorIndex, u32>::new();
+		let mut infos = Vec::with_capacity(disputes.len());
+
+		for (candidate_hash, status) in disputes {
+			let votes: Option<CandidateVotes> =
+				load_decode(db, column, &candidate_votes_key(session, &candidate_hash))?;
+			let active = !dispute_is_inactive(&status, &now);
+			let unconfirmed = !status.is_confirmed_concluded();
+
+			let (valid, invalid): (BTreeSet<ValidatorIndex>, BTreeSet<ValidatorIndex>) = votes
+				.as_ref()
+				.map(|votes| {
+					(
+						votes.valid.iter().map(|(_, index, _)| *index).collect(),
+						votes.invalid.iter().map(|(_, index, _)| *index).collect(),
+					)
+				})
+				.unwrap_or_default();
+
+			// Mirrors the recovery of the spam slots on startup, which only considers active
+			// disputes.
+			if active && unconfirmed {
+				for validator in valid.union(&invalid) {
+					*spam_slots.entry(*validator).or_default() += 1;
+				}
+			}
+
+			let local_vote = local.and_then(|index| {
+				if valid.contains(&index) {
+					Some(true)
+				} else if invalid.contains(&index) {
+					Some(false)
+				} else {
+					None
+				}
+			});
+			let participation = if local_vote.is_some() {
+				ParticipationStatus::Voted
+			} else if !active {
+				ParticipationStatus::Inactive
+			} else if unconfirmed {
+				ParticipationStatus::Unconfirmed
+			} else if local.is_some() {
+				ParticipationStatus::Pending
+			} else {
+				ParticipationStatus::Unknown
+			};
+
+			infos.push(DisputeInfo {
+				candidate_hash: candidate_hash.0,
+				para_id: votes.as_ref().map(|v| v.candidate_receipt.descriptor.para_id().into()),
+				relay_parent: votes.as_ref().map(|v| v.candidate_receipt.descriptor.relay_parent()),
+				status: status.into(),
+				active,
+				valid_votes: valid.len() as u32,
+				invalid_votes: invalid.len() as u32,
+				local_vote,
+				participation,
+			});
+		}
+
+		let mut spam_slots: Vec<_> = spam_slots
+			.into_iter()
+			.map(|(validator, unconfirmed_disputes)| SpamSlot {
+				validator: validator.0,
+				unconfirmed_disputes,
+			})
+			.collect();
+		spam_slots.sort_by(|a, b| b.unconfirmed_disputes.cmp(&a.unconfirmed_disputes));
+
+		sessions.push(SessionDisputes {
+			session,
+			local_validator: local.map(|index| index.0),
+			disputes: infos,
+			spam_slots,
+		});
+	}
+
+	Ok(DisputesInspection { earliest_session, max_spam_votes: MAX_SPAM_VOTES, sessions })
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		backend::{Backend, OverlayedBackend},
+		db::v1::{ColumnConfiguration, DbBackend},
+		metrics::Metrics,
+	};
+	use polkadot_node_primitives::ACTIVE_DURATION_SECS;
+	use polkadot_primitives::{
+		CandidateHash, InvalidDisputeStatementKind, ValidDisputeStatementKind,
+	};
+	use polkadot_primitives_test_helpers::{
+		dummy_candidate_receipt_v2, dummy_hash, dummy_signature,
+	};
+	use std::sync::Arc;
+
+	fn votes(valid: &[u32], invalid: &[u32]) -> CandidateVotes {
+		CandidateVotes {
+			candidate_receipt: dummy_candidate_receipt_v2(dummy_hash()),
+			valid: valid
+				.iter()
+				.map(|i| {
+					(ValidDisputeStatementKind::Explicit, ValidatorIndex(*i), dummy_signature())
+				})
+				.collect(),
+			invalid: invalid
+				.iter()
+				.map(|i| {
+					(InvalidDisputeStatementKind::Explicit, ValidatorIndex(*i), dummy_signature())
+				})
+				.collect(),
+		}
+	}
+
+	#[test]
+	fn inspects_stored_disputes() {
+		let db = kvdb_memorydb::create(1);
+		let db = polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(db, &[0]);
+		let store = Arc::new(db);
+		let mut backend = DbBackend::new(
+			store.clone(),
+			ColumnConfiguration { col_dispute_data: 0 },
+			Metrics::default(),
+		);
+
+		let unconfirmed = CandidateHash(Hash::repeat_byte(1));
+		let confirmed = CandidateHash(Hash::repeat_byte(2));
+		let concluded = CandidateHash(Hash::repeat_byte(3));
+		let now = 1_000;
+
+		let mut overlay_db = OverlayedBackend::new(&backend);
+		overlay_db.write_earliest_session(1);
+		overlay_db.write_recent_disputes(
+			vec![
+				((1, unconfirmed), DisputeStatus::Active),
+				((1, confirmed), DisputeStatus::Confirmed),
+				((2, concluded), DisputeStatus::ConcludedAgainst(now - ACTIVE_DURATION_SECS - 1)),
+			]
+			.into_iter()
+			.collect(),
+		);
+		overlay_db.write_candidate_votes(1, unconfirmed, votes(&[0], &[1]));
+		overlay_db.write_candidate_votes(1, confirmed, votes(&[0, 2], &[1, 3]));
+		overlay_db.write_candidate_votes(2, concluded, votes(&[], &[0, 1, 2]));
+		let write_ops = overlay_db.into_write_ops();
+		backend.write(write_ops).unwrap();
+
+		let inspection =
+			inspect_disputes(&*store, &Config { col_dispute_data: 0 }, now, |session| {
+				(session == 1).then_some(ValidatorIndex(2))
+			})
+			.unwrap();
+
+		assert_eq!(inspection.earliest_session, Some(1));
+		assert_eq!(inspection.sessions.len(), 2);
+
+		let first = &inspection.sessions[0];
+		assert_eq!(first.local_validator, Some(2));
+		let participation: Vec<_> = first
+			.disputes
+			.iter()
+			.map(|d| (d.candidate_hash, d.valid_votes, d.invalid_votes, d.participation))
+			.collect();
+		assert_eq!(
+			participation,
+			vec![
+				(unconfirmed.0, 1, 1, ParticipationStatus::Unconfirmed),
+				(confirmed.0, 2, 2, ParticipationStatus::Voted),
+			]
+		);
+		assert_eq!(first.disputes[1].local_vote, Some(true));
+		assert_eq!(
+			first.spam_slots,
+			vec![
+				SpamSlot { validator: 0, unconfirmed_disputes: 1 },
+				SpamSlot { validator: 1, unconfirmed_disputes: 1 },
+			]
+		);
+
+		let second = &inspection.sessions[1];
+		assert_eq!(second.local_validator, None);
+		assert!(!second.disputes[0].active);
+		assert_eq!(second.disputes[0].participation, ParticipationStatus::Inactive);
+		assert!(second.spam_slots.is_empty());
+	}
+}
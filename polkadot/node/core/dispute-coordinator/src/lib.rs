@@ -109,6 +109,9 @@ mod metrics;
 /// Status tracking of disputes (`DisputeStatus`).
 mod status;
 
+/// Read-only inspection of the stored disputes, for operators.
+pub mod inspect;
+
 use crate::status::Clock;
 
 #[cfg(test)]
@@ -21,7 +21,7 @@ use polkadot_primitives::{CandidateHash, SessionIndex, ValidatorIndex};
 use crate::LOG_TARGET;
 
 /// Type used for counting potential spam votes.
-type SpamCount = u32;
+pub(crate) type SpamCount = u32;
 
 /// How many unconfirmed disputes a validator is allowed to import (per session).
 ///
@@ -38,9 +38,9 @@ type SpamCount = u32;
 /// candidate would not have been available in the first place and could not have been included.)
 /// So this is really just a fallback mechanism if things go terribly wrong.
 #[cfg(not(test))]
-const MAX_SPAM_VOTES: SpamCount = 50;
+pub(crate) const MAX_SPAM_VOTES: SpamCount = 50;
 #[cfg(test)]
-const MAX_SPAM_VOTES: SpamCount = 1;
+pub(crate) const MAX_SPAM_VOTES: SpamCount = 1;
 
 /// Spam slots for raised disputes concerning unknown candidates.
 pub struct SpamSlots {
@@ -33,6 +33,8 @@ pub struct PrepareSuccess {
 	pub path: PathBuf,
 	/// Size in bytes
 	pub size: u64,
+	/// Checksum of the compiled artifact.
+	pub checksum: String,
 	/// Stats of the current preparation run.
 	pub stats: PrepareStats,
 }
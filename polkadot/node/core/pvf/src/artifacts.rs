@@ -18,7 +18,10 @@
 //!
 //! # Lifecycle of an artifact
 //!
-//! 1. During node start-up, we prune all the cached artifacts, if any.
+//! 1. During node start-up, we restore the artifacts that were prepared by a previous run from the
+//!    manifest in the cache directory (see [`Artifacts::new`]). An artifact is only restored if it
+//!    was prepared by the same node version and its checksum still matches, all other cached
+//!    artifacts are pruned.
 //!
 //! 2. In order to be executed, a PVF should be prepared first. This means that artifacts should
 //!    have an [`ArtifactState::Prepared`] entry for that artifact in the table. If not, the
@@ -53,9 +56,13 @@
 //! 7. There is a separate process for pruning the prepared artifacts whose `last_time_needed` is
 //!    older by a predefined parameter. This process is run very rarely (say, once a day). Once the
 //!    artifact is expired it is removed from disk eagerly atomically.
+//!
+//! 8. Whenever an artifact is prepared or removed, and on every pruning, the host writes the
+//!    prepared artifacts to the manifest, so that they survive a restart of the node.
 
-use crate::{host::PrecheckResultSender, worker_interface::WORKER_DIR_PREFIX};
+use crate::{host::PrecheckResultSender, worker_interface::WORKER_DIR_PREFIX, LOG_TARGET};
 use always_assert::always;
+use codec::{Decode, Encode};
 use polkadot_node_core_pvf_common::{error::PrepareError, pvf::PvfPrepData};
 use polkadot_parachain_primitives::primitives::ValidationCodeHash;
 use polkadot_primitives::ExecutorParamsPrepHash;
@@ -63,7 +70,7 @@ use std::{
 	collections::HashMap,
 	fs,
 	path::{Path, PathBuf},
-	time::{Duration, SystemTime},
+	time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
 /// The extension to use for cached artifacts.
@@ -72,6 +79,17 @@ const ARTIFACT_EXTENSION: &str = "pvf";
 /// The prefix that artifacts used to start with under the old naming scheme.
 const ARTIFACT_OLD_PREFIX: &str = "wasmtime_";
 
+/// The name of the file that lists the prepared artifacts across restarts.
+const MANIFEST_FILE_NAME: &str = "artifacts.manifest";
+
+/// The version of the manifest format. Manifests of other versions are discarded.
+const MANIFEST_VERSION: u32 = 1;
+
+/// Compute the checksum of an artifact, the same way the prepare worker does.
+pub fn compute_checksum(artifact: &[u8]) -> String {
+	blake3::hash(artifact).to_hex().to_string()
+}
+
 pub fn generate_artifact_path(cache_path: &Path) -> PathBuf {
 	let file_name = {
 		use array_bytes::Hex;
@@ -144,6 +162,8 @@ pub enum ArtifactState {
 		last_time_needed: SystemTime,
 		/// Size in bytes
 		size: u64,
+		/// The checksum of the compiled artifact, see [`compute_checksum`].
+		checksum: String,
 	},
 	/// A task to prepare this artifact is scheduled.
 	Preparing {
@@ -167,6 +187,37 @@ pub enum ArtifactState {
 /// A container of all known artifact ids and their states.
 pub struct Artifacts {
 	inner: HashMap<ArtifactId, ArtifactState>,
+	/// The cache directory which holds the manifest. `None` if the artifacts are not persisted.
+	cache_path: Option<PathBuf>,
+	/// The version of the node that prepares the artifacts.
+	node_version: Option<String>,
+	/// The encoded manifest that was written last, to skip writing it again unchanged.
+	persisted_manifest: Option<Vec<u8>>,
+}
+
+/// The on-disk list of prepared artifacts, stored in [`MANIFEST_FILE_NAME`].
+///
+/// Compiled artifacts depend on the wasmtime version and the host environment the node was built
+/// with, so they are only reused by the exact same node version. The node version also pins the
+/// wasmtime version, since the workers must have the same version as the node.
+#[derive(Debug, Encode, Decode)]
+struct Manifest {
+	version: u32,
+	node_version: String,
+	artifacts: Vec<ManifestEntry>,
+}
+
+/// A prepared artifact in the [`Manifest`].
+#[derive(Debug, Encode, Decode)]
+struct ManifestEntry {
+	code_hash: ValidationCodeHash,
+	executor_params_prep_hash: ExecutorParamsPrepHash,
+	/// The name of the artifact file in the cache directory.
+	file_name: String,
+	checksum: String,
+	size: u64,
+	/// Seconds since the UNIX epoch.
+	last_time_needed: u64,
 }
 
 /// Parameters we use to cleanup artifacts
@@ -199,7 +250,12 @@ impl ArtifactsCleanupConfig {
 impl Artifacts {
 	#[cfg(test)]
 	pub(crate) fn empty() -> Self {
-		Self { inner: HashMap::new() }
+		Self {
+			inner: HashMap::new(),
+			cache_path: None,
+			node_version: None,
+			persisted_manifest: None,
+		}
 	}
 
 	#[cfg(test)]
@@ -212,11 +268,52 @@ impl Artifacts {
 		self.inner.keys().cloned().collect()
 	}
 
-	/// Create an empty table and the cache directory on-disk if it doesn't exist.
-	pub async fn new(cache_path: &Path) -> Self {
+	/// Create the table and the cache directory on-disk if it doesn't exist.
+	///
+	/// The table is populated with the artifacts in the manifest of a previous run, if they were
+	/// prepared by the same `node_version` and their checksums still match. Without a
+	/// `node_version` nothing is restored or persisted.
+	pub async fn new(cache_path: &Path, node_version: Option<&str>) -> Self {
 		// Make sure that the cache path directory and all its parents are created.
 		let _ = tokio::fs::create_dir_all(cache_path).await;
 
+		// Restoring reads and hashes every artifact, which must not block the executor.
+		let inner = {
+			let cache_path = cache_path.to_owned();
+			let node_version = node_version.map(ToOwned::to_owned);
+			tokio::task::spawn_blocking(move || Self::restore_and_clean(&cache_path, node_version))
+				.await
+				.unwrap_or_else(|err| {
+					gum::warn!(target: LOG_TARGET, "Failed to restore PVF artifacts: {}", err);
+					HashMap::new()
+				})
+		};
+
+		Self {
+			inner,
+			cache_path: Some(cache_path.to_owned()),
+			node_version: node_version.map(ToOwned::to_owned),
+			persisted_manifest: None,
+		}
+	}
+
+	/// Restore the artifacts of a previous run and remove everything else from the cache.
+	fn restore_and_clean(
+		cache_path: &Path,
+		node_version: Option<String>,
+	) -> HashMap<ArtifactId, ArtifactState> {
+		let inner = match node_version {
+			Some(node_version) => Self::restore(cache_path, &node_version),
+			None => HashMap::new(),
+		};
+		let restored = inner
+			.values()
+			.filter_map(|state| match state {
+				ArtifactState::Prepared { path, .. } => Some(path.as_path()),
+				_ => None,
+			})
+			.collect::<Vec<_>>();
+
 		// Delete any leftover artifacts and worker dirs from previous runs. We don't delete the
 		// entire cache directory in case the user made a mistake and set it to e.g. their home
 		// directory. This is a best-effort to do clean-up, so ignore any errors.
@@ -225,14 +322,147 @@ impl Artifacts {
 			let Some(file_name) = path.file_name().and_then(|f| f.to_str()) else { continue };
 			if path.is_dir() && file_name.starts_with(WORKER_DIR_PREFIX) {
 				let _ = fs::remove_dir_all(path);
-			} else if path.extension().map_or(false, |ext| ext == ARTIFACT_EXTENSION) ||
-				file_name.starts_with(ARTIFACT_OLD_PREFIX)
+			} else if (path.extension().map_or(false, |ext| ext == ARTIFACT_EXTENSION) ||
+				file_name.starts_with(ARTIFACT_OLD_PREFIX)) &&
+				!restored.contains(&path.as_path())
 			{
 				let _ = fs::remove_file(path);
 			}
 		}
 
-		Self { inner: HashMap::new() }
+		gum::info!(
+			target: LOG_TARGET,
+			"Restored {} prepared PVF artifacts from a previous run",
+			restored.len(),
+		);
+
+		inner
+	}
+
+	/// Read the manifest in `cache_path` and return the artifacts that can be reused.
+	fn restore(cache_path: &Path, node_version: &str) -> HashMap<ArtifactId, ArtifactState> {
+		let mut inner = HashMap::new();
+
+		let manifest = match fs::read(cache_path.join(MANIFEST_FILE_NAME)) {
+			Ok(encoded) => match Manifest::decode(&mut &encoded[..]) {
+				Ok(manifest) => manifest,
+				Err(err) => {
+					gum::warn!(target: LOG_TARGET, "Discarding corrupted artifact manifest: {}", err);
+					return inner
+				},
+			},
+			Err(err) => {
+				gum::debug!(target: LOG_TARGET, "No artifact manifest to restore: {}", err);
+				return inner
+			},
+		};
+		if manifest.version != MANIFEST_VERSION || manifest.node_version != node_version {
+			gum::info!(
+				target: LOG_TARGET,
+				manifest_version = manifest.version,
+				manifest_node_version = %manifest.node_version,
+				%node_version,
+				"Discarding PVF artifacts of an incompatible node version",
+			);
+			return inner
+		}
+
+		for entry in manifest.artifacts {
+			let artifact_id = ArtifactId::new(entry.code_hash, entry.executor_params_prep_hash);
+			let path = cache_path.join(&entry.file_name);
+			// Only plain file names of artifacts are accepted, the manifest must not point
+			// anywhere outside the cache.
+			if path.parent() != Some(cache_path) ||
+				path.extension().map_or(true, |ext| ext != ARTIFACT_EXTENSION)
+			{
+				gum::warn!(target: LOG_TARGET, ?artifact_id, "Invalid artifact file name in manifest");
+				continue
+			}
+
+			match fs::read(&path) {
+				Ok(artifact) if compute_checksum(&artifact) == entry.checksum => {
+					let last_time_needed = UNIX_EPOCH + Duration::from_secs(entry.last_time_needed);
+					inner.insert(
+						artifact_id,
+						ArtifactState::Prepared {
+							path,
+							last_time_needed,
+							size: entry.size,
+							checksum: entry.checksum,
+						},
+					);
+				},
+				Ok(_) => gum::warn!(
+					target: LOG_TARGET,
+					?artifact_id,
+					"Checksum mismatch, pruning corrupted artifact {}",
+					path.display(),
+				),
+				Err(err) => gum::warn!(
+					target: LOG_TARGET,
+					?artifact_id,
+					"Failed to read artifact {}: {}",
+					path.display(),
+					err,
+				),
+			}
+		}
+
+		inner
+	}
+
+	/// Write the prepared artifacts to the manifest, so that they can be restored after a restart.
+	///
+	/// Nothing is written if the manifest didn't change since the last call. This is a best-effort
+	/// operation, errors are only logged. The manifest is replaced atomically, so it is never left
+	/// half-written.
+	pub async fn persist(&mut self) {
+		let (Some(cache_path), Some(node_version)) = (&self.cache_path, &self.node_version) else {
+			return
+		};
+
+		let mut artifacts: Vec<_> = self
+			.inner
+			.iter()
+			.filter_map(|(artifact_id, state)| match state {
+				ArtifactState::Prepared { path, last_time_needed, size, checksum } =>
+					Some(ManifestEntry {
+						code_hash: artifact_id.code_hash,
+						executor_params_prep_hash: artifact_id.executor_params_prep_hash,
+						file_name: path.file_name()?.to_str()?.to_owned(),
+						checksum: checksum.clone(),
+						size: *size,
+						last_time_needed: last_time_needed
+							.duration_since(UNIX_EPOCH)
+							.map_or(0, |d| d.as_secs()),
+					}),
+				_ => None,
+			})
+			.collect();
+		// Sorted, so that an unchanged set of artifacts encodes the same.
+		artifacts.sort_by(|a, b| a.file_name.cmp(&b.file_name));
+		let manifest =
+			Manifest { version: MANIFEST_VERSION, node_version: node_version.clone(), artifacts }
+				.encode();
+		if self.persisted_manifest.as_ref() == Some(&manifest) {
+			return
+		}
+
+		let manifest_path = cache_path.join(MANIFEST_FILE_NAME);
+		let tmp_path = manifest_path.with_extension("tmp");
+		let result = match tokio::fs::write(&tmp_path, &manifest).await {
+			Ok(()) => tokio::fs::rename(&tmp_path, &manifest_path).await,
+			Err(err) => Err(err),
+		};
+		match result {
+			Ok(()) => self.persisted_manifest = Some(manifest),
+			Err(err) => gum::warn!(
+				target: LOG_TARGET,
+				"Failed to write the artifact manifest {}: {}",
+				manifest_path.display(),
+				err,
+			),
+		}
 	}
 
 	/// Returns the state of the given artifact by its ID.
@@ -271,7 +501,10 @@ impl Artifacts {
 		// See the precondition.
 		always!(self
 			.inner
-			.insert(artifact_id, ArtifactState::Prepared { path, last_time_needed, size })
+			.insert(
+				artifact_id,
+				ArtifactState::Prepared { path, last_time_needed, size, checksum: String::new() }
+			)
 			.is_none());
 	}
 
@@ -344,7 +577,7 @@ mod tests {
 		fs::write(cache_path.join("polkadot_..."), "test").unwrap();
 		fs::create_dir(cache_path.join("worker-prepare-test")).unwrap();
 
-		let artifacts = Artifacts::new(cache_path).await;
+		let artifacts = Artifacts::new(cache_path, None).await;
 
 		let entries: Vec<String> = fs::read_dir(&cache_path)
 			.unwrap()
@@ -357,6 +590,102 @@ mod tests {
 		assert_eq!(artifacts.len(), 0);
 	}
 
+	#[tokio::test]
+	async fn prepared_artifacts_are_restored_on_startup() {
+		let tempdir = tempfile::tempdir().unwrap();
+		let cache_path = tempdir.path();
+
+		let path = generate_artifact_path(cache_path);
+		fs::write(&path, "artifact").unwrap();
+		let last_time_needed = UNIX_EPOCH + Duration::from_secs(1_000_000);
+
+		let mut artifacts = Artifacts::new(cache_path, Some("v1")).await;
+		artifacts.inner.insert(
+			artifact_id(1),
+			ArtifactState::Prepared {
+				path: path.clone(),
+				last_time_needed,
+				size: 8,
+				checksum: compute_checksum(b"artifact"),
+			},
+		);
+		artifacts.persist().await;
+		// Not in the manifest, should be cleared.
+		let stray = generate_artifact_path(cache_path);
+		fs::write(&stray, "stray").unwrap();
+
+		let artifacts = Artifacts::new(cache_path, Some("v1")).await;
+		assert_eq!(artifacts.artifact_ids(), vec![artifact_id(1)]);
+		assert_matches::assert_matches!(
+			artifacts.inner.get(&artifact_id(1)),
+			Some(ArtifactState::Prepared { path: p, last_time_needed: t, size: 8, .. })
+				if *p == path && *t == last_time_needed
+		);
+		assert!(path.exists());
+		assert!(!stray.exists());
+
+		// Artifacts of a different node version are pruned.
+		let artifacts = Artifacts::new(cache_path, Some("v2")).await;
+		assert_eq!(artifacts.len(), 0);
+		assert!(!path.exists());
+	}
+
+	#[tokio::test]
+	async fn unchanged_manifest_is_not_written_again() {
+		let tempdir = tempfile::tempdir().unwrap();
+		let cache_path = tempdir.path();
+		let manifest_path = cache_path.join(MANIFEST_FILE_NAME);
+
+		let path = generate_artifact_path(cache_path);
+		fs::write(&path, "artifact").unwrap();
+
+		let mut artifacts = Artifacts::new(cache_path, Some("v1")).await;
+		artifacts.persist().await;
+		assert!(manifest_path.exists());
+
+		fs::remove_file(&manifest_path).unwrap();
+		artifacts.persist().await;
+		assert!(!manifest_path.exists());
+
+		artifacts.inner.insert(
+			artifact_id(1),
+			ArtifactState::Prepared {
+				path,
+				last_time_needed: SystemTime::now(),
+				size: 8,
+				checksum: compute_checksum(b"artifact"),
+			},
+		);
+		artifacts.persist().await;
+		assert!(manifest_path.exists());
+	}
+
+	#[tokio::test]
+	async fn corrupted_artifacts_are_pruned_on_startup() {
+		let tempdir = tempfile::tempdir().unwrap();
+		let cache_path = tempdir.path();
+
+		let path = generate_artifact_path(cache_path);
+		fs::write(&path, "artifact").unwrap();
+
+		let mut artifacts = Artifacts::new(cache_path, Some("v1")).await;
+		artifacts.inner.insert(
+			artifact_id(1),
+			ArtifactState::Prepared {
+				path: path.clone(),
+				last_time_needed: SystemTime::now(),
+				size: 8,
+				checksum: compute_checksum(b"artifact"),
+			},
+		);
+		artifacts.persist().await;
+		fs::write(&path, "tampered").unwrap();
+
+		let artifacts = Artifacts::new(cache_path, Some("v1")).await;
+		assert_eq!(artifacts.len(), 0);
+		assert!(!path.exists());
+	}
+
 	#[tokio::test]
 	async fn test_pruned_by_cache_size() {
 		let mock_now = SystemTime::now();
@@ -370,7 +699,7 @@ mod tests {
 		let artifact_id2 = artifact_id(2);
 		let artifact_id3 = artifact_id(3);
 
-		let mut artifacts = Artifacts::new(cache_path).await;
+		let mut artifacts = Artifacts::new(cache_path, None).await;
 		let cleanup_config = ArtifactsCleanupConfig::new(1500, Duration::from_secs(0));
 
 		artifacts.insert_prepared(
@@ -415,7 +744,7 @@ mod tests {
 		let artifact_id2 = artifact_id(2);
 		let artifact_id3 = artifact_id(3);
 
-		let mut artifacts = Artifacts::new(cache_path).await;
+		let mut artifacts = Artifacts::new(cache_path, None).await;
 		let cleanup_config = ArtifactsCleanupConfig::new(1500, Duration::from_secs(12));
 
 		artifacts.insert_prepared(
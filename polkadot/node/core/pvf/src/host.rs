@@ -68,6 +68,10 @@ pub const EXECUTE_BINARY_NAME: &str = "polkadot-execute-worker";
 /// The size of incoming message queue
 pub const HOST_MESSAGE_QUEUE_SIZE: usize = 10;
 
+/// How often the prepared artifacts are persisted, if they changed. They are also persisted when
+/// the host shuts down.
+const ARTIFACTS_PERSIST_INTERVAL: Duration = Duration::from_secs(60);
+
 /// An alias to not spell the type for the oneshot sender for the PVF execution result.
 pub(crate) type ResultSender = oneshot::Sender<Result<ValidationResult, ValidationError>>;
 
@@ -250,7 +254,7 @@ pub async fn start(
 	gum::debug!(target: LOG_TARGET, ?config, "starting PVF validation host");
 
 	// Make sure the cache is initialized before doing anything else.
-	let artifacts = Artifacts::new(&config.cache_path).await;
+	let artifacts = Artifacts::new(&config.cache_path, config.node_version.as_deref()).await;
 
 	// Run checks for supported security features once per host startup. If some checks fail, warn
 	// if Secure Validator Mode is disabled and return an error otherwise.
@@ -414,6 +418,8 @@ async fn run(
 
 	let cleanup_pulse = pulse_every(cleanup_pulse_interval).fuse();
 	futures::pin_mut!(cleanup_pulse);
+	let persist_pulse = pulse_every(ARTIFACTS_PERSIST_INTERVAL).fuse();
+	futures::pin_mut!(persist_pulse);
 
 	let mut to_host_rx = to_host_rx.fuse();
 	let mut from_prepare_queue_rx = from_prepare_queue_rx.fuse();
@@ -444,6 +450,10 @@ async fn run(
 					&cleanup_config,
 				).await);
 			},
+			() = persist_pulse.select_next_some() => {
+				// Only written if the artifacts changed since the last pulse.
+				artifacts.persist().await;
+			},
 			to_host = to_host_rx.next() => {
 				let to_host = match to_host {
 					None => {
@@ -486,6 +496,8 @@ async fn run(
 			},
 		}
 	}
+
+	artifacts.persist().await;
 }
 
 async fn handle_to_host(
@@ -853,8 +865,8 @@ async fn handle_prepare_done(
 	}
 
 	*state = match result {
-		Ok(PrepareSuccess { path, size, .. }) =>
-			ArtifactState::Prepared { path, last_time_needed: SystemTime::now(), size },
+		Ok(PrepareSuccess { path, size, checksum, .. }) =>
+			ArtifactState::Prepared { path, last_time_needed: SystemTime::now(), size, checksum },
 		Err(error) => {
 			let last_time_failed = SystemTime::now();
 			let num_failures = *num_failures + 1;
@@ -42,6 +42,12 @@ enum NemesisVariant {
 	DisputeFinalizedCandidates(DisputeFinalizedCandidatesOptions),
 	/// Spam many request statements instead of sending a single one.
 	SpamStatementRequests(SpamStatementRequestsOptions),
+	/// Withhold erasure chunks and available data after backing.
+	WithholdAvailability(WithholdAvailabilityOptions),
+	/// Never approve the candidates assigned for approval checking.
+	ApprovalNoShow(ApprovalNoShowOptions),
+	/// Sign bitfields claiming every chunk pending availability is available.
+	BogusBitfields(BogusBitfieldsOptions),
 }
 
 #[derive(Debug, Parser)]
@@ -105,6 +111,21 @@ impl MalusCli {
 
 				polkadot_cli::run_node(cli, SpamStatementRequests { spam_factor }, finality_delay)?
 			},
+			NemesisVariant::WithholdAvailability(opts) => {
+				let WithholdAvailabilityOptions { percentage, cli } = opts;
+
+				polkadot_cli::run_node(cli, WithholdAvailability { percentage }, finality_delay)?
+			},
+			NemesisVariant::ApprovalNoShow(opts) => {
+				let ApprovalNoShowOptions { percentage, cli } = opts;
+
+				polkadot_cli::run_node(cli, ApprovalNoShow { percentage }, finality_delay)?
+			},
+			NemesisVariant::BogusBitfields(opts) => {
+				let BogusBitfieldsOptions { cli } = opts;
+
+				polkadot_cli::run_node(cli, BogusBitfields, finality_delay)?
+			},
 		}
 		Ok(())
 	}
@@ -244,4 +265,56 @@ mod tests {
 			assert!(opts.cli.run.base.bob);
 		});
 	}
+
+	#[test]
+	fn percentage_works_withhold_availability() {
+		let cli = MalusCli::try_parse_from(IntoIterator::into_iter([
+			"malus",
+			"withhold-availability",
+			"--percentage",
+			"50",
+			"--bob",
+		]))
+		.unwrap();
+		assert_matches::assert_matches!(cli, MalusCli {
+			variant: NemesisVariant::WithholdAvailability(opts),
+			..
+		} => {
+			assert_eq!(opts.percentage, 50);
+			assert!(opts.cli.run.base.bob);
+		});
+	}
+
+	#[test]
+	fn approval_no_show_works() {
+		let cli = MalusCli::try_parse_from(IntoIterator::into_iter([
+			"malus",
+			"approval-no-show",
+			"--bob",
+		]))
+		.unwrap();
+		assert_matches::assert_matches!(cli, MalusCli {
+			variant: NemesisVariant::ApprovalNoShow(opts),
+			..
+		} => {
+			assert_eq!(opts.percentage, 100);
+			assert!(opts.cli.run.base.bob);
+		});
+	}
+
+	#[test]
+	fn bogus_bitfields_works() {
+		let cli = MalusCli::try_parse_from(IntoIterator::into_iter([
+			"malus",
+			"bogus-bitfields",
+			"--bob",
+		]))
+		.unwrap();
+		assert_matches::assert_matches!(cli, MalusCli {
+			variant: NemesisVariant::BogusBitfields(run),
+			..
+		} => {
+			assert!(run.cli.run.base.bob);
+		});
+	}
 }
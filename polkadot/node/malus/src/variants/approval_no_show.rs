@@ -0,0 +1,142 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A malicious node variant that never issues approvals.
+//!
+//! This malus variant triggers and distributes its approval assignments like an honest validator,
+//! but never approves the candidates it is assigned to check. The validation requests issued for
+//! approval checking are dropped, so the node becomes a no-show and the honest validators have to
+//! escalate to the next tranches.
+//!
+//! Dispute participation validates with `PvfExecKind::Dispute` and is not affected, so the node
+//! still votes honestly in disputes.
+//!
+//! Attention: For usage with `zombienet` only!
+
+#![allow(missing_docs)]
+
+use polkadot_cli::{
+	service::{
+		AuxStore, Error, ExtendedOverseerGenArgs, Overseer, OverseerConnector, OverseerGen,
+		OverseerGenArgs, OverseerHandle,
+	},
+	validator_overseer_builder, Cli,
+};
+use polkadot_node_subsystem::SpawnGlue;
+use polkadot_node_subsystem_types::{ChainApiBackend, RuntimeApiSubsystemClient};
+use polkadot_primitives::PvfExecKind;
+use sp_core::traits::SpawnNamed;
+
+// Filter wrapping related types.
+use crate::{interceptor::*, shared::MALUS};
+
+use rand::distributions::{Bernoulli, Distribution};
+use std::sync::Arc;
+
+/// Wraps around candidate validation and drops the approval checks.
+#[derive(Clone)]
+struct ApprovalSkipper {
+	distribution: Bernoulli, // The probability of not approving a candidate.
+}
+
+impl<Sender> MessageInterceptor<Sender> for ApprovalSkipper
+where
+	Sender: overseer::CandidateValidationSenderTrait + Clone + Send + 'static,
+{
+	type Message = CandidateValidationMessage;
+
+	/// Intercept `ValidateFromExhaustive` of `PvfExecKind::Approval` and drop the response sender,
+	/// the approval-voting subsystem treats this as a failed check and does not issue an approval.
+	fn intercept_incoming(
+		&self,
+		_subsystem_sender: &mut Sender,
+		msg: FromOrchestra<Self::Message>,
+	) -> Option<FromOrchestra<Self::Message>> {
+		match msg {
+			FromOrchestra::Communication {
+				msg:
+					CandidateValidationMessage::ValidateFromExhaustive {
+						ref candidate_receipt,
+						exec_kind: PvfExecKind::Approval,
+						..
+					},
+			} if self.distribution.sample(&mut rand::thread_rng()) => {
+				gum::info!(
+					target: MALUS,
+					candidate_hash = ?candidate_receipt.hash(),
+					"😈 Skipping approval check, becoming a no-show.",
+				);
+				None
+			},
+			msg => Some(msg),
+		}
+	}
+}
+
+//----------------------------------------------------------------------------------
+
+#[derive(Debug, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct ApprovalNoShowOptions {
+	/// Determines the percentage of assigned candidates that are not approved.
+	/// Defaults to 100% of the assigned candidates being no-shows.
+	#[clap(short, long, ignore_case = true, default_value_t = 100, value_parser = clap::value_parser!(u8).range(0..=100))]
+	pub percentage: u8,
+
+	#[clap(flatten)]
+	pub cli: Cli,
+}
+
+/// ApprovalNoShow implementation wrapper which implements `OverseerGen` glue.
+pub(crate) struct ApprovalNoShow {
+	/// The probability of not approving an assigned candidate.
+	pub percentage: u8,
+}
+
+impl OverseerGen for ApprovalNoShow {
+	fn generate<Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'_, Spawner, RuntimeClient>,
+		ext_args: Option<ExtendedOverseerGenArgs>,
+	) -> Result<(Overseer<SpawnGlue<Spawner>, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: RuntimeApiSubsystemClient + ChainApiBackend + AuxStore + 'static,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		gum::info!(
+			target: MALUS,
+			"😈 Started Malus node that does not approve {}% of its assigned candidates.",
+			self.percentage,
+		);
+
+		let approval_skipper = ApprovalSkipper {
+			distribution: Bernoulli::new(f64::from(self.percentage) / 100.0)
+				.expect("Invalid probability! Percentage must be in range [0..=100]."),
+		};
+
+		validator_overseer_builder(
+			args,
+			ext_args.expect("Extended arguments required to build validator overseer are provided"),
+		)?
+		.replace_candidate_validation(move |cv_subsystem| {
+			InterceptedSubsystem::new(cv_subsystem, approval_skipper)
+		})
+		.build_with_connector(connector)
+		.map_err(|e| e.into())
+	}
+}
@@ -16,20 +16,26 @@
 
 //! Collection of behavior variants.
 
+mod approval_no_show;
 mod back_garbage_candidate;
+mod bogus_bitfields;
 mod common;
 mod dispute_finalized_candidates;
 mod dispute_valid_candidates;
 mod spam_statement_requests;
 mod suggest_garbage_candidate;
 mod support_disabled;
+mod withhold_availability;
 
 pub(crate) use self::{
+	approval_no_show::{ApprovalNoShow, ApprovalNoShowOptions},
 	back_garbage_candidate::{BackGarbageCandidateOptions, BackGarbageCandidates},
+	bogus_bitfields::{BogusBitfields, BogusBitfieldsOptions},
 	dispute_finalized_candidates::{DisputeFinalizedCandidates, DisputeFinalizedCandidatesOptions},
 	dispute_valid_candidates::{DisputeAncestorOptions, DisputeValidCandidates},
 	spam_statement_requests::{SpamStatementRequests, SpamStatementRequestsOptions},
 	suggest_garbage_candidate::{SuggestGarbageCandidateOptions, SuggestGarbageCandidates},
 	support_disabled::{SupportDisabled, SupportDisabledOptions},
+	withhold_availability::{WithholdAvailability, WithholdAvailabilityOptions},
 };
 pub(crate) use common::*;
@@ -0,0 +1,154 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A malicious node variant that withholds availability data after backing.
+//!
+//! This malus variant behaves honestly in backing and stores the erasure chunks and available data
+//! like any other validator, but refuses to hand them out. Chunk requests and PoV/full data
+//! requests coming from the network are answered as if the data was never stored, so the rest of
+//! the network has to recover the candidate from the honest validators.
+//!
+//! Attention: For usage with `zombienet` only!
+
+#![allow(missing_docs)]
+
+use polkadot_cli::{
+	service::{
+		AuxStore, Error, ExtendedOverseerGenArgs, Overseer, OverseerConnector, OverseerGen,
+		OverseerGenArgs, OverseerHandle,
+	},
+	validator_overseer_builder, Cli,
+};
+use polkadot_node_subsystem::SpawnGlue;
+use polkadot_node_subsystem_types::{ChainApiBackend, RuntimeApiSubsystemClient};
+use sp_core::traits::SpawnNamed;
+
+// Filter wrapping related types.
+use crate::{interceptor::*, shared::MALUS};
+
+use rand::distributions::{Bernoulli, Distribution};
+use std::sync::Arc;
+
+/// Wraps around the availability store and hides the stored data.
+#[derive(Clone)]
+struct AvailabilityWithholder {
+	distribution: Bernoulli, // The probability of withholding a chunk or the available data.
+}
+
+impl<Sender> MessageInterceptor<Sender> for AvailabilityWithholder
+where
+	Sender: overseer::AvailabilityStoreSenderTrait + Clone + Send + 'static,
+{
+	type Message = AvailabilityStoreMessage;
+
+	/// Intercept `QueryChunk` and `QueryAvailableData` and answer them with `None`.
+	fn intercept_incoming(
+		&self,
+		_subsystem_sender: &mut Sender,
+		msg: FromOrchestra<Self::Message>,
+	) -> Option<FromOrchestra<Self::Message>> {
+		match msg {
+			FromOrchestra::Communication {
+				msg: AvailabilityStoreMessage::QueryChunk(candidate_hash, validator_index, tx),
+			} =>
+				if self.distribution.sample(&mut rand::thread_rng()) {
+					gum::info!(
+						target: MALUS,
+						?candidate_hash,
+						?validator_index,
+						"😈 Withholding erasure chunk.",
+					);
+					let _ = tx.send(None);
+					None
+				} else {
+					Some(FromOrchestra::Communication {
+						msg: AvailabilityStoreMessage::QueryChunk(
+							candidate_hash,
+							validator_index,
+							tx,
+						),
+					})
+				},
+			FromOrchestra::Communication {
+				msg: AvailabilityStoreMessage::QueryAvailableData(candidate_hash, tx),
+			} =>
+				if self.distribution.sample(&mut rand::thread_rng()) {
+					gum::info!(target: MALUS, ?candidate_hash, "😈 Withholding available data.");
+					let _ = tx.send(None);
+					None
+				} else {
+					Some(FromOrchestra::Communication {
+						msg: AvailabilityStoreMessage::QueryAvailableData(candidate_hash, tx),
+					})
+				},
+			FromOrchestra::Communication { msg } => Some(FromOrchestra::Communication { msg }),
+			FromOrchestra::Signal(signal) => Some(FromOrchestra::Signal(signal)),
+		}
+	}
+}
+
+//----------------------------------------------------------------------------------
+
+#[derive(Debug, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct WithholdAvailabilityOptions {
+	/// Determines the percentage of chunk and available data queries that are withheld.
+	/// Defaults to 100% of the queries being withheld.
+	#[clap(short, long, ignore_case = true, default_value_t = 100, value_parser = clap::value_parser!(u8).range(0..=100))]
+	pub percentage: u8,
+
+	#[clap(flatten)]
+	pub cli: Cli,
+}
+
+/// WithholdAvailability implementation wrapper which implements `OverseerGen` glue.
+pub(crate) struct WithholdAvailability {
+	/// The probability of withholding the data.
+	pub percentage: u8,
+}
+
+impl OverseerGen for WithholdAvailability {
+	fn generate<Spawner, RuntimeClient>(
+		&self,
+		connector: OverseerConnector,
+		args: OverseerGenArgs<'_, Spawner, RuntimeClient>,
+		ext_args: Option<ExtendedOverseerGenArgs>,
+	) -> Result<(Overseer<SpawnGlue<Spawner>, Arc<RuntimeClient>>, OverseerHandle), Error>
+	where
+		RuntimeClient: RuntimeApiSubsystemClient + ChainApiBackend + AuxStore + 'static,
+		Spawner: 'static + SpawnNamed + Clone + Unpin,
+	{
+		gum::info!(
+			target: MALUS,
+			"😈 Started Malus node that withholds {}% of the stored availability data.",
+			self.percentage,
+		);
+
+		let withholder = AvailabilityWithholder {
+			distribution: Bernoulli::new(f64::from(self.percentage) / 100.0)
+				.expect("Invalid probability! Percentage must be in range [0..=100]."),
+		};
+
+		validator_overseer_builder(
+			args,
+			ext_args.expect("Extended arguments required to build validator overseer are provided"),
+		)?
+		.replace_availability_store(move |av_store| InterceptedSubsystem::new(av_store, withholder))
+		.build_with_connector(connector)
+		.map_err(|e| e.into())
+	}
+}
@@ -38,6 +38,7 @@ use sc_network::ProtocolName;
 use schnellru::{ByLength, LruMap};
 use task::{
 	FetchChunks, FetchChunksParams, FetchFull, FetchFullParams, FetchSystematicChunks,
+	FetchSystematicChunksParallel, FetchSystematicChunksParallelParams,
 	FetchSystematicChunksParams,
 };
 
@@ -102,6 +103,11 @@ pub enum RecoveryStrategyKind {
 	/// We try the backing group first if PoV size is lower than specified, then fallback to
 	/// systematic chunks. Regular chunk recovery as a last resort.
 	BackersFirstIfSizeLowerThenSystematicChunks(usize),
+	/// We try the backing group first if PoV size is lower than specified, then fallback to
+	/// fetching systematic chunks from validators and backers in parallel. Regular chunk recovery
+	/// as a last resort. Not the validator default until it is measured on a real network.
+	#[allow(dead_code)]
+	BackersFirstIfSizeLowerThenParallelSystematicChunks(usize),
 
 	/// The following variants are only helpful for integration tests.
 	///
@@ -117,6 +123,10 @@ pub enum RecoveryStrategyKind {
 	/// Always recover using systematic chunks, fall back to regular chunks.
 	#[allow(dead_code)]
 	SystematicChunks,
+	/// Always recover using systematic chunks fetched from validators and backers in parallel,
+	/// fall back to regular chunks.
+	#[allow(dead_code)]
+	ParallelSystematicChunks,
 }
 
 /// The Availability Recovery Subsystem.
@@ -421,6 +431,9 @@ async fn handle_recover<Context>(
 						RecoveryStrategyKind::BackersFirstIfSizeLower(fetch_chunks_threshold) |
 						RecoveryStrategyKind::BackersFirstIfSizeLowerThenSystematicChunks(
 							fetch_chunks_threshold,
+						) |
+						RecoveryStrategyKind::BackersFirstIfSizeLowerThenParallelSystematicChunks(
+							fetch_chunks_threshold,
 						) => {
 							// Get our own chunk size to get an estimate of the PoV size.
 							let chunk_size: Result<Option<usize>> =
@@ -454,6 +467,12 @@ async fn handle_recover<Context>(
 							RecoveryStrategyKind::BackersFirstIfSizeLowerThenSystematicChunks(_),
 							true,
 						) |
+						(
+							RecoveryStrategyKind::BackersFirstIfSizeLowerThenParallelSystematicChunks(
+								_,
+							),
+							true,
+						) |
 						(RecoveryStrategyKind::BackersThenSystematicChunks, _) =>
 							recovery_strategies.push_back(Box::new(FetchFull::new(
 								FetchFullParams { validators: backing_validators.to_vec() },
@@ -481,7 +500,11 @@ async fn handle_recover<Context>(
 					recovery_strategy_kind,
 					RecoveryStrategyKind::BackersThenSystematicChunks |
 						RecoveryStrategyKind::SystematicChunks |
-						RecoveryStrategyKind::BackersFirstIfSizeLowerThenSystematicChunks(_)
+						RecoveryStrategyKind::BackersFirstIfSizeLowerThenSystematicChunks(_) |
+						RecoveryStrategyKind::ParallelSystematicChunks |
+						RecoveryStrategyKind::BackersFirstIfSizeLowerThenParallelSystematicChunks(
+							_
+						)
 				) && chunk_mapping_enabled
 				{
 					let chunk_indices =
@@ -512,12 +535,23 @@ async fn handle_recover<Context>(
 						})
 						.collect();
 
-					recovery_strategies.push_back(Box::new(FetchSystematicChunks::new(
-						FetchSystematicChunksParams {
-							validators,
-							backers: backer_group.map(|v| v.to_vec()).unwrap_or_else(|| vec![]),
-						},
-					)));
+					let backers = backer_group.map(|v| v.to_vec()).unwrap_or_else(|| vec![]);
+
+					if matches!(
+						recovery_strategy_kind,
+						RecoveryStrategyKind::ParallelSystematicChunks |
+							RecoveryStrategyKind::BackersFirstIfSizeLowerThenParallelSystematicChunks(_)
+					) {
+						recovery_strategies.push_back(Box::new(
+							FetchSystematicChunksParallel::new(
+								FetchSystematicChunksParallelParams { validators, backers },
+							),
+						));
+					} else {
+						recovery_strategies.push_back(Box::new(FetchSystematicChunks::new(
+							FetchSystematicChunksParams { validators, backers },
+						)));
+					}
 				}
 			}
 
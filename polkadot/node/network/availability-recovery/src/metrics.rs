@@ -36,6 +36,7 @@ struct MetricsInner {
 	/// Split by chunk type:
 	/// - `regular_chunks`
 	/// - `systematic_chunks`
+	/// - `systematic_chunks_parallel`
 	chunk_requests_issued: CounterVec<U64>,
 
 	/// Total number of bytes recovered
@@ -98,8 +99,9 @@ struct MetricsInner {
 
 	/// Number of full recoveries that have been finished one way or the other.
 	///
-	/// Split by recovery `strategy_type` (`full_from_backers, systematic_chunks, regular_chunks,
-	/// all`). `all` is used for failed recoveries that tried all available strategies.
+	/// Split by recovery `strategy_type` (`full_from_backers, systematic_chunks,
+	/// systematic_chunks_parallel, regular_chunks, all`). `all` is used for failed recoveries that
+	/// tried all available strategies.
 	/// Also split by `result` type.
 	full_recoveries_finished: CounterVec<U64>,
 
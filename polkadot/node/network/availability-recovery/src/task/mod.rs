@@ -22,6 +22,7 @@ mod strategy;
 
 pub use self::strategy::{
 	FetchChunks, FetchChunksParams, FetchFull, FetchFullParams, FetchSystematicChunks,
+	FetchSystematicChunksParallel, FetchSystematicChunksParallelParams,
 	FetchSystematicChunksParams, RecoveryStrategy, State,
 };
 
@@ -18,11 +18,13 @@
 
 mod chunks;
 mod full;
+mod parallel_systematic;
 mod systematic;
 
 pub use self::{
 	chunks::{FetchChunks, FetchChunksParams},
 	full::{FetchFull, FetchFullParams},
+	parallel_systematic::{FetchSystematicChunksParallel, FetchSystematicChunksParallelParams},
 	systematic::{FetchSystematicChunks, FetchSystematicChunksParams},
 };
 use crate::{
@@ -30,7 +32,7 @@ use crate::{
 };
 
 use codec::Decode;
-use futures::{channel::oneshot, SinkExt};
+use futures::{channel::oneshot, future::BoxFuture, SinkExt};
 use polkadot_erasure_coding::branch_hash;
 #[cfg(not(test))]
 use polkadot_node_network_protocol::request_response::CHUNK_REQUEST_TIMEOUT;
@@ -75,12 +77,15 @@ pub const SYSTEMATIC_CHUNKS_REQ_RETRY_LIMIT: u32 = 2;
 /// infinite retry loop.
 pub const REGULAR_CHUNKS_REQ_RETRY_LIMIT: u32 = 5;
 
-// Helpful type alias for tracking ongoing chunk requests.
-type OngoingRequests = FuturesUndead<(
+// Outcome of a single chunk request, tagged with the peer and the requested validator index.
+type ChunkRequestOutput = (
 	AuthorityDiscoveryId,
 	ValidatorIndex,
 	Result<(Option<ErasureChunk>, ProtocolName), RequestError>,
-)>;
+);
+
+// Helpful type alias for tracking ongoing chunk requests.
+type OngoingRequests = FuturesUndead<ChunkRequestOutput>;
 
 const fn is_unavailable(
 	received_chunks: usize,
@@ -120,6 +125,85 @@ fn is_chunk_valid(params: &RecoveryParams, chunk: &ErasureChunk) -> bool {
 	true
 }
 
+/// Build a request for the chunk of `validator_index`, addressed to `authority_id`.
+///
+/// Returns the request to be dispatched via the network bridge, along with the future resolving
+/// to the decoded response.
+fn make_chunk_request(
+	strategy_type: &str,
+	params: &RecoveryParams,
+	authority_id: AuthorityDiscoveryId,
+	validator_index: ValidatorIndex,
+) -> (Requests, BoxFuture<'static, ChunkRequestOutput>) {
+	let candidate_hash = params.candidate_hash;
+
+	// Request data.
+	let raw_request_v2 =
+		req_res::v2::ChunkFetchingRequest { candidate_hash, index: validator_index };
+	let raw_request_v1 = req_res::v1::ChunkFetchingRequest::from(raw_request_v2);
+
+	let (req, res) = OutgoingRequest::new_with_fallback(
+		Recipient::Authority(authority_id.clone()),
+		raw_request_v2,
+		raw_request_v1,
+	);
+	let request = Requests::ChunkFetching(req);
+
+	params.metrics.on_chunk_request_issued(strategy_type);
+	let timer = params.metrics.time_chunk_request(strategy_type);
+	let v1_protocol_name = params.req_v1_protocol_name.clone();
+	let v2_protocol_name = params.req_v2_protocol_name.clone();
+
+	let chunk_mapping_enabled = params.chunk_mapping_enabled;
+	let authority_id_clone = authority_id.clone();
+
+	let response = Box::pin(async move {
+		let _timer = timer;
+		let res = match res.await {
+			Ok((bytes, protocol)) =>
+				if v2_protocol_name == protocol {
+					match req_res::v2::ChunkFetchingResponse::decode(&mut &bytes[..]) {
+						Ok(req_res::v2::ChunkFetchingResponse::Chunk(chunk)) =>
+							Ok((Some(chunk.into()), protocol)),
+						Ok(req_res::v2::ChunkFetchingResponse::NoSuchChunk) => Ok((None, protocol)),
+						Err(e) => Err(RequestError::InvalidResponse(e)),
+					}
+				} else if v1_protocol_name == protocol {
+					// V1 protocol version must not be used when chunk mapping node
+					// feature is enabled, because we can't know the real index of the
+					// returned chunk.
+					// This case should never be reached as long as the
+					// `AvailabilityChunkMapping` feature is only enabled after the
+					// v1 version is removed. Still, log this.
+					if chunk_mapping_enabled {
+						gum::info!(
+							target: LOG_TARGET,
+							?candidate_hash,
+							authority_id = ?authority_id_clone,
+							"Another validator is responding on /req_chunk/1 protocol while the availability chunk \
+							mapping feature is enabled in the runtime. All validators must switch to /req_chunk/2."
+						);
+					}
+
+					match req_res::v1::ChunkFetchingResponse::decode(&mut &bytes[..]) {
+						Ok(req_res::v1::ChunkFetchingResponse::Chunk(chunk)) =>
+							Ok((Some(chunk.recombine_into_chunk(&raw_request_v1)), protocol)),
+						Ok(req_res::v1::ChunkFetchingResponse::NoSuchChunk) => Ok((None, protocol)),
+						Err(e) => Err(RequestError::InvalidResponse(e)),
+					}
+				} else {
+					Err(RequestError::NetworkError(RequestFailure::UnknownProtocol))
+				},
+
+			Err(e) => Err(e),
+		};
+
+		(authority_id, validator_index, res)
+	});
+
+	(request, response)
+}
+
 /// Perform the validity checks after recovery.
 async fn do_post_recovery_check(
 	params: &RecoveryParams,
@@ -359,78 +443,14 @@ impl State {
 					"Requesting chunk",
 				);
 
-				// Request data.
-				let raw_request_v2 =
-					req_res::v2::ChunkFetchingRequest { candidate_hash, index: validator_index };
-				let raw_request_v1 = req_res::v1::ChunkFetchingRequest::from(raw_request_v2);
-
-				let (req, res) = OutgoingRequest::new_with_fallback(
-					Recipient::Authority(authority_id.clone()),
-					raw_request_v2,
-					raw_request_v1,
-				);
-				requests.push(Requests::ChunkFetching(req));
-
-				params.metrics.on_chunk_request_issued(strategy_type);
-				let timer = params.metrics.time_chunk_request(strategy_type);
-				let v1_protocol_name = params.req_v1_protocol_name.clone();
-				let v2_protocol_name = params.req_v2_protocol_name.clone();
-
-				let chunk_mapping_enabled = params.chunk_mapping_enabled;
-				let authority_id_clone = authority_id.clone();
-
-				requesting_chunks.push(Box::pin(async move {
-					let _timer = timer;
-					let res = match res.await {
-						Ok((bytes, protocol)) =>
-							if v2_protocol_name == protocol {
-								match req_res::v2::ChunkFetchingResponse::decode(&mut &bytes[..]) {
-									Ok(req_res::v2::ChunkFetchingResponse::Chunk(chunk)) =>
-										Ok((Some(chunk.into()), protocol)),
-									Ok(req_res::v2::ChunkFetchingResponse::NoSuchChunk) =>
-										Ok((None, protocol)),
-									Err(e) => Err(RequestError::InvalidResponse(e)),
-								}
-							} else if v1_protocol_name == protocol {
-								// V1 protocol version must not be used when chunk mapping node
-								// feature is enabled, because we can't know the real index of the
-								// returned chunk.
-								// This case should never be reached as long as the
-								// `AvailabilityChunkMapping` feature is only enabled after the
-								// v1 version is removed. Still, log this.
-								if chunk_mapping_enabled {
-									gum::info!(
-										target: LOG_TARGET,
-										?candidate_hash,
-										authority_id = ?authority_id_clone,
-										"Another validator is responding on /req_chunk/1 protocol while the availability chunk \
-										mapping feature is enabled in the runtime. All validators must switch to /req_chunk/2."
-									);
-								}
-
-								match req_res::v1::ChunkFetchingResponse::decode(&mut &bytes[..]) {
-									Ok(req_res::v1::ChunkFetchingResponse::Chunk(chunk)) => Ok((
-										Some(chunk.recombine_into_chunk(&raw_request_v1)),
-										protocol,
-									)),
-									Ok(req_res::v1::ChunkFetchingResponse::NoSuchChunk) =>
-										Ok((None, protocol)),
-									Err(e) => Err(RequestError::InvalidResponse(e)),
-								}
-							} else {
-								Err(RequestError::NetworkError(RequestFailure::UnknownProtocol))
-							},
-
-						Err(e) => Err(e),
-					};
-
-					(authority_id, validator_index, res)
-				}));
+				let (request, response) =
+					make_chunk_request(strategy_type, params, authority_id, validator_index);
+				requests.push(request);
+				requesting_chunks.push(response);
 			} else {
 				break
 			}
 		}
-
 		if requests.len() != 0 {
 			sender
 				.send_message(NetworkBridgeTxMessage::SendRequests(
@@ -441,6 +461,127 @@ impl State {
 		}
 	}
 
+	/// Process the outcome of a single chunk request, storing the chunk if it is valid and
+	/// recording an error otherwise.
+	///
+	/// Returns `true` if the request resulted in an error.
+	fn handle_chunk_response(
+		&mut self,
+		strategy_type: &str,
+		params: &RecoveryParams,
+		authority_id: &AuthorityDiscoveryId,
+		validator_index: ValidatorIndex,
+		request_result: Result<(Option<ErasureChunk>, ProtocolName), RequestError>,
+	) -> bool {
+		let metrics = &params.metrics;
+		let mut is_error = false;
+
+		match request_result {
+			Ok((maybe_chunk, protocol)) => {
+				match protocol {
+					name if name == params.req_v1_protocol_name =>
+						params.metrics.on_chunk_response_v1(),
+					name if name == params.req_v2_protocol_name =>
+						params.metrics.on_chunk_response_v2(),
+					_ => {},
+				}
+
+				match maybe_chunk {
+					Some(chunk) =>
+						if is_chunk_valid(params, &chunk) {
+							metrics.on_chunk_request_succeeded(strategy_type);
+							gum::trace!(
+								target: LOG_TARGET,
+								candidate_hash = ?params.candidate_hash,
+								?authority_id,
+								?validator_index,
+								"Received valid chunk",
+							);
+							self.insert_chunk(
+								chunk.index,
+								Chunk { chunk: chunk.chunk, validator_index },
+							);
+						} else {
+							metrics.on_chunk_request_invalid(strategy_type);
+							// Record that we got an invalid chunk so that subsequent strategies
+							// don't try requesting this again.
+							self.record_error_fatal(authority_id.clone(), validator_index);
+							is_error = true;
+						},
+					None => {
+						metrics.on_chunk_request_no_such_chunk(strategy_type);
+						gum::trace!(
+							target: LOG_TARGET,
+							candidate_hash = ?params.candidate_hash,
+							?authority_id,
+							?validator_index,
+							"Validator did not have the chunk",
+						);
+						// Record that the validator did not have this chunk so that subsequent
+						// strategies don't try requesting this again.
+						self.record_error_fatal(authority_id.clone(), validator_index);
+						is_error = true;
+					},
+				}
+			},
+			Err(err) => {
+				gum::trace!(
+					target: LOG_TARGET,
+					candidate_hash= ?params.candidate_hash,
+					?err,
+					?authority_id,
+					?validator_index,
+					"Failure requesting chunk",
+				);
+
+				is_error = true;
+
+				match err {
+					RequestError::InvalidResponse(_) => {
+						metrics.on_chunk_request_invalid(strategy_type);
+
+						gum::debug!(
+							target: LOG_TARGET,
+							candidate_hash = ?params.candidate_hash,
+							?err,
+							?authority_id,
+							?validator_index,
+							"Chunk fetching response was invalid",
+						);
+
+						// Record that we got an invalid chunk so that this or
+						// subsequent strategies don't try requesting this again.
+						self.record_error_fatal(authority_id.clone(), validator_index);
+					},
+					RequestError::NetworkError(err) => {
+						// No debug logs on general network errors - that became very
+						// spammy occasionally.
+						if let RequestFailure::Network(OutboundFailure::Timeout) = err {
+							metrics.on_chunk_request_timeout(strategy_type);
+						} else {
+							metrics.on_chunk_request_error(strategy_type);
+						}
+
+						// Record that we got a non-fatal error so that this or
+						// subsequent strategies will retry requesting this only a
+						// limited number of times.
+						self.record_error_non_fatal(authority_id.clone(), validator_index);
+					},
+					RequestError::Canceled(_) => {
+						metrics.on_chunk_request_error(strategy_type);
+
+						// Record that we got a non-fatal error so that this or
+						// subsequent strategies will retry requesting this only a
+						// limited number of times.
+						self.record_error_non_fatal(authority_id.clone(), validator_index);
+					},
+				}
+			},
+		}
+
+		is_error
+	}
+
 	/// Wait for a sufficient amount of chunks to reconstruct according to the provided `params`.
 	async fn wait_for_chunks(
 		&mut self,
@@ -465,8 +606,6 @@ impl State {
 			usize,
 		) -> bool,
 	) -> (usize, usize) {
-		let metrics = &params.metrics;
-
 		let mut total_received_responses = 0;
 		let mut error_count = 0;
 
@@ -479,116 +618,16 @@ impl State {
 
 			let (authority_id, validator_index, request_result) = res;
 
-			let mut is_error = false;
-
-			match request_result {
-				Ok((maybe_chunk, protocol)) => {
-					match protocol {
-						name if name == params.req_v1_protocol_name =>
-							params.metrics.on_chunk_response_v1(),
-						name if name == params.req_v2_protocol_name =>
-							params.metrics.on_chunk_response_v2(),
-						_ => {},
-					}
-
-					match maybe_chunk {
-						Some(chunk) =>
-							if is_chunk_valid(params, &chunk) {
-								metrics.on_chunk_request_succeeded(strategy_type);
-								gum::trace!(
-									target: LOG_TARGET,
-									candidate_hash = ?params.candidate_hash,
-									?authority_id,
-									?validator_index,
-									"Received valid chunk",
-								);
-								self.insert_chunk(
-									chunk.index,
-									Chunk { chunk: chunk.chunk, validator_index },
-								);
-							} else {
-								metrics.on_chunk_request_invalid(strategy_type);
-								error_count += 1;
-								// Record that we got an invalid chunk so that subsequent strategies
-								// don't try requesting this again.
-								self.record_error_fatal(authority_id.clone(), validator_index);
-								is_error = true;
-							},
-						None => {
-							metrics.on_chunk_request_no_such_chunk(strategy_type);
-							gum::trace!(
-								target: LOG_TARGET,
-								candidate_hash = ?params.candidate_hash,
-								?authority_id,
-								?validator_index,
-								"Validator did not have the chunk",
-							);
-							error_count += 1;
-							// Record that the validator did not have this chunk so that subsequent
-							// strategies don't try requesting this again.
-							self.record_error_fatal(authority_id.clone(), validator_index);
-							is_error = true;
-						},
-					}
-				},
-				Err(err) => {
-					error_count += 1;
-
-					gum::trace!(
-						target: LOG_TARGET,
-						candidate_hash= ?params.candidate_hash,
-						?err,
-						?authority_id,
-						?validator_index,
-						"Failure requesting chunk",
-					);
-
-					is_error = true;
-
-					match err {
-						RequestError::InvalidResponse(_) => {
-							metrics.on_chunk_request_invalid(strategy_type);
-
-							gum::debug!(
-								target: LOG_TARGET,
-								candidate_hash = ?params.candidate_hash,
-								?err,
-								?authority_id,
-								?validator_index,
-								"Chunk fetching response was invalid",
-							);
-
-							// Record that we got an invalid chunk so that this or
-							// subsequent strategies don't try requesting this again.
-							self.record_error_fatal(authority_id.clone(), validator_index);
-						},
-						RequestError::NetworkError(err) => {
-							// No debug logs on general network errors - that became very
-							// spammy occasionally.
-							if let RequestFailure::Network(OutboundFailure::Timeout) = err {
-								metrics.on_chunk_request_timeout(strategy_type);
-							} else {
-								metrics.on_chunk_request_error(strategy_type);
-							}
-
-							// Record that we got a non-fatal error so that this or
-							// subsequent strategies will retry requesting this only a
-							// limited number of times.
-							self.record_error_non_fatal(authority_id.clone(), validator_index);
-						},
-						RequestError::Canceled(_) => {
-							metrics.on_chunk_request_error(strategy_type);
-
-							// Record that we got a non-fatal error so that this or
-							// subsequent strategies will retry requesting this only a
-							// limited number of times.
-							self.record_error_non_fatal(authority_id.clone(), validator_index);
-						},
-					}
-				},
-			}
-
+			let is_error = self.handle_chunk_response(
+				strategy_type,
+				params,
+				&authority_id,
+				validator_index,
+				request_result,
+			);
 			if is_error {
+				error_count += 1;
+
 				// First, see if we can retry the request.
 				if self.can_retry_request(&(authority_id.clone(), validator_index), retry_threshold)
 				{
@@ -0,0 +1,463 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	futures_undead::FuturesUndead,
+	task::{
+		strategy::{
+			make_chunk_request, systematic::attempt_systematic_recovery, ChunkRequestOutput,
+			N_PARALLEL, SYSTEMATIC_CHUNKS_REQ_RETRY_LIMIT, TIMEOUT_START_NEW_REQUESTS,
+		},
+		RecoveryParams, RecoveryStrategy, State,
+	},
+	LOG_TARGET,
+};
+
+use futures::FutureExt;
+use polkadot_node_network_protocol::request_response::outgoing::RequestError;
+use polkadot_node_primitives::{AvailableData, ErasureChunk};
+use polkadot_node_subsystem::{messages::NetworkBridgeTxMessage, overseer, RecoveryError};
+use polkadot_primitives::{AuthorityDiscoveryId, ChunkIndex, ValidatorIndex};
+use sc_network::{IfDisconnected, ProtocolName};
+
+use std::{
+	collections::HashMap,
+	time::{Duration, Instant},
+};
+
+/// Maximum number of chunk requests in flight to a single peer at any time.
+///
+/// Backers hold all chunks, so they can serve several of them concurrently. Other validators only
+/// hold their own chunk, so they will never have more than one request in flight.
+const MAX_IN_FLIGHT_PER_PEER: usize = 8;
+
+/// Maximum number of requests in flight for the same chunk. A second request is only issued once
+/// the first one has been outstanding for longer than `TIMEOUT_START_NEW_REQUESTS`.
+const MAX_IN_FLIGHT_PER_CHUNK: usize = 2;
+
+/// Throughput (in bytes per second) assumed for peers we have not received any chunk from yet.
+const INITIAL_THROUGHPUT_ESTIMATE: f64 = 1024.0 * 1024.0;
+
+/// Lower bound of the throughput estimate, so that failing peers are deprioritised but never
+/// excluded based on their estimate alone.
+const MIN_THROUGHPUT_ESTIMATE: f64 = 1024.0;
+
+/// Weight of the most recent sample in the exponentially weighted moving average of a peer's
+/// throughput.
+const THROUGHPUT_SMOOTHING_FACTOR: f64 = 0.5;
+
+// Outcome of a chunk request, along with the time it took to complete.
+type TimedRequests = FuturesUndead<(
+	AuthorityDiscoveryId,
+	ValidatorIndex,
+	Duration,
+	Result<(Option<ErasureChunk>, ProtocolName), RequestError>,
+)>;
+
+/// Per-peer statistics used for picking the peer to request a chunk from.
+#[derive(Default)]
+struct PeerStats {
+	/// Estimated throughput in bytes per second, if we received at least one chunk from the peer.
+	throughput: Option<f64>,
+	/// Number of requests currently in flight to the peer.
+	in_flight: usize,
+}
+
+/// Adaptive estimation of the bandwidth of the peers we are fetching chunks from.
+#[derive(Default)]
+struct ThroughputEstimator {
+	peers: HashMap<AuthorityDiscoveryId, PeerStats>,
+}
+
+impl ThroughputEstimator {
+	fn throughput(&self, peer: &AuthorityDiscoveryId) -> f64 {
+		self.peers
+			.get(peer)
+			.and_then(|stats| stats.throughput)
+			.unwrap_or(INITIAL_THROUGHPUT_ESTIMATE)
+	}
+
+	fn in_flight(&self, peer: &AuthorityDiscoveryId) -> usize {
+		self.peers.get(peer).map_or(0, |stats| stats.in_flight)
+	}
+
+	/// Estimated time (in units of chunk size) it would take the peer to serve one more chunk,
+	/// given the requests it is already serving.
+	fn expected_completion(&self, peer: &AuthorityDiscoveryId) -> f64 {
+		(self.in_flight(peer) + 1) as f64 / self.throughput(peer)
+	}
+
+	fn on_request_issued(&mut self, peer: &AuthorityDiscoveryId) {
+		self.peers.entry(peer.clone()).or_default().in_flight += 1;
+	}
+
+	fn on_request_concluded(&mut self, peer: &AuthorityDiscoveryId) {
+		if let Some(stats) = self.peers.get_mut(peer) {
+			stats.in_flight = stats.in_flight.saturating_sub(1);
+		}
+	}
+
+	fn on_chunk_received(&mut self, peer: &AuthorityDiscoveryId, bytes: usize, elapsed: Duration) {
+		let sample =
+			(bytes as f64 / elapsed.as_secs_f64().max(f64::EPSILON)).max(MIN_THROUGHPUT_ESTIMATE);
+		let stats = self.peers.entry(peer.clone()).or_default();
+		stats.throughput = Some(match stats.throughput {
+			None => sample,
+			Some(previous) =>
+				THROUGHPUT_SMOOTHING_FACTOR * sample +
+					(1.0 - THROUGHPUT_SMOOTHING_FACTOR) * previous,
+		});
+	}
+
+	fn on_request_failed(&mut self, peer: &AuthorityDiscoveryId) {
+		let throughput = self.throughput(peer);
+		self.peers.entry(peer.clone()).or_default().throughput =
+			Some((throughput / 2.0).max(MIN_THROUGHPUT_ESTIMATE));
+	}
+}
+
+/// Parameters needed for fetching systematic chunks in parallel from validators and backers.
+pub struct FetchSystematicChunksParallelParams {
+	/// Validators that hold the systematic chunks.
+	pub validators: Vec<(ChunkIndex, ValidatorIndex)>,
+	/// Validators in the backing group, which hold all chunks.
+	pub backers: Vec<ValidatorIndex>,
+}
+
+/// `RecoveryStrategy` that recovers the systematic chunks by spreading the requests over the
+/// validators holding them and the backers, which hold all of them.
+///
+/// The systematic chunks are disjoint slices of the encoded available data, so each of them can be
+/// fetched from a different peer concurrently. Every chunk is requested from the peer that is
+/// expected to serve it first, based on an adaptive estimate of the throughput of each peer and
+/// the requests already in flight to it. Chunks that fail, or that are not served within
+/// `TIMEOUT_START_NEW_REQUESTS`, are requested again from another peer.
+pub struct FetchSystematicChunksParallel {
+	/// Systematic recovery threshold.
+	threshold: usize,
+	/// Systematic chunks that were not received yet and the validators that hold them.
+	chunks: Vec<(ChunkIndex, ValidatorIndex)>,
+	/// Backers, which can serve any of the chunks.
+	backers: Vec<ValidatorIndex>,
+	/// Requests in flight for each chunk (keyed by the validator holding it) and when they were
+	/// issued.
+	in_flight: HashMap<ValidatorIndex, Vec<(AuthorityDiscoveryId, Instant)>>,
+	/// Throughput estimates of the peers.
+	estimator: ThroughputEstimator,
+	/// Collection of in-flight requests.
+	requesting_chunks: TimedRequests,
+}
+
+impl FetchSystematicChunksParallel {
+	/// Instantiate a new parallel systematic chunks strategy.
+	pub fn new(params: FetchSystematicChunksParallelParams) -> Self {
+		Self {
+			threshold: params.validators.len(),
+			chunks: params.validators,
+			backers: params.backers,
+			in_flight: HashMap::new(),
+			estimator: ThroughputEstimator::default(),
+			requesting_chunks: FuturesUndead::new(),
+		}
+	}
+
+	fn in_flight_count(&self) -> usize {
+		self.in_flight.values().map(|requests| requests.len()).sum()
+	}
+
+	/// Whether a new request should be issued for the chunk held by `validator_index`.
+	fn should_request(&self, validator_index: &ValidatorIndex, now: Instant) -> bool {
+		match self.in_flight.get(validator_index) {
+			None => true,
+			Some(requests) =>
+				requests.len() < MAX_IN_FLIGHT_PER_CHUNK &&
+					requests.iter().all(|(_, issued)| {
+						now.saturating_duration_since(*issued) >= TIMEOUT_START_NEW_REQUESTS
+					}),
+		}
+	}
+
+	/// Peers that we can still request the chunk held by `validator_index` from.
+	///
+	/// The chunk holder comes first, so that it is preferred over an equally fast backer and the
+	/// backers, which serve all the chunks, are only loaded once they are known to be faster.
+	fn peers_for_chunk<'a>(
+		&'a self,
+		state: &'a State,
+		params: &'a RecoveryParams,
+		validator_index: ValidatorIndex,
+	) -> impl Iterator<Item = AuthorityDiscoveryId> + 'a {
+		std::iter::once(validator_index)
+			.chain(self.backers.iter().copied().filter(move |backer| *backer != validator_index))
+			.map(move |v_index| params.validator_authority_keys[v_index.0 as usize].clone())
+			.filter(move |peer| {
+				state.can_retry_request(
+					&(peer.clone(), validator_index),
+					SYSTEMATIC_CHUNKS_REQ_RETRY_LIMIT,
+				)
+			})
+	}
+
+	/// Pick the peer expected to serve the chunk held by `validator_index` the fastest, among the
+	/// ones that have spare capacity and are not already serving it.
+	fn select_peer(
+		&self,
+		state: &State,
+		params: &RecoveryParams,
+		validator_index: ValidatorIndex,
+	) -> Option<AuthorityDiscoveryId> {
+		let already_requested = self.in_flight.get(&validator_index);
+
+		self.peers_for_chunk(state, params, validator_index)
+			.filter(|peer| {
+				!already_requested.is_some_and(|requests| requests.iter().any(|(p, _)| p == peer)) &&
+					self.estimator.in_flight(peer) < MAX_IN_FLIGHT_PER_PEER
+			})
+			.fold(None, |best: Option<(AuthorityDiscoveryId, f64)>, peer| {
+				let expected = self.estimator.expected_completion(&peer);
+				match best {
+					Some((_, best_expected)) if best_expected <= expected => best,
+					_ => Some((peer, expected)),
+				}
+			})
+			.map(|(peer, _)| peer)
+	}
+
+	/// Whether any of the missing chunks can no longer be fetched.
+	fn is_unavailable(&self, state: &State, params: &RecoveryParams) -> bool {
+		self.chunks.iter().any(|(_, validator_index)| {
+			!self.in_flight.contains_key(validator_index) &&
+				self.peers_for_chunk(state, params, *validator_index).next().is_none()
+		})
+	}
+
+	/// Issue requests for the missing chunks to the best available peers.
+	async fn launch_requests<Sender: overseer::AvailabilityRecoverySenderTrait>(
+		&mut self,
+		state: &State,
+		sender: &mut Sender,
+		params: &RecoveryParams,
+	) {
+		let strategy_type = RecoveryStrategy::<Sender>::strategy_type(&*self);
+		let now = Instant::now();
+		let mut requests = Vec::new();
+		let mut in_flight_count = self.in_flight_count();
+
+		for (_, validator_index) in self.chunks.clone() {
+			if in_flight_count >= N_PARALLEL {
+				break
+			}
+
+			if !self.should_request(&validator_index, now) {
+				continue
+			}
+
+			let Some(peer) = self.select_peer(state, params, validator_index) else { continue };
+
+			gum::trace!(
+				target: LOG_TARGET,
+				candidate_hash = ?params.candidate_hash,
+				authority_id = ?peer,
+				?validator_index,
+				expected_completion = ?self.estimator.expected_completion(&peer),
+				"Requesting systematic chunk",
+			);
+
+			let (request, response) =
+				make_chunk_request(strategy_type, params, peer.clone(), validator_index);
+			let issued = Instant::now();
+			self.requesting_chunks.push(
+				response
+					.map(move |(authority_id, validator_index, result): ChunkRequestOutput| {
+						(authority_id, validator_index, issued.elapsed(), result)
+					})
+					.boxed(),
+			);
+			requests.push(request);
+
+			self.estimator.on_request_issued(&peer);
+			self.in_flight.entry(validator_index).or_default().push((peer, issued));
+			in_flight_count += 1;
+		}
+
+		if !requests.is_empty() {
+			sender
+				.send_message(NetworkBridgeTxMessage::SendRequests(
+					requests,
+					IfDisconnected::TryConnect,
+				))
+				.await;
+		}
+	}
+}
+
+#[async_trait::async_trait]
+impl<Sender: overseer::AvailabilityRecoverySenderTrait> RecoveryStrategy<Sender>
+	for FetchSystematicChunksParallel
+{
+	fn display_name(&self) -> &'static str {
+		"Fetch systematic chunks in parallel"
+	}
+
+	fn strategy_type(&self) -> &'static str {
+		"systematic_chunks_parallel"
+	}
+
+	async fn run(
+		mut self: Box<Self>,
+		state: &mut State,
+		sender: &mut Sender,
+		common_params: &RecoveryParams,
+	) -> Result<AvailableData, RecoveryError> {
+		// First query the store for any chunks we've got.
+		if !common_params.bypass_availability_store {
+			let local_chunk_indices = state.populate_from_av_store(common_params, sender).await;
+
+			// If we hold an invalid systematic chunk, there's no point in requesting it from
+			// ourselves. Leave it to the backers.
+			for (validator_index, chunk_index) in local_chunk_indices {
+				if !state.received_chunks.contains_key(&chunk_index) {
+					state.record_error_fatal(
+						common_params.validator_authority_keys[validator_index.0 as usize].clone(),
+						validator_index,
+					);
+				}
+			}
+		}
+
+		let strategy_type = RecoveryStrategy::<Sender>::strategy_type(&*self);
+
+		loop {
+			self.chunks.retain(|(c_index, _)| !state.received_chunks.contains_key(c_index));
+
+			if self.chunks.is_empty() {
+				return attempt_systematic_recovery(
+					strategy_type,
+					self.threshold,
+					state,
+					common_params,
+				)
+				.await
+			}
+
+			self.launch_requests(state, sender, common_params).await;
+
+			if self.is_unavailable(state, common_params) {
+				gum::debug!(
+					target: LOG_TARGET,
+					candidate_hash = ?common_params.candidate_hash,
+					erasure_root = ?common_params.erasure_root,
+					missing_chunks = %self.chunks.len(),
+					in_flight = %self.in_flight_count(),
+					systematic_threshold = ?self.threshold,
+					"Data recovery from systematic chunks in parallel is not possible",
+				);
+
+				return Err(RecoveryError::Unavailable)
+			}
+
+			// Wait for the next response. On timeout, the requests that are still in flight become
+			// eligible for being duplicated to other peers.
+			let Some((authority_id, validator_index, elapsed, request_result)) =
+				self.requesting_chunks.next_with_timeout(TIMEOUT_START_NEW_REQUESTS).await
+			else {
+				continue
+			};
+
+			if let Some(requests) = self.in_flight.get_mut(&validator_index) {
+				requests.retain(|(peer, _)| peer != &authority_id);
+				if requests.is_empty() {
+					self.in_flight.remove(&validator_index);
+				}
+			}
+			self.estimator.on_request_concluded(&authority_id);
+
+			let received_bytes = match &request_result {
+				Ok((Some(chunk), _)) => Some(chunk.chunk.len()),
+				_ => None,
+			};
+
+			let is_error = state.handle_chunk_response(
+				strategy_type,
+				common_params,
+				&authority_id,
+				validator_index,
+				request_result,
+			);
+
+			match received_bytes {
+				Some(bytes) if !is_error =>
+					self.estimator.on_chunk_received(&authority_id, bytes, elapsed),
+				_ => self.estimator.on_request_failed(&authority_id),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_keyring::Sr25519Keyring;
+
+	#[test]
+	fn throughput_estimate_adapts() {
+		let fast: AuthorityDiscoveryId = Sr25519Keyring::Alice.public().into();
+		let slow: AuthorityDiscoveryId = Sr25519Keyring::Bob.public().into();
+		let unknown: AuthorityDiscoveryId = Sr25519Keyring::Charlie.public().into();
+
+		let mut estimator = ThroughputEstimator::default();
+		assert_eq!(estimator.throughput(&unknown), INITIAL_THROUGHPUT_ESTIMATE);
+
+		estimator.on_chunk_received(&fast, 4 * 1024 * 1024, Duration::from_secs(1));
+		estimator.on_chunk_received(&slow, 64 * 1024, Duration::from_secs(1));
+		assert_eq!(estimator.throughput(&fast), 4.0 * 1024.0 * 1024.0);
+		assert_eq!(estimator.throughput(&slow), 64.0 * 1024.0);
+
+		// New samples are smoothed with the previous estimate.
+		estimator.on_chunk_received(&fast, 2 * 1024 * 1024, Duration::from_secs(1));
+		assert_eq!(estimator.throughput(&fast), 3.0 * 1024.0 * 1024.0);
+
+		// Failures halve the estimate, down to the lower bound.
+		estimator.on_request_failed(&slow);
+		assert_eq!(estimator.throughput(&slow), 32.0 * 1024.0);
+		for _ in 0..20 {
+			estimator.on_request_failed(&slow);
+		}
+		assert_eq!(estimator.throughput(&slow), MIN_THROUGHPUT_ESTIMATE);
+	}
+
+	#[test]
+	fn expected_completion_accounts_for_in_flight_requests() {
+		let fast: AuthorityDiscoveryId = Sr25519Keyring::Alice.public().into();
+		let slow: AuthorityDiscoveryId = Sr25519Keyring::Bob.public().into();
+
+		let mut estimator = ThroughputEstimator::default();
+		estimator.on_chunk_received(&fast, 4 * 1024 * 1024, Duration::from_secs(1));
+		estimator.on_chunk_received(&slow, 1024 * 1024, Duration::from_secs(1));
+
+		// The fast peer is preferred until it is serving enough requests.
+		for _ in 0..3 {
+			assert!(estimator.expected_completion(&fast) < estimator.expected_completion(&slow));
+			estimator.on_request_issued(&fast);
+		}
+		assert_eq!(estimator.expected_completion(&fast), estimator.expected_completion(&slow));
+		estimator.on_request_issued(&fast);
+		assert!(estimator.expected_completion(&fast) > estimator.expected_completion(&slow));
+
+		estimator.on_request_concluded(&fast);
+		assert_eq!(estimator.in_flight(&fast), 3);
+	}
+}
@@ -92,66 +92,65 @@ impl FetchSystematicChunks {
 		// results in failure of the entire strategy.
 		std::cmp::min(max_requests_boundary, remaining_chunks)
 	}
+}
 
-	async fn attempt_systematic_recovery<Sender: overseer::AvailabilityRecoverySenderTrait>(
-		&mut self,
-		state: &mut State,
-		common_params: &RecoveryParams,
-	) -> Result<AvailableData, RecoveryError> {
-		let strategy_type = RecoveryStrategy::<Sender>::strategy_type(self);
-		let recovery_duration = common_params.metrics.time_erasure_recovery(strategy_type);
-		let reconstruct_duration = common_params.metrics.time_erasure_reconstruct(strategy_type);
-		let chunks = state
-			.received_chunks
-			.range(
-				ChunkIndex(0)..
-					ChunkIndex(
-						u32::try_from(self.threshold)
-							.expect("validator count should not exceed u32"),
-					),
-			)
-			.map(|(_, chunk)| chunk.chunk.clone())
-			.collect::<Vec<_>>();
-
-		let available_data = polkadot_erasure_coding::reconstruct_from_systematic_v1(
-			common_params.n_validators,
-			chunks,
-		);
-
-		match available_data {
-			Ok(data) => {
-				drop(reconstruct_duration);
-
-				// Attempt post-recovery check.
-				do_post_recovery_check(common_params, data)
-					.await
-					.inspect_err(|_| {
-						recovery_duration.map(|rd| rd.stop_and_discard());
-					})
-					.inspect(|_| {
-						gum::trace!(
-							target: LOG_TARGET,
-							candidate_hash = ?common_params.candidate_hash,
-							erasure_root = ?common_params.erasure_root,
-							"Data recovery from systematic chunks complete",
-						);
-					})
-			},
-			Err(err) => {
-				reconstruct_duration.map(|rd| rd.stop_and_discard());
-				recovery_duration.map(|rd| rd.stop_and_discard());
+/// Reconstruct the available data from the systematic chunks received so far and perform the
+/// post-recovery check.
+pub(super) async fn attempt_systematic_recovery(
+	strategy_type: &str,
+	threshold: usize,
+	state: &State,
+	common_params: &RecoveryParams,
+) -> Result<AvailableData, RecoveryError> {
+	let recovery_duration = common_params.metrics.time_erasure_recovery(strategy_type);
+	let reconstruct_duration = common_params.metrics.time_erasure_reconstruct(strategy_type);
+	let chunks = state
+		.received_chunks
+		.range(
+			ChunkIndex(0)..
+				ChunkIndex(
+					u32::try_from(threshold).expect("validator count should not exceed u32"),
+				),
+		)
+		.map(|(_, chunk)| chunk.chunk.clone())
+		.collect::<Vec<_>>();
+
+	let available_data =
+		polkadot_erasure_coding::reconstruct_from_systematic_v1(common_params.n_validators, chunks);
+
+	match available_data {
+		Ok(data) => {
+			drop(reconstruct_duration);
+
+			// Attempt post-recovery check.
+			do_post_recovery_check(common_params, data)
+				.await
+				.inspect_err(|_| {
+					recovery_duration.map(|rd| rd.stop_and_discard());
+				})
+				.inspect(|_| {
+					gum::trace!(
+						target: LOG_TARGET,
+						candidate_hash = ?common_params.candidate_hash,
+						erasure_root = ?common_params.erasure_root,
+						"Data recovery from systematic chunks complete",
+					);
+				})
+		},
+		Err(err) => {
+			reconstruct_duration.map(|rd| rd.stop_and_discard());
+			recovery_duration.map(|rd| rd.stop_and_discard());
 
-				gum::debug!(
-					target: LOG_TARGET,
-					candidate_hash = ?common_params.candidate_hash,
-					erasure_root = ?common_params.erasure_root,
-					?err,
-					"Systematic data recovery error",
-				);
+			gum::debug!(
+				target: LOG_TARGET,
+				candidate_hash = ?common_params.candidate_hash,
+				erasure_root = ?common_params.erasure_root,
+				?err,
+				"Systematic data recovery error",
+			);
 
-				Err(RecoveryError::Invalid)
-			},
-		}
+			Err(RecoveryError::Invalid)
+		},
 	}
 }
 
@@ -235,7 +234,13 @@ impl<Sender: overseer::AvailabilityRecoverySenderTrait> RecoveryStrategy<Sender>
 			// If received_chunks has `systematic_chunk_threshold` entries, attempt to recover the
 			// data.
 			if systematic_chunk_count >= self.threshold {
-				return self.attempt_systematic_recovery::<Sender>(state, common_params).await
+				return attempt_systematic_recovery(
+					RecoveryStrategy::<Sender>::strategy_type(&*self),
+					self.threshold,
+					state,
+					common_params,
+				)
+				.await
 			}
 
 			if Self::is_unavailable(
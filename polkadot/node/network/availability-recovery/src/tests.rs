@@ -141,6 +141,22 @@ fn with_systematic_chunks(
 	)
 }
 
+/// Create a new instance of `AvailabilityRecoverySubsystem` which first attempts to fetch
+/// systematic chunks from validators and backers in parallel, with a fallback to requesting regular
+/// chunks.
+fn with_parallel_systematic_chunks(
+	req_receiver: IncomingRequestReceiver<request_v1::AvailableDataFetchingRequest>,
+	req_protocol_names: &ReqProtocolNames,
+	metrics: Metrics,
+) -> AvailabilityRecoverySubsystem {
+	AvailabilityRecoverySubsystem::with_recovery_strategy_kind(
+		req_receiver,
+		req_protocol_names,
+		metrics,
+		RecoveryStrategyKind::ParallelSystematicChunks,
+	)
+}
+
 // Deterministic genesis hash for protocol names
 const GENESIS_HASH: Hash = Hash::repeat_byte(0xff);
 
@@ -3127,3 +3143,152 @@ fn test_systematic_recovery_skipped_if_mapping_disabled() {
 		virtual_overseer
 	});
 }
+
+#[test]
+fn parallel_systematic_recovery_retries_failed_chunk_from_another_peer() {
+	let test_state = TestState::default();
+	let req_protocol_names = ReqProtocolNames::new(&GENESIS_HASH, None);
+	let subsystem = with_parallel_systematic_chunks(
+		request_receiver(&req_protocol_names),
+		&req_protocol_names,
+		Metrics::new_dummy(),
+	);
+
+	test_harness(subsystem, |mut virtual_overseer| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(new_leaf(
+				test_state.current,
+				1,
+			))),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				Some(GroupIndex(0)),
+				Some(test_state.core_index),
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api_session_info(&mut virtual_overseer).await;
+
+		test_state.test_runtime_api_node_features(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		// The first peer does not have the chunk, which then gets requested from another peer.
+		let mut first_request = true;
+		test_state
+			.test_chunk_requests(
+				&req_protocol_names,
+				candidate_hash,
+				&mut virtual_overseer,
+				test_state.systematic_threshold() + 1,
+				|_| {
+					if std::mem::take(&mut first_request) {
+						Has::No
+					} else {
+						Has::Yes
+					}
+				},
+				true,
+			)
+			.await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		virtual_overseer
+	});
+}
+
+#[test]
+fn large_pov_is_recovered_from_parallel_systematic_chunks() {
+	let test_state = TestState::default();
+	let req_protocol_names = ReqProtocolNames::new(&GENESIS_HASH, None);
+	let subsystem = AvailabilityRecoverySubsystem::with_recovery_strategy_kind(
+		request_receiver(&req_protocol_names),
+		&req_protocol_names,
+		Metrics::new_dummy(),
+		RecoveryStrategyKind::BackersFirstIfSizeLowerThenParallelSystematicChunks(
+			FETCH_CHUNKS_THRESHOLD,
+		),
+	);
+
+	test_harness(subsystem, |mut virtual_overseer| async move {
+		overseer_signal(
+			&mut virtual_overseer,
+			OverseerSignal::ActiveLeaves(ActiveLeavesUpdate::start_work(new_leaf(
+				test_state.current,
+				1,
+			))),
+		)
+		.await;
+
+		let (tx, rx) = oneshot::channel();
+
+		overseer_send(
+			&mut virtual_overseer,
+			AvailabilityRecoveryMessage::RecoverAvailableData(
+				test_state.candidate.clone(),
+				test_state.session_index,
+				Some(GroupIndex(0)),
+				Some(test_state.core_index),
+				tx,
+			),
+		)
+		.await;
+
+		test_state.test_runtime_api_session_info(&mut virtual_overseer).await;
+
+		test_state.test_runtime_api_node_features(&mut virtual_overseer).await;
+
+		let candidate_hash = test_state.candidate.hash();
+
+		assert_matches!(
+			overseer_recv(&mut virtual_overseer).await,
+			AllMessages::AvailabilityStore(
+				AvailabilityStoreMessage::QueryChunkSize(_, tx)
+			) => {
+				let _ = tx.send(Some(crate::FETCH_CHUNKS_THRESHOLD + 1));
+			}
+		);
+
+		test_state.respond_to_available_data_query(&mut virtual_overseer, false).await;
+		test_state.respond_to_query_all_request(&mut virtual_overseer, |_| false).await;
+
+		// The first request fails, which the parallel strategy retries from another peer. The
+		// sequential systematic strategy would fall back to regular chunks instead.
+		let mut first_request = true;
+		test_state
+			.test_chunk_requests(
+				&req_protocol_names,
+				candidate_hash,
+				&mut virtual_overseer,
+				test_state.systematic_threshold() + 1,
+				|_| {
+					if std::mem::take(&mut first_request) {
+						Has::No
+					} else {
+						Has::Yes
+					}
+				},
+				true,
+			)
+			.await;
+
+		// Recovered data should match the original one.
+		assert_eq!(rx.await.unwrap().unwrap(), test_state.available_data);
+		virtual_overseer
+	});
+}
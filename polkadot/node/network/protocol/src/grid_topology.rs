@@ -89,6 +89,16 @@ impl SessionGridTopology {
 		SessionGridTopology { shuffled_indices, canonical_shuffling, peer_ids }
 	}
 
+	/// The indices of the validators in the shuffling.
+	pub fn shuffled_indices(&self) -> &[usize] {
+		&self.shuffled_indices
+	}
+
+	/// The canonical shuffling of validators for the session.
+	pub fn canonical_shuffling(&self) -> &[TopologyPeerInfo] {
+		&self.canonical_shuffling
+	}
+
 	/// Updates the known peer ids for the passed authorities ids.
 	pub fn update_authority_ids(
 		&mut self,
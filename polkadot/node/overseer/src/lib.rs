@@ -67,7 +67,7 @@ use std::{
 	fmt::{self, Debug},
 	pin::Pin,
 	sync::Arc,
-	time::Duration,
+	time::{Duration, Instant},
 };
 
 use futures::{channel::oneshot, future::BoxFuture, select, Future, FutureExt, StreamExt};
@@ -99,6 +99,9 @@ pub use self::metrics::Metrics as OverseerMetrics;
 pub mod dummy;
 pub use self::dummy::DummySubsystem;
 
+pub mod watchdog;
+use self::watchdog::ChannelWatchdog;
+
 pub use polkadot_node_metrics::{
 	metrics::{prometheus, Metrics as MetricsTrait},
 	Metronome,
@@ -666,6 +669,19 @@ pub fn spawn_metronome_metrics<S, SupportsParachains>(
 	overseer: &mut Overseer<S, SupportsParachains>,
 	metronome_metrics: OverseerMetrics,
 ) -> Result<(), SubsystemError>
+where
+	S: Spawner,
+	SupportsParachains: HeadSupportsParachains,
+{
+	spawn_metronome(overseer, metronome_metrics, None)
+}
+
+/// Spawn the metrics metronome task, which also feeds the channel watchdog if any.
+fn spawn_metronome<S, SupportsParachains>(
+	overseer: &mut Overseer<S, SupportsParachains>,
+	metronome_metrics: OverseerMetrics,
+	mut watchdog: Option<ChannelWatchdog>,
+) -> Result<(), SubsystemError>
 where
 	S: Spawner,
 	SupportsParachains: HeadSupportsParachains,
@@ -673,13 +689,12 @@ where
 	struct ExtractNameAndMeters;
 
 	impl<'a, T: 'a> MapSubsystem<&'a OrchestratedSubsystem<T>> for ExtractNameAndMeters {
-		type Output = Option<(&'static str, SubsystemMeters)>;
+		type Output = Option<(&'static str, &'static str, SubsystemMeters)>;
 
 		fn map_subsystem(&self, subsystem: &'a OrchestratedSubsystem<T>) -> Self::Output {
-			subsystem
-				.instance
-				.as_ref()
-				.map(|instance| (instance.name, instance.meters.clone()))
+			subsystem.instance.as_ref().map(|instance| {
+				(instance.name, watchdog::message_name::<T>(), instance.meters.clone())
+			})
 		}
 	}
 	let subsystem_meters = overseer.map_subsystems(ExtractNameAndMeters);
@@ -716,15 +731,28 @@ where
 	let metronome = Metronome::new(std::time::Duration::from_millis(950)).for_each(move |_| {
 		collect_memory_stats(&metronome_metrics);
 
+		// Reading the meters drains their time of flight samples, so they are read once for both
+		// the metrics and the watchdog.
+		let readouts = subsystem_meters
+			.iter()
+			.flatten()
+			.map(|(name, message, meters)| (*name, *message, meters.read()))
+			.collect::<Vec<_>>();
+
+		if let Some(watchdog) = watchdog.as_mut() {
+			watchdog.on_sample(
+				Instant::now(),
+				readouts
+					.iter()
+					.map(|(name, message, readouts)| (*name, *message, readouts.into())),
+			);
+		}
+
 		// We combine the amount of messages from subsystems to the overseer
 		// as well as the amount of messages from external sources to the overseer
 		// into one `to_overseer` value.
 		metronome_metrics.channel_metrics_snapshot(
-			subsystem_meters
-				.iter()
-				.cloned()
-				.flatten()
-				.map(|(name, ref meters)| (name, meters.read())),
+			readouts.into_iter().map(|(name, _, readouts)| (name, readouts)),
 		);
 
 		futures::future::ready(())
@@ -755,9 +783,22 @@ where
 		}
 	}
 
-	async fn run_inner(mut self) -> SubsystemResult<()> {
+	/// Run the `Overseer`, with a [`ChannelWatchdog`] observing the channels of the subsystems.
+	///
+	/// Logging any errors.
+	pub async fn run_with_watchdog(self, watchdog: ChannelWatchdog) {
+		if let Err(err) = self.run_inner_with(Some(watchdog)).await {
+			gum::error!(target: LOG_TARGET, ?err, "Overseer exited with error");
+		}
+	}
+
+	async fn run_inner(self) -> SubsystemResult<()> {
+		self.run_inner_with(None).await
+	}
+
+	async fn run_inner_with(mut self, watchdog: Option<ChannelWatchdog>) -> SubsystemResult<()> {
 		let metrics = self.metrics.clone();
-		spawn_metronome_metrics(&mut self, metrics)?;
+		spawn_metronome(&mut self, metrics, watchdog)?;
 
 		loop {
 			select! {
@@ -1238,3 +1238,178 @@ fn context_holds_onto_message_until_enough_signals_received() {
 
 	futures::executor::block_on(test_fut);
 }
+
+#[test]
+fn channel_watchdog_knows_all_subsystems() {
+	struct ExtractMessageName;
+
+	impl<'a, T: 'a> MapSubsystem<&'a OrchestratedSubsystem<T>> for ExtractMessageName {
+		type Output = &'static str;
+
+		fn map_subsystem(&self, _subsystem: &'a OrchestratedSubsystem<T>) -> Self::Output {
+			watchdog::message_name::<T>()
+		}
+	}
+
+	let spawner = sp_core::testing::TaskExecutor::new();
+	let (overseer, _handle) = dummy_overseer_builder(spawner, MockSupportsParachains, None)
+		.unwrap()
+		.build()
+		.unwrap();
+	let messages = overseer.map_subsystems(ExtractMessageName);
+
+	assert_eq!(messages.len(), watchdog::SENDS.len());
+	for (message, sends) in watchdog::SENDS {
+		assert!(messages.contains(message), "{} is consumed by a subsystem", message);
+		for sent in sends.iter() {
+			assert!(messages.contains(sent), "{} is consumed by a subsystem", sent);
+		}
+	}
+}
+
+#[test]
+fn channel_watchdog_sends_match_the_overseer() {
+	use polkadot_node_subsystem_types::messages::*;
+	use std::{collections::HashSet, marker::PhantomData};
+
+	// Whether `Consumer` declares `Sent` in its `sends`, resolved at compile time: the generated
+	// outgoing messages of a subsystem convert from the messages it sends. The method of
+	// `Declared` is preferred when its bound holds, as it takes the probe by reference.
+	struct Probe<Consumer, Sent>(PhantomData<(Consumer, Sent)>);
+
+	trait Declared {
+		fn declared(&self) -> bool {
+			true
+		}
+	}
+	impl<Consumer: AssociateOutgoing, Sent> Declared for &Probe<Consumer, Sent> where
+		Consumer::OutgoingMessages: From<Sent>
+	{
+	}
+
+	trait Undeclared {
+		fn declared(&self) -> bool {
+			false
+		}
+	}
+	impl<Consumer, Sent> Undeclared for Probe<Consumer, Sent> {}
+
+	macro_rules! declared_sends {
+		($($message:ident,)*) => {
+			declared_sends!(@consumers [$($message,)*] [$($message,)*])
+		};
+		(@consumers [$($consumer:ident,)*] $all:tt) => {
+			vec![$(declared_sends!(@sends $consumer $all),)*]
+		};
+		(@sends $consumer:ident [$($sent:ident,)*]) => {
+			(
+				stringify!($consumer),
+				[$((
+					stringify!($sent),
+					(&Probe::<$consumer, $sent>(PhantomData)).declared(),
+				),)*]
+				.into_iter()
+				.filter_map(|(sent, declared)| declared.then_some(sent))
+				.collect::<HashSet<_>>(),
+			)
+		};
+	}
+
+	let declared = declared_sends!(
+		CandidateValidationMessage,
+		PvfCheckerMessage,
+		CandidateBackingMessage,
+		StatementDistributionMessage,
+		AvailabilityDistributionMessage,
+		AvailabilityRecoveryMessage,
+		BitfieldSigningMessage,
+		BitfieldDistributionMessage,
+		ProvisionerMessage,
+		RuntimeApiMessage,
+		AvailabilityStoreMessage,
+		NetworkBridgeRxMessage,
+		NetworkBridgeTxMessage,
+		ChainApiMessage,
+		CollationGenerationMessage,
+		CollatorProtocolMessage,
+		ApprovalDistributionMessage,
+		ApprovalVotingMessage,
+		ApprovalVotingParallelMessage,
+		GossipSupportMessage,
+		DisputeCoordinatorMessage,
+		DisputeDistributionMessage,
+		ChainSelectionMessage,
+		ProspectiveParachainsMessage,
+	);
+
+	assert_eq!(declared.len(), watchdog::SENDS.len());
+	for (consumer, sends) in declared {
+		let (_, table) = watchdog::SENDS
+			.iter()
+			.find(|(message, _)| *message == consumer)
+			.unwrap_or_else(|| panic!("{} is in the sends of the watchdog", consumer));
+		assert_eq!(
+			table.iter().copied().collect::<HashSet<_>>(),
+			sends,
+			"The watchdog knows the sends of {}",
+			consumer,
+		);
+	}
+}
+
+#[test]
+fn channel_watchdog_reports_stalled_subsystems() {
+	use watchdog::{ChannelReadout, WaitEdge, WatchdogConfig, WatchdogMetrics};
+
+	let config = WatchdogConfig { stall_threshold: Duration::from_secs(5), tracing: true };
+	let mut watchdog = ChannelWatchdog::new(config, WatchdogMetrics::default());
+	let sample = |sent, received| ChannelReadout { sent, received };
+	let start = Instant::now();
+
+	watchdog.on_sample(
+		start,
+		[
+			("candidate-validation", "CandidateValidationMessage", sample(10, 10)),
+			("candidate-backing", "CandidateBackingMessage", sample(1, 1)),
+		],
+	);
+	watchdog.on_sample(
+		start + Duration::from_secs(1),
+		[
+			("candidate-validation", "CandidateValidationMessage", sample(20, 12)),
+			("candidate-backing", "CandidateBackingMessage", sample(2, 2)),
+		],
+	);
+	assert!(watchdog.wait_graph(start + Duration::from_secs(1)).is_empty());
+
+	// The 13th message to candidate validation was sent before the second sample and is still
+	// queued, candidate backing keeps receiving messages.
+	let now = start + Duration::from_secs(7);
+	watchdog.on_sample(
+		now,
+		[
+			("candidate-validation", "CandidateValidationMessage", sample(30, 12)),
+			("candidate-backing", "CandidateBackingMessage", sample(5, 2)),
+		],
+	);
+	assert_eq!(
+		watchdog.wait_graph(now),
+		vec![WaitEdge {
+			sender: "candidate-backing",
+			receiver: "candidate-validation",
+			receiver_depth: 18,
+			receiver_oldest_message_age: Duration::from_secs(6),
+			sender_stalled: false,
+		}],
+	);
+
+	let now = start + Duration::from_secs(8);
+	watchdog.on_sample(
+		now,
+		[
+			("candidate-validation", "CandidateValidationMessage", sample(30, 25)),
+			("candidate-backing", "CandidateBackingMessage", sample(5, 5)),
+		],
+	);
+	assert!(watchdog.wait_graph(now).is_empty());
+}
@@ -0,0 +1,502 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Opt-in tracing of the channels between subsystems and a watchdog reporting subsystems which do
+//! not drain their queue.
+//!
+//! The overseer does not see the messages subsystems send to each other, so the state of a channel
+//! is derived from the meters of its receiving subsystem, which are sampled by the metrics
+//! metronome. The age of the oldest queued message is estimated from the history of the samples,
+//! and the senders of a channel are taken from the `sends` declarations of [`Overseer`].
+//!
+//! When a subsystem has not drained its queue for longer than the configured threshold, the
+//! channel-wait graph is logged: every subsystem sending to a stalled subsystem, and whether it is
+//! stalled itself, which hints at the subsystem being blocked on the full queue of the other one.
+//!
+//! [`Overseer`]: crate::Overseer
+
+use std::{
+	collections::{HashMap, VecDeque},
+	fmt,
+	time::{Duration, Instant},
+};
+
+use crate::{
+	metrics::{prometheus, MetricsTrait},
+	SubsystemMeterReadouts,
+};
+
+const LOG_TARGET: &str = "parachain::overseer-watchdog";
+
+/// The number of samples kept per channel, about two minutes with the metronome of the metrics.
+const HISTORY_LEN: usize = 128;
+
+/// The messages each subsystem sends, by the message it consumes.
+///
+/// Mirrors the `sends` declarations of [`Overseer`](crate::Overseer), the tests compare both.
+pub(crate) const SENDS: &[(&str, &[&str])] = &[
+	("CandidateValidationMessage", &["ChainApiMessage", "RuntimeApiMessage"]),
+	("PvfCheckerMessage", &["CandidateValidationMessage", "RuntimeApiMessage"]),
+	(
+		"CandidateBackingMessage",
+		&[
+			"CandidateValidationMessage",
+			"CollatorProtocolMessage",
+			"ChainApiMessage",
+			"AvailabilityDistributionMessage",
+			"AvailabilityStoreMessage",
+			"StatementDistributionMessage",
+			"ProvisionerMessage",
+			"RuntimeApiMessage",
+			"ProspectiveParachainsMessage",
+		],
+	),
+	(
+		"StatementDistributionMessage",
+		&[
+			"NetworkBridgeTxMessage",
+			"CandidateBackingMessage",
+			"RuntimeApiMessage",
+			"ProspectiveParachainsMessage",
+			"ChainApiMessage",
+		],
+	),
+	(
+		"AvailabilityDistributionMessage",
+		&[
+			"AvailabilityStoreMessage",
+			"ChainApiMessage",
+			"RuntimeApiMessage",
+			"NetworkBridgeTxMessage",
+		],
+	),
+	(
+		"AvailabilityRecoveryMessage",
+		&["NetworkBridgeTxMessage", "RuntimeApiMessage", "AvailabilityStoreMessage"],
+	),
+	(
+		"BitfieldSigningMessage",
+		&["AvailabilityStoreMessage", "RuntimeApiMessage", "BitfieldDistributionMessage"],
+	),
+	(
+		"BitfieldDistributionMessage",
+		&["RuntimeApiMessage", "NetworkBridgeTxMessage", "ProvisionerMessage"],
+	),
+	(
+		"ProvisionerMessage",
+		&[
+			"RuntimeApiMessage",
+			"CandidateBackingMessage",
+			"ChainApiMessage",
+			"DisputeCoordinatorMessage",
+			"ProspectiveParachainsMessage",
+		],
+	),
+	("RuntimeApiMessage", &[]),
+	("AvailabilityStoreMessage", &["ChainApiMessage", "RuntimeApiMessage"]),
+	(
+		"NetworkBridgeRxMessage",
+		&[
+			"BitfieldDistributionMessage",
+			"StatementDistributionMessage",
+			"ApprovalDistributionMessage",
+			"ApprovalVotingParallelMessage",
+			"GossipSupportMessage",
+			"DisputeDistributionMessage",
+			"CollationGenerationMessage",
+			"CollatorProtocolMessage",
+		],
+	),
+	("NetworkBridgeTxMessage", &[]),
+	("ChainApiMessage", &[]),
+	("CollationGenerationMessage", &["RuntimeApiMessage", "CollatorProtocolMessage"]),
+	(
+		"CollatorProtocolMessage",
+		&[
+			"NetworkBridgeTxMessage",
+			"RuntimeApiMessage",
+			"CandidateBackingMessage",
+			"ChainApiMessage",
+			"ProspectiveParachainsMessage",
+		],
+	),
+	(
+		"ApprovalDistributionMessage",
+		&["NetworkBridgeTxMessage", "ApprovalVotingMessage", "RuntimeApiMessage"],
+	),
+	(
+		"ApprovalVotingMessage",
+		&[
+			"ApprovalDistributionMessage",
+			"AvailabilityRecoveryMessage",
+			"CandidateValidationMessage",
+			"ChainApiMessage",
+			"ChainSelectionMessage",
+			"DisputeCoordinatorMessage",
+			"RuntimeApiMessage",
+		],
+	),
+	(
+		"ApprovalVotingParallelMessage",
+		&[
+			"AvailabilityRecoveryMessage",
+			"CandidateValidationMessage",
+			"ChainApiMessage",
+			"ChainSelectionMessage",
+			"DisputeCoordinatorMessage",
+			"RuntimeApiMessage",
+			"NetworkBridgeTxMessage",
+			"ApprovalVotingMessage",
+			"ApprovalDistributionMessage",
+			"ApprovalVotingParallelMessage",
+		],
+	),
+	(
+		"GossipSupportMessage",
+		&[
+			"NetworkBridgeTxMessage",
+			"NetworkBridgeRxMessage",
+			"RuntimeApiMessage",
+			"ChainSelectionMessage",
+		],
+	),
+	(
+		"DisputeCoordinatorMessage",
+		&[
+			"RuntimeApiMessage",
+			"ChainApiMessage",
+			"DisputeDistributionMessage",
+			"CandidateValidationMessage",
+			"ApprovalVotingMessage",
+			"AvailabilityStoreMessage",
+			"AvailabilityRecoveryMessage",
+			"ChainSelectionMessage",
+			"ApprovalVotingParallelMessage",
+		],
+	),
+	(
+		"DisputeDistributionMessage",
+		&["RuntimeApiMessage", "DisputeCoordinatorMessage", "NetworkBridgeTxMessage"],
+	),
+	("ChainSelectionMessage", &["ChainApiMessage"]),
+	("ProspectiveParachainsMessage", &["RuntimeApiMessage", "ChainApiMessage"]),
+];
+
+/// The name of the message type `M`, without its path.
+pub(crate) fn message_name<M>() -> &'static str {
+	let name = std::any::type_name::<M>();
+	name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Configuration of the [`ChannelWatchdog`].
+#[derive(Debug, Clone)]
+pub struct WatchdogConfig {
+	/// A subsystem is reported once the oldest message in its queue is older than this.
+	pub stall_threshold: Duration,
+	/// Log the state of every non-empty channel on each sample.
+	pub tracing: bool,
+}
+
+/// The counters of a channel, messages and signals combined.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ChannelReadout {
+	pub sent: usize,
+	pub received: usize,
+}
+
+impl From<&SubsystemMeterReadouts> for ChannelReadout {
+	fn from(readouts: &SubsystemMeterReadouts) -> Self {
+		Self {
+			sent: readouts.bounded.sent + readouts.unbounded.sent + readouts.signals.sent,
+			received: readouts.bounded.received +
+				readouts.unbounded.received +
+				readouts.signals.received,
+		}
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Sample {
+	at: Instant,
+	sent: usize,
+	depth: usize,
+}
+
+/// The channel of a subsystem.
+struct Channel {
+	/// The name of the receiving subsystem.
+	name: &'static str,
+	/// The number of messages received by the subsystem at the last sample.
+	received: usize,
+	history: VecDeque<Sample>,
+	stalled: bool,
+}
+
+impl Channel {
+	fn new(name: &'static str) -> Self {
+		Self { name, received: 0, history: VecDeque::with_capacity(HISTORY_LEN), stalled: false }
+	}
+
+	fn depth(&self) -> usize {
+		self.history.back().map_or(0, |sample| sample.depth)
+	}
+
+	/// The age of the oldest message in the queue.
+	///
+	/// This is a lower bound: the message was sent before the first sample counting it as sent.
+	fn oldest_message_age(&self, now: Instant) -> Duration {
+		self.history
+			.iter()
+			.find(|sample| sample.sent > self.received)
+			.map_or(Duration::ZERO, |sample| now.saturating_duration_since(sample.at))
+	}
+
+	fn depth_history(&self) -> Vec<usize> {
+		self.history.iter().map(|sample| sample.depth).collect()
+	}
+}
+
+/// An edge of the channel-wait graph: `sender` sends messages to `receiver`, which has not drained
+/// its queue.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WaitEdge {
+	pub sender: &'static str,
+	pub receiver: &'static str,
+	pub receiver_depth: usize,
+	pub receiver_oldest_message_age: Duration,
+	/// Whether the sender has not drained its queue either, it might be blocked on the receiver.
+	pub sender_stalled: bool,
+}
+
+impl fmt::Display for WaitEdge {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(
+			f,
+			"{} -> {} (depth {}, oldest message {:?}{})",
+			self.sender,
+			self.receiver,
+			self.receiver_depth,
+			self.receiver_oldest_message_age,
+			if self.sender_stalled { ", sender stalled" } else { "" },
+		)
+	}
+}
+
+/// Watches the channels of the subsystems of an [`Overseer`](crate::Overseer) and reports the
+/// subsystems which do not drain their queue.
+///
+/// Run the overseer with [`Overseer::run_with_watchdog`](crate::Overseer::run_with_watchdog).
+pub struct ChannelWatchdog {
+	config: WatchdogConfig,
+	metrics: WatchdogMetrics,
+	/// The channels, by the message consumed by their subsystem.
+	channels: HashMap<&'static str, Channel>,
+}
+
+impl ChannelWatchdog {
+	/// Create a new watchdog.
+	pub fn new(config: WatchdogConfig, metrics: WatchdogMetrics) -> Self {
+		Self { config, metrics, channels: HashMap::new() }
+	}
+
+	/// Record a sample of the channels, given by subsystem name and consumed message.
+	pub(crate) fn on_sample(
+		&mut self,
+		now: Instant,
+		readouts: impl IntoIterator<Item = (&'static str, &'static str, ChannelReadout)>,
+	) {
+		for (name, message, readout) in readouts {
+			let channel = self.channels.entry(message).or_insert_with(|| Channel::new(name));
+			if channel.history.len() == HISTORY_LEN {
+				channel.history.pop_front();
+			}
+			channel.history.push_back(Sample {
+				at: now,
+				sent: readout.sent,
+				depth: readout.sent.saturating_sub(readout.received),
+			});
+			channel.received = readout.received;
+		}
+
+		let mut newly_stalled = Vec::new();
+		for channel in self.channels.values_mut() {
+			let depth = channel.depth();
+			let oldest_message_age = channel.oldest_message_age(now);
+			self.metrics.on_channel_sampled(channel.name, depth, oldest_message_age);
+
+			let stalled = oldest_message_age >= self.config.stall_threshold;
+			if stalled && !channel.stalled {
+				newly_stalled.push(channel.name);
+				gum::warn!(
+					target: LOG_TARGET,
+					subsystem = channel.name,
+					depth,
+					?oldest_message_age,
+					depth_history = ?channel.depth_history(),
+					"Subsystem has not drained its queue",
+				);
+			} else if !stalled && channel.stalled {
+				gum::info!(
+					target: LOG_TARGET,
+					subsystem = channel.name,
+					depth,
+					"Subsystem drained its queue again",
+				);
+			}
+			channel.stalled = stalled;
+
+			if self.config.tracing && depth > 0 {
+				gum::debug!(
+					target: LOG_TARGET,
+					subsystem = channel.name,
+					depth,
+					?oldest_message_age,
+					depth_history = ?channel.depth_history(),
+					"Channel state",
+				);
+			}
+		}
+
+		let graph = self.wait_graph(now);
+		self.metrics.on_wait_graph(self.edges(), &graph);
+		if !newly_stalled.is_empty() {
+			let graph = graph.iter().map(ToString::to_string).collect::<Vec<_>>().join("; ");
+			gum::warn!(
+				target: LOG_TARGET,
+				stalled = ?newly_stalled,
+				%graph,
+				"Channel-wait graph",
+			);
+		}
+	}
+
+	/// The edges between the observed subsystems, as sender and receiver names.
+	fn edges(&self) -> impl Iterator<Item = (&'static str, &'static str)> + '_ {
+		SENDS.iter().flat_map(move |(sender, receivers)| {
+			receivers.iter().filter(move |receiver| *receiver != sender).filter_map(
+				move |receiver| {
+					let sender = self.channels.get(sender)?;
+					let receiver = self.channels.get(receiver)?;
+					Some((sender.name, receiver.name))
+				},
+			)
+		})
+	}
+
+	/// The edges to the subsystems which have not drained their queue.
+	pub(crate) fn wait_graph(&self, now: Instant) -> Vec<WaitEdge> {
+		SENDS
+			.iter()
+			.flat_map(|(sender, receivers)| {
+				receivers
+					.iter()
+					.filter(move |receiver| *receiver != sender)
+					.map(move |receiver| (sender, receiver))
+			})
+			.filter_map(|(sender, receiver)| {
+				let sender = self.channels.get(sender)?;
+				let receiver = self.channels.get(receiver).filter(|channel| channel.stalled)?;
+				Some(WaitEdge {
+					sender: sender.name,
+					receiver: receiver.name,
+					receiver_depth: receiver.depth(),
+					receiver_oldest_message_age: receiver.oldest_message_age(now),
+					sender_stalled: sender.stalled,
+				})
+			})
+			.collect()
+	}
+}
+
+#[derive(Clone)]
+struct MetricsInner {
+	channel_depth: prometheus::GaugeVec<prometheus::U64>,
+	oldest_message_age: prometheus::GaugeVec<prometheus::F64>,
+	channel_wait: prometheus::GaugeVec<prometheus::F64>,
+}
+
+/// Prometheus metrics of the [`ChannelWatchdog`].
+#[derive(Default, Clone)]
+pub struct WatchdogMetrics(Option<MetricsInner>);
+
+impl WatchdogMetrics {
+	fn on_channel_sampled(&self, name: &str, depth: usize, oldest_message_age: Duration) {
+		if let Some(metrics) = &self.0 {
+			metrics.channel_depth.with_label_values(&[name]).set(depth as u64);
+			metrics
+				.oldest_message_age
+				.with_label_values(&[name])
+				.set(oldest_message_age.as_secs_f64());
+		}
+	}
+
+	fn on_wait_graph(
+		&self,
+		edges: impl Iterator<Item = (&'static str, &'static str)>,
+		graph: &[WaitEdge],
+	) {
+		if let Some(metrics) = &self.0 {
+			for (sender, receiver) in edges {
+				let wait = graph
+					.iter()
+					.find(|edge| {
+						edge.sender == sender && edge.receiver == receiver && edge.sender_stalled
+					})
+					.map_or(0.0, |edge| edge.receiver_oldest_message_age.as_secs_f64());
+				metrics.channel_wait.with_label_values(&[sender, receiver]).set(wait);
+			}
+		}
+	}
+}
+
+impl MetricsTrait for WatchdogMetrics {
+	fn try_register(registry: &prometheus::Registry) -> Result<Self, prometheus::PrometheusError> {
+		let metrics = MetricsInner {
+			channel_depth: prometheus::register(
+				prometheus::GaugeVec::<prometheus::U64>::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_subsystem_queue_depth",
+						"Number of messages and signals queued for a subsystem",
+					),
+					&["subsystem_name"],
+				)?,
+				registry,
+			)?,
+			oldest_message_age: prometheus::register(
+				prometheus::GaugeVec::<prometheus::F64>::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_subsystem_oldest_message_age",
+						"Estimated age in seconds of the oldest message queued for a subsystem",
+					),
+					&["subsystem_name"],
+				)?,
+				registry,
+			)?,
+			channel_wait: prometheus::register(
+				prometheus::GaugeVec::<prometheus::F64>::new(
+					prometheus::Opts::new(
+						"polkadot_parachain_subsystem_channel_wait",
+						"Seconds a stalled subsystem has possibly been waiting on a stalled subsystem \
+						it sends messages to",
+					),
+					&["sender", "receiver"],
+				)?,
+				registry,
+			)?,
+		};
+		Ok(WatchdogMetrics(Some(metrics)))
+	}
+}
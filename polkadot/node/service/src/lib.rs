@@ -43,7 +43,8 @@ mod tests;
 use {
 	gum::info,
 	polkadot_node_core_approval_voting::{
-		self as approval_voting_subsystem, Config as ApprovalVotingConfig,
+		self as approval_voting_subsystem, timeline::ApprovalTimelines,
+		Config as ApprovalVotingConfig,
 	},
 	polkadot_node_core_av_store::Config as AvailabilityConfig,
 	polkadot_node_core_av_store::Error as AvailabilityError,
@@ -66,10 +67,15 @@ use polkadot_overseer::SpawnGlue;
 
 #[cfg(feature = "full-node")]
 pub use {
-	polkadot_overseer::{Handle, Overseer, OverseerConnector, OverseerHandle},
+	polkadot_node_core_dispute_coordinator::inspect as disputes,
+	polkadot_overseer::{
+		watchdog::WatchdogConfig as OverseerWatchdogConfig, Handle, Overseer, OverseerConnector,
+		OverseerHandle,
+	},
 	polkadot_primitives::runtime_api::ParachainHost,
 	relay_chain_selection::SelectRelayChain,
 	sc_client_api::AuxStore,
+	sc_consensus_grandpa::MonitorConfig as GrandpaMonitorConfig,
 	sp_authority_discovery::AuthorityDiscoveryApi,
 	sp_blockchain::{HeaderBackend, HeaderMetadata},
 	sp_consensus_babe::BabeApi,
@@ -225,10 +231,18 @@ pub enum Error {
 	#[error("Authorities require the real overseer implementation")]
 	AuthoritiesRequireRealOverseer,
 
+	#[cfg(feature = "full-node")]
+	#[error("Capturing the approval traffic requires parallel approval voting")]
+	ApprovalTrafficCaptureRequiresParallelApprovalVoting,
+
 	#[cfg(feature = "full-node")]
 	#[error("Creating a custom database is required for validators")]
 	DatabasePathRequired,
 
+	#[cfg(feature = "full-node")]
+	#[error(transparent)]
+	DisputesInspection(#[from] polkadot_node_core_dispute_coordinator::inspect::Error),
+
 	#[cfg(feature = "full-node")]
 	#[error("Expected at least one of polkadot, kusama, westend or rococo runtime feature")]
 	NoRuntime,
@@ -461,6 +475,7 @@ fn new_partial<ChainSelection>(
 	config: &mut Configuration,
 	Basics { task_manager, backend, client, keystore_container, telemetry }: Basics,
 	select_chain: ChainSelection,
+	grandpa_monitor: Option<sc_consensus_grandpa::GrandpaMonitor<Block>>,
 ) -> Result<
 	sc_service::PartialComponents<
 		FullClient,
@@ -485,7 +500,10 @@ fn new_partial<ChainSelection>(
 				sc_consensus_babe::BabeLink<Block>,
 				sc_consensus_beefy::BeefyVoterLinks<Block, ecdsa_crypto::AuthorityId>,
 			),
-			sc_consensus_grandpa::SharedVoterState,
+			(
+				sc_consensus_grandpa::SharedVoterState,
+				Option<sc_consensus_grandpa::GrandpaMonitor<Block>>,
+			),
 			sp_consensus_babe::SlotDuration,
 			Option<Telemetry>,
 		),
@@ -569,7 +587,7 @@ where
 	);
 
 	let import_setup = (block_import, grandpa_link, babe_link, beefy_voter_links);
-	let rpc_setup = shared_voter_state.clone();
+	let rpc_setup = (shared_voter_state.clone(), grandpa_monitor.clone());
 
 	let rpc_extensions_builder = {
 		let client = client.clone();
@@ -596,6 +614,7 @@ where
 					justification_stream: justification_stream.clone(),
 					subscription_executor: subscription_executor.clone(),
 					finality_provider: finality_proof_provider.clone(),
+					monitor: grandpa_monitor.clone(),
 				},
 				beefy: polkadot_rpc::BeefyDeps::<ecdsa_crypto::AuthorityId> {
 					beefy_finality_proof_stream: beefy_rpc_links.from_voter_justif_stream.clone(),
@@ -652,6 +671,13 @@ pub struct NewFullParams<OverseerGenerator: OverseerGen> {
 	pub hwbench: Option<sc_sysinfo::HwBench>,
 	/// Enable approval voting processing in parallel.
 	pub enable_approval_voting_parallel: bool,
+	/// An optional file to capture the approval-distribution traffic of the node to, for replaying
+	/// it with `subsystem-bench`. Only used with parallel approval voting.
+	pub approval_traffic_capture: Option<std::path::PathBuf>,
+	/// Watch the channels of the overseer for subsystems which do not drain their queue.
+	pub overseer_watchdog: Option<OverseerWatchdogConfig>,
+	/// Monitor the GRANDPA gossip for equivocations, participation and finality stalls.
+	pub grandpa_monitor: Option<GrandpaMonitorConfig>,
 }
 
 #[cfg(feature = "full-node")]
@@ -745,6 +771,9 @@ pub fn new_full<
 		prepare_workers_soft_max_num,
 		prepare_workers_hard_max_num,
 		enable_approval_voting_parallel,
+		approval_traffic_capture,
+		overseer_watchdog,
+		grandpa_monitor,
 	}: NewFullParams<OverseerGenerator>,
 ) -> Result<NewFull, Error> {
 	use polkadot_availability_recovery::FETCH_CHUNKS_THRESHOLD;
@@ -752,6 +781,10 @@ pub fn new_full<
 	use sc_network_sync::WarpSyncConfig;
 	use sc_sysinfo::Metric;
 
+	if approval_traffic_capture.is_some() && !enable_approval_voting_parallel {
+		return Err(Error::ApprovalTrafficCaptureRequiresParallelApprovalVoting)
+	}
+
 	let is_offchain_indexing_enabled = config.offchain_worker.indexing_enabled;
 	let role = config.role;
 	let force_authoring = config.force_authoring;
@@ -813,12 +846,19 @@ pub fn new_full<
 		import_queue,
 		transaction_pool,
 		other: (rpc_extensions_builder, import_setup, rpc_setup, slot_duration, mut telemetry),
-	} = new_partial::<SelectRelayChain<_>>(&mut config, basics, select_chain)?;
+	} = new_partial::<SelectRelayChain<_>>(
+		&mut config,
+		basics,
+		select_chain,
+		grandpa_monitor.map(|monitor_config| {
+			sc_consensus_grandpa::GrandpaMonitor::new(monitor_config, prometheus_registry.as_ref())
+		}),
+	)?;
 
 	let metrics = Network::register_notification_metrics(
 		config.prometheus_config.as_ref().map(|cfg| &cfg.registry),
 	);
-	let shared_voter_state = rpc_setup;
+	let (shared_voter_state, grandpa_monitor) = rpc_setup;
 	let auth_disc_publish_non_global_ips = config.network.allow_non_globals_in_dht;
 	let auth_disc_public_addresses = config.network.public_addresses.clone();
 
@@ -1005,6 +1045,8 @@ pub fn new_full<
 			chain_selection_config,
 			fetch_chunks_threshold,
 			enable_approval_voting_parallel,
+			approval_timelines: ApprovalTimelines::default(),
+			approval_traffic_capture,
 		})
 	};
 
@@ -1038,6 +1080,8 @@ pub fn new_full<
 				network_provider: Arc::new(network.clone()),
 				is_validator: role.is_authority(),
 				enable_http_requests: false,
+				limits: config.offchain_worker.limits.clone(),
+				prometheus_registry: prometheus_registry.clone(),
 				custom_extensions: move |_| vec![],
 			})?
 			.run(client.clone(), task_manager.spawn_handle())
@@ -1045,6 +1089,39 @@ pub fn new_full<
 		);
 	}
 
+	// The disputes RPC reads the database of the dispute coordinator, which is opened above, and
+	// the approvals RPC the timelines recorded by approval voting.
+	let rpc_extensions_builder = {
+		let client = client.clone();
+		let keystore = keystore_container.keystore();
+		let parachains_db = ext_overseer_args.as_ref().map(|args| args.parachains_db.clone());
+		let approval_timelines =
+			ext_overseer_args.as_ref().map(|args| args.approval_timelines.clone());
+
+		move |subscription_executor: polkadot_rpc::SubscriptionTaskExecutor|
+		      -> Result<polkadot_rpc::RpcExtension, sc_service::Error> {
+			use polkadot_rpc::{
+				approvals::{Approvals, ApprovalsApiServer},
+				disputes::{Disputes, DisputesApiServer},
+			};
+
+			let mut io = rpc_extensions_builder(subscription_executor)?;
+			if let Some(db) = &parachains_db {
+				let config = DisputeCoordinatorConfig {
+					col_dispute_data: parachains_db::REAL_COLUMNS.col_dispute_coordinator_data,
+				};
+				let disputes = Disputes::new(client.clone(), keystore.clone(), db.clone(), config);
+				io.merge(disputes.into_rpc())
+					.map_err(|e| sc_service::Error::Application(e.into()))?;
+			}
+			if let Some(timelines) = &approval_timelines {
+				io.merge(Approvals::new(timelines.clone()).into_rpc())
+					.map_err(|e| sc_service::Error::Application(e.into()))?;
+			}
+			Ok(io)
+		}
+	};
+
 	let rpc_handlers = sc_service::spawn_tasks(sc_service::SpawnTasksParams {
 		config,
 		backend: backend.clone(),
@@ -1184,6 +1261,18 @@ pub fn new_full<
 				e
 			})?;
 		let handle = Handle::new(overseer_handle.clone());
+		let watchdog = match overseer_watchdog {
+			Some(config) => {
+				use polkadot_overseer::{
+					watchdog::{ChannelWatchdog, WatchdogMetrics},
+					MetricsTrait,
+				};
+
+				let metrics = WatchdogMetrics::register(prometheus_registry.as_ref())?;
+				Some(ChannelWatchdog::new(config, metrics))
+			},
+			None => None,
+		};
 
 		{
 			let handle = handle.clone();
@@ -1196,7 +1285,11 @@ pub fn new_full<
 					let forward = polkadot_overseer::forward_events(overseer_client, handle);
 
 					let forward = forward.fuse();
-					let overseer_fut = overseer.run().fuse();
+					let overseer_fut = match watchdog {
+						Some(watchdog) => overseer.run_with_watchdog(watchdog).boxed(),
+						None => overseer.run().boxed(),
+					}
+					.fuse();
 
 					pin_mut!(overseer_fut);
 					pin_mut!(forward);
@@ -1368,6 +1461,23 @@ pub fn new_full<
 				voting_rules_builder.add(sc_consensus_grandpa::BeforeBestBlockBy(delay));
 		};
 
+		if let Some(monitor) = grandpa_monitor.clone() {
+			task_manager.spawn_handle().spawn(
+				"grandpa-monitor",
+				None,
+				sc_consensus_grandpa::run_grandpa_monitor(
+					sc_consensus_grandpa::GrandpaMonitorParams {
+						monitor,
+						client: client.clone(),
+						authority_set: link_half.shared_authority_set().clone(),
+						offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(
+							transaction_pool.clone(),
+						),
+					},
+				),
+			);
+		}
+
 		let grandpa_config = sc_consensus_grandpa::GrandpaParams {
 			config,
 			link: link_half,
@@ -1384,7 +1494,7 @@ pub fn new_full<
 		task_manager.spawn_essential_handle().spawn_blocking(
 			"grandpa-voter",
 			None,
-			sc_consensus_grandpa::run_grandpa_voter(grandpa_config)?,
+			sc_consensus_grandpa::run_grandpa_voter_with_monitor(grandpa_config, grandpa_monitor)?,
 		);
 	}
 
@@ -1413,7 +1523,7 @@ macro_rules! chain_ops {
 		let chain_selection = LongestChain::new(basics.backend.clone());
 
 		let sc_service::PartialComponents { client, backend, import_queue, task_manager, .. } =
-			new_partial::<LongestChain<_, Block>>(&mut config, basics, chain_selection)?;
+			new_partial::<LongestChain<_, Block>>(&mut config, basics, chain_selection, None)?;
 		Ok((client, backend, import_queue, task_manager))
 	}};
 }
@@ -1510,6 +1620,27 @@ pub fn revert_backend(
 	Ok(())
 }
 
+/// Inspect the disputes stored by the dispute coordinator in the parachains-db.
+///
+/// The node must not be running. The participation of the local validator is determined with the
+/// keys in the keystore of the node.
+#[cfg(feature = "full-node")]
+pub fn inspect_disputes(mut config: Configuration) -> Result<disputes::DisputesInspection, Error> {
+	let keystore = KeystoreContainer::new(&config.keystore)?.keystore();
+	let db = open_database(&config.database)?;
+	let (client, _, _, _task_manager) = new_chain_ops(&mut config)?;
+
+	let dispute_coordinator_config = DisputeCoordinatorConfig {
+		col_dispute_data: parachains_db::REAL_COLUMNS.col_dispute_coordinator_data,
+	};
+	Ok(polkadot_rpc::disputes::inspect_disputes(
+		&*client,
+		&keystore,
+		&*db,
+		&dispute_coordinator_config,
+	)?)
+}
+
 fn revert_chain_selection(db: Arc<dyn Database>, hash: Hash) -> sp_blockchain::Result<()> {
 	let config = chain_selection_subsystem::Config {
 		col_data: parachains_db::REAL_COLUMNS.col_chain_selection_data,
@@ -20,7 +20,10 @@ use polkadot_overseer::{DummySubsystem, InitializedOverseerBuilder, SubsystemErr
 use sp_core::traits::SpawnNamed;
 
 use polkadot_availability_distribution::IncomingRequestReceivers;
-use polkadot_node_core_approval_voting::{Config as ApprovalVotingConfig, RealAssignmentCriteria};
+use polkadot_node_core_approval_voting::{
+	timeline::{ApprovalTimelines, TimedAssignmentCriteria},
+	Config as ApprovalVotingConfig, RealAssignmentCriteria,
+};
 use polkadot_node_core_av_store::Config as AvailabilityConfig;
 use polkadot_node_core_candidate_validation::Config as CandidateValidationConfig;
 use polkadot_node_core_chain_selection::Config as ChainSelectionConfig;
@@ -43,7 +46,7 @@ use sc_authority_discovery::Service as AuthorityDiscoveryService;
 use sc_client_api::AuxStore;
 use sc_keystore::LocalKeystore;
 use sc_network::{NetworkStateInfo, NotificationService};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 pub use polkadot_approval_distribution::ApprovalDistribution as ApprovalDistributionSubsystem;
 pub use polkadot_availability_bitfield_distribution::BitfieldDistribution as BitfieldDistributionSubsystem;
@@ -145,6 +148,11 @@ pub struct ExtendedOverseerGenArgs {
 	/// Enable approval-voting-parallel subsystem and disable the standalone approval-voting and
 	/// approval-distribution subsystems.
 	pub enable_approval_voting_parallel: bool,
+	/// The approval timelines of recent blocks, recorded by approval-voting.
+	pub approval_timelines: ApprovalTimelines,
+	/// Capture the received approval-distribution traffic into this file. Only supported by
+	/// approval-voting-parallel.
+	pub approval_traffic_capture: Option<PathBuf>,
 }
 
 /// Obtain a prepared validator `Overseer`, that is initialized with all default values.
@@ -185,6 +193,8 @@ pub fn validator_overseer_builder<Spawner, RuntimeClient>(
 		chain_selection_config,
 		fetch_chunks_threshold,
 		enable_approval_voting_parallel,
+		approval_timelines,
+		approval_traffic_capture,
 	}: ExtendedOverseerGenArgs,
 ) -> Result<
 	InitializedOverseerBuilder<
@@ -229,6 +239,14 @@ where
 {
 	use polkadot_node_subsystem_util::metrics::Metrics;
 
+	if let Some(path) = approval_traffic_capture {
+		gum::warn!(
+			?path,
+			"Capturing the approval traffic is only supported with parallel approval voting, \
+			 nothing will be captured",
+		);
+	}
+
 	let metrics = <OverseerMetrics as MetricsTrait>::register(registry)?;
 	let notification_sinks = Arc::new(Mutex::new(HashMap::new()));
 
@@ -326,16 +344,22 @@ where
 		.approval_distribution(ApprovalDistributionSubsystem::new(
 			approval_voting_parallel_metrics.approval_distribution_metrics(),
 			approval_voting_config.slot_duration_millis,
-			Arc::new(RealAssignmentCriteria {}),
-		))
-		.approval_voting(ApprovalVotingSubsystem::with_config(
-			approval_voting_config.clone(),
-			parachains_db.clone(),
-			keystore.clone(),
-			Box::new(sync_service.clone()),
-			approval_voting_parallel_metrics.approval_voting_metrics(),
-			Arc::new(spawner.clone()),
-		))
+			Arc::new(TimedAssignmentCriteria::new(
+				RealAssignmentCriteria {},
+				approval_timelines.clone(),
+			)),
+		))
+		.approval_voting(
+			ApprovalVotingSubsystem::with_config(
+				approval_voting_config.clone(),
+				parachains_db.clone(),
+				keystore.clone(),
+				Box::new(sync_service.clone()),
+				approval_voting_parallel_metrics.approval_voting_metrics(),
+				Arc::new(spawner.clone()),
+			)
+			.with_timelines(approval_timelines),
+		)
 		.approval_voting_parallel(DummySubsystem)
 		.gossip_support(GossipSupportSubsystem::new(
 			keystore.clone(),
@@ -409,6 +433,8 @@ pub fn validator_with_parallel_overseer_builder<Spawner, RuntimeClient>(
 		chain_selection_config,
 		fetch_chunks_threshold,
 		enable_approval_voting_parallel,
+		approval_timelines,
+		approval_traffic_capture,
 	}: ExtendedOverseerGenArgs,
 ) -> Result<
 	InitializedOverseerBuilder<
@@ -549,15 +575,19 @@ where
 		))
 		.approval_distribution(DummySubsystem)
 		.approval_voting(DummySubsystem)
-		.approval_voting_parallel(ApprovalVotingParallelSubsystem::with_config(
-			approval_voting_config,
-			parachains_db.clone(),
-			keystore.clone(),
-			Box::new(sync_service.clone()),
-			approval_voting_parallel_metrics,
-			spawner.clone(),
-			overseer_message_channel_capacity_override,
-		))
+		.approval_voting_parallel(
+			ApprovalVotingParallelSubsystem::with_config(
+				approval_voting_config,
+				parachains_db.clone(),
+				keystore.clone(),
+				Box::new(sync_service.clone()),
+				approval_voting_parallel_metrics,
+				spawner.clone(),
+				overseer_message_channel_capacity_override,
+			)
+			.with_timelines(approval_timelines)
+			.with_traffic_capture(approval_traffic_capture),
+		)
 		.gossip_support(GossipSupportSubsystem::new(
 			keystore.clone(),
 			authority_discovery_service.clone(),
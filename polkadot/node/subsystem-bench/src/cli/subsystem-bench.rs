@@ -20,7 +20,9 @@
 use clap::Parser;
 use color_eyre::eyre;
 use colored::Colorize;
-use polkadot_subsystem_bench::{approval, availability, configuration, statement};
+use polkadot_subsystem_bench::{
+	approval, availability, collator, configuration, disputes, statement,
+};
 use pyroscope::PyroscopeAgent;
 use pyroscope_pprofrs::{pprof_backend, PprofConfig};
 use serde::{Deserialize, Serialize};
@@ -40,8 +42,14 @@ pub enum TestObjective {
 	DataAvailabilityWrite,
 	/// Benchmark the approval-voting and approval-distribution subsystems.
 	ApprovalVoting(approval::ApprovalsOptions),
+	/// Replay approval-distribution traffic captured from a node.
+	ApprovalReplay(approval::replay::ApprovalReplayOptions),
 	// Benchmark the statement-distribution subsystem
 	StatementDistribution,
+	/// Benchmark dispute-coordinator and dispute-distribution under a dispute storm.
+	DisputeStorm(disputes::DisputesOptions),
+	/// Benchmark the validator side of the collator-protocol subsystem.
+	CollatorProtocol(collator::CollatorProtocolOptions),
 }
 
 impl std::fmt::Display for TestObjective {
@@ -53,7 +61,10 @@ impl std::fmt::Display for TestObjective {
 				Self::DataAvailabilityRead(_) => "DataAvailabilityRead",
 				Self::DataAvailabilityWrite => "DataAvailabilityWrite",
 				Self::ApprovalVoting(_) => "ApprovalVoting",
+				Self::ApprovalReplay(_) => "ApprovalReplay",
 				Self::StatementDistribution => "StatementDistribution",
+				Self::DisputeStorm(_) => "DisputeStorm",
+				Self::CollatorProtocol(_) => "CollatorProtocol",
 			}
 		)
 	}
@@ -163,12 +174,27 @@ impl BenchCli {
 						approval::prepare_test(test_config.clone(), options.clone(), true);
 					env.runtime().block_on(approval::bench_approvals(&mut env, state))
 				},
+				TestObjective::ApprovalReplay(ref options) => {
+					let (mut env, state) =
+						approval::replay::prepare_test(test_config.clone(), options.clone(), true);
+					env.runtime().block_on(approval::replay::bench_approval_replay(&mut env, state))
+				},
 				TestObjective::StatementDistribution => {
 					let state = statement::TestState::new(&test_config);
 					let (mut env, _protocol_config) = statement::prepare_test(&state, true);
 					env.runtime()
 						.block_on(statement::benchmark_statement_distribution(&mut env, &state))
 				},
+				TestObjective::DisputeStorm(ref options) => {
+					let state = disputes::TestState::new(&test_config, options);
+					let mut env = disputes::prepare_test(&state, true);
+					env.runtime().block_on(disputes::benchmark_dispute_storm(&mut env, &state))
+				},
+				TestObjective::CollatorProtocol(ref options) => {
+					let state = collator::TestState::new(&test_config, options);
+					let mut env = collator::prepare_test(&state, true);
+					env.runtime().block_on(collator::benchmark_collator_protocol(&mut env, &state))
+				},
 			};
 			println!("\n{}\n{}", benchmark_name.purple(), usage);
 		}
@@ -95,6 +95,7 @@ use tokio::time::sleep;
 mod helpers;
 mod message_generator;
 mod mock_chain_selection;
+pub mod replay;
 mod test_message;
 
 pub(crate) const LOG_TARGET: &str = "subsystem-bench::approval";
@@ -0,0 +1,250 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Replays approval-distribution traffic captured from a node with `--approval-traffic-capture`
+//! against a real approval-distribution subsystem.
+//!
+//! The captured messages are sent at the same pace they were received by the node, with the
+//! clock of the subsystem moved back to the time of the capture, so that the assignments are
+//! accepted as if they were received live. Approval voting is mocked and accepts everything,
+//! the runtime answers the session info recorded in the capture.
+
+use crate::{
+	approval::helpers::PastSystemClock,
+	configuration::TestConfiguration,
+	dummy_builder,
+	environment::{TestEnvironment, TestEnvironmentDependencies},
+	mock::AlwaysSupportsParachains,
+	network::new_network,
+	usage::BenchmarkUsage,
+};
+use colored::Colorize;
+use futures::FutureExt;
+use overseer::{metrics::Metrics as OverseerMetrics, MetricsTrait};
+use polkadot_approval_distribution::{
+	metrics::Metrics as ApprovalDistributionMetrics, ApprovalDistribution,
+};
+use polkadot_node_core_approval_voting::RealAssignmentCriteria;
+use polkadot_node_core_approval_voting_parallel::capture::{
+	read_capture, CaptureHeader, CaptureReader, CapturedEvent, CapturedMessage,
+};
+use polkadot_node_primitives::approval::time::{Clock, SystemClock, TICK_DURATION_MILLIS};
+use polkadot_node_subsystem::{
+	messages::{RuntimeApiMessage, RuntimeApiRequest},
+	overseer, AllMessages, Overseer, OverseerConnector, SpawnGlue, SpawnedSubsystem,
+	SubsystemError,
+};
+use polkadot_node_subsystem_test_helpers::mock::new_block_import_info;
+use polkadot_node_subsystem_types::OverseerSignal;
+use polkadot_overseer::Handle as OverseerHandleReal;
+use polkadot_primitives::{NodeFeatures, SessionIndex, SessionInfo};
+use serde::{Deserialize, Serialize};
+use std::{
+	collections::HashMap,
+	path::Path,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+	time::{Duration, Instant},
+};
+use tokio::time::sleep;
+
+const LOG_TARGET: &str = "subsystem-bench::approval-replay";
+
+/// Parameters specific to the approval traffic replay
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct ApprovalReplayOptions {
+	#[clap(short, long)]
+	/// The path of the capture written by the node.
+	pub capture: String,
+}
+
+/// The captured traffic to replay.
+pub struct ReplayState {
+	header: CaptureHeader,
+	events: CaptureReader,
+	// The difference in ticks between the real clock and the time of the capture.
+	delta_ticks: Arc<AtomicU64>,
+}
+
+/// A runtime-api mock answering the session info recorded in the capture.
+struct ReplayRuntimeApi {
+	session_infos: HashMap<SessionIndex, SessionInfo>,
+}
+
+#[overseer::subsystem(RuntimeApi, error=SubsystemError, prefix=self::overseer)]
+impl<Context> ReplayRuntimeApi {
+	fn start(self, ctx: Context) -> SpawnedSubsystem {
+		let future = self.run(ctx).map(|_| Ok(())).boxed();
+
+		SpawnedSubsystem { name: "test-environment", future }
+	}
+}
+
+#[overseer::contextbounds(RuntimeApi, prefix = self::overseer)]
+impl ReplayRuntimeApi {
+	async fn run<Context>(self, mut ctx: Context) {
+		loop {
+			let msg = ctx.recv().await.expect("Overseer never fails us");
+			match msg {
+				orchestra::FromOrchestra::Signal(signal) =>
+					if signal == OverseerSignal::Conclude {
+						return
+					},
+				orchestra::FromOrchestra::Communication { msg } => match msg {
+					RuntimeApiMessage::Request(
+						_relay_parent,
+						RuntimeApiRequest::SessionInfo(session_index, sender),
+					) => {
+						let _ = sender.send(Ok(self.session_infos.get(&session_index).cloned()));
+					},
+					RuntimeApiMessage::Request(
+						_relay_parent,
+						RuntimeApiRequest::SessionExecutorParams(_session_index, sender),
+					) => {
+						let _ = sender.send(Ok(Some(Default::default())));
+					},
+					RuntimeApiMessage::Request(
+						_relay_parent,
+						RuntimeApiRequest::NodeFeatures(_session_index, sender),
+					) => {
+						let _ = sender.send(Ok(NodeFeatures::EMPTY));
+					},
+					_ => unimplemented!("Unexpected runtime-api message"),
+				},
+			}
+		}
+	}
+}
+
+/// Reads the capture and builds an environment running approval-distribution.
+pub fn prepare_test(
+	mut config: TestConfiguration,
+	options: ApprovalReplayOptions,
+	with_prometheus_endpoint: bool,
+) -> (TestEnvironment, ReplayState) {
+	// A first pass over the capture collects the session infos and counts the blocks, the events
+	// are read again while replaying.
+	let path = Path::new(&options.capture);
+	let (header, events) = read_capture(path).expect("Capture is readable");
+	let mut session_infos = HashMap::new();
+	let mut num_events = 0;
+	let mut num_blocks = 0;
+	for event in events {
+		match event.expect("Capture is valid").message {
+			CapturedMessage::SessionInfo(session_index, session_info) => {
+				session_infos.insert(session_index, session_info);
+			},
+			CapturedMessage::NewBlocks(blocks) => num_blocks += blocks.len(),
+			_ => {},
+		}
+		num_events += 1;
+	}
+	// The usage is reported per captured block.
+	config.num_blocks = num_blocks.max(1);
+	gum::info!(target: LOG_TARGET, "{}", format!("Replaying {} events over {} blocks", num_events, config.num_blocks).bright_blue());
+	let (_, events) = read_capture(path).expect("Capture is readable");
+
+	let dependencies = TestEnvironmentDependencies::default();
+	let authorities = config.generate_authorities();
+	// The network is not emulated, messages sent by approval-distribution are dropped.
+	let (network, _network_interface, _network_receiver) =
+		new_network(&config, &dependencies, &authorities, vec![]);
+
+	let delta_ticks = Arc::new(AtomicU64::new(0));
+	let approval_distribution = ApprovalDistribution::new_with_clock(
+		ApprovalDistributionMetrics::try_register(&dependencies.registry).unwrap(),
+		header.slot_duration_millis,
+		Arc::new(PastSystemClock::new(SystemClock {}, delta_ticks.clone())),
+		Arc::new(RealAssignmentCriteria {}),
+	);
+
+	let overseer_connector = OverseerConnector::with_event_capacity(64000);
+	let overseer_metrics = OverseerMetrics::try_register(&dependencies.registry).unwrap();
+	let spawn_task_handle = dependencies.task_manager.spawn_handle();
+	let (overseer, raw_handle) = dummy_builder!(spawn_task_handle, overseer_metrics)
+		.replace_runtime_api(|_| ReplayRuntimeApi { session_infos })
+		.replace_approval_distribution(|_| approval_distribution)
+		.build_with_connector(overseer_connector)
+		.expect("Should not fail");
+	let overseer_handle = OverseerHandleReal::new(raw_handle);
+
+	let env = TestEnvironment::new(
+		dependencies,
+		config,
+		network,
+		overseer,
+		overseer_handle,
+		authorities,
+		with_prometheus_endpoint,
+	);
+
+	(env, ReplayState { header, events, delta_ticks })
+}
+
+/// Replays the captured events at the pace they were captured.
+pub async fn bench_approval_replay(
+	env: &mut TestEnvironment,
+	state: ReplayState,
+) -> BenchmarkUsage {
+	let ReplayState { header, events, delta_ticks } = state;
+
+	let test_start = Instant::now();
+	delta_ticks.store(
+		SystemClock {}
+			.tick_now()
+			.saturating_sub(header.started_at_millis / TICK_DURATION_MILLIS),
+		Ordering::SeqCst,
+	);
+
+	let mut current_block = 0;
+	let mut max_lag = Duration::ZERO;
+	for event in events {
+		let CapturedEvent { elapsed_millis, message } = event.expect("Capture is valid");
+		let due = Duration::from_millis(elapsed_millis);
+		match due.checked_sub(test_start.elapsed()) {
+			Some(wait) => sleep(wait).await,
+			None => max_lag = max_lag.max(test_start.elapsed() - due),
+		}
+
+		match &message {
+			CapturedMessage::NewBlocks(blocks) => {
+				current_block += blocks.len();
+				env.metrics().set_current_block(current_block);
+			},
+			CapturedMessage::BlockFinalized(hash, number) => {
+				env.overseer_handle()
+					.clone()
+					.block_finalized(new_block_import_info(*hash, *number))
+					.await;
+			},
+			_ => {},
+		}
+
+		if let Some(msg) = message.into_approval_distribution_message() {
+			env.send_message(AllMessages::ApprovalDistribution(msg)).await;
+		}
+	}
+
+	gum::info!(target: LOG_TARGET, "Replay finished in {}", format!("{:?}ms", test_start.elapsed().as_millis()).cyan());
+	gum::info!(target: LOG_TARGET, "Maximum lag behind the capture: {}", format!("{:?}ms", max_lag.as_millis()).red());
+
+	env.stop().await;
+	env.collect_resource_usage(&["approval-distribution"], true)
+}
@@ -75,6 +75,9 @@ pub enum Strategy {
 	/// Fetch the full availability datafrom backers first. Saves CPU as we don't need to
 	/// re-construct from chunks. Typically this is only faster if nodes have enough bandwidth.
 	FullFromBackers,
+	/// Recovery from systematic chunks, fetched from validators and backers in parallel based on
+	/// their estimated bandwidth.
+	ParallelSystematic,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize, clap::Parser)]
@@ -239,6 +242,13 @@ pub fn prepare_test(
 					Metrics::try_register(&dependencies.registry).unwrap(),
 					RecoveryStrategyKind::SystematicChunks,
 				),
+				Strategy::ParallelSystematic =>
+					AvailabilityRecoverySubsystem::with_recovery_strategy_kind(
+						collation_req_receiver,
+						&state.req_protocol_names,
+						Metrics::try_register(&dependencies.registry).unwrap(),
+						RecoveryStrategyKind::ParallelSystematicChunks,
+					),
 			};
 
 			// Use a mocked av-store.
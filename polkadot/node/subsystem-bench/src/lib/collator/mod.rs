@@ -0,0 +1,304 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	dummy_builder,
+	environment::{TestEnvironment, TestEnvironmentDependencies, MAX_BLOCK_TIME},
+	mock::{
+		candidate_backing::MockCandidateBacking,
+		chain_api::{ChainApiState, MockChainApi},
+		network_bridge::{MockNetworkBridgeRx, MockNetworkBridgeTx},
+		prospective_parachains::MockProspectiveParachains,
+		runtime_api::{MockRuntimeApi, MockRuntimeApiCoreState},
+		AlwaysSupportsParachains,
+	},
+	network::{new_network, NetworkEmulatorHandle, NetworkInterface, NetworkInterfaceReceiver},
+	usage::{BenchmarkUsage, ResourceUsage},
+	NODE_UNDER_TEST,
+};
+use colored::Colorize;
+use itertools::Itertools;
+use polkadot_collator_protocol::{CollatorProtocolSubsystem, ProtocolSide};
+use polkadot_node_metrics::metrics::Metrics;
+use polkadot_node_network_protocol::{
+	peer_set::CollationVersion, v2 as protocol_v2, ObservedRole, Versioned,
+};
+use polkadot_node_subsystem::{
+	messages::{AllMessages, CollatorProtocolMessage, NetworkBridgeEvent},
+	TimeoutExt,
+};
+use polkadot_overseer::{
+	Handle as OverseerHandle, Overseer, OverseerConnector, OverseerMetrics, SpawnGlue,
+};
+use polkadot_primitives::{Hash, ValidatorId, ValidatorIndex};
+use sc_keystore::LocalKeystore;
+use sc_service::SpawnTaskHandle;
+use serde::{Deserialize, Serialize};
+use sp_core::Pair;
+use sp_keystore::{Keystore, KeystorePtr};
+use sp_runtime::RuntimeAppPublic;
+use std::{
+	collections::HashMap,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+pub use test_state::TestState;
+use test_state::OWN_PARA_ID;
+
+mod test_state;
+
+const LOG_TARGET: &str = "subsystem-bench::collator-protocol";
+
+/// Parameters specific to the collator protocol benchmark
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct CollatorProtocolOptions {
+	#[clap(short, long, default_value_t = 20)]
+	/// The number of collators advertising a collation in every block, at most the number of
+	/// connected peers. They are spread over the allowed ancestry of the leaf.
+	pub n_collators: usize,
+	#[clap(short, long, default_value_t = 5 * 1024 * 1024)]
+	/// The size of the PoV of every collation.
+	pub pov_size: usize,
+}
+
+fn make_keystore() -> KeystorePtr {
+	let keystore: KeystorePtr = Arc::new(LocalKeystore::in_memory());
+	Keystore::sr25519_generate_new(&*keystore, ValidatorId::ID, Some("//Node0"))
+		.expect("Insert key into keystore");
+	keystore
+}
+
+fn build_overseer(
+	state: &TestState,
+	network: NetworkEmulatorHandle,
+	network_interface: NetworkInterface,
+	network_receiver: NetworkInterfaceReceiver,
+	dependencies: &TestEnvironmentDependencies,
+) -> (Overseer<SpawnGlue<SpawnTaskHandle>, AlwaysSupportsParachains>, OverseerHandle) {
+	let overseer_connector = OverseerConnector::with_event_capacity(64000);
+	let overseer_metrics = OverseerMetrics::try_register(&dependencies.registry).unwrap();
+	let spawn_task_handle = dependencies.task_manager.spawn_handle();
+	let mock_runtime_api = MockRuntimeApi::new(
+		state.config.clone(),
+		state.test_authorities.clone(),
+		state.candidate_receipts.clone(),
+		Default::default(),
+		Default::default(),
+		0,
+		MockRuntimeApiCoreState::Scheduled,
+	);
+	let chain_api_state = ChainApiState { block_headers: state.block_headers.clone() };
+	let mock_chain_api = MockChainApi::new(chain_api_state);
+	let mock_prospective_parachains =
+		MockProspectiveParachains::new(state.pvd.clone(), state.minimum_relay_parents());
+	let mock_candidate_backing = MockCandidateBacking::new(
+		state.config.clone(),
+		state
+			.test_authorities
+			.validator_pairs
+			.get(NODE_UNDER_TEST as usize)
+			.unwrap()
+			.clone(),
+		state.pvd.clone(),
+		vec![ValidatorIndex(NODE_UNDER_TEST)],
+		state.collations_by_hash(),
+	);
+	let subsystem = CollatorProtocolSubsystem::new(ProtocolSide::Validator {
+		keystore: make_keystore(),
+		eviction_policy: Default::default(),
+		metrics: Metrics::try_register(&dependencies.registry).unwrap(),
+	});
+	let network_bridge_tx = MockNetworkBridgeTx::new(
+		network,
+		network_interface.subsystem_sender(),
+		state.test_authorities.clone(),
+	);
+	let network_bridge_rx = MockNetworkBridgeRx::new(network_receiver, None, false);
+
+	let dummy = dummy_builder!(spawn_task_handle, overseer_metrics)
+		.replace_runtime_api(|_| mock_runtime_api)
+		.replace_chain_api(|_| mock_chain_api)
+		.replace_prospective_parachains(|_| mock_prospective_parachains)
+		.replace_candidate_backing(|_| mock_candidate_backing)
+		.replace_collator_protocol(|_| subsystem)
+		.replace_network_bridge_tx(|_| network_bridge_tx)
+		.replace_network_bridge_rx(|_| network_bridge_rx);
+	let (overseer, raw_handle) = dummy.build_with_connector(overseer_connector).unwrap();
+	let overseer_handle = OverseerHandle::new(raw_handle);
+
+	(overseer, overseer_handle)
+}
+
+pub fn prepare_test(state: &TestState, with_prometheus_endpoint: bool) -> TestEnvironment {
+	let dependencies = TestEnvironmentDependencies::default();
+	let (network, network_interface, network_receiver) = new_network(
+		&state.config,
+		&dependencies,
+		&state.test_authorities,
+		vec![Arc::new(state.clone())],
+	);
+	let (overseer, overseer_handle) =
+		build_overseer(state, network.clone(), network_interface, network_receiver, &dependencies);
+
+	TestEnvironment::new(
+		dependencies,
+		state.config.clone(),
+		network,
+		overseer,
+		overseer_handle,
+		state.test_authorities.clone(),
+		with_prometheus_endpoint,
+	)
+}
+
+pub async fn benchmark_collator_protocol(
+	env: &mut TestEnvironment,
+	state: &TestState,
+) -> BenchmarkUsage {
+	state.reset_trackers();
+
+	let config = env.config().clone();
+	env.metrics().set_n_validators(config.n_validators);
+	env.metrics().set_n_cores(config.n_cores);
+
+	// Collators are emulated by connected peers only, their requests would fail otherwise.
+	let collators = state
+		.collators
+		.iter()
+		.filter(|collator| {
+			state
+				.test_authorities
+				.peer_id_to_authority
+				.get(&collator.peer_id)
+				.is_some_and(|authority| env.network().is_peer_connected(authority))
+		})
+		.cloned()
+		.collect_vec();
+	gum::info!(target: LOG_TARGET, "{} collators of para {:?} connected", collators.len(), OWN_PARA_ID);
+
+	// With async backing the node under test fetches and seconds up to `max_candidate_depth + 1`
+	// collations per relay parent.
+	let seconded_limit = config.max_candidate_depth as usize + 1;
+	let mut advertised: HashMap<Hash, usize> = HashMap::new();
+	let mut fetch_times = Vec::with_capacity(state.block_infos.len());
+
+	let test_start = Instant::now();
+	for block_info in state.block_infos.iter() {
+		let block_num = block_info.number as usize;
+		gum::info!(target: LOG_TARGET, "Current block {}/{} {:?}", block_num, config.num_blocks, block_info.hash);
+		env.metrics().set_current_block(block_num);
+		let block_start = Instant::now();
+		env.import_block(block_info.clone()).await;
+
+		// Collators can declare only once the para is assigned to the node under test.
+		if block_num == 1 {
+			for collator in collators.iter() {
+				let signature =
+					collator.pair.sign(&protocol_v2::declare_signature_payload(&collator.peer_id));
+				for message in [
+					NetworkBridgeEvent::PeerConnected(
+						collator.peer_id,
+						ObservedRole::Full,
+						CollationVersion::V2.into(),
+						None,
+					),
+					NetworkBridgeEvent::PeerMessage(
+						collator.peer_id,
+						Versioned::V2(protocol_v2::CollatorProtocolMessage::Declare(
+							collator.pair.public(),
+							OWN_PARA_ID,
+							signature,
+						)),
+					),
+				] {
+					env.send_message(AllMessages::CollatorProtocol(
+						CollatorProtocolMessage::NetworkBridgeUpdate(message),
+					))
+					.await;
+				}
+			}
+		}
+
+		for (collator_idx, collator) in collators.iter().enumerate() {
+			let Some(relay_parent) = state.advertisement_relay_parent(block_info, collator_idx)
+			else {
+				continue
+			};
+			let (receipt, _) = &state.collations.get(&relay_parent).unwrap()[collator_idx];
+			*advertised.entry(relay_parent).or_default() += 1;
+
+			let message = Versioned::V2(protocol_v2::CollatorProtocolMessage::AdvertiseCollation {
+				relay_parent,
+				candidate_hash: receipt.hash(),
+				parent_head_data_hash: state.pvd.parent_head.hash(),
+			});
+			env.send_message(AllMessages::CollatorProtocol(
+				CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::PeerMessage(
+					collator.peer_id,
+					message,
+				)),
+			))
+			.await;
+		}
+
+		// Wait until every relay parent got as many collations fetched as were advertised on it, up
+		// to the seconded limit.
+		async {
+			loop {
+				let pending = advertised
+					.iter()
+					.filter(|(relay_parent, count)| {
+						state.collations_fetched(relay_parent) < (**count).min(seconded_limit)
+					})
+					.count();
+				gum::debug!(target: LOG_TARGET, "{} relay parents waiting for fetches", pending);
+
+				if pending == 0 {
+					break;
+				}
+				tokio::time::sleep(Duration::from_millis(50)).await;
+			}
+		}
+		.timeout(MAX_BLOCK_TIME)
+		.await
+		.unwrap_or_else(|| {
+			panic!("Collations not fetched within {}ms", MAX_BLOCK_TIME.as_millis())
+		});
+
+		let block_time = block_start.elapsed().as_millis() as u64;
+		fetch_times.push(block_time as f64);
+		env.metrics().set_block_time(block_time);
+		gum::info!(target: LOG_TARGET, "Collations fetched in {}", format!("{:?}ms", block_time).cyan());
+	}
+
+	let collations_fetched: usize = advertised
+		.keys()
+		.map(|relay_parent| state.collations_fetched(relay_parent))
+		.sum();
+	let duration: u128 = test_start.elapsed().as_millis();
+	gum::info!(target: LOG_TARGET, "{} collations fetched in {}", collations_fetched, format!("{:?}ms", duration).cyan());
+	gum::info!(target: LOG_TARGET,
+		"Avg block time: {}",
+		format!("{} ms", test_start.elapsed().as_millis() / env.config().num_blocks as u128).red()
+	);
+
+	env.stop().await;
+	let mut usage = env.collect_resource_usage(&["collator-protocol"], false);
+	usage.latency = vec![ResourceUsage::from_per_block("Collation fetches", &fetch_times)];
+	usage
+}
@@ -0,0 +1,247 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	collator::CollatorProtocolOptions,
+	configuration::{TestAuthorities, TestConfiguration},
+	network::{HandleNetworkMessage, NetworkMessage},
+	NODE_UNDER_TEST,
+};
+use codec::Encode;
+use polkadot_node_network_protocol::request_response::{v2::CollationFetchingResponse, Requests};
+use polkadot_node_primitives::{BlockData, PoV};
+use polkadot_node_subsystem_test_helpers::mock::new_block_import_info;
+use polkadot_overseer::BlockInfo;
+use polkadot_primitives::{
+	vstaging::{
+		CandidateReceiptV2 as CandidateReceipt,
+		CommittedCandidateReceiptV2 as CommittedCandidateReceipt, MutateDescriptorV2,
+	},
+	BlockNumber, CandidateHash, CollatorPair, CoreIndex, Hash, HeadData, Header, Id,
+	PersistedValidationData, SessionIndex,
+};
+use polkadot_primitives_test_helpers::{
+	dummy_committed_candidate_receipt_v2, dummy_head_data, dummy_pvd,
+};
+use sc_network::ProtocolName;
+use sc_network_types::PeerId;
+use sp_core::{Pair, H256};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+};
+
+const SESSION_INDEX: SessionIndex = 0;
+
+/// The para the node under test is assigned to, it backs on core 0.
+pub const OWN_PARA_ID: Id = Id::new(1);
+
+/// An emulated collator, connected over one of the emulated validator peers.
+#[derive(Clone)]
+pub struct Collator {
+	pub peer_id: PeerId,
+	pub pair: CollatorPair,
+}
+
+#[derive(Clone)]
+pub struct TestState {
+	// Full test config
+	pub config: TestConfiguration,
+	// Authority keys for the network emulation.
+	pub test_authorities: TestAuthorities,
+	// Relay chain block infos
+	pub block_infos: Vec<BlockInfo>,
+	// Relay chain block headers
+	pub block_headers: HashMap<H256, Header>,
+	// Map from generated candidate receipts
+	pub candidate_receipts: HashMap<H256, Vec<CandidateReceipt>>,
+	// PersistedValidationData, we use one for all collations
+	pub pvd: PersistedValidationData,
+	// Collators of our para
+	pub collators: Vec<Collator>,
+	// Collations built by every collator in each block
+	pub collations: HashMap<H256, Vec<(CommittedCandidateReceipt, PoV)>>,
+	// Tracks how many collations were fetched in each block
+	pub fetches_tracker: HashMap<H256, Arc<AtomicUsize>>,
+}
+
+impl TestState {
+	pub fn new(config: &TestConfiguration, options: &CollatorProtocolOptions) -> Self {
+		let test_authorities = config.generate_authorities();
+		// Collators are emulated by the validator peers, except the node under test.
+		let collators = test_authorities
+			.peer_ids
+			.iter()
+			.enumerate()
+			.filter(|(index, _)| *index != NODE_UNDER_TEST as usize)
+			.take(options.n_collators)
+			.map(|(index, peer_id)| Collator {
+				peer_id: *peer_id,
+				pair: CollatorPair::from_string(&format!("//Collator{}", index), None)
+					.expect("Collator keys are valid"),
+			})
+			.collect();
+		let mut state = Self {
+			config: config.clone(),
+			test_authorities,
+			block_infos: (1..=config.num_blocks).map(generate_block_info).collect(),
+			block_headers: Default::default(),
+			candidate_receipts: Default::default(),
+			pvd: dummy_pvd(dummy_head_data(), 0),
+			collators,
+			collations: Default::default(),
+			fetches_tracker: Default::default(),
+		};
+
+		state.block_headers = state.block_infos.iter().map(generate_block_header).collect();
+
+		let pov = PoV { block_data: BlockData(vec![0; options.pov_size]) };
+		for block_info in state.block_infos.iter() {
+			for core_idx in 0..config.n_cores {
+				let mut receipt = dummy_committed_candidate_receipt_v2(block_info.hash);
+				receipt.descriptor.set_para_id(Id::new(core_idx as u32 + 1));
+				receipt.descriptor.set_core_index(CoreIndex(core_idx as u32));
+				receipt.descriptor.set_session_index(SESSION_INDEX);
+				state
+					.candidate_receipts
+					.entry(block_info.hash)
+					.or_default()
+					.push(receipt.to_plain());
+			}
+
+			// Every collator builds a different candidate on top of the same parent head.
+			let collations = (0..state.collators.len())
+				.map(|collator_idx| {
+					let mut receipt = dummy_committed_candidate_receipt_v2(block_info.hash);
+					receipt.descriptor.set_para_id(OWN_PARA_ID);
+					receipt.descriptor.set_core_index(CoreIndex(0));
+					receipt.descriptor.set_session_index(SESSION_INDEX);
+					receipt.descriptor.set_persisted_validation_data_hash(state.pvd.hash());
+					receipt.descriptor.set_pov_hash(pov.hash());
+					receipt.commitments.head_data = HeadData((collator_idx as u32).encode());
+					(receipt, pov.clone())
+				})
+				.collect();
+			state.collations.insert(block_info.hash, collations);
+			state.fetches_tracker.insert(block_info.hash, Arc::new(AtomicUsize::new(0)));
+		}
+
+		state
+	}
+
+	pub fn reset_trackers(&self) {
+		self.fetches_tracker.values().for_each(|v| v.store(0, Ordering::SeqCst));
+	}
+
+	/// Number of collations fetched by the node under test on top of `relay_parent`.
+	pub fn collations_fetched(&self, relay_parent: &Hash) -> usize {
+		self.fetches_tracker
+			.get(relay_parent)
+			.expect("Relay chain block hashes are generated at test start")
+			.load(Ordering::SeqCst)
+	}
+
+	/// All collations indexed by candidate hash, the ones the node under test is able to second.
+	pub fn collations_by_hash(&self) -> HashMap<CandidateHash, CommittedCandidateReceipt> {
+		self.collations
+			.values()
+			.flatten()
+			.map(|(receipt, _)| (receipt.hash(), receipt.clone()))
+			.collect()
+	}
+
+	/// Our para can build on every relay parent in the allowed ancestry of a leaf, down to the
+	/// first block.
+	pub fn minimum_relay_parents(&self) -> HashMap<Hash, Vec<(Id, BlockNumber)>> {
+		self.block_infos
+			.iter()
+			.map(|info| {
+				let minimum = info.number.saturating_sub(self.config.allowed_ancestry_len).max(1);
+				(info.hash, vec![(OWN_PARA_ID, minimum)])
+			})
+			.collect()
+	}
+
+	/// The relay parent `collator_idx` advertises its collation on when `leaf` is imported, if
+	/// any. Collators are spread over the allowed ancestry of the leaf, so that every relay parent
+	/// receives advertisements over several blocks.
+	pub fn advertisement_relay_parent(
+		&self,
+		leaf: &BlockInfo,
+		collator_idx: usize,
+	) -> Option<Hash> {
+		let depth = collator_idx % (self.config.allowed_ancestry_len as usize + 1);
+		let number = (leaf.number as usize).checked_sub(depth).filter(|number| *number > 0)?;
+		self.block_infos.get(number - 1).map(|info| info.hash)
+	}
+}
+
+fn generate_block_info(block_num: usize) -> BlockInfo {
+	BlockInfo {
+		parent_hash: Hash::repeat_byte(block_num as u8 - 1),
+		..new_block_import_info(Hash::repeat_byte(block_num as u8), block_num as BlockNumber)
+	}
+}
+
+fn generate_block_header(info: &BlockInfo) -> (H256, Header) {
+	(
+		info.hash,
+		Header {
+			digest: Default::default(),
+			number: info.number,
+			parent_hash: info.parent_hash,
+			extrinsics_root: Default::default(),
+			state_root: Default::default(),
+		},
+	)
+}
+
+#[async_trait::async_trait]
+impl HandleNetworkMessage for TestState {
+	async fn handle(
+		&self,
+		message: NetworkMessage,
+		_node_sender: &mut futures::channel::mpsc::UnboundedSender<NetworkMessage>,
+	) -> Option<NetworkMessage> {
+		match message {
+			NetworkMessage::RequestFromNode(_authority_id, Requests::CollationFetchingV2(req)) => {
+				let payload = req.payload;
+				let (receipt, pov) = self
+					.collations
+					.get(&payload.relay_parent)
+					.and_then(|collations| {
+						collations
+							.iter()
+							.find(|(receipt, _)| receipt.hash() == payload.candidate_hash)
+					})
+					.expect("Only advertised collations are requested")
+					.clone();
+				self.fetches_tracker
+					.get(&payload.relay_parent)
+					.expect("Relay chain block hashes are generated at test start")
+					.fetch_add(1, Ordering::SeqCst);
+
+				let res = CollationFetchingResponse::Collation(receipt.to_plain(), pov);
+				let _ = req.pending_response.send(Ok((res.encode(), ProtocolName::from(""))));
+				None
+			},
+			_ => Some(message),
+		}
+	}
+}
@@ -0,0 +1,296 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	dummy_builder,
+	environment::{TestEnvironment, TestEnvironmentDependencies, GENESIS_HASH, MAX_BLOCK_TIME},
+	mock::{
+		authority_discovery::MockAuthorityDiscovery,
+		availability_recovery::MockAvailabilityRecovery,
+		candidate_validation::MockCandidateValidation,
+		chain_api::{ChainApiState, MockChainApi},
+		network_bridge::{MockNetworkBridgeRx, MockNetworkBridgeTx},
+		runtime_api::{MockRuntimeApi, MockRuntimeApiCoreState},
+		AlwaysSupportsParachains,
+	},
+	network::{new_network, NetworkEmulatorHandle, NetworkInterface, NetworkInterfaceReceiver},
+	usage::{BenchmarkUsage, ResourceUsage},
+	NODE_UNDER_TEST,
+};
+use codec::{Decode, Encode};
+use colored::Colorize;
+use futures::channel::oneshot;
+use itertools::Itertools;
+use polkadot_dispute_distribution::DisputeDistributionSubsystem;
+use polkadot_node_core_dispute_coordinator::{
+	Config as DisputeCoordinatorConfig, DisputeCoordinatorSubsystem,
+};
+use polkadot_node_metrics::metrics::Metrics;
+use polkadot_node_network_protocol::request_response::{
+	v1::DisputeResponse, IncomingRequest, ReqProtocolNames,
+};
+use polkadot_node_subsystem::TimeoutExt;
+use polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter;
+use polkadot_overseer::{
+	Handle as OverseerHandle, Overseer, OverseerConnector, OverseerMetrics, SpawnGlue,
+};
+use polkadot_primitives::{AuthorityDiscoveryId, Block, Hash, ValidatorId, ValidatorIndex};
+use sc_keystore::LocalKeystore;
+use sc_network::request_responses::IncomingRequest as RawIncomingRequest;
+use sc_service::SpawnTaskHandle;
+use serde::{Deserialize, Serialize};
+use sp_keystore::Keystore;
+use sp_runtime::RuntimeAppPublic;
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
+pub use test_state::TestState;
+
+mod test_state;
+
+const LOG_TARGET: &str = "subsystem-bench::disputes";
+
+/// Parameters specific to the dispute storm benchmark
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct DisputesOptions {
+	#[clap(short, long, default_value_t = 10)]
+	/// The number of candidates disputed in every block, at most `n_cores`.
+	pub n_disputes_per_block: usize,
+}
+
+fn make_keystore() -> Arc<LocalKeystore> {
+	let keystore = Arc::new(LocalKeystore::in_memory());
+	Keystore::sr25519_generate_new(&*keystore, ValidatorId::ID, Some("//Node0"))
+		.expect("Insert key into keystore");
+	Keystore::sr25519_generate_new(&*keystore, AuthorityDiscoveryId::ID, Some("//Node0"))
+		.expect("Insert key into keystore");
+	keystore
+}
+
+fn build_overseer(
+	state: &TestState,
+	network: NetworkEmulatorHandle,
+	network_interface: NetworkInterface,
+	network_receiver: NetworkInterfaceReceiver,
+	dependencies: &TestEnvironmentDependencies,
+) -> (Overseer<SpawnGlue<SpawnTaskHandle>, AlwaysSupportsParachains>, OverseerHandle) {
+	let overseer_connector = OverseerConnector::with_event_capacity(64000);
+	let overseer_metrics = OverseerMetrics::try_register(&dependencies.registry).unwrap();
+	let spawn_task_handle = dependencies.task_manager.spawn_handle();
+	let mock_runtime_api = MockRuntimeApi::new(
+		state.config.clone(),
+		state.test_authorities.clone(),
+		state.candidate_receipts.clone(),
+		state.candidate_events.clone(),
+		Default::default(),
+		0,
+		MockRuntimeApiCoreState::Scheduled,
+	);
+	let chain_api_state = ChainApiState { block_headers: state.block_headers.clone() };
+	let mock_chain_api = MockChainApi::new(chain_api_state);
+	let (dispute_req_receiver, dispute_req_cfg) = IncomingRequest::get_config_receiver::<
+		Block,
+		sc_network::NetworkWorker<Block, Hash>,
+	>(&ReqProtocolNames::new(GENESIS_HASH, None));
+	let keystore = make_keystore();
+	let db = DbAdapter::new(kvdb_memorydb::create(1), &[]);
+	let dispute_coordinator = DisputeCoordinatorSubsystem::new(
+		Arc::new(db),
+		DisputeCoordinatorConfig { col_dispute_data: 0 },
+		keystore.clone(),
+		Metrics::try_register(&dependencies.registry).unwrap(),
+		true,
+	);
+	let dispute_distribution = DisputeDistributionSubsystem::new(
+		keystore,
+		dispute_req_receiver,
+		MockAuthorityDiscovery::new(&state.test_authorities),
+		Metrics::try_register(&dependencies.registry).unwrap(),
+	);
+	let network_bridge_tx = MockNetworkBridgeTx::new(
+		network,
+		network_interface.subsystem_sender(),
+		state.test_authorities.clone(),
+	);
+	let network_bridge_rx =
+		MockNetworkBridgeRx::new(network_receiver, Some(dispute_req_cfg), false);
+
+	let dummy = dummy_builder!(spawn_task_handle, overseer_metrics)
+		.replace_runtime_api(|_| mock_runtime_api)
+		.replace_chain_api(|_| mock_chain_api)
+		.replace_availability_recovery(|_| MockAvailabilityRecovery::new())
+		.replace_candidate_validation(|_| MockCandidateValidation::new())
+		.replace_dispute_coordinator(|_| dispute_coordinator)
+		.replace_dispute_distribution(|_| dispute_distribution)
+		.replace_network_bridge_tx(|_| network_bridge_tx)
+		.replace_network_bridge_rx(|_| network_bridge_rx);
+	let (overseer, raw_handle) = dummy.build_with_connector(overseer_connector).unwrap();
+	let overseer_handle = OverseerHandle::new(raw_handle);
+
+	(overseer, overseer_handle)
+}
+
+pub fn prepare_test(state: &TestState, with_prometheus_endpoint: bool) -> TestEnvironment {
+	let dependencies = TestEnvironmentDependencies::default();
+	let (network, network_interface, network_receiver) = new_network(
+		&state.config,
+		&dependencies,
+		&state.test_authorities,
+		vec![Arc::new(state.clone())],
+	);
+	let (overseer, overseer_handle) =
+		build_overseer(state, network.clone(), network_interface, network_receiver, &dependencies);
+
+	TestEnvironment::new(
+		dependencies,
+		state.config.clone(),
+		network,
+		overseer,
+		overseer_handle,
+		state.test_authorities.clone(),
+		with_prometheus_endpoint,
+	)
+}
+
+pub async fn benchmark_dispute_storm(
+	env: &mut TestEnvironment,
+	state: &TestState,
+) -> BenchmarkUsage {
+	state.reset_trackers();
+
+	let config = env.config().clone();
+	env.metrics().set_n_validators(config.n_validators);
+	env.metrics().set_n_cores(config.n_cores);
+
+	// Only connected validators raise disputes and are expected to receive our votes.
+	let connected_validators = state
+		.test_authorities
+		.validator_authority_id
+		.iter()
+		.enumerate()
+		.filter(|(i, id)| *i != NODE_UNDER_TEST as usize && env.network().is_peer_connected(id))
+		.map(|(i, _)| ValidatorIndex(i as u32))
+		.collect_vec();
+	assert!(connected_validators.len() >= 2, "At least two connected validators raise a dispute");
+	// Every dispute is raised by a different pair of validators, so that the receive rate limit
+	// per peer is not hit.
+	let mut voters = connected_validators.iter().cycle().tuples::<(_, _)>();
+
+	let test_start = Instant::now();
+	let mut disputes_raised = 0;
+	let mut confirmation_times = Vec::with_capacity(state.block_infos.len());
+	let mut distribution_times = Vec::with_capacity(state.block_infos.len());
+	for block_info in state.block_infos.iter() {
+		let block_num = block_info.number as usize;
+		gum::info!(target: LOG_TARGET, "Current block {}/{} {:?}", block_num, config.num_blocks, block_info.hash);
+		env.metrics().set_current_block(block_num);
+		let block_start = Instant::now();
+		env.import_block(block_info.clone()).await;
+
+		let disputed_candidates = state.disputed_candidates.get(&block_info.hash).unwrap();
+		let mut responses = Vec::with_capacity(disputed_candidates.len());
+		for candidate_receipt in disputed_candidates {
+			let (&invalid_voter, &valid_voter) = voters.next().expect("Voters are cycled");
+			let request = state.dispute_request(candidate_receipt, invalid_voter, valid_voter);
+			let (pending_response, response_receiver) = oneshot::channel();
+			let index = invalid_voter.0 as usize;
+			let incoming = RawIncomingRequest {
+				peer: state.test_authorities.peer_ids[index],
+				payload: request.encode(),
+				pending_response,
+			};
+			env.network()
+				.send_request_from_peer(
+					&state.test_authorities.validator_authority_id[index],
+					incoming,
+				)
+				.expect("Dispute is raised by a connected validator");
+			responses.push(response_receiver);
+		}
+		disputes_raised += disputed_candidates.len();
+
+		let mut disputes_confirmed = 0;
+		for response in responses {
+			let confirmed = response
+				.timeout(MAX_BLOCK_TIME)
+				.await
+				.unwrap_or_else(|| {
+					panic!("No dispute response within {}ms", MAX_BLOCK_TIME.as_millis())
+				})
+				.ok()
+				.and_then(|response| response.result.ok())
+				.and_then(|response| DisputeResponse::decode(&mut response.as_ref()).ok())
+				.is_some_and(|response| response == DisputeResponse::Confirmed);
+			if confirmed {
+				disputes_confirmed += 1;
+			}
+		}
+		confirmation_times.push(block_start.elapsed().as_millis() as f64);
+		gum::debug!(target: LOG_TARGET, "{}/{} disputes confirmed", disputes_confirmed, disputed_candidates.len());
+		assert_eq!(
+			disputes_confirmed,
+			disputed_candidates.len(),
+			"Every dispute raised by two validators is confirmed"
+		);
+
+		// Wait until the node under test participated and distributed its vote.
+		async {
+			loop {
+				let distributed = disputed_candidates
+					.iter()
+					.filter(|receipt| {
+						state.vote_distributed(&receipt.hash(), &connected_validators)
+					})
+					.count();
+				gum::debug!(target: LOG_TARGET, "{}/{} votes distributed", distributed, disputed_candidates.len());
+
+				if distributed == disputed_candidates.len() {
+					break;
+				}
+				tokio::time::sleep(Duration::from_millis(50)).await;
+			}
+		}
+		.timeout(MAX_BLOCK_TIME)
+		.await
+		.unwrap_or_else(|| {
+			panic!("Votes not distributed within {}ms", MAX_BLOCK_TIME.as_millis())
+		});
+		distribution_times.push(block_start.elapsed().as_millis() as f64);
+
+		let block_time = block_start.elapsed().as_millis() as u64;
+		env.metrics().set_block_time(block_time);
+		gum::info!(target: LOG_TARGET, "All votes distributed in {}", format!("{:?}ms", block_time).cyan());
+	}
+
+	let duration: u128 = test_start.elapsed().as_millis();
+	gum::info!(target: LOG_TARGET, "{} disputes processed in {}", disputes_raised, format!("{:?}ms", duration).cyan());
+	gum::info!(target: LOG_TARGET,
+		"Avg block time: {}",
+		format!("{} ms", test_start.elapsed().as_millis() / env.config().num_blocks as u128).red()
+	);
+
+	env.stop().await;
+	let mut usage =
+		env.collect_resource_usage(&["dispute-coordinator", "dispute-distribution"], false);
+	usage.latency = vec![
+		ResourceUsage::from_per_block("Dispute confirmation", &confirmation_times),
+		ResourceUsage::from_per_block("Vote distribution", &distribution_times),
+	];
+	usage
+}
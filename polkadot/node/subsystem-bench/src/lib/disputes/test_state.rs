@@ -0,0 +1,225 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	configuration::{TestAuthorities, TestConfiguration},
+	disputes::DisputesOptions,
+	network::{HandleNetworkMessage, NetworkMessage},
+};
+use codec::Encode;
+use polkadot_node_network_protocol::request_response::{
+	v1::{DisputeRequest, DisputeResponse},
+	Requests,
+};
+use polkadot_node_primitives::{InvalidDisputeVote, UncheckedDisputeMessage, ValidDisputeVote};
+use polkadot_node_subsystem_test_helpers::mock::new_block_import_info;
+use polkadot_overseer::BlockInfo;
+use polkadot_primitives::{
+	vstaging::{CandidateEvent, CandidateReceiptV2 as CandidateReceipt, MutateDescriptorV2},
+	BlockNumber, CandidateHash, CoreIndex, ExplicitDisputeStatement, GroupIndex, Hash, HeadData,
+	Header, Id, InvalidDisputeStatementKind, SessionIndex, ValidDisputeStatementKind,
+	ValidatorIndex, ValidatorSignature,
+};
+use polkadot_primitives_test_helpers::dummy_committed_candidate_receipt_v2;
+use sc_network::ProtocolName;
+use sp_core::{Pair, H256};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+};
+
+const SESSION_INDEX: SessionIndex = 0;
+
+#[derive(Clone)]
+pub struct TestState {
+	// Full test config
+	pub config: TestConfiguration,
+	// Authority keys for the network emulation.
+	pub test_authorities: TestAuthorities,
+	// Relay chain block infos
+	pub block_infos: Vec<BlockInfo>,
+	// Relay chain block headers
+	pub block_headers: HashMap<H256, Header>,
+	// Map from generated candidate receipts
+	pub candidate_receipts: HashMap<H256, Vec<CandidateReceipt>>,
+	// Candidates included per block
+	pub candidate_events: HashMap<H256, Vec<CandidateEvent>>,
+	// Candidates disputed in each block
+	pub disputed_candidates: HashMap<H256, Vec<CandidateReceipt>>,
+	// Tracks which peers received the vote of the node under test for a disputed candidate
+	pub votes_tracker: HashMap<CandidateHash, Vec<Arc<AtomicBool>>>,
+}
+
+impl TestState {
+	pub fn new(config: &TestConfiguration, options: &DisputesOptions) -> Self {
+		let test_authorities = config.generate_authorities();
+		let mut state = Self {
+			config: config.clone(),
+			test_authorities,
+			block_infos: (1..=config.num_blocks).map(generate_block_info).collect(),
+			block_headers: Default::default(),
+			candidate_receipts: Default::default(),
+			candidate_events: Default::default(),
+			disputed_candidates: Default::default(),
+			votes_tracker: Default::default(),
+		};
+
+		state.block_headers = state.block_infos.iter().map(generate_block_header).collect();
+
+		let n_disputes = options.n_disputes_per_block.min(config.n_cores);
+		for block_info in state.block_infos.iter() {
+			for core_idx in 0..config.n_cores {
+				let mut receipt = dummy_committed_candidate_receipt_v2(block_info.hash);
+				receipt.descriptor.set_para_id(Id::new(core_idx as u32 + 1));
+				receipt.descriptor.set_core_index(CoreIndex(core_idx as u32));
+				receipt.descriptor.set_session_index(SESSION_INDEX);
+				let receipt = receipt.to_plain();
+
+				state.candidate_events.entry(block_info.hash).or_default().push(
+					CandidateEvent::CandidateIncluded(
+						receipt.clone(),
+						HeadData(Vec::new()),
+						CoreIndex(core_idx as u32),
+						GroupIndex(core_idx as u32),
+					),
+				);
+				if core_idx < n_disputes {
+					state.votes_tracker.insert(
+						receipt.hash(),
+						(0..config.n_validators)
+							.map(|_| Arc::new(AtomicBool::new(false)))
+							.collect(),
+					);
+					state
+						.disputed_candidates
+						.entry(block_info.hash)
+						.or_default()
+						.push(receipt.clone());
+				}
+				state.candidate_receipts.entry(block_info.hash).or_default().push(receipt);
+			}
+		}
+
+		state
+	}
+
+	pub fn reset_trackers(&self) {
+		self.votes_tracker
+			.values()
+			.flatten()
+			.for_each(|v| v.as_ref().store(false, Ordering::SeqCst));
+	}
+
+	/// Returns true if all `peers` received the vote of the node under test on `candidate_hash`.
+	pub fn vote_distributed(
+		&self,
+		candidate_hash: &CandidateHash,
+		peers: &[ValidatorIndex],
+	) -> bool {
+		let tracker = self.votes_tracker.get(candidate_hash).expect("Candidate is disputed");
+		peers.iter().all(|v| tracker[v.0 as usize].load(Ordering::SeqCst))
+	}
+
+	/// A request that raises a dispute, `invalid_voter` and `valid_voter` cast the opposing votes.
+	pub fn dispute_request(
+		&self,
+		candidate_receipt: &CandidateReceipt,
+		invalid_voter: ValidatorIndex,
+		valid_voter: ValidatorIndex,
+	) -> DisputeRequest {
+		let candidate_hash = candidate_receipt.hash();
+
+		DisputeRequest(UncheckedDisputeMessage {
+			candidate_receipt: candidate_receipt.clone(),
+			session_index: SESSION_INDEX,
+			invalid_vote: InvalidDisputeVote {
+				validator_index: invalid_voter,
+				signature: self.sign_dispute_statement(false, candidate_hash, invalid_voter),
+				kind: InvalidDisputeStatementKind::Explicit,
+			},
+			valid_vote: ValidDisputeVote {
+				validator_index: valid_voter,
+				signature: self.sign_dispute_statement(true, candidate_hash, valid_voter),
+				kind: ValidDisputeStatementKind::Explicit,
+			},
+		})
+	}
+
+	fn sign_dispute_statement(
+		&self,
+		valid: bool,
+		candidate_hash: CandidateHash,
+		validator_index: ValidatorIndex,
+	) -> ValidatorSignature {
+		let payload = ExplicitDisputeStatement { valid, candidate_hash, session: SESSION_INDEX }
+			.signing_payload();
+		self.test_authorities
+			.validator_pairs
+			.get(validator_index.0 as usize)
+			.expect("All validators have keys")
+			.sign(&payload[..])
+	}
+}
+
+fn generate_block_info(block_num: usize) -> BlockInfo {
+	new_block_import_info(Hash::repeat_byte(block_num as u8), block_num as BlockNumber)
+}
+
+fn generate_block_header(info: &BlockInfo) -> (H256, Header) {
+	(
+		info.hash,
+		Header {
+			digest: Default::default(),
+			number: info.number,
+			parent_hash: info.parent_hash,
+			extrinsics_root: Default::default(),
+			state_root: Default::default(),
+		},
+	)
+}
+
+#[async_trait::async_trait]
+impl HandleNetworkMessage for TestState {
+	async fn handle(
+		&self,
+		message: NetworkMessage,
+		_node_sender: &mut futures::channel::mpsc::UnboundedSender<NetworkMessage>,
+	) -> Option<NetworkMessage> {
+		match message {
+			NetworkMessage::RequestFromNode(authority_id, Requests::DisputeSendingV1(req)) => {
+				let index = self
+					.test_authorities
+					.validator_authority_id
+					.iter()
+					.position(|v| v == &authority_id)
+					.unwrap();
+				let candidate_hash = req.payload.0.candidate_receipt.hash();
+				if let Some(tracker) = self.votes_tracker.get(&candidate_hash) {
+					tracker[index].store(true, Ordering::SeqCst);
+				}
+
+				let _ = req
+					.pending_response
+					.send(Ok((DisputeResponse::Confirmed.encode(), ProtocolName::from(""))));
+				None
+			},
+			_ => Some(message),
+		}
+	}
+}
@@ -149,6 +149,10 @@ pub const GENESIS_HASH: Hash = Hash::repeat_byte(0xff);
 // This should eventually be a test parameter.
 pub const MAX_TIME_OF_FLIGHT: Duration = Duration::from_millis(5000);
 
+// Benchmarks waiting for their objective to be reached in every block fail once a block takes
+// longer than this.
+pub const MAX_BLOCK_TIME: Duration = Duration::from_secs(60);
+
 /// The test environment is the high level wrapper of all things required to test
 /// a certain subsystem.
 ///
@@ -359,6 +363,7 @@ impl TestEnvironment {
 		BenchmarkUsage {
 			network_usage: self.network_usage(),
 			cpu_usage: self.cpu_usage(subsystems_under_test, break_down_cpu_usage_per_task),
+			latency: vec![],
 		}
 	}
 
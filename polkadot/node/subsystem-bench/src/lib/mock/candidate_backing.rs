@@ -18,13 +18,17 @@
 
 use crate::{configuration::TestConfiguration, NODE_UNDER_TEST};
 use futures::FutureExt;
-use polkadot_node_primitives::{SignedFullStatementWithPVD, Statement, StatementWithPVD};
+use polkadot_node_primitives::{
+	SignedFullStatement, SignedFullStatementWithPVD, Statement, StatementWithPVD,
+};
 use polkadot_node_subsystem::{
-	messages::CandidateBackingMessage, overseer, SpawnedSubsystem, SubsystemError,
+	messages::{CandidateBackingMessage, CollatorProtocolMessage},
+	overseer, SpawnedSubsystem, SubsystemError,
 };
 use polkadot_node_subsystem_types::OverseerSignal;
 use polkadot_primitives::{
-	CandidateHash, Hash, PersistedValidationData, SigningContext, ValidatorIndex, ValidatorPair,
+	vstaging::CommittedCandidateReceiptV2 as CommittedCandidateReceipt, CandidateHash, Hash,
+	PersistedValidationData, SigningContext, ValidatorIndex, ValidatorPair,
 };
 use sp_core::Pair;
 use std::collections::HashMap;
@@ -35,6 +39,8 @@ struct MockCandidateBackingState {
 	pair: ValidatorPair,
 	pvd: PersistedValidationData,
 	own_backing_group: Vec<ValidatorIndex>,
+	// Collations the node under test is able to second
+	collations: HashMap<CandidateHash, CommittedCandidateReceipt>,
 }
 
 pub struct MockCandidateBacking {
@@ -48,8 +54,37 @@ impl MockCandidateBacking {
 		pair: ValidatorPair,
 		pvd: PersistedValidationData,
 		own_backing_group: Vec<ValidatorIndex>,
+		collations: HashMap<CandidateHash, CommittedCandidateReceipt>,
 	) -> Self {
-		Self { config, state: MockCandidateBackingState { pair, pvd, own_backing_group } }
+		Self {
+			config,
+			state: MockCandidateBackingState { pair, pvd, own_backing_group, collations },
+		}
+	}
+
+	fn second_collation(
+		&self,
+		relay_parent: Hash,
+		candidate_hash: CandidateHash,
+	) -> SignedFullStatement {
+		let receipt = self
+			.state
+			.collations
+			.get(&candidate_hash)
+			.expect("Collations are generated at test start")
+			.clone();
+		let statement = Statement::Seconded(receipt);
+		let context = SigningContext { parent_hash: relay_parent, session_index: 0 };
+		let payload = statement.to_compact().signing_payload(&context);
+
+		SignedFullStatement::new(
+			statement,
+			ValidatorIndex(NODE_UNDER_TEST),
+			self.state.pair.sign(&payload[..]),
+			&context,
+			&self.state.pair.public(),
+		)
+		.unwrap()
 	}
 
 	fn handle_statement(
@@ -160,6 +195,18 @@ impl MockCandidateBacking {
 								ctx.send_message(message).await;
 							}
 						},
+						CandidateBackingMessage::CanSecond(_request, tx) => {
+							let _ = tx.send(true);
+						},
+						CandidateBackingMessage::Second(relay_parent, candidate_receipt, _, _) => {
+							let statement =
+								self.second_collation(relay_parent, candidate_receipt.hash());
+							ctx.send_message(CollatorProtocolMessage::Seconded(
+								relay_parent,
+								statement,
+							))
+							.await;
+						},
 						_ => {
 							unimplemented!("Unexpected candidate-backing message")
 						},
@@ -21,13 +21,22 @@ use polkadot_node_subsystem::{
 	messages::ProspectiveParachainsMessage, overseer, SpawnedSubsystem, SubsystemError,
 };
 use polkadot_node_subsystem_types::OverseerSignal;
-use polkadot_primitives::Hash;
+use polkadot_primitives::{BlockNumber, Hash, Id as ParaId, PersistedValidationData};
+use std::collections::HashMap;
 
-pub struct MockProspectiveParachains {}
+pub struct MockProspectiveParachains {
+	// PersistedValidationData, we use one for all candidates
+	pvd: PersistedValidationData,
+	// Minimum relay parents of the paras building on top of a leaf
+	minimum_relay_parents: HashMap<Hash, Vec<(ParaId, BlockNumber)>>,
+}
 
 impl MockProspectiveParachains {
-	pub fn new() -> Self {
-		Self {}
+	pub fn new(
+		pvd: PersistedValidationData,
+		minimum_relay_parents: HashMap<Hash, Vec<(ParaId, BlockNumber)>>,
+	) -> Self {
+		Self { pvd, minimum_relay_parents }
 	}
 }
 
@@ -51,8 +60,14 @@ impl MockProspectiveParachains {
 						return
 					},
 				orchestra::FromOrchestra::Communication { msg } => match msg {
-					ProspectiveParachainsMessage::GetMinimumRelayParents(_relay_parent, tx) => {
-						tx.send(vec![]).unwrap();
+					ProspectiveParachainsMessage::GetMinimumRelayParents(relay_parent, tx) => {
+						tx.send(
+							self.minimum_relay_parents
+								.get(&relay_parent)
+								.cloned()
+								.unwrap_or_default(),
+						)
+						.unwrap();
 					},
 					ProspectiveParachainsMessage::GetHypotheticalMembership(req, tx) => {
 						tx.send(
@@ -64,6 +79,9 @@ impl MockProspectiveParachains {
 						)
 						.unwrap();
 					},
+					ProspectiveParachainsMessage::GetProspectiveValidationData(_req, tx) => {
+						tx.send(Some(self.pvd.clone())).unwrap();
+					},
 					_ => {
 						unimplemented!("Unexpected chain-api message")
 					},
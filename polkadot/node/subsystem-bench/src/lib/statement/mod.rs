@@ -102,7 +102,8 @@ fn build_overseer(
 	);
 	let chain_api_state = ChainApiState { block_headers: state.block_headers.clone() };
 	let mock_chain_api = MockChainApi::new(chain_api_state);
-	let mock_prospective_parachains = MockProspectiveParachains::new();
+	let mock_prospective_parachains =
+		MockProspectiveParachains::new(state.pvd.clone(), Default::default());
 	let mock_candidate_backing = MockCandidateBacking::new(
 		state.config.clone(),
 		state
@@ -113,6 +114,7 @@ fn build_overseer(
 			.clone(),
 		state.pvd.clone(),
 		state.own_backing_group.clone(),
+		Default::default(),
 	);
 	let (statement_req_receiver, statement_req_cfg) =
 		IncomingRequest::get_config_receiver::<Block, sc_network::NetworkWorker<Block, Hash>>(
@@ -25,6 +25,10 @@ use std::collections::HashMap;
 pub struct BenchmarkUsage {
 	pub network_usage: Vec<ResourceUsage>,
 	pub cpu_usage: Vec<ResourceUsage>,
+	/// Time the objective of a benchmark took to be reached, empty if the benchmark does not
+	/// measure it.
+	#[serde(default)]
+	pub latency: Vec<ResourceUsage>,
 }
 
 impl std::fmt::Display for BenchmarkUsage {
@@ -46,7 +50,21 @@ impl std::fmt::Display for BenchmarkUsage {
 				.sorted()
 				.collect::<Vec<String>>()
 				.join("\n")
-		)
+		)?;
+		if !self.latency.is_empty() {
+			write!(
+				f,
+				"\n{}\n{}\n",
+				format!("{:<64}{:>12}{:>12}", "Latency, ms", "total", "per block").blue(),
+				self.latency
+					.iter()
+					.map(|v| v.to_string())
+					.sorted()
+					.collect::<Vec<String>>()
+					.join("\n")
+			)?;
+		}
+		Ok(())
 	}
 }
 
@@ -55,10 +73,12 @@ impl BenchmarkUsage {
 		let all_network_usages: Vec<&ResourceUsage> =
 			usages.iter().flat_map(|v| &v.network_usage).collect();
 		let all_cpu_usage: Vec<&ResourceUsage> = usages.iter().flat_map(|v| &v.cpu_usage).collect();
+		let all_latency: Vec<&ResourceUsage> = usages.iter().flat_map(|v| &v.latency).collect();
 
 		Self {
 			network_usage: ResourceUsage::average_by_resource_name(&all_network_usages),
 			cpu_usage: ResourceUsage::average_by_resource_name(&all_cpu_usage),
+			latency: ResourceUsage::average_by_resource_name(&all_latency),
 		}
 	}
 
@@ -70,6 +90,10 @@ impl BenchmarkUsage {
 		check_usage(&self.cpu_usage, checks)
 	}
 
+	pub fn check_latency(&self, checks: &[ResourceUsageCheck]) -> Vec<String> {
+		check_usage(&self.latency, checks)
+	}
+
 	pub fn cpu_usage_diff(&self, other: &Self, resource_name: &str) -> Option<f64> {
 		let self_res = self.cpu_usage.iter().find(|v| v.resource_name == resource_name);
 		let other_res = other.cpu_usage.iter().find(|v| v.resource_name == resource_name);
@@ -96,6 +120,11 @@ impl BenchmarkUsage {
 				unit: "seconds".to_string(),
 				value: v.per_block,
 			}))
+			.chain(self.latency.iter().map(|v| ChartItem {
+				name: v.resource_name.clone(),
+				unit: "ms".to_string(),
+				value: v.per_block,
+			}))
 			.collect::<Vec<_>>();
 
 		Ok(serde_json::to_string(&chart)?)
@@ -139,6 +168,13 @@ impl std::fmt::Display for ResourceUsage {
 }
 
 impl ResourceUsage {
+	/// Aggregates a value measured in every block, `per_block` being the mean.
+	pub fn from_per_block(resource_name: &str, values: &[f64]) -> Self {
+		let total = values.iter().sum::<f64>();
+		let per_block = if values.is_empty() { 0.0 } else { total / values.len() as f64 };
+		Self { resource_name: resource_name.to_string(), total, per_block }
+	}
+
 	fn average_by_resource_name(usages: &[&Self]) -> Vec<Self> {
 		let mut by_name: HashMap<String, Vec<&Self>> = Default::default();
 		for usage in usages {
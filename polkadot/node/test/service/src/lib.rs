@@ -106,6 +106,9 @@ pub fn new_full<OverseerGenerator: OverseerGen>(
 					prepare_workers_hard_max_num: None,
 					prepare_workers_soft_max_num: None,
 					enable_approval_voting_parallel: false,
+					approval_traffic_capture: None,
+					overseer_watchdog: None,
+					grandpa_monitor: None,
 				},
 			),
 		sc_network::config::NetworkBackendType::Litep2p =>
@@ -128,6 +131,9 @@ pub fn new_full<OverseerGenerator: OverseerGen>(
 					prepare_workers_hard_max_num: None,
 					prepare_workers_soft_max_num: None,
 					enable_approval_voting_parallel: false,
+					approval_traffic_capture: None,
+					overseer_watchdog: None,
+					grandpa_monitor: None,
 				},
 			),
 	}
@@ -100,6 +100,9 @@ fn main() -> Result<()> {
 						prepare_workers_hard_max_num: None,
 						prepare_workers_soft_max_num: None,
 						enable_approval_voting_parallel: false,
+						approval_traffic_capture: None,
+						overseer_watchdog: None,
+						grandpa_monitor: None,
 					},
 				)
 				.map_err(|e| e.to_string())?;
@@ -0,0 +1,81 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC to inspect the approval timelines of recent blocks recorded by approval voting.
+
+use jsonrpsee::{proc_macros::rpc, types::ErrorObjectOwned, Extensions};
+use polkadot_node_core_approval_voting::timeline::{ApprovalTimelines, BlockApprovalTimeline};
+use polkadot_primitives::Hash;
+use sc_rpc_api::{check_if_safe, UnsafeRpcError};
+
+/// Provides RPC methods to inspect approval timelines.
+#[rpc(client, server)]
+pub trait ApprovalsApi {
+	/// Returns the approval timeline of `block_hash`, or the timelines of all recent blocks,
+	/// highest first, if no block hash is given.
+	///
+	/// A timeline records when the block was imported and approved, the assignments and
+	/// approvals per tranche of each candidate, the no-shows and the time spent checking
+	/// assignment certificates.
+	#[method(name = "parachain_approvalTimelines", with_extensions)]
+	fn approval_timelines(
+		&self,
+		block_hash: Option<Hash>,
+	) -> Result<Vec<BlockApprovalTimeline>, Error>;
+}
+
+/// Implements the [`ApprovalsApiServer`] RPC trait.
+pub struct Approvals {
+	timelines: ApprovalTimelines,
+}
+
+impl Approvals {
+	/// Creates a new instance of the approvals RPC handler.
+	pub fn new(timelines: ApprovalTimelines) -> Self {
+		Self { timelines }
+	}
+}
+
+impl ApprovalsApiServer for Approvals {
+	fn approval_timelines(
+		&self,
+		ext: &Extensions,
+		block_hash: Option<Hash>,
+	) -> Result<Vec<BlockApprovalTimeline>, Error> {
+		check_if_safe(ext)?;
+
+		Ok(match block_hash {
+			Some(block_hash) => self.timelines.block(&block_hash).into_iter().collect(),
+			None => self.timelines.recent(),
+		})
+	}
+}
+
+/// Top-level error type for the approvals RPC handler.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Call to an unsafe RPC was denied.
+	#[error(transparent)]
+	UnsafeRpcCalled(#[from] UnsafeRpcError),
+}
+
+impl From<Error> for ErrorObjectOwned {
+	fn from(error: Error) -> Self {
+		match error {
+			Error::UnsafeRpcCalled(e) => e.into(),
+		}
+	}
+}
@@ -0,0 +1,125 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC to inspect the disputes known to the dispute coordinator of the node.
+
+use std::{
+	sync::Arc,
+	time::{SystemTime, UNIX_EPOCH},
+};
+
+use jsonrpsee::{
+	proc_macros::rpc,
+	types::{ErrorObject, ErrorObjectOwned},
+	Extensions,
+};
+use polkadot_node_core_dispute_coordinator::{
+	inspect::{self, DisputesInspection},
+	Config as DisputeCoordinatorConfig,
+};
+use polkadot_node_subsystem_util::{database::Database, signing_key_and_index};
+use polkadot_primitives::{runtime_api::ParachainHost, Block};
+use sc_rpc_api::{check_if_safe, UnsafeRpcError};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_keystore::KeystorePtr;
+
+const DISPUTES_ERROR: i32 = 10000;
+
+/// Provides RPC methods to inspect disputes.
+#[rpc(client, server)]
+pub trait DisputesApi {
+	/// Returns the disputes stored by the dispute coordinator per session, with their vote
+	/// tallies, the participation of the local validator and the spam slot occupancy.
+	#[method(name = "parachain_inspectDisputes", with_extensions)]
+	fn inspect_disputes(&self) -> Result<DisputesInspection, Error>;
+}
+
+/// Implements the [`DisputesApiServer`] RPC trait.
+pub struct Disputes<C> {
+	client: Arc<C>,
+	keystore: KeystorePtr,
+	parachains_db: Arc<dyn Database>,
+	config: DisputeCoordinatorConfig,
+}
+
+impl<C> Disputes<C> {
+	/// Creates a new instance of the disputes RPC handler.
+	pub fn new(
+		client: Arc<C>,
+		keystore: KeystorePtr,
+		parachains_db: Arc<dyn Database>,
+		config: DisputeCoordinatorConfig,
+	) -> Self {
+		Self { client, keystore, parachains_db, config }
+	}
+}
+
+impl<C> DisputesApiServer for Disputes<C>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: ParachainHost<Block>,
+{
+	fn inspect_disputes(&self, ext: &Extensions) -> Result<DisputesInspection, Error> {
+		check_if_safe(ext)?;
+
+		Ok(inspect_disputes(&*self.client, &self.keystore, &*self.parachains_db, &self.config)?)
+	}
+}
+
+/// Inspect the disputes stored in `parachains_db`.
+///
+/// The local validator of each session is looked up with the session info at the best block.
+pub fn inspect_disputes<C>(
+	client: &C,
+	keystore: &KeystorePtr,
+	parachains_db: &dyn Database,
+	config: &DisputeCoordinatorConfig,
+) -> Result<DisputesInspection, inspect::Error>
+where
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block>,
+	C::Api: ParachainHost<Block>,
+{
+	let best_hash = client.info().best_hash;
+	let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+	let local_validator = |session| {
+		let session_info = client.runtime_api().session_info(best_hash, session).ok().flatten()?;
+		signing_key_and_index(session_info.validators.iter(), keystore).map(|(_, index)| index)
+	};
+
+	inspect::inspect_disputes(parachains_db, config, now, local_validator)
+}
+
+/// Top-level error type for the disputes RPC handler.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// Failed to inspect the database of the dispute coordinator.
+	#[error(transparent)]
+	Inspect(#[from] inspect::Error),
+	/// Call to an unsafe RPC was denied.
+	#[error(transparent)]
+	UnsafeRpcCalled(#[from] UnsafeRpcError),
+}
+
+impl From<Error> for ErrorObjectOwned {
+	fn from(error: Error) -> Self {
+		match error {
+			Error::Inspect(e) => ErrorObject::owned(DISPUTES_ERROR + 1, e.to_string(), None::<()>),
+			Error::UnsafeRpcCalled(e) => e.into(),
+		}
+	}
+}
@@ -38,6 +38,9 @@ use sp_consensus_babe::BabeApi;
 use sp_consensus_beefy::AuthorityIdBound;
 use sp_keystore::KeystorePtr;
 
+pub mod approvals;
+pub mod disputes;
+
 /// A type representing all RPC extensions.
 pub type RpcExtension = RpcModule<()>;
 
@@ -61,6 +64,8 @@ pub struct GrandpaDeps<B> {
 	pub subscription_executor: sc_rpc::SubscriptionTaskExecutor,
 	/// Finality proof provider.
 	pub finality_provider: Arc<FinalityProofProvider<B, Block>>,
+	/// The GRANDPA gossip monitor, if enabled.
+	pub monitor: Option<sc_consensus_grandpa::GrandpaMonitor<Block>>,
 }
 
 /// Dependencies for BEEFY
@@ -127,7 +132,9 @@ where
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use sc_consensus_babe_rpc::{Babe, BabeApiServer};
 	use sc_consensus_beefy_rpc::{Beefy, BeefyApiServer};
-	use sc_consensus_grandpa_rpc::{Grandpa, GrandpaApiServer};
+	use sc_consensus_grandpa_rpc::{
+		Grandpa, GrandpaApiServer, GrandpaMonitorApiServer, GrandpaMonitorRpc,
+	};
 	use sc_sync_state_rpc::{SyncState, SyncStateApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 	use substrate_state_trie_migration_rpc::{StateMigration, StateMigrationApiServer};
@@ -140,6 +147,7 @@ where
 		justification_stream,
 		subscription_executor,
 		finality_provider,
+		monitor,
 	} = grandpa;
 
 	io.merge(StateMigration::new(client.clone(), backend.clone()).into_rpc())?;
@@ -167,6 +175,9 @@ where
 		)
 		.into_rpc(),
 	)?;
+	if let Some(monitor) = monitor {
+		io.merge(GrandpaMonitorRpc::new(monitor).into_rpc())?;
+	}
 	io.merge(
 		SyncState::new(chain_spec, client, shared_authority_set, babe_worker_handle)?.into_rpc(),
 	)?;
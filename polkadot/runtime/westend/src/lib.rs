@@ -2547,6 +2547,7 @@ sp_api::impl_runtime_apis! {
 	}
 
 	#[cfg(feature = "try-runtime")]
+	#[api_version(2)]
 	impl frame_try_runtime::TryRuntime<Block> for Runtime {
 		fn on_runtime_upgrade(checks: frame_try_runtime::UpgradeCheckSelect) -> (Weight, Weight) {
 			log::info!("try-runtime::on_runtime_upgrade westend.");
@@ -2564,6 +2565,14 @@ sp_api::impl_runtime_apis! {
 			// have a backtrace here.
 			Executive::try_execute_block(block, state_root_check, signature_check, select).unwrap()
 		}
+
+		fn on_runtime_upgrade_and_migrations(
+			checks: frame_try_runtime::UpgradeCheckSelect,
+			max_blocks: u32,
+		) -> (Weight, u32) {
+			log::info!("try-runtime::on_runtime_upgrade_and_migrations westend.");
+			Executive::try_runtime_upgrade_and_migrations(checks, max_blocks).unwrap()
+		}
 	}
 
 	#[cfg(feature = "runtime-benchmarks")]
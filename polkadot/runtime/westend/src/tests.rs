@@ -144,6 +144,78 @@ mod remote_tests {
 		ext.execute_with(|| Runtime::on_runtime_upgrade(UpgradeCheckSelect::PreAndPost));
 	}
 
+	#[tokio::test]
+	async fn run_multi_block_migrations() {
+		if var("RUN_MIGRATION_TESTS").is_err() {
+			return;
+		}
+
+		sp_tracing::try_init_simple();
+		let transport: Transport =
+			var("WS").unwrap_or("wss://westend-rpc.polkadot.io:443".to_string()).into();
+		let maybe_state_snapshot: Option<SnapshotConfig> = var("SNAP").map(|s| s.into()).ok();
+		let max_blocks = var("MBM_MAX_BLOCKS").map_or(1_000, |b| b.parse().unwrap());
+		let mut ext = Builder::<Block>::default()
+			.mode(if let Some(state_snapshot) = maybe_state_snapshot {
+				Mode::OfflineOrElseOnline(
+					OfflineConfig { state_snapshot: state_snapshot.clone() },
+					OnlineConfig {
+						transport,
+						state_snapshot: Some(state_snapshot),
+						..Default::default()
+					},
+				)
+			} else {
+				Mode::Online(OnlineConfig { transport, ..Default::default() })
+			})
+			.build()
+			.await
+			.unwrap();
+		ext.execute_with(|| {
+			let (_, blocks) =
+				Runtime::on_runtime_upgrade_and_migrations(UpgradeCheckSelect::All, max_blocks);
+			log::info!("Multi-block migrations completed after {blocks} blocks");
+		});
+	}
+
+	#[tokio::test]
+	async fn storage_layout_is_migrated() {
+		if var("RUN_MIGRATION_TESTS").is_err() {
+			return;
+		}
+
+		sp_tracing::try_init_simple();
+		let transport: Transport =
+			var("WS").unwrap_or("wss://westend-rpc.polkadot.io:443".to_string()).into();
+		let maybe_state_snapshot: Option<SnapshotConfig> = var("SNAP").map(|s| s.into()).ok();
+		let mut ext = Builder::<Block>::default()
+			.mode(if let Some(state_snapshot) = maybe_state_snapshot {
+				Mode::OfflineOrElseOnline(
+					OfflineConfig { state_snapshot: state_snapshot.clone() },
+					OnlineConfig {
+						transport,
+						state_snapshot: Some(state_snapshot),
+						..Default::default()
+					},
+				)
+			} else {
+				Mode::Online(OnlineConfig { transport, ..Default::default() })
+			})
+			.build()
+			.await
+			.unwrap();
+
+		let old_code = frame_storage_layout_diff::code_in_state(&mut ext).expect("code in state");
+		let new_code = WASM_BINARY.expect("wasm binary is built");
+		ext.execute_with(|| Runtime::on_runtime_upgrade(UpgradeCheckSelect::None));
+
+		let report =
+			frame_storage_layout_diff::check_upgrade(&mut ext, &old_code, new_code, None, 10)
+				.unwrap();
+		log::info!("Storage layout changes:\n{report}");
+		assert!(!report.has_undecodable_entries(), "storage entries left unmigrated");
+	}
+
 	#[tokio::test]
 	async fn delegate_stake_migration() {
 		// Intended to be run only manually.
@@ -0,0 +1,117 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Loading the inputs of a candidate validation from files or from a relay chain node.
+
+use codec::{Decode, Encode};
+use polkadot_parachain_primitives::primitives::{Id as ParaId, ValidationCode};
+use polkadot_primitives::{
+	ExecutorParams, Hash, OccupiedCoreAssumption, PersistedValidationData, SessionIndex,
+};
+use sp_core::Bytes;
+use std::path::Path;
+use substrate_rpc_client::{ws_client, StateApi, WsClient};
+
+/// Read a file that contains either raw bytes or `0x` prefixed hex.
+pub fn read_file(path: &Path) -> Result<Vec<u8>, String> {
+	let content = std::fs::read(path).map_err(|e| format!("failed to read {path:?}: {e}"))?;
+
+	match content.strip_prefix(b"0x") {
+		Some(hex) => {
+			let hex = std::str::from_utf8(hex)
+				.map_err(|e| format!("{path:?} is not valid hex: {e}"))?
+				.trim();
+			array_bytes::hex2bytes(hex).map_err(|e| format!("{path:?} is not valid hex: {e:?}"))
+		},
+		None => Ok(content),
+	}
+}
+
+/// Read a file with a SCALE encoded value, see [`read_file`].
+pub fn decode_file<T: Decode>(path: &Path) -> Result<T, String> {
+	let encoded = read_file(path)?;
+	T::decode(&mut &encoded[..]).map_err(|e| format!("failed to decode {path:?}: {e}"))
+}
+
+/// Fetches the inputs of a para from the runtime API of a relay chain node.
+pub struct Remote {
+	client: WsClient,
+	para_id: ParaId,
+	at: Option<Hash>,
+}
+
+impl Remote {
+	/// Connect to the node at `uri`. Queries are made at the block `at`, or the best block.
+	pub async fn connect(uri: &str, para_id: ParaId, at: Option<Hash>) -> Result<Self, String> {
+		Ok(Self { client: ws_client(uri).await?, para_id, at })
+	}
+
+	/// The validation code of the para, assuming that the pending candidate was included.
+	pub async fn validation_code(&self) -> Result<ValidationCode, String> {
+		self.call::<Option<ValidationCode>>(
+			"ParachainHost_validation_code",
+			(self.para_id, OccupiedCoreAssumption::Included),
+		)
+		.await?
+		.ok_or_else(|| format!("no validation code for para {}", self.para_id))
+	}
+
+	/// The persisted validation data of the para, assuming that the pending candidate was
+	/// included.
+	pub async fn persisted_validation_data(&self) -> Result<PersistedValidationData, String> {
+		self.call::<Option<PersistedValidationData>>(
+			"ParachainHost_persisted_validation_data",
+			(self.para_id, OccupiedCoreAssumption::Included),
+		)
+		.await?
+		.ok_or_else(|| format!("no persisted validation data for para {}", self.para_id))
+	}
+
+	/// The executor parameters of the session of the next block.
+	pub async fn executor_params(&self) -> Result<ExecutorParams, String> {
+		let session =
+			self.call::<SessionIndex>("ParachainHost_session_index_for_child", ()).await?;
+		self.call::<Option<ExecutorParams>>("ParachainHost_session_executor_params", session)
+			.await?
+			.ok_or_else(|| format!("no executor parameters for session {session}"))
+	}
+
+	async fn call<R: Decode>(&self, method: &str, args: impl Encode) -> Result<R, String> {
+		let result =
+			StateApi::<Hash>::call(&self.client, method.into(), Bytes(args.encode()), self.at)
+				.await
+				.map_err(|e| format!("failed to call `{method}`: {e}"))?;
+		R::decode(&mut &result[..]).map_err(|e| format!("failed to decode `{method}`: {e}"))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn read_file_accepts_raw_and_hex() {
+		let dir = tempfile::tempdir().unwrap();
+		let raw = dir.path().join("raw");
+		let hex = dir.path().join("hex");
+		std::fs::write(&raw, [1u8, 2, 3]).unwrap();
+		std::fs::write(&hex, "0x010203\n").unwrap();
+
+		assert_eq!(read_file(&raw).unwrap(), [1, 2, 3]);
+		assert_eq!(read_file(&hex).unwrap(), [1, 2, 3]);
+		assert_eq!(decode_file::<(u8, u8, u8)>(&hex).unwrap(), (1, 2, 3));
+	}
+}
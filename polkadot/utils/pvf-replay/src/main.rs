@@ -0,0 +1,269 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Replay the validation of a parachain candidate outside of a running validator.
+//!
+//! The candidate is prepared and executed by the regular PVF host, which spawns the same prepare
+//! and execute workers, with the same executor parameters and security sandbox, as a validator.
+//! The preparation and execution statistics are collected from the metrics of the host.
+
+mod input;
+
+use clap::{Parser, ValueEnum};
+use input::{decode_file, read_file, Remote};
+use polkadot_node_core_pvf::{
+	start, Config, Metrics, PrepareJobKind, Priority, PvfPrepData, ValidationError,
+	EXECUTE_BINARY_NAME, PREPARE_BINARY_NAME,
+};
+use polkadot_node_metrics::metrics::{prometheus, Metrics as _};
+use polkadot_node_primitives::{PoV, NODE_VERSION};
+use polkadot_node_subsystem::messages::PvfExecKind;
+use polkadot_parachain_primitives::primitives::{Id as ParaId, ValidationResult};
+use polkadot_primitives::{
+	executor_params::{
+		DEFAULT_APPROVAL_EXECUTION_TIMEOUT, DEFAULT_BACKING_EXECUTION_TIMEOUT,
+		DEFAULT_LENIENT_PREPARATION_TIMEOUT,
+	},
+	ExecutorParams, Hash, PersistedValidationData, PvfExecKind as RuntimePvfExecKind, PvfPrepKind,
+};
+use std::{
+	path::PathBuf,
+	process::ExitCode,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// The kind of validation to replay, which determines the timeouts and priority.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum ExecKind {
+	Backing,
+	Approval,
+	Dispute,
+}
+
+#[derive(Debug, Parser)]
+struct Opt {
+	/// The validation code. Fetched from `--uri` if not given.
+	#[arg(long)]
+	code: Option<PathBuf>,
+
+	/// The SCALE encoded persisted validation data. Fetched from `--uri` if not given.
+	#[arg(long)]
+	pvd: Option<PathBuf>,
+
+	/// The SCALE encoded PoV of the candidate.
+	#[arg(long)]
+	pov: PathBuf,
+
+	/// The SCALE encoded executor parameters. Fetched from `--uri` if given, defaults otherwise.
+	#[arg(long)]
+	executor_params: Option<PathBuf>,
+
+	/// A relay chain node to fetch the missing inputs from.
+	#[arg(long, requires = "para_id")]
+	uri: Option<String>,
+
+	/// The para to fetch the inputs of.
+	#[arg(long)]
+	para_id: Option<u32>,
+
+	/// The relay chain block to fetch the inputs at. Defaults to the best block.
+	#[arg(long, requires = "uri")]
+	at: Option<Hash>,
+
+	/// The kind of validation, which determines the timeouts and the priority.
+	#[arg(long, value_enum, default_value_t = ExecKind::Approval)]
+	exec_kind: ExecKind,
+
+	/// The directory of the prepare and execute workers. Defaults to the directory of this binary.
+	#[arg(long)]
+	workers_path: Option<PathBuf>,
+
+	/// Where to store the prepared artifact. Defaults to a temporary directory.
+	#[arg(long)]
+	cache_path: Option<PathBuf>,
+
+	/// Run the workers without the security sandbox if it is not fully supported, like a
+	/// validator with the same flag.
+	#[arg(long = "insecure-validator-i-know-what-i-do")]
+	insecure_validator: bool,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+	sp_tracing::try_init_simple();
+
+	match run(Opt::parse()).await {
+		Ok(true) => ExitCode::SUCCESS,
+		Ok(false) => ExitCode::FAILURE,
+		Err(e) => {
+			eprintln!("error: {e}");
+			ExitCode::FAILURE
+		},
+	}
+}
+
+/// Replay the candidate and return whether it is valid.
+async fn run(opt: Opt) -> Result<bool, String> {
+	let remote = match (&opt.uri, opt.para_id) {
+		(Some(uri), Some(para_id)) =>
+			Some(Remote::connect(uri, ParaId::from(para_id), opt.at).await?),
+		_ => None,
+	};
+	let no_remote = |what: &str| format!("`--{what}` is required without `--uri`");
+
+	let code = match (&opt.code, &remote) {
+		(Some(path), _) => read_file(path)?,
+		(None, Some(remote)) => remote.validation_code().await?.0,
+		(None, None) => return Err(no_remote("code")),
+	};
+	let pvd: PersistedValidationData = match (&opt.pvd, &remote) {
+		(Some(path), _) => decode_file(path)?,
+		(None, Some(remote)) => remote.persisted_validation_data().await?,
+		(None, None) => return Err(no_remote("pvd")),
+	};
+	let executor_params: ExecutorParams = match (&opt.executor_params, &remote) {
+		(Some(path), _) => decode_file(path)?,
+		(None, Some(remote)) => remote.executor_params().await?,
+		(None, None) => ExecutorParams::default(),
+	};
+	let pov: PoV = decode_file(&opt.pov)?;
+
+	let workers_path = match opt.workers_path {
+		Some(path) => path,
+		None => std::env::current_exe()
+			.ok()
+			.and_then(|exe| exe.parent().map(ToOwned::to_owned))
+			.ok_or("failed to determine the workers path")?,
+	};
+	// Keep the temporary directory alive until the replay is done.
+	let tmp_cache;
+	let cache_path = match opt.cache_path {
+		Some(path) => path,
+		None => {
+			tmp_cache = tempfile::tempdir().map_err(|e| format!("failed to create cache: {e}"))?;
+			tmp_cache.path().to_owned()
+		},
+	};
+
+	let registry = prometheus::Registry::new();
+	let metrics = Metrics::try_register(&registry).map_err(|e| e.to_string())?;
+	let config = Config::new(
+		cache_path,
+		Some(NODE_VERSION.to_owned()),
+		!opt.insecure_validator,
+		workers_path.join(PREPARE_BINARY_NAME),
+		workers_path.join(EXECUTE_BINARY_NAME),
+		1,
+		1,
+		1,
+	);
+	let (mut host, task) = start(config, metrics).await.map_err(|e| e.to_string())?;
+	tokio::spawn(task);
+	println!("security status: {:?}", host.security_status);
+
+	let (runtime_kind, exec_kind) = match opt.exec_kind {
+		// The host only drops backing jobs of relay parents that left the active leaves, which are
+		// never updated here.
+		ExecKind::Backing =>
+			(RuntimePvfExecKind::Backing, PvfExecKind::Backing(opt.at.unwrap_or_default())),
+		ExecKind::Approval => (RuntimePvfExecKind::Approval, PvfExecKind::Approval),
+		ExecKind::Dispute => (RuntimePvfExecKind::Approval, PvfExecKind::Dispute),
+	};
+	let prep_timeout = executor_params
+		.pvf_prep_timeout(PvfPrepKind::Prepare)
+		.unwrap_or(DEFAULT_LENIENT_PREPARATION_TIMEOUT);
+	let exec_timeout =
+		executor_params.pvf_exec_timeout(runtime_kind).unwrap_or(match runtime_kind {
+			RuntimePvfExecKind::Backing => DEFAULT_BACKING_EXECUTION_TIMEOUT,
+			RuntimePvfExecKind::Approval => DEFAULT_APPROVAL_EXECUTION_TIMEOUT,
+		});
+
+	let pvf =
+		PvfPrepData::from_code(code, executor_params, prep_timeout, PrepareJobKind::Compilation);
+	println!("code hash: {:?}", pvf.code_hash());
+
+	let (result_tx, result_rx) = futures::channel::oneshot::channel();
+	let started = Instant::now();
+	host.execute_pvf(
+		pvf,
+		exec_timeout,
+		Arc::new(pvd),
+		Arc::new(pov),
+		Priority::from(exec_kind),
+		exec_kind,
+		result_tx,
+	)
+	.await?;
+	let result = result_rx.await.map_err(|_| "the validation host hung up")?;
+	let elapsed = started.elapsed();
+
+	print_outcome(&result);
+	print_stats(&registry.gather(), elapsed);
+
+	Ok(result.is_ok())
+}
+
+fn print_outcome(result: &Result<ValidationResult, ValidationError>) {
+	match result {
+		Ok(result) => {
+			println!("outcome: valid");
+			println!(
+				"  head data: {} bytes, hash {:?}",
+				result.head_data.0.len(),
+				result.head_data.hash()
+			);
+			println!("  new validation code: {}", result.new_validation_code.is_some());
+			println!("  upward messages: {}", result.upward_messages.len());
+			println!("  horizontal messages: {}", result.horizontal_messages.len());
+			println!("  processed downward messages: {}", result.processed_downward_messages);
+			println!("  hrmp watermark: {}", result.hrmp_watermark);
+		},
+		Err(err) => println!("outcome: {err}"),
+	}
+}
+
+/// Print the statistics that the host observed while replaying the single candidate.
+fn print_stats(metrics: &[prometheus::prometheus::proto::MetricFamily], elapsed: Duration) {
+	let observed = |name: &str| {
+		metrics
+			.iter()
+			.find(|family| family.get_name() == name)?
+			.get_metric()
+			.first()
+			.map(|metric| metric.get_histogram())
+			.filter(|histogram| histogram.get_sample_count() != 0)
+			.map(|histogram| histogram.get_sample_sum())
+	};
+	let print = |label: &str, name: &str, unit: &str| match observed(name) {
+		Some(value) => println!("  {label}: {value:.3} {unit}"),
+		None => println!("  {label}: n/a"),
+	};
+
+	println!("statistics:");
+	println!("  total: {:.3} s", elapsed.as_secs_f64());
+	print("preparation time", "polkadot_pvf_preparation_time", "s");
+	print("execution time", "polkadot_pvf_execution_time", "s");
+	print("preparation max rss", "polkadot_pvf_preparation_max_rss", "KiB");
+	print("preparation max resident", "polkadot_pvf_preparation_max_resident", "KiB");
+	print("preparation max allocated", "polkadot_pvf_preparation_max_allocated", "KiB");
+	print(
+		"preparation peak tracked allocation",
+		"polkadot_pvf_preparation_peak_tracked_allocation",
+		"KiB",
+	);
+	print("decompressed code size", "polkadot_parachain_candidate_validation_code_size", "bytes");
+}
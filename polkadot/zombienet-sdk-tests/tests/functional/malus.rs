@@ -0,0 +1,252 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tests for the misbehaving `malus` variants that are not slashable: withholding availability
+//! data, not approving candidates and signing bogus bitfields. The honest validators must keep the
+//! parachain progressing and finalizing, without any honest validator getting disputed.
+//!
+//! Needs the `polkadot`, `polkadot-parachain` and `malus` images (or binaries). The first two are
+//! read by `zombienet_sdk::environment::get_images_from_env`, the last one from `MALUS_IMAGE`.
+
+use anyhow::anyhow;
+use serde_json::json;
+use std::time::Duration;
+use zombienet_sdk::{LocalFileSystem, Network, NetworkConfig, NetworkConfigBuilder};
+
+const PARA_ID: u32 = 2000;
+const HONEST_VALIDATORS: [&str; 4] = ["alice", "bob", "charlie", "dave"];
+const DEFAULT_MALUS_IMAGE: &str = "docker.io/paritypr/malus:latest";
+
+fn malus_network_config(malus_variant: &str) -> Result<NetworkConfig, anyhow::Error> {
+	let images = zombienet_sdk::environment::get_images_from_env();
+	let malus_image =
+		std::env::var("MALUS_IMAGE").unwrap_or_else(|_| DEFAULT_MALUS_IMAGE.to_string());
+
+	NetworkConfigBuilder::new()
+		.with_relaychain(|r| {
+			r.with_chain("rococo-local")
+				.with_default_command("polkadot")
+				.with_default_image(images.polkadot.as_str())
+				.with_default_args(vec!["-lparachain=debug".into()])
+				.with_genesis_overrides(json!({
+					"configuration": {
+						"config": {
+							"needed_approvals": 2,
+							"scheduler_params": { "max_validators_per_core": 1 }
+						}
+					}
+				}))
+				.with_node(|node| node.with_name("alice"))
+				.with_node(|node| node.with_name("bob"))
+				.with_node(|node| node.with_name("charlie"))
+				.with_node(|node| node.with_name("dave"))
+				.with_node(|node| {
+					node.with_name("malus")
+						.with_image(malus_image.as_str())
+						.with_command("malus")
+						.with_subcommand(malus_variant)
+						.with_args(vec!["-lparachain=debug,MALUS=trace".into()])
+				})
+		})
+		.with_parachain(|p| {
+			p.with_id(PARA_ID)
+				.with_default_command("polkadot-parachain")
+				.with_default_image(images.cumulus.as_str())
+				.with_collator(|n| n.with_name("collator"))
+		})
+		.build()
+		.map_err(|e| {
+			let errs = e.into_iter().map(|e| e.to_string()).collect::<Vec<_>>().join(" ");
+			anyhow!("config errs: {errs}")
+		})
+}
+
+async fn wait_metric(
+	network: &Network<LocalFileSystem>,
+	node: &str,
+	metric: &str,
+	predicate: impl Fn(f64) -> bool,
+	timeout_secs: u64,
+) -> Result<(), anyhow::Error> {
+	let node = network.get_node(node)?;
+	tokio::time::timeout(Duration::from_secs(timeout_secs), node.wait_metric(metric, predicate))
+		.await
+		.map_err(|_| anyhow!("{metric} not reached on {} within {timeout_secs}s", node.name()))??;
+	Ok(())
+}
+
+/// Polls `metric` on all `nodes` until their sum satisfies `predicate`.
+async fn wait_metric_sum(
+	network: &Network<LocalFileSystem>,
+	nodes: &[&str],
+	metric: &str,
+	predicate: impl Fn(f64) -> bool,
+	timeout_secs: u64,
+) -> Result<(), anyhow::Error> {
+	tokio::time::timeout(Duration::from_secs(timeout_secs), async {
+		loop {
+			let mut sum = 0.0;
+			for name in nodes {
+				sum += network.get_node(*name)?.reports(metric).await?;
+			}
+			if predicate(sum) {
+				return Ok::<_, anyhow::Error>(())
+			}
+			tokio::time::sleep(Duration::from_secs(6)).await;
+		}
+	})
+	.await
+	.map_err(|_| anyhow!("{metric} not reached on {nodes:?} within {timeout_secs}s"))??;
+	Ok(())
+}
+
+/// No honest validator failed to recover the data of a candidate it had to check.
+async fn assert_no_failed_recoveries(
+	network: &Network<LocalFileSystem>,
+) -> Result<(), anyhow::Error> {
+	for name in HONEST_VALIDATORS {
+		let failures = network
+			.get_node(name)?
+			.reports(
+				"polkadot_parachain_availability_recovery_recoveries_finished{result=\"failure\"}",
+			)
+			.await?;
+		assert_eq!(failures, 0.0, "{name} failed {failures} recoveries");
+	}
+	Ok(())
+}
+
+/// The parachain keeps producing blocks and the relay chain keeps finalizing them.
+async fn assert_network_recovers(network: &Network<LocalFileSystem>) -> Result<(), anyhow::Error> {
+	wait_metric(network, "collator", "block_height{status=\"finalized\"}", |b| b >= 10.0, 600)
+		.await?;
+	for name in HONEST_VALIDATORS {
+		wait_metric(network, name, "block_height{status=\"finalized\"}", |b| b >= 20.0, 300)
+			.await?;
+	}
+	Ok(())
+}
+
+/// None of these misbehaviours is slashable, no candidate must be disputed and hence no honest
+/// validator can be slashed.
+async fn assert_no_disputes(network: &Network<LocalFileSystem>) -> Result<(), anyhow::Error> {
+	for name in HONEST_VALIDATORS {
+		let disputes = network
+			.get_node(name)?
+			.reports("polkadot_parachain_candidate_disputes_total")
+			.await?;
+		assert_eq!(disputes, 0.0, "{name} observed {disputes} disputes");
+	}
+	Ok(())
+}
+
+fn init_logger() {
+	let _ = env_logger::try_init_from_env(
+		env_logger::Env::default().filter_or(env_logger::DEFAULT_FILTER_ENV, "info"),
+	);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn withhold_availability_test() -> Result<(), anyhow::Error> {
+	init_logger();
+
+	let spawn_fn = zombienet_sdk::environment::get_spawn_fn();
+	let network = spawn_fn(malus_network_config("withhold-availability")?).await?;
+
+	// The malus answers chunk requests without its chunk...
+	wait_metric_sum(
+		&network,
+		&HONEST_VALIDATORS,
+		"polkadot_parachain_availability_recovery_chunk_requests_finished{result=\"no_such_chunk\"}",
+		|n| n >= 1.0,
+		600,
+	)
+	.await?;
+	// ...and the recoveries still succeed, from the honest validators' chunks.
+	for name in HONEST_VALIDATORS {
+		wait_metric(
+			&network,
+			name,
+			"polkadot_parachain_availability_recovery_recoveries_finished{result=\"success\"}",
+			|r| r >= 1.0,
+			600,
+		)
+		.await?;
+	}
+	assert_network_recovers(&network).await?;
+	assert_no_failed_recoveries(&network).await?;
+	assert_no_disputes(&network).await?;
+
+	Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn approval_no_show_test() -> Result<(), anyhow::Error> {
+	init_logger();
+
+	let spawn_fn = zombienet_sdk::environment::get_spawn_fn();
+	let network = spawn_fn(malus_network_config("approval-no-show")?).await?;
+
+	// The honest validators notice the malus no-shows...
+	for name in HONEST_VALIDATORS {
+		wait_metric(
+			&network,
+			name,
+			"polkadot_parachain_approvals_no_shows_total",
+			|n| n >= 1.0,
+			600,
+		)
+		.await?;
+	}
+	// ...and escalate: some of them trigger their assignments of a tranche above 0 to cover them,
+	// which makes the sum of the produced tranches positive.
+	wait_metric_sum(
+		&network,
+		&HONEST_VALIDATORS,
+		"polkadot_parachain_assignments_produced_sum",
+		|tranches| tranches > 0.0,
+		600,
+	)
+	.await?;
+	assert_network_recovers(&network).await?;
+	assert_no_disputes(&network).await?;
+
+	Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn bogus_bitfields_test() -> Result<(), anyhow::Error> {
+	init_logger();
+
+	let spawn_fn = zombienet_sdk::environment::get_spawn_fn();
+	let network = spawn_fn(malus_network_config("bogus-bitfields")?).await?;
+
+	// The bogus bitfields are received...
+	for name in HONEST_VALIDATORS {
+		wait_metric(
+			&network,
+			name,
+			"polkadot_parachain_received_availability_bitfields_total",
+			|b| b >= 1.0,
+			600,
+		)
+		.await?;
+	}
+	// ...but outvoted: candidates only become available with a supermajority of honest bitfields,
+	// so the approval checkers recover the data of every included candidate.
+	for name in HONEST_VALIDATORS {
+		wait_metric(
+			&network,
+			name,
+			"polkadot_parachain_availability_recovery_recoveries_finished{result=\"success\"}",
+			|r| r >= 1.0,
+			600,
+		)
+		.await?;
+	}
+	assert_network_recovers(&network).await?;
+	assert_no_failed_recoveries(&network).await?;
+	assert_no_disputes(&network).await?;
+
+	Ok(())
+}
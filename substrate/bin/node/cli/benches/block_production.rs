@@ -99,7 +99,7 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 		},
 		prometheus_config: None,
 		telemetry_endpoints: None,
-		offchain_worker: OffchainWorkerConfig { enabled: true, indexing_enabled: false },
+		offchain_worker: OffchainWorkerConfig::new(true, false),
 		force_authoring: false,
 		disable_grandpa: false,
 		dev_key_seed: Some(Sr25519Keyring::Alice.to_seed()),
@@ -114,6 +114,8 @@ fn new_node(tokio_handle: Handle) -> node_cli::service::NewFullBase {
 	node_cli::service::new_full_base::<sc_network::NetworkWorker<_, _>>(
 		config,
 		None,
+		None,
+		node_cli::Sealing::Babe,
 		false,
 		|_, _| (),
 	)
@@ -43,11 +43,62 @@ pub struct Cli {
 	#[arg(long)]
 	pub no_hardware_benchmarks: bool,
 
+	/// Submit the runtime's pending tasks as unsigned transactions.
+	///
+	/// On new best blocks the node asks the runtime which tasks can currently be executed and
+	/// submits them to the local transaction pool as `do_task` extrinsics.
+	#[arg(long)]
+	pub submit_tasks: bool,
+
+	/// Maximum number of tasks submitted per round by `--submit-tasks`.
+	#[arg(long, default_value_t = 16, requires = "submit_tasks")]
+	pub max_tasks_per_round: u32,
+
+	/// Number of new best blocks between two rounds of `--submit-tasks`.
+	#[arg(long, default_value_t = 1, requires = "submit_tasks")]
+	pub task_submission_interval: u32,
+
+	/// Monitor the GRANDPA gossip for equivocations, participation and finality stalls.
+	///
+	/// The state of the monitor is exposed through the `grandpa_monitorStatus` RPC and as
+	/// Prometheus metrics.
+	#[arg(long)]
+	pub grandpa_monitor: bool,
+
+	/// Number of blocks the finalized block may lag behind the best block before
+	/// `--grandpa-monitor` considers finality to be lagging.
+	#[arg(long, default_value_t = 32, requires = "grandpa_monitor")]
+	pub grandpa_monitor_finality_lag: u32,
+
+	/// Report the equivocations detected by `--grandpa-monitor` to the runtime.
+	#[arg(long, requires = "grandpa_monitor")]
+	pub grandpa_monitor_report_equivocations: bool,
+
+	/// How blocks are authored by a validator.
+	///
+	/// `manual` and `instant` seal are meant for dev chains, for example one forked with
+	/// `fork-spec`: blocks are authored on demand instead of in BABE slots, using the BABE key of
+	/// the local validator (e.g. `--alice`).
+	#[arg(long, value_enum, default_value_t = Sealing::Babe)]
+	pub sealing: Sealing,
+
 	#[allow(missing_docs)]
 	#[clap(flatten)]
 	pub storage_monitor: sc_storage_monitor::StorageMonitorParams,
 }
 
+/// The block authoring mode of a validator.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum Sealing {
+	/// Author blocks in the BABE slots won by the validator.
+	#[default]
+	Babe,
+	/// Author a block on every call to the `engine_createBlock` RPC.
+	Manual,
+	/// Author a block for every transaction imported into the transaction pool.
+	Instant,
+}
+
 /// Possible subcommands of the main binary.
 #[derive(Debug, clap::Subcommand)]
 pub enum Subcommand {
@@ -100,4 +151,36 @@ pub enum Subcommand {
 
 	/// Db meta columns information.
 	ChainInfo(sc_cli::ChainInfoCmd),
+
+	/// Create a chain spec that forks the state snapshot of a live chain into a local dev chain.
+	ForkSpec(ForkSpecCmd),
+}
+
+/// The `fork-spec` command.
+///
+/// Turns a state snapshot of a live chain into a raw chain spec whose genesis state is the
+/// snapshotted state, with the validator set replaced by a single dev validator.
+#[derive(Debug, Clone, clap::Parser)]
+pub struct ForkSpecCmd {
+	/// Path to the state snapshot of the live chain.
+	///
+	/// The snapshot can be created with `try-runtime create-snapshot`.
+	#[arg(long)]
+	pub snapshot: std::path::PathBuf,
+
+	/// Secret URI of the dev keys that replace the validator keys of the forked chain.
+	#[arg(long, default_value = "//Alice")]
+	pub dev_seed: String,
+
+	/// SS58 address of the account to set as the sudo key of the forked chain.
+	#[arg(long)]
+	pub sudo: Option<String>,
+
+	/// Output file name or stdout if unspecified.
+	#[arg(long, short)]
+	pub output: Option<std::path::PathBuf>,
+
+	#[allow(missing_docs)]
+	#[command(flatten)]
+	pub shared_params: sc_cli::SharedParams,
 }
@@ -0,0 +1,247 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Fork the state of a live chain into a local development chain.
+//!
+//! The dev overrides replace the validator set of the snapshotted state by a single validator
+//! using the keys derived from [`ForkSpecCmd::dev_seed`] and reset the BABE and GRANDPA progress,
+//! so the forked chain can be run with, for example:
+//!
+//! ```bash
+//! substrate-node fork-spec --chain <live_chain_spec.json> --snapshot <state.snap> \
+//!     --sudo 5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY > fork.json
+//! substrate-node --chain fork.json --alice --tmp --sealing instant
+//! ```
+//!
+//! With `--sealing manual` blocks are only authored on calls to the `engine_createBlock` RPC, while
+//! the default `--sealing babe` authors blocks in the BABE slots of the dev validator.
+//!
+//! New eras are disabled by setting `Staking::ForceEra` to `ForceNone`, otherwise the next
+//! election would bring back the validators of the live chain, whose keys are removed.
+
+use polkadot_sdk::*;
+
+use crate::cli::ForkSpecCmd;
+use codec::Encode;
+use frame_remote_externalities::{
+	overrides::{clear_prefix, twox_64_concat_key, value_key},
+	Builder, Mode, OfflineConfig, SnapshotConfig,
+};
+use kitchensink_runtime::SessionKeys;
+use node_primitives::{AccountId, Block};
+use pallet_im_online::sr25519::AuthorityId as ImOnlineId;
+use sc_chain_spec::ChainSpec;
+use sc_cli::{CliConfiguration, SharedParams};
+use sc_service::BasePath;
+use sp_authority_discovery::AuthorityId as AuthorityDiscoveryId;
+use sp_consensus_babe::AuthorityId as BabeId;
+use sp_consensus_beefy::ecdsa_crypto::AuthorityId as BeefyId;
+use sp_consensus_grandpa::AuthorityId as GrandpaId;
+use sp_core::{
+	crypto::{Pair, Public, Ss58Codec},
+	sr25519,
+	storage::StorageMap,
+};
+use sp_mixnet::types::AuthorityId as MixnetId;
+use sp_runtime::traits::{Header as HeaderT, OpaqueKeys};
+use std::{
+	fs,
+	io::{self, Write},
+};
+
+/// `pallet_staking::Forcing::ForceNone`.
+const FORCE_NONE: u8 = 2;
+
+impl ForkSpecCmd {
+	/// Run the fork-spec command.
+	///
+	/// `chain_spec` is the chain spec of the live chain, it provides the name, id and extensions of
+	/// the resulting chain spec, while its genesis state is replaced by the snapshotted state.
+	pub async fn run(&self, mut chain_spec: Box<dyn ChainSpec>) -> sc_cli::Result<()> {
+		let sudo = self
+			.sudo
+			.as_ref()
+			.map(|sudo| AccountId::from_ss58check(sudo))
+			.transpose()
+			.map_err(|e| format!("Invalid sudo account: {:?}", e))?;
+		let overrides = DevOverrides::new(&self.dev_seed, sudo)?;
+
+		let mut ext = Builder::<Block>::new()
+			.mode(Mode::Offline(OfflineConfig {
+				state_snapshot: SnapshotConfig::new(&self.snapshot),
+			}))
+			.build()
+			.await?;
+		log::info!("Forking the state of block #{}", ext.header.number());
+
+		let mut storage = ext.to_storage()?;
+		overrides.apply(&mut storage.top);
+		chain_spec.set_storage(storage);
+
+		let json = chain_spec.as_json(true)?;
+		if let Some(output) = &self.output {
+			fs::write(output, json)?;
+		} else {
+			io::stdout().write_all(json.as_bytes())?;
+		}
+
+		Ok(())
+	}
+}
+
+impl CliConfiguration for ForkSpecCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+
+	fn base_path(&self) -> sc_cli::Result<Option<BasePath>> {
+		// As we are just creating a chain spec a tmp database is enough.
+		Ok(Some(BasePath::new_temp_dir()?))
+	}
+}
+
+/// The storage overrides applied to the forked state to make it runnable by a local dev node.
+struct DevOverrides {
+	/// The stash account of the dev validator.
+	validator: AccountId,
+	/// The session keys of the dev validator.
+	keys: SessionKeys,
+	/// The account to set as the sudo key, if any.
+	sudo: Option<AccountId>,
+}
+
+impl DevOverrides {
+	fn new(dev_seed: &str, sudo: Option<AccountId>) -> sc_cli::Result<Self> {
+		let keys = SessionKeys {
+			grandpa: public::<GrandpaId>(dev_seed)?,
+			babe: public::<BabeId>(dev_seed)?,
+			im_online: public::<ImOnlineId>(dev_seed)?,
+			authority_discovery: public::<AuthorityDiscoveryId>(dev_seed)?,
+			mixnet: public::<MixnetId>(dev_seed)?,
+			beefy: public::<BeefyId>(dev_seed)?,
+		};
+		let validator = public::<sr25519::Public>(&format!("{dev_seed}//stash"))?.into();
+
+		Ok(Self { validator, keys, sudo })
+	}
+
+	fn apply(&self, top: &mut StorageMap) {
+		let keys = &self.keys;
+
+		// The dev validator becomes the only authority of every consensus engine.
+		top.insert(value_key(b"Babe", b"Authorities"), vec![(&keys.babe, 1u64)].encode());
+		top.insert(value_key(b"Babe", b"NextAuthorities"), vec![(&keys.babe, 1u64)].encode());
+		top.insert(value_key(b"Grandpa", b"Authorities"), vec![(&keys.grandpa, 1u64)].encode());
+		top.insert(value_key(b"Beefy", b"Authorities"), vec![&keys.beefy].encode());
+		top.insert(value_key(b"Beefy", b"NextAuthorities"), vec![&keys.beefy].encode());
+		top.insert(
+			value_key(b"AuthorityDiscovery", b"Keys"),
+			vec![&keys.authority_discovery].encode(),
+		);
+		top.insert(
+			value_key(b"AuthorityDiscovery", b"NextKeys"),
+			vec![&keys.authority_discovery].encode(),
+		);
+		top.insert(value_key(b"ImOnline", b"Keys"), vec![&keys.im_online].encode());
+
+		// Replace the session keys of the live validators.
+		clear_prefix(top, &value_key(b"Session", b"NextKeys"));
+		clear_prefix(top, &value_key(b"Session", b"KeyOwner"));
+		top.remove(&value_key(b"Session", b"DisabledValidators"));
+		top.remove(&value_key(b"Session", b"QueuedChanged"));
+		top.insert(value_key(b"Session", b"Validators"), vec![&self.validator].encode());
+		top.insert(value_key(b"Session", b"QueuedKeys"), vec![(&self.validator, keys)].encode());
+		top.insert(
+			twox_64_concat_key(b"Session", b"NextKeys", &self.validator.encode()),
+			keys.encode(),
+		);
+		for id in SessionKeys::key_ids() {
+			top.insert(
+				twox_64_concat_key(
+					b"Session",
+					b"KeyOwner",
+					&(id, keys.get_raw(*id).to_vec()).encode(),
+				),
+				self.validator.encode(),
+			);
+		}
+		top.insert(value_key(b"Staking", b"ForceEra"), FORCE_NONE.encode());
+
+		// The dev node starts a new BABE epoch and GRANDPA set at genesis.
+		for item in [
+			&b"GenesisSlot"[..],
+			b"CurrentSlot",
+			b"EpochIndex",
+			b"EpochStart",
+			b"SkippedEpochs",
+			b"Initialized",
+			b"AuthorVrfRandomness",
+			b"PendingEpochConfigChange",
+		] {
+			top.remove(&value_key(b"Babe", item));
+		}
+		for item in [&b"CurrentSetId"[..], b"State", b"PendingChange", b"Stalled"] {
+			top.remove(&value_key(b"Grandpa", item));
+		}
+		clear_prefix(top, &value_key(b"Grandpa", b"SetIdSession"));
+		top.insert(twox_64_concat_key(b"Grandpa", b"SetIdSession", &0u64.encode()), 0u32.encode());
+		top.remove(&value_key(b"Beefy", b"ValidatorSetId"));
+		clear_prefix(top, &value_key(b"Beefy", b"SetIdSession"));
+
+		if let Some(sudo) = &self.sudo {
+			top.insert(value_key(b"Sudo", b"Key"), sudo.encode());
+		}
+	}
+}
+
+/// Derive the public key of type `T` from the secret URI `seed`.
+fn public<T: Public>(seed: &str) -> sc_cli::Result<<T::Pair as Pair>::Public> {
+	Ok(T::Pair::from_string(seed, None)
+		.map_err(|e| format!("Invalid dev seed: {:?}", e))?
+		.public())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn dev_overrides_replace_validators_and_keys() {
+		let mut top = StorageMap::new();
+		let live = AccountId::new([7; 32]);
+		top.insert(twox_64_concat_key(b"Session", b"NextKeys", &live.encode()), vec![1, 2, 3]);
+		top.insert(value_key(b"Babe", b"CurrentSlot"), 42u64.encode());
+		top.insert(value_key(b"Grandpa", b"CurrentSetId"), 3u64.encode());
+
+		let overrides = DevOverrides::new("//Alice", None).unwrap();
+		overrides.apply(&mut top);
+
+		assert!(!top.contains_key(&twox_64_concat_key(b"Session", b"NextKeys", &live.encode())));
+		assert_eq!(
+			top.get(&twox_64_concat_key(b"Session", b"NextKeys", &overrides.validator.encode())),
+			Some(&overrides.keys.encode())
+		);
+		assert_eq!(
+			top.get(&value_key(b"Session", b"Validators")),
+			Some(&vec![&overrides.validator].encode())
+		);
+		assert!(!top.contains_key(&value_key(b"Babe", b"CurrentSlot")));
+		assert!(!top.contains_key(&value_key(b"Grandpa", b"CurrentSetId")));
+		assert_eq!(top.get(&value_key(b"Staking", b"ForceEra")), Some(&vec![FORCE_NONE]));
+	}
+}
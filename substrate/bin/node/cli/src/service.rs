@@ -25,7 +25,7 @@ use polkadot_sdk::{
 	sp_consensus_beefy as beefy_primitives, *,
 };
 
-use crate::Cli;
+use crate::{Cli, Sealing};
 use codec::Encode;
 use frame_benchmarking_cli::SUBSTRATE_REFERENCE_HARDWARE;
 use frame_system_rpc_runtime_api::AccountNonceApi;
@@ -34,6 +34,11 @@ use kitchensink_runtime::RuntimeApi;
 use node_primitives::Block;
 use sc_client_api::{Backend, BlockBackend};
 use sc_consensus_babe::{self, SlotProportion};
+use sc_consensus_manual_seal::{
+	consensus::babe::BabeConsensusDataProvider,
+	rpc::{ManualSeal, ManualSealApiServer},
+	InstantSealParams, ManualSealParams,
+};
 use sc_network::{
 	event::Event, service::traits::NetworkService, NetworkBackend, NetworkEventStream,
 };
@@ -46,7 +51,13 @@ use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sp_api::ProvideRuntimeApi;
 use sp_core::crypto::Pair;
 use sp_runtime::{generic, traits::Block as BlockT, SaturatedConversion};
-use std::{path::Path, sync::Arc};
+use std::{
+	path::Path,
+	sync::{
+		atomic::{AtomicU64, Ordering},
+		Arc,
+	},
+};
 
 /// Host functions required for kitchensink runtime and Substrate node.
 #[cfg(not(feature = "runtime-benchmarks"))]
@@ -166,10 +177,17 @@ pub fn create_extrinsic(
 	.into()
 }
 
+/// The sending end of the commands to the manual seal authorship task.
+type ManualSealSink = futures::channel::mpsc::Sender<
+	sc_consensus_manual_seal::EngineCommand<<Block as BlockT>::Hash>,
+>;
+
 /// Creates a new partial node.
 pub fn new_partial(
 	config: &Configuration,
 	mixnet_config: Option<&sc_mixnet::Config>,
+	grandpa_monitor: Option<grandpa::GrandpaMonitor<Block>>,
+	manual_seal_sink: Option<ManualSealSink>,
 ) -> Result<
 	sc_service::PartialComponents<
 		FullClient,
@@ -307,6 +325,7 @@ pub fn new_partial(
 		let shared_authority_set = grandpa_link.shared_authority_set().clone();
 		let shared_voter_state = grandpa::SharedVoterState::empty();
 		let shared_voter_state2 = shared_voter_state.clone();
+		let rpc_grandpa_monitor = grandpa_monitor.clone();
 
 		let finality_proof_provider = grandpa::FinalityProofProvider::new_for_service(
 			backend.clone(),
@@ -338,6 +357,7 @@ pub fn new_partial(
 						justification_stream: justification_stream.clone(),
 						subscription_executor: subscription_executor.clone(),
 						finality_provider: finality_proof_provider.clone(),
+						monitor: rpc_grandpa_monitor.clone(),
 					},
 					beefy: node_rpc::BeefyDeps::<beefy_primitives::ecdsa_crypto::AuthorityId> {
 						beefy_finality_proof_stream: beefy_rpc_links
@@ -353,10 +373,16 @@ pub fn new_partial(
 					mixnet_api: mixnet_api.as_ref().cloned(),
 				};
 
-				node_rpc::create_full(deps).map_err(Into::into)
+				let mut io = node_rpc::create_full(deps)?;
+				if let Some(command_sink) = manual_seal_sink.clone() {
+					io.merge(ManualSeal::new(command_sink).into_rpc())
+						.map_err(|e| ServiceError::Other(e.to_string()))?;
+				}
+
+				Ok(io)
 			};
 
-		(rpc_extensions_builder, shared_voter_state2)
+		(rpc_extensions_builder, (shared_voter_state2, grandpa_monitor))
 	};
 
 	Ok(sc_service::PartialComponents {
@@ -398,6 +424,8 @@ pub struct NewFullBase {
 pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 	config: Configuration,
 	mixnet_config: Option<sc_mixnet::Config>,
+	grandpa_monitor_config: Option<grandpa::MonitorConfig>,
+	sealing: Sealing,
 	disable_hardware_benchmarks: bool,
 	with_startup_data: impl FnOnce(
 		&sc_consensus_babe::BabeBlockImport<
@@ -417,6 +445,7 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 	let enable_grandpa = !config.disable_grandpa;
 	let prometheus_registry = config.prometheus_registry().cloned();
 	let enable_offchain_worker = config.offchain_worker.enabled;
+	let offchain_worker_limits = config.offchain_worker.limits.clone();
 
 	let hwbench = (!disable_hardware_benchmarks)
 		.then(|| {
@@ -427,6 +456,8 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		})
 		.flatten();
 
+	let (manual_seal_sink, manual_seal_commands) = futures::channel::mpsc::channel(1024);
+
 	let sc_service::PartialComponents {
 		client,
 		backend,
@@ -437,12 +468,19 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		transaction_pool,
 		other:
 			(rpc_builder, import_setup, rpc_setup, mut telemetry, statement_store, mixnet_api_backend),
-	} = new_partial(&config, mixnet_config.as_ref())?;
+	} = new_partial(
+		&config,
+		mixnet_config.as_ref(),
+		grandpa_monitor_config.map(|monitor_config| {
+			grandpa::GrandpaMonitor::new(monitor_config, config.prometheus_registry())
+		}),
+		(sealing == Sealing::Manual).then_some(manual_seal_sink),
+	)?;
 
 	let metrics = N::register_notification_metrics(
 		config.prometheus_config.as_ref().map(|cfg| &cfg.registry),
 	);
-	let shared_voter_state = rpc_setup;
+	let (shared_voter_state, grandpa_monitor) = rpc_setup;
 	let auth_disc_publish_non_global_ips = config.network.allow_non_globals_in_dht;
 	let auth_disc_public_addresses = config.network.public_addresses.clone();
 
@@ -595,18 +633,69 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 
 		let client_clone = client.clone();
 		let slot_duration = babe_link.config().slot_duration();
-		let babe_config = sc_consensus_babe::BabeParams {
-			keystore: keystore_container.keystore(),
-			client: client.clone(),
-			select_chain,
-			env: proposer,
-			block_import,
-			sync_oracle: sync_service.clone(),
-			justification_sync_link: sync_service.clone(),
-			create_inherent_data_providers: move |parent, ()| {
+		if sealing == Sealing::Babe {
+			let babe_config = sc_consensus_babe::BabeParams {
+				keystore: keystore_container.keystore(),
+				client: client.clone(),
+				select_chain,
+				env: proposer,
+				block_import,
+				sync_oracle: sync_service.clone(),
+				justification_sync_link: sync_service.clone(),
+				create_inherent_data_providers: move |parent, ()| {
+					let client_clone = client_clone.clone();
+					async move {
+						let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+
+						let slot =
+							sp_consensus_babe::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
+								*timestamp,
+								slot_duration,
+							);
+
+						let storage_proof =
+							sp_transaction_storage_proof::registration::new_data_provider(
+								&*client_clone,
+								&parent,
+							)?;
+
+						Ok((slot, timestamp, storage_proof))
+					}
+				},
+				force_authoring,
+				backoff_authoring_blocks,
+				babe_link,
+				block_proposal_slot_portion: SlotProportion::new(0.5),
+				max_block_proposal_slot_portion: None,
+				telemetry: telemetry.as_ref().map(|x| x.handle()),
+			};
+
+			let babe = sc_consensus_babe::start_babe(babe_config)?;
+			task_manager.spawn_essential_handle().spawn_blocking(
+				"babe-proposer",
+				Some("block-authoring"),
+				babe,
+			);
+		} else {
+			let consensus_data_provider = BabeConsensusDataProvider::new(
+				client.clone(),
+				keystore_container.keystore(),
+				babe_link.epoch_changes().clone(),
+				babe_link.config().authorities.clone(),
+			)
+			.map_err(|e| ServiceError::Other(e.to_string()))?;
+
+			// BABE only imports blocks of increasing slots, so every block advances the timestamp
+			// by at least one slot, even if blocks are sealed faster than that.
+			let last_timestamp = Arc::new(AtomicU64::new(0));
+			let create_inherent_data_providers = move |parent, ()| {
 				let client_clone = client_clone.clone();
+				let last_timestamp = last_timestamp.clone();
 				async move {
-					let timestamp = sp_timestamp::InherentDataProvider::from_system_time();
+					let next = last_timestamp.load(Ordering::Relaxed) + slot_duration.as_millis();
+					let timestamp = (*sp_timestamp::Timestamp::current()).max(next);
+					last_timestamp.store(timestamp, Ordering::Relaxed);
+					let timestamp = sp_timestamp::InherentDataProvider::new(timestamp.into());
 
 					let slot =
 						sp_consensus_babe::inherents::InherentDataProvider::from_timestamp_and_slot_duration(
@@ -622,21 +711,38 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 
 					Ok((slot, timestamp, storage_proof))
 				}
-			},
-			force_authoring,
-			backoff_authoring_blocks,
-			babe_link,
-			block_proposal_slot_portion: SlotProportion::new(0.5),
-			max_block_proposal_slot_portion: None,
-			telemetry: telemetry.as_ref().map(|x| x.handle()),
-		};
+			};
 
-		let babe = sc_consensus_babe::start_babe(babe_config)?;
-		task_manager.spawn_essential_handle().spawn_blocking(
-			"babe-proposer",
-			Some("block-authoring"),
-			babe,
-		);
+			let authorship = if sealing == Sealing::Manual {
+				sc_consensus_manual_seal::run_manual_seal(ManualSealParams {
+					block_import,
+					env: proposer,
+					client: client.clone(),
+					pool: transaction_pool.clone(),
+					commands_stream: manual_seal_commands,
+					select_chain,
+					consensus_data_provider: Some(Box::new(consensus_data_provider)),
+					create_inherent_data_providers,
+				})
+				.boxed()
+			} else {
+				sc_consensus_manual_seal::run_instant_seal(InstantSealParams {
+					block_import,
+					env: proposer,
+					client: client.clone(),
+					pool: transaction_pool.clone(),
+					select_chain,
+					consensus_data_provider: Some(Box::new(consensus_data_provider)),
+					create_inherent_data_providers,
+				})
+				.boxed()
+			};
+			task_manager.spawn_essential_handle().spawn_blocking(
+				"manual-seal",
+				Some("block-authoring"),
+				authorship,
+			);
+		}
 	}
 
 	// Spawn authority discovery module.
@@ -730,6 +836,21 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 	};
 
 	if enable_grandpa {
+		if let Some(monitor) = grandpa_monitor.clone() {
+			task_manager.spawn_handle().spawn(
+				"grandpa-monitor",
+				None,
+				grandpa::run_grandpa_monitor(grandpa::GrandpaMonitorParams {
+					monitor,
+					client: client.clone(),
+					authority_set: grandpa_link.shared_authority_set().clone(),
+					offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(
+						transaction_pool.clone(),
+					),
+				}),
+			);
+		}
+
 		// start the full GRANDPA voter
 		// NOTE: non-authorities could run the GRANDPA observer protocol, but at
 		// this point the full voter should provide better guarantees of block
@@ -754,7 +875,7 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 		task_manager.spawn_essential_handle().spawn_blocking(
 			"grandpa-voter",
 			None,
-			grandpa::run_grandpa_voter(grandpa_params)?,
+			grandpa::run_grandpa_voter_with_monitor(grandpa_params, grandpa_monitor)?,
 		);
 	}
 
@@ -790,6 +911,8 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 				network_provider: Arc::new(network.clone()),
 				is_validator: role.is_authority(),
 				enable_http_requests: true,
+				limits: offchain_worker_limits,
+				prometheus_registry: prometheus_registry.clone(),
 				custom_extensions: move |_| {
 					vec![Box::new(statement_store.clone().as_statement_store_ext()) as Box<_>]
 				},
@@ -815,30 +938,50 @@ pub fn new_full_base<N: NetworkBackend<Block, <Block as BlockT>::Hash>>(
 /// Builds a new service for a full client.
 pub fn new_full(config: Configuration, cli: Cli) -> Result<TaskManager, ServiceError> {
 	let mixnet_config = cli.mixnet_params.config(config.role.is_authority());
+	let grandpa_monitor_config = cli.grandpa_monitor.then(|| grandpa::MonitorConfig {
+		finality_lag_threshold: cli.grandpa_monitor_finality_lag,
+		report_equivocations: cli.grandpa_monitor_report_equivocations,
+		..Default::default()
+	});
 	let database_path = config.database.path().map(Path::to_path_buf);
 
-	let task_manager = match config.network.network_backend.unwrap_or_default() {
-		sc_network::config::NetworkBackendType::Libp2p => {
-			let task_manager = new_full_base::<sc_network::NetworkWorker<_, _>>(
-				config,
-				mixnet_config,
-				cli.no_hardware_benchmarks,
-				|_, _| (),
-			)
-			.map(|NewFullBase { task_manager, .. }| task_manager)?;
-			task_manager
-		},
-		sc_network::config::NetworkBackendType::Litep2p => {
-			let task_manager = new_full_base::<sc_network::Litep2pNetworkBackend>(
+	let NewFullBase { task_manager, client, transaction_pool, .. } =
+		match config.network.network_backend.unwrap_or_default() {
+			sc_network::config::NetworkBackendType::Libp2p =>
+				new_full_base::<sc_network::NetworkWorker<_, _>>(
+					config,
+					mixnet_config,
+					grandpa_monitor_config,
+					cli.sealing,
+					cli.no_hardware_benchmarks,
+					|_, _| (),
+				)?,
+			sc_network::config::NetworkBackendType::Litep2p =>
+				new_full_base::<sc_network::Litep2pNetworkBackend>(
+					config,
+					mixnet_config,
+					grandpa_monitor_config,
+					cli.sealing,
+					cli.no_hardware_benchmarks,
+					|_, _| (),
+				)?,
+		};
+
+	if cli.submit_tasks {
+		let config = frame_task_submitter::TaskSubmitterConfig {
+			max_tasks_per_round: cli.max_tasks_per_round,
+			interval: cli.task_submission_interval,
+		};
+		task_manager.spawn_handle().spawn(
+			"task-submitter",
+			None,
+			frame_task_submitter::run::<Block, _, _, kitchensink_runtime::RuntimeTask>(
 				config,
-				mixnet_config,
-				cli.no_hardware_benchmarks,
-				|_, _| (),
-			)
-			.map(|NewFullBase { task_manager, .. }| task_manager)?;
-			task_manager
-		},
-	};
+				client,
+				transaction_pool,
+			),
+		);
+	}
 
 	if let Some(database_path) = database_path {
 		sc_storage_monitor::StorageMonitorService::try_spawn(
@@ -919,6 +1062,8 @@ mod tests {
 					new_full_base::<sc_network::NetworkWorker<_, _>>(
 						config,
 						None,
+						None,
+						Sealing::Babe,
 						false,
 						|block_import: &sc_consensus_babe::BabeBlockImport<Block, _, _>,
 						 babe_link: &sc_consensus_babe::BabeLink<Block>| {
@@ -1117,6 +1262,8 @@ mod tests {
 					new_full_base::<sc_network::NetworkWorker<_, _>>(
 						config,
 						None,
+						None,
+						Sealing::Babe,
 						false,
 						|_, _| (),
 					)?;
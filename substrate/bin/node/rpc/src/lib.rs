@@ -41,7 +41,8 @@ use sc_consensus_beefy::communication::notification::{
 	BeefyBestBlockStream, BeefyVersionedFinalityProofStream,
 };
 use sc_consensus_grandpa::{
-	FinalityProofProvider, GrandpaJustificationStream, SharedAuthoritySet, SharedVoterState,
+	FinalityProofProvider, GrandpaJustificationStream, GrandpaMonitor, SharedAuthoritySet,
+	SharedVoterState,
 };
 pub use sc_rpc::SubscriptionTaskExecutor;
 use sc_transaction_pool_api::TransactionPool;
@@ -51,7 +52,7 @@ use sp_block_builder::BlockBuilder;
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_consensus::SelectChain;
 use sp_consensus_babe::BabeApi;
-use sp_consensus_beefy::AuthorityIdBound;
+use sp_consensus_beefy::{AuthorityIdBound, BeefyApi};
 use sp_keystore::KeystorePtr;
 
 /// Extra dependencies for BABE.
@@ -74,6 +75,8 @@ pub struct GrandpaDeps<B> {
 	pub subscription_executor: SubscriptionTaskExecutor,
 	/// Finality proof provider.
 	pub finality_provider: Arc<FinalityProofProvider<B, Block>>,
+	/// The GRANDPA gossip monitor, if enabled.
+	pub monitor: Option<GrandpaMonitor<Block>>,
 }
 
 /// Dependencies for BEEFY
@@ -136,8 +139,10 @@ where
 		+ 'static,
 	C::Api: substrate_frame_rpc_system::AccountNonceApi<Block, AccountId, Nonce>,
 	C::Api: mmr_rpc::MmrRuntimeApi<Block, <Block as sp_runtime::traits::Block>::Hash, BlockNumber>,
+	C::Api: pallet_migrations_rpc::MigrationsRuntimeApi<Block, BlockNumber>,
 	C::Api: pallet_transaction_payment_rpc::TransactionPaymentRuntimeApi<Block, Balance>,
 	C::Api: BabeApi<Block>,
+	C::Api: BeefyApi<Block, AuthorityId>,
 	C::Api: BlockBuilder<Block>,
 	P: TransactionPool + 'static,
 	SC: SelectChain<Block> + 'static,
@@ -147,10 +152,13 @@ where
 	<AuthorityId as RuntimeAppPublic>::Signature: Send + Sync,
 {
 	use mmr_rpc::{Mmr, MmrApiServer};
+	use pallet_migrations_rpc::{Migrations, MigrationsApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use sc_consensus_babe_rpc::{Babe, BabeApiServer};
-	use sc_consensus_beefy_rpc::{Beefy, BeefyApiServer};
-	use sc_consensus_grandpa_rpc::{Grandpa, GrandpaApiServer};
+	use sc_consensus_beefy_rpc::{Beefy, BeefyApiServer, BeefyProof, BeefyProofApiServer};
+	use sc_consensus_grandpa_rpc::{
+		Grandpa, GrandpaApiServer, GrandpaMonitorApiServer, GrandpaMonitorRpc,
+	};
 	use sc_rpc::{
 		dev::{Dev, DevApiServer},
 		mixnet::MixnetApiServer,
@@ -169,22 +177,22 @@ where
 		justification_stream,
 		subscription_executor,
 		finality_provider,
+		monitor,
 	} = grandpa;
 
 	io.merge(System::new(client.clone(), pool).into_rpc())?;
 	// Making synchronous calls in light client freezes the browser currently,
 	// more context: https://github.com/paritytech/substrate/pull/3480
 	// These RPCs should use an asynchronous caller instead.
+	let offchain_storage = backend
+		.offchain_storage()
+		.ok_or_else(|| "Backend doesn't provide an offchain storage")?;
+	io.merge(Mmr::new(client.clone(), offchain_storage.clone()).into_rpc())?;
 	io.merge(
-		Mmr::new(
-			client.clone(),
-			backend
-				.offchain_storage()
-				.ok_or_else(|| "Backend doesn't provide an offchain storage")?,
-		)
-		.into_rpc(),
+		BeefyProof::<_, Block, AuthorityId, _>::new(client.clone(), offchain_storage).into_rpc(),
 	)?;
 	io.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	io.merge(Migrations::new(client.clone()).into_rpc())?;
 	io.merge(
 		Babe::new(client.clone(), babe_worker_handle.clone(), keystore, select_chain).into_rpc(),
 	)?;
@@ -198,6 +206,9 @@ where
 		)
 		.into_rpc(),
 	)?;
+	if let Some(monitor) = monitor {
+		io.merge(GrandpaMonitorRpc::new(monitor).into_rpc())?;
+	}
 
 	io.merge(
 		SyncState::new(chain_spec, client.clone(), shared_authority_set, babe_worker_handle)?
@@ -2379,7 +2379,8 @@ mod runtime {
 		RuntimeHoldReason,
 		RuntimeSlashReason,
 		RuntimeLockId,
-		RuntimeTask
+		RuntimeTask,
+		RuntimeViewFunction
 	)]
 	pub struct Runtime;
 
@@ -2921,6 +2922,15 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl frame_support::view_functions::runtime_api::RuntimeViewFunction<Block> for Runtime {
+		fn execute_view_function(
+			id: frame_support::view_functions::ViewFunctionId,
+			input: Vec<u8>,
+		) -> Result<Vec<u8>, frame_support::view_functions::ViewFunctionDispatchError> {
+			Runtime::execute_view_function(id, input)
+		}
+	}
+
 	impl sp_block_builder::BlockBuilder<Block> for Runtime {
 		fn apply_extrinsic(extrinsic: <Block as BlockT>::Extrinsic) -> ApplyExtrinsicResult {
 			Executive::apply_extrinsic(extrinsic)
@@ -3000,6 +3010,12 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl pallet_migrations_runtime_api::MigrationsApi<Block, BlockNumber> for Runtime {
+		fn status() -> pallet_migrations_runtime_api::MigrationStatus<BlockNumber> {
+			MultiBlockMigrations::status()
+		}
+	}
+
 	impl pallet_nomination_pools_runtime_api::NominationPoolsApi<Block, AccountId, Balance> for Runtime {
 		fn pending_rewards(who: AccountId) -> Balance {
 			NominationPools::api_pending_rewards(who).unwrap_or_default()
@@ -3117,6 +3133,18 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl frame_system_rpc_runtime_api::TasksApi<Block, RuntimeTask> for Runtime {
+		fn pending_tasks(max: u32) -> Vec<(RuntimeTask, Weight)> {
+			System::pending_tasks(max)
+		}
+
+		fn task_extrinsic(task: RuntimeTask) -> <Block as BlockT>::Extrinsic {
+			<Runtime as frame_system::offchain::CreateInherent<RuntimeCall>>::create_inherent(
+				frame_system::Call::do_task { task }.into(),
+			)
+		}
+	}
+
 	impl assets_api::AssetsApi<
 		Block,
 		AccountId,
@@ -3566,6 +3594,7 @@ impl_runtime_apis! {
 	}
 
 	#[cfg(feature = "try-runtime")]
+	#[api_version(2)]
 	impl frame_try_runtime::TryRuntime<Block> for Runtime {
 		fn on_runtime_upgrade(checks: frame_try_runtime::UpgradeCheckSelect) -> (Weight, Weight) {
 			// NOTE: intentional unwrap: we don't want to propagate the error backwards, and want to
@@ -3585,6 +3614,14 @@ impl_runtime_apis! {
 			// have a backtrace here.
 			Executive::try_execute_block(block, state_root_check, signature_check, select).unwrap()
 		}
+
+		fn on_runtime_upgrade_and_migrations(
+			checks: frame_try_runtime::UpgradeCheckSelect,
+			max_blocks: u32,
+		) -> (Weight, u32) {
+			// NOTE: intentional unwrap, see `on_runtime_upgrade`.
+			Executive::try_runtime_upgrade_and_migrations(checks, max_blocks).unwrap()
+		}
 	}
 
 	#[cfg(feature = "runtime-benchmarks")]
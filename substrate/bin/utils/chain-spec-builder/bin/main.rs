@@ -23,7 +23,10 @@ use staging_chain_spec_builder as chain_spec_builder;
 //avoid error message escaping
 fn main() {
 	match inner_main() {
-		Err(e) => eprintln!("{}", format!("{e}")),
+		Err(e) => {
+			eprintln!("{e}");
+			std::process::exit(1);
+		},
 		_ => {},
 	}
 }
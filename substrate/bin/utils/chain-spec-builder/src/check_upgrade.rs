@@ -0,0 +1,320 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Safety checks for runtime upgrades.
+
+use clap::Parser;
+use frame_metadata::v15::RuntimeMetadataV15;
+use frame_storage_layout_diff::{runtime_metadata, TypeComparator};
+use sc_chain_spec::GenesisConfigBuilderRuntimeCaller;
+use sc_executor::{read_embedded_version, RuntimeVersion};
+use sc_executor_common::runtime_blob::RuntimeBlob;
+use scale_info::{form::PortableForm, PortableRegistry, TypeDef, Variant};
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+/// The runtime APIs every node relies on.
+const DEFAULT_REQUIRED_APIS: [&str; 4] =
+	["Core", "Metadata", "BlockBuilder", "TaggedTransactionQueue"];
+
+/// Checks that upgrading from one runtime to another is safe.
+///
+/// Compares the `RuntimeVersion`, the runtime APIs and the call encoding of the old and the new
+/// runtime, and builds all genesis presets of the new runtime. Fails if any problem is found, in
+/// particular if the call encoding changed without a `transaction_version` bump.
+#[derive(Parser, Debug, Clone)]
+pub struct CheckUpgradeCmd {
+	/// The path to the runtime wasm blob currently on chain.
+	#[arg(long)]
+	pub old: PathBuf,
+	/// The path to the runtime wasm blob to upgrade to.
+	#[arg(long)]
+	pub new: PathBuf,
+	/// A runtime API required by the node, in addition to `Core`, `Metadata`, `BlockBuilder` and
+	/// `TaggedTransactionQueue`. Can be given multiple times.
+	#[arg(long = "require-api", value_name = "NAME")]
+	pub required_apis: Vec<String>,
+	/// Do not build the genesis presets of the new runtime.
+	#[arg(long)]
+	pub skip_presets: bool,
+}
+
+impl CheckUpgradeCmd {
+	/// Runs the checks, printing all findings.
+	pub fn run(&self) -> Result<(), String> {
+		let old = fs::read(&self.old).map_err(|e| format!("wasm blob shall be readable {e}"))?;
+		let new = fs::read(&self.new).map_err(|e| format!("wasm blob shall be readable {e}"))?;
+
+		let report = check_upgrade(&old, &new, &self.required_apis, !self.skip_presets)?;
+		report.warnings.iter().for_each(|w| println!("warning: {w}"));
+		report.errors.iter().for_each(|e| println!("error: {e}"));
+
+		if report.errors.is_empty() {
+			println!("Runtime upgrade check: OK");
+			Ok(())
+		} else {
+			Err(format!("Runtime upgrade check failed with {} error(s)", report.errors.len()))
+		}
+	}
+}
+
+/// The findings of [`check_upgrade`].
+#[derive(Debug, Default, Clone)]
+pub struct UpgradeReport {
+	/// Problems that make the upgrade unsafe.
+	pub errors: Vec<String>,
+	/// Noteworthy changes that do not necessarily make the upgrade unsafe.
+	pub warnings: Vec<String>,
+}
+
+/// Check the upgrade from the runtime `old_code` to `new_code`.
+///
+/// `required_apis` are the names of runtime APIs the new runtime must provide, in addition to
+/// those every node relies on and all APIs provided by the old runtime. If `build_presets` is set,
+/// the genesis state of every preset of the new runtime is built.
+pub fn check_upgrade(
+	old_code: &[u8],
+	new_code: &[u8],
+	required_apis: &[String],
+	build_presets: bool,
+) -> Result<UpgradeReport, String> {
+	let old_version = runtime_version(old_code)?;
+	let new_version = runtime_version(new_code)?;
+	let old_metadata = runtime_metadata(old_code)?;
+	let new_metadata = runtime_metadata(new_code)?;
+	let mut report = UpgradeReport::default();
+
+	check_versions(&old_version, &new_version, &mut report);
+	check_apis(
+		&old_version,
+		&new_version,
+		&old_metadata,
+		&new_metadata,
+		required_apis,
+		&mut report,
+	);
+
+	let changes = encoding_changes(&old_metadata, &new_metadata);
+	match (changes.is_empty(), new_version.transaction_version > old_version.transaction_version) {
+		(false, false) => report.errors.extend(
+			changes
+				.into_iter()
+				.map(|c| format!("{c}, but `transaction_version` was not bumped")),
+		),
+		(true, true) => report.warnings.push(
+			"`transaction_version` was bumped, but the transaction encoding did not change".into(),
+		),
+		_ => {},
+	}
+
+	if build_presets {
+		check_presets(new_code, &mut report);
+	}
+
+	Ok(report)
+}
+
+fn runtime_version(code: &[u8]) -> Result<RuntimeVersion, String> {
+	let blob = RuntimeBlob::uncompress_if_needed(code)
+		.map_err(|e| format!("invalid runtime wasm blob: {e}"))?;
+
+	read_embedded_version(&blob)
+		.map_err(|e| format!("failed to read the runtime version: {e}"))?
+		.ok_or_else(|| "the runtime wasm blob does not embed its version".into())
+}
+
+fn check_versions(old: &RuntimeVersion, new: &RuntimeVersion, report: &mut UpgradeReport) {
+	if old.spec_name != new.spec_name {
+		report
+			.errors
+			.push(format!("`spec_name` changed from {} to {}", old.spec_name, new.spec_name));
+	}
+	if new.spec_version <= old.spec_version {
+		report.errors.push(format!(
+			"`spec_version` must increase, but went from {} to {}",
+			old.spec_version, new.spec_version
+		));
+	}
+	if new.transaction_version < old.transaction_version {
+		report.errors.push(format!(
+			"`transaction_version` decreased from {} to {}",
+			old.transaction_version, new.transaction_version
+		));
+	}
+	if old.system_version != new.system_version {
+		report.warnings.push(format!(
+			"`system_version` changed from {} to {}, which changes how the state root is computed",
+			old.system_version, new.system_version
+		));
+	}
+}
+
+fn check_apis(
+	old: &RuntimeVersion,
+	new: &RuntimeVersion,
+	old_metadata: &RuntimeMetadataV15,
+	new_metadata: &RuntimeMetadataV15,
+	required_apis: &[String],
+	report: &mut UpgradeReport,
+) {
+	let names = old_metadata
+		.apis
+		.iter()
+		.chain(&new_metadata.apis)
+		.map(|api| (sp_crypto_hashing::blake2_64(api.name.as_bytes()), api.name.clone()))
+		.collect::<BTreeMap<_, _>>();
+	let name_of =
+		|id: &[u8; 8]| names.get(id).cloned().unwrap_or_else(|| array_bytes::bytes2hex("0x", id));
+	let new_apis = new.apis.iter().cloned().collect::<BTreeMap<_, _>>();
+
+	for (id, old_api_version) in old.apis.iter() {
+		match new_apis.get(id) {
+			None => report.errors.push(format!("runtime API {} was removed", name_of(id))),
+			Some(new_api_version) if new_api_version < old_api_version =>
+				report.errors.push(format!(
+					"runtime API {} was downgraded from version {old_api_version} to \
+					 {new_api_version}",
+					name_of(id)
+				)),
+			Some(new_api_version) if new_api_version > old_api_version =>
+				report.warnings.push(format!(
+					"runtime API {} was upgraded from version {old_api_version} to \
+					 {new_api_version}",
+					name_of(id)
+				)),
+			_ => {},
+		}
+	}
+
+	let required = DEFAULT_REQUIRED_APIS
+		.iter()
+		.map(|n| n.to_string())
+		.chain(required_apis.iter().cloned());
+	for name in required {
+		if !new_apis.contains_key(&sp_crypto_hashing::blake2_64(name.as_bytes())) {
+			report.errors.push(format!("required runtime API {name} is not provided"));
+		}
+	}
+}
+
+/// All changes to the encoding of transactions from `old` to `new`.
+///
+/// Adding calls or pallets does not change the encoding of existing transactions and is not
+/// reported.
+fn encoding_changes(old: &RuntimeMetadataV15, new: &RuntimeMetadataV15) -> Vec<String> {
+	let mut types = TypeComparator::new(&old.types, &new.types);
+	let mut changes = Vec::new();
+
+	let (old_extrinsic, new_extrinsic) = (&old.extrinsic, &new.extrinsic);
+	if old_extrinsic.version != new_extrinsic.version {
+		changes.push(format!(
+			"the extrinsic version changed from {} to {}",
+			old_extrinsic.version, new_extrinsic.version
+		));
+	}
+	if !types.same_encoding(old_extrinsic.address_ty.id, new_extrinsic.address_ty.id) {
+		changes.push("the encoding of the transaction address changed".into());
+	}
+	if !types.same_encoding(old_extrinsic.signature_ty.id, new_extrinsic.signature_ty.id) {
+		changes.push("the encoding of the transaction signature changed".into());
+	}
+	let same_extensions = old_extrinsic.signed_extensions.len() ==
+		new_extrinsic.signed_extensions.len() &&
+		old_extrinsic
+			.signed_extensions
+			.iter()
+			.zip(&new_extrinsic.signed_extensions)
+			.all(|(o, n)| {
+				o.identifier == n.identifier &&
+					types.same_encoding(o.ty.id, n.ty.id) &&
+					types.same_encoding(o.additional_signed.id, n.additional_signed.id)
+			});
+	if !same_extensions {
+		changes.push("the transaction extensions changed".into());
+	}
+
+	for old_pallet in &old.pallets {
+		let Some(old_calls) = &old_pallet.calls else { continue };
+		let pallet = &old_pallet.name;
+
+		let Some(new_pallet) = new.pallets.iter().find(|p| p.name == old_pallet.name) else {
+			changes.push(format!("pallet {pallet} and its calls were removed"));
+			continue
+		};
+		if new_pallet.index != old_pallet.index {
+			changes.push(format!(
+				"pallet {pallet} moved from index {} to {}",
+				old_pallet.index, new_pallet.index
+			));
+		}
+		let Some(new_calls) = &new_pallet.calls else {
+			changes.push(format!("all calls of pallet {pallet} were removed"));
+			continue
+		};
+
+		let old_variants = variants(&old.types, old_calls.ty.id);
+		let new_variants = variants(&new.types, new_calls.ty.id);
+		for old_call in old_variants {
+			let call = &old_call.name;
+			match new_variants.iter().find(|c| c.name == old_call.name) {
+				None => changes.push(format!("call {pallet}::{call} was removed")),
+				Some(new_call) if new_call.index != old_call.index => changes.push(format!(
+					"call {pallet}::{call} moved from index {} to {}",
+					old_call.index, new_call.index
+				)),
+				Some(new_call)
+					if new_call.fields.len() != old_call.fields.len() ||
+						!old_call
+							.fields
+							.iter()
+							.zip(&new_call.fields)
+							.all(|(o, n)| types.same_encoding(o.ty.id, n.ty.id)) =>
+					changes.push(format!("the arguments of call {pallet}::{call} changed")),
+				_ => {},
+			}
+		}
+	}
+
+	changes
+}
+
+/// The variants of the enum `ty`, or none if it is not an enum.
+fn variants(registry: &PortableRegistry, ty: u32) -> &[Variant<PortableForm>] {
+	match registry.resolve(ty).map(|t| &t.type_def) {
+		Some(TypeDef::Variant(variant)) => &variant.variants,
+		_ => &[],
+	}
+}
+
+fn check_presets(code: &[u8], report: &mut UpgradeReport) {
+	let caller: GenesisConfigBuilderRuntimeCaller = GenesisConfigBuilderRuntimeCaller::new(code);
+
+	let presets = match caller.preset_names() {
+		Ok(presets) => presets,
+		Err(e) => {
+			report.errors.push(format!("the genesis presets could not be listed: {e}"));
+			return
+		},
+	};
+
+	for preset in presets.iter().map(Some).chain([None]) {
+		if let Err(e) = caller.get_storage_for_named_preset(preset) {
+			let name = preset.map_or("default".into(), |p| format!("`{p}`"));
+			report.errors.push(format!("the {name} genesis preset does not build: {e}"));
+		}
+	}
+}
@@ -19,6 +19,10 @@
 #[cfg(feature = "generate-readme")]
 docify::compile_markdown!("README.docify.md", "README.md");
 
+mod check_upgrade;
+
+pub use check_upgrade::{check_upgrade, CheckUpgradeCmd, UpgradeReport};
+
 use clap::{Parser, Subcommand};
 use sc_chain_spec::{
 	json_patch, set_code_substitute_in_json_chain_spec, update_code_in_json_chain_spec, ChainType,
@@ -53,6 +57,7 @@ pub enum ChainSpecBuilderCmd {
 	ListPresets(ListPresetsCmd),
 	DisplayPreset(DisplayPresetCmd),
 	AddCodeSubstitute(AddCodeSubstituteCmd),
+	CheckUpgrade(CheckUpgradeCmd),
 }
 
 /// Create a new chain spec by interacting with the provided runtime wasm blob.
@@ -305,6 +310,7 @@ impl ChainSpecBuilder {
 					.map_err(|e| format!("getting default config from runtime should work: {e}"))?;
 				println!("{preset}");
 			},
+			ChainSpecBuilderCmd::CheckUpgrade(cmd) => cmd.run()?,
 		}
 		Ok(())
 	}
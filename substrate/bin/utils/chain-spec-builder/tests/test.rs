@@ -23,7 +23,7 @@ use clap::Parser;
 use cmd_lib::spawn_with_output;
 use sc_chain_spec::update_code_in_json_chain_spec;
 use serde_json::{from_reader, from_str, Value};
-use staging_chain_spec_builder::ChainSpecBuilder;
+use staging_chain_spec_builder::{check_upgrade, ChainSpecBuilder};
 
 // note: the runtime path will not be read, runtime code will be set directly, to avoid hassle with
 // creating the wasm file or providing a valid existing path during test execution.
@@ -395,3 +395,17 @@ fn create_full_raw() {
 		true,
 	);
 }
+
+#[test]
+fn check_upgrade_reports_problems() {
+	let code = substrate_test_runtime::WASM_BINARY.unwrap();
+	let report = check_upgrade(code, code, &["NotAnApi".into()], true).unwrap();
+
+	// The call encoding is unchanged, but the version is not bumped either, a required API is
+	// missing and the `foobar` preset is not a valid genesis config.
+	assert_eq!(report.errors.len(), 3, "{:?}", report.errors);
+	assert!(report.errors[0].starts_with("`spec_version` must increase"));
+	assert!(report.errors[1].starts_with("required runtime API NotAnApi"));
+	assert!(report.errors[2].starts_with("the `foobar` genesis preset does not build"));
+	assert!(report.warnings.is_empty());
+}
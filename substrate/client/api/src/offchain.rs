@@ -0,0 +1,46 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Node-side configuration of the offchain workers.
+
+use std::time::Duration;
+
+/// Resource limits enforced on the offchain workers.
+///
+/// Budgets and quotas apply to the execution of the offchain workers triggered by a single
+/// block. `None` means unlimited.
+#[derive(Debug, Clone, Default)]
+pub struct OffchainWorkerLimits {
+	/// Hosts the offchain workers are allowed to send HTTP requests to.
+	///
+	/// Every entry is either a host name (`example.com`), matching that host only, or a
+	/// wildcard (`*.example.com`), matching all the subdomains of the given host.
+	/// If `None` any host can be contacted.
+	pub http_allowed_hosts: Option<Vec<String>>,
+	/// Maximum number of HTTP requests started per block.
+	pub max_http_requests: Option<u32>,
+	/// Wall-clock time budget per block.
+	///
+	/// Once the budget is exhausted new HTTP requests are refused and the host calls which
+	/// would block waiting for a deadline (HTTP calls and sleeps) return immediately.
+	pub time_budget: Option<Duration>,
+	/// Maximum number of bytes, keys and values, written to the local storage per block.
+	///
+	/// Writes exceeding the quota are refused.
+	pub local_storage_quota: Option<u64>,
+}
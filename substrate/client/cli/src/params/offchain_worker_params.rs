@@ -25,7 +25,8 @@
 
 use clap::{ArgAction, Args};
 use sc_network::config::Role;
-use sc_service::config::OffchainWorkerConfig;
+use sc_service::config::{OffchainWorkerConfig, OffchainWorkerLimits};
+use std::time::Duration;
 
 use crate::{error, OffchainWorkerEnabled};
 
@@ -47,6 +48,27 @@ pub struct OffchainWorkerParams {
 	/// Allows the runtime to write directly to offchain workers DB during block import.
 	#[arg(long = "enable-offchain-indexing", value_name = "ENABLE_OFFCHAIN_INDEXING", default_value_t = false, action = ArgAction::Set)]
 	pub indexing_enabled: bool,
+
+	/// Restrict the hosts offchain workers may send HTTP requests to.
+	///
+	/// Can be passed multiple times. A leading `*.` matches any subdomain, e.g.
+	/// `*.example.com`. If not given, offchain workers may contact any host.
+	#[arg(long = "offchain-worker-http-allow", value_name = "HOST")]
+	pub http_allowed_hosts: Vec<String>,
+
+	/// Maximum number of HTTP requests a single offchain worker run may start.
+	#[arg(long = "offchain-worker-max-http-requests", value_name = "COUNT")]
+	pub max_http_requests: Option<u32>,
+
+	/// Time budget in milliseconds for a single offchain worker run.
+	///
+	/// HTTP requests and sleeps are cut short once the budget is exhausted.
+	#[arg(long = "offchain-worker-time-budget", value_name = "MS")]
+	pub time_budget: Option<u64>,
+
+	/// Maximum number of bytes a single offchain worker run may write to local storage.
+	#[arg(long = "offchain-worker-storage-quota", value_name = "BYTES")]
+	pub local_storage_quota: Option<u64>,
 }
 
 impl OffchainWorkerParams {
@@ -60,6 +82,13 @@ impl OffchainWorkerParams {
 		};
 
 		let indexing_enabled = self.indexing_enabled;
-		Ok(OffchainWorkerConfig { enabled, indexing_enabled })
+		let limits = OffchainWorkerLimits {
+			http_allowed_hosts: (!self.http_allowed_hosts.is_empty())
+				.then(|| self.http_allowed_hosts.clone()),
+			max_http_requests: self.max_http_requests,
+			time_budget: self.time_budget.map(Duration::from_millis),
+			local_storage_quota: self.local_storage_quota,
+		};
+		Ok(OffchainWorkerConfig::new(enabled, indexing_enabled).with_limits(limits))
 	}
 }
@@ -28,6 +28,7 @@ use sc_rpc::{
 	utils::{BoundedVecDeque, PendingSubscription},
 	SubscriptionTaskExecutor,
 };
+use sc_rpc_api::UnsafeRpcError;
 use sp_application_crypto::RuntimeAppPublic;
 use sp_runtime::traits::Block as BlockT;
 
@@ -35,7 +36,7 @@ use futures::{task::SpawnError, FutureExt, StreamExt};
 use jsonrpsee::{
 	core::async_trait,
 	proc_macros::rpc,
-	types::{ErrorObject, ErrorObjectOwned},
+	types::{error::METHOD_NOT_FOUND_CODE, ErrorObject, ErrorObjectOwned},
 	PendingSubscriptionSink,
 };
 use log::warn;
@@ -45,6 +46,9 @@ use sc_consensus_beefy::communication::notification::{
 };
 
 mod notification;
+mod proof_bundle;
+
+pub use proof_bundle::{BeefyProof, BeefyProofApiClient, BeefyProofApiServer, EncodedProofBundle};
 
 #[derive(Debug, thiserror::Error)]
 /// Top-level error type for the RPC handler
@@ -55,6 +59,12 @@ pub enum Error {
 	/// The BEEFY RPC background task failed to spawn.
 	#[error("BEEFY RPC background task failed to spawn")]
 	RpcTaskFailure(#[from] SpawnError),
+	/// A BEEFY proof bundle could not be generated.
+	#[error("BEEFY proof bundle unavailable: {0}")]
+	ProofBundleUnavailable(String),
+	/// Call to an unsafe RPC was denied.
+	#[error(transparent)]
+	UnsafeRpcCalled(#[from] UnsafeRpcError),
 }
 
 /// The error codes returned by jsonrpc.
@@ -63,6 +73,10 @@ pub enum ErrorCode {
 	NotReady = 1,
 	/// Returned on BEEFY RPC background task failure.
 	TaskFailure = 2,
+	/// Returned when a BEEFY proof bundle could not be generated.
+	ProofBundleUnavailable = 3,
+	/// Returned when an unsafe RPC was called externally.
+	UnsafeRpcCalled = METHOD_NOT_FOUND_CODE as isize,
 }
 
 impl From<Error> for ErrorCode {
@@ -70,6 +84,8 @@ impl From<Error> for ErrorCode {
 		match error {
 			Error::EndpointNotReady => ErrorCode::NotReady,
 			Error::RpcTaskFailure(_) => ErrorCode::TaskFailure,
+			Error::ProofBundleUnavailable(_) => ErrorCode::ProofBundleUnavailable,
+			Error::UnsafeRpcCalled(_) => ErrorCode::UnsafeRpcCalled,
 		}
 	}
 }
@@ -0,0 +1,470 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! RPC API returning self-contained BEEFY proof bundles for light clients.
+
+use std::{marker::PhantomData, sync::Arc};
+
+use codec::{Decode, Encode};
+use jsonrpsee::{proc_macros::rpc, Extensions};
+use serde::{Deserialize, Serialize};
+
+use sc_client_api::BlockBackend;
+use sc_consensus_beefy::justification::BeefyVersionedFinalityProof;
+use sc_rpc_api::check_if_safe;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_consensus_beefy::{
+	AuthorityIdBound, BeefyApi as BeefyRuntimeApi, MmrRootHash, VersionedFinalityProof,
+	BEEFY_ENGINE_ID,
+};
+use sp_consensus_beefy_light_client::BeefyProofBundle;
+use sp_core::{
+	offchain::{storage::OffchainDb, OffchainDbExt, OffchainStorage},
+	Bytes,
+};
+use sp_mmr_primitives::MmrApi;
+use sp_runtime::traits::{Block as BlockT, NumberFor, One, Saturating};
+
+use crate::Error;
+
+/// The maximum number of blocks after the requested one that are searched for a BEEFY
+/// justification.
+const MAX_JUSTIFICATION_SEARCH_DEPTH: u32 = 4096;
+
+/// A SCALE-encoded [`BeefyProofBundle`] proving a block of a BEEFY finalized chain.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EncodedProofBundle<Hash> {
+	/// Hash of the BEEFY finalized block whose signed commitment is part of the bundle.
+	pub commitment_block_hash: Hash,
+	/// The SCALE-encoded [`BeefyProofBundle`].
+	pub bundle: Bytes,
+}
+
+/// Provides RPC methods for BEEFY light clients.
+#[rpc(client, server)]
+pub trait BeefyProofApi<Number, Hash> {
+	/// Returns a self-contained BEEFY proof bundle for the given block.
+	///
+	/// The bundle contains the first stored BEEFY justification of a block at or after the given
+	/// one, the validator set that signed it and the MMR leaf of the given block together with its
+	/// proof against the MMR root signed by the justification. Generating MMR proofs requires the
+	/// node to run with offchain indexing enabled.
+	///
+	/// Finding the justification may read the headers and justifications of up to
+	/// `MAX_JUSTIFICATION_SEARCH_DEPTH` blocks, so this method is unsafe.
+	#[method(name = "beefy_getProofBundle", blocking, with_extensions)]
+	fn proof_bundle(&self, block_number: Number) -> Result<EncodedProofBundle<Hash>, Error>;
+}
+
+/// Implements the [`BeefyProofApiServer`] on top of a client.
+pub struct BeefyProof<Client, Block, AuthorityId, S> {
+	client: Arc<Client>,
+	offchain_db: OffchainDb<S>,
+	_phantom: PhantomData<(Block, AuthorityId)>,
+}
+
+impl<Client, Block, AuthorityId, S> BeefyProof<Client, Block, AuthorityId, S>
+where
+	S: OffchainStorage,
+{
+	/// Create a new [`BeefyProof`] RPC handler.
+	pub fn new(client: Arc<Client>, offchain_storage: S) -> Self {
+		Self { client, offchain_db: OffchainDb::new(offchain_storage), _phantom: PhantomData }
+	}
+}
+
+impl<Client, Block, AuthorityId, S> BeefyProof<Client, Block, AuthorityId, S>
+where
+	Block: BlockT,
+	AuthorityId: AuthorityIdBound,
+	Client: HeaderBackend<Block> + BlockBackend<Block>,
+{
+	/// Find the first BEEFY justification at or after `block_number`.
+	fn find_finality_proof(
+		&self,
+		block_number: NumberFor<Block>,
+	) -> Result<(Block::Hash, BeefyVersionedFinalityProof<Block, AuthorityId>), Error> {
+		let finalized_number = self.client.info().finalized_number;
+		let last = finalized_number
+			.min(block_number.saturating_add(MAX_JUSTIFICATION_SEARCH_DEPTH.into()));
+
+		let mut number = block_number;
+		while number <= last {
+			let hash = self
+				.client
+				.hash(number)
+				.map_err(|e| Error::ProofBundleUnavailable(e.to_string()))?
+				.ok_or_else(|| {
+					Error::ProofBundleUnavailable(format!("Unknown block #{}", number))
+				})?;
+			let encoded = self
+				.client
+				.justifications(hash)
+				.map_err(|e| Error::ProofBundleUnavailable(e.to_string()))?
+				.and_then(|justifications| justifications.into_justification(BEEFY_ENGINE_ID));
+			if let Some(encoded) = encoded {
+				let proof = Decode::decode(&mut &encoded[..])
+					.map_err(|e| Error::ProofBundleUnavailable(e.to_string()))?;
+				return Ok((hash, proof))
+			}
+			number = number.saturating_add(One::one());
+		}
+
+		Err(Error::ProofBundleUnavailable(format!(
+			"No BEEFY justification found for block #{}",
+			block_number
+		)))
+	}
+}
+
+impl<Client, Block, AuthorityId, S> BeefyProofApiServer<NumberFor<Block>, Block::Hash>
+	for BeefyProof<Client, Block, AuthorityId, S>
+where
+	Block: BlockT,
+	AuthorityId: AuthorityIdBound,
+	<AuthorityId as sp_application_crypto::RuntimeAppPublic>::Signature: Send + Sync,
+	Client: ProvideRuntimeApi<Block> + HeaderBackend<Block> + BlockBackend<Block>,
+	Client: Send + Sync + 'static,
+	Client::Api: BeefyRuntimeApi<Block, AuthorityId>,
+	Client::Api: MmrApi<Block, MmrRootHash, NumberFor<Block>>,
+	S: OffchainStorage + 'static,
+{
+	fn proof_bundle(
+		&self,
+		ext: &Extensions,
+		block_number: NumberFor<Block>,
+	) -> Result<EncodedProofBundle<Block::Hash>, Error> {
+		check_if_safe(ext)?;
+
+		let (hash, finality_proof) = self.find_finality_proof(block_number)?;
+		let VersionedFinalityProof::V1(signed_commitment) = finality_proof;
+		let commitment = &signed_commitment.commitment;
+
+		let mut api = self.client.runtime_api();
+		api.register_extension(OffchainDbExt::new(self.offchain_db.clone()));
+
+		let validator_set = api
+			.validator_set(hash)
+			.map_err(|e| Error::ProofBundleUnavailable(e.to_string()))?
+			.filter(|set| set.id() == commitment.validator_set_id)
+			.ok_or_else(|| {
+				Error::ProofBundleUnavailable(format!(
+					"Validator set #{} not found",
+					commitment.validator_set_id
+				))
+			})?;
+
+		let (leaves, leaf_proof) = api
+			.generate_proof(hash, vec![block_number], Some(commitment.block_number))
+			.map_err(|e| Error::ProofBundleUnavailable(e.to_string()))?
+			.map_err(|e| Error::ProofBundleUnavailable(e.to_string()))?;
+		let leaf = leaves.into_iter().next().ok_or_else(|| {
+			Error::ProofBundleUnavailable(format!("No MMR leaf for block #{}", block_number))
+		})?;
+
+		let bundle =
+			BeefyProofBundle { signed_commitment, validator_set, leaf: leaf.0, leaf_proof };
+		Ok(EncodedProofBundle { commitment_block_hash: hash, bundle: bundle.encode().into() })
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	use jsonrpsee::RpcModule;
+	use sc_block_builder::BlockBuilderBuilder;
+	use sc_rpc_api::DenyUnsafe;
+	use sp_api::ApiRef;
+	use sp_consensus::BlockOrigin;
+	use sp_consensus_beefy::{
+		ecdsa_crypto::{AuthorityId, Signature},
+		known_payloads::MMR_ROOT_ID,
+		mmr::{BeefyAuthoritySet, MmrLeaf, MmrLeafVersion},
+		test_utils::Keyring,
+		Commitment, MmrHashing, Payload, SignedCommitment, ValidatorSet,
+	};
+	use sp_consensus_beefy_light_client::{
+		authority_set_commitment, BeefyEcdsaToEthereum, BeefyLightClient,
+	};
+	use sp_core::{offchain::storage::InMemOffchainStorage, H256};
+	use sp_mmr_primitives::{mmr_lib, EncodableOpaqueLeaf, Error as MmrError, LeafProof};
+	use sp_runtime::{traits::Hash as _, Justification};
+	use substrate_test_runtime_client::{
+		runtime::Block, Backend, Client, ClientBlockImportExt, ClientExt,
+		DefaultTestClientBuilderExt, TestClientBuilder, TestClientBuilderExt,
+	};
+
+	type TestLeaf = MmrLeaf<u64, H256, MmrRootHash, Vec<u8>>;
+	type TestBundle = BeefyProofBundle<u64, AuthorityId, Signature>;
+
+	const AUTHORITIES: [Keyring<AuthorityId>; 4] =
+		[Keyring::Alice, Keyring::Bob, Keyring::Charlie, Keyring::Dave];
+
+	struct MmrMerge;
+
+	impl mmr_lib::Merge for MmrMerge {
+		type Item = MmrRootHash;
+
+		fn merge(left: &Self::Item, right: &Self::Item) -> mmr_lib::Result<Self::Item> {
+			let mut concat = left.as_bytes().to_vec();
+			concat.extend_from_slice(right.as_bytes());
+			Ok(MmrHashing::hash(&concat))
+		}
+	}
+
+	/// A runtime with an MMR leaf for every block of the chain, starting at block #1.
+	#[derive(Clone)]
+	struct TestApi {
+		validator_set: ValidatorSet<AuthorityId>,
+		leaves: Vec<Vec<u8>>,
+	}
+
+	impl TestApi {
+		/// Generate a proof of `leaf_index` in the MMR made of the first `leaf_count` leaves.
+		fn generate_proof(&self, leaf_index: u64, leaf_count: u64) -> (MmrRootHash, Vec<H256>) {
+			let store = mmr_lib::util::MemStore::default();
+			let mut mmr = mmr_lib::util::MemMMR::<_, MmrMerge>::new(0, &store);
+			let positions = self.leaves[..leaf_count as usize]
+				.iter()
+				.map(|leaf| mmr.push(MmrHashing::hash(leaf)).unwrap())
+				.collect::<Vec<_>>();
+			let proof = mmr.gen_proof(vec![positions[leaf_index as usize]]).unwrap();
+			(mmr.get_root().unwrap(), proof.proof_items().to_vec())
+		}
+	}
+
+	// compiler gets confused and warns us about unused inner
+	#[allow(dead_code)]
+	struct RuntimeApi {
+		inner: TestApi,
+	}
+
+	sp_api::mock_impl_runtime_apis! {
+		impl BeefyRuntimeApi<Block, AuthorityId> for RuntimeApi {
+			fn validator_set() -> Option<ValidatorSet<AuthorityId>> {
+				Some(self.inner.validator_set.clone())
+			}
+		}
+
+		impl MmrApi<Block, MmrRootHash, u64> for RuntimeApi {
+			fn generate_proof(
+				block_numbers: Vec<u64>,
+				best_known_block_number: Option<u64>,
+			) -> Result<(Vec<EncodableOpaqueLeaf>, LeafProof<MmrRootHash>), MmrError> {
+				let leaf_index = block_numbers[0] - 1;
+				let leaf_count = best_known_block_number.ok_or(MmrError::GenerateProof)?;
+				let (_, items) = self.inner.generate_proof(leaf_index, leaf_count);
+				Ok((
+					vec![EncodableOpaqueLeaf(self.inner.leaves[leaf_index as usize].clone())],
+					LeafProof { leaf_indices: vec![leaf_index], leaf_count, items },
+				))
+			}
+		}
+	}
+
+	/// A client that serves blocks from a test client and runtime calls from [`TestApi`].
+	struct TestClient {
+		client: Arc<Client<Backend>>,
+		api: TestApi,
+	}
+
+	impl ProvideRuntimeApi<Block> for TestClient {
+		type Api = RuntimeApi;
+
+		fn runtime_api(&self) -> ApiRef<Self::Api> {
+			RuntimeApi { inner: self.api.clone() }.into()
+		}
+	}
+
+	impl HeaderBackend<Block> for TestClient {
+		fn header(&self, hash: H256) -> sp_blockchain::Result<Option<<Block as BlockT>::Header>> {
+			self.client.header(hash)
+		}
+
+		fn info(&self) -> sp_blockchain::Info<Block> {
+			self.client.info()
+		}
+
+		fn status(&self, hash: H256) -> sp_blockchain::Result<sp_blockchain::BlockStatus> {
+			self.client.status(hash)
+		}
+
+		fn number(&self, hash: H256) -> sp_blockchain::Result<Option<u64>> {
+			self.client.number(hash)
+		}
+
+		fn hash(&self, number: u64) -> sp_blockchain::Result<Option<H256>> {
+			self.client.hash(number)
+		}
+	}
+
+	impl BlockBackend<Block> for TestClient {
+		fn block_body(
+			&self,
+			hash: H256,
+		) -> sp_blockchain::Result<Option<Vec<<Block as BlockT>::Extrinsic>>> {
+			self.client.block_body(hash)
+		}
+
+		fn block_indexed_body(&self, hash: H256) -> sp_blockchain::Result<Option<Vec<Vec<u8>>>> {
+			self.client.block_indexed_body(hash)
+		}
+
+		fn block(
+			&self,
+			hash: H256,
+		) -> sp_blockchain::Result<Option<sp_runtime::generic::SignedBlock<Block>>> {
+			self.client.block(hash)
+		}
+
+		fn block_status(&self, hash: H256) -> sp_blockchain::Result<sp_consensus::BlockStatus> {
+			self.client.block_status(hash)
+		}
+
+		fn justifications(
+			&self,
+			hash: H256,
+		) -> sp_blockchain::Result<Option<sp_runtime::Justifications>> {
+			self.client.justifications(hash)
+		}
+
+		fn block_hash(&self, number: u64) -> sp_blockchain::Result<Option<H256>> {
+			self.client.block_hash(number)
+		}
+
+		fn indexed_transaction(&self, hash: H256) -> sp_blockchain::Result<Option<Vec<u8>>> {
+			self.client.indexed_transaction(hash)
+		}
+
+		fn requires_full_sync(&self) -> bool {
+			self.client.requires_full_sync()
+		}
+	}
+
+	fn authority_set(validator_set: &ValidatorSet<AuthorityId>) -> BeefyAuthoritySet<MmrRootHash> {
+		BeefyAuthoritySet {
+			id: validator_set.id(),
+			len: validator_set.len() as u32,
+			keyset_commitment: authority_set_commitment::<_, BeefyEcdsaToEthereum>(
+				validator_set.validators(),
+			),
+		}
+	}
+
+	/// Import `blocks` blocks, finalize them and add a BEEFY justification to `justified`.
+	async fn setup_io_handler(
+		blocks: u64,
+		justified: u64,
+	) -> (RpcModule<BeefyProof<TestClient, Block, AuthorityId, InMemOffchainStorage>>, TestApi) {
+		let client = Arc::new(TestClientBuilder::new().build());
+		let validator_set = ValidatorSet::new(AUTHORITIES.iter().map(|k| k.public()), 0).unwrap();
+		let next_authority_set =
+			authority_set(&ValidatorSet::new(AUTHORITIES.iter().map(|k| k.public()), 1).unwrap());
+
+		let mut leaves = Vec::new();
+		for _ in 0..blocks {
+			let parent = client.chain_info();
+			let block = BlockBuilderBuilder::new(&*client)
+				.on_parent_block(parent.best_hash)
+				.with_parent_block_number(parent.best_number)
+				.build()
+				.unwrap()
+				.build()
+				.unwrap()
+				.block;
+			client.import(BlockOrigin::Own, block).await.unwrap();
+			leaves.push(
+				TestLeaf {
+					version: MmrLeafVersion::new(1, 5),
+					parent_number_and_hash: (parent.best_number, parent.best_hash),
+					beefy_next_authority_set: next_authority_set.clone(),
+					leaf_extra: vec![],
+				}
+				.encode(),
+			);
+		}
+		let api = TestApi { validator_set, leaves };
+
+		let (mmr_root, _) = api.generate_proof(0, justified);
+		let commitment = Commitment {
+			payload: Payload::from_single_entry(MMR_ROOT_ID, mmr_root.encode()),
+			block_number: justified,
+			validator_set_id: 0,
+		};
+		let signatures = AUTHORITIES.iter().map(|k| Some(k.sign(&commitment.encode()))).collect();
+		let finality_proof = VersionedFinalityProof::<u64, Signature>::V1(SignedCommitment {
+			commitment,
+			signatures,
+		});
+		let justification: Justification = (BEEFY_ENGINE_ID, finality_proof.encode());
+
+		let justified_hash = client.hash(justified).unwrap().unwrap();
+		client.finalize_block(justified_hash, Some(justification)).unwrap();
+		client.finalize_block(client.chain_info().best_hash, None).unwrap();
+
+		let test_client = Arc::new(TestClient { client, api: api.clone() });
+		let mut rpc = BeefyProof::new(test_client, InMemOffchainStorage::default()).into_rpc();
+		rpc.extensions_mut().insert(DenyUnsafe::No);
+		(rpc, api)
+	}
+
+	#[tokio::test]
+	async fn proof_bundle_rpc_is_verified_by_light_client() {
+		let (rpc, api) = setup_io_handler(6, 4).await;
+
+		let proof: EncodedProofBundle<H256> =
+			rpc.call("beefy_getProofBundle", [2u64]).await.unwrap();
+		let bundle = TestBundle::decode(&mut &proof.bundle[..]).unwrap();
+		assert_eq!(bundle.signed_commitment.commitment.block_number, 4);
+		assert_eq!(bundle.leaf, api.leaves[1]);
+
+		// The bundle is accepted by a light client that only knows the authority set commitment.
+		let mut light_client = BeefyLightClient::<u64, AuthorityId>::new(
+			authority_set(&api.validator_set),
+			authority_set(&ValidatorSet::new(AUTHORITIES.iter().map(|k| k.public()), 1).unwrap()),
+		);
+		let leaf: TestLeaf = light_client.import(&bundle).unwrap();
+		assert_eq!(leaf.parent_number_and_hash.0, 1);
+		assert_eq!(light_client.latest_block(), Some(&4));
+	}
+
+	#[tokio::test]
+	async fn proof_bundle_rpc_without_justification() {
+		let (rpc, _) = setup_io_handler(6, 4).await;
+
+		let request = r#"{"jsonrpc":"2.0","method":"beefy_getProofBundle","params":[5],"id":1}"#;
+		let expected = r#"{"jsonrpc":"2.0","id":1,"error":{"code":3,"message":"BEEFY proof bundle unavailable: No BEEFY justification found for block #5"}}"#;
+		let (response, _) = rpc.raw_json_request(request, 1).await.unwrap();
+
+		assert_eq!(response, expected);
+	}
+	#[tokio::test]
+	async fn proof_bundle_rpc_is_unsafe() {
+		let (mut rpc, _) = setup_io_handler(6, 4).await;
+		rpc.extensions_mut().insert(DenyUnsafe::Yes);
+
+		let request = r#"{"jsonrpc":"2.0","method":"beefy_getProofBundle","params":[2],"id":1}"#;
+		let expected = r#"{"jsonrpc":"2.0","id":1,"error":{"code":-32601,"message":"RPC call is unsafe to be called externally"}}"#;
+		let (response, _) = rpc.raw_json_request(request, 1).await.unwrap();
+
+		assert_eq!(response, expected);
+	}
+}
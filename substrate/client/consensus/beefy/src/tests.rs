@@ -649,6 +649,86 @@ async fn beefy_finalizing_blocks() {
 	finalize_block_and_wait_for_beefy(&net, peers, &hashes[21], &[]).await;
 }
 
+#[tokio::test]
+async fn beefy_finality_proofs_verified_by_light_client() {
+	use sp_consensus_beefy::mmr::BeefyAuthoritySet;
+	use sp_consensus_beefy_light_client::{
+		authority_set_commitment, verify_commitment, BeefyEcdsaToEthereum,
+		Error as LightClientError,
+	};
+
+	sp_tracing::try_init_simple();
+
+	let peers = [BeefyKeyring::Alice, BeefyKeyring::Bob, BeefyKeyring::Charlie];
+	let validator_set = ValidatorSet::new(make_beefy_ids(&peers), 0).unwrap();
+	let session_len = 10;
+	let min_block_delta = 1;
+
+	let mut net = BeefyTestNet::new(3);
+	let api = Arc::new(TestApi::with_validator_set(&validator_set));
+	let beefy_peers = peers.iter().enumerate().map(|(id, key)| (id, key, api.clone())).collect();
+	tokio::spawn(initialize_beefy(&mut net, beefy_peers, min_block_delta));
+
+	let hashes = net.generate_blocks_and_sync(5, session_len, &validator_set, false).await;
+	let net = Arc::new(Mutex::new(net));
+
+	let (_, mut versioned_finality_proofs) =
+		get_beefy_streams(&mut net.lock(), [(0, BeefyKeyring::Alice)].into_iter());
+	peers.iter().enumerate().for_each(|(index, _)| {
+		net.lock()
+			.peer(index)
+			.client()
+			.as_client()
+			.finalize_block(hashes[1], None)
+			.unwrap();
+	});
+
+	// Take the finality proof produced by the voters.
+	let finality_proof = Arc::new(Mutex::new(None));
+	let sink = finality_proof.clone();
+	let stream = versioned_finality_proofs.pop().unwrap();
+	run_until(
+		Box::pin(stream.take(1).for_each(move |proof| {
+			*sink.lock() = Some(proof);
+			future::ready(())
+		})),
+		&net,
+	)
+	.await;
+	let VersionedFinalityProof::V1(mut signed_commitment) =
+		finality_proof.lock().take().expect("BEEFY finalizes block #1; qed");
+	assert_eq!(signed_commitment.commitment.block_number, 1);
+
+	// The light client only knows the commitment to the authority set, as found in MMR leaves.
+	let authority_set = BeefyAuthoritySet {
+		id: validator_set.id(),
+		len: validator_set.len() as u32,
+		keyset_commitment: authority_set_commitment::<_, BeefyEcdsaToEthereum>(
+			validator_set.validators(),
+		),
+	};
+
+	// Signed commitments produced by the voters are accepted.
+	let mmr_root = verify_commitment::<_, _, BeefyEcdsaToEthereum>(
+		&signed_commitment,
+		&validator_set,
+		&authority_set,
+	)
+	.unwrap();
+	assert_eq!(mmr_root, GOOD_MMR_ROOT);
+
+	// And rejected once tampered with.
+	signed_commitment.commitment.block_number = 2;
+	assert_eq!(
+		verify_commitment::<_, _, BeefyEcdsaToEthereum>(
+			&signed_commitment,
+			&validator_set,
+			&authority_set,
+		),
+		Err(LightClientError::NotEnoughCorrectSignatures)
+	);
+}
+
 #[tokio::test]
 async fn lagging_validators() {
 	sp_tracing::try_init_simple();
@@ -30,11 +30,16 @@ use jsonrpsee::{
 
 mod error;
 mod finality;
+mod monitor;
 mod notification;
 mod report;
 
 use error::Error;
 use finality::{EncodedFinalityProof, RpcFinalityProofProvider};
+pub use monitor::{
+	GrandpaMonitorApiClient, GrandpaMonitorApiServer, GrandpaMonitorRpc, ReportedEquivocation,
+	ReportedMonitorStatus, ReportedParticipation,
+};
 use notification::JustificationNotification;
 use report::{ReportAuthoritySet, ReportVoterState, ReportedRoundStates};
 use sc_consensus_grandpa::GrandpaJustificationStream;
@@ -138,6 +143,7 @@ mod tests {
 	use sc_block_builder::BlockBuilderBuilder;
 	use sc_consensus_grandpa::{
 		report, AuthorityId, FinalityProof, GrandpaJustification, GrandpaJustificationSender,
+		GrandpaMonitor, MonitorConfig,
 	};
 	use sc_rpc::testing::test_executor;
 	use sp_blockchain::HeaderBackend;
@@ -304,6 +310,20 @@ mod tests {
 		assert_eq!(expected_response, response);
 	}
 
+	#[tokio::test]
+	async fn monitor_status_rpc_handler() {
+		let monitor = GrandpaMonitor::<Block>::new(MonitorConfig::default(), None);
+		let rpc = GrandpaMonitorRpc::new(monitor).into_rpc();
+
+		let status: ReportedMonitorStatus<u64> =
+			rpc.call("grandpa_monitorStatus", EmptyParams::new()).await.unwrap();
+		assert_eq!(status.set_id, 0);
+		assert_eq!(status.best_number, 0);
+		assert!(!status.finality_lagging);
+		assert!(status.participation.is_empty());
+		assert!(status.equivocations.is_empty());
+	}
+
 	#[tokio::test]
 	async fn subscribe_and_unsubscribe_with_wrong_id() {
 		let (rpc, _) = setup_io_handler(TestVoterState);
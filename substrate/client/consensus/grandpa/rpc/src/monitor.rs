@@ -0,0 +1,146 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! RPC API for the GRANDPA gossip monitor.
+
+use codec::Encode;
+use jsonrpsee::{core::async_trait, proc_macros::rpc};
+use serde::{Deserialize, Serialize};
+
+use sc_consensus_grandpa::{AuthorityId, GrandpaMonitor, MonitorStatus};
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+
+use crate::error::Error;
+
+/// Provides RPC methods for inspecting the state observed by the GRANDPA monitor.
+#[rpc(client, server)]
+pub trait GrandpaMonitorApi<Number> {
+	/// Returns the round state, the participation of the authorities in the current round and
+	/// the equivocations detected by the GRANDPA monitor.
+	#[method(name = "grandpa_monitorStatus")]
+	async fn monitor_status(&self) -> Result<ReportedMonitorStatus<Number>, Error>;
+}
+
+/// The participation of an authority in the current round.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportedParticipation {
+	/// The authority.
+	pub authority: AuthorityId,
+	/// Whether a prevote of the authority has been seen.
+	pub prevoted: bool,
+	/// Whether a precommit of the authority has been seen.
+	pub precommitted: bool,
+}
+
+/// An equivocation detected by the GRANDPA monitor.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportedEquivocation {
+	/// The authority set id the equivocation happened in.
+	pub set_id: u64,
+	/// The round the equivocation happened in.
+	pub round: u64,
+	/// The equivocating authority.
+	pub offender: AuthorityId,
+	/// The SCALE encoded `EquivocationProof`.
+	pub proof: sp_core::Bytes,
+}
+
+/// The state observed by the GRANDPA monitor in a form suitable for serialization.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReportedMonitorStatus<Number> {
+	/// The current authority set id.
+	pub set_id: u64,
+	/// The current round.
+	pub round: u64,
+	/// For how long the current round has been running, in milliseconds.
+	pub round_duration_ms: u64,
+	/// Whether the current round is considered to be stuck.
+	pub round_stuck: bool,
+	/// The best block number.
+	pub best_number: Number,
+	/// The finalized block number.
+	pub finalized_number: Number,
+	/// Whether finality is considered to be lagging behind the best block.
+	pub finality_lagging: bool,
+	/// The participation of the authorities in the current round.
+	pub participation: Vec<ReportedParticipation>,
+	/// The most recently detected equivocations.
+	pub equivocations: Vec<ReportedEquivocation>,
+}
+
+impl<H: Encode, N: Encode> From<MonitorStatus<H, N>> for ReportedMonitorStatus<N> {
+	fn from(status: MonitorStatus<H, N>) -> Self {
+		let participation = status
+			.participation
+			.into_iter()
+			.map(|p| ReportedParticipation {
+				authority: p.authority,
+				prevoted: p.prevoted,
+				precommitted: p.precommitted,
+			})
+			.collect();
+
+		let equivocations = status
+			.equivocations
+			.into_iter()
+			.map(|proof| ReportedEquivocation {
+				set_id: proof.set_id(),
+				round: proof.round(),
+				offender: proof.offender().clone(),
+				proof: proof.encode().into(),
+			})
+			.collect();
+
+		Self {
+			set_id: status.set_id,
+			round: status.round,
+			round_duration_ms: status.round_duration.as_millis().try_into().unwrap_or(u64::MAX),
+			round_stuck: status.round_stuck,
+			best_number: status.best_number,
+			finalized_number: status.finalized_number,
+			finality_lagging: status.finality_lagging,
+			participation,
+			equivocations,
+		}
+	}
+}
+
+/// Implements the [`GrandpaMonitorApiServer`] on top of a [`GrandpaMonitor`].
+pub struct GrandpaMonitorRpc<Block: BlockT> {
+	monitor: GrandpaMonitor<Block>,
+}
+
+impl<Block: BlockT> GrandpaMonitorRpc<Block> {
+	/// Prepare a new [`GrandpaMonitorRpc`] handler.
+	pub fn new(monitor: GrandpaMonitor<Block>) -> Self {
+		Self { monitor }
+	}
+}
+
+#[async_trait]
+impl<Block> GrandpaMonitorApiServer<NumberFor<Block>> for GrandpaMonitorRpc<Block>
+where
+	Block: BlockT,
+{
+	async fn monitor_status(&self) -> Result<ReportedMonitorStatus<NumberFor<Block>>, Error> {
+		Ok(self.monitor.status().into())
+	}
+}
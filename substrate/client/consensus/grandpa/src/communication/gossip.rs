@@ -100,7 +100,9 @@ use sp_consensus_grandpa::AuthorityId;
 use sp_runtime::traits::{Block as BlockT, NumberFor, Zero};
 
 use super::{benefit, cost, Round, SetId, NEIGHBOR_REBROADCAST_PERIOD};
-use crate::{environment, CatchUp, CompactCommit, SignedMessage, LOG_TARGET};
+use crate::{
+	environment, monitor::GrandpaMonitor, CatchUp, CompactCommit, SignedMessage, LOG_TARGET,
+};
 
 use std::{
 	collections::{HashSet, VecDeque},
@@ -1316,6 +1318,7 @@ pub(super) struct GossipValidator<Block: BlockT> {
 	report_sender: TracingUnboundedSender<PeerReport>,
 	metrics: Option<Metrics>,
 	telemetry: Option<TelemetryHandle>,
+	monitor: Option<GrandpaMonitor<Block>>,
 }
 
 impl<Block: BlockT> GossipValidator<Block> {
@@ -1344,17 +1347,29 @@ impl<Block: BlockT> GossipValidator<Block> {
 			report_sender: tx,
 			metrics,
 			telemetry,
+			monitor: None,
 		};
 
 		(val, rx)
 	}
 
+	/// Feed all the rounds, sets and signature-checked votes noted by this validator to the given
+	/// monitor.
+	pub(super) fn with_monitor(mut self, monitor: Option<GrandpaMonitor<Block>>) -> Self {
+		self.monitor = monitor;
+		self
+	}
+
 	/// Note a round in the current set has started.
 	pub(super) fn note_round<F>(&self, round: Round, send_neighbor: F)
 	where
 		F: FnOnce(Vec<PeerId>, NeighborPacket<NumberFor<Block>>),
 	{
 		let maybe_msg = self.inner.write().note_round(round);
+		if let Some(monitor) = &self.monitor {
+			monitor.note_round(round.0);
+		}
+
 		if let Some((to, msg)) = maybe_msg {
 			send_neighbor(to, msg);
 		}
@@ -1366,6 +1381,10 @@ impl<Block: BlockT> GossipValidator<Block> {
 	where
 		F: FnOnce(Vec<PeerId>, NeighborPacket<NumberFor<Block>>),
 	{
+		if let Some(monitor) = &self.monitor {
+			monitor.note_set(set_id.0, authorities.clone());
+		}
+
 		let maybe_msg = self.inner.write().note_set(set_id, authorities);
 		if let Some((to, msg)) = maybe_msg {
 			send_neighbor(to, msg);
@@ -1415,7 +1434,12 @@ impl<Block: BlockT> GossipValidator<Block> {
 			match GossipMessage::<Block>::decode_all(&mut data) {
 				Ok(GossipMessage::Vote(ref message)) => {
 					message_name = Some("vote");
-					self.inner.write().validate_round_message(who, message)
+					let action = self.inner.write().validate_round_message(who, message);
+					if let (Action::Keep(_, _), Some(monitor)) = (&action, &self.monitor) {
+						monitor.note_vote(message.round.0, message.set_id.0, &message.message);
+					}
+
+					action
 				},
 				Ok(GossipMessage::Commit(ref message)) => {
 					message_name = Some("commit");
@@ -1438,7 +1462,14 @@ impl<Block: BlockT> GossipValidator<Block> {
 				},
 				Ok(GossipMessage::CatchUp(ref message)) => {
 					message_name = Some("catch_up");
-					self.inner.write().validate_catch_up_message(who, message)
+					let action = self.inner.write().validate_catch_up_message(who, message);
+					if let (Action::ProcessAndDiscard(_, _), Some(monitor)) =
+						(&action, &self.monitor)
+					{
+						monitor.note_catch_up(message.set_id.0, &message.message);
+					}
+
+					action
 				},
 				Ok(GossipMessage::CatchUpRequest(request)) => {
 					message_name = Some("catch_up_request");
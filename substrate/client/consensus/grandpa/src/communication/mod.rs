@@ -53,8 +53,8 @@ use sp_keystore::KeystorePtr;
 use sp_runtime::traits::{Block as BlockT, Hash as HashT, Header as HeaderT, NumberFor};
 
 use crate::{
-	environment::HasVoted, CatchUp, Commit, CommunicationIn, CommunicationOutH, CompactCommit,
-	Error, Message, SignedMessage, LOG_TARGET,
+	environment::HasVoted, monitor::GrandpaMonitor, CatchUp, Commit, CommunicationIn,
+	CommunicationOutH, CompactCommit, Error, Message, SignedMessage, LOG_TARGET,
 };
 use gossip::{
 	FullCatchUpMessage, FullCommitMessage, GossipMessage, GossipValidator, PeerReport, VoteMessage,
@@ -252,12 +252,13 @@ impl<B: BlockT, N: Network<B>, S: Syncing<B>> NetworkBridge<B, N, S> {
 		set_state: crate::environment::SharedVoterSetState<B>,
 		prometheus_registry: Option<&Registry>,
 		telemetry: Option<TelemetryHandle>,
+		monitor: Option<GrandpaMonitor<B>>,
 	) -> Self {
 		let protocol = config.protocol_name.clone();
 		let (validator, report_stream) =
 			GossipValidator::new(config, set_state.clone(), prometheus_registry, telemetry.clone());
 
-		let validator = Arc::new(validator);
+		let validator = Arc::new(validator.with_monitor(monitor));
 		let gossip_engine = Arc::new(Mutex::new(GossipEngine::new(
 			service.clone(),
 			sync.clone(),
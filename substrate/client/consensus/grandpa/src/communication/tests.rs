@@ -353,6 +353,7 @@ pub(crate) fn make_test_network() -> (impl Future<Output = Tester>, TestNetwork)
 		voter_set_state(),
 		None,
 		None,
+		None,
 	);
 
 	(
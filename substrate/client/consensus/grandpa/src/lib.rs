@@ -120,6 +120,7 @@ mod environment;
 mod finality_proof;
 mod import;
 mod justification;
+mod monitor;
 mod notification;
 mod observer;
 mod until_imported;
@@ -133,6 +134,10 @@ pub use finality_grandpa::voter::report;
 pub use finality_proof::{FinalityProof, FinalityProofError, FinalityProofProvider};
 pub use import::{find_forced_change, find_scheduled_change, GrandpaBlockImport};
 pub use justification::GrandpaJustification;
+pub use monitor::{
+	run_grandpa_monitor, AuthorityParticipation, GrandpaMonitor, GrandpaMonitorParams,
+	MonitorConfig, MonitorStatus,
+};
 pub use notification::{GrandpaJustificationSender, GrandpaJustificationStream};
 pub use observer::run_grandpa_observer;
 pub use voting_rule::{
@@ -735,6 +740,27 @@ pub fn grandpa_peers_set_config<B: BlockT, N: NetworkBackend<B, <B as BlockT>::H
 pub fn run_grandpa_voter<Block: BlockT, BE: 'static, C, N, S, SC, VR>(
 	grandpa_params: GrandpaParams<Block, C, N, S, SC, VR>,
 ) -> sp_blockchain::Result<impl Future<Output = ()> + Send>
+where
+	BE: Backend<Block> + 'static,
+	N: NetworkT<Block> + Sync + 'static,
+	S: SyncingT<Block> + Sync + 'static,
+	SC: SelectChain<Block> + 'static,
+	VR: VotingRule<Block, C> + Clone + 'static,
+	NumberFor<Block>: BlockNumberOps,
+	C: ClientForGrandpa<Block, BE> + 'static,
+	C::Api: GrandpaApi<Block>,
+{
+	run_grandpa_voter_with_monitor(grandpa_params, None)
+}
+
+/// Run a GRANDPA voter as a task, like [`run_grandpa_voter`], feeding all the votes received
+/// through gossip to `monitor`, if any.
+///
+/// The background task of the monitor must be spawned separately, see [`run_grandpa_monitor`].
+pub fn run_grandpa_voter_with_monitor<Block: BlockT, BE: 'static, C, N, S, SC, VR>(
+	grandpa_params: GrandpaParams<Block, C, N, S, SC, VR>,
+	monitor: Option<GrandpaMonitor<Block>>,
+) -> sp_blockchain::Result<impl Future<Output = ()> + Send>
 where
 	BE: Backend<Block> + 'static,
 	N: NetworkT<Block> + Sync + 'static,
@@ -782,6 +808,7 @@ where
 		persistent_data.set_state.clone(),
 		prometheus_registry.as_ref(),
 		telemetry.clone(),
+		monitor,
 	);
 
 	let conf = config.clone();
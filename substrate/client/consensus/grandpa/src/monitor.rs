@@ -0,0 +1,807 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Monitoring of GRANDPA gossip for equivocations and finality stalls.
+//!
+//! The [`GrandpaMonitor`] is fed with all signature-checked votes that the GRANDPA gossip
+//! validator accepts, including the ones that are part of catch-up messages. It does not require
+//! the local node to be a voter, so it can be used on observer and RPC nodes. It keeps track of:
+//!
+//! - the current round and set, together with the prevote/precommit participation of every
+//!   authority in the rounds it tracks;
+//! - equivocations, i.e. authorities that cast two different votes of the same kind in the same
+//!   round;
+//! - the finality lag, i.e. the distance between the best and the finalized block, and whether the
+//!   current round has been running for too long.
+//!
+//! The monitor is enabled by running the voter with [`crate::run_grandpa_voter_with_monitor`] and
+//! by spawning [`run_grandpa_monitor`] as a background task. Its state can be queried with
+//! [`GrandpaMonitor::status`] and it exposes Prometheus metrics when given a registry.
+
+use std::{
+	collections::{BTreeMap, HashMap, HashSet, VecDeque},
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+use futures_timer::Delay;
+use log::{debug, warn};
+use parking_lot::Mutex;
+use prometheus_endpoint::{
+	register, Counter, CounterVec, Gauge, Opts, PrometheusError, Registry, U64,
+};
+
+use sc_client_api::backend::Backend;
+use sc_transaction_pool_api::OffchainTransactionPoolFactory;
+use sp_consensus_grandpa::{
+	AuthorityId, AuthoritySignature, Equivocation, EquivocationProof, GrandpaApi, RoundNumber,
+	SetId,
+};
+use sp_runtime::traits::{Block as BlockT, NumberFor, Saturating, UniqueSaturatedInto, Zero};
+
+use crate::{
+	authorities::SharedAuthoritySet, environment, CatchUp, ClientForGrandpa, Message,
+	SignedMessage, LOG_TARGET,
+};
+
+/// The number of rounds of the current set for which votes are kept.
+const MAX_TRACKED_ROUNDS: usize = 4;
+
+/// The number of detected equivocations that are kept for inspection.
+const MAX_KEPT_EQUIVOCATIONS: usize = 64;
+
+/// How often the background task checks the finality of the chain.
+const CHECK_INTERVAL: Duration = Duration::from_secs(6);
+
+/// Configuration of the [`GrandpaMonitor`].
+#[derive(Clone, Debug)]
+pub struct MonitorConfig {
+	/// The number of blocks the finalized block may lag behind the best block before finality is
+	/// considered to be lagging.
+	pub finality_lag_threshold: u32,
+	/// The duration after which a round that is still running is considered to be stuck.
+	pub stuck_round_threshold: Duration,
+	/// Whether detected equivocations should be reported to the runtime.
+	pub report_equivocations: bool,
+}
+
+impl Default for MonitorConfig {
+	fn default() -> Self {
+		Self {
+			finality_lag_threshold: 32,
+			stuck_round_threshold: Duration::from_secs(60),
+			report_equivocations: false,
+		}
+	}
+}
+
+/// The participation of an authority in a round.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuthorityParticipation {
+	/// The authority.
+	pub authority: AuthorityId,
+	/// Whether a prevote of the authority was observed.
+	pub prevoted: bool,
+	/// Whether a precommit of the authority was observed.
+	pub precommitted: bool,
+}
+
+/// A snapshot of the state of the [`GrandpaMonitor`].
+#[derive(Clone, Debug)]
+pub struct MonitorStatus<H, N> {
+	/// The id of the current authority set.
+	pub set_id: SetId,
+	/// The current round.
+	pub round: RoundNumber,
+	/// For how long the current round has been running.
+	pub round_duration: Duration,
+	/// Whether the current round has been running for longer than the configured threshold.
+	pub round_stuck: bool,
+	/// The best block number.
+	pub best_number: N,
+	/// The finalized block number.
+	pub finalized_number: N,
+	/// Whether the finalized block lags behind the best block by more than the configured
+	/// threshold.
+	pub finality_lagging: bool,
+	/// The participation of every authority of the current set in the current round.
+	pub participation: Vec<AuthorityParticipation>,
+	/// The most recently detected equivocations.
+	pub equivocations: Vec<EquivocationProof<H, N>>,
+}
+
+/// The votes observed in a single round.
+struct RoundVotes<H, N> {
+	prevotes: HashMap<AuthorityId, (finality_grandpa::Prevote<H, N>, AuthoritySignature)>,
+	precommits: HashMap<AuthorityId, (finality_grandpa::Precommit<H, N>, AuthoritySignature)>,
+}
+
+impl<H, N> Default for RoundVotes<H, N> {
+	fn default() -> Self {
+		Self { prevotes: HashMap::new(), precommits: HashMap::new() }
+	}
+}
+
+/// The kind of a vote.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+enum VoteKind {
+	Prevote,
+	Precommit,
+}
+
+impl VoteKind {
+	fn as_str(&self) -> &'static str {
+		match self {
+			VoteKind::Prevote => "prevote",
+			VoteKind::Precommit => "precommit",
+		}
+	}
+}
+
+struct MonitorState<Block: BlockT> {
+	set_id: SetId,
+	authorities: Vec<AuthorityId>,
+	round: RoundNumber,
+	round_started: Instant,
+	rounds: BTreeMap<RoundNumber, RoundVotes<Block::Hash, NumberFor<Block>>>,
+	equivocators: HashSet<(RoundNumber, AuthorityId, VoteKind)>,
+	equivocations: VecDeque<EquivocationProof<Block::Hash, NumberFor<Block>>>,
+	pending_reports: Vec<(SetId, Equivocation<Block::Hash, NumberFor<Block>>)>,
+	best_number: NumberFor<Block>,
+	finalized_number: NumberFor<Block>,
+}
+
+impl<Block: BlockT> MonitorState<Block> {
+	fn new() -> Self {
+		Self {
+			set_id: 0,
+			authorities: Vec::new(),
+			round: 0,
+			round_started: Instant::now(),
+			rounds: BTreeMap::new(),
+			equivocators: HashSet::new(),
+			equivocations: VecDeque::new(),
+			pending_reports: Vec::new(),
+			best_number: Zero::zero(),
+			finalized_number: Zero::zero(),
+		}
+	}
+}
+
+/// Prometheus metrics of the [`GrandpaMonitor`].
+#[derive(Clone)]
+struct Metrics {
+	set_id: Gauge<U64>,
+	round: Gauge<U64>,
+	prevotes: Gauge<U64>,
+	precommits: Gauge<U64>,
+	missed_votes: CounterVec<U64>,
+	equivocations: Counter<U64>,
+	finality_lag: Gauge<U64>,
+	round_stuck: Gauge<U64>,
+}
+
+impl Metrics {
+	fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		Ok(Self {
+			set_id: register(
+				Gauge::new(
+					"substrate_finality_grandpa_monitor_set_id",
+					"The current GRANDPA authority set id observed by the monitor.",
+				)?,
+				registry,
+			)?,
+			round: register(
+				Gauge::new(
+					"substrate_finality_grandpa_monitor_round",
+					"The current GRANDPA round observed by the monitor.",
+				)?,
+				registry,
+			)?,
+			prevotes: register(
+				Gauge::new(
+					"substrate_finality_grandpa_monitor_prevotes",
+					"Number of authorities whose prevote was observed in the current round.",
+				)?,
+				registry,
+			)?,
+			precommits: register(
+				Gauge::new(
+					"substrate_finality_grandpa_monitor_precommits",
+					"Number of authorities whose precommit was observed in the current round.",
+				)?,
+				registry,
+			)?,
+			missed_votes: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_finality_grandpa_monitor_missed_votes",
+						"Number of votes of authorities that were not observed in a round.",
+					),
+					&["vote"],
+				)?,
+				registry,
+			)?,
+			equivocations: register(
+				Counter::new(
+					"substrate_finality_grandpa_monitor_equivocations",
+					"Number of equivocations detected by the monitor.",
+				)?,
+				registry,
+			)?,
+			finality_lag: register(
+				Gauge::new(
+					"substrate_finality_grandpa_monitor_finality_lag",
+					"Number of blocks the finalized block lags behind the best block.",
+				)?,
+				registry,
+			)?,
+			round_stuck: register(
+				Gauge::new(
+					"substrate_finality_grandpa_monitor_round_stuck",
+					"Whether the current round has been running for longer than expected.",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// A monitor of GRANDPA gossip, see the [module documentation](self) for details.
+///
+/// The monitor is cheap to clone, all clones share the same state.
+pub struct GrandpaMonitor<Block: BlockT> {
+	config: MonitorConfig,
+	state: Arc<Mutex<MonitorState<Block>>>,
+	metrics: Option<Metrics>,
+}
+
+impl<Block: BlockT> Clone for GrandpaMonitor<Block> {
+	fn clone(&self) -> Self {
+		Self {
+			config: self.config.clone(),
+			state: self.state.clone(),
+			metrics: self.metrics.clone(),
+		}
+	}
+}
+
+impl<Block: BlockT> GrandpaMonitor<Block> {
+	/// Create a new monitor, registering its metrics in the given registry if any.
+	pub fn new(config: MonitorConfig, prometheus_registry: Option<&Registry>) -> Self {
+		let metrics = match prometheus_registry.map(Metrics::register) {
+			Some(Ok(metrics)) => Some(metrics),
+			Some(Err(e)) => {
+				debug!(target: LOG_TARGET, "Failed to register monitor metrics: {:?}", e);
+				None
+			},
+			None => None,
+		};
+
+		Self { config, state: Arc::new(Mutex::new(MonitorState::new())), metrics }
+	}
+
+	/// The current state of the monitor.
+	pub fn status(&self) -> MonitorStatus<Block::Hash, NumberFor<Block>> {
+		let state = self.state.lock();
+		let votes = state.rounds.get(&state.round);
+		let participation = state
+			.authorities
+			.iter()
+			.map(|authority| AuthorityParticipation {
+				authority: authority.clone(),
+				prevoted: votes.map_or(false, |v| v.prevotes.contains_key(authority)),
+				precommitted: votes.map_or(false, |v| v.precommits.contains_key(authority)),
+			})
+			.collect();
+		let round_duration = state.round_started.elapsed();
+
+		MonitorStatus {
+			set_id: state.set_id,
+			round: state.round,
+			round_duration,
+			round_stuck: round_duration > self.config.stuck_round_threshold,
+			best_number: state.best_number,
+			finalized_number: state.finalized_number,
+			finality_lagging: self.is_lagging(state.best_number, state.finalized_number),
+			participation,
+			equivocations: state.equivocations.iter().cloned().collect(),
+		}
+	}
+
+	/// Note that a voter set with the given id and authorities has started.
+	pub(crate) fn note_set(&self, set_id: SetId, authorities: Vec<AuthorityId>) {
+		let mut state = self.state.lock();
+		if state.set_id == set_id && !state.authorities.is_empty() {
+			return
+		}
+
+		state.set_id = set_id;
+		state.authorities = authorities;
+		state.round = 0;
+		state.round_started = Instant::now();
+		state.rounds.clear();
+		state.equivocators.clear();
+
+		if let Some(metrics) = &self.metrics {
+			metrics.set_id.set(set_id);
+			metrics.round.set(0);
+		}
+	}
+
+	/// Note that a round of the current set has started.
+	pub(crate) fn note_round(&self, round: RoundNumber) {
+		let mut state = self.state.lock();
+		if round < state.round || (round == state.round && state.rounds.contains_key(&round)) {
+			return
+		}
+
+		state.round = round;
+		state.round_started = Instant::now();
+		state.rounds.entry(round).or_default();
+		self.prune_rounds(&mut state);
+
+		if let Some(metrics) = &self.metrics {
+			metrics.round.set(round);
+			metrics.round_stuck.set(0);
+		}
+		self.update_participation_metrics(&state);
+	}
+
+	/// Note a signature-checked vote of the given round and set.
+	pub(crate) fn note_vote(
+		&self,
+		round: RoundNumber,
+		set_id: SetId,
+		vote: &SignedMessage<Block::Header>,
+	) {
+		match &vote.message {
+			Message::Prevote(prevote) => self.import_prevote(
+				round,
+				set_id,
+				&vote.id,
+				prevote.clone(),
+				vote.signature.clone(),
+			),
+			Message::Precommit(precommit) => self.import_precommit(
+				round,
+				set_id,
+				&vote.id,
+				precommit.clone(),
+				vote.signature.clone(),
+			),
+			Message::PrimaryPropose(_) => {},
+		}
+	}
+
+	/// Note the votes of a catch-up message of the given set.
+	///
+	/// Unlike round messages the votes of catch-up messages are not signature-checked by the gossip
+	/// validator, hence they are checked here.
+	pub(crate) fn note_catch_up(&self, set_id: SetId, catch_up: &CatchUp<Block::Header>) {
+		let round = catch_up.round_number;
+		for signed in &catch_up.prevotes {
+			let message = finality_grandpa::Message::Prevote(signed.prevote.clone());
+			if sp_consensus_grandpa::check_message_signature(
+				&message,
+				&signed.id,
+				&signed.signature,
+				round,
+				set_id,
+			) {
+				self.import_prevote(
+					round,
+					set_id,
+					&signed.id,
+					signed.prevote.clone(),
+					signed.signature.clone(),
+				);
+			}
+		}
+
+		for signed in &catch_up.precommits {
+			let message = finality_grandpa::Message::Precommit(signed.precommit.clone());
+			if sp_consensus_grandpa::check_message_signature(
+				&message,
+				&signed.id,
+				&signed.signature,
+				round,
+				set_id,
+			) {
+				self.import_precommit(
+					round,
+					set_id,
+					&signed.id,
+					signed.precommit.clone(),
+					signed.signature.clone(),
+				);
+			}
+		}
+	}
+
+	/// Note the current best and finalized block numbers of the chain.
+	pub(crate) fn note_chain_head(
+		&self,
+		best_number: NumberFor<Block>,
+		finalized_number: NumberFor<Block>,
+	) {
+		let mut state = self.state.lock();
+		state.best_number = best_number;
+		state.finalized_number = finalized_number;
+
+		let lag: u64 = best_number.saturating_sub(finalized_number).unique_saturated_into();
+		let round_stuck = state.round_started.elapsed() > self.config.stuck_round_threshold;
+
+		if self.is_lagging(best_number, finalized_number) {
+			warn!(
+				target: LOG_TARGET,
+				"Finality is lagging {} blocks behind the best block #{} (set: {}, round: {}).",
+				lag,
+				best_number,
+				state.set_id,
+				state.round,
+			);
+		}
+
+		if round_stuck {
+			warn!(
+				target: LOG_TARGET,
+				"Round {} of set {} has been running for {:?} without completing.",
+				state.round,
+				state.set_id,
+				state.round_started.elapsed(),
+			);
+		}
+
+		if let Some(metrics) = &self.metrics {
+			metrics.finality_lag.set(lag);
+			metrics.round_stuck.set(round_stuck as u64);
+		}
+	}
+
+	/// Take the equivocations that were detected since the last call and should be reported.
+	pub(crate) fn take_pending_reports(
+		&self,
+	) -> Vec<(SetId, Equivocation<Block::Hash, NumberFor<Block>>)> {
+		std::mem::take(&mut self.state.lock().pending_reports)
+	}
+
+	fn is_lagging(
+		&self,
+		best_number: NumberFor<Block>,
+		finalized_number: NumberFor<Block>,
+	) -> bool {
+		best_number.saturating_sub(finalized_number) > self.config.finality_lag_threshold.into()
+	}
+
+	fn import_prevote(
+		&self,
+		round: RoundNumber,
+		set_id: SetId,
+		id: &AuthorityId,
+		prevote: finality_grandpa::Prevote<Block::Hash, NumberFor<Block>>,
+		signature: AuthoritySignature,
+	) {
+		let mut state = self.state.lock();
+		let Some(votes) = self.round_votes(&mut state, round, set_id, id) else { return };
+
+		let equivocation = match votes.prevotes.get(id) {
+			Some((first, _)) if *first == prevote => return,
+			Some((first, first_signature)) =>
+				Equivocation::Prevote(finality_grandpa::Equivocation {
+					round_number: round,
+					identity: id.clone(),
+					first: (first.clone(), first_signature.clone()),
+					second: (prevote, signature),
+				}),
+			None => {
+				votes.prevotes.insert(id.clone(), (prevote, signature));
+				if round == state.round {
+					self.update_participation_metrics(&state);
+				}
+				return
+			},
+		};
+
+		self.note_equivocation(&mut state, VoteKind::Prevote, equivocation);
+	}
+
+	fn import_precommit(
+		&self,
+		round: RoundNumber,
+		set_id: SetId,
+		id: &AuthorityId,
+		precommit: finality_grandpa::Precommit<Block::Hash, NumberFor<Block>>,
+		signature: AuthoritySignature,
+	) {
+		let mut state = self.state.lock();
+		let Some(votes) = self.round_votes(&mut state, round, set_id, id) else { return };
+
+		let equivocation = match votes.precommits.get(id) {
+			Some((first, _)) if *first == precommit => return,
+			Some((first, first_signature)) =>
+				Equivocation::Precommit(finality_grandpa::Equivocation {
+					round_number: round,
+					identity: id.clone(),
+					first: (first.clone(), first_signature.clone()),
+					second: (precommit, signature),
+				}),
+			None => {
+				votes.precommits.insert(id.clone(), (precommit, signature));
+				if round == state.round {
+					self.update_participation_metrics(&state);
+				}
+				return
+			},
+		};
+
+		self.note_equivocation(&mut state, VoteKind::Precommit, equivocation);
+	}
+
+	/// The votes of the given round, if the round and voter are tracked.
+	fn round_votes<'a>(
+		&self,
+		state: &'a mut MonitorState<Block>,
+		round: RoundNumber,
+		set_id: SetId,
+		id: &AuthorityId,
+	) -> Option<&'a mut RoundVotes<Block::Hash, NumberFor<Block>>> {
+		if set_id != state.set_id || !state.authorities.contains(id) {
+			return None
+		}
+
+		// don't start tracking rounds that are older than all the tracked ones.
+		if state.rounds.len() >= MAX_TRACKED_ROUNDS &&
+			state.rounds.keys().next().map_or(false, |oldest| round < *oldest)
+		{
+			return None
+		}
+
+		if !state.rounds.contains_key(&round) {
+			state.rounds.insert(round, RoundVotes::default());
+			self.prune_rounds(state);
+		}
+
+		state.rounds.get_mut(&round)
+	}
+
+	fn note_equivocation(
+		&self,
+		state: &mut MonitorState<Block>,
+		kind: VoteKind,
+		equivocation: Equivocation<Block::Hash, NumberFor<Block>>,
+	) {
+		// only keep the first equivocation of an authority per round and vote kind.
+		if !state.equivocators.insert((
+			equivocation.round_number(),
+			equivocation.offender().clone(),
+			kind,
+		)) {
+			return
+		}
+
+		warn!(
+			target: LOG_TARGET,
+			"Detected {} equivocation of {} in round {} of set {}.",
+			kind.as_str(),
+			equivocation.offender(),
+			equivocation.round_number(),
+			state.set_id,
+		);
+
+		if self.config.report_equivocations {
+			state.pending_reports.push((state.set_id, equivocation.clone()));
+		}
+
+		state
+			.equivocations
+			.push_back(EquivocationProof::new(state.set_id, equivocation));
+		if state.equivocations.len() > MAX_KEPT_EQUIVOCATIONS {
+			state.equivocations.pop_front();
+		}
+
+		if let Some(metrics) = &self.metrics {
+			metrics.equivocations.inc();
+		}
+	}
+
+	/// Stop tracking the oldest rounds, recording the votes that were missed in them.
+	fn prune_rounds(&self, state: &mut MonitorState<Block>) {
+		while state.rounds.len() > MAX_TRACKED_ROUNDS {
+			let Some((round, votes)) = state.rounds.pop_first() else { break };
+			state.equivocators.retain(|(r, _, _)| *r != round);
+
+			// The missed votes are aggregated over all authorities, a label per authority would
+			// grow with every authority set change. The participation of individual authorities
+			// is available through `GrandpaMonitor::status`.
+			let Some(metrics) = &self.metrics else { continue };
+			let missed_prevotes =
+				state.authorities.iter().filter(|a| !votes.prevotes.contains_key(*a)).count();
+			let missed_precommits =
+				state.authorities.iter().filter(|a| !votes.precommits.contains_key(*a)).count();
+			metrics
+				.missed_votes
+				.with_label_values(&[VoteKind::Prevote.as_str()])
+				.inc_by(missed_prevotes as u64);
+			metrics
+				.missed_votes
+				.with_label_values(&[VoteKind::Precommit.as_str()])
+				.inc_by(missed_precommits as u64);
+		}
+	}
+
+	fn update_participation_metrics(&self, state: &MonitorState<Block>) {
+		let (Some(metrics), Some(votes)) = (&self.metrics, state.rounds.get(&state.round)) else {
+			return
+		};
+
+		metrics.prevotes.set(votes.prevotes.len() as u64);
+		metrics.precommits.set(votes.precommits.len() as u64);
+	}
+}
+
+/// Parameters used to run the GRANDPA monitor.
+pub struct GrandpaMonitorParams<Block: BlockT, C> {
+	/// The monitor, it must be the one passed to [`crate::run_grandpa_voter_with_monitor`].
+	pub monitor: GrandpaMonitor<Block>,
+	/// The client.
+	pub client: Arc<C>,
+	/// The shared authority set, see [`crate::LinkHalf::shared_authority_set`].
+	pub authority_set: SharedAuthoritySet<Block::Hash, NumberFor<Block>>,
+	/// Offchain transaction pool factory.
+	///
+	/// This will be used to create an offchain transaction pool instance for sending an
+	/// equivocation report from the runtime.
+	pub offchain_tx_pool_factory: OffchainTransactionPoolFactory<Block>,
+}
+
+/// Run the background task of the GRANDPA monitor.
+///
+/// It periodically checks the finality of the chain and, if configured, reports the
+/// equivocations detected by the monitor to the runtime.
+pub async fn run_grandpa_monitor<Block, BE, C>(params: GrandpaMonitorParams<Block, C>)
+where
+	Block: BlockT,
+	BE: Backend<Block>,
+	C: ClientForGrandpa<Block, BE>,
+	C::Api: GrandpaApi<Block>,
+{
+	let GrandpaMonitorParams { monitor, client, authority_set, offchain_tx_pool_factory } = params;
+
+	loop {
+		Delay::new(CHECK_INTERVAL).await;
+
+		let info = client.info();
+		monitor.note_chain_head(info.best_number, info.finalized_number);
+
+		for (set_id, equivocation) in monitor.take_pending_reports() {
+			if set_id != authority_set.set_id() {
+				debug!(target: LOG_TARGET, "Not reporting equivocation of past set {}.", set_id);
+				continue
+			}
+
+			let offender = equivocation.offender().clone();
+			match environment::report_equivocation(
+				&*client,
+				&authority_set,
+				&offchain_tx_pool_factory,
+				equivocation,
+			) {
+				Ok(()) => debug!(target: LOG_TARGET, "Reported equivocation of {}.", offender),
+				Err(e) => warn!(
+					target: LOG_TARGET,
+					"Failed to report equivocation of {}: {}",
+					offender,
+					e,
+				),
+			}
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_network_test::{Block, Hash};
+	use sp_keyring::Ed25519Keyring;
+
+	fn signed_prevote(
+		keyring: Ed25519Keyring,
+		round: RoundNumber,
+		set_id: SetId,
+		target_hash: Hash,
+	) -> SignedMessage<<Block as BlockT>::Header> {
+		let message = Message::Prevote(finality_grandpa::Prevote { target_hash, target_number: 1 });
+		let payload = sp_consensus_grandpa::localized_payload(round, set_id, &message);
+		let signature = AuthoritySignature::from(keyring.sign(&payload[..]));
+		SignedMessage { message, signature, id: keyring.public().into() }
+	}
+
+	fn monitor() -> GrandpaMonitor<Block> {
+		let monitor = GrandpaMonitor::new(
+			MonitorConfig { report_equivocations: true, ..Default::default() },
+			None,
+		);
+		monitor.note_set(
+			1,
+			vec![Ed25519Keyring::Alice.public().into(), Ed25519Keyring::Bob.public().into()],
+		);
+		monitor.note_round(1);
+		monitor
+	}
+
+	#[test]
+	fn tracks_participation_in_current_round() {
+		let monitor = monitor();
+		monitor.note_vote(1, 1, &signed_prevote(Ed25519Keyring::Alice, 1, 1, Hash::repeat_byte(1)));
+
+		let status = monitor.status();
+		assert_eq!((status.set_id, status.round), (1, 1));
+		assert_eq!(
+			status
+				.participation
+				.iter()
+				.map(|p| (p.prevoted, p.precommitted))
+				.collect::<Vec<_>>(),
+			vec![(true, false), (false, false)],
+		);
+	}
+
+	#[test]
+	fn detects_equivocations_once() {
+		let monitor = monitor();
+		let alice = Ed25519Keyring::Alice;
+		monitor.note_vote(1, 1, &signed_prevote(alice, 1, 1, Hash::repeat_byte(1)));
+		// the same vote again is not an equivocation.
+		monitor.note_vote(1, 1, &signed_prevote(alice, 1, 1, Hash::repeat_byte(1)));
+		assert!(monitor.status().equivocations.is_empty());
+
+		monitor.note_vote(1, 1, &signed_prevote(alice, 1, 1, Hash::repeat_byte(2)));
+		monitor.note_vote(1, 1, &signed_prevote(alice, 1, 1, Hash::repeat_byte(3)));
+
+		let equivocations = monitor.status().equivocations;
+		assert_eq!(equivocations.len(), 1);
+		assert_eq!(equivocations[0].offender(), &AuthorityId::from(alice.public()));
+		assert_eq!(equivocations[0].round(), 1);
+		assert_eq!(monitor.take_pending_reports().len(), 1);
+		assert!(monitor.take_pending_reports().is_empty());
+	}
+
+	#[test]
+	fn ignores_votes_of_other_sets_and_unknown_voters() {
+		let monitor = monitor();
+		let charlie = Ed25519Keyring::Charlie;
+		monitor.note_vote(1, 2, &signed_prevote(Ed25519Keyring::Alice, 1, 2, Hash::zero()));
+		monitor.note_vote(1, 1, &signed_prevote(charlie, 1, 1, Hash::repeat_byte(1)));
+		monitor.note_vote(1, 1, &signed_prevote(charlie, 1, 1, Hash::repeat_byte(2)));
+
+		let status = monitor.status();
+		assert!(status.participation.iter().all(|p| !p.prevoted));
+		assert!(status.equivocations.is_empty());
+	}
+
+	#[test]
+	fn detects_finality_lag() {
+		let monitor = monitor();
+		monitor.note_chain_head(100, 90);
+		assert!(!monitor.status().finality_lagging);
+
+		monitor.note_chain_head(100, 50);
+		assert!(monitor.status().finality_lagging);
+	}
+}
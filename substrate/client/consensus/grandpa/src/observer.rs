@@ -42,6 +42,7 @@ use crate::{
 	aux_schema::PersistentData,
 	communication::{Network as NetworkT, NetworkBridge, Syncing as SyncingT},
 	environment, global_communication,
+	monitor::GrandpaMonitor,
 	notification::GrandpaJustificationSender,
 	ClientForGrandpa, CommandOrError, CommunicationIn, Config, Error, LinkHalf, VoterCommand,
 	VoterSetState, LOG_TARGET,
@@ -161,7 +162,8 @@ where
 /// Run a GRANDPA observer as a task, the observer will finalize blocks only by
 /// listening for and validating GRANDPA commits instead of following the full
 /// protocol. Provide configuration and a link to a block import worker that has
-/// already been instantiated with `block_import`.
+/// already been instantiated with `block_import`. An optional [`GrandpaMonitor`] is fed
+/// with the gossip messages received by the observer.
 /// NOTE: this is currently not part of the crate's public API since we don't consider
 /// it stable enough to use on a live network.
 pub fn run_grandpa_observer<BE, Block: BlockT, Client, N, S, SC>(
@@ -170,6 +172,7 @@ pub fn run_grandpa_observer<BE, Block: BlockT, Client, N, S, SC>(
 	network: N,
 	sync: S,
 	notification_service: Box<dyn NotificationService>,
+	monitor: Option<GrandpaMonitor<Block>>,
 ) -> sp_blockchain::Result<impl Future<Output = ()> + Send>
 where
 	BE: Backend<Block> + Unpin + 'static,
@@ -196,6 +199,7 @@ where
 		persistent_data.set_state.clone(),
 		None,
 		telemetry.clone(),
+		monitor,
 	);
 
 	let observer_work = ObserverWork::new(
@@ -1044,6 +1044,7 @@ async fn voter_persists_its_votes() {
 			set_state,
 			None,
 			None,
+			None,
 		)
 	};
 
@@ -1296,6 +1297,7 @@ async fn finalize_3_voters_1_light_observer() {
 		net.peers[3].network_service().clone(),
 		net.peers[3].sync_service().clone(),
 		notification_service,
+		None,
 	)
 	.unwrap();
 	net.peer(0).push_blocks(20, false);
@@ -1470,6 +1472,7 @@ where
 		set_state.clone(),
 		None,
 		None,
+		None,
 	);
 
 	Environment {
@@ -0,0 +1,766 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Sassafras slot claiming, tickets generation and block authoring worker.
+
+use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+
+use codec::Encode;
+use futures::{future, prelude::*};
+use log::{debug, info, warn};
+
+use sc_client_api::{backend::AuxStore, BlockchainEvents};
+use sc_consensus::{
+	block_import::{BlockImport, BlockImportParams, StateAction},
+	JustificationSyncLink,
+};
+use sc_consensus_epochs::{
+	descendent_query, Epoch as EpochT, SharedEpochChanges, ViableEpochDescriptor,
+};
+use sc_consensus_slots::{
+	BackoffAuthoringBlocksStrategy, InherentDataProviderExt, SlotInfo, StorageChanges,
+};
+use sc_telemetry::TelemetryHandle;
+use sc_transaction_pool_api::OffchainTransactionPoolFactory;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_application_crypto::AppCrypto;
+use sp_blockchain::{Error as ClientError, HeaderBackend, HeaderMetadata};
+use sp_consensus::{BlockOrigin, Environment, Error as ConsensusError, Proposer, SelectChain};
+use sp_consensus_sassafras::{
+	ticket_id_threshold, vrf::RingContext, AuthorityId, AuthorityIndex, AuthoritySignature,
+	Randomness, SassafrasApi, Slot, TicketBody, TicketClaim, TicketEnvelope, TicketId,
+};
+use sp_core::{
+	crypto::{ByteArray, Pair as _},
+	ed25519::Pair as EphemeralPair,
+	U256,
+};
+use sp_inherents::CreateInherentDataProviders;
+use sp_keystore::KeystorePtr;
+use sp_runtime::{
+	traits::{Block as BlockT, Header, NumberFor},
+	DigestItem,
+};
+
+use crate::{
+	aux_schema, find_next_epoch_digest, find_slot, sassafras_err, vrf, Epoch, Error,
+	SassafrasIntermediate, SassafrasLink, SlotClaim, SyncOracle, TicketSecret, INTERMEDIATE_KEY,
+	LOG_TARGET,
+};
+
+pub use sc_consensus_slots::SlotProportion;
+
+/// Get the fallback author for the given slot and with given authorities.
+///
+/// The fallback author is entitled to claim the slots which haven't been assigned to any
+/// ticket. This should always assign the slot to some authority unless the authorities
+/// list is empty.
+pub(crate) fn fallback_slot_author(
+	slot: Slot,
+	authorities: &[AuthorityId],
+	randomness: &Randomness,
+) -> Option<AuthorityIndex> {
+	if authorities.is_empty() {
+		return None
+	}
+
+	let rand =
+		U256::from_big_endian(&(randomness, slot).using_encoded(sp_crypto_hashing::blake2_256));
+
+	let authorities_len = U256::from(authorities.len());
+	let idx = rand % authorities_len;
+
+	Some(idx.as_u32())
+}
+
+/// Tries to claim the given slot.
+///
+/// If the slot is assigned to a ticket (`maybe_ticket` is `Some`) then the slot can be
+/// claimed only if the ticket has been generated by the local node, otherwise the slot can
+/// be claimed only if one of the local authorities is the fallback author for the slot.
+///
+/// Returns the slot claim together with the identifier of the claiming authority.
+pub fn claim_slot(
+	slot: Slot,
+	epoch: &Epoch,
+	maybe_ticket: Option<(TicketId, TicketBody)>,
+	keystore: &KeystorePtr,
+) -> Option<(SlotClaim, AuthorityId)> {
+	if epoch.authorities.is_empty() {
+		return None
+	}
+
+	let mut epoch_index = epoch.index;
+	if epoch.end_slot() <= slot {
+		// Slot doesn't strictly belong to the epoch, create a clone with fixed values.
+		epoch_index = epoch.clone_for_slot(slot).index;
+	}
+
+	let mut sign_data = vrf::slot_claim_sign_data(&epoch.randomness, slot, epoch_index);
+
+	let (authority_idx, ticket_claim) = match maybe_ticket {
+		Some((ticket_id, ticket_body)) => {
+			debug!(target: LOG_TARGET, "Slot {} is assigned to ticket {:032x}", slot, ticket_id);
+			let TicketSecret { authority_idx, erased_seed } = epoch.tickets_aux.get(&ticket_id)?;
+
+			let revealed_input =
+				vrf::revealed_key_input(&epoch.randomness, ticket_body.attempt_idx, epoch_index);
+			sign_data
+				.push_vrf_input(revealed_input)
+				.expect("Slot claim sign data has space for one more input; qed");
+
+			// Proves the ticket ownership by signing with the erased key.
+			let challenge = sign_data.challenge::<32>();
+			let erased_signature = EphemeralPair::from_seed(erased_seed).sign(&challenge);
+
+			(*authority_idx, Some(TicketClaim { erased_signature }))
+		},
+		None => {
+			debug!(target: LOG_TARGET, "Slot {} is not assigned to any ticket", slot);
+			(fallback_slot_author(slot, &epoch.authorities, &epoch.randomness)?, None)
+		},
+	};
+
+	let authority_id = epoch.authorities.get(authority_idx as usize)?;
+
+	let vrf_signature = keystore
+		.bandersnatch_vrf_sign(AuthorityId::ID, authority_id.as_ref(), &sign_data)
+		.ok()
+		.flatten()?;
+
+	let claim = SlotClaim { authority_idx, slot, vrf_signature, ticket_claim };
+
+	Some((claim, authority_id.clone()))
+}
+
+/// Generate the tickets of the local authorities for the given epoch.
+///
+/// The secrets required to claim the slots which will be assigned to the generated
+/// tickets are stored in the epoch `tickets_aux`.
+pub fn generate_epoch_tickets(
+	epoch: &mut Epoch,
+	keystore: &KeystorePtr,
+	ring_context: &RingContext,
+) -> Vec<TicketEnvelope> {
+	let mut tickets = Vec::new();
+
+	let threshold = ticket_id_threshold(
+		epoch.config.redundancy_factor,
+		epoch.length,
+		epoch.config.attempts_number,
+		epoch.authorities.len() as u32,
+	);
+	debug!(target: LOG_TARGET, "Tickets threshold for epoch {}: {:032x}", epoch.index, threshold);
+
+	let public_keys: Vec<_> = epoch.authorities.iter().map(|a| *a.as_ref()).collect();
+
+	for (authority_idx, authority_id) in epoch.authorities.iter().enumerate() {
+		if !keystore.has_keys(&[(authority_id.to_raw_vec(), AuthorityId::ID)]) {
+			continue
+		}
+
+		let Some(prover) = ring_context.prover(&public_keys, authority_idx) else {
+			warn!(target: LOG_TARGET, "Unable to build ring prover for authority {}", authority_idx);
+			continue
+		};
+
+		debug!(
+			target: LOG_TARGET,
+			"Generating tickets for authority {} (attempts: {})",
+			authority_idx,
+			epoch.config.attempts_number,
+		);
+
+		for attempt_idx in 0..epoch.config.attempts_number {
+			let ticket_id_input = vrf::ticket_id_input(&epoch.randomness, attempt_idx, epoch.index);
+			let Some(ticket_id_pre_output) = keystore
+				.bandersnatch_vrf_pre_output(
+					AuthorityId::ID,
+					authority_id.as_ref(),
+					&ticket_id_input,
+				)
+				.ok()
+				.flatten()
+			else {
+				continue
+			};
+
+			let ticket_id = vrf::make_ticket_id(&ticket_id_input, &ticket_id_pre_output);
+			if ticket_id >= threshold {
+				continue
+			}
+
+			let revealed_input =
+				vrf::revealed_key_input(&epoch.randomness, attempt_idx, epoch.index);
+			let Some(revealed_pre_output) = keystore
+				.bandersnatch_vrf_pre_output(
+					AuthorityId::ID,
+					authority_id.as_ref(),
+					&revealed_input,
+				)
+				.ok()
+				.flatten()
+			else {
+				continue
+			};
+			let revealed_seed = vrf::make_revealed_key_seed(&revealed_input, &revealed_pre_output);
+			let revealed_public = EphemeralPair::from_seed(&revealed_seed).public();
+
+			let (erased_pair, erased_seed) = EphemeralPair::generate();
+
+			let body =
+				TicketBody { attempt_idx, erased_public: erased_pair.public(), revealed_public };
+
+			let sign_data = vrf::ticket_body_sign_data(&body, ticket_id_input);
+			let Some(signature) = keystore
+				.bandersnatch_ring_vrf_sign(
+					AuthorityId::ID,
+					authority_id.as_ref(),
+					&sign_data,
+					&prover,
+				)
+				.ok()
+				.flatten()
+			else {
+				continue
+			};
+
+			debug!(target: LOG_TARGET, "Generated ticket {:032x}", ticket_id);
+
+			tickets.push(TicketEnvelope { body, signature });
+			epoch.tickets_aux.insert(
+				ticket_id,
+				TicketSecret { authority_idx: authority_idx as AuthorityIndex, erased_seed },
+			);
+		}
+	}
+
+	tickets
+}
+
+/// Parameters for Sassafras.
+pub struct SassafrasParams<B: BlockT, C, SC, E, I, SO, L, CIDP, BS> {
+	/// The keystore that manages the keys of the node.
+	pub keystore: KeystorePtr,
+
+	/// The client to use
+	pub client: Arc<C>,
+
+	/// The SelectChain Strategy
+	pub select_chain: SC,
+
+	/// The environment we are producing blocks for.
+	pub env: E,
+
+	/// The underlying block-import object to supply our produced blocks to.
+	/// This must be a `SassafrasBlockImport` or a wrapper of it, otherwise
+	/// critical consensus logic will be omitted.
+	pub block_import: I,
+
+	/// A sync oracle
+	pub sync_oracle: SO,
+
+	/// Hook into the sync module to control the justification sync process.
+	pub justification_sync_link: L,
+
+	/// Something that can create the inherent data providers.
+	pub create_inherent_data_providers: CIDP,
+
+	/// Force authoring of blocks even if we are offline
+	pub force_authoring: bool,
+
+	/// Strategy and parameters for backing off block production.
+	pub backoff_authoring_blocks: Option<BS>,
+
+	/// The source of timestamps for relative slots
+	pub sassafras_link: SassafrasLink<B>,
+
+	/// The proportion of the slot dedicated to proposing.
+	///
+	/// The block proposing will be limited to this proportion of the slot from the starting of the
+	/// slot. However, the proposing can still take longer when there is some lenience factor
+	/// applied, because there were no blocks produced for some slots.
+	pub block_proposal_slot_portion: SlotProportion,
+
+	/// The maximum proportion of the slot dedicated to proposing with any lenience factor applied
+	/// due to no blocks being produced.
+	pub max_block_proposal_slot_portion: Option<SlotProportion>,
+
+	/// Handle use to report telemetries.
+	pub telemetry: Option<TelemetryHandle>,
+
+	/// The offchain transaction pool factory.
+	///
+	/// Will be used to submit the tickets generated by the local node.
+	pub offchain_tx_pool_factory: OffchainTransactionPoolFactory<B>,
+}
+
+/// Start the Sassafras worker.
+///
+/// The returned future drives both the block authoring, on every slot, and the tickets
+/// generation, on every epoch change announced by the best chain.
+pub fn start_sassafras<B, C, SC, E, I, SO, CIDP, BS, L, Error>(
+	SassafrasParams {
+		keystore,
+		client,
+		select_chain,
+		env,
+		block_import,
+		sync_oracle,
+		justification_sync_link,
+		create_inherent_data_providers,
+		force_authoring,
+		backoff_authoring_blocks,
+		sassafras_link,
+		block_proposal_slot_portion,
+		max_block_proposal_slot_portion,
+		telemetry,
+		offchain_tx_pool_factory,
+	}: SassafrasParams<B, C, SC, E, I, SO, L, CIDP, BS>,
+) -> Result<SassafrasWorker, ConsensusError>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>
+		+ HeaderBackend<B>
+		+ HeaderMetadata<B, Error = ClientError>
+		+ BlockchainEvents<B>
+		+ AuxStore
+		+ Send
+		+ Sync
+		+ 'static,
+	C::Api: SassafrasApi<B>,
+	SC: SelectChain<B> + 'static,
+	E: Environment<B, Error = Error> + Send + Sync + 'static,
+	E::Proposer: Proposer<B, Error = Error>,
+	I: BlockImport<B, Error = ConsensusError> + Send + Sync + 'static,
+	SO: SyncOracle + Send + Sync + Clone + 'static,
+	L: JustificationSyncLink<B> + 'static,
+	CIDP: CreateInherentDataProviders<B, ()> + Send + Sync + 'static,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send,
+	BS: BackoffAuthoringBlocksStrategy<NumberFor<B>> + Send + Sync + 'static,
+	Error: std::error::Error + Send + From<ConsensusError> + From<I::Error> + 'static,
+{
+	let slot_worker = SassafrasSlotWorker {
+		client: client.clone(),
+		block_import,
+		env,
+		sync_oracle: sync_oracle.clone(),
+		justification_sync_link,
+		force_authoring,
+		backoff_authoring_blocks,
+		keystore: keystore.clone(),
+		epoch_changes: sassafras_link.epoch_changes.clone(),
+		genesis_epoch: sassafras_link.config.genesis_epoch.clone(),
+		block_proposal_slot_portion,
+		max_block_proposal_slot_portion,
+		telemetry,
+	};
+
+	info!(target: LOG_TARGET, "🌳 Starting Sassafras Authorship worker");
+
+	let slot_worker = sc_consensus_slots::start_slot_worker(
+		sassafras_link.config.slot_duration(),
+		select_chain,
+		sc_consensus_slots::SimpleSlotWorkerToSlotWorker(slot_worker),
+		sync_oracle.clone(),
+		create_inherent_data_providers,
+	);
+
+	let tickets_worker = tickets_worker(
+		client,
+		keystore,
+		sassafras_link.epoch_changes,
+		sassafras_link.config.genesis_epoch,
+		sync_oracle,
+		offchain_tx_pool_factory,
+	);
+
+	let inner = future::join(slot_worker, tickets_worker).map(|_| ());
+
+	Ok(SassafrasWorker { inner: Box::pin(inner) })
+}
+
+/// Worker for Sassafras which implements `Future<Output=()>`. This must be polled.
+#[must_use]
+pub struct SassafrasWorker {
+	inner: Pin<Box<dyn Future<Output = ()> + Send + 'static>>,
+}
+
+impl Future for SassafrasWorker {
+	type Output = ();
+
+	fn poll(
+		mut self: Pin<&mut Self>,
+		cx: &mut std::task::Context,
+	) -> std::task::Poll<Self::Output> {
+		self.inner.as_mut().poll(cx)
+	}
+}
+
+/// Generates and submits the tickets of the local authorities for the next epoch.
+///
+/// Tickets are generated as soon as the next epoch is announced by a new best block, that
+/// is on import of the first block of every epoch. The tickets are submitted on top of the
+/// announcing block: the runtime accepts them only during the first half of the epoch.
+async fn tickets_worker<B, C, SO>(
+	client: Arc<C>,
+	keystore: KeystorePtr,
+	epoch_changes: SharedEpochChanges<B, Epoch>,
+	genesis_epoch: sp_consensus_sassafras::Epoch,
+	sync_oracle: SO,
+	offchain_tx_pool_factory: OffchainTransactionPoolFactory<B>,
+) where
+	B: BlockT,
+	C: ProvideRuntimeApi<B>
+		+ HeaderBackend<B>
+		+ HeaderMetadata<B, Error = ClientError>
+		+ BlockchainEvents<B>
+		+ AuxStore,
+	C::Api: SassafrasApi<B>,
+	SO: SyncOracle,
+{
+	let mut notifications = client.import_notification_stream();
+
+	while let Some(notification) = notifications.next().await {
+		if !notification.is_new_best || sync_oracle.is_major_syncing() {
+			continue
+		}
+
+		match find_next_epoch_digest::<B>(&notification.header) {
+			Ok(Some(_)) => {},
+			_ => continue,
+		}
+
+		let hash = notification.hash;
+		let number = *notification.header.number();
+
+		if let Err(err) = generate_and_submit_tickets(
+			&*client,
+			&keystore,
+			&epoch_changes,
+			&genesis_epoch,
+			&offchain_tx_pool_factory,
+			&notification.header,
+		) {
+			warn!(
+				target: LOG_TARGET,
+				"🌳 Unable to generate tickets announced at #{} ({}): {}", number, hash, err,
+			);
+		}
+	}
+}
+
+fn generate_and_submit_tickets<B, C>(
+	client: &C,
+	keystore: &KeystorePtr,
+	epoch_changes: &SharedEpochChanges<B, Epoch>,
+	genesis_epoch: &sp_consensus_sassafras::Epoch,
+	offchain_tx_pool_factory: &OffchainTransactionPoolFactory<B>,
+	header: &B::Header,
+) -> Result<(), Error<B>>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + HeaderMetadata<B, Error = ClientError> + AuxStore,
+	C::Api: SassafrasApi<B>,
+{
+	let hash = header.hash();
+	let number = *header.number();
+	let slot = find_slot::<B>(header)?;
+
+	// Lookup the epoch announced by the given block. That is the epoch a child of the
+	// block would be in at the end of the current epoch.
+	let (descriptor, mut next_epoch) = {
+		let epoch_changes = epoch_changes.shared_data();
+		let query = || descendent_query(client);
+		let lookup = |slot| {
+			epoch_changes
+				.epoch_descriptor_for_child_of(query(), &hash, number, slot)
+				.map_err(|e| Error::<B>::ForkTree(Box::new(e)))?
+				.ok_or(Error::<B>::FetchEpoch(hash))
+		};
+
+		let current_descriptor = lookup(slot)?;
+		let current_end = epoch_changes
+			.viable_epoch(&current_descriptor, |slot| Epoch::genesis(genesis_epoch, slot))
+			.ok_or(Error::<B>::FetchEpoch(hash))?
+			.as_ref()
+			.end_slot();
+
+		let descriptor = lookup(current_end)?;
+		let next_epoch = epoch_changes
+			.viable_epoch(&descriptor, |slot| Epoch::genesis(genesis_epoch, slot))
+			.ok_or(Error::<B>::FetchEpoch(hash))?
+			.into_cloned_inner();
+
+		(descriptor, next_epoch)
+	};
+
+	let ViableEpochDescriptor::Signaled(identifier, _) = descriptor else {
+		return Err(sassafras_err(Error::FetchEpoch(hash)))
+	};
+
+	if !next_epoch.tickets_aux.is_empty() {
+		debug!(target: LOG_TARGET, "Tickets for epoch {} already generated", next_epoch.index);
+		return Ok(())
+	}
+
+	if !next_epoch
+		.authorities
+		.iter()
+		.any(|authority_id| keystore.has_keys(&[(authority_id.to_raw_vec(), AuthorityId::ID)]))
+	{
+		return Ok(())
+	}
+
+	let ring_context = client
+		.runtime_api()
+		.ring_context(hash)
+		.map_err(Error::RuntimeApi)?
+		.ok_or(Error::RingContextUnavailable(hash))?;
+
+	let tickets = generate_epoch_tickets(&mut next_epoch, keystore, &ring_context);
+	if tickets.is_empty() {
+		debug!(target: LOG_TARGET, "No tickets generated for epoch {}", next_epoch.index);
+		return Ok(())
+	}
+
+	{
+		let mut epoch_changes = epoch_changes.shared_data();
+		let Some(epoch) = epoch_changes.epoch_mut(&identifier) else {
+			// The announcing block has been pruned in the meantime.
+			return Err(sassafras_err(Error::FetchEpoch(hash)))
+		};
+		epoch.tickets_aux = next_epoch.tickets_aux;
+
+		aux_schema::write_epoch_changes::<B, _, _>(&*epoch_changes, |insert| {
+			client.insert_aux(insert, [])
+		})
+		.map_err(Error::Client)?;
+	}
+
+	let tickets_count = tickets.len();
+
+	let mut runtime_api = client.runtime_api();
+	runtime_api.register_extension(offchain_tx_pool_factory.offchain_transaction_pool(hash));
+	let submitted = runtime_api
+		.submit_tickets_unsigned_extrinsic(hash, tickets)
+		.map_err(Error::RuntimeApi)?;
+
+	if submitted {
+		info!(
+			target: LOG_TARGET,
+			"🎫 Submitted {} tickets for epoch {}", tickets_count, next_epoch.index,
+		);
+	} else {
+		warn!(target: LOG_TARGET, "🎫 Unable to submit tickets for epoch {}", next_epoch.index);
+	}
+
+	Ok(())
+}
+
+struct SassafrasSlotWorker<B: BlockT, C, E, I, SO, L, BS> {
+	client: Arc<C>,
+	block_import: I,
+	env: E,
+	sync_oracle: SO,
+	justification_sync_link: L,
+	force_authoring: bool,
+	backoff_authoring_blocks: Option<BS>,
+	keystore: KeystorePtr,
+	epoch_changes: SharedEpochChanges<B, Epoch>,
+	genesis_epoch: sp_consensus_sassafras::Epoch,
+	block_proposal_slot_portion: SlotProportion,
+	max_block_proposal_slot_portion: Option<SlotProportion>,
+	telemetry: Option<TelemetryHandle>,
+}
+
+#[async_trait::async_trait]
+impl<B, C, E, I, Error, SO, L, BS> sc_consensus_slots::SimpleSlotWorker<B>
+	for SassafrasSlotWorker<B, C, E, I, SO, L, BS>
+where
+	B: BlockT,
+	C: ProvideRuntimeApi<B> + HeaderBackend<B> + HeaderMetadata<B, Error = ClientError>,
+	C::Api: SassafrasApi<B>,
+	E: Environment<B, Error = Error> + Send + Sync,
+	E::Proposer: Proposer<B, Error = Error>,
+	I: BlockImport<B> + Send + Sync + 'static,
+	SO: SyncOracle + Send + Clone + Sync,
+	L: JustificationSyncLink<B>,
+	BS: BackoffAuthoringBlocksStrategy<NumberFor<B>> + Send + Sync,
+	Error: std::error::Error + Send + From<ConsensusError> + From<I::Error> + 'static,
+{
+	type Claim = (SlotClaim, AuthorityId);
+	type SyncOracle = SO;
+	type JustificationSyncLink = L;
+	type CreateProposer =
+		Pin<Box<dyn Future<Output = Result<E::Proposer, ConsensusError>> + Send + 'static>>;
+	type Proposer = E::Proposer;
+	type BlockImport = I;
+	type AuxData = ViableEpochDescriptor<B::Hash, NumberFor<B>, Epoch>;
+
+	fn logging_target(&self) -> &'static str {
+		LOG_TARGET
+	}
+
+	fn block_import(&mut self) -> &mut Self::BlockImport {
+		&mut self.block_import
+	}
+
+	fn aux_data(&self, parent: &B::Header, slot: Slot) -> Result<Self::AuxData, ConsensusError> {
+		self.epoch_changes
+			.shared_data()
+			.epoch_descriptor_for_child_of(
+				descendent_query(&*self.client),
+				&parent.hash(),
+				*parent.number(),
+				slot,
+			)
+			.map_err(|e| ConsensusError::ChainLookup(e.to_string()))?
+			.ok_or(ConsensusError::InvalidAuthoritiesSet)
+	}
+
+	fn authorities_len(&self, epoch_descriptor: &Self::AuxData) -> Option<usize> {
+		self.epoch_changes
+			.shared_data()
+			.viable_epoch(epoch_descriptor, |slot| Epoch::genesis(&self.genesis_epoch, slot))
+			.map(|epoch| epoch.as_ref().authorities.len())
+	}
+
+	async fn claim_slot(
+		&mut self,
+		parent_header: &B::Header,
+		slot: Slot,
+		epoch_descriptor: &ViableEpochDescriptor<B::Hash, NumberFor<B>, Epoch>,
+	) -> Option<Self::Claim> {
+		debug!(target: LOG_TARGET, "Attempting to claim slot {}", slot);
+
+		// Get the ticket assigned to the slot (if any) according to the parent state.
+		let maybe_ticket = self
+			.client
+			.runtime_api()
+			.slot_ticket(parent_header.hash(), slot)
+			.map_err(|err| warn!(target: LOG_TARGET, "Unable to fetch slot ticket: {}", err))
+			.ok()?;
+
+		let claim = claim_slot(
+			slot,
+			self.epoch_changes
+				.shared_data()
+				.viable_epoch(epoch_descriptor, |slot| Epoch::genesis(&self.genesis_epoch, slot))?
+				.as_ref(),
+			maybe_ticket,
+			&self.keystore,
+		);
+
+		if claim.is_some() {
+			debug!(target: LOG_TARGET, "Claimed slot {}", slot);
+		}
+
+		claim
+	}
+
+	fn pre_digest_data(&self, _slot: Slot, claim: &Self::Claim) -> Vec<DigestItem> {
+		vec![DigestItem::from(&claim.0)]
+	}
+
+	async fn block_import_params(
+		&self,
+		header: B::Header,
+		header_hash: &B::Hash,
+		body: Vec<B::Extrinsic>,
+		storage_changes: StorageChanges<B>,
+		(_, public): Self::Claim,
+		epoch_descriptor: Self::AuxData,
+	) -> Result<BlockImportParams<B>, ConsensusError> {
+		let signature = self
+			.keystore
+			.bandersnatch_sign(
+				<AuthorityId as AppCrypto>::ID,
+				public.as_ref(),
+				header_hash.as_ref(),
+			)
+			.map_err(|e| ConsensusError::CannotSign(format!("{}. Key: {:?}", e, public)))?
+			.ok_or_else(|| {
+				ConsensusError::CannotSign(format!(
+					"Could not find key in keystore. Key: {:?}",
+					public
+				))
+			})?;
+
+		let signature: AuthoritySignature = signature.into();
+		let digest_item = DigestItem::from(&signature);
+
+		let mut import_block = BlockImportParams::new(BlockOrigin::Own, header);
+		import_block.post_digests.push(digest_item);
+		import_block.body = Some(body);
+		import_block.state_action =
+			StateAction::ApplyChanges(sc_consensus::StorageChanges::Changes(storage_changes));
+		import_block
+			.insert_intermediate(INTERMEDIATE_KEY, SassafrasIntermediate::<B> { epoch_descriptor });
+
+		Ok(import_block)
+	}
+
+	fn force_authoring(&self) -> bool {
+		self.force_authoring
+	}
+
+	fn should_backoff(&self, slot: Slot, chain_head: &B::Header) -> bool {
+		if let Some(ref strategy) = self.backoff_authoring_blocks {
+			if let Ok(chain_head_slot) = find_slot::<B>(chain_head) {
+				return strategy.should_backoff(
+					*chain_head.number(),
+					chain_head_slot,
+					self.client.info().finalized_number,
+					slot,
+					self.logging_target(),
+				)
+			}
+		}
+		false
+	}
+
+	fn sync_oracle(&mut self) -> &mut Self::SyncOracle {
+		&mut self.sync_oracle
+	}
+
+	fn justification_sync_link(&mut self) -> &mut Self::JustificationSyncLink {
+		&mut self.justification_sync_link
+	}
+
+	fn proposer(&mut self, block: &B::Header) -> Self::CreateProposer {
+		Box::pin(self.env.init(block).map_err(|e| ConsensusError::ClientImport(e.to_string())))
+	}
+
+	fn telemetry(&self) -> Option<TelemetryHandle> {
+		self.telemetry.clone()
+	}
+
+	fn proposing_remaining_duration(&self, slot_info: &SlotInfo<B>) -> Duration {
+		let parent_slot = find_slot::<B>(&slot_info.chain_head).ok();
+
+		sc_consensus_slots::proposing_remaining_duration(
+			parent_slot,
+			slot_info,
+			&self.block_proposal_slot_portion,
+			self.max_block_proposal_slot_portion.as_ref(),
+			sc_consensus_slots::SlotLenienceType::Exponential,
+			self.logging_target(),
+		)
+	}
+}
@@ -0,0 +1,77 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Inherent data provider for the Sassafras worker and verifier.
+
+use std::ops::Deref;
+
+use sp_consensus_slots::{Slot, SlotDuration};
+use sp_inherents::{InherentData, InherentIdentifier};
+
+/// Provides the current slot to the Sassafras worker and verifier.
+///
+/// Sassafras doesn't register any inherent: the slot is only carried by the slot claim
+/// pre-runtime digest. Nodes are still expected to pass this provider as the first element
+/// of the inherent data providers tuple, so the slot worker can read the current slot.
+#[derive(Debug, Clone, Copy)]
+pub struct InherentDataProvider {
+	slot: Slot,
+}
+
+impl InherentDataProvider {
+	/// Create new inherent data provider from the given `slot`.
+	pub fn new(slot: Slot) -> Self {
+		Self { slot }
+	}
+
+	/// Creates the inherent data provider by calculating the slot from the given
+	/// `timestamp` and `duration`.
+	pub fn from_timestamp_and_slot_duration(
+		timestamp: sp_timestamp::Timestamp,
+		slot_duration: SlotDuration,
+	) -> Self {
+		let slot = Slot::from_timestamp(timestamp, slot_duration);
+		Self { slot }
+	}
+}
+
+impl Deref for InherentDataProvider {
+	type Target = Slot;
+
+	fn deref(&self) -> &Self::Target {
+		&self.slot
+	}
+}
+
+#[async_trait::async_trait]
+impl sp_inherents::InherentDataProvider for InherentDataProvider {
+	async fn provide_inherent_data(
+		&self,
+		_inherent_data: &mut InherentData,
+	) -> Result<(), sp_inherents::Error> {
+		Ok(())
+	}
+
+	async fn try_handle_error(
+		&self,
+		_identifier: &InherentIdentifier,
+		_error: &[u8],
+	) -> Option<Result<(), sp_inherents::Error>> {
+		None
+	}
+}
@@ -0,0 +1,426 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! # Sassafras (Semi Anonymous Sortition of Staked Assignees For Fixed-time Rhythmic
+//! Assignment of Slots)
+//!
+//! Sassafras is a constant-time block production mechanism. The slots of an epoch are
+//! assigned, before the epoch starts, to the tickets submitted by the validators during
+//! the previous epoch.
+//!
+//! A ticket is generated by evaluating a VRF over the next epoch randomness and an attempt
+//! index. Only tickets whose identifier is below a threshold, computed from the epoch
+//! configuration, are valid candidates. Tickets are submitted on-chain together with a ring
+//! VRF proof that they were generated by a member of the next epoch validator set, without
+//! revealing which one. The runtime sorts the received tickets and assigns them to the epoch
+//! slots.
+//!
+//! When a validator authors a block on a slot assigned to one of its tickets it proves the
+//! ownership of the ticket by:
+//! - signing the slot claim with the ephemeral key committed in the ticket body (the "erased" key),
+//!   which is only known to the ticket creator;
+//! - revealing the VRF pre-output which deterministically derives the other ephemeral key committed
+//!   in the ticket body (the "revealed" key).
+//!
+//! Slots without an assigned ticket are claimed by a fallback authority, which is picked
+//! at index:
+//!
+//! `blake2_256(epoch_randomness ++ slot_number) % authorities_len`.
+//!
+//! The fork choice rule is weight-based, where weight equals the number of blocks
+//! authored on slots assigned to tickets. We pick the heaviest chain and go with the
+//! longest one in case of a tie.
+//!
+//! The tickets generated by the local node are stored, together with the secrets required
+//! to claim them, in the epoch changes tree.
+//!
+//! An in-depth description of the protocol can be found here:
+//! <https://research.web3.foundation/Polkadot/protocols/block-production/SASSAFRAS>
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+use std::{
+	collections::BTreeMap,
+	ops::{Deref, DerefMut},
+};
+
+use codec::{Decode, Encode};
+use log::{debug, trace};
+
+use sc_client_api::{backend::AuxStore, UsageProvider};
+use sc_consensus_epochs::{Epoch as EpochT, SharedEpochChanges, ViableEpochDescriptor};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::Result as ClientResult;
+use sp_consensus::Error as ConsensusError;
+use sp_consensus_slots::SlotDuration;
+use sp_runtime::{
+	generic::OpaqueDigestItemId,
+	traits::{Block as BlockT, Header, NumberFor, Zero},
+};
+
+pub use sc_consensus_slots::SlotProportion;
+pub use sp_consensus::SyncOracle;
+pub use sp_consensus_sassafras::{
+	digests::{ConsensusLog, NextEpochDescriptor, SlotClaim},
+	vrf, AuthorityId, AuthorityIndex, AuthorityPair, AuthoritySignature, EpochConfiguration,
+	Randomness, SassafrasApi, SassafrasBlockWeight, Slot, TicketBody, TicketClaim, TicketEnvelope,
+	TicketId, SASSAFRAS_ENGINE_ID,
+};
+
+pub use authorship::{start_sassafras, SassafrasParams, SassafrasWorker};
+pub use aux_schema::load_block_weight as block_weight;
+pub use block_import::{block_import, revert, SassafrasBlockImport};
+pub use verification::{import_queue, ImportQueueParams, SassafrasVerifier};
+
+mod block_import;
+mod verification;
+
+pub mod authorship;
+pub mod aux_schema;
+pub mod inherents;
+#[cfg(test)]
+mod tests;
+
+const LOG_TARGET: &str = "sassafras";
+
+/// Secret data required to claim a slot assigned to a ticket generated by the local node.
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct TicketSecret {
+	/// Index of the local authority which generated the ticket.
+	pub authority_idx: AuthorityIndex,
+	/// Seed of the ephemeral key erased on ticket claim.
+	pub erased_seed: [u8; 32],
+}
+
+/// Sassafras epoch information.
+///
+/// Together with the epoch parameters it holds the secrets of the tickets generated by the
+/// local node for the epoch.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode)]
+pub struct Epoch {
+	inner: sp_consensus_sassafras::Epoch,
+	/// Tickets generated by the local node for this epoch.
+	pub tickets_aux: BTreeMap<TicketId, TicketSecret>,
+}
+
+impl Deref for Epoch {
+	type Target = sp_consensus_sassafras::Epoch;
+
+	fn deref(&self) -> &Self::Target {
+		&self.inner
+	}
+}
+
+impl DerefMut for Epoch {
+	fn deref_mut(&mut self) -> &mut Self::Target {
+		&mut self.inner
+	}
+}
+
+impl From<sp_consensus_sassafras::Epoch> for Epoch {
+	fn from(epoch: sp_consensus_sassafras::Epoch) -> Self {
+		Epoch { inner: epoch, tickets_aux: Default::default() }
+	}
+}
+
+impl EpochT for Epoch {
+	type NextEpochDescriptor = (NextEpochDescriptor, EpochConfiguration);
+	type Slot = Slot;
+
+	fn increment(&self, (descriptor, config): (NextEpochDescriptor, EpochConfiguration)) -> Epoch {
+		sp_consensus_sassafras::Epoch {
+			index: self.index + 1,
+			start: self.start + self.length as u64,
+			length: self.length,
+			randomness: descriptor.randomness,
+			authorities: descriptor.authorities,
+			config,
+		}
+		.into()
+	}
+
+	fn start_slot(&self) -> Slot {
+		self.start
+	}
+
+	fn end_slot(&self) -> Slot {
+		self.start + self.length as u64
+	}
+}
+
+impl Epoch {
+	/// Create the genesis epoch (epoch #0).
+	///
+	/// This is defined to start at the slot of the first block, so that has to be provided.
+	pub fn genesis(genesis_epoch: &sp_consensus_sassafras::Epoch, slot: Slot) -> Epoch {
+		sp_consensus_sassafras::Epoch { index: 0, start: slot, ..genesis_epoch.clone() }.into()
+	}
+
+	/// Clone and tweak epoch information to refer to the specified slot.
+	///
+	/// All the information which depends on the slot value is recomputed and assigned
+	/// to the returned epoch instance. Tickets are bound to the epoch index, thus the
+	/// tickets of the original epoch are dropped if the slot falls beyond its end.
+	///
+	/// The `slot` must be greater than or equal the original epoch start slot,
+	/// if is less this operation is equivalent to a simple clone.
+	pub fn clone_for_slot(&self, slot: Slot) -> Epoch {
+		let mut epoch = self.clone();
+
+		let skipped_epochs = *slot.saturating_sub(self.start) / self.length as u64;
+		if skipped_epochs == 0 {
+			return epoch
+		}
+
+		let index = epoch.index.checked_add(skipped_epochs).expect(
+			"epoch number is u64; it should be strictly smaller than number of slots; \
+				slots relate in some way to wall clock time; \
+				if u64 is not enough we should crash for safety; qed.",
+		);
+
+		let start = skipped_epochs
+			.checked_mul(epoch.length as u64)
+			.and_then(|skipped_slots| epoch.start.checked_add(skipped_slots))
+			.expect(
+				"slot number is u64; it should relate in some way to wall clock time; \
+				 if u64 is not enough we should crash for safety; qed.",
+			);
+
+		epoch.index = index;
+		epoch.start = Slot::from(start);
+		epoch.tickets_aux.clear();
+
+		epoch
+	}
+}
+
+/// Sassafras protocol configuration.
+#[derive(Clone, Debug)]
+pub struct SassafrasConfiguration {
+	/// The slot duration.
+	///
+	/// This is not part of the on-chain configuration and must match the one used by the
+	/// runtime to check the timestamp against the slot.
+	pub slot_duration: SlotDuration,
+	/// The epoch used to import the first block of the chain.
+	pub genesis_epoch: sp_consensus_sassafras::Epoch,
+}
+
+impl SassafrasConfiguration {
+	/// The slot duration.
+	pub fn slot_duration(&self) -> SlotDuration {
+		self.slot_duration
+	}
+}
+
+/// Read configuration from the runtime state at current best block.
+pub fn configuration<B: BlockT, C>(
+	client: &C,
+	slot_duration: SlotDuration,
+) -> ClientResult<SassafrasConfiguration>
+where
+	C: AuxStore + ProvideRuntimeApi<B> + UsageProvider<B>,
+	C::Api: SassafrasApi<B>,
+{
+	let at_hash = if client.usage_info().chain.finalized_state.is_some() {
+		client.usage_info().chain.best_hash
+	} else {
+		debug!(target: LOG_TARGET, "No finalized state is available. Reading config from genesis");
+		client.usage_info().chain.genesis_hash
+	};
+
+	let genesis_epoch = client.runtime_api().current_epoch(at_hash)?;
+
+	Ok(SassafrasConfiguration { slot_duration, genesis_epoch })
+}
+
+/// Errors encountered by the Sassafras routines.
+#[derive(Debug, thiserror::Error)]
+pub enum Error<B: BlockT> {
+	/// Multiple Sassafras pre-runtime digests
+	#[error("Multiple Sassafras pre-runtime digests, rejecting!")]
+	MultiplePreRuntimeDigests,
+	/// No Sassafras pre-runtime digest found
+	#[error("No Sassafras pre-runtime digest found")]
+	NoPreRuntimeDigest,
+	/// Multiple Sassafras epoch change digests
+	#[error("Multiple Sassafras epoch change digests, rejecting!")]
+	MultipleEpochChangeDigests,
+	/// Could not fetch epoch
+	#[error("Could not fetch epoch at {0:?}")]
+	FetchEpoch(B::Hash),
+	/// Header rejected: too far in the future
+	#[error("Header {0:?} rejected: too far in the future")]
+	TooFarInFuture(B::Hash),
+	/// Parent unavailable. Cannot import
+	#[error("Parent ({0}) of {1} unavailable. Cannot import")]
+	ParentUnavailable(B::Hash, B::Hash),
+	/// Slot number must increase
+	#[error("Slot number must increase: parent slot: {0}, this slot: {1}")]
+	SlotMustIncrease(Slot, Slot),
+	/// Header has a bad seal
+	#[error("Header {0:?} has a bad seal")]
+	HeaderBadSeal(B::Hash),
+	/// Header is unsealed
+	#[error("Header {0:?} is unsealed")]
+	HeaderUnsealed(B::Hash),
+	/// Slot author not found
+	#[error("Slot author not found")]
+	SlotAuthorNotFound,
+	/// Bad signature
+	#[error("Bad signature on {0:?}")]
+	BadSignature(B::Hash),
+	/// Invalid author: Expected fallback author
+	#[error("Invalid author: Expected fallback author: {0:?}, got: {1:?}.")]
+	InvalidAuthor(AuthorityId, AuthorityId),
+	/// The slot is assigned to a ticket but the header doesn't claim it
+	#[error("Missing ticket claim for slot {0}")]
+	MissingTicketClaim(Slot),
+	/// The slot is not assigned to a ticket but the header claims one
+	#[error("Unexpected ticket claim for slot {0}")]
+	UnexpectedTicketClaim(Slot),
+	/// Ticket ownership verification failed
+	#[error("Ticket ownership verification failed for ticket {0:032x}")]
+	TicketVerificationFailed(TicketId),
+	/// VRF verification failed
+	#[error("VRF verification failed")]
+	VrfVerificationFailed,
+	/// Could not fetch parent header
+	#[error("Could not fetch parent header: {0}")]
+	FetchParentHeader(sp_blockchain::Error),
+	/// Expected epoch change to happen.
+	#[error("Expected epoch change to happen at {0:?}, s{1}")]
+	ExpectedEpochChange(B::Hash, Slot),
+	/// Unexpected epoch change
+	#[error("Unexpected epoch change")]
+	UnexpectedEpochChange,
+	/// Parent block has no associated weight
+	#[error("Parent block of {0} has no associated weight")]
+	ParentBlockNoAssociatedWeight(B::Hash),
+	/// Ring context not available in the runtime state
+	#[error("Ring context not available at {0:?}")]
+	RingContextUnavailable(B::Hash),
+	/// Check inherents error
+	#[error("Checking inherents failed: {0}")]
+	CheckInherents(sp_inherents::Error),
+	/// Unhandled check inherents error
+	#[error("Checking inherents unhandled error: {}", String::from_utf8_lossy(.0))]
+	CheckInherentsUnhandled(sp_inherents::InherentIdentifier),
+	/// Create inherents error.
+	#[error("Creating inherents failed: {0}")]
+	CreateInherents(sp_inherents::Error),
+	/// Client error
+	#[error(transparent)]
+	Client(sp_blockchain::Error),
+	/// Runtime Api error.
+	#[error(transparent)]
+	RuntimeApi(sp_api::ApiError),
+	/// Fork tree error
+	#[error(transparent)]
+	ForkTree(Box<fork_tree::Error<sp_blockchain::Error>>),
+}
+
+impl<B: BlockT> From<Error<B>> for String {
+	fn from(error: Error<B>) -> String {
+		error.to_string()
+	}
+}
+
+fn sassafras_err<B: BlockT>(error: Error<B>) -> Error<B> {
+	debug!(target: LOG_TARGET, "{}", error);
+	error
+}
+
+/// Intermediate value passed to block importer.
+pub struct SassafrasIntermediate<B: BlockT> {
+	/// The epoch descriptor.
+	pub epoch_descriptor: ViableEpochDescriptor<B::Hash, NumberFor<B>, Epoch>,
+}
+
+/// Intermediate key for Sassafras engine.
+pub static INTERMEDIATE_KEY: &[u8] = b"sass1";
+
+/// State that must be shared between the import queue and the authoring logic.
+#[derive(Clone)]
+pub struct SassafrasLink<Block: BlockT> {
+	epoch_changes: SharedEpochChanges<Block, Epoch>,
+	config: SassafrasConfiguration,
+}
+
+impl<Block: BlockT> SassafrasLink<Block> {
+	/// Get the epoch changes of this link.
+	pub fn epoch_changes(&self) -> &SharedEpochChanges<Block, Epoch> {
+		&self.epoch_changes
+	}
+
+	/// Get the config of this link.
+	pub fn config(&self) -> &SassafrasConfiguration {
+		&self.config
+	}
+}
+
+/// Extract the Sassafras slot claim from the given header.
+///
+/// Pre-runtime digests are mandatory, the function will return `Err` if none is found.
+pub fn find_slot_claim<B: BlockT>(header: &B::Header) -> Result<SlotClaim, Error<B>> {
+	let mut claim: Option<_> = None;
+	for log in header.digest().logs() {
+		trace!(target: LOG_TARGET, "Checking log {:?}, looking for pre runtime digest", log);
+		match (log.pre_runtime_try_to::<SlotClaim>(&SASSAFRAS_ENGINE_ID), claim.is_some()) {
+			(Some(_), true) => return Err(sassafras_err(Error::MultiplePreRuntimeDigests)),
+			(None, _) => trace!(target: LOG_TARGET, "Ignoring digest not meant for us"),
+			(s, false) => claim = s,
+		}
+	}
+	claim.ok_or_else(|| sassafras_err(Error::NoPreRuntimeDigest))
+}
+
+/// Extract the slot of the given header.
+///
+/// The genesis block doesn't contain a slot claim, its slot is defined to be zero.
+pub fn find_slot<B: BlockT>(header: &B::Header) -> Result<Slot, Error<B>> {
+	if header.number().is_zero() {
+		return Ok(0.into())
+	}
+	find_slot_claim::<B>(header).map(|claim| claim.slot)
+}
+
+/// Extract the Sassafras epoch change digest from the given header, if it exists.
+fn find_next_epoch_digest<B: BlockT>(
+	header: &B::Header,
+) -> Result<Option<NextEpochDescriptor>, Error<B>> {
+	let mut epoch_digest: Option<_> = None;
+	for log in header.digest().logs() {
+		trace!(target: LOG_TARGET, "Checking log {:?}, looking for epoch change digest.", log);
+		let log = log.try_to::<ConsensusLog>(OpaqueDigestItemId::Consensus(&SASSAFRAS_ENGINE_ID));
+		match (log, epoch_digest.is_some()) {
+			(Some(ConsensusLog::NextEpochData(_)), true) =>
+				return Err(sassafras_err(Error::MultipleEpochChangeDigests)),
+			(Some(ConsensusLog::NextEpochData(epoch)), false) => epoch_digest = Some(epoch),
+			_ => trace!(target: LOG_TARGET, "Ignoring digest not meant for us"),
+		}
+	}
+
+	Ok(epoch_digest)
+}
+
+/// Map a consensus error to a client import error.
+fn import_err<B: BlockT>(error: Error<B>) -> ConsensusError {
+	ConsensusError::ClientImport(sassafras_err(error).into())
+}
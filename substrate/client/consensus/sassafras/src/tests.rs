@@ -0,0 +1,809 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Sassafras client tests
+
+use super::*;
+
+use authorship::{claim_slot, generate_epoch_tickets};
+use futures::{future, StreamExt};
+use inherents::InherentDataProvider;
+use parking_lot::Mutex;
+use sc_block_builder::BlockBuilderBuilder;
+use sc_client_api::{
+	BlockchainEvents, FinalityNotifications, ImportNotifications, OnFinalityAction,
+	OnImportAction, PreCommitActions, StorageEventStream, StorageKey,
+};
+use sc_consensus::{
+	BlockCheckParams, BlockImport, BlockImportParams, BoxBlockImport, BoxJustificationImport,
+	ImportResult,
+};
+use sc_consensus_epochs::{
+	descendent_query, EpochChangesFor, EpochIdentifier, EpochIdentifierPosition,
+};
+use sc_consensus_slots::{BackoffAuthoringOnFinalizedHeadLagging, CheckedHeader};
+use sc_network_test::{BlockImportAdapter, Peer, PeersClient, PeersFullClient, TestNetFactory};
+use sc_transaction_pool_api::{OffchainTransactionPoolFactory, RejectAllTxPool};
+use sp_api::ApiRef;
+use sp_application_crypto::AppCrypto;
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
+use sp_blockchain::{CachedHeaderMetadata, HeaderBackend, HeaderMetadata};
+use sp_consensus::{
+	BlockOrigin, DisableProofRecording, Environment, NoNetwork as DummyOracle, Proposal,
+	Proposer,
+};
+use sp_consensus_sassafras::vrf::RingContext;
+use sp_core::Pair;
+use sp_inherents::{CheckInherentsResult, InherentData};
+use sp_keystore::{testing::MemoryKeystore, Keystore, KeystorePtr};
+use sp_runtime::{generic::Digest, DigestItem};
+use sp_timestamp::Timestamp;
+use std::{sync::Arc, task::Poll, time::Duration};
+use substrate_test_runtime_client::{
+	runtime::{Block as TestBlock, Hash, Header as TestHeader},
+	DefaultTestClientBuilderExt, TestClientBuilder, TestClientBuilderExt,
+};
+use verification::{check_header, VerificationParams};
+
+const EPOCH_LENGTH: u32 = 10;
+
+fn create_keystore(seeds: &[&str]) -> (KeystorePtr, Vec<AuthorityId>) {
+	let keystore = MemoryKeystore::new();
+	let authorities = seeds
+		.iter()
+		.map(|seed| {
+			keystore
+				.bandersnatch_generate_new(AuthorityId::ID, Some(seed))
+				.expect("Creates authority key")
+				.into()
+		})
+		.collect();
+	(Arc::new(keystore), authorities)
+}
+
+fn create_genesis_epoch(authorities: Vec<AuthorityId>) -> sp_consensus_sassafras::Epoch {
+	sp_consensus_sassafras::Epoch {
+		index: 0,
+		start: 0.into(),
+		length: EPOCH_LENGTH,
+		randomness: [0xab; 32],
+		authorities,
+		config: EpochConfiguration { redundancy_factor: 1, attempts_number: 32 },
+	}
+}
+
+fn create_epoch(authorities: Vec<AuthorityId>) -> Epoch {
+	create_genesis_epoch(authorities).into()
+}
+
+fn make_sealed_header(
+	claim: &SlotClaim,
+	author: &AuthorityId,
+	keystore: &KeystorePtr,
+) -> TestHeader {
+	let digest = Digest { logs: vec![DigestItem::from(claim)] };
+	let mut header =
+		TestHeader::new(1, Default::default(), Default::default(), [0; 32].into(), digest);
+
+	let signature: AuthoritySignature = keystore
+		.bandersnatch_sign(AuthorityId::ID, author.as_ref(), header.hash().as_ref())
+		.unwrap()
+		.unwrap()
+		.into();
+	header.digest_mut().push(DigestItem::from(&signature));
+	header
+}
+
+fn verify(
+	header: TestHeader,
+	epoch: &Epoch,
+	maybe_ticket: Option<(TicketId, TicketBody)>,
+) -> Result<AuthorityId, Error<TestBlock>> {
+	let claim = find_slot_claim::<TestBlock>(&header).unwrap();
+	let params =
+		VerificationParams { slot_now: claim.slot + 1, header, claim, epoch, maybe_ticket };
+	match check_header::<TestBlock>(params)? {
+		CheckedHeader::Checked(_, info) => Ok(info.author),
+		CheckedHeader::Deferred(..) => panic!("Unexpected deferred header"),
+	}
+}
+
+#[test]
+fn fallback_claim_and_verification() {
+	let (keystore, authorities) = create_keystore(&["//Alice", "//Bob", "//Charlie"]);
+	let epoch = create_epoch(authorities.clone());
+
+	for slot in 1..EPOCH_LENGTH as u64 {
+		let slot = Slot::from(slot);
+		let (claim, author) = claim_slot(slot, &epoch, None, &keystore).unwrap();
+
+		let expected_idx =
+			authorship::fallback_slot_author(slot, &authorities, &epoch.randomness).unwrap();
+		assert_eq!(claim.authority_idx, expected_idx);
+		assert_eq!(author, authorities[expected_idx as usize]);
+		assert!(claim.ticket_claim.is_none());
+
+		let header = make_sealed_header(&claim, &author, &keystore);
+		assert_eq!(verify(header, &epoch, None).unwrap(), author);
+	}
+}
+
+#[test]
+fn fallback_claim_requires_author_key() {
+	let (_, authorities) = create_keystore(&["//Alice", "//Bob"]);
+	let (keystore, _) = create_keystore(&["//Charlie"]);
+	let epoch = create_epoch(authorities);
+
+	assert!(claim_slot(1.into(), &epoch, None, &keystore).is_none());
+}
+
+#[test]
+fn tickets_generation_claim_and_verification() {
+	let (keystore, authorities) = create_keystore(&["//Alice"]);
+	let mut epoch = create_epoch(authorities.clone());
+	let ring_ctx = RingContext::new_testing();
+
+	let tickets = generate_epoch_tickets(&mut epoch, &keystore, &ring_ctx);
+	assert!(!tickets.is_empty());
+	assert_eq!(tickets.len(), epoch.tickets_aux.len());
+
+	let (ticket_id, secret) = epoch.tickets_aux.first_key_value().unwrap();
+	let ticket_id = *ticket_id;
+	assert_eq!(secret.authority_idx, 0);
+	let ticket_body = tickets
+		.iter()
+		.map(|t| t.body.clone())
+		.find(|body| {
+			let input = vrf::ticket_id_input(&epoch.randomness, body.attempt_idx, epoch.index);
+			let pre_output = keystore
+				.bandersnatch_vrf_pre_output(AuthorityId::ID, authorities[0].as_ref(), &input)
+				.unwrap()
+				.unwrap();
+			vrf::make_ticket_id(&input, &pre_output) == ticket_id
+		})
+		.unwrap();
+	let maybe_ticket = Some((ticket_id, ticket_body));
+
+	let slot = Slot::from(3);
+	let (claim, author) = claim_slot(slot, &epoch, maybe_ticket.clone(), &keystore).unwrap();
+	assert!(claim.ticket_claim.is_some());
+
+	// Valid ticket claim
+	let header = make_sealed_header(&claim, &author, &keystore);
+	assert_eq!(verify(header.clone(), &epoch, maybe_ticket.clone()).unwrap(), author);
+
+	// Slot is not assigned to any ticket according to the parent state
+	assert!(matches!(verify(header, &epoch, None), Err(Error::UnexpectedTicketClaim(_))));
+
+	// Slot is assigned to a ticket but the fallback author tries to claim it
+	let (fallback_claim, _) = claim_slot(slot, &epoch, None, &keystore).unwrap();
+	let header = make_sealed_header(&fallback_claim, &author, &keystore);
+	assert!(matches!(verify(header, &epoch, maybe_ticket), Err(Error::MissingTicketClaim(_))));
+}
+
+#[test]
+fn unknown_ticket_cannot_be_claimed() {
+	let (keystore, authorities) = create_keystore(&["//Alice"]);
+	let epoch = create_epoch(authorities);
+
+	let (erased, _) = sp_core::ed25519::Pair::generate();
+	let (revealed, _) = sp_core::ed25519::Pair::generate();
+	let ticket_body = TicketBody {
+		attempt_idx: 0,
+		erased_public: erased.public(),
+		revealed_public: revealed.public(),
+	};
+
+	assert!(claim_slot(1.into(), &epoch, Some((123, ticket_body)), &keystore).is_none());
+}
+
+#[test]
+fn tampered_seal_is_rejected() {
+	let (keystore, authorities) = create_keystore(&["//Alice", "//Bob"]);
+	let epoch = create_epoch(authorities);
+
+	let (claim, author) = claim_slot(1.into(), &epoch, None, &keystore).unwrap();
+	let mut header = make_sealed_header(&claim, &author, &keystore);
+	header.number = 2;
+
+	assert!(matches!(verify(header, &epoch, None), Err(Error::BadSignature(_))));
+}
+
+#[test]
+fn epoch_increment_and_clone_for_slot() {
+	let (keystore, authorities) = create_keystore(&["//Alice"]);
+	let mut epoch = create_epoch(authorities.clone());
+	generate_epoch_tickets(&mut epoch, &keystore, &RingContext::new_testing());
+	assert!(!epoch.tickets_aux.is_empty());
+
+	let descriptor = NextEpochDescriptor {
+		randomness: [0xcd; 32],
+		authorities: authorities.clone(),
+		config: None,
+	};
+	let next = epoch.increment((descriptor, epoch.config.clone()));
+	assert_eq!(next.index, 1);
+	assert_eq!(next.start, Slot::from(EPOCH_LENGTH as u64));
+	assert_eq!(next.randomness, [0xcd; 32]);
+	assert!(next.tickets_aux.is_empty());
+
+	// Slot within the same epoch
+	let same = epoch.clone_for_slot((EPOCH_LENGTH as u64 - 1).into());
+	assert_eq!(same, epoch);
+
+	// Two epochs skipped, tickets are bound to the original epoch index
+	let skipped = epoch.clone_for_slot((3 * EPOCH_LENGTH as u64 + 1).into());
+	assert_eq!(skipped.index, 3);
+	assert_eq!(skipped.start, Slot::from(3 * EPOCH_LENGTH as u64));
+	assert!(skipped.tickets_aux.is_empty());
+}
+
+#[test]
+fn epoch_changes_aux_roundtrip() {
+	let client = TestClientBuilder::new().build();
+
+	let epoch_changes = aux_schema::load_epoch_changes::<TestBlock, _>(&client).unwrap();
+	assert!(epoch_changes.shared_data().tree().roots().next().is_none());
+
+	let (_, authorities) = create_keystore(&["//Alice"]);
+	let mut epoch = create_epoch(authorities);
+	epoch
+		.tickets_aux
+		.insert(42, TicketSecret { authority_idx: 0, erased_seed: [7; 32] });
+
+	let genesis_hash = client.chain_info().genesis_hash;
+	let next_descriptor =
+		NextEpochDescriptor { randomness: [0; 32], authorities: vec![], config: None };
+	let next_epoch = epoch.increment((next_descriptor, epoch.config.clone()));
+
+	// The current epoch is associated to the parent of the reset block.
+	let mut changes = EpochChangesFor::<TestBlock, Epoch>::default();
+	changes.reset(genesis_hash, [1; 32].into(), 1, epoch.clone(), next_epoch);
+
+	aux_schema::write_epoch_changes::<TestBlock, _, _>(&changes, |values| {
+		client.insert_aux(values, [])
+	})
+	.unwrap();
+
+	let loaded = aux_schema::load_epoch_changes::<TestBlock, _>(&client).unwrap();
+	let id = EpochIdentifier {
+		position: EpochIdentifierPosition::Regular,
+		hash: genesis_hash,
+		number: 0,
+	};
+	assert_eq!(loaded.shared_data().epoch(&id), Some(&epoch));
+}
+
+#[test]
+fn block_weight_aux_roundtrip() {
+	let client = TestClientBuilder::new().build();
+	let hash = client.chain_info().genesis_hash;
+
+	assert_eq!(aux_schema::load_block_weight(&client, hash).unwrap(), None);
+
+	aux_schema::write_block_weight(hash, 3, |values| {
+		client.insert_aux(&values.iter().map(|(k, v)| (k.as_slice(), *v)).collect::<Vec<_>>(), [])
+	})
+	.unwrap();
+
+	assert_eq!(aux_schema::load_block_weight(&client, hash).unwrap(), Some(3));
+}
+
+const SLOT_DURATION_MS: u64 = 500;
+
+type Mutator = Arc<dyn Fn(&mut TestHeader) + Send + Sync>;
+
+/// A runtime which assigns every slot to its fallback author.
+///
+/// The ring context is not available, thus the authorities never submit tickets.
+#[derive(Clone)]
+struct TestApi {
+	genesis_epoch: sp_consensus_sassafras::Epoch,
+}
+
+// compiler gets confused and warns us about unused inner
+#[allow(dead_code)]
+struct RuntimeApi {
+	inner: TestApi,
+}
+
+sp_api::mock_impl_runtime_apis! {
+	impl SassafrasApi<TestBlock> for RuntimeApi {
+		fn ring_context() -> Option<vrf::RingContext> {
+			None
+		}
+
+		fn slot_ticket(_slot: Slot) -> Option<(TicketId, TicketBody)> {
+			None
+		}
+
+		fn current_epoch() -> sp_consensus_sassafras::Epoch {
+			self.inner.genesis_epoch.clone()
+		}
+	}
+
+	impl BlockBuilderApi<TestBlock> for RuntimeApi {
+		fn check_inherents(_block: TestBlock, _data: InherentData) -> CheckInherentsResult {
+			CheckInherentsResult::new()
+		}
+	}
+}
+
+/// A client that serves blocks from a test network peer and runtime calls from [`TestApi`].
+struct PeerClient {
+	client: Arc<PeersFullClient>,
+	api: TestApi,
+}
+
+impl ProvideRuntimeApi<TestBlock> for PeerClient {
+	type Api = RuntimeApi;
+
+	fn runtime_api(&self) -> ApiRef<Self::Api> {
+		RuntimeApi { inner: self.api.clone() }.into()
+	}
+}
+
+impl HeaderBackend<TestBlock> for PeerClient {
+	fn header(&self, hash: Hash) -> sp_blockchain::Result<Option<TestHeader>> {
+		self.client.header(hash)
+	}
+
+	fn info(&self) -> sp_blockchain::Info<TestBlock> {
+		self.client.info()
+	}
+
+	fn status(&self, hash: Hash) -> sp_blockchain::Result<sp_blockchain::BlockStatus> {
+		self.client.status(hash)
+	}
+
+	fn number(&self, hash: Hash) -> sp_blockchain::Result<Option<u64>> {
+		self.client.number(hash)
+	}
+
+	fn hash(&self, number: u64) -> sp_blockchain::Result<Option<Hash>> {
+		self.client.hash(number)
+	}
+}
+
+impl HeaderMetadata<TestBlock> for PeerClient {
+	type Error = sp_blockchain::Error;
+
+	fn header_metadata(
+		&self,
+		hash: Hash,
+	) -> sp_blockchain::Result<CachedHeaderMetadata<TestBlock>> {
+		self.client.header_metadata(hash)
+	}
+
+	fn insert_header_metadata(&self, hash: Hash, metadata: CachedHeaderMetadata<TestBlock>) {
+		self.client.insert_header_metadata(hash, metadata)
+	}
+
+	fn remove_header_metadata(&self, hash: Hash) {
+		self.client.remove_header_metadata(hash)
+	}
+}
+
+impl AuxStore for PeerClient {
+	fn insert_aux<
+		'a,
+		'b: 'a,
+		'c: 'a,
+		I: IntoIterator<Item = &'a (&'c [u8], &'c [u8])>,
+		D: IntoIterator<Item = &'a &'b [u8]>,
+	>(
+		&self,
+		insert: I,
+		delete: D,
+	) -> sp_blockchain::Result<()> {
+		self.client.insert_aux(insert, delete)
+	}
+
+	fn get_aux(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+		self.client.get_aux(key)
+	}
+}
+
+impl PreCommitActions<TestBlock> for PeerClient {
+	fn register_import_action(&self, op: OnImportAction<TestBlock>) {
+		self.client.register_import_action(op)
+	}
+
+	fn register_finality_action(&self, op: OnFinalityAction<TestBlock>) {
+		self.client.register_finality_action(op)
+	}
+}
+
+impl BlockchainEvents<TestBlock> for PeerClient {
+	fn import_notification_stream(&self) -> ImportNotifications<TestBlock> {
+		self.client.import_notification_stream()
+	}
+
+	fn every_import_notification_stream(&self) -> ImportNotifications<TestBlock> {
+		self.client.every_import_notification_stream()
+	}
+
+	fn finality_notification_stream(&self) -> FinalityNotifications<TestBlock> {
+		self.client.finality_notification_stream()
+	}
+
+	fn storage_changes_notification_stream(
+		&self,
+		filter_keys: Option<&[StorageKey]>,
+		child_filter_keys: Option<&[(StorageKey, Option<Vec<StorageKey>>)]>,
+	) -> sp_blockchain::Result<StorageEventStream<Hash>> {
+		self.client.storage_changes_notification_stream(filter_keys, child_filter_keys)
+	}
+}
+
+#[derive(Clone)]
+struct DummyFactory {
+	client: Arc<PeersFullClient>,
+	epoch_changes: SharedEpochChanges<TestBlock, Epoch>,
+	authorities: Vec<AuthorityId>,
+	mutator: Mutator,
+}
+
+struct DummyProposer {
+	factory: DummyFactory,
+	parent_header: TestHeader,
+}
+
+impl Environment<TestBlock> for DummyFactory {
+	type CreateProposer = future::Ready<Result<DummyProposer, sp_blockchain::Error>>;
+	type Proposer = DummyProposer;
+	type Error = sp_blockchain::Error;
+
+	fn init(&mut self, parent_header: &TestHeader) -> Self::CreateProposer {
+		future::ready(Ok(DummyProposer { factory: self.clone(), parent_header: parent_header.clone() }))
+	}
+}
+
+impl DummyProposer {
+	/// Announce the next epoch if the block is the first one of its epoch, as the runtime would.
+	fn next_epoch_digest(&self, slot: Slot) -> Option<DigestItem> {
+		let parent_hash = self.parent_header.hash();
+		let parent_slot = find_slot::<TestBlock>(&self.parent_header).unwrap();
+		let epoch_descriptor = self
+			.factory
+			.epoch_changes
+			.shared_data()
+			.epoch_descriptor_for_child_of(
+				descendent_query(&*self.factory.client),
+				&parent_hash,
+				*self.parent_header.number(),
+				slot,
+			)
+			.unwrap()
+			.unwrap();
+
+		(parent_slot < epoch_descriptor.start_slot()).then(|| {
+			let descriptor = NextEpochDescriptor {
+				randomness: parent_hash.into(),
+				authorities: self.factory.authorities.clone(),
+				config: None,
+			};
+			DigestItem::Consensus(
+				SASSAFRAS_ENGINE_ID,
+				ConsensusLog::NextEpochData(descriptor).encode(),
+			)
+		})
+	}
+}
+
+impl Proposer<TestBlock> for DummyProposer {
+	type Error = sp_blockchain::Error;
+	type Proposal = future::Ready<Result<Proposal<TestBlock, ()>, sp_blockchain::Error>>;
+	type ProofRecording = DisableProofRecording;
+	type Proof = ();
+
+	fn propose(
+		self,
+		_: InherentData,
+		mut inherent_digests: Digest,
+		_: Duration,
+		_: Option<usize>,
+	) -> Self::Proposal {
+		let claim = inherent_digests
+			.logs()
+			.iter()
+			.find_map(|log| SlotClaim::try_from(log).ok())
+			.expect("slot claim is provided by the slot worker");
+		if let Some(digest) = self.next_epoch_digest(claim.slot) {
+			inherent_digests.push(digest);
+		}
+
+		let block_builder = BlockBuilderBuilder::new(&*self.factory.client)
+			.on_parent_block(self.parent_header.hash())
+			.with_parent_block_number(*self.parent_header.number())
+			.with_inherent_digests(inherent_digests)
+			.build()
+			.unwrap();
+
+		let mut block = match block_builder.build() {
+			Ok(b) => b.block,
+			Err(e) => return future::ready(Err(e)),
+		};
+
+		(self.factory.mutator)(&mut block.header);
+
+		future::ready(Ok(Proposal { block, proof: (), storage_changes: Default::default() }))
+	}
+}
+
+#[derive(Clone)]
+struct PanickingBlockImport<B>(B);
+
+#[async_trait::async_trait]
+impl<BI> BlockImport<TestBlock> for PanickingBlockImport<BI>
+where
+	BI: BlockImport<TestBlock> + Send + Sync,
+{
+	type Error = BI::Error;
+
+	async fn import_block(
+		&self,
+		block: BlockImportParams<TestBlock>,
+	) -> Result<ImportResult, Self::Error> {
+		Ok(self.0.import_block(block).await.expect("importing block failed"))
+	}
+
+	async fn check_block(
+		&self,
+		block: BlockCheckParams<TestBlock>,
+	) -> Result<ImportResult, Self::Error> {
+		Ok(self.0.check_block(block).await.expect("checking block failed"))
+	}
+}
+
+type SassafrasPeerBlockImport =
+	PanickingBlockImport<SassafrasBlockImport<TestBlock, PeerClient, Arc<PeersFullClient>>>;
+
+type SassafrasPeer = Peer<Option<PeerData>, SassafrasPeerBlockImport>;
+
+type TestSelectChain =
+	substrate_test_runtime_client::LongestChain<substrate_test_runtime_client::Backend, TestBlock>;
+
+type TestVerifier = SassafrasVerifier<
+	TestBlock,
+	PeerClient,
+	TestSelectChain,
+	Box<
+		dyn CreateInherentDataProviders<
+			TestBlock,
+			(),
+			InherentDataProviders = (InherentDataProvider,),
+		>,
+	>,
+>;
+
+struct PeerData {
+	client: Arc<PeerClient>,
+	link: SassafrasLink<TestBlock>,
+	block_import: Mutex<Option<BoxBlockImport<TestBlock>>>,
+}
+
+struct SassafrasTestNet {
+	peers: Vec<SassafrasPeer>,
+	api: TestApi,
+}
+
+impl Default for SassafrasTestNet {
+	fn default() -> Self {
+		let (_, authorities) = create_keystore(&PEERS);
+		let api = TestApi { genesis_epoch: create_genesis_epoch(authorities) };
+		Self { peers: Vec::new(), api }
+	}
+}
+
+impl TestNetFactory for SassafrasTestNet {
+	type Verifier = TestVerifier;
+	type PeerData = Option<PeerData>;
+	type BlockImport = SassafrasPeerBlockImport;
+
+	fn make_block_import(
+		&self,
+		client: PeersClient,
+	) -> (
+		BlockImportAdapter<Self::BlockImport>,
+		Option<BoxJustificationImport<TestBlock>>,
+		Option<PeerData>,
+	) {
+		let inner = client.as_client();
+		let client = Arc::new(PeerClient { client: inner.clone(), api: self.api.clone() });
+
+		let config = SassafrasConfiguration {
+			slot_duration: SlotDuration::from_millis(SLOT_DURATION_MS),
+			genesis_epoch: self.api.genesis_epoch.clone(),
+		};
+		let (block_import, link) =
+			crate::block_import(config, inner, client.clone()).expect("can initialize block-import");
+
+		let block_import = PanickingBlockImport(block_import);
+
+		let data_block_import =
+			Mutex::new(Some(Box::new(block_import.clone()) as BoxBlockImport<_>));
+		(
+			BlockImportAdapter::new(block_import),
+			None,
+			Some(PeerData { client, link, block_import: data_block_import }),
+		)
+	}
+
+	fn make_verifier(&self, _client: PeersClient, maybe_link: &Option<PeerData>) -> Self::Verifier {
+		let data = maybe_link
+			.as_ref()
+			.expect("sassafras link always provided to verifier instantiation");
+
+		let (_, longest_chain) = TestClientBuilder::new().build_with_longest_chain();
+
+		SassafrasVerifier {
+			client: data.client.clone(),
+			select_chain: longest_chain,
+			create_inherent_data_providers: Box::new(|_, _| async {
+				let slot = InherentDataProvider::from_timestamp_and_slot_duration(
+					Timestamp::current(),
+					SlotDuration::from_millis(SLOT_DURATION_MS),
+				);
+				Ok((slot,))
+			}),
+			genesis_epoch: data.link.config.genesis_epoch.clone(),
+			epoch_changes: data.link.epoch_changes.clone(),
+			telemetry: None,
+			offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(
+				RejectAllTxPool::default(),
+			),
+		}
+	}
+
+	fn peer(&mut self, i: usize) -> &mut SassafrasPeer {
+		&mut self.peers[i]
+	}
+
+	fn peers(&self) -> &Vec<SassafrasPeer> {
+		&self.peers
+	}
+
+	fn peers_mut(&mut self) -> &mut Vec<SassafrasPeer> {
+		&mut self.peers
+	}
+
+	fn mut_peers<F: FnOnce(&mut Vec<SassafrasPeer>)>(&mut self, closure: F) {
+		closure(&mut self.peers);
+	}
+}
+
+const PEERS: [&str; 3] = ["//Alice", "//Bob", "//Charlie"];
+
+/// Run a network of validators, one per authority, until each of them has imported blocks
+/// authored by itself and by other validators beyond the first epoch change.
+async fn run_network(mutator: impl Fn(&mut TestHeader) + Send + Sync + 'static) {
+	sp_tracing::try_init_simple();
+	let mutator = Arc::new(mutator) as Mutator;
+
+	let net = SassafrasTestNet::new(PEERS.len());
+	let authorities = net.api.genesis_epoch.authorities.clone();
+
+	let net = Arc::new(Mutex::new(net));
+	let mut import_notifications = Vec::new();
+	let mut sassafras_futures = Vec::new();
+
+	for (peer_id, seed) in PEERS.iter().enumerate() {
+		let mut net = net.lock();
+		let peer = net.peer(peer_id);
+		let select_chain = peer.select_chain().expect("Full client has select_chain");
+		let (keystore, _) = create_keystore(&[*seed]);
+
+		let data = peer.data.as_ref().expect("sassafras link set up during initialization");
+		let client = data.client.clone();
+
+		let env = DummyFactory {
+			client: client.client.clone(),
+			epoch_changes: data.link.epoch_changes.clone(),
+			authorities: authorities.clone(),
+			mutator: mutator.clone(),
+		};
+
+		let mut got_own = false;
+		let mut got_other = false;
+
+		import_notifications.push(
+			// run each future until we get one of our own blocks and one of another peer
+			// within the second epoch.
+			client
+				.import_notification_stream()
+				.take_while(move |n| {
+					future::ready(
+						n.header.number() <= &(EPOCH_LENGTH as u64 + 1) || {
+							if n.origin == BlockOrigin::Own {
+								got_own = true;
+							} else {
+								got_other = true;
+							}
+							!(got_own && got_other)
+						},
+					)
+				})
+				.for_each(|_| future::ready(())),
+		);
+
+		let inner_client = client.client.clone();
+		sassafras_futures.push(
+			start_sassafras(SassafrasParams {
+				keystore,
+				client,
+				select_chain,
+				env,
+				block_import: data.block_import.lock().take().expect("import set up during init"),
+				sync_oracle: DummyOracle,
+				justification_sync_link: (),
+				create_inherent_data_providers: Box::new(move |parent, _| {
+					// Claim the slot following the parent one. Slots based on time may lead
+					// a validator to build a block on the same slot of an imported one.
+					let parent_header = inner_client.header(parent).ok().flatten().unwrap();
+					let slot = find_slot::<TestBlock>(&parent_header).unwrap() + 1;
+
+					async move { Ok((InherentDataProvider::new(slot),)) }
+				}),
+				force_authoring: false,
+				backoff_authoring_blocks: Some(BackoffAuthoringOnFinalizedHeadLagging::default()),
+				sassafras_link: data.link.clone(),
+				block_proposal_slot_portion: SlotProportion::new(0.5),
+				max_block_proposal_slot_portion: None,
+				telemetry: None,
+				offchain_tx_pool_factory: OffchainTransactionPoolFactory::new(
+					RejectAllTxPool::default(),
+				),
+			})
+			.expect("Starts sassafras"),
+		);
+	}
+
+	future::select(
+		future::poll_fn(move |cx| {
+			let mut net = net.lock();
+			net.poll(cx);
+			for p in net.peers() {
+				if let Some((h, e)) = p.failed_verifications().into_iter().next() {
+					panic!("Verification failed for {:?}: {}", h, e);
+				}
+			}
+
+			Poll::<()>::Pending
+		}),
+		future::select(
+			future::join_all(import_notifications),
+			future::join_all(sassafras_futures),
+		),
+	)
+	.await;
+}
+
+#[tokio::test]
+async fn validators_author_and_import_blocks_across_epochs() {
+	run_network(|_| ()).await;
+}
+
+#[tokio::test]
+#[should_panic(expected = "Expected epoch change to happen")]
+async fn validators_reject_missing_epoch_change() {
+	run_network(|header| {
+		header.digest_mut().logs.retain(|log| log.as_consensus().is_none());
+	})
+	.await;
+}
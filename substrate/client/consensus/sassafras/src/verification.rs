@@ -0,0 +1,575 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Types and functions related to block verification.
+
+use std::sync::Arc;
+
+use log::{debug, info, trace, warn};
+use prometheus_endpoint::Registry;
+
+use sc_client_api::backend::AuxStore;
+use sc_consensus::{
+	block_import::{BlockImport, BlockImportParams},
+	import_queue::{BasicQueue, BoxJustificationImport, DefaultImportQueue, Verifier},
+};
+use sc_consensus_epochs::{descendent_query, Epoch as EpochT, SharedEpochChanges};
+use sc_consensus_slots::{check_equivocation, CheckedHeader, InherentDataProviderExt};
+use sc_telemetry::{telemetry, TelemetryHandle, CONSENSUS_DEBUG, CONSENSUS_TRACE};
+use sc_transaction_pool_api::OffchainTransactionPoolFactory;
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_block_builder::BlockBuilder as BlockBuilderApi;
+use sp_blockchain::{HeaderBackend, HeaderMetadata, Result as ClientResult};
+use sp_consensus::{BlockOrigin, Error as ConsensusError};
+use sp_core::{
+	crypto::{VrfPublic, Wraps},
+	ed25519, Pair,
+};
+use sp_inherents::{CreateInherentDataProviders, InherentData, InherentDataProvider};
+use sp_runtime::{
+	traits::{Block as BlockT, Header},
+	DigestItem,
+};
+
+use crate::{
+	authorship::fallback_slot_author, find_slot_claim, sassafras_err, vrf, AuthorityId,
+	AuthorityPair, AuthoritySignature, Epoch, Error, SassafrasApi, SassafrasIntermediate,
+	SassafrasLink, Slot, SlotClaim, TicketBody, TicketId, INTERMEDIATE_KEY, LOG_TARGET,
+};
+
+/// Verification parameters
+pub(crate) struct VerificationParams<'a, B: 'a + BlockT> {
+	/// The header being verified.
+	pub(crate) header: B::Header,
+	/// The slot claim of the header being verified.
+	pub(crate) claim: SlotClaim,
+	/// The slot number of the current time.
+	pub(crate) slot_now: Slot,
+	/// Epoch descriptor of the epoch this block _should_ be under, if it's valid.
+	pub(crate) epoch: &'a Epoch,
+	/// The ticket assigned to the claimed slot, according to the parent block state.
+	pub(crate) maybe_ticket: Option<(TicketId, TicketBody)>,
+}
+
+/// Verified information
+pub(crate) struct VerifiedHeaderInfo {
+	/// The seal digest item removed from the header.
+	pub(crate) seal: DigestItem,
+	/// Author of the header.
+	pub(crate) author: AuthorityId,
+}
+
+/// Check a header has been signed by the right key. If the slot is too far in
+/// the future, an error will be returned. If successful, returns the pre-header
+/// and the digest item containing the seal.
+///
+/// The seal must be the last digest. Otherwise, the whole header is considered
+/// unsigned. This is required for security and must not be changed.
+///
+/// If the claimed slot is assigned to a ticket, the claim must prove the ticket
+/// ownership, otherwise the block must have been authored by the slot fallback author.
+pub(crate) fn check_header<B: BlockT + Sized>(
+	params: VerificationParams<B>,
+) -> Result<CheckedHeader<B::Header, VerifiedHeaderInfo>, Error<B>> {
+	let VerificationParams { mut header, claim, slot_now, epoch, maybe_ticket } = params;
+
+	trace!(target: LOG_TARGET, "Checking header");
+	let seal = header
+		.digest_mut()
+		.pop()
+		.ok_or_else(|| sassafras_err(Error::HeaderUnsealed(header.hash())))?;
+
+	let signature = AuthoritySignature::try_from(&seal)
+		.map_err(|_| sassafras_err(Error::HeaderBadSeal(header.hash())))?;
+
+	// The pre-hash of the header doesn't include the seal and that's what we sign
+	let pre_hash = header.hash();
+
+	if claim.slot > slot_now {
+		header.digest_mut().push(seal);
+		return Ok(CheckedHeader::Deferred(header, claim.slot))
+	}
+
+	let author = epoch
+		.authorities
+		.get(claim.authority_idx as usize)
+		.ok_or_else(|| sassafras_err(Error::SlotAuthorNotFound))?;
+
+	// Check header signature (aka the seal)
+	if !AuthorityPair::verify(&signature, &pre_hash, author) {
+		return Err(sassafras_err(Error::BadSignature(pre_hash)))
+	}
+
+	let mut epoch_index = epoch.index;
+	if epoch.end_slot() <= claim.slot {
+		// Slot doesn't strictly belong to the epoch, create a clone with fixed values.
+		epoch_index = epoch.clone_for_slot(claim.slot).index;
+	}
+
+	let mut sign_data = vrf::slot_claim_sign_data(&epoch.randomness, claim.slot, epoch_index);
+
+	match (&maybe_ticket, &claim.ticket_claim) {
+		(Some((ticket_id, ticket_body)), Some(ticket_claim)) => {
+			debug!(
+				target: LOG_TARGET,
+				"Verifying primary block #{} at slot: {}",
+				header.number(),
+				claim.slot,
+			);
+
+			let revealed_input =
+				vrf::revealed_key_input(&epoch.randomness, ticket_body.attempt_idx, epoch_index);
+			sign_data
+				.push_vrf_input(revealed_input.clone())
+				.expect("Slot claim sign data has space for one more input; qed");
+
+			// Check ticket ownership, proven by the erased key signature.
+			let challenge = sign_data.challenge::<32>();
+			if !ed25519::Pair::verify(
+				&ticket_claim.erased_signature,
+				&challenge,
+				&ticket_body.erased_public,
+			) {
+				return Err(sassafras_err(Error::TicketVerificationFailed(*ticket_id)))
+			}
+
+			// Check the revealed key is derived from the revealed VRF pre-output.
+			let Some(revealed_pre_output) = claim.vrf_signature.pre_outputs.get(1) else {
+				return Err(sassafras_err(Error::VrfVerificationFailed))
+			};
+			let revealed_seed = vrf::make_revealed_key_seed(&revealed_input, revealed_pre_output);
+			let revealed_public = ed25519::Pair::from_seed(&revealed_seed).public();
+			if revealed_public != ticket_body.revealed_public {
+				return Err(sassafras_err(Error::TicketVerificationFailed(*ticket_id)))
+			}
+		},
+		(None, None) => {
+			debug!(
+				target: LOG_TARGET,
+				"Verifying fallback block #{} at slot: {}",
+				header.number(),
+				claim.slot,
+			);
+
+			// Check the author is the slot fallback author.
+			let expected_idx =
+				fallback_slot_author(claim.slot, &epoch.authorities, &epoch.randomness)
+					.ok_or_else(|| sassafras_err(Error::SlotAuthorNotFound))?;
+			if expected_idx != claim.authority_idx {
+				let expected = epoch.authorities[expected_idx as usize].clone();
+				return Err(sassafras_err(Error::InvalidAuthor(expected, author.clone())))
+			}
+		},
+		(Some(_), None) => return Err(sassafras_err(Error::MissingTicketClaim(claim.slot))),
+		(None, Some(_)) => return Err(sassafras_err(Error::UnexpectedTicketClaim(claim.slot))),
+	}
+
+	// Check the slot claim VRF signature.
+	if !author.as_inner_ref().vrf_verify(&sign_data, &claim.vrf_signature) {
+		return Err(sassafras_err(Error::VrfVerificationFailed))
+	}
+
+	let info = VerifiedHeaderInfo { seal, author: author.clone() };
+
+	Ok(CheckedHeader::Checked(header, info))
+}
+
+/// A verifier for Sassafras blocks.
+pub struct SassafrasVerifier<Block: BlockT, Client, SelectChain, CIDP> {
+	pub(crate) client: Arc<Client>,
+	pub(crate) select_chain: SelectChain,
+	pub(crate) create_inherent_data_providers: CIDP,
+	pub(crate) genesis_epoch: sp_consensus_sassafras::Epoch,
+	pub(crate) epoch_changes: SharedEpochChanges<Block, Epoch>,
+	pub(crate) telemetry: Option<TelemetryHandle>,
+	pub(crate) offchain_tx_pool_factory: OffchainTransactionPoolFactory<Block>,
+}
+
+impl<Block, Client, SelectChain, CIDP> SassafrasVerifier<Block, Client, SelectChain, CIDP>
+where
+	Block: BlockT,
+	Client: AuxStore + HeaderBackend<Block> + HeaderMetadata<Block> + ProvideRuntimeApi<Block>,
+	Client::Api: BlockBuilderApi<Block> + SassafrasApi<Block>,
+	SelectChain: sp_consensus::SelectChain<Block>,
+	CIDP: CreateInherentDataProviders<Block, ()>,
+{
+	async fn check_inherents(
+		&self,
+		block: Block,
+		at_hash: Block::Hash,
+		inherent_data: InherentData,
+		create_inherent_data_providers: CIDP::InherentDataProviders,
+	) -> Result<(), Error<Block>> {
+		let inherent_res = self
+			.client
+			.runtime_api()
+			.check_inherents(at_hash, block, inherent_data)
+			.map_err(Error::RuntimeApi)?;
+
+		if !inherent_res.ok() {
+			for (i, e) in inherent_res.into_errors() {
+				match create_inherent_data_providers.try_handle_error(&i, &e).await {
+					Some(res) => res.map_err(Error::CheckInherents)?,
+					None => return Err(Error::CheckInherentsUnhandled(i)),
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	async fn check_and_report_equivocation(
+		&self,
+		slot_now: Slot,
+		slot: Slot,
+		header: &Block::Header,
+		author: &AuthorityId,
+		origin: &BlockOrigin,
+	) -> Result<(), Error<Block>> {
+		// Don't report any equivocations during initial sync as they are most likely stale.
+		if *origin == BlockOrigin::NetworkInitialSync {
+			return Ok(())
+		}
+
+		// Check if authorship of this header is an equivocation and return a proof if so.
+		let Some(equivocation_proof) =
+			check_equivocation(&*self.client, slot_now, slot, header, author)
+				.map_err(Error::Client)?
+		else {
+			return Ok(())
+		};
+
+		info!(
+			target: LOG_TARGET,
+			"Slot author {:?} is equivocating at slot {} with headers {:?} and {:?}",
+			author,
+			slot,
+			equivocation_proof.first_header.hash(),
+			equivocation_proof.second_header.hash(),
+		);
+
+		// Get the best block on which we will build and send the equivocation report.
+		let best_hash = self
+			.select_chain
+			.best_chain()
+			.await
+			.map(|h| h.hash())
+			.map_err(|e| Error::Client(e.into()))?;
+
+		// Generate a key ownership proof. We start by trying to generate the key ownership
+		// proof at the parent of the equivocating header, this will make sure that proof
+		// generation is successful since it happens during the on-going session (i.e. session
+		// keys are available in the state to be able to generate the proof). This might fail
+		// if the equivocation happens on the first block of the session, in which case its
+		// parent would be on the previous session. If generation on the parent header fails
+		// we try with best block as well.
+		let generate_key_owner_proof = |at_hash: Block::Hash| {
+			self.client
+				.runtime_api()
+				.generate_key_ownership_proof(at_hash, equivocation_proof.offender.clone())
+				.map_err(Error::RuntimeApi)
+		};
+
+		let parent_hash = *header.parent_hash();
+		let key_owner_proof = match generate_key_owner_proof(parent_hash)? {
+			Some(proof) => proof,
+			None => match generate_key_owner_proof(best_hash)? {
+				Some(proof) => proof,
+				None => {
+					debug!(
+						target: LOG_TARGET,
+						"Equivocation offender is not part of the authority set."
+					);
+					return Ok(())
+				},
+			},
+		};
+
+		// Submit equivocation report at best block.
+		let mut runtime_api = self.client.runtime_api();
+
+		// Register the offchain tx pool to be able to use it from the runtime.
+		runtime_api
+			.register_extension(self.offchain_tx_pool_factory.offchain_transaction_pool(best_hash));
+
+		runtime_api
+			.submit_report_equivocation_unsigned_extrinsic(
+				best_hash,
+				equivocation_proof,
+				key_owner_proof,
+			)
+			.map_err(Error::RuntimeApi)?;
+
+		info!(target: LOG_TARGET, "Submitted equivocation report for author {:?}", author);
+
+		Ok(())
+	}
+}
+
+#[async_trait::async_trait]
+impl<Block, Client, SelectChain, CIDP> Verifier<Block>
+	for SassafrasVerifier<Block, Client, SelectChain, CIDP>
+where
+	Block: BlockT,
+	Client: HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ HeaderBackend<Block>
+		+ ProvideRuntimeApi<Block>
+		+ Send
+		+ Sync
+		+ AuxStore,
+	Client::Api: BlockBuilderApi<Block> + SassafrasApi<Block>,
+	SelectChain: sp_consensus::SelectChain<Block>,
+	CIDP: CreateInherentDataProviders<Block, ()> + Send + Sync,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send + Sync,
+{
+	async fn verify(
+		&self,
+		mut block: BlockImportParams<Block>,
+	) -> Result<BlockImportParams<Block>, String> {
+		trace!(
+			target: LOG_TARGET,
+			"Verifying origin: {:?} header: {:?} justification(s): {:?} body: {:?}",
+			block.origin,
+			block.header,
+			block.justifications,
+			block.body,
+		);
+
+		let hash = block.header.hash();
+		let parent_hash = *block.header.parent_hash();
+
+		let info = self.client.info();
+		let number = *block.header.number();
+
+		if info.block_gap.map_or(false, |gap| gap.start <= number && number <= gap.end) ||
+			block.with_state()
+		{
+			// Verification for imported blocks is skipped in two cases:
+			// 1. When importing blocks below the last finalized block during network initial
+			//    synchronization.
+			// 2. When importing whole state we don't calculate epoch descriptor, but rather read it
+			//    from the state after import. We also skip all verifications because there's no
+			//    parent state and we trust the sync module to verify that the state is correct and
+			//    finalized.
+			return Ok(block)
+		}
+
+		let create_inherent_data_providers = self
+			.create_inherent_data_providers
+			.create_inherent_data_providers(parent_hash, ())
+			.await
+			.map_err(|e| Error::<Block>::Client(ConsensusError::from(e).into()))?;
+
+		let slot_now = create_inherent_data_providers.slot();
+
+		let parent_header_metadata = self
+			.client
+			.header_metadata(parent_hash)
+			.map_err(Error::<Block>::FetchParentHeader)?;
+
+		let claim = find_slot_claim::<Block>(&block.header)?;
+
+		// The ticket assigned to the slot is read from the parent state, the same
+		// state used by the author to claim the slot.
+		let maybe_ticket = self
+			.client
+			.runtime_api()
+			.slot_ticket(parent_hash, claim.slot)
+			.map_err(Error::<Block>::RuntimeApi)?;
+
+		let (check_header, epoch_descriptor) = {
+			let epoch_changes = self.epoch_changes.shared_data();
+			let epoch_descriptor = epoch_changes
+				.epoch_descriptor_for_child_of(
+					descendent_query(&*self.client),
+					&parent_hash,
+					parent_header_metadata.number,
+					claim.slot,
+				)
+				.map_err(|e| Error::<Block>::ForkTree(Box::new(e)))?
+				.ok_or(Error::<Block>::FetchEpoch(parent_hash))?;
+			let viable_epoch = epoch_changes
+				.viable_epoch(&epoch_descriptor, |slot| Epoch::genesis(&self.genesis_epoch, slot))
+				.ok_or(Error::<Block>::FetchEpoch(parent_hash))?;
+
+			// We add one to the current slot to allow for some small drift.
+			let v_params = VerificationParams {
+				header: block.header.clone(),
+				claim: claim.clone(),
+				slot_now: slot_now + 1,
+				epoch: viable_epoch.as_ref(),
+				maybe_ticket,
+			};
+
+			(check_header::<Block>(v_params)?, epoch_descriptor)
+		};
+
+		match check_header {
+			CheckedHeader::Checked(pre_header, verified_info) => {
+				// The header is valid but let's check if there was something else already
+				// proposed at the same slot by the given author. If there was, we will
+				// report the equivocation to the runtime.
+				if let Err(err) = self
+					.check_and_report_equivocation(
+						slot_now,
+						claim.slot,
+						&block.header,
+						&verified_info.author,
+						&block.origin,
+					)
+					.await
+				{
+					warn!(
+						target: LOG_TARGET,
+						"Error checking/reporting Sassafras equivocation: {}", err
+					);
+				}
+
+				if let Some(inner_body) = block.body {
+					let new_block = Block::new(pre_header.clone(), inner_body);
+					if !block.state_action.skip_execution_checks() {
+						// If the body is passed through and the block was executed,
+						// we need to use the runtime to check that the internally-set
+						// timestamp in the inherents actually matches the claimed slot.
+						let inherent_data = create_inherent_data_providers
+							.create_inherent_data()
+							.await
+							.map_err(Error::<Block>::CreateInherents)?;
+
+						self.check_inherents(
+							new_block.clone(),
+							parent_hash,
+							inherent_data,
+							create_inherent_data_providers,
+						)
+						.await?;
+					}
+
+					let (_, inner_body) = new_block.deconstruct();
+					block.body = Some(inner_body);
+				}
+
+				trace!(target: LOG_TARGET, "Checked {:?}; importing.", pre_header);
+				telemetry!(
+					self.telemetry;
+					CONSENSUS_TRACE;
+					"sassafras.checked_and_importing";
+					"pre_header" => ?pre_header,
+				);
+
+				block.header = pre_header;
+				block.post_digests.push(verified_info.seal);
+				block.insert_intermediate(
+					INTERMEDIATE_KEY,
+					SassafrasIntermediate::<Block> { epoch_descriptor },
+				);
+				block.post_hash = Some(hash);
+
+				Ok(block)
+			},
+			CheckedHeader::Deferred(a, b) => {
+				debug!(target: LOG_TARGET, "Checking {:?} failed; {:?}, {:?}.", hash, a, b);
+				telemetry!(
+					self.telemetry;
+					CONSENSUS_DEBUG;
+					"sassafras.header_too_far_in_future";
+					"hash" => ?hash, "a" => ?a, "b" => ?b
+				);
+				Err(Error::<Block>::TooFarInFuture(hash).into())
+			},
+		}
+	}
+}
+
+/// Parameters passed to [`import_queue`].
+pub struct ImportQueueParams<'a, Block: BlockT, BI, Client, CIDP, SelectChain, Spawn> {
+	/// The Sassafras link that is created by [`block_import`](crate::block_import).
+	pub link: SassafrasLink<Block>,
+	/// The block import that should be wrapped.
+	pub block_import: BI,
+	/// Optional justification import.
+	pub justification_import: Option<BoxJustificationImport<Block>>,
+	/// The client to interact with the internals of the node.
+	pub client: Arc<Client>,
+	/// A [`SelectChain`](sp_consensus::SelectChain) implementation.
+	///
+	/// Used to determine the best block that should be used as basis when sending an equivocation
+	/// report.
+	pub select_chain: SelectChain,
+	/// Used to crate the inherent data providers.
+	///
+	/// These inherent data providers are then used to create the inherent data that is
+	/// passed to the `check_inherents` runtime call.
+	pub create_inherent_data_providers: CIDP,
+	/// Spawner for spawning futures.
+	pub spawner: &'a Spawn,
+	/// Registry for prometheus metrics.
+	pub registry: Option<&'a Registry>,
+	/// Optional telemetry handle to report telemetry events.
+	pub telemetry: Option<TelemetryHandle>,
+	/// The offchain transaction pool factory.
+	///
+	/// Will be used when sending equivocation reports.
+	pub offchain_tx_pool_factory: OffchainTransactionPoolFactory<Block>,
+}
+
+/// Start an import queue for the Sassafras consensus algorithm.
+///
+/// The block import object provided must be the `SassafrasBlockImport` or a wrapper
+/// of it, otherwise crucial import logic will be omitted.
+pub fn import_queue<Block: BlockT, Client, SelectChain, BI, CIDP, Spawn>(
+	ImportQueueParams {
+		link,
+		block_import,
+		justification_import,
+		client,
+		select_chain,
+		create_inherent_data_providers,
+		spawner,
+		registry,
+		telemetry,
+		offchain_tx_pool_factory,
+	}: ImportQueueParams<'_, Block, BI, Client, CIDP, SelectChain, Spawn>,
+) -> ClientResult<DefaultImportQueue<Block>>
+where
+	BI: BlockImport<Block, Error = ConsensusError> + Send + Sync + 'static,
+	Client: ProvideRuntimeApi<Block>
+		+ HeaderBackend<Block>
+		+ HeaderMetadata<Block, Error = sp_blockchain::Error>
+		+ AuxStore
+		+ Send
+		+ Sync
+		+ 'static,
+	Client::Api: BlockBuilderApi<Block> + SassafrasApi<Block> + ApiExt<Block>,
+	SelectChain: sp_consensus::SelectChain<Block> + 'static,
+	CIDP: CreateInherentDataProviders<Block, ()> + Send + Sync + 'static,
+	CIDP::InherentDataProviders: InherentDataProviderExt + Send + Sync,
+	Spawn: sp_core::traits::SpawnEssentialNamed,
+{
+	let verifier = SassafrasVerifier {
+		select_chain,
+		create_inherent_data_providers,
+		genesis_epoch: link.config.genesis_epoch,
+		epoch_changes: link.epoch_changes,
+		telemetry,
+		client,
+		offchain_tx_pool_factory,
+	};
+
+	Ok(BasicQueue::new(verifier, Box::new(block_import), justification_import, spawner, registry))
+}
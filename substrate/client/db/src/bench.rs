@@ -34,7 +34,9 @@ use sp_state_machine::{
 };
 use sp_trie::{
 	cache::{CacheSize, SharedTrieCache},
-	prefixed_key, MemoryDB, MerkleValue,
+	prefixed_key,
+	recorder::RecordedSizes,
+	MemoryDB, MerkleValue,
 };
 use std::{
 	cell::{Cell, RefCell},
@@ -71,6 +73,16 @@ struct KeyTracker {
 	child_keys: LinkedHashMap<Vec<u8>, LinkedHashMap<Vec<u8>, TrackedStorageKey>>,
 }
 
+/// The sizes per key recorded at the start and at the end of a benchmark repetition.
+///
+/// Each snapshot holds the root of the main trie at that point along with the recorded sizes.
+pub struct RecordedRepetition<H> {
+	/// The snapshot taken at the start of the repetition.
+	pub start: (H, RecordedSizes<H>),
+	/// The snapshot taken at the end of the repetition.
+	pub end: (H, RecordedSizes<H>),
+}
+
 /// State that manages the backend database reference. Allows runtime to control the database.
 pub struct BenchmarkingState<Hasher: Hash> {
 	root: Cell<Hasher::Output>,
@@ -83,6 +95,14 @@ pub struct BenchmarkingState<Hasher: Hash> {
 	whitelist: RefCell<Vec<TrackedStorageKey>>,
 	proof_recorder: Option<sp_trie::recorder::Recorder<Hasher>>,
 	proof_recorder_root: Cell<Hasher::Output>,
+	/// The recorded sizes at the start of the current benchmark repetition.
+	repetition_start: RefCell<Option<(Hasher::Output, RecordedSizes<Hasher::Output>)>>,
+	/// The recorded sizes of all completed benchmark repetitions.
+	recorded_sizes: RefCell<Vec<RecordedRepetition<Hasher::Output>>>,
+	/// Whether the runtime marked any repetition since the last [`Self::take_recorded_sizes`].
+	repetitions_marked: Cell<bool>,
+	/// The recorded sizes at every proof size query, for runtimes that don't mark repetitions.
+	proof_size_queries: RefCell<Vec<(Hasher::Output, RecordedSizes<Hasher::Output>)>>,
 	shared_trie_cache: SharedTrieCache<Hasher>,
 }
 
@@ -153,6 +173,10 @@ impl<Hasher: Hash> BenchmarkingState<Hasher> {
 			whitelist: Default::default(),
 			proof_recorder: record_proof.then(Default::default),
 			proof_recorder_root: Cell::new(root),
+			repetition_start: Default::default(),
+			recorded_sizes: Default::default(),
+			repetitions_marked: Cell::new(false),
+			proof_size_queries: Default::default(),
 			// Enable the cache, but do not sync anything to the shared state.
 			shared_trie_cache: SharedTrieCache::new(CacheSize::new(0)),
 		};
@@ -184,6 +208,29 @@ impl<Hasher: Hash> BenchmarkingState<Hasher> {
 		self.proof_recorder.clone()
 	}
 
+	/// Take the sizes per key of all benchmark repetitions completed since the last call.
+	///
+	/// Empty unless size attribution is enabled on the [`Self::recorder`]. A repetition that was
+	/// started but not completed is discarded.
+	///
+	/// Runtimes built against an older `frame-benchmarking` don't mark their repetitions. If no
+	/// repetition was marked, every two consecutive proof size queries are taken as the start and
+	/// the end of a repetition instead, as that is where such runtimes query the proof size.
+	pub fn take_recorded_sizes(&self) -> Vec<RecordedRepetition<Hasher::Output>> {
+		self.repetition_start.take();
+		let queries = self.proof_size_queries.take();
+		if self.repetitions_marked.replace(false) {
+			return self.recorded_sizes.take()
+		}
+
+		let mut queries = queries.into_iter();
+		let mut repetitions = Vec::new();
+		while let (Some(start), Some(end)) = (queries.next(), queries.next()) {
+			repetitions.push(RecordedRepetition { start, end });
+		}
+		repetitions
+	}
+
 	fn reopen(&self) -> Result<(), String> {
 		*self.state.borrow_mut() = None;
 		let db = match self.db.take() {
@@ -626,6 +673,12 @@ impl<Hasher: Hash> StateBackend<Hasher> for BenchmarkingState<Hasher> {
 
 	fn proof_size(&self) -> Option<u32> {
 		self.proof_recorder.as_ref().map(|recorder| {
+			if let Some(sizes) = recorder.recorded_sizes() {
+				self.proof_size_queries
+					.borrow_mut()
+					.push((self.proof_recorder_root.get(), sizes));
+			}
+
 			let proof_size = recorder.estimate_encoded_size() as u32;
 
 			let proof = recorder.to_storage_proof();
@@ -653,6 +706,22 @@ impl<Hasher: Hash> StateBackend<Hasher> for BenchmarkingState<Hasher> {
 			}
 		})
 	}
+
+	fn mark_repetition(&self, start: bool) {
+		self.repetitions_marked.set(true);
+		let Some(sizes) = self.proof_recorder.as_ref().and_then(|r| r.recorded_sizes()) else {
+			return
+		};
+		let snapshot = (self.proof_recorder_root.get(), sizes);
+
+		if start {
+			*self.repetition_start.borrow_mut() = Some(snapshot);
+		} else if let Some(start) = self.repetition_start.take() {
+			self.recorded_sizes
+				.borrow_mut()
+				.push(RecordedRepetition { start, end: snapshot });
+		}
+	}
 }
 
 impl<Hasher: Hash> std::fmt::Debug for BenchmarkingState<Hasher> {
@@ -691,6 +760,71 @@ mod test {
 		assert_eq!(bench_state.read_write_count(), (1, 0, 0, 0));
 	}
 
+	#[test]
+	fn recorded_sizes_are_captured_per_repetition() {
+		let key = hex("ce6e1397e668c7fcf47744350dc59688455a2c2dbd2e2a649df4e55d93cd7158");
+		let storage = sp_runtime::Storage {
+			top: vec![(key.clone(), hex("0102030405060708"))].into_iter().collect(),
+			..sp_runtime::Storage::default()
+		};
+		let bench_state =
+			BenchmarkingState::<HashingFor<crate::tests::Block>>::new(storage, None, true, true)
+				.unwrap();
+
+		bench_state.mark_repetition(true);
+		bench_state.mark_repetition(false);
+		assert!(bench_state.take_recorded_sizes().is_empty());
+
+		bench_state.recorder().unwrap().enable_size_attribution();
+		bench_state.mark_repetition(true);
+		// Proof size queries in between are not taken as markers.
+		bench_state.proof_size();
+		bench_state.storage(&key).unwrap();
+		bench_state.proof_size();
+		bench_state.mark_repetition(false);
+		// An end without a start is ignored.
+		bench_state.mark_repetition(false);
+
+		let recorded = bench_state.take_recorded_sizes();
+		assert_eq!(recorded.len(), 1);
+		let RecordedRepetition { start: (_, start), end: (root, end) } = &recorded[0];
+		assert!(start.per_key.is_empty());
+		assert!(end.per_key[root][&key[..]] > 0);
+
+		// A repetition that is not completed is discarded.
+		bench_state.mark_repetition(true);
+		assert!(bench_state.take_recorded_sizes().is_empty());
+		bench_state.mark_repetition(false);
+		assert!(bench_state.take_recorded_sizes().is_empty());
+	}
+
+	#[test]
+	fn recorded_sizes_fall_back_to_proof_size_queries() {
+		let key = hex("ce6e1397e668c7fcf47744350dc59688455a2c2dbd2e2a649df4e55d93cd7158");
+		let storage = sp_runtime::Storage {
+			top: vec![(key.clone(), hex("0102030405060708"))].into_iter().collect(),
+			..sp_runtime::Storage::default()
+		};
+		let bench_state =
+			BenchmarkingState::<HashingFor<crate::tests::Block>>::new(storage, None, true, true)
+				.unwrap();
+		bench_state.recorder().unwrap().enable_size_attribution();
+
+		// A runtime that doesn't mark its repetitions only queries the proof size around them.
+		bench_state.proof_size();
+		bench_state.storage(&key).unwrap();
+		bench_state.proof_size();
+		// A start without an end is discarded.
+		bench_state.proof_size();
+
+		let recorded = bench_state.take_recorded_sizes();
+		assert_eq!(recorded.len(), 1);
+		let RecordedRepetition { start: (_, start), end: (root, end) } = &recorded[0];
+		assert!(start.per_key.is_empty());
+		assert!(end.per_key[root][&key[..]] > 0);
+		assert!(bench_state.take_recorded_sizes().is_empty());
+	}
+
 	#[test]
 	fn read_to_main_and_child_tries() {
 		let bench_state = BenchmarkingState::<HashingFor<crate::tests::Block>>::new(
@@ -18,7 +18,9 @@
 
 use std::{collections::HashSet, str::FromStr, sync::Arc, thread::sleep};
 
-use crate::NetworkProvider;
+use crate::{
+	limits::is_uri_allowed, metrics::MetricsLink, NetworkProvider, OffchainWorkerLimits, LOG_TARGET,
+};
 use codec::{Decode, Encode};
 use futures::Future;
 pub use http::SharedClient;
@@ -47,6 +49,32 @@ pub(crate) struct Api {
 	is_validator: bool,
 	/// Everything HTTP-related is handled by a different struct.
 	http: http::HttpApi,
+	/// Limits set by the node operator.
+	limits: Arc<OffchainWorkerLimits>,
+	/// Number of HTTP requests started so far.
+	http_requests: u32,
+	/// End of the time budget, if any.
+	budget_deadline: Option<Timestamp>,
+	/// Offchain workers metrics.
+	metrics: MetricsLink,
+}
+
+impl Api {
+	/// Restricts the given deadline to the end of the time budget.
+	fn clamp_deadline(&self, deadline: Option<Timestamp>) -> Option<Timestamp> {
+		match (deadline, self.budget_deadline) {
+			(Some(deadline), Some(budget)) => Some(deadline.min(budget)),
+			(deadline, budget) => deadline.or(budget),
+		}
+	}
+
+	/// Refuses an HTTP request for the given `reason`.
+	fn refuse_http_request(&self, uri: &str, reason: &'static str) -> Result<HttpRequestId, ()> {
+		tracing::warn!(target: LOG_TARGET, %uri, reason, "HTTP request refused");
+		self.metrics
+			.report(|metrics| metrics.http_requests_refused.with_label_values(&[reason]).inc());
+		Err(())
+	}
 }
 
 impl offchain::Externalities for Api {
@@ -66,6 +94,7 @@ impl offchain::Externalities for Api {
 	}
 
 	fn sleep_until(&mut self, deadline: Timestamp) {
+		let deadline = self.clamp_deadline(Some(deadline)).unwrap_or(deadline);
 		sleep(timestamp::timestamp_from_now(deadline));
 	}
 
@@ -79,7 +108,21 @@ impl offchain::Externalities for Api {
 		uri: &str,
 		_meta: &[u8],
 	) -> Result<HttpRequestId, ()> {
-		self.http.request_start(method, uri)
+		if !is_uri_allowed(&self.limits, uri) {
+			return self.refuse_http_request(uri, "host_not_allowed")
+		}
+
+		if self.limits.max_http_requests.map_or(false, |max| self.http_requests >= max) {
+			return self.refuse_http_request(uri, "requests_budget")
+		}
+
+		if self.budget_deadline.map_or(false, |deadline| timestamp::now() >= deadline) {
+			return self.refuse_http_request(uri, "time_budget")
+		}
+
+		let id = self.http.request_start(method, uri)?;
+		self.http_requests += 1;
+		Ok(id)
 	}
 
 	fn http_request_add_header(
@@ -97,6 +140,7 @@ impl offchain::Externalities for Api {
 		chunk: &[u8],
 		deadline: Option<Timestamp>,
 	) -> Result<(), HttpError> {
+		let deadline = self.clamp_deadline(deadline);
 		self.http.request_write_body(request_id, chunk, deadline)
 	}
 
@@ -105,6 +149,7 @@ impl offchain::Externalities for Api {
 		ids: &[HttpRequestId],
 		deadline: Option<Timestamp>,
 	) -> Vec<HttpRequestStatus> {
+		let deadline = self.clamp_deadline(deadline);
 		self.http.response_wait(ids, deadline)
 	}
 
@@ -118,6 +163,7 @@ impl offchain::Externalities for Api {
 		buffer: &mut [u8],
 		deadline: Option<Timestamp>,
 	) -> Result<usize, HttpError> {
+		let deadline = self.clamp_deadline(deadline);
 		self.http.response_read_body(request_id, buffer, deadline)
 	}
 
@@ -201,10 +247,24 @@ impl AsyncApi {
 		network_provider: Arc<dyn NetworkProvider + Send + Sync>,
 		is_validator: bool,
 		shared_http_client: SharedClient,
+		limits: Arc<OffchainWorkerLimits>,
+		metrics: MetricsLink,
 	) -> (Api, Self) {
 		let (http_api, http_worker) = http::http(shared_http_client);
 
-		let api = Api { network_provider, is_validator, http: http_api };
+		let budget_deadline = limits.time_budget.map(|budget| {
+			timestamp::now().add(offchain::Duration::from_millis(budget.as_millis() as u64))
+		});
+
+		let api = Api {
+			network_provider,
+			is_validator,
+			http: http_api,
+			limits,
+			http_requests: 0,
+			budget_deadline,
+			metrics,
+		};
 
 		let async_api = Self { http: Some(http_worker) };
 
@@ -328,7 +388,15 @@ mod tests {
 		let mock = Arc::new(TestNetwork());
 		let shared_client = SharedClient::new().unwrap();
 
-		AsyncApi::new(mock, false, shared_client)
+		AsyncApi::new(mock, false, shared_client, Default::default(), Default::default())
+	}
+
+	fn limited_offchain_api(limits: OffchainWorkerLimits) -> Api {
+		sp_tracing::try_init_simple();
+		let mock = Arc::new(TestNetwork());
+		let shared_client = SharedClient::new().unwrap();
+
+		AsyncApi::new(mock, false, shared_client, Arc::new(limits), Default::default()).0
 	}
 
 	fn offchain_db() -> OffchainDb<LocalStorage> {
@@ -448,4 +516,43 @@ mod tests {
 		// then
 		assert_ne!(seed, [0; 32]);
 	}
+
+	#[test]
+	fn should_refuse_requests_to_hosts_not_allowed() {
+		let mut api = limited_offchain_api(OffchainWorkerLimits {
+			http_allowed_hosts: Some(vec!["localhost".into()]),
+			..Default::default()
+		});
+
+		assert!(api.http_request_start("GET", "http://localhost:1234", &[]).is_ok());
+		assert!(api.http_request_start("GET", "http://example.com", &[]).is_err());
+	}
+
+	#[test]
+	fn should_refuse_requests_over_budget() {
+		let mut api = limited_offchain_api(OffchainWorkerLimits {
+			max_http_requests: Some(2),
+			..Default::default()
+		});
+
+		assert!(api.http_request_start("GET", "http://localhost:1234", &[]).is_ok());
+		assert!(api.http_request_start("GET", "http://localhost:1234", &[]).is_ok());
+		assert!(api.http_request_start("GET", "http://localhost:1234", &[]).is_err());
+	}
+
+	#[test]
+	fn should_respect_time_budget() {
+		let mut api = limited_offchain_api(OffchainWorkerLimits {
+			time_budget: Some(std::time::Duration::from_millis(50)),
+			..Default::default()
+		});
+
+		// Sleeping is cut to the end of the budget.
+		let now = api.timestamp();
+		api.sleep_until(now.add(sp_core::offchain::Duration::from_millis(10_000)));
+		assert!(api.timestamp().unix_millis() - now.unix_millis() < 5_000);
+
+		// The budget is exhausted, no more requests can be started.
+		assert!(api.http_request_start("GET", "http://localhost:1234", &[]).is_err());
+	}
 }
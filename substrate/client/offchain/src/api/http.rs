@@ -1130,7 +1130,13 @@ mod tests {
 
 		{
 			let mock = Arc::new(TestNetwork());
-			let (mut api, async_api) = AsyncApi::new(mock, false, shared_client.clone());
+			let (mut api, async_api) = AsyncApi::new(
+				mock,
+				false,
+				shared_client.clone(),
+				Default::default(),
+				Default::default(),
+			);
 			api.timestamp();
 
 			futures::executor::block_on(async move {
@@ -1145,7 +1151,13 @@ mod tests {
 
 		{
 			let mock = Arc::new(TestNetwork());
-			let (mut api, async_api) = AsyncApi::new(mock, false, shared_client.clone());
+			let (mut api, async_api) = AsyncApi::new(
+				mock,
+				false,
+				shared_client.clone(),
+				Default::default(),
+				Default::default(),
+			);
 			let id = api.http_request_start("lol", "nope", &[]).unwrap();
 			api.http_request_write_body(id, &[], None).unwrap();
 			futures::executor::block_on(async move {
@@ -35,13 +35,14 @@
 
 #![warn(missing_docs)]
 
-use std::{fmt, sync::Arc};
+use std::{fmt, sync::Arc, time::Instant};
 
 use futures::{
 	future::{ready, Future},
 	prelude::*,
 };
 use parking_lot::Mutex;
+use prometheus_endpoint::Registry;
 use sc_client_api::BlockchainEvents;
 use sc_network::{NetworkPeers, NetworkStateInfo};
 use sc_transaction_pool_api::OffchainTransactionPoolFactory;
@@ -53,7 +54,10 @@ use sp_runtime::traits::{self, Header};
 use threadpool::ThreadPool;
 
 mod api;
+mod limits;
+mod metrics;
 
+pub use sc_client_api::offchain::OffchainWorkerLimits;
 pub use sp_core::offchain::storage::OffchainDb;
 pub use sp_offchain::{OffchainWorkerApi, STORAGE_PREFIX};
 
@@ -110,6 +114,10 @@ pub struct OffchainWorkerOptions<RA, Block: traits::Block, Storage, CE> {
 	///
 	/// If not enabled, any http request will panic.
 	pub enable_http_requests: bool,
+	/// Resource limits applied to every offchain worker run.
+	pub limits: OffchainWorkerLimits,
+	/// Prometheus registry used to report offchain worker metrics.
+	pub prometheus_registry: Option<Registry>,
 	/// Callback to create custom [`Extension`]s that should be registered for the
 	/// `offchain_worker` runtime call.
 	///
@@ -137,6 +145,8 @@ pub struct OffchainWorkers<RA, Block: traits::Block, Storage> {
 	transaction_pool: Option<OffchainTransactionPoolFactory<Block>>,
 	network_provider: Arc<dyn NetworkProvider + Send + Sync>,
 	is_validator: bool,
+	limits: Arc<OffchainWorkerLimits>,
+	metrics: metrics::MetricsLink,
 	custom_extensions: Box<dyn Fn(Block::Hash) -> Vec<Box<dyn Extension>> + Send>,
 }
 
@@ -151,6 +161,8 @@ impl<RA, Block: traits::Block, Storage> OffchainWorkers<RA, Block, Storage> {
 			network_provider,
 			is_validator,
 			enable_http_requests,
+			limits,
+			prometheus_registry,
 			custom_extensions,
 		}: OffchainWorkerOptions<RA, Block, Storage, CE>,
 	) -> std::io::Result<Self> {
@@ -167,6 +179,8 @@ impl<RA, Block: traits::Block, Storage> OffchainWorkers<RA, Block, Storage> {
 			transaction_pool,
 			is_validator,
 			network_provider,
+			limits: Arc::new(limits),
+			metrics: metrics::MetricsLink::new(prometheus_registry.as_ref()),
 			custom_extensions: Box::new(custom_extensions),
 		})
 	}
@@ -247,6 +261,8 @@ where
 				self.network_provider.clone(),
 				self.is_validator,
 				self.shared_http_client.clone(),
+				self.limits.clone(),
+				self.metrics.clone(),
 			);
 			tracing::debug!(target: LOG_TARGET, "Spawning offchain workers at {hash:?}");
 			let header = header.clone();
@@ -259,6 +275,8 @@ where
 			let db = self.offchain_db.clone();
 			let tx_pool = self.transaction_pool.clone();
 			let custom_extensions = (*self.custom_extensions)(hash);
+			let local_storage_quota = self.limits.local_storage_quota;
+			let metrics = self.metrics.clone();
 
 			self.spawn_worker(move || {
 				let mut runtime = client.runtime_api();
@@ -274,8 +292,10 @@ where
 				}
 
 				if let Some(offchain_db) = db {
+					let offchain_db =
+						limits::QuotaDb::new(offchain_db, local_storage_quota, metrics.clone());
 					runtime.register_extension(offchain::OffchainDbExt::new(
-						offchain::LimitedExternalities::new(capabilities, offchain_db.clone()),
+						offchain::LimitedExternalities::new(capabilities, offchain_db),
 					));
 				}
 
@@ -285,12 +305,32 @@ where
 
 				custom_extensions.into_iter().for_each(|ext| runtime.register_extension(ext));
 
-				let run = if version == 2 {
-					runtime.offchain_worker(hash, &header)
+				let started = Instant::now();
+				let execute = || {
+					if version == 2 {
+						runtime.offchain_worker(hash, &header)
+					} else {
+						#[allow(deprecated)]
+						runtime.offchain_worker_before_version_2(hash, *header.number())
+					}
+				};
+				let run = if metrics.is_enabled() {
+					let (run, timings) = metrics::with_module_timings(execute);
+					metrics.report(|metrics| {
+						for (module, elapsed) in timings {
+							metrics
+								.module_execution_time
+								.with_label_values(&[&module])
+								.observe(elapsed.as_secs_f64());
+						}
+					});
+					run
 				} else {
-					#[allow(deprecated)]
-					runtime.offchain_worker_before_version_2(hash, *header.number())
+					execute()
 				};
+				metrics.report(|metrics| {
+					metrics.execution_time.observe(started.elapsed().as_secs_f64())
+				});
 
 				if let Err(e) = run {
 					tracing::error!(
@@ -465,6 +505,8 @@ mod tests {
 			network_provider: network,
 			is_validator: false,
 			enable_http_requests: false,
+			limits: Default::default(),
+			prometheus_registry: None,
 			custom_extensions: |_| Vec::new(),
 		})
 		.unwrap();
@@ -0,0 +1,197 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Node-side resource limits applied to the offchain workers.
+//!
+//! Offchain workers run arbitrary runtime code on the node. The limits enforced here allow
+//! node operators to restrict the hosts contacted by the offchain workers and to bound the
+//! resources consumed by the offchain workers of every block.
+
+use std::str::FromStr;
+
+use sp_core::offchain::{DbExternalities, StorageKind};
+
+use crate::{metrics::MetricsLink, OffchainWorkerLimits, LOG_TARGET};
+
+/// Returns `true` if the host of the given `uri` can be contacted under the given `limits`.
+///
+/// Invalid URIs and URIs without a host are never allowed when an allow-list is set.
+pub(crate) fn is_uri_allowed(limits: &OffchainWorkerLimits, uri: &str) -> bool {
+	let Some(allowed_hosts) = &limits.http_allowed_hosts else { return true };
+
+	let Some(host) = hyper::Uri::from_str(uri)
+		.ok()
+		.and_then(|uri| uri.host().map(|host| host.trim_end_matches('.').to_ascii_lowercase()))
+	else {
+		return false
+	};
+
+	allowed_hosts.iter().any(|pattern| host_matches(pattern, &host))
+}
+
+/// Matches a lowercase `host` against an allow-list entry.
+fn host_matches(pattern: &str, host: &str) -> bool {
+	let pattern = pattern.trim_end_matches('.').to_ascii_lowercase();
+	match pattern.strip_prefix("*.") {
+		Some(domain) => host
+			.strip_suffix(domain)
+			.map_or(false, |subdomain| subdomain.len() > 1 && subdomain.ends_with('.')),
+		None => host == pattern,
+	}
+}
+
+/// Local storage wrapper enforcing the local storage quota.
+///
+/// Every instance accounts for the writes of the offchain workers run for a single block.
+pub(crate) struct QuotaDb<Db> {
+	inner: Db,
+	remaining: Option<u64>,
+	metrics: MetricsLink,
+}
+
+impl<Db> QuotaDb<Db> {
+	/// Wraps the given local storage, allowing at most `quota` bytes to be written.
+	pub(crate) fn new(inner: Db, quota: Option<u64>, metrics: MetricsLink) -> Self {
+		Self { inner, remaining: quota, metrics }
+	}
+
+	/// Charges the write of the given key and value to the quota.
+	///
+	/// Returns `false` if the write exceeds the remaining quota.
+	fn charge(&mut self, key: &[u8], value: &[u8]) -> bool {
+		let Some(remaining) = self.remaining.as_mut() else { return true };
+
+		let size = (key.len() + value.len()) as u64;
+		if size > *remaining {
+			tracing::warn!(
+				target: LOG_TARGET,
+				size,
+				remaining = *remaining,
+				"Local storage quota exceeded, refusing write",
+			);
+			self.metrics.report(|metrics| metrics.local_storage_writes_refused.inc());
+			return false
+		}
+
+		*remaining -= size;
+		true
+	}
+}
+
+impl<Db: DbExternalities> DbExternalities for QuotaDb<Db> {
+	fn local_storage_set(&mut self, kind: StorageKind, key: &[u8], value: &[u8]) {
+		if self.charge(key, value) {
+			self.inner.local_storage_set(kind, key, value)
+		}
+	}
+
+	fn local_storage_clear(&mut self, kind: StorageKind, key: &[u8]) {
+		self.inner.local_storage_clear(kind, key)
+	}
+
+	fn local_storage_compare_and_set(
+		&mut self,
+		kind: StorageKind,
+		key: &[u8],
+		old_value: Option<&[u8]>,
+		new_value: &[u8],
+	) -> bool {
+		if self.local_storage_get(kind, key).as_deref() != old_value {
+			return false
+		}
+
+		self.charge(key, new_value) &&
+			self.inner.local_storage_compare_and_set(kind, key, old_value, new_value)
+	}
+
+	fn local_storage_get(&mut self, kind: StorageKind, key: &[u8]) -> Option<Vec<u8>> {
+		self.inner.local_storage_get(kind, key)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sc_client_db::offchain::LocalStorage;
+	use sp_core::offchain::storage::OffchainDb;
+
+	fn limits(hosts: &[&str]) -> OffchainWorkerLimits {
+		OffchainWorkerLimits {
+			http_allowed_hosts: Some(hosts.iter().map(|h| h.to_string()).collect()),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn any_host_allowed_without_allow_list() {
+		let limits = OffchainWorkerLimits::default();
+		assert!(is_uri_allowed(&limits, "https://example.com/path"));
+		assert!(is_uri_allowed(&limits, "not a uri"));
+	}
+
+	#[test]
+	fn exact_hosts_are_matched() {
+		let limits = limits(&["example.com", "Price.Feed.io"]);
+
+		assert!(is_uri_allowed(&limits, "https://example.com/api?x=1"));
+		assert!(is_uri_allowed(&limits, "http://EXAMPLE.com:8080"));
+		assert!(is_uri_allowed(&limits, "https://price.feed.io"));
+		assert!(!is_uri_allowed(&limits, "https://api.example.com"));
+		assert!(!is_uri_allowed(&limits, "https://example.com.evil.io"));
+		assert!(!is_uri_allowed(&limits, "https://notexample.com"));
+		assert!(!is_uri_allowed(&limits, "/relative/path"));
+	}
+
+	#[test]
+	fn wildcard_hosts_match_subdomains_only() {
+		let limits = limits(&["*.example.com"]);
+
+		assert!(is_uri_allowed(&limits, "https://api.example.com"));
+		assert!(is_uri_allowed(&limits, "https://a.b.example.com"));
+		assert!(!is_uri_allowed(&limits, "https://example.com"));
+		assert!(!is_uri_allowed(&limits, "https://badexample.com"));
+	}
+
+	#[test]
+	fn local_storage_quota_is_enforced() {
+		let db = OffchainDb::new(LocalStorage::new_test());
+		let mut db = QuotaDb::new(db, Some(10), Default::default());
+		let kind = StorageKind::PERSISTENT;
+
+		db.local_storage_set(kind, b"key", b"value");
+		assert_eq!(db.local_storage_get(kind, b"key"), Some(b"value".to_vec()));
+
+		// 8 bytes were charged, a 3 bytes write exceeds the quota.
+		db.local_storage_set(kind, b"k2", b"v");
+		assert_eq!(db.local_storage_get(kind, b"k2"), None);
+		assert!(!db.local_storage_compare_and_set(kind, b"key", Some(b"value"), b"v"));
+
+		// Writes fitting in the remaining quota are still accepted.
+		assert!(db.local_storage_compare_and_set(kind, b"k", None, b"v"));
+		assert_eq!(db.local_storage_get(kind, b"k"), Some(b"v".to_vec()));
+	}
+
+	#[test]
+	fn no_quota_allows_any_write() {
+		let db = OffchainDb::new(LocalStorage::new_test());
+		let mut db = QuotaDb::new(db, None, Default::default());
+
+		db.local_storage_set(StorageKind::PERSISTENT, b"key", &[0; 1024]);
+		assert_eq!(db.local_storage_get(StorageKind::PERSISTENT, b"key"), Some(vec![0; 1024]));
+	}
+}
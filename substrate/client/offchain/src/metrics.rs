@@ -0,0 +1,350 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with this program. If not, see <https://www.gnu.org/licenses/>.
+
+//! Prometheus metrics of the offchain workers.
+
+use std::{
+	collections::{BTreeMap, HashMap},
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+use parking_lot::Mutex;
+use prometheus_endpoint::{
+	register, CounterVec, Histogram, HistogramOpts, HistogramVec, Opts, PrometheusError, Registry,
+	U64,
+};
+use sp_tracing::{WASM_NAME_KEY, WASM_TARGET_KEY, WASM_TRACE_IDENTIFIER};
+use tracing::{
+	field::{Field, Visit},
+	level_filters::LevelFilter,
+	span::{Attributes, Id, Record},
+	subscriber::Interest,
+	Dispatch, Event, Metadata, Subscriber,
+};
+
+use crate::LOG_TARGET;
+
+/// Name of the span entered by FRAME around the offchain worker of every pallet.
+const MODULE_SPAN_NAME: &str = "offchain_worker";
+
+/// Offchain workers Prometheus metrics.
+pub(crate) struct Metrics {
+	/// Execution time of the offchain workers of a block.
+	pub execution_time: Histogram,
+	/// Execution time of the offchain worker of every runtime module.
+	pub module_execution_time: HistogramVec,
+	/// HTTP requests refused because of the limits.
+	pub http_requests_refused: CounterVec<U64>,
+	/// Local storage writes refused because of the quota.
+	pub local_storage_writes_refused: prometheus_endpoint::Counter<U64>,
+}
+
+impl Metrics {
+	/// Register the metrics at the given Prometheus registry.
+	pub(crate) fn register(registry: &Registry) -> Result<Self, PrometheusError> {
+		let buckets = prometheus_endpoint::exponential_buckets(0.001, 4.0, 9)?;
+		Ok(Self {
+			execution_time: register(
+				Histogram::with_opts(
+					HistogramOpts::new(
+						"substrate_offchain_worker_execution_time_seconds",
+						"Time taken to run the offchain workers of a block",
+					)
+					.buckets(buckets.clone()),
+				)?,
+				registry,
+			)?,
+			module_execution_time: register(
+				HistogramVec::new(
+					HistogramOpts::new(
+						"substrate_offchain_worker_module_execution_time_seconds",
+						"Time taken to run the offchain worker of a runtime module",
+					)
+					.buckets(buckets),
+					&["module"],
+				)?,
+				registry,
+			)?,
+			http_requests_refused: register(
+				CounterVec::new(
+					Opts::new(
+						"substrate_offchain_worker_http_requests_refused_total",
+						"HTTP requests of the offchain workers refused by the node limits",
+					),
+					&["reason"],
+				)?,
+				registry,
+			)?,
+			local_storage_writes_refused: register(
+				prometheus_endpoint::Counter::new(
+					"substrate_offchain_worker_local_storage_writes_refused_total",
+					"Local storage writes of the offchain workers refused by the quota",
+				)?,
+				registry,
+			)?,
+		})
+	}
+}
+
+/// Cheaply cloneable link to the (optional) metrics.
+#[derive(Clone, Default)]
+pub(crate) struct MetricsLink(Option<Arc<Metrics>>);
+
+impl MetricsLink {
+	pub(crate) fn new(registry: Option<&Registry>) -> Self {
+		Self(registry.and_then(|registry| {
+			Metrics::register(registry)
+				.map_err(|err| {
+					tracing::warn!(
+						target: LOG_TARGET,
+						"Failed to register prometheus metrics: {}",
+						err
+					);
+				})
+				.ok()
+				.map(Arc::new)
+		}))
+	}
+
+	/// Returns `true` if the metrics are registered.
+	pub(crate) fn is_enabled(&self) -> bool {
+		self.0.is_some()
+	}
+
+	pub(crate) fn report(&self, do_this: impl FnOnce(&Metrics)) {
+		if let Some(metrics) = self.0.as_ref() {
+			do_this(metrics);
+		}
+	}
+}
+
+/// Runs `f`, measuring the time spent in the offchain worker of every runtime module.
+///
+/// FRAME enters an `offchain_worker` span around the offchain worker of every pallet, the
+/// span target being the pallet module path. The spans crossing the runtime boundary are
+/// only emitted by runtimes built with the `with-tracing` feature: for other runtimes the
+/// returned map is empty.
+///
+/// All the tracing data is forwarded to the current default dispatcher.
+pub(crate) fn with_module_timings<R>(f: impl FnOnce() -> R) -> (R, BTreeMap<String, Duration>) {
+	let timings = Arc::new(Mutex::new(BTreeMap::new()));
+	let dispatch = Dispatch::new(ModuleTimer {
+		inner: tracing::dispatcher::get_default(Clone::clone),
+		spans: Default::default(),
+		timings: timings.clone(),
+	});
+
+	let result = tracing::dispatcher::with_default(&dispatch, f);
+	drop(dispatch);
+
+	let timings = std::mem::take(&mut *timings.lock());
+	(result, timings)
+}
+
+/// Subscriber wrapping the default dispatcher, timing the module spans.
+struct ModuleTimer {
+	inner: Dispatch,
+	/// Module spans being tracked, with the time they have been entered at.
+	spans: Mutex<HashMap<Id, (String, Option<Instant>)>>,
+	/// Accumulated execution time per module.
+	timings: Arc<Mutex<BTreeMap<String, Duration>>>,
+}
+
+impl ModuleTimer {
+	/// Returns `true` if the given callsite may be a module span.
+	fn maybe_module_span(metadata: &Metadata<'_>) -> bool {
+		metadata.is_span() &&
+			(metadata.name() == WASM_TRACE_IDENTIFIER || metadata.name() == MODULE_SPAN_NAME)
+	}
+
+	/// Returns the module of the given span, if it is a module span.
+	fn module_of(attrs: &Attributes<'_>) -> Option<String> {
+		let metadata = attrs.metadata();
+		if metadata.name() == WASM_TRACE_IDENTIFIER {
+			let mut visitor = WasmSpanVisitor::default();
+			attrs.record(&mut visitor);
+			(visitor.name.as_deref() == Some(MODULE_SPAN_NAME)).then_some(visitor.target?)
+		} else {
+			(metadata.name() == MODULE_SPAN_NAME).then(|| metadata.target().to_owned())
+		}
+	}
+}
+
+impl Subscriber for ModuleTimer {
+	fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+		// The module spans are emitted at `TRACE` level, they must not be filtered out by the
+		// node logger.
+		if Self::maybe_module_span(metadata) {
+			Interest::always()
+		} else {
+			self.inner.register_callsite(metadata)
+		}
+	}
+
+	fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+		Self::maybe_module_span(metadata) || self.inner.enabled(metadata)
+	}
+
+	fn max_level_hint(&self) -> Option<LevelFilter> {
+		Some(LevelFilter::TRACE)
+	}
+
+	fn new_span(&self, attrs: &Attributes<'_>) -> Id {
+		let id = self.inner.new_span(attrs);
+		if let Some(module) = Self::module_of(attrs) {
+			self.spans.lock().insert(id.clone(), (module, None));
+		}
+		id
+	}
+
+	fn record(&self, span: &Id, values: &Record<'_>) {
+		self.inner.record(span, values)
+	}
+
+	fn record_follows_from(&self, span: &Id, follows: &Id) {
+		self.inner.record_follows_from(span, follows)
+	}
+
+	fn event_enabled(&self, event: &Event<'_>) -> bool {
+		self.inner.event_enabled(event)
+	}
+
+	fn event(&self, event: &Event<'_>) {
+		self.inner.event(event)
+	}
+
+	fn enter(&self, span: &Id) {
+		if let Some((_, entered)) = self.spans.lock().get_mut(span) {
+			*entered = Some(Instant::now());
+		}
+		self.inner.enter(span)
+	}
+
+	fn exit(&self, span: &Id) {
+		self.inner.exit(span);
+		if let Some((module, Some(entered))) = self.spans.lock().remove(span) {
+			*self.timings.lock().entry(module).or_default() += entered.elapsed();
+		}
+	}
+
+	fn clone_span(&self, id: &Id) -> Id {
+		self.inner.clone_span(id)
+	}
+
+	fn try_close(&self, id: Id) -> bool {
+		self.inner.try_close(id)
+	}
+
+	fn current_span(&self) -> tracing::span::Current {
+		self.inner.current_span()
+	}
+}
+
+/// Extracts the name and target of a span coming from the runtime.
+#[derive(Default)]
+struct WasmSpanVisitor {
+	name: Option<String>,
+	target: Option<String>,
+}
+
+impl Visit for WasmSpanVisitor {
+	fn record_str(&mut self, field: &Field, value: &str) {
+		match field.name() {
+			n if n == WASM_NAME_KEY => self.name = Some(value.to_owned()),
+			n if n == WASM_TARGET_KEY => self.target = Some(value.to_owned()),
+			_ => {},
+		}
+	}
+
+	fn record_debug(&mut self, _field: &Field, _value: &dyn std::fmt::Debug) {}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::sync::atomic::{AtomicU64, Ordering};
+
+	/// Subscriber enabling everything up to `INFO`, standing for the node logger.
+	#[derive(Default)]
+	struct TestSubscriber(AtomicU64);
+
+	impl Subscriber for TestSubscriber {
+		fn register_callsite(&self, metadata: &'static Metadata<'static>) -> Interest {
+			if self.enabled(metadata) {
+				Interest::always()
+			} else {
+				Interest::never()
+			}
+		}
+
+		fn enabled(&self, metadata: &Metadata<'_>) -> bool {
+			*metadata.level() <= tracing::Level::INFO
+		}
+
+		fn max_level_hint(&self) -> Option<LevelFilter> {
+			Some(LevelFilter::INFO)
+		}
+
+		fn new_span(&self, _: &Attributes<'_>) -> Id {
+			Id::from_u64(self.0.fetch_add(1, Ordering::Relaxed) + 1)
+		}
+
+		fn record(&self, _: &Id, _: &Record<'_>) {}
+
+		fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+		fn event(&self, _: &Event<'_>) {}
+
+		fn enter(&self, _: &Id) {}
+
+		fn exit(&self, _: &Id) {}
+	}
+
+	#[test]
+	fn module_spans_are_timed() {
+		let logger = Dispatch::new(TestSubscriber::default());
+		let (result, timings) = tracing::dispatcher::with_default(&logger, || {
+			with_module_timings(|| {
+				let span = tracing::info_span!(target: "pallet_example", "offchain_worker");
+				span.in_scope(|| std::thread::sleep(Duration::from_millis(5)));
+				let other = tracing::info_span!(target: "pallet_other", "something_else");
+				other.in_scope(|| ());
+				42
+			})
+		});
+
+		assert_eq!(result, 42);
+		assert_eq!(timings.keys().collect::<Vec<_>>(), vec!["pallet_example"]);
+		assert!(timings["pallet_example"] >= Duration::from_millis(5));
+	}
+
+	#[test]
+	fn trace_module_spans_are_timed_when_the_logger_is_at_info() {
+		let logger = Dispatch::new(TestSubscriber::default());
+		let ((), timings) = tracing::dispatcher::with_default(&logger, || {
+			with_module_timings(|| {
+				let span = tracing::trace_span!(target: "pallet_example", "offchain_worker");
+				span.in_scope(|| std::thread::sleep(Duration::from_millis(5)));
+			})
+		});
+
+		assert_eq!(timings.keys().collect::<Vec<_>>(), vec!["pallet_example"]);
+		assert!(timings["pallet_example"] >= Duration::from_millis(5));
+	}
+}
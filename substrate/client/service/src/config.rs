@@ -21,6 +21,7 @@
 pub use jsonrpsee::server::BatchRequestConfig as RpcBatchRequestConfig;
 use prometheus_endpoint::Registry;
 use sc_chain_spec::ChainSpec;
+pub use sc_client_api::offchain::OffchainWorkerLimits;
 pub use sc_client_db::{BlocksPruning, Database, DatabaseSource, PruningMode};
 pub use sc_executor::{WasmExecutionMethod, WasmtimeInstantiationStrategy};
 pub use sc_network::{
@@ -154,6 +155,21 @@ pub struct OffchainWorkerConfig {
 	pub enabled: bool,
 	/// allow writes from the runtime to the offchain worker database.
 	pub indexing_enabled: bool,
+	/// Resource limits applied to offchain workers.
+	pub limits: OffchainWorkerLimits,
+}
+
+impl OffchainWorkerConfig {
+	/// Create a new configuration, without any limits applied to the offchain workers.
+	pub fn new(enabled: bool, indexing_enabled: bool) -> Self {
+		Self { enabled, indexing_enabled, limits: Default::default() }
+	}
+
+	/// Apply the given resource limits to the offchain workers.
+	pub fn with_limits(mut self, limits: OffchainWorkerLimits) -> Self {
+		self.limits = limits;
+		self
+	}
 }
 
 /// Configuration of the Prometheus endpoint.
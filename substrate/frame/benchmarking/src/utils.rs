@@ -320,6 +320,11 @@ pub trait Benchmarking {
 	fn proof_size(&self) -> Option<u32> {
 		self.proof_size()
 	}
+
+	/// Mark the start or the end of a benchmark repetition.
+	fn mark_repetition(&mut self, start: bool) {
+		self.mark_repetition(start)
+	}
 }
 
 /// The pallet benchmarking trait.
@@ -396,6 +401,7 @@ impl<'a> BenchmarkRecording<'a> {
 impl<'a> Recording for BenchmarkRecording<'a> {
 	fn start(&mut self) {
 		(self.on_before_start.take().expect("start called more than once"))();
+		crate::benchmarking::mark_repetition(true);
 		self.start_pov = crate::benchmarking::proof_size();
 		self.start_extrinsic = Some(crate::benchmarking::current_time());
 	}
@@ -403,6 +409,7 @@ impl<'a> Recording for BenchmarkRecording<'a> {
 	fn stop(&mut self) {
 		self.finish_extrinsic = Some(crate::benchmarking::current_time());
 		self.end_pov = crate::benchmarking::proof_size();
+		crate::benchmarking::mark_repetition(false);
 	}
 }
 
@@ -62,9 +62,13 @@
 //!
 //! When working with migrations, it's crucial to ensure the safety of your migrations. The
 //! preferred tool to test migrations is
-//! [`try-runtime-cli`](https://github.com/paritytech/try-runtime-cli). Support will be added to
-//! dry-run MBMs once they are stable
-//! (tracked: <https://github.com/paritytech/try-runtime-cli/issues/17>).
+//! [`try-runtime-cli`](https://github.com/paritytech/try-runtime-cli).
+//!
+//! MBMs are dry-run by the `on_runtime_upgrade_and_migrations` function of the `TryRuntime`
+//! runtime API, backed by `frame_executive::Executive::try_runtime_upgrade_and_migrations`: it
+//! runs the single-block migrations, then steps the MBMs in empty blocks until they completed.
+//! `frame-storage-layout-diff --execute-migrations` uses it to diff the storage layout after all
+//! migrations ran.
 
 pub mod migrations;
 mod mock;
@@ -19,9 +19,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use frame_support::dispatch::DispatchResult;
-use frame_system::offchain::CreateInherent;
-#[cfg(feature = "experimental")]
-use frame_system::offchain::SubmitTransaction;
+use frame_system::offchain::{CreateInherent, SubmitTransaction};
 // Re-export pallet items so that they can be accessed from the crate namespace.
 pub use pallet::*;
 
@@ -34,7 +32,6 @@ mod benchmarking;
 pub mod weights;
 pub use weights::*;
 
-#[cfg(feature = "experimental")]
 const LOG_TARGET: &str = "pallet-example-tasks";
 
 #[frame_support::pallet(dev_mode)]
@@ -49,7 +46,7 @@ pub mod pallet {
 		NotFound,
 	}
 
-	#[pallet::tasks_experimental]
+	#[pallet::tasks]
 	impl<T: Config> Pallet<T> {
 		/// Add a pair of numbers into the totals and remove them.
 		#[pallet::task_list(Numbers::<T>::iter_keys())]
@@ -68,7 +65,6 @@ pub mod pallet {
 
 	#[pallet::hooks]
 	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
-		#[cfg(feature = "experimental")]
 		fn offchain_worker(_block_number: BlockNumberFor<T>) {
 			if let Some(key) = Numbers::<T>::iter_keys().next() {
 				// Create a valid task
@@ -85,9 +81,6 @@ pub mod pallet {
 				}
 			}
 		}
-
-		#[cfg(not(feature = "experimental"))]
-		fn offchain_worker(_block_number: BlockNumberFor<T>) {}
 	}
 
 	#[pallet::config]
@@ -63,11 +63,9 @@ impl pallet_example_tasks::Config for Runtime {
 }
 
 pub fn advance_to(b: u64) {
-	#[cfg(feature = "experimental")]
 	use frame_support::traits::Hooks;
 	while System::block_number() < b {
 		System::set_block_number(System::block_number() + 1);
-		#[cfg(feature = "experimental")]
 		TasksExample::offchain_worker(System::block_number());
 	}
 }
@@ -19,16 +19,11 @@
 #![cfg(test)]
 
 use crate::{mock::*, Numbers};
-#[cfg(feature = "experimental")]
 use codec::Decode;
-use frame_support::traits::Task;
-#[cfg(feature = "experimental")]
+use frame_support::{assert_noop, assert_ok, traits::Task};
 use sp_core::offchain::{testing, OffchainWorkerExt, TransactionPoolExt};
 use sp_runtime::BuildStorage;
 
-#[cfg(feature = "experimental")]
-use frame_support::{assert_noop, assert_ok};
-
 // This function basically just builds a genesis storage key/value store according to
 // our desired mockup.
 pub fn new_test_ext() -> sp_io::TestExternalities {
@@ -74,6 +69,25 @@ fn runtime_task_enumerate_works_via_pallet_config() {
 	});
 }
 
+#[test]
+fn pending_tasks_returns_valid_tasks_with_weights() {
+	new_test_ext().execute_with(|| {
+		Numbers::<Runtime>::insert(0, 1);
+		Numbers::<Runtime>::insert(1, 4);
+		Numbers::<Runtime>::insert(2, 9);
+
+		let pending = System::pending_tasks(2);
+		assert_eq!(pending.len(), 2);
+		for (task, weight) in pending {
+			assert!(task.is_valid());
+			assert_eq!(weight, task.weight());
+		}
+
+		assert_eq!(System::pending_tasks(10).len(), 3);
+		assert!(System::pending_tasks(0).is_empty());
+	});
+}
+
 #[test]
 fn task_index_works_at_pallet_level() {
 	new_test_ext().execute_with(|| {
@@ -96,7 +110,6 @@ fn task_index_works_at_runtime_level() {
 	});
 }
 
-#[cfg(feature = "experimental")]
 #[test]
 fn task_execution_works() {
 	new_test_ext().execute_with(|| {
@@ -118,7 +131,6 @@ fn task_execution_works() {
 	});
 }
 
-#[cfg(feature = "experimental")]
 #[test]
 fn task_execution_fails_for_invalid_task() {
 	new_test_ext().execute_with(|| {
@@ -135,7 +147,6 @@ fn task_execution_fails_for_invalid_task() {
 	});
 }
 
-#[cfg(feature = "experimental")]
 #[test]
 fn task_with_offchain_worker() {
 	let (offchain, _offchain_state) = testing::TestOffchainExt::new();
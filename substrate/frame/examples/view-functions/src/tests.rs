@@ -0,0 +1,164 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for `pallet-example-view-functions`.
+#![cfg(test)]
+
+use crate::{
+	pallet::{self, Pallet},
+	pallet2,
+};
+use codec::{Decode, Encode};
+use frame_support::{
+	derive_impl,
+	traits::PalletInfoAccess,
+	view_functions::{ViewFunction, ViewFunctionDispatchError, ViewFunctionId},
+};
+use scale_info::meta_type;
+use sp_io::hashing::twox_128;
+use sp_runtime::BuildStorage;
+
+type Block = frame_system::mocking::MockBlock<Runtime>;
+frame_support::construct_runtime!(
+	pub enum Runtime {
+		System: frame_system,
+		ViewFunctionsExample: pallet,
+		ViewFunctionsInstance: pallet2,
+		ViewFunctionsInstance1: pallet2::<Instance1>,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Runtime {
+	type Block = Block;
+}
+
+impl pallet::Config for Runtime {}
+impl pallet2::Config for Runtime {}
+impl pallet2::Config<pallet2::Instance1> for Runtime {}
+
+pub fn new_test_ext() -> sp_io::TestExternalities {
+	let t = RuntimeGenesisConfig { system: Default::default() }.build_storage().unwrap();
+	t.into()
+}
+
+fn execute_view_function<V: ViewFunction>(
+	view_function: V,
+) -> Result<V::ReturnType, ViewFunctionDispatchError>
+where
+	V::ReturnType: Decode,
+{
+	let output = Runtime::execute_view_function(V::id(), view_function.encode())?;
+	Ok(Decode::decode(&mut &output[..]).expect("view function output is valid"))
+}
+
+#[test]
+fn pallet_get_value_query() {
+	new_test_ext().execute_with(|| {
+		let some_value = Some(99);
+		pallet::SomeValue::<Runtime>::set(some_value);
+		assert_eq!(some_value, Pallet::<Runtime>::get_value());
+
+		let query = pallet::GetValueViewFunction::<Runtime>::new();
+		assert_eq!(execute_view_function(query), Ok(some_value));
+	});
+}
+
+#[test]
+fn pallet_get_value_with_arg_query() {
+	new_test_ext().execute_with(|| {
+		let some_key = 1u32;
+		let some_value = Some(123);
+		pallet::SomeMap::<Runtime>::set(some_key, some_value);
+		assert_eq!(some_value, Pallet::<Runtime>::get_value_with_arg(some_key));
+
+		let query = pallet::GetValueWithArgViewFunction::<Runtime>::new(some_key);
+		assert_eq!(execute_view_function(query), Ok(some_value));
+	});
+}
+
+#[test]
+fn pallet_multiple_instances() {
+	use pallet2::Instance1;
+
+	new_test_ext().execute_with(|| {
+		let instance_value = Some(123);
+		let instance1_value = Some(456);
+
+		pallet2::SomeValue::<Runtime>::set(instance_value);
+		pallet2::SomeValue::<Runtime, Instance1>::set(instance1_value);
+
+		let query = pallet2::GetValueViewFunction::<Runtime>::new();
+		assert_eq!(execute_view_function(query), Ok(instance_value));
+
+		let query_instance1 = pallet2::GetValueViewFunction::<Runtime, Instance1>::new();
+		assert_eq!(execute_view_function(query_instance1), Ok(instance1_value));
+	});
+}
+
+#[test]
+fn unknown_view_function_is_not_found() {
+	new_test_ext().execute_with(|| {
+		let id = ViewFunctionId {
+			prefix: twox_128(ViewFunctionsExample::name().as_bytes()),
+			suffix: [0u8; 16],
+		};
+		assert_eq!(
+			Runtime::execute_view_function(id.clone(), Vec::new()),
+			Err(ViewFunctionDispatchError::NotFound(id)),
+		);
+	});
+}
+
+#[test]
+fn invalid_input_is_a_codec_error() {
+	new_test_ext().execute_with(|| {
+		let id = pallet::GetValueWithArgViewFunction::<Runtime>::id();
+		assert_eq!(
+			Runtime::execute_view_function(id, vec![1]),
+			Err(ViewFunctionDispatchError::Codec),
+		);
+	});
+}
+
+#[test]
+fn metadata_ir_definitions() {
+	let metadata_ir = Runtime::metadata_ir();
+	let pallet = metadata_ir
+		.pallets
+		.into_iter()
+		.find(|pallet| pallet.name == "ViewFunctionsExample")
+		.unwrap();
+
+	let view_function_ids =
+		pallet.view_functions.iter().map(|view_fn| view_fn.id).collect::<Vec<_>>();
+	assert_eq!(
+		view_function_ids,
+		vec![
+			<[u8; 32]>::from(pallet::GetValueViewFunction::<Runtime>::id()),
+			<[u8; 32]>::from(pallet::GetValueWithArgViewFunction::<Runtime>::id()),
+		],
+	);
+
+	let get_value_with_arg = &pallet.view_functions[1];
+	assert_eq!(get_value_with_arg.name, "get_value_with_arg");
+	assert_eq!(get_value_with_arg.inputs.len(), 1);
+	assert_eq!(get_value_with_arg.inputs[0].name, "key");
+	assert_eq!(get_value_with_arg.inputs[0].ty, meta_type::<u32>());
+	assert_eq!(get_value_with_arg.output, meta_type::<Option<u32>>());
+	assert_eq!(get_value_with_arg.docs, vec![" Query value with args."]);
+}
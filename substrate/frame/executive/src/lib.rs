@@ -466,6 +466,59 @@ where
 		Ok(before_all_weight.saturating_add(try_on_runtime_upgrade_weight))
 	}
 
+	/// Execute all Migrations of this runtime, including the multi-block migrations.
+	///
+	/// Runs [`Self::try_runtime_upgrade`] and then steps the [`MultiStepMigrator`] in empty blocks
+	/// on top of the current state until it completed. The blocks carry neither inherents nor
+	/// digests, thus only `on_initialize` runs in them: the `on_idle` and `on_finalize` hooks are
+	/// skipped since they may rely on inherents, like the timestamp. The `pre/post_upgrade` hooks
+	/// of the stepped migrations are run by the migrator itself when compiled with `try-runtime`.
+	/// The state decoding and `try_state` checks selected by `checks` are repeated once all
+	/// migrations completed.
+	///
+	/// Returns the weight of the runtime upgrade and the number of blocks the multi-block
+	/// migrations took. Fails if they did not complete within `max_blocks`.
+	pub fn try_runtime_upgrade_and_migrations(
+		checks: UpgradeCheckSelect,
+		max_blocks: u32,
+	) -> Result<(Weight, u32), TryRuntimeError> {
+		let weight = Self::try_runtime_upgrade(checks)?;
+
+		// The hash of the latest block is not part of its state.
+		let mut parent_hash = System::Hash::default();
+		let mut blocks = 0u32;
+		while <System as frame_system::Config>::MultiBlockMigrator::ongoing() {
+			if blocks >= max_blocks {
+				log::error!(
+					target: LOG_TARGET,
+					"try-runtime: multi-block migrations did not complete within {max_blocks} blocks",
+				);
+				return Err("Multi-block migrations did not complete in time".into())
+			}
+
+			let number = frame_system::Pallet::<System>::block_number() + One::one();
+			Self::initialize_block_impl(&number, &parent_hash, &Default::default());
+			// Steps the migrations, as when the inherents of a block are applied.
+			Self::inherents_applied();
+			parent_hash = <frame_system::Pallet<System>>::finalize().hash();
+			blocks += 1;
+		}
+		log::info!(target: LOG_TARGET, "try-runtime: multi-block migrations took {blocks} blocks");
+
+		if checks.any() {
+			let res = AllPalletsWithSystem::try_decode_entire_state();
+			Self::log_decode_result(res)?;
+		}
+		if checks.try_state() {
+			AllPalletsWithSystem::try_state(
+				frame_system::Pallet::<System>::block_number(),
+				TryStateSelect::All,
+			)?;
+		}
+
+		Ok((weight, blocks))
+	}
+
 	/// Logs the result of trying to decode the entire state.
 	fn log_decode_result(
 		res: Result<usize, alloc::vec::Vec<TryDecodeEntireStorageError>>,
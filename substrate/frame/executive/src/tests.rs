@@ -541,6 +541,8 @@ impl MockedSystemCallbacks {
 
 parameter_types! {
 	pub static MbmActive: bool = false;
+	/// Number of steps after which the MBMs complete. Zero means they never complete.
+	pub static MbmSteps: u32 = 0;
 }
 
 pub struct MockedModeGetter;
@@ -550,6 +552,10 @@ impl MultiStepMigrator for MockedModeGetter {
 	}
 
 	fn step() -> Weight {
+		if MbmSteps::get() > 0 {
+			MbmSteps::mutate(|steps| *steps -= 1);
+			MbmActive::set(MbmSteps::get() > 0);
+		}
 		Weight::zero()
 	}
 }
@@ -1271,6 +1277,85 @@ fn try_execute_block_works() {
 	});
 }
 
+#[test]
+#[cfg(feature = "try-runtime")]
+fn try_runtime_upgrade_and_migrations_works() {
+	use frame_try_runtime::UpgradeCheckSelect;
+
+	new_test_ext(1).execute_with(|| {
+		MbmActive::set(true);
+		MbmSteps::set(3);
+
+		let (_, blocks) =
+			Executive::try_runtime_upgrade_and_migrations(UpgradeCheckSelect::None, 10).unwrap();
+		assert_eq!(blocks, 3);
+		assert!(!MbmActive::get());
+		assert_eq!(System::block_number(), 3);
+	});
+
+	new_test_ext(1).execute_with(|| {
+		MbmActive::set(true);
+		MbmSteps::set(3);
+
+		assert!(Executive::try_runtime_upgrade_and_migrations(UpgradeCheckSelect::None, 2).is_err());
+	});
+}
+
+/// A runtime with `pallet_timestamp`, whose `on_finalize` panics in blocks without the timestamp
+/// inherent.
+#[cfg(feature = "try-runtime")]
+mod with_timestamp {
+	use super::MockedModeGetter;
+	use frame_support::derive_impl;
+
+	frame_support::construct_runtime!(
+		pub enum Runtime {
+			System: frame_system,
+			Timestamp: pallet_timestamp,
+		}
+	);
+
+	pub type Block = frame_system::mocking::MockBlock<Runtime>;
+
+	#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+	impl frame_system::Config for Runtime {
+		type Block = Block;
+		type MultiBlockMigrator = MockedModeGetter;
+	}
+
+	#[derive_impl(pallet_timestamp::config_preludes::TestDefaultConfig)]
+	impl pallet_timestamp::Config for Runtime {}
+
+	pub type Executive = crate::Executive<
+		Runtime,
+		Block,
+		frame_system::ChainContext<Runtime>,
+		Runtime,
+		AllPalletsWithSystem,
+	>;
+}
+
+#[test]
+#[cfg(feature = "try-runtime")]
+fn try_runtime_upgrade_and_migrations_skips_on_finalize() {
+	use frame_try_runtime::UpgradeCheckSelect;
+	use with_timestamp::{Executive, Runtime, System};
+
+	let mut ext: sp_io::TestExternalities = frame_system::GenesisConfig::<Runtime>::default()
+		.build_storage()
+		.unwrap()
+		.into();
+	ext.execute_with(|| {
+		MbmActive::set(true);
+		MbmSteps::set(3);
+
+		let (_, blocks) =
+			Executive::try_runtime_upgrade_and_migrations(UpgradeCheckSelect::All, 10).unwrap();
+		assert_eq!(blocks, 3);
+		assert_eq!(System::block_number(), 3);
+	});
+}
+
 /// Same as `extrinsic_while_exts_forbidden_errors` but using the try-runtime function.
 #[test]
 #[cfg(feature = "try-runtime")]
@@ -0,0 +1,166 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! RPC interface reporting the progress of multi-block migrations.
+
+use std::sync::Arc;
+
+use codec::Codec;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::error::{ErrorCode, ErrorObject},
+};
+use pallet_migrations_runtime_api::{ActiveMigrationStatus, MigrationStatus};
+use serde::{Deserialize, Serialize};
+use sp_api::ProvideRuntimeApi;
+use sp_blockchain::HeaderBackend;
+use sp_core::Bytes;
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+
+pub use pallet_migrations_runtime_api::MigrationsApi as MigrationsRuntimeApi;
+
+/// The progress of the multi-block migrations.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", tag = "status")]
+pub enum MigrationsStatus<BlockNumber> {
+	/// No migration is running.
+	Idle,
+	/// A migration is running.
+	#[serde(rename_all = "camelCase")]
+	Active {
+		/// The index of the migration in the runtime's migrations.
+		index: u32,
+		/// The number of migrations in the runtime's migrations.
+		total: u32,
+		/// The identifier of the migration.
+		id: Bytes,
+		/// The encoded inner cursor of the migration, if it made progress yet.
+		inner_cursor: Option<Bytes>,
+		/// The block number that the migration started at.
+		started_at: BlockNumber,
+		/// The maximal number of steps the migration may take, if bounded.
+		max_steps: Option<u32>,
+		/// Upper bound on the block by which all remaining migrations complete, if known.
+		completes_by: Option<BlockNumber>,
+	},
+	/// A migration failed and the chain is stuck until governance intervenes.
+	Stuck,
+}
+
+impl<BlockNumber> From<MigrationStatus<BlockNumber>> for MigrationsStatus<BlockNumber> {
+	fn from(status: MigrationStatus<BlockNumber>) -> Self {
+		match status {
+			MigrationStatus::Idle => Self::Idle,
+			MigrationStatus::Stuck => Self::Stuck,
+			MigrationStatus::Active(ActiveMigrationStatus {
+				index,
+				total,
+				id,
+				inner_cursor,
+				started_at,
+				max_steps,
+				completes_by,
+			}) => Self::Active {
+				index,
+				total,
+				id: id.into(),
+				inner_cursor: inner_cursor.map(Into::into),
+				started_at,
+				max_steps,
+				completes_by,
+			},
+		}
+	}
+}
+
+#[rpc(client, server)]
+pub trait MigrationsApi<BlockHash, BlockNumber> {
+	/// Returns the progress of the multi-block migrations at the given block, or the best block.
+	#[method(name = "migrations_status")]
+	fn status(&self, at: Option<BlockHash>) -> RpcResult<MigrationsStatus<BlockNumber>>;
+}
+
+/// Provides the RPC methods of the multi-block migrations pallet.
+pub struct Migrations<C, B> {
+	client: Arc<C>,
+	_marker: std::marker::PhantomData<B>,
+}
+
+impl<C, B> Migrations<C, B> {
+	/// Creates a new instance of the migrations RPC handler.
+	pub fn new(client: Arc<C>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<C, Block> MigrationsApiServer<<Block as BlockT>::Hash, NumberFor<Block>>
+	for Migrations<C, Block>
+where
+	Block: BlockT,
+	NumberFor<Block>: Codec,
+	C: ProvideRuntimeApi<Block> + HeaderBackend<Block> + Send + Sync + 'static,
+	C::Api: MigrationsRuntimeApi<Block, NumberFor<Block>>,
+{
+	fn status(&self, at: Option<Block::Hash>) -> RpcResult<MigrationsStatus<NumberFor<Block>>> {
+		let at = at.unwrap_or_else(|| self.client.info().best_hash);
+		self.client.runtime_api().status(at).map(Into::into).map_err(|e| {
+			ErrorObject::owned(
+				ErrorCode::InternalError.code(),
+				"Unable to query the migration status.",
+				Some(e.to_string()),
+			)
+		})
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn active_status_serializes_with_camel_case_fields() {
+		let status: MigrationsStatus<u32> = MigrationStatus::Active(ActiveMigrationStatus {
+			index: 1,
+			total: 3,
+			id: b"mbm".to_vec(),
+			inner_cursor: None,
+			started_at: 10,
+			max_steps: Some(5),
+			completes_by: None,
+		})
+		.into();
+
+		assert_eq!(
+			serde_json::to_value(&status).unwrap(),
+			serde_json::json!({
+				"status": "active",
+				"index": 1,
+				"total": 3,
+				"id": "0x6d626d",
+				"innerCursor": null,
+				"startedAt": 10,
+				"maxSteps": 5,
+				"completesBy": null,
+			})
+		);
+		assert_eq!(
+			serde_json::to_value(MigrationsStatus::<u32>::Idle).unwrap(),
+			serde_json::json!({ "status": "idle" })
+		);
+	}
+}
@@ -258,6 +258,39 @@ impl<Id> HistoricCleanupSelector<Id> {
 	}
 }
 
+/// The progress of the multi-block migrations, as reported by [`Pallet::status`].
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode, scale_info::TypeInfo)]
+pub enum MigrationStatus<BlockNumber> {
+	/// No migration is running.
+	Idle,
+	/// A migration is running.
+	Active(ActiveMigrationStatus<BlockNumber>),
+	/// A migration failed and the chain is stuck until governance intervenes.
+	Stuck,
+}
+
+/// The progress of the currently running migration.
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode, scale_info::TypeInfo)]
+pub struct ActiveMigrationStatus<BlockNumber> {
+	/// The index of the migration in the MBM tuple.
+	pub index: u32,
+	/// The number of migrations in the MBM tuple.
+	pub total: u32,
+	/// The identifier of the migration.
+	pub id: Vec<u8>,
+	/// The encoded inner cursor of the migration, if it made progress yet.
+	pub inner_cursor: Option<Vec<u8>>,
+	/// The block number that the migration started at.
+	pub started_at: BlockNumber,
+	/// The maximal number of steps the migration may take, if bounded.
+	pub max_steps: Option<u32>,
+	/// Upper bound on the block by which all remaining migrations complete.
+	///
+	/// Assumes that every block makes at least one step. `None` if any of the remaining migrations
+	/// has an unbounded number of steps.
+	pub completes_by: Option<BlockNumber>,
+}
+
 /// Convenience alias for [`MigrationCursor`].
 pub type CursorOf<T> = MigrationCursor<RawCursorOf<T>, BlockNumberFor<T>>;
 
@@ -828,6 +861,31 @@ impl<T: Config> Pallet<T> {
 			.max(T::WeightInfo::exec_migration_advance())
 			.max(T::WeightInfo::exec_migration_fail())
 	}
+
+	/// The progress of the multi-block migrations.
+	pub fn status() -> MigrationStatus<BlockNumberFor<T>> {
+		let cursor = match Cursor::<T>::get() {
+			None => return MigrationStatus::Idle,
+			Some(MigrationCursor::Stuck) => return MigrationStatus::Stuck,
+			Some(MigrationCursor::Active(cursor)) => cursor,
+		};
+
+		let total = T::Migrations::len();
+		let remaining_steps = (cursor.index..total)
+			.map(|n| T::Migrations::nth_max_steps(n).flatten())
+			.try_fold(0u32, |acc, steps| Some(acc.saturating_add(steps?)));
+
+		MigrationStatus::Active(ActiveMigrationStatus {
+			index: cursor.index,
+			total,
+			id: T::Migrations::nth_id(cursor.index).unwrap_or_default(),
+			inner_cursor: cursor.inner_cursor.map(|c| c.into_inner()),
+			started_at: cursor.started_at,
+			max_steps: T::Migrations::nth_max_steps(cursor.index).flatten(),
+			completes_by: remaining_steps
+				.map(|steps| cursor.started_at.saturating_add(steps.into())),
+		})
+	}
 }
 
 impl<T: Config> MultiStepMigrator for Pallet<T> {
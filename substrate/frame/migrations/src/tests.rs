@@ -22,7 +22,8 @@ use frame_support::{pallet_prelude::Weight, traits::OnRuntimeUpgrade};
 use crate::{
 	mock::{Test as T, *},
 	mock_helpers::{MockedMigrationKind::*, *},
-	Cursor, Event, FailedMigrationHandling, MigrationCursor,
+	ActiveMigrationStatus, Cursor, Event, FailedMigrationHandling, MigrationCursor,
+	MigrationStatus,
 };
 
 #[docify::export]
@@ -86,6 +87,33 @@ fn simple_multiple_works() {
 	});
 }
 
+#[test]
+fn status_reports_progress() {
+	test_closure(|| {
+		MockedMigrations::set(vec![(SucceedAfter, 2), (SucceedAfter, 3)]);
+		assert_eq!(Migrations::status(), MigrationStatus::Idle);
+
+		System::set_block_number(1);
+		Migrations::on_runtime_upgrade();
+		assert_eq!(
+			Migrations::status(),
+			MigrationStatus::Active(ActiveMigrationStatus {
+				index: 0,
+				total: 2,
+				id: mocked_id(SucceedAfter, 2).into_inner(),
+				inner_cursor: None,
+				started_at: 1,
+				max_steps: Some(2),
+				// Both migrations take at most five steps in total.
+				completes_by: Some(6),
+			})
+		);
+
+		run_to_block(10);
+		assert_eq!(Migrations::status(), MigrationStatus::Idle);
+	});
+}
+
 #[test]
 #[cfg_attr(feature = "try-runtime", should_panic)]
 fn failing_migration_sets_cursor_to_stuck() {
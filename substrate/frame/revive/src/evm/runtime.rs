@@ -98,6 +98,12 @@ impl<Address, Signature, E: EthExtra> ExtrinsicMetadata
 		Signature,
 		E::Extension,
 	>::VERSIONS;
+	const TRANSACTION_EXTENSION_VERSIONS: &'static [u8] = generic::UncheckedExtrinsic::<
+		Address,
+		CallOf<E::Config>,
+		Signature,
+		E::Extension,
+	>::TRANSACTION_EXTENSION_VERSIONS;
 	type TransactionExtensions = E::Extension;
 }
 
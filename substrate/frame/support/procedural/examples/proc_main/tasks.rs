@@ -26,7 +26,7 @@ use frame_support_procedural::pallet_section;
 #[pallet_section]
 mod tasks_example {
 	#[docify::export(tasks_example)]
-	#[pallet::tasks_experimental]
+	#[pallet::tasks]
 	impl<T: Config> Pallet<T> {
 		/// Add a pair of numbers into the totals and remove them.
 		#[pallet::task_list(Numbers::<T>::iter_keys())]
@@ -50,6 +50,7 @@ pub fn expand_runtime_metadata(
 			let constants = expand_pallet_metadata_constants(runtime, decl);
 			let errors = expand_pallet_metadata_errors(runtime, decl);
 			let associated_types = expand_pallet_metadata_associated_types(runtime, decl);
+			let view_functions = expand_pallet_metadata_view_functions(runtime, decl);
 			let docs = expand_pallet_metadata_docs(runtime, decl);
 			let attr = decl.cfg_pattern.iter().fold(TokenStream::new(), |acc, pattern| {
 				let attr = TokenStream::from_str(&format!("#[cfg({})]", pattern.original()))
@@ -72,6 +73,7 @@ pub fn expand_runtime_metadata(
 					error: #errors,
 					docs: #docs,
 					associated_types: #associated_types,
+					view_functions: #view_functions,
 					deprecation_info: #deprecation_info,
 				}
 			}
@@ -113,6 +115,30 @@ pub fn expand_runtime_metadata(
 						<#extrinsic as #scrate::traits::SignedTransactionBuilder>::Extension
 					>();
 
+				let extensions: #scrate::__private::Vec<_> = <
+						<
+							#extrinsic as #scrate::sp_runtime::traits::ExtrinsicMetadata
+						>::TransactionExtensions
+						as
+						#scrate::sp_runtime::traits::TransactionExtension::<
+							<#runtime as #system_path::Config>::RuntimeCall
+						>
+					>::metadata()
+						.into_iter()
+						.map(|meta| #scrate::__private::metadata_ir::TransactionExtensionMetadataIR {
+							identifier: meta.identifier,
+							ty: meta.ty,
+							implicit: meta.implicit,
+						})
+						.collect();
+				// Every transaction extension version currently uses the whole pipeline.
+				let extensions_by_version = <
+						#extrinsic as #scrate::sp_runtime::traits::ExtrinsicMetadata
+					>::TRANSACTION_EXTENSION_VERSIONS
+						.iter()
+						.map(|version| (*version, (0..extensions.len() as u32).collect()))
+						.collect();
+
 				#scrate::__private::metadata_ir::MetadataIR {
 					pallets: #scrate::__private::vec![ #(#pallets),* ],
 					extrinsic: #scrate::__private::metadata_ir::ExtrinsicMetadataIR {
@@ -122,22 +148,8 @@ pub fn expand_runtime_metadata(
 						call_ty,
 						signature_ty,
 						extra_ty,
-						extensions: <
-								<
-									#extrinsic as #scrate::sp_runtime::traits::ExtrinsicMetadata
-								>::TransactionExtensions
-								as
-								#scrate::sp_runtime::traits::TransactionExtension::<
-									<#runtime as #system_path::Config>::RuntimeCall
-								>
-							>::metadata()
-								.into_iter()
-								.map(|meta| #scrate::__private::metadata_ir::TransactionExtensionMetadataIR {
-									identifier: meta.identifier,
-									ty: meta.ty,
-									implicit: meta.implicit,
-								})
-								.collect(),
+						extensions,
+						extensions_by_version,
 					},
 					ty: #scrate::__private::scale_info::meta_type::<#runtime>(),
 					apis: (&rt).runtime_metadata(),
@@ -276,3 +288,12 @@ fn expand_pallet_metadata_associated_types(runtime: &Ident, decl: &Pallet) -> To
 		#path::Pallet::<#runtime #(, #path::#instance)*>::pallet_associated_types_metadata()
 	}
 }
+
+fn expand_pallet_metadata_view_functions(runtime: &Ident, decl: &Pallet) -> TokenStream {
+	let path = &decl.path;
+	let instance = decl.instance.as_ref().into_iter();
+
+	quote! {
+		#path::Pallet::<#runtime #(, #path::#instance)*>::pallet_view_functions_metadata()
+	}
+}
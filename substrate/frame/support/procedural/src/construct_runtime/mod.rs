@@ -400,6 +400,7 @@ fn construct_runtime_final_expansion(
 
 	let dispatch = expand::expand_outer_dispatch(&name, system_pallet, &pallets, &scrate);
 	let tasks = expand::expand_outer_task(&name, &pallets, &scrate);
+	let view_functions = expand::expand_outer_view_function(&name, &pallets, &scrate);
 	let metadata = expand::expand_runtime_metadata(
 		&name,
 		&pallets,
@@ -491,6 +492,8 @@ fn construct_runtime_final_expansion(
 
 		#tasks
 
+		#view_functions
+
 		#metadata
 
 		#outer_config
@@ -954,6 +954,15 @@ pub fn extra_constants(_: TokenStream, _: TokenStream) -> TokenStream {
 	pallet_macro_stub()
 }
 
+///
+/// ---
+///
+/// Documentation for this macro can be found at `frame_support::pallet_macros::view_functions`.
+#[proc_macro_attribute]
+pub fn view_functions(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
 ///
 /// ---
 ///
@@ -1114,9 +1123,9 @@ pub fn composite_enum(_: TokenStream, _: TokenStream) -> TokenStream {
 /// Such a script can then create and submit all such work items at any given time.
 ///
 /// These work items are defined as instances of the `Task` trait (found at
-/// `frame_support::traits::Task`). [`pallet:tasks_experimental`](macro@tasks_experimental) when
-/// attached to an `impl` block inside a pallet, will generate an enum `Task<T>` whose variants
-/// are mapped to functions inside this `impl` block.
+/// `frame_support::traits::Task`). [`pallet:tasks`](macro@tasks) when attached to an `impl`
+/// block inside a pallet, will generate an enum `Task<T>` whose variants are mapped to functions
+/// inside this `impl` block.
 ///
 /// Each such function must have the following set of attributes:
 ///
@@ -1129,7 +1138,9 @@ pub fn composite_enum(_: TokenStream, _: TokenStream) -> TokenStream {
 /// [`construct_runtime`](macro@construct_runtime).
 ///
 /// Finally, the `RuntimeTask` can then used by a script or off-chain worker to create and
-/// submit such tasks via an extrinsic defined in `frame_system` called `do_task`.
+/// submit such tasks via an extrinsic defined in `frame_system` called `do_task`. Runtimes
+/// implementing `frame_system_rpc_runtime_api::TasksApi` let the node discover pending tasks and
+/// submit them on its own.
 ///
 /// When submitted as unsigned transactions (for example via an off-chain workder), note
 /// that the tasks will be executed in a random order.
@@ -1139,6 +1150,13 @@ pub fn composite_enum(_: TokenStream, _: TokenStream) -> TokenStream {
 /// Now, this can be executed as follows:
 #[doc = docify::embed!("examples/proc_main/tasks.rs", tasks_work)]
 #[proc_macro_attribute]
+pub fn tasks(_: TokenStream, _: TokenStream) -> TokenStream {
+	pallet_macro_stub()
+}
+
+/// Deprecated alias of [`pallet::tasks`](macro@tasks), from before tasks were stabilised.
+#[deprecated(note = "use `#[pallet::tasks]` instead")]
+#[proc_macro_attribute]
 pub fn tasks_experimental(_: TokenStream, _: TokenStream) -> TokenStream {
 	pallet_macro_stub()
 }
@@ -1146,7 +1164,7 @@ pub fn tasks_experimental(_: TokenStream, _: TokenStream) -> TokenStream {
 /// Allows defining an iterator over available work items for a task.
 ///
 /// This attribute is attached to a function inside an `impl` block annotated with
-/// [`pallet::tasks_experimental`](macro@tasks_experimental).
+/// [`pallet::tasks`](macro@tasks).
 ///
 /// It takes an iterator as input that yields a tuple with same types as the function
 /// arguments.
@@ -1158,7 +1176,7 @@ pub fn task_list(_: TokenStream, _: TokenStream) -> TokenStream {
 /// Allows defining conditions for a task to run.
 ///
 /// This attribute is attached to a function inside an `impl` block annotated with
-/// [`pallet::tasks_experimental`](macro@tasks_experimental) to define the conditions for a
+/// [`pallet::tasks`](macro@tasks) to define the conditions for a
 /// given work item to be valid.
 ///
 /// It takes a closure as input, which is then used to define the condition. The closure
@@ -1172,7 +1190,7 @@ pub fn task_condition(_: TokenStream, _: TokenStream) -> TokenStream {
 /// Allows defining the weight of a task.
 ///
 /// This attribute is attached to a function inside an `impl` block annotated with
-/// [`pallet::tasks_experimental`](macro@tasks_experimental) define the weight of a given work
+/// [`pallet::tasks`](macro@tasks) define the weight of a given work
 /// item.
 ///
 /// It takes a closure as input, which should return a `Weight` value.
@@ -1184,7 +1202,7 @@ pub fn task_weight(_: TokenStream, _: TokenStream) -> TokenStream {
 /// Allows defining an index for a task.
 ///
 /// This attribute is attached to a function inside an `impl` block annotated with
-/// [`pallet::tasks_experimental`](macro@tasks_experimental) to define the index of a given
+/// [`pallet::tasks`](macro@tasks) to define the index of a given
 /// work item.
 ///
 /// It takes an integer literal as input, which is then used to define the index. This
@@ -35,6 +35,7 @@ mod tasks;
 mod tt_default_parts;
 mod type_value;
 mod validate_unsigned;
+mod view_functions;
 mod warnings;
 
 use crate::pallet::Def;
@@ -77,6 +78,7 @@ pub fn expand(mut def: Def) -> proc_macro2::TokenStream {
 	let tt_default_parts = tt_default_parts::expand_tt_default_parts(&mut def);
 	let doc_only = doc_only::expand_doc_only(&mut def);
 	let composites = composite::expand_composites(&mut def);
+	let view_functions = view_functions::expand_view_functions(&def);
 
 	def.item.attrs.insert(
 		0,
@@ -119,6 +121,7 @@ storage item. Otherwise, all storage items are listed among [*Type Definitions*]
 		#tt_default_parts
 		#doc_only
 		#composites
+		#view_functions
 	);
 
 	def.item
@@ -22,6 +22,7 @@
 use crate::pallet::{parse::tasks::*, Def};
 use inflector::Inflector;
 use proc_macro2::TokenStream as TokenStream2;
+use proc_macro_warning::Warning;
 use quote::{format_ident, quote, ToTokens};
 use syn::{parse_quote_spanned, spanned::Spanned};
 
@@ -224,8 +225,17 @@ pub fn expand_tasks(def: &Def) -> TokenStream2 {
 	let tasks_expansion = tasks_def.expand_to_tokens(def);
 	let task_enum_expansion = task_enum.expand_to_tokens(def);
 
+	let experimental_warning = tasks_def.experimental_attr_span.map(|span| {
+		Warning::new_deprecated("TasksExperimental")
+			.old("use `#[pallet::tasks_experimental]`")
+			.new("use `#[pallet::tasks]`")
+			.span(span)
+			.build_or_panic()
+	});
+
 	quote! {
 		#tasks_expansion
 		#task_enum_expansion
+		#experimental_warning
 	}
 }
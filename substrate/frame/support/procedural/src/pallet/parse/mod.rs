@@ -36,6 +36,7 @@ pub mod storage;
 pub mod tasks;
 pub mod type_value;
 pub mod validate_unsigned;
+pub mod view_functions;
 
 #[cfg(test)]
 pub mod tests;
@@ -65,6 +66,7 @@ pub struct Def {
 	pub genesis_build: Option<genesis_build::GenesisBuildDef>,
 	pub validate_unsigned: Option<validate_unsigned::ValidateUnsignedDef>,
 	pub extra_constants: Option<extra_constants::ExtraConstantsDef>,
+	pub view_functions: Option<view_functions::ViewFunctionsImplDef>,
 	pub composites: Vec<composite::CompositeDef>,
 	pub type_values: Vec<type_value::TypeValueDef>,
 	pub frame_system: syn::Path,
@@ -100,6 +102,7 @@ impl Def {
 		let mut genesis_build = None;
 		let mut validate_unsigned = None;
 		let mut extra_constants = None;
+		let mut view_functions = None;
 		let mut storages = vec![];
 		let mut type_values = vec![];
 		let mut composites: Vec<CompositeDef> = vec![];
@@ -126,14 +129,21 @@ impl Def {
 				},
 				Some(PalletAttr::RuntimeCall(cw, span)) if call.is_none() =>
 					call = Some(call::CallDef::try_from(span, index, item, dev_mode, cw)?),
-				Some(PalletAttr::Tasks(span)) if tasks.is_none() => {
+				Some(
+					attr @ (PalletAttr::Tasks(span) | PalletAttr::TasksExperimental(span)),
+				) if tasks.is_none() => {
 					let item_tokens = item.to_token_stream();
 					// `TasksDef::parse` needs to know if attr was provided so we artificially
 					// re-insert it here
-					tasks = Some(syn::parse2::<tasks::TasksDef>(quote::quote_spanned! { span =>
-						#[pallet::tasks_experimental]
-						#item_tokens
-					})?);
+					let mut tasks_def =
+						syn::parse2::<tasks::TasksDef>(quote::quote_spanned! { span =>
+							#[pallet::tasks]
+							#item_tokens
+						})?;
+					if matches!(attr, PalletAttr::TasksExperimental(_)) {
+						tasks_def.experimental_attr_span = Some(span);
+					}
+					tasks = Some(tasks_def);
 
 					// replace item with a no-op because it will be handled by the expansion of tasks
 					*item = syn::Item::Verbatim(quote::quote!());
@@ -179,6 +189,9 @@ impl Def {
 				Some(PalletAttr::ExtraConstants(_)) =>
 					extra_constants =
 						Some(extra_constants::ExtraConstantsDef::try_from(item)?),
+				Some(PalletAttr::ViewFunctions(span)) if view_functions.is_none() =>
+					view_functions =
+						Some(view_functions::ViewFunctionsImplDef::try_from(span, item)?),
 				Some(PalletAttr::Composite(span)) => {
 					let composite =
 						composite::CompositeDef::try_from(span, &frame_support, item)?;
@@ -237,6 +250,7 @@ impl Def {
 			tasks,
 			task_enum,
 			extra_constants,
+			view_functions,
 			genesis_config,
 			genesis_build,
 			validate_unsigned,
@@ -258,7 +272,7 @@ impl Def {
 		Ok(def)
 	}
 
-	/// Performs extra logic checks necessary for the `#[pallet::tasks_experimental]` feature.
+	/// Performs extra logic checks necessary for the `#[pallet::tasks]` feature.
 	fn resolve_tasks(
 		item_span: &proc_macro2::Span,
 		tasks: &mut Option<tasks::TasksDef>,
@@ -274,15 +288,12 @@ impl Def {
 		// ensure that if `task_enum` is specified, `tasks` is also specified
 		match (&task_enum, &tasks) {
 			(Some(_), None) =>
-				return Err(syn::Error::new(
-					*item_span,
-					"Missing `#[pallet::tasks_experimental]` impl",
-				)),
+				return Err(syn::Error::new(*item_span, "Missing `#[pallet::tasks]` impl")),
 			(None, Some(tasks)) =>
 				if tasks.tasks_attr.is_none() {
 					return Err(syn::Error::new(
 						tasks.item_impl.impl_token.span(),
-						"A `#[pallet::tasks_experimental]` attribute must be attached to your `Task` impl if the \
+						"A `#[pallet::tasks]` attribute must be attached to your `Task` impl if the \
 						task enum has been omitted",
 					));
 				} else {
@@ -404,6 +415,9 @@ impl Def {
 		if let Some(extra_constants) = &self.extra_constants {
 			instances.extend_from_slice(&extra_constants.instances[..]);
 		}
+		if let Some(view_functions) = &self.view_functions {
+			instances.extend_from_slice(&view_functions.instances[..]);
+		}
 		if let Some(task_enum) = &self.task_enum {
 			instances.push(task_enum.instance_usage.clone());
 		}
@@ -542,6 +556,7 @@ impl GenericKind {
 mod keyword {
 	syn::custom_keyword!(origin);
 	syn::custom_keyword!(call);
+	syn::custom_keyword!(tasks);
 	syn::custom_keyword!(tasks_experimental);
 	syn::custom_keyword!(task_enum);
 	syn::custom_keyword!(task_list);
@@ -563,6 +578,7 @@ mod keyword {
 	syn::custom_keyword!(pallet);
 	syn::custom_keyword!(extra_constants);
 	syn::custom_keyword!(composite_enum);
+	syn::custom_keyword!(view_functions);
 }
 
 /// The possible values for the `#[pallet::config]` attribute.
@@ -638,6 +654,7 @@ enum PalletAttr {
 	RuntimeCall(Option<InheritedCallWeightAttr>, proc_macro2::Span),
 	Error(proc_macro2::Span),
 	Tasks(proc_macro2::Span),
+	TasksExperimental(proc_macro2::Span),
 	TaskList(proc_macro2::Span),
 	TaskCondition(proc_macro2::Span),
 	TaskIndex(proc_macro2::Span),
@@ -652,6 +669,7 @@ enum PalletAttr {
 	TypeValue(proc_macro2::Span),
 	ExtraConstants(proc_macro2::Span),
 	Composite(proc_macro2::Span),
+	ViewFunctions(proc_macro2::Span),
 }
 
 impl PalletAttr {
@@ -661,6 +679,7 @@ impl PalletAttr {
 			Self::Pallet(span) => *span,
 			Self::Hooks(span) => *span,
 			Self::Tasks(span) => *span,
+			Self::TasksExperimental(span) => *span,
 			Self::TaskCondition(span) => *span,
 			Self::TaskIndex(span) => *span,
 			Self::TaskList(span) => *span,
@@ -677,6 +696,7 @@ impl PalletAttr {
 			Self::TypeValue(span) => *span,
 			Self::ExtraConstants(span) => *span,
 			Self::Composite(span) => *span,
+			Self::ViewFunctions(span) => *span,
 		}
 	}
 }
@@ -746,8 +766,13 @@ impl syn::parse::Parse for PalletAttr {
 				false => Some(InheritedCallWeightAttr::parse(&content)?),
 			};
 			Ok(PalletAttr::RuntimeCall(attr, span))
+		} else if lookahead.peek(keyword::tasks) {
+			Ok(PalletAttr::Tasks(content.parse::<keyword::tasks>()?.span()))
 		} else if lookahead.peek(keyword::tasks_experimental) {
-			Ok(PalletAttr::Tasks(content.parse::<keyword::tasks_experimental>()?.span()))
+			// Deprecated alias of `tasks`, kept for pallets written before stabilisation.
+			Ok(PalletAttr::TasksExperimental(
+				content.parse::<keyword::tasks_experimental>()?.span(),
+			))
 		} else if lookahead.peek(keyword::task_enum) {
 			Ok(PalletAttr::RuntimeTask(content.parse::<keyword::task_enum>()?.span()))
 		} else if lookahead.peek(keyword::task_condition) {
@@ -778,6 +803,8 @@ impl syn::parse::Parse for PalletAttr {
 			Ok(PalletAttr::ExtraConstants(content.parse::<keyword::extra_constants>()?.span()))
 		} else if lookahead.peek(keyword::composite_enum) {
 			Ok(PalletAttr::Composite(content.parse::<keyword::composite_enum>()?.span()))
+		} else if lookahead.peek(keyword::view_functions) {
+			Ok(PalletAttr::ViewFunctions(content.parse::<keyword::view_functions>()?.span()))
 		} else {
 			Err(lookahead.error())
 		}
@@ -41,7 +41,7 @@ use syn::{
 pub mod keywords {
 	use syn::custom_keyword;
 
-	custom_keyword!(tasks_experimental);
+	custom_keyword!(tasks);
 	custom_keyword!(task_enum);
 	custom_keyword!(task_list);
 	custom_keyword!(task_condition);
@@ -50,7 +50,7 @@ pub mod keywords {
 	custom_keyword!(pallet);
 }
 
-/// Represents the `#[pallet::tasks_experimental]` attribute and its attached item. Also includes
+/// Represents the `#[pallet::tasks]` attribute and its attached item. Also includes
 /// metadata about the linked [`TaskEnumDef`] if applicable.
 #[derive(Clone, Debug)]
 pub struct TasksDef {
@@ -59,6 +59,9 @@ pub struct TasksDef {
 	pub item_impl: ItemImpl,
 	pub enum_ident: Ident,
 	pub enum_arguments: PathArguments,
+	/// Span of the deprecated `#[pallet::tasks_experimental]` attribute, if it was used in place
+	/// of `#[pallet::tasks]`.
+	pub experimental_attr_span: Option<proc_macro2::Span>,
 }
 
 impl syn::parse::Parse for TasksDef {
@@ -72,7 +75,7 @@ impl syn::parse::Parse for TasksDef {
 		if let Some(extra_tasks_attr) = tasks_attrs.get(1) {
 			return Err(Error::new(
 				extra_tasks_attr.span(),
-				"unexpected extra `#[pallet::tasks_experimental]` attribute",
+				"unexpected extra `#[pallet::tasks]` attribute",
 			))
 		}
 		let tasks: Vec<TaskDef> = if tasks_attr.is_some() {
@@ -112,14 +115,21 @@ impl syn::parse::Parse for TasksDef {
 		let enum_ident = last_seg.ident.clone();
 		let enum_arguments = last_seg.arguments.clone();
 
-		Ok(TasksDef { tasks_attr, item_impl, tasks, enum_ident, enum_arguments })
+		Ok(TasksDef {
+			tasks_attr,
+			item_impl,
+			tasks,
+			enum_ident,
+			enum_arguments,
+			experimental_attr_span: None,
+		})
 	}
 }
 
-/// Parsing for a `#[pallet::tasks_experimental]` attr.
-pub type PalletTasksAttr = PalletTaskAttr<keywords::tasks_experimental>;
+/// Parsing for a `#[pallet::tasks]` attr.
+pub type PalletTasksAttr = PalletTaskAttr<keywords::tasks>;
 
-/// Parsing for any of the attributes that can be used within a `#[pallet::tasks_experimental]`
+/// Parsing for any of the attributes that can be used within a `#[pallet::tasks]`
 /// [`ItemImpl`].
 pub type TaskAttr = PalletTaskAttr<TaskAttrMeta>;
 
@@ -519,7 +529,8 @@ fn partition_tasks_attrs(item_impl: &ItemImpl) -> (Vec<syn::Attribute>, Vec<syn:
 		else {
 			return false
 		};
-		prefix.ident == "pallet" && suffix.ident == "tasks_experimental"
+		prefix.ident == "pallet" &&
+			(suffix.ident == "tasks" || suffix.ident == "tasks_experimental")
 	})
 }
 
@@ -532,7 +543,8 @@ fn partition_task_attrs(item: &ImplItemFn) -> (Vec<syn::Attribute>, Vec<syn::Att
 		// N.B: the `PartialEq` impl between `Ident` and `&str` is more efficient than
 		// parsing and makes no stack or heap allocations
 		prefix.ident == "pallet" &&
-			(suffix.ident == "tasks_experimental" ||
+			(suffix.ident == "tasks" ||
+				suffix.ident == "tasks_experimental" ||
 				suffix.ident == "task_list" ||
 				suffix.ident == "task_condition" ||
 				suffix.ident == "task_weight" ||
@@ -584,21 +596,21 @@ fn test_parse_task_condition() {
 
 #[test]
 fn test_parse_tasks_attr() {
-	parse2::<PalletTasksAttr>(quote!(#[pallet::tasks_experimental])).unwrap();
+	parse2::<PalletTasksAttr>(quote!(#[pallet::tasks])).unwrap();
 	assert_parse_error_matches!(
 		parse2::<PalletTasksAttr>(quote!(#[pallet::taskss])),
-		"expected `tasks_experimental`"
+		"expected `tasks`"
 	);
 	assert_parse_error_matches!(
 		parse2::<PalletTasksAttr>(quote!(#[pallet::tasks_])),
-		"expected `tasks_experimental`"
+		"expected `tasks`"
 	);
 	assert_parse_error_matches!(
 		parse2::<PalletTasksAttr>(quote!(#[pal::tasks])),
 		"expected `pallet`"
 	);
 	assert_parse_error_matches!(
-		parse2::<PalletTasksAttr>(quote!(#[pallet::tasks_experimental()])),
+		parse2::<PalletTasksAttr>(quote!(#[pallet::tasks()])),
 		"unexpected token"
 	);
 }
@@ -607,7 +619,7 @@ fn test_parse_tasks_attr() {
 fn test_parse_tasks_def_basic() {
 	simulate_manifest_dir("../../examples/basic", || {
 		let parsed = parse2::<TasksDef>(quote! {
-			#[pallet::tasks_experimental]
+			#[pallet::tasks]
 			impl<T: Config<I>, I: 'static> Pallet<T, I> {
 				/// Add a pair of numbers into the totals and remove them.
 				#[pallet::task_list(Numbers::<T, I>::iter_keys())]
@@ -633,7 +645,7 @@ fn test_parse_tasks_def_basic() {
 fn test_parse_tasks_def_basic_increment_decrement() {
 	simulate_manifest_dir("../../examples/basic", || {
 		let parsed = parse2::<TasksDef>(quote! {
-			#[pallet::tasks_experimental]
+			#[pallet::tasks]
 			impl<T: Config<I>, I: 'static> Pallet<T, I> {
 				/// Get the value and check if it can be incremented
 				#[pallet::task_index(0)]
@@ -686,7 +698,7 @@ fn test_parse_tasks_def_duplicate_index() {
 	simulate_manifest_dir("../../examples/basic", || {
 		assert_parse_error_matches!(
 			parse2::<TasksDef>(quote! {
-				#[pallet::tasks_experimental]
+				#[pallet::tasks]
 				impl<T: Config<I>, I: 'static> Pallet<T, I> {
 					#[pallet::task_list(Something::iter())]
 					#[pallet::task_condition(|i| i % 2 == 0)]
@@ -715,7 +727,7 @@ fn test_parse_tasks_def_missing_task_list() {
 	simulate_manifest_dir("../../examples/basic", || {
 		assert_parse_error_matches!(
 			parse2::<TasksDef>(quote! {
-				#[pallet::tasks_experimental]
+				#[pallet::tasks]
 				impl<T: Config<I>, I: 'static> Pallet<T, I> {
 					#[pallet::task_condition(|i| i % 2 == 0)]
 					#[pallet::task_index(0)]
@@ -734,7 +746,7 @@ fn test_parse_tasks_def_missing_task_condition() {
 	simulate_manifest_dir("../../examples/basic", || {
 		assert_parse_error_matches!(
 			parse2::<TasksDef>(quote! {
-				#[pallet::tasks_experimental]
+				#[pallet::tasks]
 				impl<T: Config<I>, I: 'static> Pallet<T, I> {
 					#[pallet::task_list(Something::iter())]
 					#[pallet::task_index(0)]
@@ -753,7 +765,7 @@ fn test_parse_tasks_def_missing_task_index() {
 	simulate_manifest_dir("../../examples/basic", || {
 		assert_parse_error_matches!(
 			parse2::<TasksDef>(quote! {
-				#[pallet::tasks_experimental]
+				#[pallet::tasks]
 				impl<T: Config<I>, I: 'static> Pallet<T, I> {
 					#[pallet::task_condition(|i| i % 2 == 0)]
 					#[pallet::task_list(Something::iter())]
@@ -772,7 +784,7 @@ fn test_parse_tasks_def_missing_task_weight() {
 	simulate_manifest_dir("../../examples/basic", || {
 		assert_parse_error_matches!(
 			parse2::<TasksDef>(quote! {
-				#[pallet::tasks_experimental]
+				#[pallet::tasks]
 				impl<T: Config<I>, I: 'static> Pallet<T, I> {
 					#[pallet::task_condition(|i| i % 2 == 0)]
 					#[pallet::task_list(Something::iter())]
@@ -792,7 +804,7 @@ fn test_parse_tasks_def_unexpected_extra_task_list_attr() {
 	simulate_manifest_dir("../../examples/basic", || {
 		assert_parse_error_matches!(
 			parse2::<TasksDef>(quote! {
-				#[pallet::tasks_experimental]
+				#[pallet::tasks]
 				impl<T: Config<I>, I: 'static> Pallet<T, I> {
 					#[pallet::task_condition(|i| i % 2 == 0)]
 					#[pallet::task_index(0)]
@@ -814,7 +826,7 @@ fn test_parse_tasks_def_unexpected_extra_task_condition_attr() {
 	simulate_manifest_dir("../../examples/basic", || {
 		assert_parse_error_matches!(
 			parse2::<TasksDef>(quote! {
-				#[pallet::tasks_experimental]
+				#[pallet::tasks]
 				impl<T: Config<I>, I: 'static> Pallet<T, I> {
 					#[pallet::task_condition(|i| i % 2 == 0)]
 					#[pallet::task_condition(|i| i % 4 == 0)]
@@ -836,7 +848,7 @@ fn test_parse_tasks_def_unexpected_extra_task_index_attr() {
 	simulate_manifest_dir("../../examples/basic", || {
 		assert_parse_error_matches!(
 			parse2::<TasksDef>(quote! {
-				#[pallet::tasks_experimental]
+				#[pallet::tasks]
 				impl<T: Config<I>, I: 'static> Pallet<T, I> {
 					#[pallet::task_condition(|i| i % 2 == 0)]
 					#[pallet::task_index(0)]
@@ -858,11 +870,11 @@ fn test_parse_tasks_def_extra_tasks_attribute() {
 	simulate_manifest_dir("../../examples/basic", || {
 		assert_parse_error_matches!(
 			parse2::<TasksDef>(quote! {
-				#[pallet::tasks_experimental]
-				#[pallet::tasks_experimental]
+				#[pallet::tasks]
+				#[pallet::tasks]
 				impl<T: Config<I>, I: 'static> Pallet<T, I> {}
 			}),
-			r"unexpected extra `#\[pallet::tasks_experimental\]` attribute"
+			r"unexpected extra `#\[pallet::tasks\]` attribute"
 		);
 	});
 }
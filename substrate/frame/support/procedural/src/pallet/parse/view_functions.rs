@@ -0,0 +1,155 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use super::helper;
+use frame_support_procedural_tools::get_doc_literals;
+use inflector::Inflector;
+use syn::spanned::Spanned;
+
+/// Definition of view functions, typically `impl<T: Config> Pallet<T> { ... }`.
+pub struct ViewFunctionsImplDef {
+	/// The where_clause used.
+	pub where_clause: Option<syn::WhereClause>,
+	/// The span of the `pallet::view_functions` attribute.
+	pub attr_span: proc_macro2::Span,
+	/// A set of usage of instance, must be check for consistency with trait.
+	pub instances: Vec<helper::InstanceUsage>,
+	/// The view functions defined.
+	pub view_functions: Vec<ViewFunctionDef>,
+}
+
+impl ViewFunctionsImplDef {
+	pub fn try_from(attr_span: proc_macro2::Span, item: &mut syn::Item) -> syn::Result<Self> {
+		let syn::Item::Impl(item_impl) = item else {
+			return Err(syn::Error::new(
+				item.span(),
+				"Invalid pallet::view_functions, expected item impl",
+			))
+		};
+
+		let instances = vec![
+			helper::check_impl_gen(&item_impl.generics, item_impl.impl_token.span())?,
+			helper::check_pallet_struct_usage(&item_impl.self_ty)?,
+		];
+
+		if let Some((_, _, for_)) = item_impl.trait_ {
+			let msg = "Invalid pallet::view_functions, expected no trait ident as in \
+				`impl<..> Pallet<..> { .. }`";
+			return Err(syn::Error::new(for_.span(), msg))
+		}
+
+		let mut view_functions = Vec::new();
+		for impl_item in &item_impl.items {
+			let syn::ImplItem::Fn(method) = impl_item else {
+				let msg = "Invalid pallet::view_functions, only methods accepted";
+				return Err(syn::Error::new(impl_item.span(), msg))
+			};
+			view_functions.push(ViewFunctionDef::try_from(method)?);
+		}
+
+		Ok(Self {
+			where_clause: item_impl.generics.where_clause.clone(),
+			attr_span,
+			instances,
+			view_functions,
+		})
+	}
+}
+
+/// Definition of a single view function.
+pub struct ViewFunctionDef {
+	/// Name of the function.
+	pub name: syn::Ident,
+	/// The doc associated.
+	pub docs: Vec<syn::Expr>,
+	/// The arguments of the function, as `(name, type)`.
+	pub args: Vec<(syn::Ident, Box<syn::Type>)>,
+	/// The type returned by the function.
+	pub return_type: syn::Type,
+	/// Attributes of the function.
+	pub attrs: Vec<syn::Attribute>,
+}
+
+impl ViewFunctionDef {
+	fn try_from(method: &syn::ImplItemFn) -> syn::Result<Self> {
+		if !matches!(method.vis, syn::Visibility::Public(_)) {
+			let msg = "Invalid pallet::view_functions, view function must be public: \
+				`pub fn`";
+			let span = match method.vis {
+				syn::Visibility::Inherited => method.sig.span(),
+				_ => method.vis.span(),
+			};
+			return Err(syn::Error::new(span, msg))
+		}
+
+		if !method.sig.generics.params.is_empty() {
+			let msg = "Invalid pallet::view_functions, view function must have 0 generics";
+			return Err(syn::Error::new(method.sig.generics.params[0].span(), msg))
+		}
+
+		let syn::ReturnType::Type(_, return_type) = &method.sig.output else {
+			let msg = "Invalid pallet::view_functions, view function must return a value";
+			return Err(syn::Error::new(method.sig.span(), msg))
+		};
+
+		let mut args = Vec::new();
+		for input in &method.sig.inputs {
+			let syn::FnArg::Typed(arg) = input else {
+				let msg = "Invalid pallet::view_functions, view function must not take `self`";
+				return Err(syn::Error::new(input.span(), msg))
+			};
+			let syn::Pat::Ident(ident) = &*arg.pat else {
+				let msg = "Invalid pallet::view_functions, argument must be an ident";
+				return Err(syn::Error::new(arg.pat.span(), msg))
+			};
+			args.push((ident.ident.clone(), arg.ty.clone()));
+		}
+
+		Ok(Self {
+			name: method.sig.ident.clone(),
+			docs: get_doc_literals(&method.attrs),
+			args,
+			return_type: *return_type.clone(),
+			attrs: method.attrs.clone(),
+		})
+	}
+
+	/// Name of the struct generated to hold the arguments of the view function.
+	pub fn view_function_struct_ident(&self) -> syn::Ident {
+		syn::Ident::new(
+			&format!("{}ViewFunction", self.name.to_string().to_pascal_case()),
+			self.name.span(),
+		)
+	}
+
+	/// The signature of the view function, used to derive its id.
+	pub fn signature(&self) -> String {
+		let args = self
+			.args
+			.iter()
+			.map(|(name, ty)| format!("{}: {}", name, quote::quote!(#ty)))
+			.collect::<Vec<_>>()
+			.join(", ");
+		let return_type = &self.return_type;
+		format!("{}({}) -> {}", self.name, args, quote::quote!(#return_type))
+	}
+
+	/// The suffix of the id of the view function, which is the hash of its signature.
+	pub fn view_function_id_suffix_bytes(&self) -> [u8; 16] {
+		sp_crypto_hashing::twox_128(self.signature().as_bytes())
+	}
+}
@@ -182,6 +182,7 @@ fn construct_runtime_final_expansion(
 	let mut slash_reason = None;
 	let mut lock_id = None;
 	let mut task = None;
+	let mut view_function = None;
 
 	for runtime_type in runtime_types.iter() {
 		match runtime_type {
@@ -224,6 +225,9 @@ fn construct_runtime_final_expansion(
 			RuntimeType::RuntimeTask(_) => {
 				task = Some(expand::expand_outer_task(&name, &pallets, &scrate));
 			},
+			RuntimeType::RuntimeViewFunction(_) => {
+				view_function = Some(expand::expand_outer_view_function(&name, &pallets, &scrate));
+			},
 		}
 	}
 
@@ -301,6 +305,8 @@ fn construct_runtime_final_expansion(
 
 		#task
 
+		#view_function
+
 		#metadata
 
 		#outer_config
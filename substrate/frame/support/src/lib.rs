@@ -86,6 +86,7 @@ pub mod storage;
 #[cfg(test)]
 mod tests;
 pub mod traits;
+pub mod view_functions;
 pub mod weights;
 #[doc(hidden)]
 pub mod unsigned {
@@ -897,8 +898,8 @@ pub mod pallet_prelude {
 			bounded_btree_set::BoundedBTreeSet,
 			bounded_vec::BoundedVec,
 			types::{
-				CountedStorageMap, CountedStorageNMap, Key as NMapKey, OptionQuery, ResultQuery,
-				StorageDoubleMap, StorageMap, StorageNMap, StorageValue, ValueQuery,
+				CountedStorageMap, CountedStorageNMap, Deposited, Key as NMapKey, OptionQuery,
+				ResultQuery, StorageDoubleMap, StorageMap, StorageNMap, StorageValue, ValueQuery,
 			},
 			weak_bounded_vec::WeakBoundedVec,
 			StorageList,
@@ -1418,6 +1419,53 @@ pub mod pallet_macros {
 	/// args, 0 generics, and some return type.
 	pub use frame_support_procedural::extra_constants;
 
+	/// Declares typed read-only queries of the pallet, callable from outside the runtime.
+	///
+	/// Must be defined like:
+	///
+	/// ```
+	/// #[frame_support::pallet]
+	/// mod pallet {
+	/// # 	use frame_support::pallet_prelude::*;
+	/// #
+	/// 	#[pallet::pallet]
+	/// 	pub struct Pallet<T>(_);
+	///
+	/// # 	#[pallet::config]
+	/// # 	pub trait Config: frame_system::Config {}
+	/// #
+	/// 	#[pallet::storage]
+	/// 	pub type SomeMap<T> = StorageMap<_, Twox64Concat, u32, u32>;
+	///
+	/// 	#[pallet::view_functions]
+	/// 	impl<T: Config> Pallet<T> // $optional_where_clause
+	/// 	{
+	/// 		/// Query a value of `SomeMap`.
+	/// 		pub fn get_value_with_arg(key: u32) -> Option<u32> {
+	/// 			SomeMap::<T>::get(key)
+	/// 		}
+	/// 	}
+	/// }
+	/// ```
+	///
+	/// I.e. a regular rust `impl` block with some optional where clause and public functions
+	/// without receiver and generics, returning some value. The arguments and the return type
+	/// must implement `Encode`, `Decode` and `TypeInfo`.
+	///
+	/// For each function, the macro generates a struct holding its arguments, named after the
+	/// function in upper camel case with a `ViewFunction` suffix (`GetValueWithArgViewFunction`
+	/// above), implementing [`ViewFunction`](crate::view_functions::ViewFunction). A view function
+	/// is identified by a [`ViewFunctionId`](crate::view_functions::ViewFunctionId) made of the
+	/// hash of the pallet name and the hash of the function signature; changing the signature
+	/// therefore changes the id.
+	///
+	/// The view functions are described in the pallet metadata. The runtime aggregates them in
+	/// `RuntimeViewFunction` (to be listed in `#[runtime::derive]` with the
+	/// [`runtime`](crate::runtime) macro), and exposes them by implementing
+	/// [`RuntimeViewFunction`](crate::view_functions::runtime_api::RuntimeViewFunction) with the
+	/// generated `Runtime::execute_view_function`.
+	pub use frame_support_procedural::view_functions;
+
 	#[rustfmt::skip]
 	/// Allows bypassing the `frame_system::Config` supertrait check.
 	///
@@ -2510,9 +2558,10 @@ pub mod pallet_macros {
 	/// - For general usage examples of `deprecated` attribute please refer to <https://doc.rust-lang.org/nightly/reference/attributes/diagnostics.html#the-deprecated-attribute>
 	pub use frame_support_procedural::storage;
 
-	pub use frame_support_procedural::{
-		task_condition, task_index, task_list, task_weight, tasks_experimental,
-	};
+	pub use frame_support_procedural::{task_condition, task_index, task_list, task_weight, tasks};
+
+	#[allow(deprecated)]
+	pub use frame_support_procedural::tasks_experimental;
 
 	/// Allows a pallet to declare a type as an origin.
 	///
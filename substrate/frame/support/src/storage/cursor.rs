@@ -0,0 +1,341 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Bounded, resumable iteration over storage maps.
+//!
+//! Iterating a whole map within a single block is unbounded in both weight and proof size. The
+//! [`IterableStorage`] trait instead processes a map in steps: each step handles at most a given
+//! number of entries and never consumes more weight than is left in a [`WeightMeter`]. A step
+//! returns a [`StorageCursor`] from which the next step resumes, or `None` once the end of the map
+//! is reached. This matches the contract of [`SteppedMigration`], so that a step can directly back
+//! a multi-block migration or an `on_idle` hook:
+//!
+//! ```ignore
+//! fn step(
+//! 	cursor: Option<StorageCursor<Ledgers<T>>>,
+//! 	meter: &mut WeightMeter,
+//! ) -> Result<Option<StorageCursor<Ledgers<T>>>, SteppedMigrationError> {
+//! 	let limits = IterationLimits::new(T::WeightInfo::migrate_ledger());
+//! 	Ok(Ledgers::<T>::translate_bounded(cursor, meter, limits, |_, old: OldLedger| {
+//! 		Some(old.into())
+//! 	}))
+//! }
+//! ```
+//!
+//! The weight of a single entry must be provided by the caller. It should at least account for
+//! reading, decoding and possibly writing the entry, plus whatever the caller does with the entry.
+//! The `storage_iter_item` and `storage_translate_item` benchmarks of `frame-system-benchmarking`
+//! measure the former for a given value size.
+//!
+//! [`SteppedMigration`]: crate::migrations::SteppedMigration
+
+use crate::{
+	storage::unhashed,
+	traits::Get,
+	weights::{Weight, WeightMeter},
+	BoundedVec,
+};
+use alloc::vec::Vec;
+use codec::{Decode, FullCodec};
+
+/// The position of a bounded iteration over the storage map `M`.
+///
+/// This is the raw key of the last entry that was processed; iteration resumes right after it. It
+/// is bounded by the maximum raw key length of the map, so that it can be stored on-chain.
+pub type StorageCursor<M> = BoundedVec<u8, <M as IterableStorage>::MaxKeyLen>;
+
+/// The limits of a single step of a bounded iteration.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IterationLimits {
+	/// The maximum number of entries to process.
+	pub max_items: u32,
+	/// The weight consumed from the meter for every processed entry.
+	pub item_weight: Weight,
+}
+
+impl IterationLimits {
+	/// Process as many entries as the weight meter allows, consuming `item_weight` for each.
+	pub fn new(item_weight: Weight) -> Self {
+		Self { max_items: u32::MAX, item_weight }
+	}
+
+	/// Process at most `max_items` entries.
+	pub fn max_items(self, max_items: u32) -> Self {
+		Self { max_items, ..self }
+	}
+}
+
+/// A storage map whose entries can be iterated in bounded, resumable steps.
+///
+/// Implemented for [`StorageMap`](super::types::StorageMap),
+/// [`StorageDoubleMap`](super::types::StorageDoubleMap) and
+/// [`StorageNMap`](super::types::StorageNMap) with reversible hashers and keys of bounded length.
+///
+/// Entries are visited in lexicographical order of their raw keys. Entries inserted behind the
+/// cursor in between two steps are not visited. Entries whose key or value fail to decode are
+/// skipped, but still count against the limits.
+pub trait IterableStorage: Sized {
+	/// The full key of an entry.
+	type Key;
+	/// The value of an entry.
+	type Value: FullCodec;
+	/// The maximum length of the raw key of an entry.
+	type MaxKeyLen: Get<u32>;
+
+	/// The prefix under which all entries are stored.
+	fn final_prefix() -> [u8; 32];
+
+	/// Decode the key of an entry from its raw key, without the prefix.
+	fn decode_key(raw_key_without_prefix: &[u8]) -> Result<Self::Key, codec::Error>;
+
+	/// Iterate over the entries after `cursor`, calling `f` for each of them.
+	///
+	/// Returns the cursor to resume from, or `None` if all entries were processed.
+	fn iter_bounded(
+		cursor: Option<StorageCursor<Self>>,
+		meter: &mut WeightMeter,
+		limits: IterationLimits,
+		mut f: impl FnMut(Self::Key, Self::Value),
+	) -> Option<StorageCursor<Self>> {
+		step::<Self>(cursor, meter, limits, |raw_key, raw_value| {
+			if let Some((key, value)) = decode_entry::<Self, Self::Value>(raw_key, raw_value) {
+				f(key, value);
+			}
+		})
+	}
+
+	/// Translate the values of the entries after `cursor` from `O` by `f`. Returning `None` from
+	/// `f` removes the entry.
+	///
+	/// Returns the cursor to resume from, or `None` if all entries were processed.
+	fn translate_bounded<O: Decode>(
+		cursor: Option<StorageCursor<Self>>,
+		meter: &mut WeightMeter,
+		limits: IterationLimits,
+		mut f: impl FnMut(Self::Key, O) -> Option<Self::Value>,
+	) -> Option<StorageCursor<Self>> {
+		step::<Self>(cursor, meter, limits, |raw_key, raw_value| {
+			if let Some((key, value)) = decode_entry::<Self, O>(raw_key, raw_value) {
+				match f(key, value) {
+					Some(new) => unhashed::put(raw_key, &new),
+					None => unhashed::kill(raw_key),
+				}
+			}
+		})
+	}
+
+	/// Remove the entries after `cursor`, calling `f` for each of them.
+	///
+	/// Returns the cursor to resume from, or `None` if all entries were removed.
+	fn drain_bounded(
+		cursor: Option<StorageCursor<Self>>,
+		meter: &mut WeightMeter,
+		limits: IterationLimits,
+		mut f: impl FnMut(Self::Key, Self::Value),
+	) -> Option<StorageCursor<Self>> {
+		step::<Self>(cursor, meter, limits, |raw_key, raw_value| {
+			unhashed::kill(raw_key);
+			if let Some((key, value)) = decode_entry::<Self, Self::Value>(raw_key, raw_value) {
+				f(key, value);
+			}
+		})
+	}
+}
+
+/// Process the entries of `M` after `cursor` within `limits`, passing the full raw key and the
+/// raw value of each entry to `process`.
+fn step<M: IterableStorage>(
+	cursor: Option<StorageCursor<M>>,
+	meter: &mut WeightMeter,
+	limits: IterationLimits,
+	mut process: impl FnMut(&[u8], &[u8]),
+) -> Option<StorageCursor<M>> {
+	let prefix = M::final_prefix();
+	let mut previous_key = cursor.map_or_else(|| prefix.to_vec(), Into::into);
+	let mut processed = 0;
+
+	loop {
+		if processed >= limits.max_items || meter.try_consume(limits.item_weight).is_err() {
+			return Some(into_cursor::<M>(previous_key))
+		}
+
+		let key = sp_io::storage::next_key(&previous_key).filter(|k| k.starts_with(&prefix))?;
+		processed += 1;
+
+		if let Some(value) = unhashed::get_raw(&key) {
+			process(&key, &value);
+		}
+		previous_key = key;
+	}
+}
+
+fn decode_entry<M: IterableStorage, V: Decode>(
+	raw_key: &[u8],
+	raw_value: &[u8],
+) -> Option<(M::Key, V)> {
+	let key = M::decode_key(&raw_key[M::final_prefix().len()..]);
+	let value = V::decode(&mut &raw_value[..]);
+
+	match (key, value) {
+		(Ok(key), Ok(value)) => Some((key, value)),
+		_ => {
+			crate::defensive!(
+				"Bounded iteration: failed to decode entry",
+				array_bytes::bytes2hex("0x", raw_key)
+			);
+			None
+		},
+	}
+}
+
+fn into_cursor<M: IterableStorage>(raw_key: Vec<u8>) -> StorageCursor<M> {
+	if raw_key.len() > M::MaxKeyLen::get() as usize {
+		// Resuming from a truncated key revisits some entries, but never skips any.
+		crate::defensive!(
+			"Bounded iteration: key exceeds the maximum key length",
+			array_bytes::bytes2hex("0x", &raw_key)
+		);
+	}
+
+	BoundedVec::truncate_from(raw_key)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		hash::*,
+		storage::types::{Key, StorageDoubleMap, StorageMap, StorageNMap, ValueQuery},
+		traits::StorageInstance,
+	};
+	use sp_io::TestExternalities;
+
+	struct Prefix;
+	impl StorageInstance for Prefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "foo";
+	}
+
+	type Map = StorageMap<Prefix, Twox64Concat, u32, u64, ValueQuery>;
+	type DoubleMap = StorageDoubleMap<Prefix, Blake2_128Concat, u16, Twox64Concat, u32, u64>;
+	type NMap = StorageNMap<Prefix, (Key<Identity, u8>, Key<Twox64Concat, u16>), u64>;
+
+	const ITEM: Weight = Weight::from_parts(10, 1);
+
+	#[test]
+	fn iter_bounded_resumes_from_cursor() {
+		TestExternalities::default().execute_with(|| {
+			(0..10u32).for_each(|i| Map::insert(i, i as u64 * 2));
+
+			let mut seen = Vec::new();
+			let mut cursor = None;
+			let mut steps = 0;
+			loop {
+				let mut meter = WeightMeter::new();
+				cursor = Map::iter_bounded(
+					cursor,
+					&mut meter,
+					IterationLimits::new(ITEM).max_items(3),
+					|k, v| seen.push((k, v)),
+				);
+				steps += 1;
+				if cursor.is_none() {
+					break
+				}
+				assert_eq!(meter.consumed(), ITEM * 3);
+			}
+
+			// 10 entries in steps of 3, the last step only discovers the end.
+			assert_eq!(steps, 4);
+			seen.sort();
+			assert_eq!(seen, (0..10u32).map(|i| (i, i as u64 * 2)).collect::<Vec<_>>());
+		});
+	}
+
+	#[test]
+	fn iter_bounded_respects_weight() {
+		TestExternalities::default().execute_with(|| {
+			(0..10u32).for_each(|i| Map::insert(i, 1));
+
+			let mut meter = WeightMeter::with_limit(ITEM * 4 + Weight::from_parts(5, 0));
+			let mut count = 0;
+			let cursor =
+				Map::iter_bounded(None, &mut meter, IterationLimits::new(ITEM), |_, _| count += 1);
+
+			assert_eq!(count, 4);
+			assert_eq!(meter.consumed(), ITEM * 4);
+			assert!(cursor.is_some());
+
+			// Without any weight left, no progress is made and the cursor stays put.
+			let mut meter = WeightMeter::with_limit(Weight::zero());
+			let same = Map::iter_bounded(
+				cursor.clone(),
+				&mut meter,
+				IterationLimits::new(ITEM),
+				|_, _| count += 1,
+			);
+			assert_eq!(same, cursor);
+			assert_eq!(count, 4);
+		});
+	}
+
+	#[test]
+	fn translate_and_drain_bounded_work() {
+		TestExternalities::default().execute_with(|| {
+			(0..5u16).for_each(|i| {
+				DoubleMap::insert(i, i as u32, 1);
+				unhashed::put(&NMap::hashed_key_for((i as u8, i)), &(i as u32));
+			});
+
+			let mut cursor = None;
+			loop {
+				cursor = NMap::translate_bounded(
+					cursor,
+					&mut WeightMeter::new(),
+					IterationLimits::new(ITEM).max_items(2),
+					|(k1, k2), old: u32| (k1 % 2 == 0).then(|| old as u64 + k2 as u64),
+				);
+				if cursor.is_none() {
+					break
+				}
+			}
+			assert_eq!(
+				NMap::iter().collect::<Vec<_>>(),
+				vec![((0, 0), 0), ((2, 2), 4), ((4, 4), 8)],
+			);
+
+			let mut drained = Vec::new();
+			let mut cursor = None;
+			loop {
+				cursor = DoubleMap::drain_bounded(
+					cursor,
+					&mut WeightMeter::new(),
+					IterationLimits::new(ITEM).max_items(2),
+					|k, v| drained.push((k, v)),
+				);
+				if cursor.is_none() {
+					break
+				}
+			}
+			drained.sort();
+			assert_eq!(drained, (0..5u16).map(|i| ((i, i as u32), 1)).collect::<Vec<_>>());
+			assert_eq!(DoubleMap::iter().count(), 0);
+		});
+	}
+}
@@ -31,6 +31,7 @@ use sp_core::storage::ChildInfo;
 use sp_runtime::generic::{Digest, DigestItem};
 
 pub use self::{
+	cursor::{IterableStorage, IterationLimits, StorageCursor},
 	stream_iter::StorageStreamIter,
 	transactional::{
 		in_storage_layer, with_storage_layer, with_transaction, with_transaction_unchecked,
@@ -44,6 +45,7 @@ pub mod bounded_btree_map;
 pub mod bounded_btree_set;
 pub mod bounded_vec;
 pub mod child;
+pub mod cursor;
 #[doc(hidden)]
 pub mod generator;
 pub mod hashed;
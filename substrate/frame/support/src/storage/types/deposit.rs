@@ -0,0 +1,517 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Deposit-backed storage entries, see [`Deposited`].
+
+use crate::{
+	storage::{
+		types::{QueryKindTrait, StorageDoubleMap, StorageMap},
+		unhashed, with_storage_layer,
+	},
+	traits::{Consideration, Footprint, Get, StorageInstance},
+	StorageHasher,
+};
+use codec::{Decode, Encode, EncodeLike, FullCodec, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::{DispatchError, DispatchResult, RuntimeDebug};
+
+/// A storage value together with the account that paid for it and the ticket holding its deposit.
+///
+/// Using it as the value of a [`StorageMap`] or [`StorageDoubleMap`] ties each entry to a
+/// depositor and a [`Consideration`] ticket paying for the entry's [`Footprint`]: its storage
+/// key, depositor, ticket and value. The `*_with_deposit` methods of these maps take the deposit
+/// when an entry is created, re-price it whenever the entry is mutated and release it when the
+/// entry is removed. `poke_deposit` re-prices an entry without changing it, e.g. after the deposit
+/// parameters changed.
+///
+/// Every such method runs in its own storage layer: if the deposit can not be taken or released,
+/// neither the entry nor the ticket is changed.
+///
+/// A `Deposited` can only be created by taking its deposit, and can not be cloned. The plain
+/// `remove` and `take` methods of the maps don't release the deposit: only use the
+/// `*_with_deposit` methods to remove entries.
+#[must_use = "the deposit is only released by `Deposited::release`"]
+#[derive(Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct Deposited<AccountId, Ticket, Value> {
+	/// The account the deposit is taken from and released to.
+	depositor: AccountId,
+	/// The consideration ticket for the footprint of the entry.
+	ticket: Ticket,
+	/// The stored value.
+	value: Value,
+}
+
+impl<AccountId, Ticket, Value> Deposited<AccountId, Ticket, Value>
+where
+	AccountId: Encode + Clone,
+	Ticket: Consideration<AccountId, Footprint> + MaxEncodedLen,
+	Value: Encode,
+{
+	/// The footprint a deposit is taken for: one item, as large as the storage `key` plus the
+	/// encoded entry.
+	///
+	/// The ticket is accounted for with its maximum encoded length, as it is only known once the
+	/// deposit is taken.
+	pub fn footprint(key: &[u8], depositor: &AccountId, value: &Value) -> Footprint {
+		Footprint::from_parts(
+			1,
+			key.len() + depositor.encoded_size() + Ticket::max_encoded_len() + value.encoded_size(),
+		)
+	}
+
+	/// Take the deposit for storing `value` under the storage `key` from `depositor`.
+	pub fn new(key: &[u8], depositor: AccountId, value: Value) -> Result<Self, DispatchError> {
+		let ticket = Ticket::new(&depositor, Self::footprint(key, &depositor, &value))?;
+		Ok(Self { depositor, ticket, value })
+	}
+
+	/// The account the deposit is taken from.
+	pub fn depositor(&self) -> &AccountId {
+		&self.depositor
+	}
+
+	/// The ticket holding the deposit.
+	pub fn ticket(&self) -> &Ticket {
+		&self.ticket
+	}
+
+	/// The stored value.
+	pub fn value(&self) -> &Value {
+		&self.value
+	}
+
+	/// Re-price the deposit for the current value, stored under the storage `key`.
+	///
+	/// Returns whether the ticket changed.
+	pub fn poke(&mut self, key: &[u8]) -> Result<bool, DispatchError> {
+		let footprint = Self::footprint(key, &self.depositor, &self.value);
+		let ticket = self.ticket.clone().update(&self.depositor, footprint)?;
+		let changed = ticket != self.ticket;
+		self.ticket = ticket;
+		Ok(changed)
+	}
+
+	/// Release the deposit to the depositor and return the value.
+	pub fn release(self) -> Result<Value, DispatchError> {
+		self.ticket.drop(&self.depositor)?;
+		Ok(self.value)
+	}
+
+	/// Mutate the value of `entry`, stored under the storage `key`, with `f`, keeping the deposit
+	/// in line with the result.
+	///
+	/// A created entry is paid for by `depositor`, existing entries keep their depositor.
+	fn try_mutate_entry<R, E, F>(
+		key: &[u8],
+		entry: &mut Option<Self>,
+		depositor: &AccountId,
+		f: F,
+	) -> Result<R, E>
+	where
+		E: From<DispatchError>,
+		F: FnOnce(&mut Option<Value>) -> Result<R, E>,
+	{
+		let (depositor, ticket, mut value) = match entry.take() {
+			Some(Self { depositor, ticket, value }) => (depositor, Some(ticket), Some(value)),
+			None => (depositor.clone(), None, None),
+		};
+		let result = f(&mut value)?;
+
+		*entry = match (ticket, value) {
+			(None, None) => None,
+			(None, Some(value)) => Some(Self::new(key, depositor, value)?),
+			(Some(ticket), Some(value)) => {
+				let ticket = ticket.update(&depositor, Self::footprint(key, &depositor, &value))?;
+				Some(Self { depositor, ticket, value })
+			},
+			(Some(ticket), None) => {
+				ticket.drop(&depositor)?;
+				None
+			},
+		};
+		Ok(result)
+	}
+
+	/// Replace `entry`, stored under the storage `key`, by `value` paid for by `depositor`,
+	/// releasing any previous deposit.
+	fn replace_entry(
+		key: &[u8],
+		entry: &mut Option<Self>,
+		depositor: AccountId,
+		value: Value,
+	) -> DispatchResult {
+		if let Some(old) = entry.take() {
+			old.release()?;
+		}
+		*entry = Some(Self::new(key, depositor, value)?);
+		Ok(())
+	}
+}
+
+/// Mutate the entry stored under the storage `key` with `f`, in a new storage layer.
+fn try_mutate_stored<AccountId, Ticket, Value, R, E, F>(key: &[u8], f: F) -> Result<R, E>
+where
+	AccountId: Decode + Encode,
+	Ticket: Decode + Encode,
+	Value: Decode + Encode,
+	E: From<DispatchError>,
+	F: FnOnce(&mut Option<Deposited<AccountId, Ticket, Value>>) -> Result<R, E>,
+{
+	with_storage_layer(|| {
+		let mut entry = unhashed::get(key);
+		let result = f(&mut entry)?;
+		match entry {
+			Some(entry) => unhashed::put(key, &entry),
+			None => unhashed::kill(key),
+		}
+		Ok(result)
+	})
+}
+
+impl<Prefix, Hasher, Key, AccountId, Ticket, Value, QueryKind, OnEmpty, MaxValues>
+	StorageMap<Prefix, Hasher, Key, Deposited<AccountId, Ticket, Value>, QueryKind, OnEmpty, MaxValues>
+where
+	Prefix: StorageInstance,
+	Hasher: StorageHasher,
+	Key: FullCodec,
+	AccountId: FullCodec + Clone,
+	Ticket: Consideration<AccountId, Footprint> + MaxEncodedLen,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Deposited<AccountId, Ticket, Value>, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+{
+	/// Store `value` under `key`, taking its deposit from `depositor`.
+	///
+	/// The deposit of a previous value is released to its depositor.
+	pub fn insert_with_deposit<KeyArg: EncodeLike<Key>>(
+		key: KeyArg,
+		depositor: AccountId,
+		value: Value,
+	) -> DispatchResult {
+		let key = Self::hashed_key_for(key);
+		try_mutate_stored(&key, |entry| Deposited::replace_entry(&key, entry, depositor, value))
+	}
+
+	/// Mutate the value under `key` if `f` returns `Ok`, adjusting the deposit to the new size.
+	///
+	/// A value created by `f` is paid for by `depositor`; a value removed by `f` has its deposit
+	/// released. Existing values keep their original depositor.
+	pub fn try_mutate_with_deposit<KeyArg, R, E, F>(
+		key: KeyArg,
+		depositor: &AccountId,
+		f: F,
+	) -> Result<R, E>
+	where
+		KeyArg: EncodeLike<Key>,
+		E: From<DispatchError>,
+		F: FnOnce(&mut Option<Value>) -> Result<R, E>,
+	{
+		let key = Self::hashed_key_for(key);
+		try_mutate_stored(&key, |entry| Deposited::try_mutate_entry(&key, entry, depositor, f))
+	}
+
+	/// Remove the value under `key`, releasing its deposit.
+	pub fn remove_with_deposit<KeyArg: EncodeLike<Key>>(
+		key: KeyArg,
+	) -> Result<Option<Value>, DispatchError> {
+		let key = Self::hashed_key_for(key);
+		try_mutate_stored(&key, |entry| entry.take().map(Deposited::release).transpose())
+	}
+
+	/// Re-price the deposit of the value under `key`.
+	///
+	/// Returns whether the deposit changed; `false` if there is no value.
+	pub fn poke_deposit<KeyArg: EncodeLike<Key>>(key: KeyArg) -> Result<bool, DispatchError> {
+		let key = Self::hashed_key_for(key);
+		try_mutate_stored(&key, |entry| entry.as_mut().map_or(Ok(false), |entry| entry.poke(&key)))
+	}
+}
+
+impl<
+		Prefix,
+		Hasher1,
+		Key1,
+		Hasher2,
+		Key2,
+		AccountId,
+		Ticket,
+		Value,
+		QueryKind,
+		OnEmpty,
+		MaxValues,
+	>
+	StorageDoubleMap<
+		Prefix,
+		Hasher1,
+		Key1,
+		Hasher2,
+		Key2,
+		Deposited<AccountId, Ticket, Value>,
+		QueryKind,
+		OnEmpty,
+		MaxValues,
+	>
+where
+	Prefix: StorageInstance,
+	Hasher1: StorageHasher,
+	Hasher2: StorageHasher,
+	Key1: FullCodec,
+	Key2: FullCodec,
+	AccountId: FullCodec + Clone,
+	Ticket: Consideration<AccountId, Footprint> + MaxEncodedLen,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Deposited<AccountId, Ticket, Value>, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+{
+	/// Store `value` under the given keys, taking its deposit from `depositor`.
+	///
+	/// The deposit of a previous value is released to its depositor.
+	pub fn insert_with_deposit<KArg1, KArg2>(
+		k1: KArg1,
+		k2: KArg2,
+		depositor: AccountId,
+		value: Value,
+	) -> DispatchResult
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+	{
+		let key = Self::hashed_key_for(k1, k2);
+		try_mutate_stored(&key, |entry| Deposited::replace_entry(&key, entry, depositor, value))
+	}
+
+	/// Mutate the value under the given keys if `f` returns `Ok`, adjusting the deposit to the new
+	/// size.
+	///
+	/// A value created by `f` is paid for by `depositor`; a value removed by `f` has its deposit
+	/// released. Existing values keep their original depositor.
+	pub fn try_mutate_with_deposit<KArg1, KArg2, R, E, F>(
+		k1: KArg1,
+		k2: KArg2,
+		depositor: &AccountId,
+		f: F,
+	) -> Result<R, E>
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+		E: From<DispatchError>,
+		F: FnOnce(&mut Option<Value>) -> Result<R, E>,
+	{
+		let key = Self::hashed_key_for(k1, k2);
+		try_mutate_stored(&key, |entry| Deposited::try_mutate_entry(&key, entry, depositor, f))
+	}
+
+	/// Remove the value under the given keys, releasing its deposit.
+	pub fn remove_with_deposit<KArg1, KArg2>(
+		k1: KArg1,
+		k2: KArg2,
+	) -> Result<Option<Value>, DispatchError>
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+	{
+		let key = Self::hashed_key_for(k1, k2);
+		try_mutate_stored(&key, |entry| entry.take().map(Deposited::release).transpose())
+	}
+
+	/// Re-price the deposit of the value under the given keys.
+	///
+	/// Returns whether the deposit changed; `false` if there is no value.
+	pub fn poke_deposit<KArg1, KArg2>(k1: KArg1, k2: KArg2) -> Result<bool, DispatchError>
+	where
+		KArg1: EncodeLike<Key1>,
+		KArg2: EncodeLike<Key2>,
+	{
+		let key = Self::hashed_key_for(k1, k2);
+		try_mutate_stored(&key, |entry| entry.as_mut().map_or(Ok(false), |entry| entry.poke(&key)))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{assert_ok, hash::*, storage::unhashed};
+	use sp_io::TestExternalities;
+
+	struct Prefix;
+	impl StorageInstance for Prefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "foo";
+	}
+
+	/// Footprint size of a `Map` entry, without its value: the storage key (pallet and storage
+	/// prefixes, `Blake2_128Concat` of the `u16` key), the depositor and the ticket.
+	const MAP_ENTRY: u64 = 32 + 16 + 2 + 8 + 8;
+
+	/// Footprint size of a `DoubleMap` entry, without its value.
+	const DOUBLE_MAP_ENTRY: u64 = 32 + 16 + 2 + 8 + 1 + 8 + 8;
+
+	/// Largest value size a [`TestTicket`] accepts for a `Map` entry.
+	const MAX_VALUE_SIZE: u64 = 8;
+
+	/// Holds one unit per byte of footprint, tracked in storage so it follows storage layers.
+	#[derive(Clone, Encode, Decode, PartialEq, Eq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+	struct TestTicket(u64);
+
+	fn held(who: u64) -> u64 {
+		unhashed::get_or_default(&(b"held", who).encode())
+	}
+
+	fn set_held(who: u64, amount: u64) {
+		unhashed::put(&(b"held", who).encode(), &amount);
+	}
+
+	impl Consideration<u64, Footprint> for TestTicket {
+		fn new(who: &u64, new: Footprint) -> Result<Self, DispatchError> {
+			if new.size > MAP_ENTRY + MAX_VALUE_SIZE {
+				return Err("TooBig".into())
+			}
+			set_held(*who, held(*who) + new.size);
+			Ok(Self(new.size))
+		}
+		fn update(self, who: &u64, new: Footprint) -> Result<Self, DispatchError> {
+			self.drop(who)?;
+			Self::new(who, new)
+		}
+		fn drop(self, who: &u64) -> Result<(), DispatchError> {
+			set_held(*who, held(*who) - self.0);
+			Ok(())
+		}
+		#[cfg(feature = "runtime-benchmarks")]
+		fn ensure_successful(_: &u64, _: Footprint) {}
+	}
+
+	type Entry = Deposited<u64, TestTicket, Vec<u8>>;
+	type Map = StorageMap<Prefix, Blake2_128Concat, u16, Entry>;
+	type DoubleMap = StorageDoubleMap<Prefix, Blake2_128Concat, u16, Twox64Concat, u8, Entry>;
+
+	#[test]
+	fn footprint_covers_key_and_metadata() {
+		assert_eq!(Map::hashed_key_for(1).len() as u64 + 8 + 8, MAP_ENTRY);
+		assert_eq!(DoubleMap::hashed_key_for(1, 2).len() as u64 + 8 + 8, DOUBLE_MAP_ENTRY);
+		assert_eq!(
+			Entry::footprint(&Map::hashed_key_for(1), &10, &vec![1, 2]),
+			Footprint::from_parts(1, (MAP_ENTRY + 3) as usize)
+		);
+	}
+
+	#[test]
+	fn deposit_follows_map_entry() {
+		TestExternalities::default().execute_with(|| {
+			// Takes one byte for the length prefix plus one per element, on top of the entry.
+			assert_ok!(Map::insert_with_deposit(1, 10, vec![1, 2]));
+			assert_eq!(held(10), MAP_ENTRY + 3);
+
+			assert_ok!(Map::try_mutate_with_deposit(1, &20, |value| {
+				value.as_mut().unwrap().push(3);
+				Ok::<_, DispatchError>(())
+			}));
+			// The original depositor pays for the larger value.
+			assert_eq!((held(10), held(20)), (MAP_ENTRY + 4, 0));
+			assert_eq!(Map::get(1).unwrap().value, vec![1, 2, 3]);
+
+			// Replacing releases the previous deposit.
+			assert_ok!(Map::insert_with_deposit(1, 20, vec![]));
+			assert_eq!((held(10), held(20)), (0, MAP_ENTRY + 1));
+
+			assert_eq!(Map::remove_with_deposit(1), Ok(Some(vec![])));
+			assert_eq!(held(20), 0);
+			assert!(!Map::contains_key(1));
+			assert_eq!(Map::remove_with_deposit(1), Ok(None));
+		});
+	}
+
+	#[test]
+	fn mutate_creates_and_removes_entries() {
+		TestExternalities::default().execute_with(|| {
+			assert_ok!(Map::try_mutate_with_deposit(1, &10, |value| {
+				*value = Some(vec![1]);
+				Ok::<_, DispatchError>(())
+			}));
+			assert_eq!(held(10), MAP_ENTRY + 2);
+			assert_eq!(Map::get(1).unwrap().depositor, 10);
+
+			assert_ok!(Map::try_mutate_with_deposit(1, &20, |value| {
+				*value = None;
+				Ok::<_, DispatchError>(())
+			}));
+			assert_eq!(held(10), 0);
+			assert!(!Map::contains_key(1));
+		});
+	}
+
+	#[test]
+	fn failed_deposit_changes_nothing() {
+		TestExternalities::default().execute_with(|| {
+			assert_ok!(Map::insert_with_deposit(1, 10, vec![1]));
+
+			let grow = Map::try_mutate_with_deposit(1, &10, |value| {
+				*value = Some(vec![0; MAX_VALUE_SIZE as usize]);
+				Ok::<_, DispatchError>(())
+			});
+			assert_eq!(grow, Err("TooBig".into()));
+			assert_eq!(
+				Map::get(1).unwrap(),
+				Entry { depositor: 10, ticket: TestTicket(MAP_ENTRY + 2), value: vec![1] }
+			);
+			assert_eq!(held(10), MAP_ENTRY + 2);
+
+			let replace = Map::insert_with_deposit(1, 20, vec![0; MAX_VALUE_SIZE as usize]);
+			assert_eq!(replace, Err("TooBig".into()));
+			assert_eq!(Map::get(1).unwrap().depositor, 10);
+			assert_eq!((held(10), held(20)), (MAP_ENTRY + 2, 0));
+		});
+	}
+
+	#[test]
+	fn poke_deposit_reprices_entry() {
+		TestExternalities::default().execute_with(|| {
+			// An entry written without its deposit, e.g. by a migration.
+			Map::insert(1, Entry { depositor: 10, ticket: TestTicket(0), value: vec![1, 2] });
+
+			assert_eq!(Map::poke_deposit(1), Ok(true));
+			assert_eq!(Map::get(1).unwrap().ticket, TestTicket(MAP_ENTRY + 3));
+			assert_eq!(held(10), MAP_ENTRY + 3);
+
+			assert_eq!(Map::poke_deposit(1), Ok(false));
+			assert_eq!(Map::poke_deposit(2), Ok(false));
+		});
+	}
+
+	#[test]
+	fn deposit_follows_double_map_entry() {
+		TestExternalities::default().execute_with(|| {
+			assert_ok!(DoubleMap::insert_with_deposit(1, 2, 10, vec![1]));
+			assert_eq!(held(10), DOUBLE_MAP_ENTRY + 2);
+
+			assert_ok!(DoubleMap::try_mutate_with_deposit(1, 2, &10, |value| {
+				value.as_mut().unwrap().clear();
+				Ok::<_, DispatchError>(())
+			}));
+			assert_eq!(held(10), DOUBLE_MAP_ENTRY + 1);
+			assert_eq!(DoubleMap::poke_deposit(1, 2), Ok(false));
+
+			assert_eq!(DoubleMap::remove_with_deposit(1, 2), Ok(Some(vec![])));
+			assert_eq!(held(10), 0);
+			assert!(!DoubleMap::contains_key(1, 2));
+		});
+	}
+}
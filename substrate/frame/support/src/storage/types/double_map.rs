@@ -21,7 +21,8 @@
 use crate::{
 	storage::{
 		types::{OptionQuery, QueryKindTrait, StorageEntryMetadataBuilder},
-		KeyLenOf, StorageAppend, StorageDecodeLength, StoragePrefixedMap, StorageTryAppend,
+		IterableStorage, KeyLenOf, StorageAppend, StorageDecodeLength, StoragePrefixedMap,
+		StorageTryAppend,
 	},
 	traits::{Get, GetDefault, StorageInfo, StorageInstance},
 	StorageHasher, Twox128,
@@ -146,6 +147,35 @@ where
 	}
 }
 
+impl<Prefix, Hasher1, Key1, Hasher2, Key2, Value, QueryKind, OnEmpty, MaxValues> IterableStorage
+	for StorageDoubleMap<Prefix, Hasher1, Key1, Hasher2, Key2, Value, QueryKind, OnEmpty, MaxValues>
+where
+	Prefix: StorageInstance,
+	Hasher1: crate::hash::StorageHasher + crate::ReversibleStorageHasher,
+	Hasher2: crate::hash::StorageHasher + crate::ReversibleStorageHasher,
+	Key1: FullCodec + MaxEncodedLen,
+	Key2: FullCodec + MaxEncodedLen,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+{
+	type Key = (Key1, Key2);
+	type Value = Value;
+	type MaxKeyLen = KeyLenOf<Self>;
+
+	fn final_prefix() -> [u8; 32] {
+		<Self as StoragePrefixedMap<Value>>::final_prefix()
+	}
+
+	fn decode_key(raw_key_without_prefix: &[u8]) -> Result<(Key1, Key2), codec::Error> {
+		let mut key1_material = Hasher1::reverse(raw_key_without_prefix);
+		let key1 = Key1::decode(&mut key1_material)?;
+		let key2 = Key2::decode(&mut Hasher2::reverse(key1_material))?;
+		Ok((key1, key2))
+	}
+}
+
 impl<Prefix, Hasher1, Key1, Hasher2, Key2, Value, QueryKind, OnEmpty, MaxValues>
 	crate::storage::generator::StorageDoubleMap<Key1, Key2, Value>
 	for StorageDoubleMap<Prefix, Hasher1, Key1, Hasher2, Key2, Value, QueryKind, OnEmpty, MaxValues>
@@ -21,7 +21,8 @@
 use crate::{
 	storage::{
 		types::{OptionQuery, QueryKindTrait, StorageEntryMetadataBuilder},
-		KeyLenOf, StorageAppend, StorageDecodeLength, StoragePrefixedMap, StorageTryAppend,
+		IterableStorage, KeyLenOf, StorageAppend, StorageDecodeLength, StoragePrefixedMap,
+		StorageTryAppend,
 	},
 	traits::{Get, GetDefault, StorageInfo, StorageInstance},
 	StorageHasher, Twox128,
@@ -96,6 +97,30 @@ where
 	}
 }
 
+impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues> IterableStorage
+	for StorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues>
+where
+	Prefix: StorageInstance,
+	Hasher: crate::hash::StorageHasher + crate::ReversibleStorageHasher,
+	Key: FullCodec + MaxEncodedLen,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+{
+	type Key = Key;
+	type Value = Value;
+	type MaxKeyLen = KeyLenOf<Self>;
+
+	fn final_prefix() -> [u8; 32] {
+		<Self as StoragePrefixedMap<Value>>::final_prefix()
+	}
+
+	fn decode_key(raw_key_without_prefix: &[u8]) -> Result<Key, codec::Error> {
+		Key::decode(&mut Hasher::reverse(raw_key_without_prefix))
+	}
+}
+
 impl<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues>
 	crate::storage::generator::StorageMap<Key, Value>
 	for StorageMap<Prefix, Hasher, Key, Value, QueryKind, OnEmpty, MaxValues>
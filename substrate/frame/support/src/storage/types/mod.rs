@@ -24,6 +24,7 @@ use sp_metadata_ir::{StorageEntryMetadataIR, StorageEntryModifierIR};
 
 mod counted_map;
 mod counted_nmap;
+mod deposit;
 mod double_map;
 mod key;
 mod map;
@@ -32,6 +33,7 @@ mod value;
 
 pub use counted_map::{CountedStorageMap, CountedStorageMapInstance, Counter};
 pub use counted_nmap::{CountedStorageNMap, CountedStorageNMapInstance};
+pub use deposit::Deposited;
 pub use double_map::StorageDoubleMap;
 pub use key::{
 	EncodeLikeTuple, HasKeyPrefix, HasReversibleKeyPrefix, Key, KeyGenerator,
@@ -21,13 +21,15 @@
 use crate::{
 	storage::{
 		types::{
-			EncodeLikeTuple, HasKeyPrefix, HasReversibleKeyPrefix, OptionQuery, QueryKindTrait,
-			StorageEntryMetadataBuilder, TupleToEncodedIter,
+			EncodeLikeTuple, HasKeyPrefix, HasReversibleKeyPrefix, KeyGeneratorMaxEncodedLen,
+			OptionQuery, QueryKindTrait, ReversibleKeyGenerator, StorageEntryMetadataBuilder,
+			TupleToEncodedIter,
 		},
-		KeyGenerator, PrefixIterator, StorageAppend, StorageDecodeLength, StoragePrefixedMap,
-		StorageTryAppend,
+		IterableStorage, KeyGenerator, KeyLenOf, PrefixIterator, StorageAppend,
+		StorageDecodeLength, StoragePrefixedMap, StorageTryAppend,
 	},
 	traits::{Get, GetDefault, StorageInfo, StorageInstance},
+	StorageHasher, Twox128,
 };
 use alloc::{vec, vec::Vec};
 use codec::{Decode, Encode, EncodeLike, FullCodec, MaxEncodedLen};
@@ -91,6 +93,43 @@ pub struct StorageNMap<
 	MaxValues = GetDefault,
 >(core::marker::PhantomData<(Prefix, Key, Value, QueryKind, OnEmpty, MaxValues)>);
 
+impl<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues> Get<u32>
+	for KeyLenOf<StorageNMap<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues>>
+where
+	Prefix: StorageInstance,
+	Key: KeyGeneratorMaxEncodedLen,
+{
+	fn get() -> u32 {
+		// The `max_len` of all key hashes plus the pallet prefix and storage prefix (which both
+		// are hashed with `Twox128`).
+		let z = Key::key_max_encoded_len() + Twox128::max_len::<()>() * 2;
+		z as u32
+	}
+}
+
+impl<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues> IterableStorage
+	for StorageNMap<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues>
+where
+	Prefix: StorageInstance,
+	Key: ReversibleKeyGenerator + KeyGeneratorMaxEncodedLen,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+{
+	type Key = Key::Key;
+	type Value = Value;
+	type MaxKeyLen = KeyLenOf<Self>;
+
+	fn final_prefix() -> [u8; 32] {
+		<Self as StoragePrefixedMap<Value>>::final_prefix()
+	}
+
+	fn decode_key(raw_key_without_prefix: &[u8]) -> Result<Key::Key, codec::Error> {
+		Key::decode_final_key(raw_key_without_prefix).map(|(key, _)| key)
+	}
+}
+
 impl<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues>
 	crate::storage::generator::StorageNMap<Key, Value>
 	for StorageNMap<Prefix, Key, Value, QueryKind, OnEmpty, MaxValues>
@@ -693,6 +732,16 @@ mod test {
 		}
 	}
 
+	#[test]
+	fn keylenof_works() {
+		type A =
+			StorageNMap<Prefix, (NMapKey<Blake2_128Concat, u16>, NMapKey<Twox64Concat, u32>), u32>;
+		let size = 16 * 2 // Two Twox128
+			+ 16 + 2 // Blake2_128Concat = hash + key
+			+ 8 + 4; // Twox64Concat = hash + key
+		assert_eq!(KeyLenOf::<A>::get(), size);
+	}
+
 	#[test]
 	fn test_1_key() {
 		type A = StorageNMap<Prefix, NMapKey<Blake2_128Concat, u16>, u32, OptionQuery>;
@@ -41,8 +41,8 @@ pub mod __private {
 /// off-chain worker) including methods for enumerating, validating, indexing, and running
 /// tasks of this type.
 pub trait Task: Sized + FullCodec + TypeInfo + Clone + Debug + PartialEq + Eq {
-	/// An [`Iterator`] over tasks of this type used as the return type for `enumerate`.
-	type Enumeration: Iterator;
+	/// An [`Iterator`] over tasks of this type used as the return type for `iter`.
+	type Enumeration: Iterator<Item = Self>;
 
 	/// Inspects the pallet's state and enumerates tasks of this type.
 	fn iter() -> Self::Enumeration;
@@ -68,6 +68,9 @@ pub trait Task: Sized + FullCodec + TypeInfo + Clone + Debug + PartialEq + Eq {
 	fn task_index(&self) -> u32;
 }
 
+/// No tasks at all, for runtimes not using tasks.
+///
+/// `()` is never valid, thus it can't be submitted through `frame_system::Call::do_task`.
 impl Task for () {
 	type Enumeration = IntoIter<Self>;
 
@@ -76,7 +79,7 @@ impl Task for () {
 	}
 
 	fn is_valid(&self) -> bool {
-		true
+		false
 	}
 
 	fn run(&self) -> Result<(), DispatchError> {
@@ -0,0 +1,150 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Traits for querying pallet view functions.
+//!
+//! View functions are read-only queries declared in a pallet with
+//! [`#[pallet::view_functions]`](crate::pallet_macros::view_functions). They are aggregated by
+//! `construct_runtime!`/`#[runtime]` and exposed through the single
+//! [`runtime_api::RuntimeViewFunction`] runtime API, dispatching on a [`ViewFunctionId`].
+
+use alloc::vec::Vec;
+use codec::{Decode, DecodeAll, Encode, Output};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+
+/// The unique identifier for a view function.
+#[derive(Clone, Encode, Decode, RuntimeDebug, TypeInfo, PartialEq, Eq)]
+pub struct ViewFunctionId {
+	/// The part of the id used to dispatch to the pallet declaring the view function.
+	///
+	/// This is the `twox_128` hash of the pallet name.
+	pub prefix: [u8; 16],
+	/// The part of the id used to dispatch to a view function within the pallet.
+	///
+	/// This is the `twox_128` hash of the view function signature.
+	pub suffix: [u8; 16],
+}
+
+impl From<ViewFunctionId> for [u8; 32] {
+	fn from(value: ViewFunctionId) -> Self {
+		let mut output = [0u8; 32];
+		output[..16].copy_from_slice(&value.prefix);
+		output[16..].copy_from_slice(&value.suffix);
+		output
+	}
+}
+
+/// Error returned when dispatching a view function.
+#[derive(Encode, Decode, RuntimeDebug, TypeInfo, PartialEq, Eq)]
+pub enum ViewFunctionDispatchError {
+	/// View functions are not implemented for this runtime.
+	NotImplemented,
+	/// A view function with the given `ViewFunctionId` was not found.
+	NotFound(ViewFunctionId),
+	/// Failed to decode the view function input.
+	Codec,
+}
+
+impl From<codec::Error> for ViewFunctionDispatchError {
+	fn from(_: codec::Error) -> Self {
+		ViewFunctionDispatchError::Codec
+	}
+}
+
+/// Dispatch a view function given its [`ViewFunctionId`].
+///
+/// Implemented by the pallets, dispatching on [`ViewFunctionId::suffix`], and by the runtime,
+/// dispatching on [`ViewFunctionId::prefix`].
+pub trait DispatchViewFunction {
+	/// Execute the view function with the given `id`, decoding its arguments from `input` and
+	/// writing the encoded result to `output`.
+	fn dispatch_view_function<O: Output>(
+		id: &ViewFunctionId,
+		input: &mut &[u8],
+		output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError>;
+}
+
+impl DispatchViewFunction for () {
+	fn dispatch_view_function<O: Output>(
+		_id: &ViewFunctionId,
+		_input: &mut &[u8],
+		_output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError> {
+		Err(ViewFunctionDispatchError::NotImplemented)
+	}
+}
+
+/// The prefix of the [`ViewFunctionId`] of every view function of a pallet.
+///
+/// Automatically implemented for each pallet by the [`pallet`](crate::pallet) macro.
+pub trait ViewFunctionIdPrefix {
+	/// The prefix, unique to the pallet in the runtime.
+	fn prefix() -> [u8; 16];
+}
+
+/// The suffix of the [`ViewFunctionId`] of a view function.
+///
+/// Automatically implemented for each view function by the [`pallet`](crate::pallet) macro.
+pub trait ViewFunctionIdSuffix {
+	/// The suffix, unique to the view function within its pallet.
+	const SUFFIX: [u8; 16];
+}
+
+/// A view function, holding its arguments.
+///
+/// Automatically implemented for each view function by the [`pallet`](crate::pallet) macro.
+pub trait ViewFunction: DecodeAll {
+	/// The identifier of the view function.
+	fn id() -> ViewFunctionId;
+
+	/// The type returned by the view function.
+	type ReturnType: Encode;
+
+	/// Call the view function.
+	fn invoke(self) -> Self::ReturnType;
+
+	/// Decode the view function from `input`, call it and write the encoded result to `output`.
+	fn execute<O: Output>(
+		input: &mut &[u8],
+		output: &mut O,
+	) -> Result<(), ViewFunctionDispatchError> {
+		let view_function = Self::decode_all(input)?;
+		let result = view_function.invoke();
+		Encode::encode_to(&result, output);
+		Ok(())
+	}
+}
+
+/// Runtime API for executing view functions.
+pub mod runtime_api {
+	use super::*;
+
+	sp_api::decl_runtime_apis! {
+		/// Runtime API for executing view functions.
+		pub trait RuntimeViewFunction {
+			/// Execute a view function, given its id and its SCALE encoded arguments.
+			///
+			/// Returns the SCALE encoded result of the view function.
+			fn execute_view_function(
+				query_id: ViewFunctionId,
+				input: Vec<u8>,
+			) -> Result<Vec<u8>, ViewFunctionDispatchError>;
+		}
+	}
+}
@@ -53,8 +53,8 @@ parameter_types! {
 /// Latest stable metadata version used for testing.
 const LATEST_METADATA_VERSION: u32 = 15;
 
-/// Unstable metadata version.
-const UNSTABLE_METADATA_VERSION: u32 = u32::MAX;
+/// Metadata version 16, exposing deprecation info, associated types and view functions.
+const METADATA_VERSION_16: u32 = 16;
 
 pub struct SomeType1;
 impl From<SomeType1> for u64 {
@@ -1945,10 +1945,46 @@ fn metadata_at_version() {
 	assert_eq!(metadata, at_metadata);
 }
 
+#[test]
+fn metadata_at_version_16() {
+	use codec::Decode;
+	use frame_metadata::{
+		v16::{ItemDeprecationInfo, VariantDeprecationInfo},
+		RuntimeMetadata, RuntimeMetadataPrefixed,
+	};
+
+	let bytes =
+		&Runtime::metadata_at_version(METADATA_VERSION_16).expect("Metadata must be present; qed");
+	let metadata: RuntimeMetadataPrefixed =
+		Decode::decode(&mut &bytes[..]).expect("Metadata encoded properly; qed");
+	let RuntimeMetadata::V16(metadata) = metadata.1 else {
+		panic!("Metadata at version 16 must be V16");
+	};
+
+	let example = metadata.pallets.iter().find(|p| p.name == "Example").unwrap();
+	// `foo` is deprecated.
+	let calls = &example.calls.as_ref().unwrap().deprecation_info;
+	assert!(matches!(calls.0.get(&0), Some(VariantDeprecationInfo::Deprecated { .. })));
+	// `NonExistentStorageValue` is deprecated.
+	let errors = &example.error.as_ref().unwrap().deprecation_info;
+	assert_eq!(errors.0.keys().collect::<Vec<_>>(), vec![&1]);
+	// The whole event enum is deprecated, so each of its variants is.
+	let events = &example.event.as_ref().unwrap().deprecation_info;
+	assert!(!events.0.is_empty());
+
+	let example2 = metadata.pallets.iter().find(|p| p.name == "Example2").unwrap();
+	assert!(matches!(example2.deprecation_info, ItemDeprecationInfo::Deprecated { .. }));
+
+	assert_eq!(
+		metadata.extrinsic.transaction_extensions_by_version.get(&0).map(|e| e.len()),
+		Some(metadata.extrinsic.transaction_extensions.len()),
+	);
+}
+
 #[test]
 fn metadata_versions() {
 	assert_eq!(
-		vec![14, LATEST_METADATA_VERSION, UNSTABLE_METADATA_VERSION],
+		vec![14, LATEST_METADATA_VERSION, METADATA_VERSION_16],
 		Runtime::metadata_versions()
 	);
 }
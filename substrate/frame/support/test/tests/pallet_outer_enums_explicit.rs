@@ -73,7 +73,6 @@ frame_support::construct_runtime!(
 	}
 );
 
-#[cfg(feature = "experimental")]
 #[test]
 fn module_error_outer_enum_expand_explicit() {
 	use common::outer_enums::{pallet, pallet2};
@@ -90,10 +89,10 @@ fn module_error_outer_enum_expand_explicit() {
 			frame_system::Error::NonZeroRefCount => (),
 			frame_system::Error::CallFiltered => (),
 			frame_system::Error::MultiBlockMigrationsOngoing => (),
-			frame_system::Error::InvalidTask => (),
-			frame_system::Error::FailedTask => (),
 			frame_system::Error::NothingAuthorized => (),
 			frame_system::Error::Unauthorized => (),
+			frame_system::Error::InvalidTask => (),
+			frame_system::Error::FailedTask => (),
 			frame_system::Error::__Ignore(_, _) => (),
 		},
 
@@ -31,7 +31,7 @@ mod pallet_with_instance {
 	#[pallet::task_enum]
 	pub enum Task<T> {}
 
-	#[pallet::tasks_experimental]
+	#[pallet::tasks]
 	impl frame_support::traits::Task for Task {}
 }
 
@@ -195,6 +195,7 @@ fn runtime_metadata() {
 				" Documentation on multiline.",
 			]),
 			deprecation_info: DeprecationStatusIR::DeprecatedWithoutNote,
+			version: 1.into(),
 
 		},
 		RuntimeApiMetadataIR {
@@ -233,6 +234,7 @@ fn runtime_metadata() {
 				" The `Core` runtime api that every Substrate runtime needs to implement.",
 			]),
 			deprecation_info: DeprecationStatusIR::NotDeprecated,
+			version: 5.into(),
 		},
 	];
 
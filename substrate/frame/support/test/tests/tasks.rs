@@ -15,8 +15,6 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-#![cfg(feature = "experimental")]
-
 #[frame_support::pallet(dev_mode)]
 mod my_pallet {
 	use frame_support::pallet_prelude::{StorageValue, ValueQuery};
@@ -30,7 +28,7 @@ mod my_pallet {
 	#[pallet::storage]
 	pub type SomeStorage<T, I = ()> = StorageValue<_, (u32, u64), ValueQuery>;
 
-	#[pallet::tasks_experimental]
+	#[pallet::tasks]
 	impl<T: Config<I>, I> Pallet<T, I> {
 		#[pallet::task_index(0)]
 		#[pallet::task_condition(|i, j| i == 0u32 && j == 2u64)]
@@ -57,7 +55,7 @@ mod my_pallet_2 {
 	#[pallet::storage]
 	pub type SomeStorage<T, I = ()> = StorageValue<_, (u32, u64), ValueQuery>;
 
-	#[pallet::tasks_experimental]
+	#[pallet::tasks]
 	impl<T: Config<I>, I> Pallet<T, I> {
 		#[pallet::task_index(0)]
 		#[pallet::task_condition(|i, j| i == 0u32 && j == 2u64)]
@@ -20,12 +20,24 @@
 use alloc::{vec, vec::Vec};
 use codec::Encode;
 use frame_benchmarking::v2::*;
-use frame_support::{dispatch::DispatchClass, storage, traits::Get};
+use frame_support::{
+	dispatch::DispatchClass,
+	storage::{self, IterableStorage, IterationLimits},
+	storage_alias,
+	traits::Get,
+	weights::{Weight, WeightMeter},
+	Twox64Concat,
+};
 use frame_system::{Call, Pallet as System, RawOrigin};
 use sp_core::storage::well_known_keys;
 use sp_runtime::traits::Hash;
 
 pub struct Pallet<T: Config>(System<T>);
+
+/// The map iterated by the bounded storage iteration benchmarks.
+#[storage_alias]
+type IterationMap = StorageMap<Benchmark, Twox64Concat, u32, Vec<u8>>;
+
 pub trait Config: frame_system::Config {
 	/// Adds ability to the Runtime to test against their sample code.
 	///
@@ -196,6 +208,54 @@ mod benchmarks {
 		Ok(())
 	}
 
+	/// Process a single entry with a value of `v` bytes through
+	/// [`IterableStorage::iter_bounded`].
+	#[benchmark]
+	fn storage_iter_item(v: Linear<0, { 16 * 1024 }>) -> Result<(), BenchmarkError> {
+		IterationMap::insert(0, vec![1u8; v as usize]);
+		let mut meter = WeightMeter::new();
+		let mut count = 0;
+
+		#[block]
+		{
+			IterationMap::iter_bounded(
+				None,
+				&mut meter,
+				IterationLimits::new(Weight::zero()).max_items(1),
+				|_, value| {
+					count += value.len();
+				},
+			);
+		}
+
+		assert_eq!(count, v as usize);
+		Ok(())
+	}
+
+	/// Process a single entry with a value of `v` bytes through
+	/// [`IterableStorage::translate_bounded`].
+	#[benchmark]
+	fn storage_translate_item(v: Linear<0, { 16 * 1024 }>) -> Result<(), BenchmarkError> {
+		IterationMap::insert(0, vec![1u8; v as usize]);
+		let mut meter = WeightMeter::new();
+
+		#[block]
+		{
+			IterationMap::translate_bounded(
+				None,
+				&mut meter,
+				IterationLimits::new(Weight::zero()).max_items(1),
+				|_, mut value: Vec<u8>| {
+					value.iter_mut().for_each(|b| *b = 2);
+					Some(value)
+				},
+			);
+		}
+
+		assert_eq!(IterationMap::get(0), Some(vec![2u8; v as usize]));
+		Ok(())
+	}
+
 	#[benchmark]
 	fn authorize_upgrade() -> Result<(), BenchmarkError> {
 		let runtime_blob = T::prepare_set_code_data();
@@ -23,6 +23,12 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
+extern crate alloc;
+
+use alloc::vec::Vec;
+use sp_runtime::traits::Block as BlockT;
+use sp_weights::Weight;
+
 #[docify::export(AccountNonceApi)]
 sp_api::decl_runtime_apis! {
 	/// The API to query account nonce.
@@ -34,3 +40,18 @@ sp_api::decl_runtime_apis! {
 		fn account_nonce(account: AccountId) -> Nonce;
 	}
 }
+
+sp_api::decl_runtime_apis! {
+	/// The API to discover the runtime's pending tasks and build the extrinsics executing them.
+	///
+	/// `RuntimeTask` is the runtime's aggregated task type, as generated by `construct_runtime`.
+	pub trait TasksApi<RuntimeTask> where
+		RuntimeTask: codec::Codec,
+	{
+		/// Get up to `max` tasks which are currently valid, together with their weight.
+		fn pending_tasks(max: u32) -> Vec<(RuntimeTask, Weight)>;
+
+		/// Get the unsigned `frame_system::Call::do_task` extrinsic executing `task`.
+		fn task_extrinsic(task: RuntimeTask) -> <Block as BlockT>::Extrinsic;
+	}
+}
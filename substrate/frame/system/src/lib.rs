@@ -780,7 +780,10 @@ pub mod pallet {
 			Ok(().into())
 		}
 
-		#[cfg(feature = "experimental")]
+		/// Execute a valid [`Task`] of the runtime.
+		///
+		/// Can be submitted by any origin, including as an unsigned transaction; the task is
+		/// checked against its `is_valid` condition before it runs.
 		#[pallet::call_index(8)]
 		#[pallet::weight(task.weight())]
 		pub fn do_task(_origin: OriginFor<T>, task: T::RuntimeTask) -> DispatchResultWithPostInfo {
@@ -867,17 +870,14 @@ pub mod pallet {
 		KilledAccount { account: T::AccountId },
 		/// On on-chain remark happened.
 		Remarked { sender: T::AccountId, hash: T::Hash },
-		#[cfg(feature = "experimental")]
+		/// An upgrade was authorized.
+		UpgradeAuthorized { code_hash: T::Hash, check_version: bool },
 		/// A [`Task`] has started executing
 		TaskStarted { task: T::RuntimeTask },
-		#[cfg(feature = "experimental")]
 		/// A [`Task`] has finished executing.
 		TaskCompleted { task: T::RuntimeTask },
-		#[cfg(feature = "experimental")]
 		/// A [`Task`] failed during execution.
 		TaskFailed { task: T::RuntimeTask, err: DispatchError },
-		/// An upgrade was authorized.
-		UpgradeAuthorized { code_hash: T::Hash, check_version: bool },
 	}
 
 	/// Error for the System pallet
@@ -901,16 +901,14 @@ pub mod pallet {
 		CallFiltered,
 		/// A multi-block migration is ongoing and prevents the current code from being replaced.
 		MultiBlockMigrationsOngoing,
-		#[cfg(feature = "experimental")]
-		/// The specified [`Task`] is not valid.
-		InvalidTask,
-		#[cfg(feature = "experimental")]
-		/// The specified [`Task`] failed during execution.
-		FailedTask,
 		/// No upgrade authorized.
 		NothingAuthorized,
 		/// The submitted code is not authorized.
 		Unauthorized,
+		/// The specified [`Task`] is not valid.
+		InvalidTask,
+		/// The specified [`Task`] failed during execution.
+		FailedTask,
 	}
 
 	/// Exposed trait-generic origin type.
@@ -1074,7 +1072,6 @@ pub mod pallet {
 					})
 				}
 			}
-			#[cfg(feature = "experimental")]
 			if let Call::do_task { ref task } = call {
 				if task.is_valid() {
 					return Ok(ValidTransaction {
@@ -2174,6 +2171,25 @@ impl<T: Config> Pallet<T> {
 		}
 		Ok(actual_hash)
 	}
+
+	/// Returns the currently valid tasks among the first `max` tasks of the runtime, together with
+	/// their weight.
+	///
+	/// At most `max` tasks are checked, so fewer than `max` tasks may be returned even if more
+	/// are valid.
+	///
+	/// Meant to back the `TasksApi` runtime API, which the node uses to discover work to submit
+	/// through [`Call::do_task`].
+	pub fn pending_tasks(max: u32) -> Vec<(T::RuntimeTask, Weight)> {
+		T::RuntimeTask::iter()
+			.take(max as usize)
+			.filter(|task| task.is_valid())
+			.map(|task| {
+				let weight = task.weight();
+				(task, weight)
+			})
+			.collect()
+	}
 }
 
 /// Returns a 32 byte datum which is guaranteed to be universally unique. `entropy` is provided
@@ -46,5 +46,20 @@ sp_api::decl_runtime_apis! {
 			signature_check: bool,
 			try_state: TryStateSelect,
 		) -> Weight;
+
+		/// Dry-run runtime upgrades and then execute the multi-block migrations to completion.
+		///
+		/// Like [`on_runtime_upgrade`](TryRuntime::on_runtime_upgrade), followed by empty blocks
+		/// until the multi-block migrations completed. The `pre_upgrade` and `post_upgrade` hooks
+		/// of the stepped migrations run as part of their execution, the checks selected by
+		/// `checks` are repeated once all migrations completed.
+		///
+		/// Returns the consumed weight of the runtime upgrade and the number of blocks the
+		/// multi-block migrations took. Fails if they did not complete within `max_blocks`.
+		#[api_version(2)]
+		fn on_runtime_upgrade_and_migrations(
+			checks: UpgradeCheckSelect,
+			max_blocks: u32,
+		) -> (Weight, u32);
 	}
 }
@@ -0,0 +1,584 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+#![warn(missing_docs)]
+
+//! A `no_std` verifier for BEEFY + MMR light clients.
+//!
+//! A BEEFY light client follows a chain by only knowing the commitment to the current and the next
+//! BEEFY authority sets, i.e. the [`BeefyAuthoritySet`] that `pallet-beefy-mmr` puts in every MMR
+//! leaf. Given a [`BeefyProofBundle`] the client:
+//!
+//! 1. checks that the validator set of the bundle matches the known authority set commitment (a
+//!    binary merkle root of the authority ids converted with an [`AuthorityToMerkleLeaf`]),
+//! 2. checks that more than 2/3 of the validators signed the BEEFY commitment,
+//! 3. extracts the MMR root from the commitment payload and verifies the MMR leaf proof against it.
+//!
+//! The verified [`MmrLeaf`] gives access to the parent block hash and the next authority set of
+//! the chain, and to any extra data the runtime added to the leaf. When a commitment is signed by
+//! the next authority set, the bundle must contain the latest leaf of the MMR, which is used to
+//! learn the authority set that follows it. Such bundles can be requested for the first block of a
+//! session from the `beefy_getProofBundle` RPC.
+//!
+//! Stateless building blocks are available as [`verify_commitment`] and [`verify_mmr_leaf`],
+//! while [`BeefyLightClient`] keeps track of the authority sets across a chain of bundles.
+
+extern crate alloc;
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use core::marker::PhantomData;
+use scale_info::TypeInfo;
+use sp_consensus_beefy::{
+	ecdsa_crypto,
+	known_payloads::MMR_ROOT_ID,
+	mmr::{BeefyAuthoritySet, MmrLeaf},
+	BeefyAuthorityId, BeefySignatureHasher, MmrHashing, MmrRootHash, SignedCommitment,
+	ValidatorSet,
+};
+use sp_mmr_primitives::{mmr_lib, utils::NodesUtils, LeafProof};
+use sp_runtime::{
+	traits::{Hash as HashT, Member},
+	RuntimeAppPublic, RuntimeDebug,
+};
+
+/// Everything a light client needs to verify that a block is part of a BEEFY finalized chain.
+///
+/// The bundle contains a BEEFY signed commitment, the validator set that signed it, and an MMR
+/// leaf together with a proof of its inclusion in the MMR root the commitment signs.
+#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct BeefyProofBundle<Number, AuthorityId, Signature> {
+	/// The signed commitment of a BEEFY finalized block.
+	pub signed_commitment: SignedCommitment<Number, Signature>,
+	/// The validator set that signed the commitment.
+	pub validator_set: ValidatorSet<AuthorityId>,
+	/// The SCALE-encoded MMR leaf.
+	pub leaf: Vec<u8>,
+	/// The proof of the leaf against the MMR root of the commitment.
+	pub leaf_proof: LeafProof<MmrRootHash>,
+}
+
+/// Errors that can occur while verifying BEEFY proofs.
+#[derive(Clone, Copy, RuntimeDebug, PartialEq, Eq)]
+pub enum Error {
+	/// The commitment is not newer than the last imported one.
+	StaleCommitment,
+	/// The commitment is signed by neither the current nor the next authority set.
+	UnknownAuthoritySet,
+	/// The id of the validator set does not match the one of the commitment.
+	InvalidValidatorSetId,
+	/// The size of the validator set does not match the one of the authority set.
+	InvalidValidatorSetLen,
+	/// The validator set does not match the commitment of the authority set.
+	InvalidValidatorSetRoot,
+	/// The number of signatures does not match the size of the validator set.
+	InvalidSignaturesLen,
+	/// Not enough validators signed the commitment.
+	NotEnoughCorrectSignatures,
+	/// The commitment payload does not contain an MMR root.
+	MmrRootMissingFromCommitment,
+	/// The MMR leaf proof is invalid.
+	InvalidMmrProof,
+	/// The MMR leaf can't be decoded.
+	InvalidMmrLeaf,
+	/// A commitment of the next authority set was not accompanied by the latest MMR leaf.
+	HandoverLeafRequired,
+	/// The next authority set announced by the MMR leaf does not follow the current one.
+	InvalidNextAuthoritySet,
+}
+
+/// Conversion of a BEEFY authority id to the leaf of the authority set merkle tree.
+pub trait AuthorityToMerkleLeaf<AuthorityId> {
+	/// Convert the authority id to the merkle leaf data.
+	fn convert(authority_id: &AuthorityId) -> Vec<u8>;
+}
+
+/// Converts ECDSA BEEFY authority ids to their Ethereum address, as done by `pallet-beefy-mmr`.
+///
+/// Keys that can't be converted are mapped to an empty leaf.
+pub struct BeefyEcdsaToEthereum;
+
+impl AuthorityToMerkleLeaf<ecdsa_crypto::AuthorityId> for BeefyEcdsaToEthereum {
+	fn convert(authority_id: &ecdsa_crypto::AuthorityId) -> Vec<u8> {
+		use k256::{elliptic_curve::sec1::ToEncodedPoint, PublicKey};
+
+		PublicKey::from_sec1_bytes(authority_id.as_ref())
+			.map(|public| {
+				let uncompressed = public.to_encoded_point(false);
+				MmrHashing::hash(&uncompressed.as_bytes()[1..])[12..].to_vec()
+			})
+			.unwrap_or_default()
+	}
+}
+
+/// Compute the commitment to the given authorities, as found in [`BeefyAuthoritySet`].
+pub fn authority_set_commitment<AuthorityId, C>(authorities: &[AuthorityId]) -> MmrRootHash
+where
+	C: AuthorityToMerkleLeaf<AuthorityId>,
+{
+	binary_merkle_tree::merkle_root::<MmrHashing, _>(authorities.iter().map(C::convert))
+}
+
+/// The number of correct signatures required from a validator set of the given size.
+///
+/// This is the `2/3 + 1` supermajority used by BEEFY.
+pub fn signatures_required(validators_len: usize) -> usize {
+	validators_len - validators_len.saturating_sub(1) / 3
+}
+
+/// Verify a signed commitment against a known authority set.
+///
+/// `validator_set` must be the full set of validators that is committed to by `authority_set`.
+/// Returns the MMR root signed by the commitment.
+pub fn verify_commitment<Number, AuthorityId, C>(
+	signed_commitment: &SignedCommitment<Number, <AuthorityId as RuntimeAppPublic>::Signature>,
+	validator_set: &ValidatorSet<AuthorityId>,
+	authority_set: &BeefyAuthoritySet<MmrRootHash>,
+) -> Result<MmrRootHash, Error>
+where
+	Number: Clone + Encode + PartialEq,
+	AuthorityId: BeefyAuthorityId<BeefySignatureHasher>,
+	C: AuthorityToMerkleLeaf<AuthorityId>,
+{
+	let commitment = &signed_commitment.commitment;
+	if commitment.validator_set_id != authority_set.id || validator_set.id() != authority_set.id {
+		return Err(Error::InvalidValidatorSetId)
+	}
+	if validator_set.len() == 0 || validator_set.len() != authority_set.len as usize {
+		return Err(Error::InvalidValidatorSetLen)
+	}
+	if authority_set_commitment::<_, C>(validator_set.validators()) !=
+		authority_set.keyset_commitment
+	{
+		return Err(Error::InvalidValidatorSetRoot)
+	}
+	if signed_commitment.signatures.len() != validator_set.len() {
+		return Err(Error::InvalidSignaturesLen)
+	}
+
+	let correct_signatures = signed_commitment
+		.verify_signatures::<_, BeefySignatureHasher>(
+			commitment.block_number.clone(),
+			validator_set,
+		)
+		.map_err(|_| Error::InvalidSignaturesLen)?
+		.len();
+	if correct_signatures < signatures_required(validator_set.len()) {
+		return Err(Error::NotEnoughCorrectSignatures)
+	}
+
+	commitment
+		.payload
+		.get_decoded(&MMR_ROOT_ID)
+		.ok_or(Error::MmrRootMissingFromCommitment)
+}
+
+/// Verify the proof of a SCALE-encoded MMR leaf against the given MMR root.
+///
+/// Returns the decoded leaf.
+pub fn verify_mmr_leaf<Number, Hash, ExtraData>(
+	mmr_root: MmrRootHash,
+	leaf: &[u8],
+	proof: &LeafProof<MmrRootHash>,
+) -> Result<MmrLeaf<Number, Hash, MmrRootHash, ExtraData>, Error>
+where
+	Number: Decode,
+	Hash: Decode,
+	ExtraData: Decode,
+{
+	let &[leaf_index] = &proof.leaf_indices[..] else { return Err(Error::InvalidMmrProof) };
+	if leaf_index >= proof.leaf_count {
+		return Err(Error::InvalidMmrProof)
+	}
+
+	let mmr_size = NodesUtils::new(proof.leaf_count).size();
+	let is_valid =
+		mmr_lib::MerkleProof::<MmrRootHash, MmrMerge>::new(mmr_size, proof.items.clone())
+			.verify(
+				mmr_root,
+				alloc::vec![(mmr_lib::leaf_index_to_pos(leaf_index), MmrHashing::hash(leaf))],
+			)
+			.unwrap_or(false);
+	if !is_valid {
+		return Err(Error::InvalidMmrProof)
+	}
+
+	Decode::decode(&mut &leaf[..]).map_err(|_| Error::InvalidMmrLeaf)
+}
+
+/// The merging of MMR nodes used by `pallet-mmr` with the BEEFY MMR hashing.
+struct MmrMerge;
+
+impl mmr_lib::Merge for MmrMerge {
+	type Item = MmrRootHash;
+
+	fn merge(left: &Self::Item, right: &Self::Item) -> mmr_lib::Result<Self::Item> {
+		let mut concat = left.as_bytes().to_vec();
+		concat.extend_from_slice(right.as_bytes());
+		Ok(MmrHashing::hash(&concat))
+	}
+}
+
+/// A BEEFY light client that follows the authority set changes of a chain.
+///
+/// `C` defines how authority ids are converted to the leaves of the authority set commitment, it
+/// must match the conversion used by the runtime.
+pub struct BeefyLightClient<Number, AuthorityId, C = BeefyEcdsaToEthereum> {
+	current_authority_set: BeefyAuthoritySet<MmrRootHash>,
+	next_authority_set: BeefyAuthoritySet<MmrRootHash>,
+	latest_block: Option<Number>,
+	_phantom: PhantomData<(AuthorityId, C)>,
+}
+
+impl<Number, AuthorityId, C> BeefyLightClient<Number, AuthorityId, C>
+where
+	Number: Member + Encode + Decode + PartialOrd,
+	AuthorityId: BeefyAuthorityId<BeefySignatureHasher>,
+	C: AuthorityToMerkleLeaf<AuthorityId>,
+{
+	/// Create a light client trusting the given current and next authority sets.
+	pub fn new(
+		current_authority_set: BeefyAuthoritySet<MmrRootHash>,
+		next_authority_set: BeefyAuthoritySet<MmrRootHash>,
+	) -> Self {
+		Self {
+			current_authority_set,
+			next_authority_set,
+			latest_block: None,
+			_phantom: PhantomData,
+		}
+	}
+
+	/// The authority set that is expected to sign the next commitments.
+	pub fn current_authority_set(&self) -> &BeefyAuthoritySet<MmrRootHash> {
+		&self.current_authority_set
+	}
+
+	/// The authority set that follows the current one.
+	pub fn next_authority_set(&self) -> &BeefyAuthoritySet<MmrRootHash> {
+		&self.next_authority_set
+	}
+
+	/// The block number of the latest imported commitment.
+	pub fn latest_block(&self) -> Option<&Number> {
+		self.latest_block.as_ref()
+	}
+
+	/// Verify and import a proof bundle, returning its verified MMR leaf.
+	///
+	/// The commitment of the bundle must be newer than the last imported one. If it is signed by
+	/// the next authority set, the bundle must contain the latest leaf of the MMR and the
+	/// authority sets are rotated.
+	pub fn import<Hash, ExtraData>(
+		&mut self,
+		bundle: &BeefyProofBundle<
+			Number,
+			AuthorityId,
+			<AuthorityId as RuntimeAppPublic>::Signature,
+		>,
+	) -> Result<MmrLeaf<Number, Hash, MmrRootHash, ExtraData>, Error>
+	where
+		Hash: Decode,
+		ExtraData: Decode,
+	{
+		let commitment = &bundle.signed_commitment.commitment;
+		if self
+			.latest_block
+			.as_ref()
+			.map_or(false, |latest| commitment.block_number <= *latest)
+		{
+			return Err(Error::StaleCommitment)
+		}
+
+		let is_handover = if commitment.validator_set_id == self.current_authority_set.id {
+			false
+		} else if commitment.validator_set_id == self.next_authority_set.id {
+			true
+		} else {
+			return Err(Error::UnknownAuthoritySet)
+		};
+		let authority_set =
+			if is_handover { &self.next_authority_set } else { &self.current_authority_set };
+
+		let mmr_root = verify_commitment::<_, _, C>(
+			&bundle.signed_commitment,
+			&bundle.validator_set,
+			authority_set,
+		)?;
+		let leaf =
+			verify_mmr_leaf::<Number, Hash, ExtraData>(mmr_root, &bundle.leaf, &bundle.leaf_proof)?;
+
+		if is_handover {
+			if bundle.leaf_proof.leaf_indices != [bundle.leaf_proof.leaf_count - 1] {
+				return Err(Error::HandoverLeafRequired)
+			}
+			if leaf.beefy_next_authority_set.id != self.next_authority_set.id + 1 {
+				return Err(Error::InvalidNextAuthoritySet)
+			}
+
+			self.current_authority_set = core::mem::replace(
+				&mut self.next_authority_set,
+				leaf.beefy_next_authority_set.clone(),
+			);
+		}
+		self.latest_block = Some(commitment.block_number.clone());
+
+		Ok(leaf)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use sp_consensus_beefy::{
+		mmr::MmrLeafVersion, test_utils::Keyring, Commitment, Payload, ValidatorSetId,
+	};
+	use sp_core::H256;
+
+	type TestLeaf = MmrLeaf<u64, H256, MmrRootHash, Vec<u8>>;
+	type TestBundle =
+		BeefyProofBundle<u64, ecdsa_crypto::AuthorityId, ecdsa_crypto::AuthoritySignature>;
+	type TestLightClient = BeefyLightClient<u64, ecdsa_crypto::AuthorityId>;
+
+	const ALL: [Keyring<ecdsa_crypto::AuthorityId>; 4] =
+		[Keyring::Alice, Keyring::Bob, Keyring::Charlie, Keyring::Dave];
+
+	fn validator_set(
+		keys: &[Keyring<ecdsa_crypto::AuthorityId>],
+		id: ValidatorSetId,
+	) -> ValidatorSet<ecdsa_crypto::AuthorityId> {
+		ValidatorSet::new(keys.iter().map(|k| k.public()), id).unwrap()
+	}
+
+	fn authority_set(
+		keys: &[Keyring<ecdsa_crypto::AuthorityId>],
+		id: ValidatorSetId,
+	) -> BeefyAuthoritySet<MmrRootHash> {
+		let validators = validator_set(keys, id);
+		BeefyAuthoritySet {
+			id,
+			len: validators.len() as u32,
+			keyset_commitment: authority_set_commitment::<_, BeefyEcdsaToEthereum>(
+				validators.validators(),
+			),
+		}
+	}
+
+	fn make_leaf(number: u64, next_set: BeefyAuthoritySet<MmrRootHash>) -> Vec<u8> {
+		TestLeaf {
+			version: MmrLeafVersion::new(1, 5),
+			parent_number_and_hash: (number - 1, H256::repeat_byte(number as u8)),
+			beefy_next_authority_set: next_set,
+			leaf_extra: vec![],
+		}
+		.encode()
+	}
+
+	/// Build a bundle for `leaves[leaf_index]`, signed by the given keys.
+	///
+	/// The leaves are appended to an MMR the same way `pallet-mmr` does it, the commitment signs
+	/// its root.
+	fn make_bundle(
+		block_number: u64,
+		set_id: ValidatorSetId,
+		signers: &[Keyring<ecdsa_crypto::AuthorityId>],
+		leaves: &[Vec<u8>],
+		leaf_index: usize,
+	) -> TestBundle {
+		let store = mmr_lib::util::MemStore::default();
+		let mut mmr = mmr_lib::util::MemMMR::<_, MmrMerge>::new(0, &store);
+		let positions = leaves
+			.iter()
+			.map(|leaf| mmr.push(MmrHashing::hash(leaf)).unwrap())
+			.collect::<Vec<_>>();
+		let mmr_root = mmr.get_root().unwrap();
+		let proof = mmr.gen_proof(vec![positions[leaf_index]]).unwrap();
+
+		let commitment = Commitment {
+			payload: Payload::from_single_entry(MMR_ROOT_ID, mmr_root.encode()),
+			block_number,
+			validator_set_id: set_id,
+		};
+		let signatures = ALL
+			.iter()
+			.map(|k| signers.contains(k).then(|| k.sign(&commitment.encode())))
+			.collect();
+
+		TestBundle {
+			signed_commitment: SignedCommitment { commitment, signatures },
+			validator_set: validator_set(&ALL, set_id),
+			leaf: leaves[leaf_index].clone(),
+			leaf_proof: LeafProof {
+				leaf_indices: vec![leaf_index as u64],
+				leaf_count: leaves.len() as u64,
+				items: proof.proof_items().to_vec(),
+			},
+		}
+	}
+
+	/// The leaves of blocks `first..=last`, announcing the given next authority set.
+	fn make_leaves(first: u64, last: u64, next_set: ValidatorSetId) -> Vec<Vec<u8>> {
+		(first..=last)
+			.map(|number| make_leaf(number, authority_set(&ALL, next_set)))
+			.collect()
+	}
+
+	#[test]
+	fn authority_set_commitment_matches_pallet_beefy_mmr() {
+		// Test vectors from `pallet-beefy-mmr`.
+		let mock_beefy_id = |id: u8| {
+			let mut raw = [id; 33];
+			raw[0] = 0x02;
+			ecdsa_crypto::AuthorityId::from(sp_core::ecdsa::Public::from_raw(raw))
+		};
+
+		assert_eq!(
+			authority_set_commitment::<_, BeefyEcdsaToEthereum>(&[
+				mock_beefy_id(1),
+				mock_beefy_id(2)
+			]),
+			array_bytes::hex_n_into_unchecked::<_, H256, 32>(
+				"176e73f1bf656478b728e28dd1a7733c98621b8acf830bff585949763dca7a96"
+			),
+		);
+		assert_eq!(
+			authority_set_commitment::<_, BeefyEcdsaToEthereum>(&[
+				mock_beefy_id(3),
+				mock_beefy_id(4)
+			]),
+			array_bytes::hex_n_into_unchecked::<_, H256, 32>(
+				"9c6b2c1b0d0b25a008e6c882cc7b415f309965c72ad2b944ac0931048ca31cd5"
+			),
+		);
+	}
+
+	#[test]
+	fn should_verify_commitment_and_leaf() {
+		let set = authority_set(&ALL, 0);
+		let leaves = make_leaves(1, 7, 1);
+
+		for leaf_index in 0..leaves.len() {
+			let bundle = make_bundle(7, 0, &ALL[..3], &leaves, leaf_index);
+			let mmr_root = verify_commitment::<_, _, BeefyEcdsaToEthereum>(
+				&bundle.signed_commitment,
+				&bundle.validator_set,
+				&set,
+			)
+			.unwrap();
+			let verified: TestLeaf =
+				verify_mmr_leaf(mmr_root, &bundle.leaf, &bundle.leaf_proof).unwrap();
+			assert_eq!(
+				verified.parent_number_and_hash,
+				(leaf_index as u64, H256::repeat_byte(leaf_index as u8 + 1))
+			);
+		}
+	}
+
+	#[test]
+	fn should_reject_invalid_commitments() {
+		let set = authority_set(&ALL, 0);
+		let leaves = make_leaves(1, 2, 1);
+		let verify = |bundle: &TestBundle, set: &BeefyAuthoritySet<MmrRootHash>| {
+			verify_commitment::<_, _, BeefyEcdsaToEthereum>(
+				&bundle.signed_commitment,
+				&bundle.validator_set,
+				set,
+			)
+		};
+
+		// not enough signatures
+		let bundle = make_bundle(2, 0, &ALL[..2], &leaves, 0);
+		assert_eq!(verify(&bundle, &set), Err(Error::NotEnoughCorrectSignatures));
+
+		// signatures of another commitment
+		let mut bundle = make_bundle(2, 0, &ALL, &leaves, 0);
+		bundle.signed_commitment.commitment.block_number = 3;
+		assert_eq!(verify(&bundle, &set), Err(Error::NotEnoughCorrectSignatures));
+
+		// validator set that doesn't match the authority set
+		let mut bundle = make_bundle(2, 0, &ALL, &leaves, 0);
+		bundle.validator_set =
+			validator_set(&[Keyring::Alice, Keyring::Bob, Keyring::Eve, Keyring::Dave], 0);
+		assert_eq!(verify(&bundle, &set), Err(Error::InvalidValidatorSetRoot));
+
+		// commitment of another set
+		let bundle = make_bundle(2, 1, &ALL, &leaves, 0);
+		assert_eq!(verify(&bundle, &set), Err(Error::InvalidValidatorSetId));
+	}
+
+	#[test]
+	fn should_reject_invalid_leaf_proofs() {
+		let leaves = make_leaves(1, 5, 1);
+		let bundle = make_bundle(5, 0, &ALL, &leaves, 2);
+		let mmr_root =
+			bundle.signed_commitment.commitment.payload.get_decoded(&MMR_ROOT_ID).unwrap();
+
+		// proof of another leaf
+		let result: Result<TestLeaf, _> = verify_mmr_leaf(mmr_root, &leaves[1], &bundle.leaf_proof);
+		assert_eq!(result, Err(Error::InvalidMmrProof));
+
+		// wrong root
+		let result: Result<TestLeaf, _> =
+			verify_mmr_leaf(H256::repeat_byte(42), &bundle.leaf, &bundle.leaf_proof);
+		assert_eq!(result, Err(Error::InvalidMmrProof));
+
+		// wrong leaf count
+		let mut proof = bundle.leaf_proof.clone();
+		proof.leaf_count = 4;
+		let result: Result<TestLeaf, _> = verify_mmr_leaf(mmr_root, &bundle.leaf, &proof);
+		assert_eq!(result, Err(Error::InvalidMmrProof));
+	}
+
+	#[test]
+	fn light_client_follows_authority_set_changes() {
+		let mut client = TestLightClient::new(authority_set(&ALL, 0), authority_set(&ALL, 1));
+
+		// commitment of the current set
+		let leaves = make_leaves(1, 2, 1);
+		let verified: TestLeaf = client.import(&make_bundle(2, 0, &ALL, &leaves, 0)).unwrap();
+		assert_eq!(verified.parent_number_and_hash.0, 0);
+		assert_eq!(client.latest_block(), Some(&2));
+
+		// stale commitment
+		assert_eq!(
+			client.import::<H256, Vec<u8>>(&make_bundle(2, 0, &ALL, &leaves, 0)),
+			Err(Error::StaleCommitment)
+		);
+
+		// handover without the latest leaf
+		let mut leaves = make_leaves(1, 3, 1);
+		leaves.extend(make_leaves(4, 4, 2));
+		assert_eq!(
+			client.import::<H256, Vec<u8>>(&make_bundle(4, 1, &ALL, &leaves, 2)),
+			Err(Error::HandoverLeafRequired)
+		);
+
+		// handover with the latest leaf
+		let verified: TestLeaf = client.import(&make_bundle(4, 1, &ALL, &leaves, 3)).unwrap();
+		assert_eq!(verified.beefy_next_authority_set.id, 2);
+		assert_eq!(client.current_authority_set(), &authority_set(&ALL, 1));
+		assert_eq!(client.next_authority_set(), &authority_set(&ALL, 2));
+
+		// the old set is not trusted anymore
+		let leaves = make_leaves(1, 6, 2);
+		assert_eq!(
+			client.import::<H256, Vec<u8>>(&make_bundle(6, 0, &ALL, &leaves, 5)),
+			Err(Error::UnknownAuthoritySet)
+		);
+	}
+}
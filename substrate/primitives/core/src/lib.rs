@@ -70,6 +70,8 @@ pub mod testing;
 pub mod traits;
 pub mod uint;
 
+#[cfg(feature = "bandersnatch-experimental")]
+pub mod bandersnatch;
 #[cfg(feature = "bls-experimental")]
 pub mod bls;
 pub mod crypto_bytes;
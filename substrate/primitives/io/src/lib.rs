@@ -105,6 +105,8 @@ use sp_core::{
 	LogLevel, LogLevelFilter, OpaquePeerId, H256,
 };
 
+#[cfg(feature = "bandersnatch-experimental")]
+use sp_core::bandersnatch;
 #[cfg(feature = "bls-experimental")]
 use sp_core::{bls381, ecdsa_bls381};
 
@@ -1235,6 +1237,25 @@ pub trait Crypto {
 			.ecdsa_bls381_generate_new(id, seed)
 			.expect("`ecdsa_bls381_generate` failed")
 	}
+
+	/// Generate a `bandersnatch` key pair for the given key type using an optional
+	/// `seed` and store it in the keystore.
+	///
+	/// The `seed` needs to be a valid utf8.
+	///
+	/// Returns the public key.
+	#[cfg(feature = "bandersnatch-experimental")]
+	fn bandersnatch_generate(
+		&mut self,
+		id: KeyTypeId,
+		seed: Option<Vec<u8>>,
+	) -> bandersnatch::Public {
+		let seed = seed.as_ref().map(|s| std::str::from_utf8(s).expect("Seed is valid utf8!"));
+		self.extension::<KeystoreExt>()
+			.expect("No `keystore` associated for the current context!")
+			.bandersnatch_generate_new(id, seed)
+			.expect("`bandersnatch_generate` failed")
+	}
 }
 
 /// Interface that provides functions for hashing with different algorithms.
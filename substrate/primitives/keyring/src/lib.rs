@@ -28,10 +28,16 @@ pub mod sr25519;
 /// Test account crypto for ed25519.
 pub mod ed25519;
 
+/// Test account crypto for bandersnatch.
+#[cfg(feature = "bandersnatch-experimental")]
+pub mod bandersnatch;
+
 /// Convenience export: Sr25519's Keyring is exposed as `AccountKeyring`, since it tends to be
 /// used for accounts (although it may also be used by authorities).
 pub use sr25519::Keyring as AccountKeyring;
 
+#[cfg(feature = "bandersnatch-experimental")]
+pub use bandersnatch::Keyring as BandersnatchKeyring;
 pub use ed25519::Keyring as Ed25519Keyring;
 pub use sr25519::Keyring as Sr25519Keyring;
 
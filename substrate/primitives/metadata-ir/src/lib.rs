@@ -30,9 +30,9 @@ mod types;
 use frame_metadata::RuntimeMetadataPrefixed;
 pub use types::*;
 
-mod unstable;
 mod v14;
 mod v15;
+mod v16;
 
 /// Metadata V14.
 const V14: u32 = 14;
@@ -40,14 +40,13 @@ const V14: u32 = 14;
 /// Metadata V15.
 const V15: u32 = 15;
 
-/// Unstable metadata V16.
-const UNSTABLE_V16: u32 = u32::MAX;
+/// Metadata V16.
+const V16: u32 = 16;
 
 /// Transform the IR to the specified version.
 ///
 /// Use [`supported_versions`] to find supported versions.
 pub fn into_version(metadata: MetadataIR, version: u32) -> Option<RuntimeMetadataPrefixed> {
-	// Note: Unstable metadata version is `u32::MAX` until stabilized.
 	match version {
 		// Version V14. This needs to be around until the
 		// deprecation of the `Metadata_metadata` runtime call in favor of
@@ -57,8 +56,8 @@ pub fn into_version(metadata: MetadataIR, version: u32) -> Option<RuntimeMetadat
 		// Version V15 - latest stable.
 		V15 => Some(into_latest(metadata)),
 
-		// Unstable metadata under `u32::MAX`.
-		UNSTABLE_V16 => Some(into_unstable(metadata)),
+		// Version V16, adding deprecation info, associated types and view functions.
+		V16 => Some(into_v16(metadata)),
 
 		_ => None,
 	}
@@ -66,7 +65,7 @@ pub fn into_version(metadata: MetadataIR, version: u32) -> Option<RuntimeMetadat
 
 /// Returns the supported metadata versions.
 pub fn supported_versions() -> alloc::vec::Vec<u32> {
-	alloc::vec![V14, V15, UNSTABLE_V16]
+	alloc::vec![V14, V15, V16]
 }
 
 /// Transform the IR to the latest stable metadata version.
@@ -81,8 +80,8 @@ pub fn into_v14(metadata: MetadataIR) -> RuntimeMetadataPrefixed {
 	latest.into()
 }
 
-/// Transform the IR to unstable metadata version 16.
-pub fn into_unstable(metadata: MetadataIR) -> RuntimeMetadataPrefixed {
+/// Transform the IR to metadata version 16.
+pub fn into_v16(metadata: MetadataIR) -> RuntimeMetadataPrefixed {
 	let latest: frame_metadata::v16::RuntimeMetadataV16 = metadata.into();
 	latest.into()
 }
@@ -90,6 +89,7 @@ pub fn into_unstable(metadata: MetadataIR) -> RuntimeMetadataPrefixed {
 #[cfg(test)]
 mod test {
 	use super::*;
+	use codec::{Decode, Encode};
 	use frame_metadata::{v14::META_RESERVED, RuntimeMetadata};
 	use scale_info::meta_type;
 
@@ -104,6 +104,7 @@ mod test {
 				signature_ty: meta_type::<()>(),
 				extra_ty: meta_type::<()>(),
 				extensions: vec![],
+				extensions_by_version: Default::default(),
 			},
 			ty: meta_type::<()>(),
 			apis: vec![],
@@ -134,4 +135,97 @@ mod test {
 
 		assert!(matches!(metadata.1, RuntimeMetadata::V15(_)));
 	}
+
+	#[test]
+	fn into_version_16() {
+		let ir = ir_metadata();
+		let metadata = into_version(ir, V16).expect("Should return prefixed metadata");
+
+		assert_eq!(metadata.0, META_RESERVED);
+
+		assert!(matches!(metadata.1, RuntimeMetadata::V16(_)));
+	}
+
+	#[test]
+	fn v16_round_trip() {
+		#[allow(dead_code)]
+		#[derive(scale_info::TypeInfo)]
+		enum Call {
+			First,
+			Second,
+		}
+
+		let mut ir = ir_metadata();
+		ir.extrinsic.versions = vec![4, 5];
+		ir.extrinsic.extensions_by_version = [(0, vec![0, 1])].into_iter().collect();
+		ir.apis = vec![RuntimeApiMetadataIR {
+			name: "Api",
+			methods: vec![],
+			docs: vec![],
+			deprecation_info: DeprecationStatusIR::NotDeprecated,
+			version: 3.into(),
+		}];
+		ir.pallets = vec![PalletMetadataIR {
+			name: "Example",
+			storage: None,
+			calls: Some(PalletCallMetadataIR {
+				ty: meta_type::<Call>(),
+				deprecation_info: DeprecationInfoIR::ItemDeprecated(
+					DeprecationStatusIR::DeprecatedWithoutNote,
+				),
+			}),
+			event: None,
+			constants: vec![],
+			error: None,
+			associated_types: vec![PalletAssociatedTypeMetadataIR {
+				name: "Balance",
+				ty: meta_type::<u64>(),
+				docs: vec![],
+			}],
+			view_functions: vec![PalletViewFunctionMetadataIR {
+				name: "get_value",
+				id: [1; 32],
+				inputs: vec![PalletViewFunctionParamMetadataIR {
+					name: "key",
+					ty: meta_type::<u32>(),
+				}],
+				output: meta_type::<u64>(),
+				docs: vec![],
+				deprecation_info: DeprecationStatusIR::NotDeprecated,
+			}],
+			index: 0,
+			docs: vec![],
+			deprecation_info: DeprecationStatusIR::Deprecated { since: None, note: "Use another" },
+		}];
+
+		let encoded = into_version(ir, V16).expect("Should return prefixed metadata").encode();
+		let decoded = frame_metadata::RuntimeMetadataPrefixed::decode(&mut &encoded[..])
+			.expect("Metadata should decode");
+		let RuntimeMetadata::V16(metadata) = decoded.1 else { panic!("Expected V16 metadata") };
+
+		assert_eq!(metadata.extrinsic.versions, vec![4, 5]);
+		assert_eq!(
+			metadata.extrinsic.transaction_extensions_by_version.get(&0),
+			Some(&vec![codec::Compact(0), codec::Compact(1)])
+		);
+		assert_eq!(metadata.apis[0].version, 3.into());
+
+		let pallet = &metadata.pallets[0];
+		assert_eq!(pallet.associated_types[0].name, "Balance");
+		assert_eq!(pallet.view_functions[0].name, "get_value");
+		assert_eq!(pallet.view_functions[0].id, [1; 32]);
+		assert_eq!(pallet.view_functions[0].inputs[0].name, "key");
+		assert!(matches!(
+			pallet.deprecation_info,
+			frame_metadata::v16::ItemDeprecationInfo::Deprecated { .. }
+		));
+
+		// Deprecating the whole call enum deprecates each of its variants.
+		let calls = &pallet.calls.as_ref().expect("Calls should be present").deprecation_info;
+		assert_eq!(calls.0.len(), 2);
+		assert!(calls.0.values().all(|info| matches!(
+			info,
+			frame_metadata::v16::VariantDeprecationInfo::DeprecatedWithoutNote
+		)));
+	}
 }
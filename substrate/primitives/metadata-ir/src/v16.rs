@@ -20,7 +20,8 @@
 use crate::{
 	DeprecationInfoIR, DeprecationStatusIR, OuterEnumsIR, PalletAssociatedTypeMetadataIR,
 	PalletCallMetadataIR, PalletConstantMetadataIR, PalletErrorMetadataIR, PalletEventMetadataIR,
-	PalletStorageMetadataIR, StorageEntryMetadataIR,
+	PalletStorageMetadataIR, PalletViewFunctionMetadataIR, PalletViewFunctionParamMetadataIR,
+	StorageEntryMetadataIR,
 };
 
 use super::types::{
@@ -28,13 +29,16 @@ use super::types::{
 	RuntimeApiMethodMetadataIR, RuntimeApiMethodParamMetadataIR, TransactionExtensionMetadataIR,
 };
 
+use codec::Compact;
 use frame_metadata::v16::{
-	CustomMetadata, DeprecationInfo, DeprecationStatus, ExtrinsicMetadata, OuterEnums,
-	PalletAssociatedTypeMetadata, PalletCallMetadata, PalletConstantMetadata, PalletErrorMetadata,
-	PalletEventMetadata, PalletMetadata, PalletStorageMetadata, RuntimeApiMetadata,
-	RuntimeApiMethodMetadata, RuntimeApiMethodParamMetadata, RuntimeMetadataV16,
-	StorageEntryMetadata, TransactionExtensionMetadata,
+	CustomMetadata, EnumDeprecationInfo, ExtrinsicMetadata, FunctionParamMetadata,
+	ItemDeprecationInfo, OuterEnums, PalletAssociatedTypeMetadata, PalletCallMetadata,
+	PalletConstantMetadata, PalletErrorMetadata, PalletEventMetadata, PalletMetadata,
+	PalletStorageMetadata, PalletViewFunctionMetadata, RuntimeApiMetadata,
+	RuntimeApiMethodMetadata, RuntimeMetadataV16, StorageEntryMetadata,
+	TransactionExtensionMetadata, VariantDeprecationInfo,
 };
+use scale_info::{MetaType, TypeDef};
 
 impl From<MetadataIR> for RuntimeMetadataV16 {
 	fn from(ir: MetadataIR) -> Self {
@@ -57,6 +61,7 @@ impl From<RuntimeApiMetadataIR> for RuntimeApiMetadata {
 			methods: ir.methods.into_iter().map(Into::into).collect(),
 			docs: ir.docs,
 			deprecation_info: ir.deprecation_info.into(),
+			version: ir.version,
 		}
 	}
 }
@@ -73,9 +78,9 @@ impl From<RuntimeApiMethodMetadataIR> for RuntimeApiMethodMetadata {
 	}
 }
 
-impl From<RuntimeApiMethodParamMetadataIR> for RuntimeApiMethodParamMetadata {
+impl From<RuntimeApiMethodParamMetadataIR> for FunctionParamMetadata {
 	fn from(ir: RuntimeApiMethodParamMetadataIR) -> Self {
-		RuntimeApiMethodParamMetadata { name: ir.name, ty: ir.ty }
+		FunctionParamMetadata { name: ir.name, ty: ir.ty }
 	}
 }
 
@@ -85,6 +90,7 @@ impl From<PalletMetadataIR> for PalletMetadata {
 			name: ir.name,
 			storage: ir.storage.map(Into::into),
 			calls: ir.calls.map(Into::into),
+			view_functions: ir.view_functions.into_iter().map(Into::into).collect(),
 			event: ir.event.map(Into::into),
 			constants: ir.constants.into_iter().map(Into::into).collect(),
 			error: ir.error.map(Into::into),
@@ -124,21 +130,43 @@ impl From<PalletAssociatedTypeMetadataIR> for PalletAssociatedTypeMetadata {
 	}
 }
 
+impl From<PalletViewFunctionMetadataIR> for PalletViewFunctionMetadata {
+	fn from(ir: PalletViewFunctionMetadataIR) -> Self {
+		PalletViewFunctionMetadata {
+			name: ir.name,
+			id: ir.id,
+			inputs: ir.inputs.into_iter().map(Into::into).collect(),
+			output: ir.output,
+			docs: ir.docs,
+			deprecation_info: ir.deprecation_info.into(),
+		}
+	}
+}
+
+impl From<PalletViewFunctionParamMetadataIR> for FunctionParamMetadata {
+	fn from(ir: PalletViewFunctionParamMetadataIR) -> Self {
+		FunctionParamMetadata { name: ir.name, ty: ir.ty }
+	}
+}
+
 impl From<PalletErrorMetadataIR> for PalletErrorMetadata {
 	fn from(ir: PalletErrorMetadataIR) -> Self {
-		PalletErrorMetadata { ty: ir.ty, deprecation_info: ir.deprecation_info.into() }
+		let deprecation_info = enum_deprecation_info(&ir.ty, ir.deprecation_info);
+		PalletErrorMetadata { ty: ir.ty, deprecation_info }
 	}
 }
 
 impl From<PalletEventMetadataIR> for PalletEventMetadata {
 	fn from(ir: PalletEventMetadataIR) -> Self {
-		PalletEventMetadata { ty: ir.ty, deprecation_info: ir.deprecation_info.into() }
+		let deprecation_info = enum_deprecation_info(&ir.ty, ir.deprecation_info);
+		PalletEventMetadata { ty: ir.ty, deprecation_info }
 	}
 }
 
 impl From<PalletCallMetadataIR> for PalletCallMetadata {
 	fn from(ir: PalletCallMetadataIR) -> Self {
-		PalletCallMetadata { ty: ir.ty, deprecation_info: ir.deprecation_info.into() }
+		let deprecation_info = enum_deprecation_info(&ir.ty, ir.deprecation_info);
+		PalletCallMetadata { ty: ir.ty, deprecation_info }
 	}
 }
 
@@ -162,9 +190,11 @@ impl From<TransactionExtensionMetadataIR> for TransactionExtensionMetadata {
 
 impl From<ExtrinsicMetadataIR> for ExtrinsicMetadata {
 	fn from(ir: ExtrinsicMetadataIR) -> Self {
-		// Assume version 0 for all extensions.
-		let indexes = (0..ir.extensions.len()).map(|index| index as u32).collect();
-		let transaction_extensions_by_version = [(0, indexes)].iter().cloned().collect();
+		let transaction_extensions_by_version = ir
+			.extensions_by_version
+			.into_iter()
+			.map(|(version, indexes)| (version, indexes.into_iter().map(Compact).collect()))
+			.collect();
 
 		ExtrinsicMetadata {
 			versions: ir.versions,
@@ -186,26 +216,46 @@ impl From<OuterEnumsIR> for OuterEnums {
 	}
 }
 
-impl From<DeprecationStatusIR> for DeprecationStatus {
+impl From<DeprecationStatusIR> for ItemDeprecationInfo {
 	fn from(ir: DeprecationStatusIR) -> Self {
 		match ir {
-			DeprecationStatusIR::NotDeprecated => DeprecationStatus::NotDeprecated,
-			DeprecationStatusIR::DeprecatedWithoutNote => DeprecationStatus::DeprecatedWithoutNote,
+			DeprecationStatusIR::NotDeprecated => ItemDeprecationInfo::NotDeprecated,
+			DeprecationStatusIR::DeprecatedWithoutNote =>
+				ItemDeprecationInfo::DeprecatedWithoutNote,
 			DeprecationStatusIR::Deprecated { since, note } =>
-				DeprecationStatus::Deprecated { since, note },
+				ItemDeprecationInfo::Deprecated { since, note },
 		}
 	}
 }
 
-impl From<DeprecationInfoIR> for DeprecationInfo {
-	fn from(ir: DeprecationInfoIR) -> Self {
-		match ir {
-			DeprecationInfoIR::NotDeprecated => DeprecationInfo::NotDeprecated,
-			DeprecationInfoIR::ItemDeprecated(status) =>
-				DeprecationInfo::ItemDeprecated(status.into()),
-			DeprecationInfoIR::VariantsDeprecated(btree) => DeprecationInfo::VariantsDeprecated(
-				btree.into_iter().map(|(key, value)| (key.0, value.into())).collect(),
-			),
-		}
+/// Deprecation of a single variant, `None` if the variant is not deprecated.
+fn variant_deprecation_info(ir: DeprecationStatusIR) -> Option<VariantDeprecationInfo> {
+	match ir {
+		DeprecationStatusIR::NotDeprecated => None,
+		DeprecationStatusIR::DeprecatedWithoutNote =>
+			Some(VariantDeprecationInfo::DeprecatedWithoutNote),
+		DeprecationStatusIR::Deprecated { since, note } =>
+			Some(VariantDeprecationInfo::Deprecated { since, note }),
 	}
 }
+
+/// Deprecation of the enum `ty` of calls, events or errors.
+///
+/// V16 only records deprecation per variant, so deprecating the whole enum is expressed by
+/// deprecating each of its variants.
+fn enum_deprecation_info(ty: &MetaType, ir: DeprecationInfoIR) -> EnumDeprecationInfo {
+	let variants = match ir {
+		DeprecationInfoIR::NotDeprecated => Default::default(),
+		DeprecationInfoIR::VariantsDeprecated(variants) => variants
+			.into_iter()
+			.filter_map(|(index, status)| Some((index.0, variant_deprecation_info(status)?)))
+			.collect(),
+		DeprecationInfoIR::ItemDeprecated(status) =>
+			match (variant_deprecation_info(status), ty.type_info().type_def) {
+				(Some(status), TypeDef::Variant(def)) =>
+					def.variants.iter().map(|variant| (variant.index, status.clone())).collect(),
+				_ => Default::default(),
+			},
+	};
+	EnumDeprecationInfo(variants)
+}
@@ -1415,6 +1415,12 @@ pub trait ExtrinsicMetadata {
 	/// By format we mean the encoded representation of the `Extrinsic`.
 	const VERSIONS: &'static [u8];
 
+	/// The versions of the transaction extension pipeline accepted by this `Extrinsic`.
+	///
+	/// Defaults to `[0]`, the only version supported by extrinsics that don't version their
+	/// transaction extensions.
+	const TRANSACTION_EXTENSION_VERSIONS: &'static [u8] = &[0];
+
 	/// Transaction extensions attached to this `Extrinsic`.
 	type TransactionExtensions;
 }
@@ -376,6 +376,9 @@ pub trait Backend<H: Hasher>: core::fmt::Debug {
 		unimplemented!()
 	}
 
+	/// Mark the start or the end of a benchmark repetition
+	fn mark_repetition(&self, _start: bool) {}
+
 	/// Extend storage info for benchmarking db
 	fn get_read_and_written_keys(&self) -> Vec<(Vec<u8>, u32, u32, bool)> {
 		unimplemented!()
@@ -645,6 +645,10 @@ where
 		self.backend.proof_size()
 	}
 
+	fn mark_repetition(&mut self, start: bool) {
+		self.backend.mark_repetition(start)
+	}
+
 	fn get_read_and_written_keys(&self) -> Vec<(Vec<u8>, u32, u32, bool)> {
 		self.backend.get_read_and_written_keys()
 	}
@@ -304,12 +304,35 @@ impl<S: TrieBackendStorage<H>, H: Hasher, C: TrieCacheProvider<H>, R: TrieRecord
 			Option<&mut dyn TrieRecorder<H::Out>>,
 			Option<&mut dyn TrieCache<NodeCodec<H>>>,
 		) -> RE,
+	) -> RE {
+		self.with_optionally_iteration_recorder_and_cache(storage_root, false, callback)
+	}
+
+	/// Call the given closure passing it the recorder and the cache.
+	///
+	/// Same as [`Self::with_recorder_and_cache`], but if `iteration` is `true` the recorder is
+	/// created with [`TrieRecorderProvider::as_iteration_trie_recorder`].
+	#[inline]
+	fn with_optionally_iteration_recorder_and_cache<RE>(
+		&self,
+		storage_root: Option<H::Out>,
+		iteration: bool,
+		callback: impl FnOnce(
+			Option<&mut dyn TrieRecorder<H::Out>>,
+			Option<&mut dyn TrieCache<NodeCodec<H>>>,
+		) -> RE,
 	) -> RE {
 		let storage_root = storage_root.unwrap_or_else(|| self.root);
 		let mut cache = self.trie_node_cache.as_ref().map(|c| c.as_trie_db_cache(storage_root));
 		let cache = cache.as_mut().map(|c| c as _);
 
-		let mut recorder = self.recorder.as_ref().map(|r| r.as_trie_recorder(storage_root));
+		let mut recorder = self.recorder.as_ref().map(|r| {
+			if iteration {
+				r.as_iteration_trie_recorder(storage_root)
+			} else {
+				r.as_trie_recorder(storage_root)
+			}
+		});
 		let recorder = match recorder.as_mut() {
 			Some(recorder) => Some(recorder as &mut dyn TrieRecorder<H::Out>),
 			None => None,
@@ -368,6 +391,8 @@ where
 {
 	/// Calls the given closure with a [`TrieDb`] constructed for the given
 	/// storage root and (optionally) child trie.
+	///
+	/// Only used for iterating, so the recorder doesn't attribute the recorded nodes to keys.
 	#[inline]
 	fn with_trie_db<RE>(
 		&self,
@@ -381,7 +406,7 @@ where
 			.map(|child_info| KeySpacedDB::new(backend, child_info.keyspace()));
 		let db = db.as_ref().map(|db| db as &dyn HashDBRef<H, Vec<u8>>).unwrap_or(backend);
 
-		self.with_recorder_and_cache(Some(root), |recorder, cache| {
+		self.with_optionally_iteration_recorder_and_cache(Some(root), true, |recorder, cache| {
 			let trie = TrieDBBuilder::<H>::new(db, &root)
 				.with_optional_recorder(recorder)
 				.with_optional_cache(cache)
@@ -174,6 +174,14 @@ pub trait TrieRecorderProvider<H: Hasher> {
 
 	/// Provide a recorder implementing [`trie_db::TrieRecorder`].
 	fn as_trie_recorder(&self, storage_root: H::Out) -> Self::Recorder<'_>;
+
+	/// Provide a recorder implementing [`trie_db::TrieRecorder`] for iterating the trie.
+	///
+	/// Recorders that attribute the recorded nodes to the accessed keys should not attribute the
+	/// nodes recorded while iterating. Defaults to [`Self::as_trie_recorder`].
+	fn as_iteration_trie_recorder(&self, storage_root: H::Out) -> Self::Recorder<'_> {
+		self.as_trie_recorder(storage_root)
+	}
 }
 
 /// Type that is able to provide a proof size estimation.
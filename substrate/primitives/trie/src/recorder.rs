@@ -66,6 +66,9 @@ struct RecorderInner<H> {
 	///
 	/// Mapping: `Hash(Node) -> Node`.
 	accessed_nodes: HashMap<H, Vec<u8>>,
+
+	/// The keys whose access recorded the nodes, if enabled.
+	size_attribution: Option<SizeAttribution<H>>,
 }
 
 impl<H> Default for RecorderInner<H> {
@@ -74,10 +77,48 @@ impl<H> Default for RecorderInner<H> {
 			recorded_keys: Default::default(),
 			accessed_nodes: Default::default(),
 			transactions: Vec::new(),
+			size_attribution: None,
 		}
 	}
 }
 
+/// Tracks which key access recorded which node.
+///
+/// Trie nodes are recorded while walking down to a key and the key itself is only reported at the
+/// end of the walk. So the nodes a [`TrieRecorder`] recorded since its last key access are
+/// attributed to its next one. Nodes of a walk that doesn't end in a key access are dropped
+/// together with the [`TrieRecorder`] and stay unattributed, as do all nodes recorded by
+/// [`Recorder::as_iteration_trie_recorder`].
+struct SizeAttribution<H> {
+	/// Mapping: `Hash(Node) -> (StorageRoot, Key)`.
+	owners: HashMap<H, (H, Arc<[u8]>)>,
+}
+
+impl<H> Default for SizeAttribution<H> {
+	fn default() -> Self {
+		Self { owners: Default::default() }
+	}
+}
+
+/// The encoded size of the recorded nodes, attributed to the keys whose access recorded them.
+///
+/// Returned by [`Recorder::recorded_sizes`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RecordedSizes<H> {
+	/// Mapping: `StorageRoot -> (Key -> Encoded size)`.
+	///
+	/// There are multiple tries when working with e.g. child tries.
+	pub per_key: HashMap<H, HashMap<Arc<[u8]>, usize>>,
+	/// The encoded size of nodes that were recorded without accessing a key, e.g. while iterating.
+	pub unattributed: usize,
+}
+
+impl<H> Default for RecordedSizes<H> {
+	fn default() -> Self {
+		Self { per_key: Default::default(), unattributed: 0 }
+	}
+}
+
 /// The trie recorder.
 ///
 /// Owns the recorded data. Is used to transform data into a storage
@@ -126,10 +167,22 @@ impl<H: Hasher> Recorder<H> {
 			inner: self.inner.lock(),
 			storage_root,
 			encoded_size_estimation: self.encoded_size_estimation.clone(),
+			pending: Some(Vec::new()),
 			_phantom: PhantomData,
 		}
 	}
 
+	/// Returns the recorder as [`TrieRecorder`](trie_db::TrieRecorder) compatible type for
+	/// iterating the trie.
+	///
+	/// Works like [`Self::as_trie_recorder`], but the recorded nodes are never attributed to a key
+	/// by [`Self::recorded_sizes`]. Iterating records the nodes of a whole range of keys, so they
+	/// can't be attributed to the key that is reported next.
+	#[inline]
+	pub fn as_iteration_trie_recorder(&self, storage_root: H::Out) -> TrieRecorder<'_, H> {
+		TrieRecorder::<H> { pending: None, ..self.as_trie_recorder(storage_root) }
+	}
+
 	/// Drain the recording into a [`StorageProof`].
 	///
 	/// While a recorder can be cloned, all share the same internal state. After calling this
@@ -164,12 +217,49 @@ impl<H: Hasher> Recorder<H> {
 
 	/// Reset the state.
 	///
-	/// This discards all recorded data.
+	/// This discards all recorded data. Size attribution stays enabled if it was enabled before.
 	pub fn reset(&self) {
-		mem::take(&mut *self.inner.lock());
+		let mut inner = self.inner.lock();
+		let size_attribution = inner.size_attribution.is_some();
+		mem::take(&mut *inner);
+		inner.size_attribution = size_attribution.then(Default::default);
 		self.encoded_size_estimation.store(0, Ordering::Relaxed);
 	}
 
+	/// Enable attributing the size of recorded nodes to the keys whose access recorded them.
+	///
+	/// Only nodes that are recorded after calling this function are attributed. Use
+	/// [`Self::recorded_sizes`] to fetch the result.
+	pub fn enable_size_attribution(&self) {
+		self.inner.lock().size_attribution.get_or_insert_with(Default::default);
+	}
+
+	/// Returns the encoded size of the recorded nodes per accessed key.
+	///
+	/// A node is attributed to the first key whose access recorded it. Returns `None` if
+	/// [`Self::enable_size_attribution`] was not called.
+	pub fn recorded_sizes(&self) -> Option<RecordedSizes<H::Out>> {
+		let inner = self.inner.lock();
+		let attribution = inner.size_attribution.as_ref()?;
+		let mut sizes = RecordedSizes::default();
+
+		for (hash, node) in inner.accessed_nodes.iter() {
+			let size = node.encoded_size();
+			match attribution.owners.get(hash) {
+				Some((storage_root, key)) =>
+					*sizes
+						.per_key
+						.entry(*storage_root)
+						.or_default()
+						.entry(key.clone())
+						.or_default() += size,
+				None => sizes.unattributed += size,
+			}
+		}
+
+		Some(sizes)
+	}
+
 	/// Start a new transaction.
 	pub fn start_transaction(&self) {
 		let mut inner = self.inner.lock();
@@ -248,6 +338,8 @@ pub struct TrieRecorder<'a, H: Hasher> {
 	inner: MutexGuard<'a, RecorderInner<H::Out>>,
 	storage_root: H::Out,
 	encoded_size_estimation: Arc<AtomicUsize>,
+	/// Nodes that were recorded since the last key access, `None` if they are not attributed.
+	pending: Option<Vec<H::Out>>,
 	_phantom: PhantomData<H>,
 }
 
@@ -264,11 +356,38 @@ impl<H: Hasher> crate::TrieRecorderProvider<H> for Recorder<H> {
 	fn as_trie_recorder(&self, storage_root: H::Out) -> Self::Recorder<'_> {
 		Recorder::as_trie_recorder(&self, storage_root)
 	}
+
+	fn as_iteration_trie_recorder(&self, storage_root: H::Out) -> Self::Recorder<'_> {
+		Recorder::as_iteration_trie_recorder(&self, storage_root)
+	}
 }
 
 impl<'a, H: Hasher> TrieRecorder<'a, H> {
+	/// Remember `hash` as pending, if size attribution is enabled.
+	fn push_pending(&mut self, hash: H::Out) {
+		if let Some(pending) = self.pending.as_mut() {
+			if self.inner.size_attribution.is_some() {
+				pending.push(hash);
+			}
+		}
+	}
+
+	/// Attribute all pending nodes to `full_key`.
+	fn attribute_pending(&mut self, full_key: &[u8]) {
+		let Some(pending) = self.pending.as_mut().filter(|p| !p.is_empty()) else { return };
+		let Some(attribution) = self.inner.size_attribution.as_mut() else { return };
+
+		let storage_root = self.storage_root;
+		let key: Arc<[u8]> = full_key.into();
+		for hash in pending.drain(..) {
+			attribution.owners.insert(hash, (storage_root, key.clone()));
+		}
+	}
+
 	/// Update the recorded keys entry for the given `full_key`.
 	fn update_recorded_keys(&mut self, full_key: &[u8], access: RecordedForKey) {
+		self.attribute_pending(full_key);
+
 		let inner = self.inner.deref_mut();
 
 		let entry =
@@ -323,11 +442,13 @@ impl<'a, H: Hasher> trie_db::TrieRecorder<H::Out> for TrieRecorder<'a, H> {
 				);
 
 				let inner = self.inner.deref_mut();
+				let mut newly_recorded = false;
 
 				inner.accessed_nodes.entry(hash).or_insert_with(|| {
 					let node = node_owned.to_encoded::<NodeCodec<H>>();
 
 					encoded_size_update += node.encoded_size();
+					newly_recorded = true;
 
 					if let Some(tx) = inner.transactions.last_mut() {
 						tx.accessed_nodes.insert(hash);
@@ -335,6 +456,10 @@ impl<'a, H: Hasher> trie_db::TrieRecorder<H::Out> for TrieRecorder<'a, H> {
 
 					node
 				});
+
+				if newly_recorded {
+					self.push_pending(hash);
+				}
 			},
 			TrieAccess::EncodedNode { hash, encoded_node } => {
 				tracing::trace!(
@@ -344,11 +469,13 @@ impl<'a, H: Hasher> trie_db::TrieRecorder<H::Out> for TrieRecorder<'a, H> {
 				);
 
 				let inner = self.inner.deref_mut();
+				let mut newly_recorded = false;
 
 				inner.accessed_nodes.entry(hash).or_insert_with(|| {
 					let node = encoded_node.into_owned();
 
 					encoded_size_update += node.encoded_size();
+					newly_recorded = true;
 
 					if let Some(tx) = inner.transactions.last_mut() {
 						tx.accessed_nodes.insert(hash);
@@ -356,6 +483,10 @@ impl<'a, H: Hasher> trie_db::TrieRecorder<H::Out> for TrieRecorder<'a, H> {
 
 					node
 				});
+
+				if newly_recorded {
+					self.push_pending(hash);
+				}
 			},
 			TrieAccess::Value { hash, value, full_key } => {
 				tracing::trace!(
@@ -366,11 +497,13 @@ impl<'a, H: Hasher> trie_db::TrieRecorder<H::Out> for TrieRecorder<'a, H> {
 				);
 
 				let inner = self.inner.deref_mut();
+				let mut newly_recorded = false;
 
 				inner.accessed_nodes.entry(hash).or_insert_with(|| {
 					let value = value.into_owned();
 
 					encoded_size_update += value.encoded_size();
+					newly_recorded = true;
 
 					if let Some(tx) = inner.transactions.last_mut() {
 						tx.accessed_nodes.insert(hash);
@@ -379,6 +512,10 @@ impl<'a, H: Hasher> trie_db::TrieRecorder<H::Out> for TrieRecorder<'a, H> {
 					value
 				});
 
+				if newly_recorded {
+					self.push_pending(hash);
+				}
+
 				self.update_recorded_keys(full_key, RecordedForKey::Value);
 			},
 			TrieAccess::Hash { full_key } => {
@@ -632,6 +769,91 @@ mod tests {
 		}
 	}
 
+	#[test]
+	fn recorded_sizes_works() {
+		let (db, root) = create_trie::<Layout>(TEST_DATA);
+
+		let recorder = Recorder::default();
+		assert!(recorder.recorded_sizes().is_none());
+		recorder.enable_size_attribution();
+
+		for (key, value) in TEST_DATA.iter().take(2) {
+			let mut trie_recorder = recorder.as_trie_recorder(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root)
+				.with_recorder(&mut trie_recorder)
+				.build();
+			assert_eq!(value.to_vec(), trie.get(key).unwrap().unwrap());
+		}
+
+		let sizes = recorder.recorded_sizes().unwrap();
+		assert_eq!(sizes.unattributed, 0);
+		assert_eq!(sizes.per_key.len(), 1);
+
+		let per_key = &sizes.per_key[&root];
+		let (first, second) = (per_key[TEST_DATA[0].0], per_key[TEST_DATA[1].0]);
+		// The first access also paid for the nodes that both keys share.
+		assert!(first > second && second > 0, "{first} {second}");
+		assert_eq!(first + second, recorder.estimate_encoded_size());
+
+		// Reading a key again does not add anything.
+		{
+			let mut trie_recorder = recorder.as_trie_recorder(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root)
+				.with_recorder(&mut trie_recorder)
+				.build();
+			trie.get(TEST_DATA[1].0).unwrap().unwrap();
+		}
+		assert_eq!(recorder.recorded_sizes().unwrap(), sizes);
+
+		// The attribution survives a reset.
+		recorder.reset();
+		assert_eq!(recorder.recorded_sizes(), Some(Default::default()));
+	}
+
+	#[test]
+	fn recorded_sizes_does_not_attribute_iteration() {
+		let (db, root) = create_trie::<Layout>(TEST_DATA);
+
+		let recorder = Recorder::default();
+		recorder.enable_size_attribution();
+
+		// A walk that doesn't access a key leaves its nodes unattributed.
+		{
+			let mut trie_recorder = recorder.as_trie_recorder(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root)
+				.with_recorder(&mut trie_recorder)
+				.build();
+			trie.key_iter().unwrap().next().unwrap().unwrap();
+		}
+		let recorded = recorder.estimate_encoded_size();
+		assert!(recorded > 0);
+
+		// As do all nodes recorded while iterating, even the values.
+		{
+			let mut trie_recorder = recorder.as_iteration_trie_recorder(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root)
+				.with_recorder(&mut trie_recorder)
+				.build();
+			for item in trie.iter().unwrap() {
+				item.unwrap();
+			}
+		}
+		assert!(recorder.estimate_encoded_size() > recorded);
+
+		// Accessing a key afterwards doesn't take over the nodes recorded before.
+		{
+			let mut trie_recorder = recorder.as_trie_recorder(root);
+			let trie = TrieDBBuilder::<Layout>::new(&db, &root)
+				.with_recorder(&mut trie_recorder)
+				.build();
+			assert_eq!(TEST_DATA[0].1.to_vec(), trie.get(TEST_DATA[0].0).unwrap().unwrap());
+		}
+
+		let sizes = recorder.recorded_sizes().unwrap();
+		assert!(sizes.per_key.is_empty());
+		assert_eq!(sizes.unattributed, recorder.estimate_encoded_size());
+	}
+
 	#[test]
 	fn recorder_transaction_accessed_keys_works() {
 		let key = TEST_DATA[0].0;
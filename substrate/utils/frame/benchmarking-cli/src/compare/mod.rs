@@ -0,0 +1,299 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Contains the [`CompareCmd`] that compares the results of two benchmark runs.
+
+mod weights;
+
+use std::{
+	collections::{BTreeMap, BTreeSet},
+	fs,
+	path::{Path, PathBuf},
+};
+
+use clap::Parser;
+use comfy_table::Table;
+use frame_benchmarking::{AnalysisChoice, BenchmarkBatchSplitResults};
+use log::{info, warn};
+
+use sc_cli::{CliConfiguration, Result, SharedParams};
+
+use weights::{ExtrinsicWeight, Weights};
+
+/// Compare the weights of two benchmark runs to detect regressions.
+///
+/// Accepts either two `--json-file` outputs of the `pallet` command or two generated
+/// `weights.rs` files. Prints the change of the base weight, the component slopes, the proof
+/// size and the database reads and writes of every extrinsic and fails if any of them increased
+/// by more than `--threshold`.
+#[derive(Debug, Parser)]
+pub struct CompareCmd {
+	#[allow(missing_docs)]
+	#[clap(flatten)]
+	pub shared_params: SharedParams,
+
+	/// The results of the old run.
+	///
+	/// Files with a `.json` extension are treated as `--json-file` output, all others as weight
+	/// files.
+	#[arg(value_name = "OLD")]
+	pub old: PathBuf,
+
+	/// The results of the new run; must have the same format as `OLD`.
+	#[arg(value_name = "NEW")]
+	pub new: PathBuf,
+
+	/// Maximal tolerated increase of any weight term.
+	#[arg(long, default_value_t = 10.0, value_name = "PERCENT")]
+	pub threshold: f64,
+
+	/// Only print the terms that changed.
+	#[arg(long)]
+	pub changed_only: bool,
+
+	/// Which analysis function to use when analyzing JSON results.
+	///
+	/// Should match the `--output-analysis` of the `pallet` command.
+	#[arg(long)]
+	pub output_analysis: Option<String>,
+
+	/// Which analysis function to use when analyzing measured proof sizes of JSON results.
+	///
+	/// Should match the `--output-pov-analysis` of the `pallet` command.
+	#[arg(long, default_value("median-slopes"))]
+	pub output_pov_analysis: Option<String>,
+}
+
+/// Errors that can be returned by this command.
+#[derive(Debug, thiserror::Error)]
+#[allow(missing_docs)]
+pub enum Error {
+	#[error("{0} weight terms increased by more than {1}%")]
+	Regression(usize, f64),
+
+	#[error("Cannot compare a JSON file with a weight file")]
+	MixedFormats,
+}
+
+/// The change of a single term of a weight formula.
+#[derive(Debug, Clone, PartialEq)]
+struct Change {
+	/// The extrinsic this term belongs to.
+	extrinsic: String,
+	/// The term, for example `base ref_time`, `proof_size * n` or `base reads`.
+	term: String,
+	old: u128,
+	new: u128,
+}
+
+impl Change {
+	/// The relative change in percent or `None` if the term is new.
+	fn percent(&self) -> Option<f64> {
+		match (self.old, self.new) {
+			(0, 0) => Some(0.0),
+			(0, _) => None,
+			(old, new) => Some((new as f64 - old as f64) / old as f64 * 100.0),
+		}
+	}
+
+	/// Whether the term increased by more than `threshold` percent.
+	fn exceeds(&self, threshold: f64) -> bool {
+		match self.percent() {
+			_ if self.new <= self.old => false,
+			Some(percent) => percent > threshold,
+			None => true,
+		}
+	}
+}
+
+impl CompareCmd {
+	/// Compare both inputs and print the results.
+	pub fn run(&self) -> Result<()> {
+		let (old, new) = match (self.load(&self.old)?, self.load(&self.new)?) {
+			((old, true), (new, true)) | ((old, false), (new, false)) => (old, new),
+			_ => return Err(sc_cli::Error::Application(Box::new(Error::MixedFormats))),
+		};
+
+		for name in old.keys().filter(|name| !new.contains_key(*name)) {
+			warn!("Extrinsic {} was removed", name);
+		}
+		for name in new.keys().filter(|name| !old.contains_key(*name)) {
+			warn!("Extrinsic {} was added", name);
+		}
+
+		let changes = compare(&old, &new);
+		let regressions = changes.iter().filter(|c| c.exceeds(self.threshold)).count();
+		self.print_summary(&changes);
+
+		if regressions != 0 {
+			return Err(sc_cli::Error::Application(Box::new(Error::Regression(
+				regressions,
+				self.threshold,
+			))))
+		}
+		info!("No weight term increased by more than {}%", self.threshold);
+		Ok(())
+	}
+
+	/// Load the weights from a file and return whether it was a JSON file.
+	fn load(&self, path: &Path) -> Result<(Weights, bool)> {
+		let content =
+			fs::read_to_string(path).map_err(|e| format!("Failed to read {:?}: {}", path, e))?;
+
+		if path.extension().is_some_and(|e| e == "json") {
+			let batches: Vec<BenchmarkBatchSplitResults> = serde_json::from_str(&content)
+				.map_err(|e| format!("Failed to deserialize {:?}: {}", path, e))?;
+			let analysis_choice: AnalysisChoice =
+				self.output_analysis.clone().try_into().map_err(|e: &str| e.to_string())?;
+			let pov_analysis_choice: AnalysisChoice =
+				self.output_pov_analysis.clone().try_into().map_err(|e: &str| e.to_string())?;
+			let weights = weights::from_json(&batches, &analysis_choice, &pov_analysis_choice)?;
+			return Ok((weights, true))
+		}
+
+		let weights = weights::from_weight_file(&content)
+			.map_err(|e| format!("Failed to parse {:?}: {}", path, e))?;
+		if weights.is_empty() {
+			return Err(format!("No weight functions found in {:?}", path).into())
+		}
+		Ok((weights, false))
+	}
+
+	/// Print a table with all changes.
+	fn print_summary(&self, changes: &[Change]) {
+		let mut table = Table::new();
+		table.set_header(["Extrinsic", "Term", "Old", "New", "Change", "Result"]);
+
+		for change in changes {
+			if self.changed_only && change.old == change.new {
+				continue
+			}
+			let percent = change.percent().map_or("new".into(), |p| format!("{:+.2} %", p));
+			let result = if change.exceeds(self.threshold) { "❌ Regression" } else { "✅ Pass" };
+			table.add_row([
+				change.extrinsic.clone(),
+				change.term.clone(),
+				change.old.to_string(),
+				change.new.to_string(),
+				percent,
+				result.into(),
+			]);
+		}
+
+		info!("\n{}", table);
+	}
+}
+
+/// Compare all terms of the extrinsics that exist in both inputs.
+fn compare(old: &Weights, new: &Weights) -> Vec<Change> {
+	let mut changes = Vec::new();
+
+	for (extrinsic, old) in old.iter() {
+		let Some(new) = new.get(extrinsic) else { continue };
+		let mut push = |term: String, old: u128, new: u128| {
+			changes.push(Change { extrinsic: extrinsic.clone(), term, old, new })
+		};
+
+		push("base ref_time".into(), old.base_ref_time, new.base_ref_time);
+		for (component, old, new) in slopes(old, new, |w| &w.ref_time_slopes) {
+			push(format!("ref_time * {}", component), old, new);
+		}
+		push("base proof_size".into(), old.base_proof_size, new.base_proof_size);
+		for (component, old, new) in slopes(old, new, |w| &w.proof_size_slopes) {
+			push(format!("proof_size * {}", component), old, new);
+		}
+		push("base reads".into(), old.base_reads, new.base_reads);
+		for (component, old, new) in slopes(old, new, |w| &w.read_slopes) {
+			push(format!("reads * {}", component), old, new);
+		}
+		push("base writes".into(), old.base_writes, new.base_writes);
+		for (component, old, new) in slopes(old, new, |w| &w.write_slopes) {
+			push(format!("writes * {}", component), old, new);
+		}
+	}
+
+	changes
+}
+
+/// Pair up the slopes of all components that are used by either side.
+fn slopes(
+	old: &ExtrinsicWeight,
+	new: &ExtrinsicWeight,
+	select: impl Fn(&ExtrinsicWeight) -> &BTreeMap<String, u128>,
+) -> Vec<(String, u128, u128)> {
+	let (old, new) = (select(old), select(new));
+	let components = old.keys().chain(new.keys()).collect::<BTreeSet<_>>();
+
+	components
+		.into_iter()
+		.map(|c| {
+			let (o, n) = (old.get(c).copied(), new.get(c).copied());
+			(c.clone(), o.unwrap_or_default(), n.unwrap_or_default())
+		})
+		.collect()
+}
+
+impl CliConfiguration for CompareCmd {
+	fn shared_params(&self) -> &SharedParams {
+		&self.shared_params
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn weight(base: u128, slope: u128) -> ExtrinsicWeight {
+		ExtrinsicWeight {
+			base_ref_time: base,
+			base_proof_size: 3593,
+			base_reads: 2,
+			ref_time_slopes: [("n".to_string(), slope)].into(),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn compare_works() {
+		let old = Weights::from([("a".into(), weight(1000, 10)), ("b".into(), weight(1000, 0))]);
+		let mut new_b = weight(900, 0);
+		new_b.proof_size_slopes.insert("n".into(), 5);
+		new_b.base_reads = 3;
+		new_b.write_slopes.insert("n".into(), 1);
+		let new = Weights::from([("a".into(), weight(1200, 10)), ("b".into(), new_b)]);
+
+		let changes = compare(&old, &new);
+		let regressions =
+			changes.iter().filter(|c| c.exceeds(10.0)).map(|c| &c.term).collect::<Vec<_>>();
+		// `a` got 20% more expensive, `b` now has a proof size slope, reads once more and writes
+		// per `n`.
+		assert_eq!(regressions, ["base ref_time", "proof_size * n", "base reads", "writes * n"]);
+		assert_eq!(changes.iter().filter(|c| c.exceeds(25.0)).count(), 3);
+	}
+
+	#[test]
+	fn change_percent_works() {
+		let change = |old, new| Change { extrinsic: "a".into(), term: "b".into(), old, new };
+
+		assert_eq!(change(100, 150).percent(), Some(50.0));
+		assert_eq!(change(100, 50).percent(), Some(-50.0));
+		assert_eq!(change(0, 0).percent(), Some(0.0));
+		assert_eq!(change(0, 1).percent(), None);
+		assert!(change(0, 1).exceeds(1000.0));
+		assert!(!change(100, 50).exceeds(0.0));
+	}
+}
@@ -0,0 +1,342 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Reads the weights of extrinsics from benchmark JSON output or generated `weights.rs` files.
+
+use std::collections::BTreeMap;
+
+use frame_benchmarking::{
+	Analysis, AnalysisChoice, BenchmarkBatchSplitResults, BenchmarkResult, BenchmarkSelector,
+};
+
+/// The weight formula of a single extrinsic.
+///
+/// All values use the units of the weight files: picoseconds for `ref_time`, bytes for the
+/// `proof_size` and the number of accesses for database reads and writes.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct ExtrinsicWeight {
+	/// The constant `ref_time` part.
+	pub base_ref_time: u128,
+	/// The constant `proof_size` part.
+	pub base_proof_size: u128,
+	/// The constant number of database reads.
+	pub base_reads: u128,
+	/// The constant number of database writes.
+	pub base_writes: u128,
+	/// The `ref_time` slope per component.
+	pub ref_time_slopes: BTreeMap<String, u128>,
+	/// The `proof_size` slope per component.
+	pub proof_size_slopes: BTreeMap<String, u128>,
+	/// The database reads slope per component.
+	pub read_slopes: BTreeMap<String, u128>,
+	/// The database writes slope per component.
+	pub write_slopes: BTreeMap<String, u128>,
+}
+
+/// Weight formulas by extrinsic name.
+pub(crate) type Weights = BTreeMap<String, ExtrinsicWeight>;
+
+/// Analyze the raw results of a `--json-file` output.
+///
+/// Extrinsics are named `pallet::benchmark`. Since the JSON output carries no storage info, the
+/// `proof_size` is derived from the measured proof sizes and not from the worst-case estimation.
+pub(crate) fn from_json(
+	batches: &[BenchmarkBatchSplitResults],
+	analysis_choice: &AnalysisChoice,
+	pov_analysis_choice: &AnalysisChoice,
+) -> Result<Weights, String> {
+	let mut weights = Weights::new();
+
+	for batch in batches {
+		// Skip if there are no results
+		if batch.time_results.is_empty() {
+			continue
+		}
+
+		let pallet = String::from_utf8_lossy(&batch.pallet);
+		let benchmark = String::from_utf8_lossy(&batch.benchmark);
+		let name = format!("{}::{}", pallet, benchmark);
+
+		let ref_time = analysis_function(analysis_choice)(
+			&batch.time_results,
+			BenchmarkSelector::ExtrinsicTime,
+		)
+		.ok_or_else(|| format!("Could not analyze the extrinsic time of {}", name))?;
+		let proof_size =
+			analysis_function(pov_analysis_choice)(&batch.db_results, BenchmarkSelector::ProofSize)
+				.ok_or_else(|| format!("Could not analyze the proof size of {}", name))?;
+		let reads = analysis_function(analysis_choice)(&batch.db_results, BenchmarkSelector::Reads)
+			.ok_or_else(|| format!("Could not analyze the reads of {}", name))?;
+		let writes =
+			analysis_function(analysis_choice)(&batch.db_results, BenchmarkSelector::Writes)
+				.ok_or_else(|| format!("Could not analyze the writes of {}", name))?;
+
+		weights.insert(
+			name,
+			ExtrinsicWeight {
+				base_ref_time: ref_time.base,
+				base_proof_size: proof_size.base,
+				base_reads: reads.base,
+				base_writes: writes.base,
+				ref_time_slopes: used_slopes(&ref_time),
+				proof_size_slopes: used_slopes(&proof_size),
+				read_slopes: used_slopes(&reads),
+				write_slopes: used_slopes(&writes),
+			},
+		);
+	}
+
+	Ok(weights)
+}
+
+/// Parse the weight functions of a `weights.rs` file as generated by the `pallet` command.
+///
+/// Only the first implementation of each function is considered, since files generated by the
+/// default template implement the same weights for `SubstrateWeight<T>` and `()`.
+pub(crate) fn from_weight_file(content: &str) -> Result<Weights, String> {
+	let mut weights = Weights::new();
+	// The name and the comment-stripped body of the function that is currently being parsed.
+	let mut current: Option<(String, String)> = None;
+
+	for line in content.lines() {
+		let code = line.split("//").next().unwrap_or_default().trim();
+
+		match current.as_mut() {
+			None => {
+				let Some(signature) = code.strip_prefix("fn ") else { continue };
+				if !signature.ends_with("-> Weight {") {
+					continue
+				}
+				let name = signature.split('(').next().unwrap_or_default().trim();
+				current = Some((name.into(), String::new()));
+			},
+			Some((name, body)) if code == "}" => {
+				if !weights.contains_key(name.as_str()) {
+					let weight = parse_body(body).map_err(|e| format!("{}: {}", name, e))?;
+					weights.insert(name.clone(), weight);
+				}
+				current = None;
+			},
+			Some((_, body)) => body.extend(code.chars().filter(|c| !c.is_whitespace())),
+		}
+	}
+
+	Ok(weights)
+}
+
+/// Sum up all `Weight::from_parts` and `DbWeight` read and write terms of a whitespace-free
+/// function body.
+fn parse_body(body: &str) -> Result<ExtrinsicWeight, String> {
+	const FROM_PARTS: &str = "Weight::from_parts(";
+	const READS: &str = "DbWeight::get().reads(";
+	const WRITES: &str = "DbWeight::get().writes(";
+
+	let mut weight = ExtrinsicWeight::default();
+	let mut rest = body;
+
+	while let Some((start, term)) = [FROM_PARTS, READS, WRITES]
+		.into_iter()
+		.filter_map(|t| Some((rest.find(t)?, t)))
+		.min()
+	{
+		let (args, after) = split_args(&rest[start + term.len()..])
+			.ok_or_else(|| format!("unterminated `{}`", term.trim_end_matches('(')))?;
+		rest = after;
+
+		if term == FROM_PARTS {
+			let (ref_time, proof_size) =
+				args.trim_end_matches(',').split_once(',').ok_or("expected two arguments")?;
+			let (ref_time, proof_size) = (parse_number(ref_time)?, parse_number(proof_size)?);
+
+			match rest.strip_prefix(SATURATING_MUL) {
+				Some(mul) => {
+					let component = parse_component(mul)?;
+					add_slope(&mut weight.ref_time_slopes, component, ref_time);
+					add_slope(&mut weight.proof_size_slopes, component, proof_size);
+				},
+				None => {
+					weight.base_ref_time += ref_time;
+					weight.base_proof_size += proof_size;
+				},
+			}
+			continue
+		}
+
+		let (base, slopes) = if term == READS {
+			(&mut weight.base_reads, &mut weight.read_slopes)
+		} else {
+			(&mut weight.base_writes, &mut weight.write_slopes)
+		};
+		// Either a plain count like `1_u64` or a slope like `(1_u64).saturating_mul(p.into())`.
+		match args.strip_prefix('(').and_then(|args| args.split_once(')')) {
+			Some((count, mul)) => {
+				let mul = mul.strip_prefix(SATURATING_MUL).ok_or("expected `saturating_mul`")?;
+				add_slope(slopes, parse_component(mul)?, parse_number(count)?);
+			},
+			None => *base += parse_number(args)?,
+		}
+	}
+
+	Ok(weight)
+}
+
+const SATURATING_MUL: &str = ".saturating_mul(";
+
+/// Split the arguments of a call, which starts right after its opening parenthesis, from the
+/// code following its closing parenthesis.
+fn split_args(call: &str) -> Option<(&str, &str)> {
+	let mut depth = 0usize;
+	for (i, c) in call.char_indices() {
+		match c {
+			'(' => depth += 1,
+			')' if depth == 0 => return Some((&call[..i], &call[i + 1..])),
+			')' => depth -= 1,
+			_ => {},
+		}
+	}
+	None
+}
+
+/// Parse the component of a `saturating_mul` argument like `n.into())`.
+fn parse_component(mul: &str) -> Result<&str, String> {
+	let component =
+		mul.split(|c: char| !c.is_alphanumeric() && c != '_').next().unwrap_or_default();
+	if component.is_empty() {
+		return Err("expected a component in `saturating_mul`".into())
+	}
+	Ok(component)
+}
+
+/// Add a non-zero slope of `component`.
+fn add_slope(slopes: &mut BTreeMap<String, u128>, component: &str, slope: u128) {
+	if slope != 0 {
+		*slopes.entry(component.into()).or_default() += slope;
+	}
+}
+
+/// Parse a Rust integer literal like `12_345` or `3_u64`.
+fn parse_number(literal: &str) -> Result<u128, String> {
+	let digits: String = literal.chars().filter(|c| *c != '_').collect();
+	let digits = ["u64", "u128"].iter().fold(digits.as_str(), |d, s| d.trim_end_matches(s));
+	digits.parse().map_err(|e| format!("invalid number `{}`: {}", literal, e))
+}
+
+/// The analysis function that corresponds to the choice.
+fn analysis_function(
+	choice: &AnalysisChoice,
+) -> fn(&Vec<BenchmarkResult>, BenchmarkSelector) -> Option<Analysis> {
+	match choice {
+		AnalysisChoice::MinSquares => Analysis::min_squares_iqr,
+		AnalysisChoice::MedianSlopes => Analysis::median_slopes,
+		AnalysisChoice::Max => Analysis::max,
+	}
+}
+
+/// The non-zero slopes of an analysis by component name.
+fn used_slopes(analysis: &Analysis) -> BTreeMap<String, u128> {
+	analysis
+		.names
+		.iter()
+		.zip(analysis.slopes.iter())
+		.filter(|(_, slope)| **slope != 0)
+		.map(|(name, slope)| (name.clone(), *slope))
+		.collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const WEIGHTS: &str = r#"
+pub trait WeightInfo {
+	fn remark(b: u32, ) -> Weight;
+	fn set_code() -> Weight;
+}
+
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	/// The range of component `b` is `[0, 3932160]`.
+	fn remark(b: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 1_724_000 picoseconds.
+		Weight::from_parts(1_020_000, 0)
+			.saturating_add(Weight::from_parts(0, 1_500))
+			// Standard Error: 104
+			.saturating_add(Weight::from_parts(387, 0).saturating_mul(b.into()))
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().reads((2_u64).saturating_mul(b.into())))
+			.saturating_add(T::DbWeight::get().writes((1_u64).saturating_mul(b.into())))
+			.saturating_add(Weight::from_parts(0, 2_u64).saturating_mul(b.into()))
+	}
+	fn set_code() -> Weight {
+		Weight::from_parts(
+			100_000_000,
+			67035,
+		)
+			.saturating_add(T::DbWeight::get().writes(2_u64))
+	}
+}
+
+impl WeightInfo for () {
+	fn set_code() -> Weight {
+		Weight::from_parts(1, 1)
+	}
+}
+"#;
+
+	#[test]
+	fn from_weight_file_works() {
+		let weights = from_weight_file(WEIGHTS).unwrap();
+		assert_eq!(weights.len(), 2);
+
+		let remark = &weights["remark"];
+		assert_eq!(remark.base_ref_time, 1_020_000);
+		assert_eq!(remark.base_proof_size, 1_500);
+		assert_eq!(remark.ref_time_slopes, [("b".to_string(), 387)].into());
+		assert_eq!(remark.proof_size_slopes, [("b".to_string(), 2)].into());
+		assert_eq!(remark.base_reads, 1);
+		assert_eq!(remark.base_writes, 0);
+		assert_eq!(remark.read_slopes, [("b".to_string(), 2)].into());
+		assert_eq!(remark.write_slopes, [("b".to_string(), 1)].into());
+
+		// The `()` implementation is ignored.
+		let set_code = &weights["set_code"];
+		assert_eq!(set_code.base_ref_time, 100_000_000);
+		assert_eq!(set_code.base_proof_size, 67035);
+		assert!(set_code.ref_time_slopes.is_empty());
+		assert_eq!(set_code.base_writes, 2);
+	}
+
+	#[test]
+	fn from_weight_file_rejects_garbage() {
+		let err =
+			from_weight_file("fn foo() -> Weight {\nWeight::from_parts(x, 0)\n}").unwrap_err();
+		assert!(err.starts_with("foo: invalid number `x`"), "{}", err);
+
+		let err =
+			from_weight_file("fn foo() -> Weight {\nT::DbWeight::get().reads(1\n}").unwrap_err();
+		assert_eq!(err, "foo: unterminated `DbWeight::get().reads`");
+	}
+
+	#[test]
+	fn parse_number_works() {
+		assert_eq!(parse_number("1_000_u64"), Ok(1000));
+		assert_eq!(parse_number("42"), Ok(42));
+		assert!(parse_number("").is_err());
+	}
+}
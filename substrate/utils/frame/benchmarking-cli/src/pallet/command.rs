@@ -32,6 +32,7 @@ use frame_benchmarking::{
 	Analysis, BenchmarkBatch, BenchmarkBatchSplitResults, BenchmarkList, BenchmarkParameter,
 	BenchmarkResult, BenchmarkSelector,
 };
+use frame_pov_profiler::{ProofSizeProfile, StorageNames};
 use frame_support::traits::StorageInfo;
 use linked_hash_map::LinkedHashMap;
 use sc_cli::{execution_method_from_cli, ChainSpec, CliConfiguration, Result, SharedParams};
@@ -56,6 +57,7 @@ use std::{
 	collections::{BTreeMap, BTreeSet, HashMap},
 	fmt::Debug,
 	fs,
+	path::Path,
 	str::FromStr,
 	time,
 };
@@ -329,6 +331,20 @@ impl PalletCmd {
 			return Ok(())
 		}
 
+		let pov_profile_names = self.pov_profile.is_some().then(|| {
+			if let Some(recorder) = state_with_tracking.recorder() {
+				recorder.enable_size_attribution();
+			}
+			StorageNames::from_items(storage_info.iter().map(|info| {
+				(
+					String::from_utf8_lossy(&info.pallet_name).into_owned(),
+					String::from_utf8_lossy(&info.storage_name).into_owned(),
+					info.prefix.clone(),
+				)
+			}))
+		});
+		let mut pov_profiles = BTreeMap::<(String, String), ProofSizeProfile>::new();
+
 		// Run the benchmarks
 		let mut batches = Vec::new();
 		let mut batches_db = Vec::new();
@@ -437,6 +453,8 @@ impl PalletCmd {
 				// Do one loop of DB tracking.
 				{
 					let state = &state_with_tracking;
+					// Drop the sizes that a failed benchmark left behind.
+					state.take_recorded_sizes();
 					let batch: Vec<BenchmarkBatch> = match Self::exec_state_machine::<
 						std::result::Result<Vec<BenchmarkBatch>, String>,
 						_,
@@ -475,6 +493,18 @@ impl PalletCmd {
 					};
 
 					batches_db.extend(batch);
+
+					if let Some(names) = &pov_profile_names {
+						let profile = Self::largest_pov_profile(
+							state_with_tracking.take_recorded_sizes(),
+							names,
+						);
+						let largest =
+							pov_profiles.entry((pallet.clone(), extrinsic.clone())).or_default();
+						if profile.total() > largest.total() {
+							*largest = profile;
+						}
+					}
 				}
 				// Finally run a bunch of loops to get extrinsic timing information.
 				for r in 0..self.external_repeat {
@@ -549,12 +579,51 @@ impl PalletCmd {
 			return Err(format!("{} benchmarks failed", failed.len()).into())
 		}
 
+		if let Some(path) = &self.pov_profile {
+			Self::write_pov_profiles(path, pov_profiles)?;
+		}
+
 		// Combine all of the benchmark results, so that benchmarks of the same pallet/function
 		// are together.
 		let batches = combine_batches(batches, batches_db);
 		self.output(&batches, &storage_info, &component_ranges, pov_modes)
 	}
 
+	/// The proof size profile of the most expensive repetition of a benchmark.
+	fn largest_pov_profile<H: Eq + std::hash::Hash>(
+		repetitions: Vec<sc_client_db::bench::RecordedRepetition<H>>,
+		names: &StorageNames,
+	) -> ProofSizeProfile {
+		repetitions
+			.iter()
+			.map(|repetition| {
+				let ((start_root, start), (end_root, end)) = (&repetition.start, &repetition.end);
+				let start = ProofSizeProfile::from_recorded_sizes(start, start_root, names);
+				ProofSizeProfile::from_recorded_sizes(end, end_root, names).since(&start)
+			})
+			.max_by_key(|profile| profile.total())
+			.unwrap_or_default()
+	}
+
+	/// Write the proof size profiles with one `pallet::extrinsic` root frame per benchmark.
+	fn write_pov_profiles(
+		path: &Path,
+		profiles: BTreeMap<(String, String), ProofSizeProfile>,
+	) -> Result<()> {
+		let mut all = ProofSizeProfile::default();
+		for ((pallet, extrinsic), profile) in profiles {
+			all.add_below(&format!("{pallet}::{extrinsic}"), profile);
+		}
+
+		let mut file = fs::File::create(path)
+			.map_err(|e| format!("Could not create PoV profile file {:?}: {}", path, e))?;
+		all.write_folded(&mut file)
+			.map_err(|e| format!("Could not write PoV profile file {:?}: {}", path, e))?;
+		log::info!(target: LOG_TARGET, "Wrote the PoV profiles to {:?}", path);
+
+		Ok(())
+	}
+
 	fn select_benchmarks_to_run(&self, list: Vec<BenchmarkList>) -> Result<Vec<SelectedBenchmark>> {
 		let extrinsic = self.extrinsic.clone().unwrap_or_default();
 		let extrinsic_split: Vec<&str> = extrinsic.split(',').collect();
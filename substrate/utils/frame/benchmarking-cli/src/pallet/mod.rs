@@ -94,6 +94,14 @@ pub struct PalletCmd {
 	#[arg(long, conflicts_with = "json_output")]
 	pub json_file: Option<PathBuf>,
 
+	/// Write the proof size profile of every benchmark in the folded stack format to this file.
+	///
+	/// The recorded proof of the most expensive run of each benchmark is attributed to the
+	/// storage items it accessed. The file can be rendered as a flame graph, e.g. with
+	/// `inferno-flamegraph`.
+	#[arg(long, value_name = "PATH")]
+	pub pov_profile: Option<PathBuf>,
+
 	/// Don't print the median-slopes linear regression analysis.
 	#[arg(long)]
 	pub no_median_slopes: bool,
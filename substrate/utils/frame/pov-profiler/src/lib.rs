@@ -0,0 +1,213 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Proof size profiler
+//!
+//! Explains which storage accesses contributed how many bytes to the storage proof (PoV) of a
+//! block, an extrinsic or a hook.
+//!
+//! The trie [`Recorder`] can attribute every recorded trie node to the key whose access recorded
+//! it (see [`Recorder::enable_size_attribution`]). A node that is shared by multiple keys is
+//! attributed to the first one, which is exactly the key that made the proof grow. The keys are
+//! then resolved to the pallet and storage item they belong to with [`StorageNames`], and the
+//! result is collected into a [`ProofSizeProfile`]. Profiles can be printed or written in the
+//! folded stack format, which tools like `inferno-flamegraph` render as a flame graph.
+//!
+//! [`profile_block`] replays a block on top of the state of its parent and profiles the block
+//! initialization, every extrinsic and the block finalization separately.
+//! [`profile_runtime_upgrade`] profiles the single-block migrations of a runtime built with the
+//! `try-runtime` feature, on top of e.g. a state snapshot created by `try-runtime
+//! create-snapshot`. The `pallet` benchmark command of `frame-benchmarking-cli` profiles
+//! benchmarks with `--pov-profile`.
+//!
+//! The crate is both a library and a binary, which replays a block of a live chain or executes the
+//! migrations of a runtime on top of a live chain or a snapshot:
+//!
+//! ```sh
+//! frame-pov-profiler block --uri wss://my-chain-rpc.io:443 --block 0x1234... --output block.folded
+//! frame-pov-profiler on-runtime-upgrade --snapshot chain.snap --runtime runtime.wasm
+//! inferno-flamegraph block.folded > block.svg
+//! ```
+//!
+//! [`Recorder`]: sp_trie::recorder::Recorder
+//! [`Recorder::enable_size_attribution`]: sp_trie::recorder::Recorder::enable_size_attribution
+
+mod names;
+mod profile;
+
+pub use frame_storage_layout_diff::{code_in_state, runtime_metadata};
+pub use names::{StorageNames, CHILD_TRIES, UNATTRIBUTED, UNKNOWN, WELL_KNOWN};
+pub use profile::ProofSizeProfile;
+
+use codec::{Decode, Encode};
+use sc_executor::WasmExecutor;
+use sp_core::{
+	traits::{CallContext, WrappedRuntimeCode},
+	Hasher,
+};
+use sp_runtime::{
+	traits::{Block as BlockT, HashingFor},
+	ApplyExtrinsicResult,
+};
+use sp_state_machine::{OverlayedChanges, StateMachine, TestExternalities, TrieBackendBuilder};
+use sp_trie::{proof_size_extension::ProofSizeExt, recorder::Recorder};
+use std::borrow::Cow;
+
+const LOG_TARGET: &str = "pov-profiler";
+
+type HostFunctions = (
+	sp_io::SubstrateHostFunctions,
+	cumulus_primitives_proof_size_hostfunction::storage_proof_size::HostFunctions,
+);
+
+/// The frame of the block initialization, including `on_initialize`.
+pub const INITIALIZE_BLOCK: &str = "initialize_block";
+/// The frame of the block finalization, including `on_idle` and `on_finalize`.
+pub const FINALIZE_BLOCK: &str = "finalize_block";
+/// The frame of the single-block migrations, see [`profile_runtime_upgrade`].
+#[cfg(feature = "try-runtime")]
+pub const ON_RUNTIME_UPGRADE: &str = "on_runtime_upgrade";
+
+/// Replay `block` with the runtime `code` on top of `ext` and profile its proof size.
+///
+/// `ext` must hold the state of the parent block and is not modified. The block is executed the
+/// way it is built, through `Core_initialize_block`, `BlockBuilder_apply_extrinsic` per extrinsic
+/// and `BlockBuilder_finalize_block`. The returned profile has one root frame per call:
+/// [`INITIALIZE_BLOCK`], `extrinsic <index>` and [`FINALIZE_BLOCK`], each followed by the frames
+/// of [`StorageNames::frames`].
+pub fn profile_block<B: BlockT>(
+	ext: &mut TestExternalities<HashingFor<B>>,
+	code: &[u8],
+	block: B,
+	names: &StorageNames,
+) -> Result<ProofSizeProfile, String> {
+	profile_calls(ext, code, names, |call| {
+		let (header, extrinsics) = block.deconstruct();
+		call(INITIALIZE_BLOCK, "Core_initialize_block", &header.encode())?;
+
+		for (index, extrinsic) in extrinsics.iter().enumerate() {
+			let result = call(
+				&format!("extrinsic {index}"),
+				"BlockBuilder_apply_extrinsic",
+				&extrinsic.encode(),
+			)?;
+			match ApplyExtrinsicResult::decode(&mut &result[..]) {
+				Ok(Ok(_)) => {},
+				Ok(Err(e)) => return Err(format!("extrinsic {index} is invalid: {e:?}")),
+				Err(e) =>
+					return Err(format!("failed to decode the result of extrinsic {index}: {e}")),
+			}
+		}
+
+		call(FINALIZE_BLOCK, "BlockBuilder_finalize_block", &[])?;
+		Ok(())
+	})
+}
+
+/// Execute the single-block migrations of the runtime `code` on top of `ext` and profile their
+/// proof size.
+///
+/// This is the proof size counterpart of `try-runtime on-runtime-upgrade`: the migrations are run
+/// through `TryRuntime_on_runtime_upgrade`, so the runtime must be built with the `try-runtime`
+/// feature. `ext` is typically a `frame-remote-externalities` snapshot, as created by `try-runtime
+/// create-snapshot`, and is not modified. No pre- and post-upgrade checks are run. Multi-block
+/// migrations are not profiled, as each of their steps is part of a different block.
+///
+/// The returned profile has the root frame [`ON_RUNTIME_UPGRADE`], followed by the frames of
+/// [`StorageNames::frames`].
+#[cfg(feature = "try-runtime")]
+pub fn profile_runtime_upgrade<H>(
+	ext: &mut TestExternalities<H>,
+	code: &[u8],
+	names: &StorageNames,
+) -> Result<ProofSizeProfile, String>
+where
+	H: Hasher + 'static,
+	H::Out: Ord + 'static + codec::Codec,
+{
+	profile_calls(ext, code, names, |call| {
+		let checks = frame_try_runtime::UpgradeCheckSelect::None;
+		call(ON_RUNTIME_UPGRADE, "TryRuntime_on_runtime_upgrade", &checks.encode())?;
+		Ok(())
+	})
+}
+
+/// Pass `calls` a function that calls the runtime `code` on top of `ext`.
+///
+/// The proof recorded by each call is profiled below the frame the call is made with. All calls
+/// share the same overlay, so each call sees the changes of the previous ones.
+fn profile_calls<H>(
+	ext: &mut TestExternalities<H>,
+	code: &[u8],
+	names: &StorageNames,
+	calls: impl FnOnce(
+		&mut dyn FnMut(&str, &str, &[u8]) -> Result<Vec<u8>, String>,
+	) -> Result<(), String>,
+) -> Result<ProofSizeProfile, String>
+where
+	H: Hasher + 'static,
+	H::Out: Ord + 'static + codec::Codec,
+{
+	let backend = ext.as_backend();
+	let main_root = *backend.root();
+
+	let recorder = Recorder::<H>::default();
+	recorder.enable_size_attribution();
+	let proving_backend =
+		TrieBackendBuilder::wrap(&backend).with_recorder(recorder.clone()).build();
+
+	let executor = WasmExecutor::<HostFunctions>::builder()
+		.with_allow_missing_host_functions(true)
+		.build();
+	let wrapped_code = WrappedRuntimeCode(Cow::Borrowed(code));
+	let runtime_code = sp_core::traits::RuntimeCode {
+		code_fetcher: &wrapped_code,
+		heap_pages: None,
+		hash: sp_crypto_hashing::blake2_256(code).to_vec(),
+	};
+	let mut overlay = OverlayedChanges::default();
+	let mut extensions = sp_externalities::Extensions::default();
+	extensions.register(ProofSizeExt::new(recorder.clone()));
+
+	let mut profile = ProofSizeProfile::default();
+	let mut recorded = ProofSizeProfile::default();
+	calls(&mut |frame: &str, method: &str, data: &[u8]| -> Result<Vec<u8>, String> {
+		log::debug!(target: LOG_TARGET, "profiling {frame}");
+
+		let result = StateMachine::new(
+			&proving_backend,
+			&mut overlay,
+			&executor,
+			method,
+			data,
+			&mut extensions,
+			&runtime_code,
+			CallContext::Onchain,
+		)
+		.execute()
+		.map_err(|e| format!("failed to call `{method}`: {e}"))?;
+
+		let sizes = recorder.recorded_sizes().expect("size attribution is enabled above; qed");
+		let now = ProofSizeProfile::from_recorded_sizes(&sizes, &main_root, names);
+		profile.add_below(frame, now.since(&recorded));
+		recorded = now;
+
+		Ok(result)
+	})?;
+
+	Ok(profile)
+}
@@ -0,0 +1,175 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Replay a block of a live chain, or execute the migrations of a runtime on top of a live chain
+//! or a state snapshot, and report which storage items contributed how many bytes to the storage
+//! proof.
+
+use clap::{Args, Parser};
+use frame_pov_profiler::{
+	code_in_state, profile_block, profile_runtime_upgrade, runtime_metadata, ProofSizeProfile,
+	StorageNames,
+};
+use remote_externalities::{Builder, Mode, OfflineConfig, OnlineConfig, SnapshotConfig, Transport};
+use sp_core::H256;
+use sp_runtime::{
+	generic::{self, SignedBlock},
+	traits::{BlakeTwo256, Block as BlockT, Header as HeaderT},
+	OpaqueExtrinsic,
+};
+use std::{path::PathBuf, process::ExitCode};
+use substrate_rpc_client::{ws_client, ChainApi};
+
+type Header = generic::Header<u32, BlakeTwo256>;
+type Block = generic::Block<Header, OpaqueExtrinsic>;
+
+#[derive(Debug, Parser)]
+enum Command {
+	/// Replay a block on top of the state of its parent.
+	Block(BlockOpt),
+	/// Execute the single-block migrations of a runtime built with the `try-runtime` feature.
+	OnRuntimeUpgrade(OnRuntimeUpgradeOpt),
+}
+
+#[derive(Debug, Args)]
+struct BlockOpt {
+	/// The node to fetch the block and the state of its parent from.
+	#[arg(long)]
+	uri: String,
+
+	/// The hash of the block to profile. Defaults to the latest finalized block.
+	#[arg(long)]
+	block: Option<H256>,
+
+	/// The WASM blob to replay the block with. Defaults to the code in the parent's state.
+	#[arg(long)]
+	runtime: Option<PathBuf>,
+
+	/// Write the profile in the folded stack format to this file.
+	#[arg(long)]
+	output: Option<PathBuf>,
+}
+
+#[derive(Debug, Args)]
+struct OnRuntimeUpgradeOpt {
+	/// The WASM blob of the runtime whose migrations are executed.
+	#[arg(long)]
+	runtime: PathBuf,
+
+	/// The node to scrape the state from.
+	#[arg(long, required_unless_present = "snapshot")]
+	uri: Option<String>,
+
+	/// The state snapshot to use, e.g. one created by `try-runtime create-snapshot`. Written to
+	/// if `--uri` is given as well.
+	#[arg(long)]
+	snapshot: Option<PathBuf>,
+
+	/// The block hash at which to scrape the state. Defaults to the latest finalized block.
+	#[arg(long, requires = "uri")]
+	at: Option<H256>,
+
+	/// Write the profile in the folded stack format to this file.
+	#[arg(long)]
+	output: Option<PathBuf>,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+	sp_tracing::try_init_simple();
+
+	let result = match Command::parse() {
+		Command::Block(opt) => profile_block_of_chain(opt).await,
+		Command::OnRuntimeUpgrade(opt) => profile_migrations(opt).await,
+	};
+	match result {
+		Ok(()) => ExitCode::SUCCESS,
+		Err(e) => {
+			eprintln!("error: {e}");
+			ExitCode::FAILURE
+		},
+	}
+}
+
+async fn profile_block_of_chain(opt: BlockOpt) -> Result<(), String> {
+	let client = ws_client(&opt.uri).await?;
+	let hash = match opt.block {
+		Some(hash) => hash,
+		None => ChainApi::<(), H256, Header, ()>::finalized_head(&client)
+			.await
+			.map_err(|e| format!("failed to fetch the finalized head: {e}"))?,
+	};
+	let block = ChainApi::<(), H256, Header, SignedBlock<Block>>::block(&client, Some(hash))
+		.await
+		.map_err(|e| format!("failed to fetch block {hash:?}: {e}"))?
+		.ok_or_else(|| format!("block {hash:?} not found"))?
+		.block;
+
+	let mut ext = Builder::<Block>::new()
+		.mode(Mode::Online(OnlineConfig {
+			transport: Transport::from(opt.uri),
+			at: Some(*block.header().parent_hash()),
+			..Default::default()
+		}))
+		.build()
+		.await?;
+
+	let code = match opt.runtime {
+		Some(path) => std::fs::read(path).map_err(|e| format!("failed to read runtime: {e}"))?,
+		None => code_in_state(&mut ext).ok_or("no code found in state")?,
+	};
+	let names = StorageNames::from_metadata(&runtime_metadata(&code)?);
+
+	let profile = profile_block(&mut ext, &code, block, &names)?;
+	report(&profile, opt.output)
+}
+
+async fn profile_migrations(opt: OnRuntimeUpgradeOpt) -> Result<(), String> {
+	let code = std::fs::read(&opt.runtime).map_err(|e| format!("failed to read runtime: {e}"))?;
+
+	let mode = match opt.uri {
+		Some(uri) => Mode::Online(OnlineConfig {
+			transport: Transport::from(uri),
+			at: opt.at,
+			state_snapshot: opt.snapshot.map(SnapshotConfig::new),
+			..Default::default()
+		}),
+		None => Mode::Offline(OfflineConfig {
+			state_snapshot: SnapshotConfig::new(opt.snapshot.expect("enforced by clap; qed")),
+		}),
+	};
+	let mut ext = Builder::<Block>::new().mode(mode).build().await?;
+	let names = StorageNames::from_metadata(&runtime_metadata(&code)?);
+
+	let profile = profile_runtime_upgrade(&mut ext, &code, &names)?;
+	report(&profile, opt.output)
+}
+
+/// Print `profile` and write it to `output` in the folded stack format.
+fn report(profile: &ProofSizeProfile, output: Option<PathBuf>) -> Result<(), String> {
+	print!("{profile}");
+
+	if let Some(path) = output {
+		let mut file =
+			std::fs::File::create(&path).map_err(|e| format!("failed to create {path:?}: {e}"))?;
+		profile
+			.write_folded(&mut file)
+			.map_err(|e| format!("failed to write {path:?}: {e}"))?;
+	}
+
+	Ok(())
+}
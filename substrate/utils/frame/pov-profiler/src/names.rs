@@ -0,0 +1,116 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Resolve storage keys to the names of the storage items they belong to.
+
+use frame_metadata::RuntimeMetadataV15;
+use sp_crypto_hashing::twox_128;
+use std::collections::HashMap;
+
+/// The frame of keys that are not in any known pallet.
+pub const UNKNOWN: &str = "unknown";
+/// The frame of well-known keys like `:code`.
+pub const WELL_KNOWN: &str = "well_known_keys";
+/// The frame of keys in child tries.
+pub const CHILD_TRIES: &str = "child_tries";
+/// The frame of proof nodes that were not recorded by accessing a key, e.g. while iterating.
+pub const UNATTRIBUTED: &str = "unattributed";
+
+/// Maps storage keys to the pallet and storage item they belong to.
+#[derive(Debug, Default, Clone)]
+pub struct StorageNames {
+	/// Mapping: `twox128(pallet) ++ twox128(item) -> (pallet, item)`.
+	items: HashMap<[u8; 32], (String, String)>,
+	/// Mapping: `twox128(pallet) -> pallet`.
+	pallets: HashMap<[u8; 16], String>,
+}
+
+impl StorageNames {
+	/// Build the names from `(pallet, item, prefix)` triples, where the prefix is the 32 byte
+	/// hashed prefix of the storage item.
+	///
+	/// Items with a prefix of a different length are ignored.
+	pub fn from_items(items: impl IntoIterator<Item = (String, String, Vec<u8>)>) -> Self {
+		let mut names = Self::default();
+
+		for (pallet, item, prefix) in items {
+			let Ok(prefix) = <[u8; 32]>::try_from(prefix) else { continue };
+			let pallet_prefix = <[u8; 16]>::try_from(&prefix[..16]).expect("32 >= 16; qed");
+
+			names.pallets.entry(pallet_prefix).or_insert_with(|| pallet.clone());
+			names.items.insert(prefix, (pallet, item));
+		}
+
+		names
+	}
+
+	/// Build the names from the storage items in the runtime `metadata`.
+	pub fn from_metadata(metadata: &RuntimeMetadataV15) -> Self {
+		Self::from_items(metadata.pallets.iter().filter_map(|p| p.storage.as_ref()).flat_map(
+			|storage| {
+				storage.entries.iter().map(|entry| {
+					let prefix =
+						[twox_128(storage.prefix.as_bytes()), twox_128(entry.name.as_bytes())]
+							.concat();
+					(storage.prefix.clone(), entry.name.clone(), prefix)
+				})
+			},
+		))
+	}
+
+	/// The frames of a key in the main trie.
+	///
+	/// Returns `[pallet, item]` for keys of a known storage item, `[pallet, "unknown"]` for other
+	/// keys of a known pallet, `["well_known_keys", key]` for keys starting with `:` and
+	/// `["unknown"]` otherwise.
+	pub fn frames(&self, key: &[u8]) -> Vec<String> {
+		if let Some((pallet, item)) =
+			key.get(..32).and_then(|prefix| self.items.get(prefix)).cloned()
+		{
+			return vec![pallet, item]
+		}
+		if let Some(pallet) = key.get(..16).and_then(|prefix| self.pallets.get(prefix)).cloned() {
+			return vec![pallet, UNKNOWN.into()]
+		}
+		if key.starts_with(b":") {
+			return vec![WELL_KNOWN.into(), String::from_utf8_lossy(key).into_owned()]
+		}
+
+		vec![UNKNOWN.into()]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn frames_works() {
+		let prefix = [twox_128(b"System"), twox_128(b"Account")].concat();
+		let names = StorageNames::from_items([("System".into(), "Account".into(), prefix.clone())]);
+
+		let key = [&prefix[..], &[1; 48]].concat();
+		assert_eq!(names.frames(&key), ["System", "Account"]);
+		assert_eq!(names.frames(&prefix), ["System", "Account"]);
+		assert_eq!(
+			names.frames(&[&twox_128(b"System")[..], &twox_128(b"Number")[..]].concat()),
+			["System", UNKNOWN]
+		);
+		assert_eq!(names.frames(b":code"), [WELL_KNOWN, ":code"]);
+		assert_eq!(names.frames(&[2; 32]), [UNKNOWN]);
+	}
+}
@@ -0,0 +1,185 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The proof size profile and its flame graph compatible output.
+
+use crate::names::{StorageNames, CHILD_TRIES, UNATTRIBUTED};
+use sp_trie::recorder::RecordedSizes;
+use std::{collections::BTreeMap, fmt, io};
+
+/// Proof sizes in bytes per stack of frames, e.g. `[extrinsic, pallet, item]`.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct ProofSizeProfile {
+	stacks: BTreeMap<Vec<String>, u64>,
+}
+
+impl ProofSizeProfile {
+	/// Attribute the recorded `sizes` to storage items.
+	///
+	/// Keys of the trie with `main_root` are resolved with `names`, all other tries are child
+	/// tries.
+	pub fn from_recorded_sizes<H: Eq + std::hash::Hash>(
+		sizes: &RecordedSizes<H>,
+		main_root: &H,
+		names: &StorageNames,
+	) -> Self {
+		let mut profile = Self::default();
+
+		for (root, keys) in sizes.per_key.iter() {
+			for (key, size) in keys.iter() {
+				let stack =
+					if root == main_root { names.frames(key) } else { vec![CHILD_TRIES.into()] };
+				profile.add(stack, *size as u64);
+			}
+		}
+		profile.add(vec![UNATTRIBUTED.into()], sizes.unattributed as u64);
+
+		profile
+	}
+
+	/// Add `size` bytes to `stack`.
+	pub fn add(&mut self, stack: Vec<String>, size: u64) {
+		if size != 0 {
+			*self.stacks.entry(stack).or_default() += size;
+		}
+	}
+
+	/// Add all stacks of `other` below the frame `root`.
+	pub fn add_below(&mut self, root: &str, other: Self) {
+		for (stack, size) in other.stacks {
+			self.add(std::iter::once(root.to_string()).chain(stack).collect(), size);
+		}
+	}
+
+	/// The profile of everything that was recorded since `earlier`.
+	///
+	/// Proof sizes can only grow while recording, unless a transaction was rolled back.
+	pub fn since(&self, earlier: &Self) -> Self {
+		let stacks = self
+			.stacks
+			.iter()
+			.map(|(stack, size)| {
+				(
+					stack.clone(),
+					size.saturating_sub(earlier.stacks.get(stack).copied().unwrap_or(0)),
+				)
+			})
+			.filter(|(_, size)| *size != 0)
+			.collect();
+
+		Self { stacks }
+	}
+
+	/// The total proof size in bytes.
+	pub fn total(&self) -> u64 {
+		self.stacks.values().sum()
+	}
+
+	/// Whether nothing was recorded.
+	pub fn is_empty(&self) -> bool {
+		self.stacks.is_empty()
+	}
+
+	/// The proof sizes by stack.
+	pub fn stacks(&self) -> impl Iterator<Item = (&[String], u64)> {
+		self.stacks.iter().map(|(stack, size)| (&stack[..], *size))
+	}
+
+	/// Write the profile in the folded stack format, one `frame;frame;frame bytes` line per stack.
+	///
+	/// Semicolons in frames are replaced by underscores, since they separate the frames.
+	/// The output can be rendered with `inferno-flamegraph` or `flamegraph.pl`.
+	pub fn write_folded(&self, out: &mut impl io::Write) -> io::Result<()> {
+		for (stack, size) in self.stacks.iter() {
+			let frames = stack.iter().map(|f| f.replace(';', "_")).collect::<Vec<_>>();
+			writeln!(out, "{} {}", frames.join(";"), size)?;
+		}
+
+		Ok(())
+	}
+}
+
+impl fmt::Display for ProofSizeProfile {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let total = self.total();
+		writeln!(f, "total proof size: {} bytes", total)?;
+
+		let mut stacks = self.stacks.iter().collect::<Vec<_>>();
+		stacks.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+		for (stack, size) in stacks {
+			let percent = *size as f64 * 100.0 / total as f64;
+			writeln!(f, "{:>10} bytes {:>6.2}%  {}", size, percent, stack.join(" / "))?;
+		}
+
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{collections::HashMap, sync::Arc};
+
+	fn stack(frames: &[&str]) -> Vec<String> {
+		frames.iter().map(|f| f.to_string()).collect()
+	}
+
+	#[test]
+	fn from_recorded_sizes_works() {
+		let prefix =
+			[sp_crypto_hashing::twox_128(b"System"), sp_crypto_hashing::twox_128(b"Account")]
+				.concat();
+		let names = StorageNames::from_items([("System".into(), "Account".into(), prefix.clone())]);
+
+		let main: HashMap<Arc<[u8]>, usize> =
+			[(prefix.into(), 100), (b":code".to_vec().into(), 10)].into();
+		let child: HashMap<Arc<[u8]>, usize> = [(b"foo".to_vec().into(), 5)].into();
+		let sizes = RecordedSizes { per_key: [(1u8, main), (2u8, child)].into(), unattributed: 7 };
+
+		let profile = ProofSizeProfile::from_recorded_sizes(&sizes, &1u8, &names);
+		assert_eq!(profile.total(), 122);
+		assert_eq!(
+			profile.stacks().collect::<Vec<_>>(),
+			[
+				(&stack(&["System", "Account"])[..], 100),
+				(&stack(&[CHILD_TRIES])[..], 5),
+				(&stack(&[UNATTRIBUTED])[..], 7),
+				(&stack(&["well_known_keys", ":code"])[..], 10),
+			]
+		);
+	}
+
+	#[test]
+	fn since_and_folded_output_works() {
+		let mut earlier = ProofSizeProfile::default();
+		earlier.add(stack(&["System", "Account"]), 100);
+
+		let mut later = earlier.clone();
+		later.add(stack(&["System", "Account"]), 20);
+		later.add(stack(&["Balances", "Total Issuance;"]), 30);
+
+		let mut profile = ProofSizeProfile::default();
+		profile.add_below("extrinsic 1", later.since(&earlier));
+
+		let mut out = Vec::new();
+		profile.write_folded(&mut out).unwrap();
+		assert_eq!(
+			String::from_utf8(out).unwrap(),
+			"extrinsic 1;Balances;Total Issuance_ 30\nextrinsic 1;System;Account 20\n"
+		);
+	}
+}
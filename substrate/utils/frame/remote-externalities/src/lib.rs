@@ -20,6 +20,8 @@
 //! An equivalent of `sp_io::TestExternalities` that can load its state from a remote substrate
 //! based chain, or a local state snapshot file.
 
+pub mod overrides;
+
 use codec::{Compact, Decode, Encode};
 use indicatif::{ProgressBar, ProgressStyle};
 use jsonrpsee::{core::params::ArrayParams, http_client::HttpClient};
@@ -29,17 +31,19 @@ use sp_core::{
 	hexdisplay::HexDisplay,
 	storage::{
 		well_known_keys::{is_default_child_storage_key, DEFAULT_CHILD_STORAGE_KEY_PREFIX},
-		ChildInfo, ChildType, PrefixedStorageKey, StorageData, StorageKey,
+		ChildInfo, ChildType, PrefixedStorageKey, Storage, StorageChild, StorageData, StorageKey,
+		StorageMap,
 	},
 };
 use sp_runtime::{
 	traits::{Block as BlockT, HashingFor},
 	StateVersion,
 };
-use sp_state_machine::TestExternalities;
+use sp_state_machine::{Backend, IterArgs, TestExternalities};
 use spinners::{Spinner, Spinners};
 use std::{
 	cmp::{max, min},
+	collections::{BTreeMap, HashMap},
 	fs,
 	ops::{Deref, DerefMut},
 	path::{Path, PathBuf},
@@ -111,6 +115,47 @@ pub struct RemoteExternalities<B: BlockT> {
 	pub header: B::Header,
 }
 
+impl<B: BlockT> RemoteExternalities<B> {
+	/// Collect the entire state of these externalities, including all default child tries, into a
+	/// [`Storage`].
+	///
+	/// The resulting storage can, for example, be used as the genesis state of a chain spec in
+	/// order to fork a live chain into a local development node.
+	pub fn to_storage(&mut self) -> Result<Storage, &'static str> {
+		let backend = self.inner_ext.as_backend();
+		let mut top = BTreeMap::new();
+		let mut children_default = HashMap::new();
+
+		for pair in backend.pairs(IterArgs::default()).map_err(|_| "failed to iterate state")? {
+			let (key, value) = pair.map_err(|_| "failed to read top key")?;
+
+			// Default child storage roots are not part of the genesis storage, the child tries are
+			// collected separately and their roots are recomputed when the state is built.
+			if let Some(child_root_key) = key.strip_prefix(DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+				let child_info = ChildInfo::new_default(child_root_key);
+				let mut args = IterArgs::default();
+				args.child_info = Some(child_info.clone());
+
+				let mut data = StorageMap::new();
+				for child_pair in
+					backend.pairs(args).map_err(|_| "failed to iterate child state")?
+				{
+					let (child_key, child_value) =
+						child_pair.map_err(|_| "failed to read child key")?;
+					data.insert(child_key, child_value);
+				}
+
+				children_default.insert(child_root_key.to_vec(), StorageChild { child_info, data });
+				continue
+			}
+
+			top.insert(key, value);
+		}
+
+		Ok(Storage { top, children_default })
+	}
+}
+
 impl<B: BlockT> Deref for RemoteExternalities<B> {
 	type Target = TestExternalities<HashingFor<B>>;
 	fn deref(&self) -> &Self::Target {
@@ -1265,6 +1310,37 @@ mod tests {
 			.execute_with(|| {});
 	}
 
+	#[tokio::test]
+	async fn can_collect_storage_from_snapshot() {
+		init_logger();
+		let mut ext = Builder::<Block>::new()
+			.mode(Mode::Offline(OfflineConfig {
+				state_snapshot: SnapshotConfig::new("test_data/test.snap"),
+			}))
+			.build()
+			.await
+			.expect("Can't read state snapshot file");
+
+		let storage = ext.to_storage().unwrap();
+		assert!(!storage.top.is_empty());
+
+		// every key in the snapshot is either a top key or the root of a default child trie.
+		ext.execute_with(|| {
+			let mut key = vec![];
+			while let Some(next) = sp_io::storage::next_key(&key) {
+				match next.strip_prefix(DEFAULT_CHILD_STORAGE_KEY_PREFIX) {
+					Some(child_root_key) =>
+						assert!(storage.children_default.contains_key(child_root_key)),
+					None => assert_eq!(
+						storage.top.get(&next),
+						sp_io::storage::get(&next).map(|v| v.to_vec()).as_ref()
+					),
+				}
+				key = next;
+			}
+		});
+	}
+
 	#[tokio::test]
 	async fn can_exclude_from_snapshot() {
 		init_logger();
@@ -0,0 +1,61 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Helpers to override storage items of a snapshotted state, as collected by
+//! [`RemoteExternalities::to_storage`](crate::RemoteExternalities::to_storage).
+//!
+//! The keys are built from the pallet and storage item names, so no runtime types are required.
+
+use sp_core::storage::StorageMap;
+use sp_crypto_hashing::{twox_128, twox_64};
+
+/// The storage key of the `StorageValue` `item` of `pallet`.
+///
+/// This is also the prefix of all entries of a storage map.
+pub fn value_key(pallet: &[u8], item: &[u8]) -> Vec<u8> {
+	[twox_128(pallet), twox_128(item)].concat()
+}
+
+/// The storage key of the entry `key` of a `StorageMap` using the `Twox64Concat` hasher.
+pub fn twox_64_concat_key(pallet: &[u8], item: &[u8], key: &[u8]) -> Vec<u8> {
+	[&value_key(pallet, item)[..], &twox_64(key), key].concat()
+}
+
+/// Remove all keys of `top` starting with `prefix`.
+pub fn clear_prefix(top: &mut StorageMap, prefix: &[u8]) {
+	top.retain(|key, _| !key.starts_with(prefix));
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn clear_prefix_removes_all_map_entries() {
+		let mut top = StorageMap::new();
+		top.insert(twox_64_concat_key(b"Session", b"NextKeys", &[1]), vec![1]);
+		top.insert(twox_64_concat_key(b"Session", b"NextKeys", &[2]), vec![2]);
+		top.insert(value_key(b"Session", b"Validators"), vec![3]);
+
+		clear_prefix(&mut top, &value_key(b"Session", b"NextKeys"));
+
+		assert_eq!(
+			top.into_iter().collect::<Vec<_>>(),
+			vec![(value_key(b"Session", b"Validators"), vec![3])]
+		);
+	}
+}
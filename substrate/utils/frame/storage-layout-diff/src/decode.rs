@@ -0,0 +1,216 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decoding raw SCALE bytes against a type of a [`PortableRegistry`].
+//!
+//! The values are not materialised; the decoder only walks the type definition and consumes the
+//! bytes it describes, which is all that is needed to tell whether a storage value (or key) is
+//! valid under a given type.
+
+use codec::{Compact, Decode, Error};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+
+/// The maximum nesting depth accepted while decoding, protecting against malicious or accidentally
+/// infinitely recursive data.
+const MAX_DEPTH: u32 = 256;
+
+/// Decode `input` entirely as an instance of the type `ty` of `registry`.
+///
+/// Fails if the bytes do not describe a valid instance of the type or if any bytes remain after
+/// decoding it.
+pub fn decode_all(registry: &PortableRegistry, ty: u32, mut input: &[u8]) -> Result<(), Error> {
+	skip(registry, ty, &mut input, 0)?;
+
+	if input.is_empty() {
+		Ok(())
+	} else {
+		Err(Error::from("input has trailing bytes"))
+	}
+}
+
+/// Consume exactly one instance of the type `ty` of `registry` from the front of `input`.
+pub fn skip(
+	registry: &PortableRegistry,
+	ty: u32,
+	input: &mut &[u8],
+	depth: u32,
+) -> Result<(), Error> {
+	if depth > MAX_DEPTH {
+		return Err(Error::from("maximum decoding depth exceeded"))
+	}
+
+	let ty = registry.resolve(ty).ok_or(Error::from("type not found in registry"))?;
+
+	match &ty.type_def {
+		TypeDef::Composite(composite) => skip_fields(registry, &composite.fields, input, depth),
+		TypeDef::Variant(variant) => {
+			let index = u8::decode(input)?;
+			let variant = variant
+				.variants
+				.iter()
+				.find(|v| v.index == index)
+				.ok_or(Error::from("unknown variant index"))?;
+
+			skip_fields(registry, &variant.fields, input, depth)
+		},
+		TypeDef::Sequence(sequence) => {
+			let len = Compact::<u32>::decode(input)?.0;
+			skip_many(registry, sequence.type_param.id, len, input, depth)
+		},
+		TypeDef::Array(array) => skip_many(registry, array.type_param.id, array.len, input, depth),
+		TypeDef::Tuple(tuple) =>
+			tuple.fields.iter().try_for_each(|f| skip(registry, f.id, input, depth + 1)),
+		TypeDef::Primitive(primitive) => skip_primitive(primitive, input),
+		TypeDef::Compact(_) => Compact::<u128>::decode(input).map(|_| ()),
+		TypeDef::BitSequence(bits) => {
+			let store = registry
+				.resolve(bits.bit_store_type.id)
+				.ok_or(Error::from("bit store type not found in registry"))?;
+			let store_bits = match &store.type_def {
+				TypeDef::Primitive(TypeDefPrimitive::U8) => 8,
+				TypeDef::Primitive(TypeDefPrimitive::U16) => 16,
+				TypeDef::Primitive(TypeDefPrimitive::U32) => 32,
+				TypeDef::Primitive(TypeDefPrimitive::U64) => 64,
+				_ => return Err(Error::from("unsupported bit store type")),
+			};
+			let len = Compact::<u32>::decode(input)?.0 as usize;
+			advance(input, len.div_ceil(store_bits) * (store_bits / 8))
+		},
+	}
+}
+
+fn skip_fields(
+	registry: &PortableRegistry,
+	fields: &[Field<PortableForm>],
+	input: &mut &[u8],
+	depth: u32,
+) -> Result<(), Error> {
+	fields.iter().try_for_each(|f| skip(registry, f.ty.id, input, depth + 1))
+}
+
+fn skip_many(
+	registry: &PortableRegistry,
+	ty: u32,
+	len: u32,
+	input: &mut &[u8],
+	depth: u32,
+) -> Result<(), Error> {
+	// Byte sequences and arrays are by far the most common; skip them in one go.
+	if let Some(TypeDef::Primitive(TypeDefPrimitive::U8)) =
+		registry.resolve(ty).map(|t| &t.type_def)
+	{
+		return advance(input, len as usize)
+	}
+
+	(0..len).try_for_each(|_| skip(registry, ty, input, depth + 1))
+}
+
+fn skip_primitive(primitive: &TypeDefPrimitive, input: &mut &[u8]) -> Result<(), Error> {
+	match primitive {
+		TypeDefPrimitive::Bool => bool::decode(input).map(|_| ()),
+		TypeDefPrimitive::Char => char::decode(input).map(|_| ()),
+		TypeDefPrimitive::Str => String::decode(input).map(|_| ()),
+		TypeDefPrimitive::U8 | TypeDefPrimitive::I8 => advance(input, 1),
+		TypeDefPrimitive::U16 | TypeDefPrimitive::I16 => advance(input, 2),
+		TypeDefPrimitive::U32 | TypeDefPrimitive::I32 => advance(input, 4),
+		TypeDefPrimitive::U64 | TypeDefPrimitive::I64 => advance(input, 8),
+		TypeDefPrimitive::U128 | TypeDefPrimitive::I128 => advance(input, 16),
+		TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => advance(input, 32),
+	}
+}
+
+fn advance(input: &mut &[u8], len: usize) -> Result<(), Error> {
+	if input.len() < len {
+		return Err(Error::from("not enough data to fill buffer"))
+	}
+
+	*input = &input[len..];
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use scale_info::{meta_type, Registry, TypeInfo};
+
+	#[derive(Encode, TypeInfo)]
+	enum Status {
+		Active(u32),
+		#[codec(index = 5)]
+		Retired {
+			at: u64,
+			reason: Vec<u8>,
+		},
+	}
+
+	#[derive(Encode, TypeInfo)]
+	struct Account {
+		nonce: Compact<u32>,
+		balances: (u128, u128),
+		status: Status,
+		tags: Vec<String>,
+		flags: [bool; 2],
+	}
+
+	fn registry_of<T: TypeInfo + 'static>() -> (PortableRegistry, u32) {
+		let mut registry = Registry::new();
+		let id = registry.register_type(&meta_type::<T>()).id;
+		(registry.into(), id)
+	}
+
+	#[test]
+	fn decodes_valid_values() {
+		let (registry, id) = registry_of::<Account>();
+		let account = Account {
+			nonce: Compact(7),
+			balances: (1, 2),
+			status: Status::Retired { at: 10, reason: b"done".to_vec() },
+			tags: vec!["a".into(), "bc".into()],
+			flags: [true, false],
+		};
+
+		assert!(decode_all(&registry, id, &account.encode()).is_ok());
+
+		let account = Account { status: Status::Active(1), ..account };
+		assert!(decode_all(&registry, id, &account.encode()).is_ok());
+	}
+
+	#[test]
+	fn rejects_invalid_values() {
+		let (registry, id) = registry_of::<Account>();
+		let account = Account {
+			nonce: Compact(7),
+			balances: (1, 2),
+			status: Status::Active(1),
+			tags: vec![],
+			flags: [true, true],
+		};
+		let encoded = account.encode();
+
+		// Truncated.
+		assert!(decode_all(&registry, id, &encoded[..encoded.len() - 1]).is_err());
+		// Trailing bytes.
+		assert!(decode_all(&registry, id, &[&encoded[..], &[0]].concat()).is_err());
+		// Unknown variant index.
+		let (status_registry, status_id) = registry_of::<Status>();
+		assert!(decode_all(&status_registry, status_id, &[1, 0, 0, 0, 0]).is_err());
+		// Invalid boolean.
+		let (bool_registry, bool_id) = registry_of::<bool>();
+		assert!(decode_all(&bool_registry, bool_id, &[2]).is_err());
+	}
+}
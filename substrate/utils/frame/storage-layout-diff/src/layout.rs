@@ -0,0 +1,371 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Comparison of the storage layout described by two runtime metadata.
+
+use frame_metadata::{
+	v14::{StorageEntryType, StorageHasher},
+	v15::{RuntimeMetadataV15, StorageEntryMetadata},
+};
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef};
+use std::collections::BTreeMap;
+
+/// The aspects of a storage item's layout that may change between two runtimes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct LayoutChange {
+	/// The item changed from a value to a map or vice versa.
+	pub kind: bool,
+	/// The hashers of the map keys changed.
+	pub hashers: bool,
+	/// The encoding of the map key changed.
+	pub key: bool,
+	/// The encoding of the value changed.
+	pub value: bool,
+}
+
+impl LayoutChange {
+	/// Whether anything changed at all.
+	pub fn any(&self) -> bool {
+		self.kind || self.hashers || self.key || self.value
+	}
+}
+
+impl std::fmt::Display for LayoutChange {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		let changes = [
+			(self.kind, "storage kind"),
+			(self.hashers, "key hashers"),
+			(self.key, "key type"),
+			(self.value, "value type"),
+		];
+		let changes = changes.iter().filter(|(c, _)| *c).map(|(_, n)| *n).collect::<Vec<_>>();
+		write!(f, "{}", changes.join(", "))
+	}
+}
+
+/// How a single storage item differs between the old and the new runtime.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ItemDiff {
+	/// The item only exists in the new runtime.
+	Added,
+	/// The item only exists in the old runtime.
+	Removed,
+	/// The item exists in both runtimes, but its layout changed.
+	Changed(LayoutChange),
+}
+
+/// A storage item identified by its pallet prefix and name.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct StorageItem {
+	/// The storage prefix of the pallet.
+	pub pallet: String,
+	/// The name of the storage item.
+	pub name: String,
+}
+
+impl StorageItem {
+	/// The raw storage prefix under which all keys of this item live.
+	pub fn prefix(&self) -> Vec<u8> {
+		[
+			sp_crypto_hashing::twox_128(self.pallet.as_bytes()),
+			sp_crypto_hashing::twox_128(self.name.as_bytes()),
+		]
+		.concat()
+	}
+}
+
+impl std::fmt::Display for StorageItem {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		write!(f, "{}::{}", self.pallet, self.name)
+	}
+}
+
+/// Compare the storage layout of `old` and `new`, returning all items that were added, removed or
+/// changed.
+///
+/// Types are compared by their encoding, not their names: renaming a type or a field, or wrapping
+/// a type in a single-field struct (e.g. `Vec<T>` into `BoundedVec<T, S>`), is not a change.
+pub fn diff(old: &RuntimeMetadataV15, new: &RuntimeMetadataV15) -> BTreeMap<StorageItem, ItemDiff> {
+	let old_items = storage_items(old);
+	let new_items = storage_items(new);
+	let mut types = TypeComparator::new(&old.types, &new.types);
+	let mut diff = BTreeMap::new();
+
+	for (item, old_entry) in &old_items {
+		let Some(new_entry) = new_items.get(item) else {
+			diff.insert(item.clone(), ItemDiff::Removed);
+			continue
+		};
+
+		let change = match (&old_entry.ty, &new_entry.ty) {
+			(StorageEntryType::Plain(old), StorageEntryType::Plain(new)) =>
+				LayoutChange { value: !types.same_encoding(old.id, new.id), ..Default::default() },
+			(
+				StorageEntryType::Map { hashers: old_hashers, key: old_key, value: old_value },
+				StorageEntryType::Map { hashers: new_hashers, key: new_key, value: new_value },
+			) => LayoutChange {
+				hashers: old_hashers != new_hashers,
+				key: !types.same_encoding(old_key.id, new_key.id),
+				value: !types.same_encoding(old_value.id, new_value.id),
+				..Default::default()
+			},
+			_ => LayoutChange { kind: true, ..Default::default() },
+		};
+
+		if change.any() {
+			diff.insert(item.clone(), ItemDiff::Changed(change));
+		}
+	}
+
+	for item in new_items.keys().filter(|item| !old_items.contains_key(*item)) {
+		diff.insert(item.clone(), ItemDiff::Added);
+	}
+
+	diff
+}
+
+/// All storage entries of `metadata`, keyed by their item.
+pub(crate) fn storage_items(
+	metadata: &RuntimeMetadataV15,
+) -> BTreeMap<StorageItem, &StorageEntryMetadata<PortableForm>> {
+	metadata
+		.pallets
+		.iter()
+		.filter_map(|p| p.storage.as_ref())
+		.flat_map(|storage| {
+			storage.entries.iter().map(|entry| {
+				(StorageItem { pallet: storage.prefix.clone(), name: entry.name.clone() }, entry)
+			})
+		})
+		.collect()
+}
+
+/// The number of bytes a hasher puts in front of the encoded key, and whether the encoded key
+/// follows.
+pub(crate) fn hasher_layout(hasher: &StorageHasher) -> (usize, bool) {
+	match hasher {
+		StorageHasher::Blake2_128 | StorageHasher::Twox128 => (16, false),
+		StorageHasher::Blake2_256 | StorageHasher::Twox256 => (32, false),
+		StorageHasher::Blake2_128Concat => (16, true),
+		StorageHasher::Twox64Concat => (8, true),
+		StorageHasher::Identity => (0, true),
+	}
+}
+
+/// Structural comparison of types living in two different registries.
+///
+/// Two types are considered the same if they share the same SCALE encoding; see [`diff`] for
+/// the details. Results are cached, so a single comparator should be reused for all comparisons
+/// between the same two registries.
+pub struct TypeComparator<'a> {
+	old: &'a PortableRegistry,
+	new: &'a PortableRegistry,
+	/// Results of finished comparisons.
+	cache: BTreeMap<(u32, u32), bool>,
+	/// Comparisons currently in progress. Recursive types are assumed equal when they come back
+	/// to a pair that is already being compared.
+	in_progress: Vec<(u32, u32)>,
+}
+
+impl<'a> TypeComparator<'a> {
+	/// Create a comparator between the types of the `old` and the `new` registry.
+	pub fn new(old: &'a PortableRegistry, new: &'a PortableRegistry) -> Self {
+		Self { old, new, cache: Default::default(), in_progress: Default::default() }
+	}
+
+	/// Whether the type `old` of the old registry and `new` of the new registry share the same
+	/// encoding.
+	pub fn same_encoding(&mut self, old: u32, new: u32) -> bool {
+		let (old, new) = (unwrap(self.old, old), unwrap(self.new, new));
+
+		if let Some(eq) = self.cache.get(&(old, new)) {
+			return *eq
+		}
+		if self.in_progress.contains(&(old, new)) {
+			return true
+		}
+
+		self.in_progress.push((old, new));
+		let eq = self.eq_uncached(old, new);
+		self.in_progress.pop();
+		self.cache.insert((old, new), eq);
+
+		eq
+	}
+
+	fn eq_uncached(&mut self, old: u32, new: u32) -> bool {
+		let (old_registry, new_registry) = (self.old, self.new);
+		let (Some(old), Some(new)) = (old_registry.resolve(old), new_registry.resolve(new)) else {
+			return false
+		};
+
+		match (&old.type_def, &new.type_def) {
+			(TypeDef::Composite(old), TypeDef::Composite(new)) =>
+				self.fields_eq(&old.fields, &new.fields),
+			(TypeDef::Composite(old), TypeDef::Tuple(new)) =>
+				old.fields.len() == new.fields.len() &&
+					old.fields
+						.iter()
+						.zip(&new.fields)
+						.all(|(o, n)| self.same_encoding(o.ty.id, n.id)),
+			(TypeDef::Tuple(old), TypeDef::Composite(new)) =>
+				old.fields.len() == new.fields.len() &&
+					old.fields
+						.iter()
+						.zip(&new.fields)
+						.all(|(o, n)| self.same_encoding(o.id, n.ty.id)),
+			(TypeDef::Variant(old), TypeDef::Variant(new)) =>
+				old.variants.len() == new.variants.len() &&
+					old.variants.iter().all(|o| {
+						new.variants
+							.iter()
+							.find(|n| n.index == o.index)
+							.is_some_and(|n| self.fields_eq(&o.fields, &n.fields))
+					}),
+			(TypeDef::Sequence(old), TypeDef::Sequence(new)) =>
+				self.same_encoding(old.type_param.id, new.type_param.id),
+			(TypeDef::Array(old), TypeDef::Array(new)) =>
+				old.len == new.len && self.same_encoding(old.type_param.id, new.type_param.id),
+			(TypeDef::Tuple(old), TypeDef::Tuple(new)) =>
+				old.fields.len() == new.fields.len() &&
+					old.fields
+						.iter()
+						.zip(&new.fields)
+						.all(|(o, n)| self.same_encoding(o.id, n.id)),
+			(TypeDef::Primitive(old), TypeDef::Primitive(new)) => old == new,
+			(TypeDef::Compact(old), TypeDef::Compact(new)) =>
+				self.same_encoding(old.type_param.id, new.type_param.id),
+			(TypeDef::BitSequence(old), TypeDef::BitSequence(new)) =>
+				self.same_encoding(old.bit_store_type.id, new.bit_store_type.id) &&
+					self.same_encoding(old.bit_order_type.id, new.bit_order_type.id),
+			_ => false,
+		}
+	}
+
+	fn fields_eq(&mut self, old: &[Field<PortableForm>], new: &[Field<PortableForm>]) -> bool {
+		old.len() == new.len() &&
+			old.iter().zip(new).all(|(o, n)| self.same_encoding(o.ty.id, n.ty.id))
+	}
+}
+
+/// Strip single-field structs and tuples, which encode exactly like the type they wrap.
+fn unwrap(registry: &PortableRegistry, mut ty: u32) -> u32 {
+	// Bounded, so that a malformed self-referencing registry cannot loop forever.
+	for _ in 0..64 {
+		let inner = match registry.resolve(ty).map(|t| &t.type_def) {
+			Some(TypeDef::Composite(c)) if c.fields.len() == 1 => c.fields[0].ty.id,
+			Some(TypeDef::Tuple(t)) if t.fields.len() == 1 => t.fields[0].id,
+			_ => return ty,
+		};
+		ty = inner;
+	}
+	ty
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use scale_info::{meta_type, Registry, TypeInfo};
+
+	fn registry_of<T: TypeInfo + 'static>() -> (PortableRegistry, u32) {
+		let mut registry = Registry::new();
+		let id = registry.register_type(&meta_type::<T>()).id;
+		(registry.into(), id)
+	}
+
+	fn same_layout<A: TypeInfo + 'static, B: TypeInfo + 'static>() -> bool {
+		let (old, old_id) = registry_of::<A>();
+		let (new, new_id) = registry_of::<B>();
+		TypeComparator::new(&old, &new).same_encoding(old_id, new_id)
+	}
+
+	mod old {
+		#[derive(scale_info::TypeInfo)]
+		pub struct Ledger {
+			pub stash: [u8; 32],
+			pub total: u128,
+			pub unlocking: Vec<(u128, u32)>,
+		}
+
+		#[derive(scale_info::TypeInfo)]
+		pub enum Vote {
+			Aye,
+			Nay(u32),
+		}
+
+		#[derive(scale_info::TypeInfo)]
+		pub struct Node {
+			pub children: Vec<Node>,
+		}
+	}
+
+	mod new {
+		#[derive(scale_info::TypeInfo)]
+		pub struct Chunk {
+			pub value: u128,
+			pub era: u32,
+		}
+
+		#[derive(scale_info::TypeInfo)]
+		pub struct Unlocking(pub Vec<Chunk>);
+
+		#[derive(scale_info::TypeInfo)]
+		pub struct StakingLedger {
+			pub account: [u8; 32],
+			pub amount: u128,
+			pub chunks: Unlocking,
+		}
+
+		#[derive(scale_info::TypeInfo)]
+		pub struct NarrowLedger {
+			pub stash: [u8; 32],
+			pub total: u64,
+			pub unlocking: Vec<(u128, u32)>,
+		}
+
+		#[derive(scale_info::TypeInfo)]
+		pub enum Vote {
+			Aye,
+			Nay(u32),
+			Abstain,
+		}
+
+		#[derive(scale_info::TypeInfo)]
+		pub struct Node {
+			pub children: Vec<Node>,
+		}
+	}
+
+	#[test]
+	fn renames_and_wrappers_are_not_changes() {
+		assert!(same_layout::<old::Ledger, new::StakingLedger>());
+		assert!(same_layout::<old::Node, new::Node>());
+		assert!(same_layout::<u32, (u32,)>());
+		assert!(same_layout::<(u128, u32), new::Chunk>());
+		assert!(same_layout::<new::Chunk, (u128, u32)>());
+	}
+
+	#[test]
+	fn encoding_changes_are_detected() {
+		assert!(!same_layout::<old::Ledger, new::NarrowLedger>());
+		assert!(!same_layout::<old::Vote, new::Vote>());
+		assert!(!same_layout::<Vec<u8>, [u8; 32]>());
+		assert!(!same_layout::<u32, codec::Compact<u32>>());
+		assert!(!same_layout::<(u32, u128), new::Chunk>());
+		assert!(!same_layout::<new::Chunk, (u128, u32, u8)>());
+	}
+}
@@ -0,0 +1,443 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Storage layout diff
+//!
+//! Detects storage items whose layout changes in a runtime upgrade without their existing data
+//! being migrated.
+//!
+//! The storage layout of the old and the new runtime is read from the metadata of their WASM
+//! blobs, and every storage item whose key hashers, key type or value type changed is listed
+//! (see [`diff`]). Types are compared by their encoding rather than their names, so renaming a
+//! type or wrapping it in a newtype is not reported.
+//!
+//! Listing the changes alone cannot tell whether the upgrade ships a migration for them. Hence,
+//! every key that exists under a changed item in a given state is then decoded with the new
+//! runtime's types (see [`check_state`]), and all entries that fail to decode are reported. The
+//! state is usually a `frame-remote-externalities` snapshot or live chain state, on top of which
+//! the new runtime's single- and multi-block migrations were executed through its `TryRuntime` API
+//! (see `execute_migrations`, which requires the `try-runtime` feature). [`check_upgrade`] bundles
+//! all of the above.
+//!
+//! The crate is both a library, to be used in try-runtime tests of a runtime, and a binary meant
+//! to be run in CI, which exits with a non-zero code if any undecodable entries are found:
+//!
+//! ```sh
+//! frame-storage-layout-diff --new target/release/wbuild/my-runtime/my_runtime.wasm \
+//!   --uri wss://my-chain-rpc.io:443 --execute-migrations --max-migration-blocks 100
+//! ```
+
+mod decode;
+mod layout;
+
+pub use decode::decode_all;
+pub use layout::{diff, ItemDiff, LayoutChange, StorageItem, TypeComparator};
+
+use codec::{Decode, Encode};
+use frame_metadata::{
+	v14::StorageEntryType, v15::RuntimeMetadataV15, RuntimeMetadata, RuntimeMetadataPrefixed,
+};
+use sc_executor::WasmExecutor;
+use sp_core::{
+	storage::well_known_keys,
+	traits::{CallContext, CodeExecutor, FetchRuntimeCode, RuntimeCode},
+	Hasher, OpaqueMetadata,
+};
+use sp_state_machine::{BasicExternalities, TestExternalities};
+use std::{borrow::Cow, collections::BTreeMap};
+
+const LOG_TARGET: &str = "storage-layout-diff";
+
+/// The metadata version from which the storage layout is read.
+const METADATA_VERSION: u32 = 15;
+
+/// The first version of the `TryRuntime` API that executes multi-block migrations.
+#[cfg(feature = "try-runtime")]
+const TRY_RUNTIME_MBM_VERSION: u32 = 2;
+
+/// The result of checking all entries of a storage item against its new layout.
+#[derive(Debug, Clone, Default)]
+pub struct ItemCheck {
+	/// The number of entries that were checked.
+	pub checked: u64,
+	/// The number of entries that failed to decode.
+	pub failed: u64,
+	/// The raw keys of some of the failed entries along with the reason they failed.
+	pub samples: Vec<(Vec<u8>, String)>,
+}
+
+/// The outcome of checking a runtime upgrade.
+#[derive(Debug, Clone, Default)]
+pub struct Report {
+	/// All storage items whose layout differs between the old and the new runtime.
+	pub diff: BTreeMap<StorageItem, ItemDiff>,
+	/// The entries of the changed items that were checked against the new layout.
+	pub checks: BTreeMap<StorageItem, ItemCheck>,
+	/// Items removed by the new runtime which still have entries in the state, along with the
+	/// number of those entries.
+	pub stale: BTreeMap<StorageItem, u64>,
+}
+
+impl Report {
+	/// Whether any entry in the state failed to decode with the new layout.
+	pub fn has_undecodable_entries(&self) -> bool {
+		self.checks.values().any(|check| check.failed > 0)
+	}
+}
+
+impl std::fmt::Display for Report {
+	fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+		for (item, diff) in &self.diff {
+			match diff {
+				ItemDiff::Added => writeln!(f, "+ {item}")?,
+				ItemDiff::Removed => match self.stale.get(item) {
+					Some(count) => writeln!(f, "- {item} ({count} stale entries left in state)")?,
+					None => writeln!(f, "- {item}")?,
+				},
+				ItemDiff::Changed(change) => {
+					write!(f, "~ {item}: {change} changed")?;
+					match self.checks.get(item) {
+						Some(check) => writeln!(
+							f,
+							" ({} of {} entries undecodable)",
+							check.failed, check.checked
+						)?,
+						None => writeln!(f)?,
+					}
+					for (key, reason) in
+						self.checks.get(item).map(|c| &c.samples[..]).unwrap_or(&[])
+					{
+						writeln!(f, "    0x{}: {reason}", hex::encode(key))?;
+					}
+				},
+			}
+		}
+
+		Ok(())
+	}
+}
+
+/// Read the metadata of the runtime `code`, which must support metadata V15.
+pub fn runtime_metadata(code: &[u8]) -> Result<RuntimeMetadataV15, String> {
+	let executor = executor();
+	let encoded = call(
+		&executor,
+		code,
+		&mut BasicExternalities::default(),
+		"Metadata_metadata_at_version",
+		&METADATA_VERSION.encode(),
+	)?;
+
+	let opaque = Option::<OpaqueMetadata>::decode(&mut &encoded[..])
+		.map_err(|e| format!("failed to decode metadata: {e}"))?
+		.ok_or_else(|| format!("runtime does not support metadata V{METADATA_VERSION}"))?;
+
+	match RuntimeMetadataPrefixed::decode(&mut &opaque[..])
+		.map_err(|e| format!("failed to decode metadata: {e}"))?
+		.1
+	{
+		RuntimeMetadata::V15(metadata) => Ok(metadata),
+		_ => Err(format!("runtime returned metadata other than V{METADATA_VERSION}")),
+	}
+}
+
+/// Execute the migrations of the runtime `code` on top of `ext`, committing the resulting
+/// changes.
+///
+/// The single-block migrations run first. Multi-block migrations are then stepped through in
+/// empty blocks until they complete, which must happen within `max_blocks` blocks. These blocks
+/// carry no inherents, thus the `on_finalize` hooks are not run in them. Runtimes whose
+/// `TryRuntime` API predates multi-block migrations only execute their single-block migrations.
+///
+/// The runtime must be built with the `try-runtime` feature. No pre- and post-upgrade checks are
+/// run; use `try-runtime-cli` for those.
+#[cfg(feature = "try-runtime")]
+pub fn execute_migrations<H>(
+	ext: &mut TestExternalities<H>,
+	code: &[u8],
+	max_blocks: u32,
+) -> Result<(), String>
+where
+	H: Hasher + 'static,
+	H::Out: Ord + 'static + codec::Codec,
+{
+	let executor = executor();
+	let checks = frame_try_runtime::UpgradeCheckSelect::None;
+
+	if try_runtime_version(&executor, code)? >= TRY_RUNTIME_MBM_VERSION {
+		log::info!(target: LOG_TARGET, "executing single- and multi-block migrations of the new runtime");
+
+		let encoded = call(
+			&executor,
+			code,
+			&mut ext.ext(),
+			"TryRuntime_on_runtime_upgrade_and_migrations",
+			&(checks, max_blocks).encode(),
+		)?;
+		let (_, blocks) = <(sp_weights::Weight, u32)>::decode(&mut &encoded[..])
+			.map_err(|e| format!("failed to decode migration result: {e}"))?;
+		log::info!(target: LOG_TARGET, "multi-block migrations completed after {blocks} blocks");
+	} else {
+		log::warn!(
+			target: LOG_TARGET,
+			"the new runtime does not support executing multi-block migrations, only executing its \
+			single-block migrations",
+		);
+
+		call(&executor, code, &mut ext.ext(), "TryRuntime_on_runtime_upgrade", &checks.encode())?;
+	}
+
+	ext.commit_all()
+}
+
+/// The version of the `TryRuntime` API implemented by the runtime `code`.
+#[cfg(feature = "try-runtime")]
+fn try_runtime_version(
+	executor: &WasmExecutor<sp_io::SubstrateHostFunctions>,
+	code: &[u8],
+) -> Result<u32, String> {
+	let encoded = call(executor, code, &mut BasicExternalities::default(), "Core_version", &[])?;
+	let version = sp_version::RuntimeVersion::decode(&mut &encoded[..])
+		.map_err(|e| format!("failed to decode runtime version: {e}"))?;
+
+	version
+		.api_version(&sp_crypto_hashing::blake2_64(b"TryRuntime"))
+		.ok_or_else(|| "runtime does not implement the `TryRuntime` API".into())
+}
+
+/// Decode every entry of the given storage `items` in `ext` with the layout of `metadata`.
+///
+/// At most `max_samples` failing keys are recorded per item. Items that are not part of
+/// `metadata` are ignored.
+pub fn check_state<'a, H>(
+	ext: &mut TestExternalities<H>,
+	metadata: &RuntimeMetadataV15,
+	items: impl IntoIterator<Item = &'a StorageItem>,
+	max_samples: usize,
+) -> BTreeMap<StorageItem, ItemCheck>
+where
+	H: Hasher + 'static,
+	H::Out: Ord + 'static + codec::Codec,
+{
+	let entries = layout::storage_items(metadata);
+
+	ext.execute_with(|| {
+		items
+			.into_iter()
+			.filter_map(|item| {
+				let entry = entries.get(item)?;
+				log::debug!(target: LOG_TARGET, "checking entries of {item}");
+
+				let mut check = ItemCheck::default();
+				for_each_entry(item, &entry.ty, |key, value| {
+					check.checked += 1;
+					if let Err(reason) = check_entry(metadata, &entry.ty, key, value) {
+						check.failed += 1;
+						if check.samples.len() < max_samples {
+							check.samples.push((key.to_vec(), reason));
+						}
+					}
+				});
+
+				Some((item.clone(), check))
+			})
+			.collect()
+	})
+}
+
+/// Count the entries left in `ext` under each of the given storage `items`.
+///
+/// Only items with at least one entry are returned.
+pub fn count_entries<'a, H>(
+	ext: &mut TestExternalities<H>,
+	items: impl IntoIterator<Item = &'a StorageItem>,
+) -> BTreeMap<StorageItem, u64>
+where
+	H: Hasher + 'static,
+	H::Out: Ord + 'static + codec::Codec,
+{
+	ext.execute_with(|| {
+		items
+			.into_iter()
+			.filter_map(|item| {
+				let prefix = item.prefix();
+				let mut count = sp_io::storage::exists(&prefix) as u64;
+				let mut next = sp_io::storage::next_key(&prefix);
+				while let Some(key) = next.filter(|k| k.starts_with(&prefix)) {
+					count += 1;
+					next = sp_io::storage::next_key(&key);
+				}
+
+				(count > 0).then(|| (item.clone(), count))
+			})
+			.collect()
+	})
+}
+
+/// Check the upgrade from the runtime `old_code` to `new_code` against the state in `ext`.
+///
+/// If `migrate` is set, the migrations of the new runtime are executed first, and its multi-block
+/// migrations must complete within the given number of blocks, see `execute_migrations`. This
+/// requires the `try-runtime` feature.
+pub fn check_upgrade<H>(
+	ext: &mut TestExternalities<H>,
+	old_code: &[u8],
+	new_code: &[u8],
+	migrate: Option<u32>,
+	max_samples: usize,
+) -> Result<Report, String>
+where
+	H: Hasher + 'static,
+	H::Out: Ord + 'static + codec::Codec,
+{
+	let old = runtime_metadata(old_code)?;
+	let new = runtime_metadata(new_code)?;
+	let diff = diff(&old, &new);
+
+	#[cfg(feature = "try-runtime")]
+	if let Some(max_blocks) = migrate {
+		execute_migrations(ext, new_code, max_blocks)?;
+	}
+	#[cfg(not(feature = "try-runtime"))]
+	if migrate.is_some() {
+		return Err("executing migrations requires the `try-runtime` feature".into())
+	}
+
+	let changed = diff.iter().filter(|(_, d)| matches!(d, ItemDiff::Changed(_))).map(|(i, _)| i);
+	let checks = check_state(ext, &new, changed, max_samples);
+	let removed = diff.iter().filter(|(_, d)| **d == ItemDiff::Removed).map(|(i, _)| i);
+	let stale = count_entries(ext, removed);
+
+	Ok(Report { diff, checks, stale })
+}
+
+/// The runtime code currently stored in `ext`.
+pub fn code_in_state<H>(ext: &mut TestExternalities<H>) -> Option<Vec<u8>>
+where
+	H: Hasher + 'static,
+	H::Out: Ord + 'static + codec::Codec,
+{
+	ext.execute_with(|| sp_io::storage::get(well_known_keys::CODE).map(|code| code.to_vec()))
+}
+
+/// Call `f` with every key and value stored under `item` in the current externalities.
+fn for_each_entry(
+	item: &StorageItem,
+	ty: &StorageEntryType<scale_info::form::PortableForm>,
+	mut f: impl FnMut(&[u8], &[u8]),
+) {
+	let prefix = item.prefix();
+
+	match ty {
+		StorageEntryType::Plain(_) =>
+			if let Some(value) = sp_io::storage::get(&prefix) {
+				f(&prefix, &value)
+			},
+		StorageEntryType::Map { .. } => {
+			// A value stored right at the prefix is a leftover of a former plain item.
+			if let Some(value) = sp_io::storage::get(&prefix) {
+				f(&prefix, &value)
+			}
+
+			let mut next = sp_io::storage::next_key(&prefix);
+			while let Some(key) = next.filter(|k| k.starts_with(&prefix)) {
+				if let Some(value) = sp_io::storage::get(&key) {
+					f(&key, &value)
+				}
+				next = sp_io::storage::next_key(&key);
+			}
+		},
+	}
+}
+
+/// Check that `key` and `value` are a valid entry of a storage item of type `ty`.
+fn check_entry(
+	metadata: &RuntimeMetadataV15,
+	ty: &StorageEntryType<scale_info::form::PortableForm>,
+	key: &[u8],
+	value: &[u8],
+) -> Result<(), String> {
+	let registry = &metadata.types;
+
+	match ty {
+		StorageEntryType::Plain(value_ty) =>
+			decode_all(registry, value_ty.id, value).map_err(|e| format!("undecodable value: {e}")),
+		StorageEntryType::Map { hashers, key: key_ty, value: value_ty } => {
+			let key_types = if hashers.len() == 1 {
+				vec![key_ty.id]
+			} else {
+				match registry.resolve(key_ty.id).map(|t| &t.type_def) {
+					Some(scale_info::TypeDef::Tuple(tuple))
+						if tuple.fields.len() == hashers.len() =>
+						tuple.fields.iter().map(|f| f.id).collect(),
+					_ => return Err("key type does not match the hashers".into()),
+				}
+			};
+
+			let mut key = &key[32..];
+			for (hasher, key_ty) in hashers.iter().zip(key_types) {
+				let (hash_len, concat) = layout::hasher_layout(hasher);
+				if key.len() < hash_len {
+					return Err("key too short for its hashers".into())
+				}
+				key = &key[hash_len..];
+				if concat {
+					decode::skip(registry, key_ty, &mut key, 0)
+						.map_err(|e| format!("undecodable key: {e}"))?;
+				}
+			}
+			if !key.is_empty() {
+				return Err("key too long for its hashers".into())
+			}
+
+			decode_all(registry, value_ty.id, value).map_err(|e| format!("undecodable value: {e}"))
+		},
+	}
+}
+
+fn executor() -> WasmExecutor<sp_io::SubstrateHostFunctions> {
+	WasmExecutor::builder().with_allow_missing_host_functions(true).build()
+}
+
+/// Call `method` of the runtime `code` with `data` on top of `ext`.
+fn call(
+	executor: &WasmExecutor<sp_io::SubstrateHostFunctions>,
+	code: &[u8],
+	ext: &mut dyn sp_externalities::Externalities,
+	method: &str,
+	data: &[u8],
+) -> Result<Vec<u8>, String> {
+	let fetcher = CodeFetcher(Cow::Borrowed(code));
+	let runtime_code = RuntimeCode {
+		code_fetcher: &fetcher,
+		heap_pages: None,
+		hash: sp_crypto_hashing::blake2_256(code).to_vec(),
+	};
+
+	executor
+		.call(ext, &runtime_code, method, data, CallContext::Offchain)
+		.0
+		.map_err(|e| format!("failed to call `{method}`: {e}"))
+}
+
+struct CodeFetcher<'a>(Cow<'a, [u8]>);
+
+impl FetchRuntimeCode for CodeFetcher<'_> {
+	fn fetch_runtime_code(&self) -> Option<Cow<[u8]>> {
+		Some(self.0.as_ref().into())
+	}
+}
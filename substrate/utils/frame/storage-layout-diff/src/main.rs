@@ -0,0 +1,111 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Report storage items whose layout changes between two runtimes without their data being
+//! migrated. Exits with a non-zero code if any existing entry does not decode with the new layout.
+
+use clap::Parser;
+use frame_storage_layout_diff::{check_upgrade, code_in_state};
+use remote_externalities::{Builder, Mode, OfflineConfig, OnlineConfig, SnapshotConfig, Transport};
+use sp_runtime::{generic, traits::BlakeTwo256, OpaqueExtrinsic};
+use std::{path::PathBuf, process::ExitCode, str::FromStr};
+
+type Block = generic::Block<generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
+#[derive(Debug, Parser)]
+struct Opt {
+	/// The WASM blob of the new runtime.
+	#[arg(long)]
+	new: PathBuf,
+
+	/// The WASM blob of the old runtime. Defaults to the code found in the state.
+	#[arg(long)]
+	old: Option<PathBuf>,
+
+	/// The node to scrape the state from.
+	#[arg(long, required_unless_present = "snapshot")]
+	uri: Option<String>,
+
+	/// The state snapshot to use. Written to if `--uri` is given as well.
+	#[arg(long)]
+	snapshot: Option<PathBuf>,
+
+	/// The block hash at which to scrape the state. Defaults to the latest finalized block.
+	#[arg(long, requires = "uri")]
+	at: Option<String>,
+
+	/// Execute the migrations of the new runtime before checking the state.
+	///
+	/// The new runtime must be built with the `try-runtime` feature.
+	#[arg(long)]
+	execute_migrations: bool,
+
+	/// The maximum number of blocks the multi-block migrations may take to complete.
+	#[arg(long, default_value_t = 100)]
+	max_migration_blocks: u32,
+
+	/// The maximum number of undecodable keys to print per storage item.
+	#[arg(long, default_value_t = 10)]
+	max_samples: usize,
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+	sp_tracing::try_init_simple();
+
+	match run(Opt::parse()).await {
+		Ok(true) => ExitCode::SUCCESS,
+		Ok(false) => ExitCode::FAILURE,
+		Err(e) => {
+			eprintln!("error: {e}");
+			ExitCode::FAILURE
+		},
+	}
+}
+
+/// Returns whether all checked entries decode with the new layout.
+async fn run(opt: Opt) -> Result<bool, String> {
+	let new_code = std::fs::read(&opt.new).map_err(|e| format!("failed to read new code: {e}"))?;
+
+	let mode = match opt.uri {
+		Some(uri) => Mode::Online(OnlineConfig {
+			transport: Transport::from(uri),
+			at: opt
+				.at
+				.map(|at| sp_core::H256::from_str(&at))
+				.transpose()
+				.map_err(|e| format!("invalid block hash: {e}"))?,
+			state_snapshot: opt.snapshot.map(SnapshotConfig::new),
+			..Default::default()
+		}),
+		None => Mode::Offline(OfflineConfig {
+			state_snapshot: SnapshotConfig::new(opt.snapshot.expect("enforced by clap; qed")),
+		}),
+	};
+	let mut ext = Builder::<Block>::new().mode(mode).build().await?;
+
+	let old_code = match opt.old {
+		Some(path) => std::fs::read(path).map_err(|e| format!("failed to read old code: {e}"))?,
+		None => code_in_state(&mut ext).ok_or("no code found in state")?,
+	};
+
+	let migrate = opt.execute_migrations.then_some(opt.max_migration_blocks);
+	let report = check_upgrade(&mut ext, &old_code, &new_code, migrate, opt.max_samples)?;
+
+	print!("{report}");
+	Ok(!report.has_undecodable_entries())
+}
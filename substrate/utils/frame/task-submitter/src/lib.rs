@@ -0,0 +1,218 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Node service submitting the runtime's pending tasks.
+//!
+//! Pallets declare service work with `#[pallet::tasks]`, which anyone may execute through the
+//! unsigned `frame_system::Call::do_task`. This service discovers such work through the
+//! [`TasksApi`] runtime API on new best blocks and submits the matching extrinsics to the local
+//! transaction pool.
+//!
+//! Submission is rate-limited by [`TaskSubmitterConfig`]. Whether a task is actually accepted is
+//! decided by the runtime's `ValidateUnsigned` implementation, which re-checks the task's
+//! condition; the pool also drops tasks that are already queued.
+
+use std::{fmt::Debug, sync::Arc};
+
+use codec::Codec;
+use futures::StreamExt;
+use sc_client_api::BlockchainEvents;
+use sc_transaction_pool_api::{TransactionPool, TransactionSource};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_consensus::BlockOrigin;
+use sp_runtime::traits::Block as BlockT;
+
+pub use frame_system_rpc_runtime_api::TasksApi;
+
+/// Log target of this crate.
+pub const LOG_TARGET: &str = "task-submitter";
+
+/// Rate limits of the task submitter.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TaskSubmitterConfig {
+	/// Maximum number of tasks submitted per round.
+	pub max_tasks_per_round: u32,
+	/// Number of new best blocks between two rounds. A value of `0` is treated as `1`.
+	pub interval: u32,
+}
+
+impl Default for TaskSubmitterConfig {
+	fn default() -> Self {
+		Self { max_tasks_per_round: 16, interval: 1 }
+	}
+}
+
+/// Decides which new best blocks start a submission round.
+#[derive(Debug)]
+struct RoundSchedule {
+	interval: u32,
+	blocks_since_last_round: Option<u32>,
+}
+
+impl RoundSchedule {
+	fn new(interval: u32) -> Self {
+		Self { interval: interval.max(1), blocks_since_last_round: None }
+	}
+
+	/// Notes a new best block and returns whether a round is due on it.
+	///
+	/// The first block seen always starts a round.
+	fn note_new_best(&mut self) -> bool {
+		let due = match self.blocks_since_last_round {
+			None => true,
+			Some(blocks) => blocks + 1 >= self.interval,
+		};
+		self.blocks_since_last_round =
+			if due { Some(0) } else { self.blocks_since_last_round.map(|blocks| blocks + 1) };
+		due
+	}
+}
+
+/// Run the task submitter until the import notification stream ends.
+///
+/// `RuntimeTask` is the runtime's aggregated task type, as used by its [`TasksApi`]
+/// implementation.
+pub async fn run<Block, Client, Pool, RuntimeTask>(
+	config: TaskSubmitterConfig,
+	client: Arc<Client>,
+	pool: Arc<Pool>,
+) where
+	Block: BlockT,
+	Client: BlockchainEvents<Block> + ProvideRuntimeApi<Block> + Send + Sync + 'static,
+	Client::Api: TasksApi<Block, RuntimeTask>,
+	Pool: TransactionPool<Block = Block> + 'static,
+	RuntimeTask: Codec + Debug,
+{
+	let mut schedule = RoundSchedule::new(config.interval);
+	let mut imports = client.import_notification_stream();
+
+	while let Some(notification) = imports.next().await {
+		if !notification.is_new_best || notification.origin == BlockOrigin::NetworkInitialSync {
+			continue
+		}
+		if !schedule.note_new_best() {
+			continue
+		}
+
+		let extrinsics = pending_task_extrinsics::<Block, Client, RuntimeTask>(
+			&client,
+			notification.hash,
+			config.max_tasks_per_round,
+		);
+		submit(&*pool, notification.hash, extrinsics).await;
+	}
+}
+
+/// Build the `do_task` extrinsics of the tasks pending at `at`.
+fn pending_task_extrinsics<Block, Client, RuntimeTask>(
+	client: &Client,
+	at: Block::Hash,
+	max: u32,
+) -> Vec<Block::Extrinsic>
+where
+	Block: BlockT,
+	Client: ProvideRuntimeApi<Block>,
+	Client::Api: TasksApi<Block, RuntimeTask>,
+	RuntimeTask: Codec + Debug,
+{
+	let api = client.runtime_api();
+	match api.has_api::<dyn TasksApi<Block, RuntimeTask>>(at) {
+		Ok(true) => {},
+		Ok(false) => {
+			log::trace!(target: LOG_TARGET, "Runtime at {at:?} does not implement `TasksApi`");
+			return Vec::new()
+		},
+		Err(err) => {
+			log::debug!(target: LOG_TARGET, "Failed to check for `TasksApi` at {at:?}: {err}");
+			return Vec::new()
+		},
+	}
+
+	let tasks = match api.pending_tasks(at, max) {
+		Ok(tasks) => tasks,
+		Err(err) => {
+			log::warn!(target: LOG_TARGET, "Failed to fetch pending tasks at {at:?}: {err}");
+			return Vec::new()
+		},
+	};
+
+	tasks
+		.into_iter()
+		.filter_map(|(task, weight)| {
+			log::trace!(target: LOG_TARGET, "Pending task {task:?} with weight {weight:?}");
+			api.task_extrinsic(at, task)
+				.map_err(
+					|err| log::warn!(target: LOG_TARGET, "Failed to build task extrinsic: {err}"),
+				)
+				.ok()
+		})
+		.collect()
+}
+
+/// Submit `extrinsics` to the pool as local transactions.
+async fn submit<Pool: TransactionPool>(
+	pool: &Pool,
+	at: <Pool::Block as BlockT>::Hash,
+	extrinsics: Vec<<Pool::Block as BlockT>::Extrinsic>,
+) {
+	if extrinsics.is_empty() {
+		return
+	}
+
+	let total = extrinsics.len();
+	let results = match pool.submit_at(at, TransactionSource::Local, extrinsics).await {
+		Ok(results) => results,
+		Err(err) => {
+			log::warn!(target: LOG_TARGET, "Failed to submit task extrinsics at {at:?}: {err}");
+			return
+		},
+	};
+
+	let mut submitted = 0;
+	for result in results {
+		match result {
+			Ok(_) => submitted += 1,
+			// Usually the task is already in the pool or is no longer valid.
+			Err(err) => log::debug!(target: LOG_TARGET, "Task extrinsic not submitted: {err}"),
+		}
+	}
+	log::debug!(target: LOG_TARGET, "Submitted {submitted} of {total} pending tasks at {at:?}");
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn first_block_starts_a_round() {
+		let mut schedule = RoundSchedule::new(3);
+		assert!(schedule.note_new_best());
+	}
+
+	#[test]
+	fn rounds_respect_the_interval() {
+		let mut schedule = RoundSchedule::new(3);
+		let rounds = (0..7).map(|_| schedule.note_new_best()).collect::<Vec<_>>();
+		assert_eq!(rounds, vec![true, false, false, true, false, false, true]);
+	}
+
+	#[test]
+	fn zero_interval_runs_every_block() {
+		let mut schedule = RoundSchedule::new(0);
+		assert!((0..4).all(|_| schedule.note_new_best()));
+	}
+}
@@ -160,6 +160,8 @@ pub fn new_full<Network: sc_network::NetworkBackend<Block, <Block as BlockT>::Ha
 				)),
 				network_provider: Arc::new(network.clone()),
 				enable_http_requests: true,
+				limits: config.offchain_worker.limits.clone(),
+				prometheus_registry: config.prometheus_registry().cloned(),
 				custom_extensions: |_| vec![],
 			})?;
 		task_manager.spawn_handle().spawn(
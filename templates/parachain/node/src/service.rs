@@ -298,6 +298,8 @@ pub async fn start_parachain_node(
 				network_provider: Arc::new(network.clone()),
 				is_validator: parachain_config.role.is_authority(),
 				enable_http_requests: false,
+				limits: parachain_config.offchain_worker.limits.clone(),
+				prometheus_registry: prometheus_registry.clone(),
 				custom_extensions: move |_| vec![],
 			})?;
 		task_manager.spawn_handle().spawn(
@@ -388,6 +390,14 @@ pub async fn start_parachain_node(
 	})?;
 
 	if validator {
+		// On-demand orders need the `on-demand` feature of `cumulus-client-service`, which the
+		// omni-node enables.
+		if collator_options.on_demand_orders.is_some() {
+			return Err(sc_service::Error::Other(
+				"On-demand orders are not supported by this node, use polkadot-omni-node".into(),
+			))
+		}
+
 		start_consensus(
 			client.clone(),
 			backend,
@@ -31,6 +31,7 @@ use frame_support::{
 };
 use pallet_grandpa::AuthorityId as GrandpaId;
 use sp_api::impl_runtime_apis;
+#[cfg(not(feature = "sassafras"))]
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_core::{crypto::KeyTypeId, OpaqueMetadata};
 use sp_runtime::{
@@ -42,9 +43,13 @@ use sp_version::RuntimeVersion;
 
 // Local module imports
 use super::{
-	AccountId, Aura, Balance, Block, Executive, Grandpa, InherentDataExt, Nonce, Runtime,
-	RuntimeCall, RuntimeGenesisConfig, SessionKeys, System, TransactionPayment, VERSION,
+	AccountId, Balance, Block, Executive, Grandpa, InherentDataExt, Nonce, Runtime, RuntimeCall,
+	RuntimeGenesisConfig, SessionKeys, System, TransactionPayment, VERSION,
 };
+#[cfg(not(feature = "sassafras"))]
+use super::Aura;
+#[cfg(feature = "sassafras")]
+use super::Sassafras;
 
 impl_runtime_apis! {
 	impl sp_api::Core<Block> for Runtime {
@@ -112,6 +117,7 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[cfg(not(feature = "sassafras"))]
 	impl sp_consensus_aura::AuraApi<Block, AuraId> for Runtime {
 		fn slot_duration() -> sp_consensus_aura::SlotDuration {
 			sp_consensus_aura::SlotDuration::from_millis(Aura::slot_duration())
@@ -122,6 +128,51 @@ impl_runtime_apis! {
 		}
 	}
 
+	#[cfg(feature = "sassafras")]
+	impl sp_consensus_sassafras::SassafrasApi<Block> for Runtime {
+		fn ring_context() -> Option<sp_consensus_sassafras::vrf::RingContext> {
+			Sassafras::ring_context()
+		}
+
+		fn submit_tickets_unsigned_extrinsic(
+			tickets: Vec<sp_consensus_sassafras::TicketEnvelope>,
+		) -> bool {
+			Sassafras::submit_tickets_unsigned_extrinsic(tickets)
+		}
+
+		fn slot_ticket_id(slot: sp_consensus_sassafras::Slot) -> Option<sp_consensus_sassafras::TicketId> {
+			Sassafras::slot_ticket_id(slot)
+		}
+
+		fn slot_ticket(
+			slot: sp_consensus_sassafras::Slot,
+		) -> Option<(sp_consensus_sassafras::TicketId, sp_consensus_sassafras::TicketBody)> {
+			Sassafras::slot_ticket(slot)
+		}
+
+		fn current_epoch() -> sp_consensus_sassafras::Epoch {
+			Sassafras::current_epoch()
+		}
+
+		fn next_epoch() -> sp_consensus_sassafras::Epoch {
+			Sassafras::next_epoch()
+		}
+
+		fn generate_key_ownership_proof(
+			_authority_id: sp_consensus_sassafras::AuthorityId,
+		) -> Option<sp_consensus_sassafras::OpaqueKeyOwnershipProof> {
+			// Equivocations are not reported: there is no staking to slash.
+			None
+		}
+
+		fn submit_report_equivocation_unsigned_extrinsic(
+			_equivocation_proof: sp_consensus_sassafras::EquivocationProof<<Block as BlockT>::Header>,
+			_key_owner_proof: sp_consensus_sassafras::OpaqueKeyOwnershipProof,
+		) -> bool {
+			false
+		}
+	}
+
 	impl sp_session::SessionKeys<Block> for Runtime {
 		fn generate_session_keys(seed: Option<Vec<u8>>) -> Vec<u8> {
 			SessionKeys::generate(seed)
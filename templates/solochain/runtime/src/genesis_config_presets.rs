@@ -18,14 +18,40 @@
 use crate::{AccountId, BalancesConfig, RuntimeGenesisConfig, SudoConfig};
 use alloc::{vec, vec::Vec};
 use serde_json::Value;
+#[cfg(not(feature = "sassafras"))]
 use sp_consensus_aura::sr25519::AuthorityId as AuraId;
 use sp_consensus_grandpa::AuthorityId as GrandpaId;
+#[cfg(feature = "sassafras")]
+use sp_consensus_sassafras::{AuthorityId as SassafrasId, EpochConfiguration};
 use sp_genesis_builder::{self, PresetId};
-use sp_keyring::AccountKeyring;
+use sp_keyring::{AccountKeyring, Sr25519Keyring};
+
+/// The key used by the authorities to author blocks.
+#[cfg(not(feature = "sassafras"))]
+type BlockAuthorId = AuraId;
+/// The key used by the authorities to author blocks.
+#[cfg(feature = "sassafras")]
+type BlockAuthorId = SassafrasId;
+
+// Returns the block production key of the given well-known account.
+#[cfg(not(feature = "sassafras"))]
+fn block_author(who: Sr25519Keyring) -> BlockAuthorId {
+	who.public().into()
+}
+
+// Returns the block production key of the given well-known account.
+#[cfg(feature = "sassafras")]
+fn block_author(who: Sr25519Keyring) -> BlockAuthorId {
+	use core::str::FromStr;
+	sp_keyring::BandersnatchKeyring::from_str(who.into())
+		.expect("all the well-known accounts have a bandersnatch key; qed")
+		.public()
+		.into()
+}
 
 // Returns the genesis config presets populated with given parameters.
 fn testnet_genesis(
-	initial_authorities: Vec<(AuraId, GrandpaId)>,
+	initial_authorities: Vec<(BlockAuthorId, GrandpaId)>,
 	endowed_accounts: Vec<AccountId>,
 	root: AccountId,
 ) -> Value {
@@ -37,9 +63,16 @@ fn testnet_genesis(
 				.map(|k| (k, 1u128 << 60))
 				.collect::<Vec<_>>(),
 		},
+		#[cfg(not(feature = "sassafras"))]
 		aura: pallet_aura::GenesisConfig {
 			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect::<Vec<_>>(),
 		},
+		#[cfg(feature = "sassafras")]
+		sassafras: pallet_sassafras::GenesisConfig {
+			authorities: initial_authorities.iter().map(|x| (x.0.clone())).collect::<Vec<_>>(),
+			epoch_config: EpochConfiguration { redundancy_factor: 1, attempts_number: 8 },
+			..Default::default()
+		},
 		grandpa: pallet_grandpa::GenesisConfig {
 			authorities: initial_authorities.iter().map(|x| (x.1.clone(), 1)).collect::<Vec<_>>(),
 			..Default::default()
@@ -55,7 +88,7 @@ fn testnet_genesis(
 pub fn development_config_genesis() -> Value {
 	testnet_genesis(
 		vec![(
-			sp_keyring::Sr25519Keyring::Alice.public().into(),
+			block_author(Sr25519Keyring::Alice),
 			sp_keyring::Ed25519Keyring::Alice.public().into(),
 		)],
 		vec![
@@ -73,11 +106,11 @@ pub fn local_config_genesis() -> Value {
 	testnet_genesis(
 		vec![
 			(
-				sp_keyring::Sr25519Keyring::Alice.public().into(),
+				block_author(Sr25519Keyring::Alice),
 				sp_keyring::Ed25519Keyring::Alice.public().into(),
 			),
 			(
-				sp_keyring::Sr25519Keyring::Bob.public().into(),
+				block_author(Sr25519Keyring::Bob),
 				sp_keyring::Ed25519Keyring::Bob.public().into(),
 			),
 		],
@@ -50,6 +50,7 @@ pub mod opaque {
 	pub type Hash = <BlakeTwo256 as HashT>::Output;
 }
 
+#[cfg(not(feature = "sassafras"))]
 impl_opaque_keys! {
 	pub struct SessionKeys {
 		pub aura: Aura,
@@ -57,6 +58,14 @@ impl_opaque_keys! {
 	}
 }
 
+#[cfg(feature = "sassafras")]
+impl_opaque_keys! {
+	pub struct SessionKeys {
+		pub sassafras: Sassafras,
+		pub grandpa: Grandpa,
+	}
+}
+
 // To learn more about runtime versioning, see:
 // https://docs.substrate.io/main-docs/build/upgrade#runtime-versioning
 #[sp_version::runtime_version]
@@ -80,7 +89,8 @@ mod block_times {
 	/// This determines the average expected block time that we are targeting. Blocks will be
 	/// produced at a minimum duration defined by `SLOT_DURATION`. `SLOT_DURATION` is picked up by
 	/// `pallet_timestamp` which is in turn picked up by `pallet_aura` to implement `fn
-	/// slot_duration()`.
+	/// slot_duration()`. With the `sassafras` feature the node uses it to configure the slot
+	/// worker.
 	///
 	/// Change this to adjust the block time.
 	pub const MILLI_SECS_PER_BLOCK: u64 = 6000;
@@ -88,6 +98,12 @@ mod block_times {
 	// NOTE: Currently it is not possible to change the slot duration after the chain has started.
 	// Attempting to do so will brick block production.
 	pub const SLOT_DURATION: u64 = MILLI_SECS_PER_BLOCK;
+
+	/// The number of slots of a Sassafras epoch.
+	///
+	/// Tickets for the next epoch are collected during the first half of the epoch.
+	#[cfg(feature = "sassafras")]
+	pub const EPOCH_DURATION_IN_SLOTS: u32 = 60;
 }
 pub use block_times::*;
 
@@ -183,6 +199,7 @@ pub type Executive = frame_executive::Executive<
 >;
 
 // Create the runtime by composing the FRAME pallets that were previously configured.
+#[cfg(not(feature = "sassafras"))]
 #[frame_support::runtime]
 mod runtime {
 	#[runtime::runtime]
@@ -224,3 +241,47 @@ mod runtime {
 	#[runtime::pallet_index(7)]
 	pub type Template = pallet_template;
 }
+
+// The same runtime, with Sassafras in place of Aura for block production.
+#[cfg(feature = "sassafras")]
+#[frame_support::runtime]
+mod runtime {
+	#[runtime::runtime]
+	#[runtime::derive(
+		RuntimeCall,
+		RuntimeEvent,
+		RuntimeError,
+		RuntimeOrigin,
+		RuntimeFreezeReason,
+		RuntimeHoldReason,
+		RuntimeSlashReason,
+		RuntimeLockId,
+		RuntimeTask
+	)]
+	pub struct Runtime;
+
+	#[runtime::pallet_index(0)]
+	pub type System = frame_system;
+
+	#[runtime::pallet_index(1)]
+	pub type Timestamp = pallet_timestamp;
+
+	#[runtime::pallet_index(2)]
+	pub type Sassafras = pallet_sassafras;
+
+	#[runtime::pallet_index(3)]
+	pub type Grandpa = pallet_grandpa;
+
+	#[runtime::pallet_index(4)]
+	pub type Balances = pallet_balances;
+
+	#[runtime::pallet_index(5)]
+	pub type TransactionPayment = pallet_transaction_payment;
+
+	#[runtime::pallet_index(6)]
+	pub type Sudo = pallet_sudo;
+
+	// Include the custom logic from the pallet-template in the runtime.
+	#[runtime::pallet_index(7)]
+	pub type Template = pallet_template;
+}
@@ -239,11 +239,19 @@ pub use frame_executive;
 #[cfg(feature = "frame-metadata-hash-extension")]
 pub use frame_metadata_hash_extension;
 
+/// Attributes the storage proof size of blocks, extrinsics and benchmarks to storage items.
+#[cfg(feature = "frame-pov-profiler")]
+pub use frame_pov_profiler;
+
 /// An externalities provided environment that can load itself from remote nodes or cached
 /// files.
 #[cfg(feature = "frame-remote-externalities")]
 pub use frame_remote_externalities;
 
+/// Detects storage items whose layout changes between two runtimes without being migrated.
+#[cfg(feature = "frame-storage-layout-diff")]
+pub use frame_storage_layout_diff;
+
 /// Support code for the runtime.
 #[cfg(feature = "frame-support")]
 pub use frame_support;
@@ -272,6 +280,10 @@ pub use frame_system_benchmarking;
 #[cfg(feature = "frame-system-rpc-runtime-api")]
 pub use frame_system_rpc_runtime_api;
 
+/// Node service submitting the runtime's pending FRAME tasks as unsigned transactions.
+#[cfg(feature = "frame-task-submitter")]
+pub use frame_task_submitter;
+
 /// Supporting types for try-runtime, testing and dry-running commands.
 #[cfg(feature = "frame-try-runtime")]
 pub use frame_try_runtime;
@@ -491,6 +503,14 @@ pub use pallet_message_queue;
 #[cfg(feature = "pallet-migrations")]
 pub use pallet_migrations;
 
+/// RPC interface for the multi-block migrations pallet.
+#[cfg(feature = "pallet-migrations-rpc")]
+pub use pallet_migrations_rpc;
+
+/// Runtime API for the multi-block migrations FRAME pallet.
+#[cfg(feature = "pallet-migrations-runtime-api")]
+pub use pallet_migrations_runtime_api;
+
 /// FRAME's mixnet pallet.
 #[cfg(feature = "pallet-mixnet")]
 pub use pallet_mixnet;
@@ -1280,6 +1300,10 @@ pub use sp_consensus_babe;
 #[cfg(feature = "sp-consensus-beefy")]
 pub use sp_consensus_beefy;
 
+/// A no_std verifier of BEEFY signed commitments and MMR leaf proofs for light clients.
+#[cfg(feature = "sp-consensus-beefy-light-client")]
+pub use sp_consensus_beefy_light_client;
+
 /// Primitives for GRANDPA integration, suitable for WASM compilation.
 #[cfg(feature = "sp-consensus-grandpa")]
 pub use sp_consensus_grandpa;